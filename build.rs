@@ -0,0 +1,17 @@
+//! Embeds the git hash of the build, so that the About dialog can tell apart builds of the same version.
+
+use std::process::Command;
+
+fn main() {
+    let git_hash = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map_or_else(|| String::from("unknown"), |hash| hash.trim().to_string());
+
+    println!("cargo:rustc-env=RUSTY_CHIP_GIT_HASH={git_hash}");
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs");
+}
@@ -0,0 +1,73 @@
+/*
+ * The C interface of the RustyChip CHIP-8 emulator core, built with the `ffi` feature, e.g.
+ * `cargo rustc --release -p rusty-chip-core --features ffi --crate-type staticlib`.
+ *
+ * Every emulator is created with rusty_chip_create and must be freed with rusty_chip_destroy.
+ * The other functions take the pointer it returned, and do nothing (or return zero) when given NULL.
+ * An emulator must only be used from one thread at a time.
+ */
+
+#ifndef RUSTY_CHIP_H
+#define RUSTY_CHIP_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* The platforms whose quirks an emulator can be created with. */
+#define RUSTY_CHIP_PLATFORM_DEFAULT 0
+#define RUSTY_CHIP_PLATFORM_COSMAC_VIP 1
+#define RUSTY_CHIP_PLATFORM_CHIP_48 2
+#define RUSTY_CHIP_PLATFORM_SCHIP 3
+#define RUSTY_CHIP_PLATFORM_XO_CHIP 4
+
+/* An emulator, only ever used through a pointer. */
+typedef struct RustyChip RustyChip;
+
+/* Creates an emulator with the quirks of the platform and no game, returning NULL if the platform is unknown. */
+RustyChip *rusty_chip_create(uint32_t platform);
+
+/* Frees the emulator. NULL is ignored. */
+void rusty_chip_destroy(RustyChip *chip);
+
+/* Loads the game, which starts running from the next frame. The bytes are copied. Returns false if no game was given. */
+bool rusty_chip_load(RustyChip *chip, const uint8_t *data, size_t len);
+
+/* Changes the number of instructions run in every frame, 10 unless changed. */
+void rusty_chip_set_cycles_per_frame(RustyChip *chip, uint32_t cycles_per_frame);
+
+/* Runs a single frame of the game, for callers which pace the frames themselves at 60Hz. */
+void rusty_chip_step_frame(RustyChip *chip);
+
+/* Advances the game by the time elapsed, running as many frames as fit. Returns the number of frames run. */
+uint32_t rusty_chip_advance(RustyChip *chip, double elapsed_ms);
+
+/* Presses or releases the CHIP-8 key (0x0 to 0xF). Other keys are ignored. */
+void rusty_chip_key_down(RustyChip *chip, uint8_t key);
+void rusty_chip_key_up(RustyChip *chip, uint8_t key);
+
+/*
+ * Draws the display, returning its RGBA pixels row by row: width by height pixels of 4 bytes each.
+ * The pixels stay valid until the next call to this function or rusty_chip_destroy.
+ */
+const uint8_t *rusty_chip_framebuffer(RustyChip *chip);
+
+/* Returns the size of the display in pixels, which changes with the resolution the game uses. */
+uint32_t rusty_chip_framebuffer_width(const RustyChip *chip);
+uint32_t rusty_chip_framebuffer_height(const RustyChip *chip);
+
+/* Returns true while the game is making a sound, for callers playing their own tone. */
+bool rusty_chip_is_sounding(const RustyChip *chip);
+
+/* Returns true if the game has halted, either through an error or by exiting. */
+bool rusty_chip_is_halted(const RustyChip *chip);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,73 @@
+//! A module to contain the optional heap allocation instrumentation of the emulator.  
+//! Only compiled with the `alloc-tracking` feature, in which case the binary installs the [`CountingAllocator`](CountingAllocator) globally.  
+//! Allocations are counted per thread so that parallel tests do not interfere with each other.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// A global allocator which defers to the system allocator while counting the allocations made on each thread.
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        record_allocation();
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        record_allocation();
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        record_allocation();
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+/// Increments the allocation count of the current thread.  
+/// Silently does nothing if the thread is being torn down and its local storage is gone.
+fn record_allocation() {
+    let _ = ALLOCATIONS.try_with(|allocations| allocations.set(allocations.get() + 1));
+}
+
+/// Returns the number of allocations made on the current thread so far.
+#[must_use]
+pub fn allocation_count() -> usize {
+    ALLOCATIONS.try_with(Cell::get).unwrap_or(0)
+}
+
+/// Runs the provided closure, returning its result along with the number of allocations it made on the current thread.  
+/// The count is only meaningful when the [`CountingAllocator`](CountingAllocator) is installed as the global allocator.
+///
+/// # Parameters
+///
+/// * `f` - The closure to measure.
+pub fn count_allocations<R>(f: impl FnOnce() -> R) -> (R, usize) {
+    let before = allocation_count();
+    let result = f();
+    (result, allocation_count() - before)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn count_vec_allocation() {
+        let (vec, allocations) = count_allocations(|| Vec::<u8>::with_capacity(16));
+        assert_eq!(vec.capacity(), 16, "Closure result not returned.");
+        assert_eq!(allocations, 1, "Allocation not counted.");
+
+        let ((), allocations) = count_allocations(|| {});
+        assert_eq!(allocations, 0, "Allocation counted when none were made.");
+    }
+}
@@ -0,0 +1,431 @@
+//! A module to contain minimal audio functionality of the emulator.  
+//! This started from the example provided by the SDL2 crate.  
+//! The generator running in the audio callback is controlled through a command channel so that changes made at runtime are applied at buffer boundaries, keeping them glitch-free and thread-safe.  
+//! Web-viewable documentation [here](https://docs.rs/sdl2/latest/sdl2/audio/index.html).  
+//! Without the `std` feature only the commands are built, for firmware driving its own sound hardware.
+
+use core::error::Error;
+use core::fmt::{Display, Formatter};
+#[cfg(feature = "std")]
+use std::f32::consts::TAU;
+#[cfg(feature = "std")]
+use std::sync::mpsc::{self, Receiver, Sender};
+
+/// The frequency of the tone played by the CHIP-8, in Hz.
+pub const DEFAULT_FREQUENCY: f32 = 440.0;
+
+/// The volume of the tone played by the CHIP-8, from 0 to 1.
+pub const DEFAULT_VOLUME: f32 = 0.25;
+
+/// Denotes the shape of the wave produced by a voice of the [`ToneGenerator`](ToneGenerator).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Waveform {
+    #[default]
+    Square,
+    Triangle,
+    Sawtooth,
+    Sine
+}
+
+#[cfg(feature = "std")]
+impl Waveform {
+    /// Returns the sample of the wave at the provided phase, from -1 to 1.
+    ///
+    /// # Parameters
+    ///
+    /// * `phase` - How far along a single period we are, from 0 to 1.
+    fn sample(self, phase: f32) -> f32 {
+        match self {
+            Waveform::Square => if phase <= 0.5 { 1.0 } else { -1.0 },
+            Waveform::Triangle => 1.0 - 4.0 * (phase - 0.5).abs(),
+            Waveform::Sawtooth => 2.0 * phase - 1.0,
+            Waveform::Sine => (TAU * phase).sin()
+        }
+    }
+}
+
+/// The number of voices mixed together by the [`ToneGenerator`](ToneGenerator).
+pub const VOICE_COUNT: usize = 4;
+
+/// The voice used for the single tone of the CHIP-8.
+pub const PRIMARY_VOICE: usize = 0;
+
+/// The number of bytes in a 1-bit sample pattern.
+pub const PATTERN_LENGTH: usize = 16;
+
+/// The number of samples (bits) in a 1-bit sample pattern.
+const PATTERN_BITS: usize = PATTERN_LENGTH * 8;
+
+/// The pitch of the XO-CHIP at which a sample pattern is played at [`PATTERN_BASE_RATE`](PATTERN_BASE_RATE).
+pub const DEFAULT_PITCH: u8 = 64;
+
+/// The rate at which the bits of a sample pattern are played at the default pitch, in bits per second.
+const PATTERN_BASE_RATE: f32 = 4000.0;
+
+/// The number of pitch steps which double the rate of a sample pattern.
+const PITCH_STEPS_PER_OCTAVE: i32 = 48;
+
+/// The factor a single pitch step multiplies the rate of a sample pattern by, the 48th root of 2.
+const PITCH_STEP_RATIO: f32 = 1.014_545_3;
+
+/// Returns the number of times per second a sample pattern is played at the provided XO-CHIP pitch, for use with [`SetFrequency`](AudioCommand::SetFrequency).
+///
+/// # Parameters
+///
+/// * `pitch` - The pitch register of the XO-CHIP.
+#[must_use]
+pub fn pattern_frequency(pitch: u8) -> f32 {
+    #[allow(clippy::cast_precision_loss)]
+    let bits = PATTERN_BITS as f32;
+
+    // The pitch is split into whole octaves and the steps left over, as raising to a power needs the standard library
+    let steps = i32::from(pitch) - i32::from(DEFAULT_PITCH);
+    let octaves = steps.div_euclid(PITCH_STEPS_PER_OCTAVE);
+    let mut rate = PATTERN_BASE_RATE;
+    for _ in 0..steps.rem_euclid(PITCH_STEPS_PER_OCTAVE) {
+        rate *= PITCH_STEP_RATIO;
+    }
+    for _ in 0..octaves.unsigned_abs() {
+        rate = if octaves > 0 { rate * 2.0 } else { rate / 2.0 };
+    }
+
+    rate / bits
+}
+
+/// Denotes a change to apply to a voice of the [`ToneGenerator`](ToneGenerator) at the start of its next buffer.  
+/// The first member of each variant is the index of the voice to change.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AudioCommand {
+    /// Sets the volume, from 0 to 1.
+    SetVolume(usize, f32),
+
+    /// Sets the frequency of the tone (or the number of times per second the pattern is played), in Hz.
+    SetFrequency(usize, f32),
+
+    /// Sets the shape of the wave.
+    SetWaveform(usize, Waveform),
+
+    /// Plays the provided 1-bit sample pattern instead of the waveform, or returns to the waveform if there is none.
+    SetPattern(usize, Option<[u8; PATTERN_LENGTH]>),
+
+    /// Sets the stereo position, from -1 (left) to 1 (right). Ignored when the output is mono.
+    SetPan(usize, f32),
+
+    /// Opens (plays the tone) or closes (silences the tone) the gate.
+    SetGate(usize, bool)
+}
+
+/// Denotes a failure to change the audio at runtime.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AudioError {
+    /// The audio callback has been dropped (e.g. the device was closed), so the command could not be delivered.
+    Disconnected(AudioCommand)
+}
+
+impl Display for AudioError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            AudioError::Disconnected(command) => write!(f, "Audio device is no longer running, could not apply {command:?}.")
+        }
+    }
+}
+
+impl Error for AudioError {}
+
+/// Sends [commands](AudioCommand) to a running [`ToneGenerator`](ToneGenerator).
+#[cfg(feature = "std")]
+#[derive(Clone)]
+pub struct AudioController {
+    commands: Sender<AudioCommand>
+}
+
+#[cfg(feature = "std")]
+impl AudioController {
+    /// Queues the provided command for the generator.
+    ///
+    /// # Parameters
+    ///
+    /// * `command` - The change to apply.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the generator is no longer running.
+    pub fn send(&self, command: AudioCommand) -> Result<(), AudioError> {
+        self.commands.send(command).map_err(|e| AudioError::Disconnected(e.0))
+    }
+
+    /// Opens or closes the gate of a voice of the generator.
+    ///
+    /// # Parameters
+    ///
+    /// * `voice` - The index of the voice to change.
+    /// * `is_open` - True if the voice should play, false if it should be silenced.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the generator is no longer running.
+    pub fn set_gate(&self, voice: usize, is_open: bool) -> Result<(), AudioError> {
+        self.send(AudioCommand::SetGate(voice, is_open))
+    }
+}
+
+/// Stores the information to produce a single tone or sample pattern.
+#[cfg(feature = "std")]
+struct Voice {
+    phase_inc: f32,
+    phase: f32,
+    volume: f32,
+    waveform: Waveform,
+    pattern: Option<[u8; PATTERN_LENGTH]>,
+    pan: f32,
+    is_gate_open: bool
+}
+
+#[cfg(feature = "std")]
+impl Voice {
+    /// Returns the next sample of the voice (before panning), advancing its phase.
+    fn next_sample(&mut self) -> f32 {
+        if !self.is_gate_open {
+            return 0.0;
+        }
+
+        let sample = match &self.pattern {
+            Some(pattern) => {
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+                let bit = ((self.phase * PATTERN_BITS as f32) as usize).min(PATTERN_BITS - 1);
+                if (pattern[bit / 8] >> (7 - bit % 8)) & 1 == 1 { 1.0 } else { -1.0 }
+            },
+            None => self.waveform.sample(self.phase)
+        };
+        self.phase = (self.phase + self.phase_inc) % 1.0;
+
+        sample * self.volume
+    }
+}
+
+/// Mixes several voices into a stream of any number of channels, applying any queued [commands](AudioCommand) at the start of each buffer.
+#[cfg(feature = "std")]
+pub struct ToneGenerator {
+    sample_rate: f32,
+    channels: usize,
+    voices: Vec<Voice>,
+    commands: Receiver<AudioCommand>
+}
+
+#[cfg(feature = "std")]
+impl ToneGenerator {
+    /// Creates a generator with [`VOICE_COUNT`](VOICE_COUNT) silent (gate closed) voices using the default tone, along with the controller used to change it.
+    ///
+    /// # Parameters
+    ///
+    /// * `sample_rate` - The sample rate of the audio device, in Hz.
+    /// * `channels` - The number of channels of the audio device. A single channel is played in mono, while devices with more channels get the left and right mix in the first two, leaving the rest silent.
+    #[must_use]
+    pub fn new(sample_rate: i32, channels: u8) -> (AudioController, ToneGenerator) {
+        let (sender, receiver) = mpsc::channel();
+        #[allow(clippy::cast_precision_loss)]
+        let sample_rate = sample_rate as f32;
+        let voices = (0..VOICE_COUNT).map(|_| Voice {
+            phase_inc: DEFAULT_FREQUENCY / sample_rate,
+            phase: 0.0,
+            volume: DEFAULT_VOLUME,
+            waveform: Waveform::default(),
+            pattern: None,
+            pan: 0.0,
+            is_gate_open: false
+        }).collect();
+        let generator = ToneGenerator {
+            sample_rate,
+            channels: usize::from(channels.max(1)),
+            voices,
+            commands: receiver
+        };
+
+        (AudioController { commands: sender }, generator)
+    }
+
+    /// Applies all the commands queued since the previous buffer.  
+    /// Commands for voices which do not exist are ignored.
+    fn apply_commands(&mut self) {
+        while let Ok(command) = self.commands.try_recv() {
+            let sample_rate = self.sample_rate;
+            let voice_index = match command {
+                AudioCommand::SetVolume(voice, _) | AudioCommand::SetFrequency(voice, _) | AudioCommand::SetWaveform(voice, _) |
+                AudioCommand::SetPattern(voice, _) | AudioCommand::SetPan(voice, _) | AudioCommand::SetGate(voice, _) => voice
+            };
+            let Some(voice) = self.voices.get_mut(voice_index) else {
+                continue;
+            };
+
+            match command {
+                AudioCommand::SetVolume(_, volume) => voice.volume = volume.clamp(0.0, 1.0),
+                AudioCommand::SetFrequency(_, frequency) => voice.phase_inc = frequency.max(0.0) / sample_rate,
+                AudioCommand::SetWaveform(_, waveform) => voice.waveform = waveform,
+                AudioCommand::SetPattern(_, pattern) => voice.pattern = pattern,
+                AudioCommand::SetPan(_, pan) => voice.pan = pan.clamp(-1.0, 1.0),
+                AudioCommand::SetGate(_, is_open) => voice.is_gate_open = is_open
+            }
+        }
+    }
+
+    /// Mixes all the voices into the buffer, a frame of every channel at a time, interleaving the left and right channels when the output has more than one.  
+    /// This is what the audio device's callback runs, and can be called directly to render audio without one.
+    ///
+    /// # Parameters
+    ///
+    /// * `out` - The buffer of samples to fill.
+    pub fn mix(&mut self, out: &mut [f32]) {
+        self.apply_commands();
+
+        for frame in out.chunks_mut(self.channels) {
+            let mut mono = 0.0;
+            let mut left = 0.0;
+            let mut right = 0.0;
+            for voice in &mut self.voices {
+                let sample = voice.next_sample();
+                mono += sample;
+                left += sample * (1.0 - voice.pan).min(1.0);
+                right += sample * (1.0 + voice.pan).min(1.0);
+            }
+
+            match frame {
+                [left_out, right_out, rest @ ..] => {
+                    *left_out = left.clamp(-1.0, 1.0);
+                    *right_out = right.clamp(-1.0, 1.0);
+                    rest.fill(0.0);
+                },
+                _ => frame.fill(mono.clamp(-1.0, 1.0))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn closed_gate_is_silent() {
+        let (_controller, mut generator) = ToneGenerator::new(44100, 1);
+
+        let mut out = [1.0; 16];
+        generator.mix(&mut out);
+        assert!(out.iter().all(|x| *x == 0.0), "Closed gate produced sound.");
+    }
+
+    #[test]
+    fn commands_applied_at_buffer_boundary() {
+        let (controller, mut generator) = ToneGenerator::new(44100, 1);
+
+        controller.set_gate(PRIMARY_VOICE, true).unwrap();
+        controller.send(AudioCommand::SetVolume(PRIMARY_VOICE, 0.5)).unwrap();
+        assert!(!generator.voices[PRIMARY_VOICE].is_gate_open, "Command applied before the next buffer.");
+
+        let mut out = [0.0; 16];
+        generator.mix(&mut out);
+        assert!(generator.voices[PRIMARY_VOICE].is_gate_open, "Gate command not applied.");
+        assert!((out[0] - 0.5).abs() < f32::EPSILON, "Volume command not applied.");
+    }
+
+    #[test]
+    fn mix_voices() {
+        let (controller, mut generator) = ToneGenerator::new(44100, 1);
+
+        controller.set_gate(0, true).unwrap();
+        controller.set_gate(1, true).unwrap();
+        controller.send(AudioCommand::SetGate(VOICE_COUNT, true)).unwrap();
+
+        let mut out = [0.0; 4];
+        generator.mix(&mut out);
+        assert!((out[0] - DEFAULT_VOLUME * 2.0).abs() < f32::EPSILON, "Voices not mixed together.");
+    }
+
+    #[test]
+    fn stereo_panning() {
+        let (controller, mut generator) = ToneGenerator::new(44100, 2);
+
+        controller.set_gate(0, true).unwrap();
+        controller.send(AudioCommand::SetPan(0, -1.0)).unwrap();
+        controller.set_gate(1, true).unwrap();
+        controller.send(AudioCommand::SetPan(1, 1.0)).unwrap();
+        controller.send(AudioCommand::SetVolume(1, 0.5)).unwrap();
+
+        let mut out = [0.0; 8];
+        generator.mix(&mut out);
+        assert!((out[0] - DEFAULT_VOLUME).abs() < f32::EPSILON, "Left voice not panned left.");
+        assert!((out[1] - 0.5).abs() < f32::EPSILON, "Right voice not panned right.");
+    }
+
+    #[test]
+    fn mono_output() {
+        let (controller, mut generator) = ToneGenerator::new(44100, 1);
+
+        controller.set_gate(0, true).unwrap();
+        controller.send(AudioCommand::SetPan(0, 1.0)).unwrap();
+
+        let mut out = [0.0; 4];
+        generator.mix(&mut out);
+        assert!(out.iter().all(|x| (*x - DEFAULT_VOLUME).abs() < f32::EPSILON), "Panned voice not played in mono.");
+    }
+
+    #[test]
+    fn surround_output() {
+        let (controller, mut generator) = ToneGenerator::new(44100, 6);
+        let (stereo_controller, mut stereo_generator) = ToneGenerator::new(44100, 2);
+        for controller in [&controller, &stereo_controller] {
+            controller.set_gate(0, true).unwrap();
+            controller.send(AudioCommand::SetPan(0, 1.0)).unwrap();
+            controller.send(AudioCommand::SetWaveform(0, Waveform::Sine)).unwrap();
+        }
+
+        let mut out = [1.0; 6 * 8];
+        generator.mix(&mut out);
+        let mut stereo_out = [0.0; 2 * 8];
+        stereo_generator.mix(&mut stereo_out);
+        assert_eq!(generator.channels, 6, "Channel count not kept.");
+        for (frame, stereo_frame) in out.chunks(6).zip(stereo_out.chunks(2)) {
+            assert_eq!(frame[..2], *stereo_frame, "Frames not stepped by the channel count.");
+            assert!(frame[2..].iter().all(|x| *x == 0.0), "Extra channels not silent.");
+        }
+    }
+
+    #[test]
+    fn pattern_playback() {
+        let (controller, mut generator) = ToneGenerator::new(44100, 1);
+
+        let mut pattern = [0x0; PATTERN_LENGTH];
+        pattern[0] = 0x80;
+        controller.set_gate(0, true).unwrap();
+        controller.send(AudioCommand::SetVolume(0, 1.0)).unwrap();
+        controller.send(AudioCommand::SetPattern(0, Some(pattern))).unwrap();
+        #[allow(clippy::cast_precision_loss)]
+        controller.send(AudioCommand::SetFrequency(0, 44100.0 / PATTERN_BITS as f32)).unwrap();
+
+        let mut out = [0.0; 3];
+        generator.mix(&mut out);
+        assert_eq!(out, [1.0, -1.0, -1.0], "Pattern not played bit by bit.");
+    }
+
+    #[test]
+    fn pitch_to_frequency() {
+        assert!((pattern_frequency(DEFAULT_PITCH) - 31.25).abs() < f32::EPSILON, "Default pitch played at the wrong rate.");
+        assert!((pattern_frequency(DEFAULT_PITCH + 48) - 62.5).abs() < 0.0001, "Pitch not doubled after an octave.");
+    }
+
+    #[test]
+    fn waveform_samples() {
+        assert!((Waveform::Square.sample(0.25) - 1.0).abs() < f32::EPSILON, "Square wave sampled incorrectly.");
+        assert!((Waveform::Square.sample(0.75) + 1.0).abs() < f32::EPSILON, "Square wave sampled incorrectly.");
+        assert!((Waveform::Triangle.sample(0.5) - 1.0).abs() < f32::EPSILON, "Triangle wave sampled incorrectly.");
+        assert!((Waveform::Sawtooth.sample(0.0) + 1.0).abs() < f32::EPSILON, "Sawtooth wave sampled incorrectly.");
+        assert!((Waveform::Sine.sample(0.25) - 1.0).abs() < 0.0001, "Sine wave sampled incorrectly.");
+    }
+
+    #[test]
+    fn disconnected_generator() {
+        let (controller, generator) = ToneGenerator::new(44100, 1);
+        drop(generator);
+
+        assert_eq!(controller.set_gate(PRIMARY_VOICE, true), Err(AudioError::Disconnected(AudioCommand::SetGate(PRIMARY_VOICE, true))), "Missing generator not reported.");
+    }
+}
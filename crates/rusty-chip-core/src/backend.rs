@@ -0,0 +1,251 @@
+//! A module to contain the traits the [interpreter](crate::interpreter::Interpreter) draws, plays sound, and reads keys through, so that it does not depend on any particular library.
+//! The emulator's own window implements them with SDL in the `frontend` module of the `rusty_chip` crate, while other frontends (e.g. a terminal or a web page) can provide their own, and headless runs leave them out entirely.
+
+use core::fmt::Debug;
+
+#[cfg(not(feature = "std"))]
+use crate::alloc_prelude::*;
+use crate::audio::{AudioCommand, AudioError};
+#[cfg(feature = "std")]
+use crate::audio::AudioController;
+use crate::palette::Colour;
+
+/// Stores a rectangle of the window, in pixels from its top left corner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32
+}
+
+impl Rect {
+    /// Returns the rectangle with its top left corner at the point.
+    ///
+    /// # Parameters
+    ///
+    /// * `x` - The X coordinate of the left edge.
+    /// * `y` - The Y coordinate of the top edge.
+    /// * `width` - The width of the rectangle.
+    /// * `height` - The height of the rectangle.
+    #[must_use]
+    pub const fn new(x: i32, y: i32, width: u32, height: u32) -> Rect {
+        Rect { x, y, width, height }
+    }
+
+    /// Returns the X coordinate just past the right edge.
+    #[must_use]
+    #[allow(clippy::cast_possible_wrap)]
+    pub const fn right(&self) -> i32 {
+        self.x + self.width as i32
+    }
+
+    /// Returns the Y coordinate just past the bottom edge.
+    #[must_use]
+    #[allow(clippy::cast_possible_wrap)]
+    pub const fn bottom(&self) -> i32 {
+        self.y + self.height as i32
+    }
+
+    /// Returns true if the other rectangle lies entirely within this one.
+    ///
+    /// # Parameters
+    ///
+    /// * `other` - The rectangle to check.
+    #[must_use]
+    pub fn contains(&self, other: &Rect) -> bool {
+        other.x >= self.x && other.y >= self.y && other.right() <= self.right() && other.bottom() <= self.bottom()
+    }
+}
+
+/// Denotes the kind of message shown to the user, which decides the icon of its box.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageKind {
+    Information,
+    Warning,
+    Error
+}
+
+/// The window the display of the game is drawn to.
+/// A frame is drawn by clearing the window, drawing the rectangles of the pixels (and any overlays), then presenting it.
+/// Displays must be `Send` and `Sync` so that the interpreter can run on its own thread; a window tied to one thread can be driven by recording the frames and replaying them there.
+pub trait Display: Send + Sync {
+    /// Fills the whole window with the colour.
+    ///
+    /// # Parameters
+    ///
+    /// * `colour` - The colour to fill the window with.
+    fn clear(&mut self, colour: Colour);
+
+    /// Fills the rectangles with the colour, blended over what is already drawn with the provided opacity.
+    ///
+    /// # Parameters
+    ///
+    /// * `colour` - The colour to fill the rectangles with.
+    /// * `alpha` - The opacity, from 0 (invisible) to 255 (opaque).
+    /// * `rects` - The rectangles to fill.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the rectangles could not be drawn.
+    fn fill_rects(&mut self, colour: Colour, alpha: u8, rects: &[Rect]) -> Result<(), String>;
+
+    /// Draws the outlines of the rectangles in the colour.
+    ///
+    /// # Parameters
+    ///
+    /// * `colour` - The colour of the outlines.
+    /// * `rects` - The rectangles to outline.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the rectangles could not be drawn.
+    fn draw_rects(&mut self, colour: Colour, rects: &[Rect]) -> Result<(), String>;
+
+    /// Shows everything drawn since the window was last presented.
+    fn present(&mut self);
+
+    /// Sets the title of the window.
+    ///
+    /// # Parameters
+    ///
+    /// * `title` - The new title.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the title could not be set.
+    fn set_title(&mut self, title: &str) -> Result<(), String>;
+
+    /// Shows a message to the user, waiting until it is dismissed. Displays which cannot show messages ignore them.
+    ///
+    /// # Parameters
+    ///
+    /// * `kind` - The kind of message.
+    /// * `title` - The title of the message.
+    /// * `message` - The body of the message.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the message could not be shown.
+    fn show_message(&self, kind: MessageKind, title: &str, message: &str) -> Result<(), String> {
+        let _ = (kind, title, message);
+        Ok(())
+    }
+
+    /// Offers the user a choice between the provided options, returning the index of the chosen one, or `None` if the user chose nothing (or the display cannot offer choices).
+    ///
+    /// # Parameters
+    ///
+    /// * `kind` - The kind of message.
+    /// * `title` - The title of the message.
+    /// * `message` - The body of the message.
+    /// * `choices` - The text of each option, in order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the choice could not be offered.
+    fn show_choice(&self, kind: MessageKind, title: &str, message: &str, choices: &[&str]) -> Result<Option<usize>, String> {
+        let _ = (kind, title, message, choices);
+        Ok(None)
+    }
+}
+
+/// The output the sound of the game is played through, driven by [audio commands](AudioCommand).
+pub trait AudioSink: Send + Sync {
+    /// Queues the command for the output.
+    ///
+    /// # Parameters
+    ///
+    /// * `command` - The change to apply.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the output is no longer running.
+    fn send(&self, command: AudioCommand) -> Result<(), AudioError>;
+
+    /// Opens or closes the gate of a voice of the output.
+    ///
+    /// # Parameters
+    ///
+    /// * `voice` - The index of the voice to change.
+    /// * `is_open` - True if the voice should play, false if it should be silenced.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the output is no longer running.
+    fn set_gate(&self, voice: usize, is_open: bool) -> Result<(), AudioError> {
+        self.send(AudioCommand::SetGate(voice, is_open))
+    }
+}
+
+#[cfg(feature = "std")]
+impl AudioSink for AudioController {
+    fn send(&self, command: AudioCommand) -> Result<(), AudioError> {
+        AudioController::send(self, command)
+    }
+}
+
+/// The keys of a host device (e.g. a keyboard) and how they map onto the CHIP-8 keypad.
+pub trait InputSource {
+    /// The keys of the device as the frontend reports them.
+    type Key: Copy + Debug;
+
+    /// Returns the CHIP-8 key (`0x0` to `0xF`) the host key is usually mapped to, or `None` if it is not on the keypad.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The host key.
+    fn keypad_key(&self, key: Self::Key) -> Option<u8>;
+
+    /// Returns the name of the host key, as [key profiles](crate::key_profile) refer to it.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The host key.
+    fn key_name(&self, key: Self::Key) -> String;
+}
+
+/// Maps the characters of a keyboard's keys onto the keypad, with the left four columns of keys standing in for its 4x4 grid, for frontends which are given characters rather than key codes (e.g. a terminal).  
+/// Letters are mapped regardless of their case, and named as the uppercase letter.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CharKeyboard;
+
+impl CharKeyboard {
+    /// The keys standing in for the keypad, in the order of the CHIP-8 keys they map to.
+    const LAYOUT: [char; 16] = ['x', '1', '2', '3', 'q', 'w', 'e', 'a', 's', 'd', 'z', 'c', '4', 'r', 'f', 'v'];
+}
+
+impl InputSource for CharKeyboard {
+    type Key = char;
+
+    fn keypad_key(&self, key: char) -> Option<u8> {
+        let key = key.to_ascii_lowercase();
+        CharKeyboard::LAYOUT.iter().position(|layout_key| *layout_key == key).and_then(|index| u8::try_from(index).ok())
+    }
+
+    fn key_name(&self, key: char) -> String {
+        key.to_uppercase().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rect_edges() {
+        let rect = Rect::new(10, 20, 30, 40);
+        assert_eq!((rect.right(), rect.bottom()), (40, 60), "Edges calculated incorrectly.");
+        assert!(rect.contains(&Rect::new(10, 20, 30, 40)), "Rectangle does not contain itself.");
+        assert!(!rect.contains(&Rect::new(11, 20, 30, 40)), "Overhanging rectangle contained.");
+    }
+
+    #[test]
+    fn char_keyboard_layout() {
+        let keys: Vec<Option<u8>> = "1234qwerASDFzxcv".chars().map(|key| CharKeyboard.keypad_key(key)).collect();
+        let expected = [0x1, 0x2, 0x3, 0xC, 0x4, 0x5, 0x6, 0xD, 0x7, 0x8, 0x9, 0xE, 0xA, 0x0, 0xB, 0xF].map(Some);
+        assert_eq!(keys, expected, "Incorrect key mapping.");
+        assert_eq!(CharKeyboard.keypad_key('g'), None, "Ignored key is mapped.");
+        assert_eq!(CharKeyboard.key_name('q'), "Q", "Key named incorrectly.");
+    }
+}
@@ -0,0 +1,69 @@
+//! A module for an experimental bank switching scheme, letting homebrew programs grow past the 4K the CHIP-8's 12-bit addresses can reach without moving to XO-CHIP.
+//! The program is split into a fixed part, from the load address up to [`BANK_WINDOW_ADDRESS`], and 2K banks holding the rest of the file.
+//! The banks are stored above the 4K limit and copied one at a time into the window between [`BANK_WINDOW_ADDRESS`] and `0xFFF`, with bank 0 there when the game starts.
+//! Writing a bank number to [`BANK_SELECT_ADDRESS`] (e.g. `A1FF 6002 F055` for bank 2) swaps that bank into the window, keeping any changes made to the bank it replaces. Reading the address returns the current bank.
+//! Code switching banks should run from the fixed part, as the window changes under it.
+//! Disassemblies and symbol files mark the code of each bank with a `.bank <n>` directive (see [`disassemble_banked`](crate::opcodes::disassemble_banked) and [`SymbolTable`](crate::symbols::SymbolTable)).
+
+use crate::interpreter::RAM_SIZE;
+
+/// The size of a bank, which is also the size of the window it is swapped into.
+pub const BANK_SIZE: usize = 0x800;
+
+/// The address of the window the selected bank is swapped into.
+pub const BANK_WINDOW_ADDRESS: usize = 0x800;
+
+/// The memory-mapped register which switches banks when written to, and holds the current bank.
+pub const BANK_SELECT_ADDRESS: usize = 0x1FF;
+
+/// The address the banks are stored from, out of reach of the 12-bit addresses.
+const BANK_STORAGE_ADDRESS: usize = BANK_WINDOW_ADDRESS + BANK_SIZE;
+
+/// The most banks which fit in the memory.
+pub const MAX_BANKS: usize = (RAM_SIZE - BANK_STORAGE_ADDRESS) / BANK_SIZE;
+
+/// Loads the game into the memory split into its fixed part and banks, with bank 0 in the window, dropping any banks which do not fit.
+/// Returns the number of banks the game has (always at least one).
+///
+/// # Parameters
+///
+/// * `ram` - The memory to load the game into, which is expected to be cleared.
+/// * `load_address` - The address the fixed part of the game is loaded at, which must not be past the window.
+/// * `game_data` - The bytes of the game file.
+pub(crate) fn load(ram: &mut [u8], load_address: usize, game_data: &[u8]) -> u8 {
+    let (fixed, banked) = game_data.split_at(game_data.len().min(BANK_WINDOW_ADDRESS - load_address));
+    ram[load_address..load_address + fixed.len()].copy_from_slice(fixed);
+
+    let banked = &banked[..banked.len().min(MAX_BANKS * BANK_SIZE)];
+    ram[BANK_STORAGE_ADDRESS..BANK_STORAGE_ADDRESS + banked.len()].copy_from_slice(banked);
+    ram.copy_within(BANK_STORAGE_ADDRESS..BANK_STORAGE_ADDRESS + BANK_SIZE, BANK_WINDOW_ADDRESS);
+    ram[BANK_SELECT_ADDRESS] = 0;
+
+    #[allow(clippy::cast_possible_truncation)]
+    let bank_count = banked.len().div_ceil(BANK_SIZE).max(1) as u8;
+    bank_count
+}
+
+/// Swaps the bank into the window, first storing the window back into the bank it held.
+///
+/// # Parameters
+///
+/// * `ram` - The memory holding the banks.
+/// * `bank` - The bank to swap in, which must be one the game has.
+pub(crate) fn switch(ram: &mut [u8], bank: u8) {
+    let window = BANK_WINDOW_ADDRESS..BANK_WINDOW_ADDRESS + BANK_SIZE;
+    ram.copy_within(window.clone(), bank_address(ram[BANK_SELECT_ADDRESS]));
+
+    let bank_address = bank_address(bank);
+    ram.copy_within(bank_address..bank_address + BANK_SIZE, window.start);
+    ram[BANK_SELECT_ADDRESS] = bank;
+}
+
+/// Returns the address the bank is stored at.
+///
+/// # Parameters
+///
+/// * `bank` - The number of the bank.
+fn bank_address(bank: u8) -> usize {
+    BANK_STORAGE_ADDRESS + usize::from(bank) * BANK_SIZE
+}
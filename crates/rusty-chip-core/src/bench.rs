@@ -0,0 +1,152 @@
+//! A module to compare the speed of the [dispatch designs](crate::dispatch) across a directory of games, so that performance work is guided by measurements rather than guesswork.
+//! Each game is run without a window for a number of frames under every design, keeping the fastest of a few runs to smooth out noise from the rest of the system.
+//! The report is a Markdown table with a row per game, giving each design's time along with its speed relative to the match-based design.
+
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+use crate::dispatch::Dispatch;
+use crate::emulator::Emulator;
+
+/// The number of frames each game is run for by default, ten seconds of play.
+pub const BENCH_FRAMES: u64 = 600;
+
+/// The number of times each game is run under each design, keeping the fastest.
+pub const BENCH_RUNS: u32 = 3;
+
+/// Stores how long a single game took to run under each design.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BenchResult {
+    /// The path to the game file.
+    pub path: String,
+    /// The fastest time of each design, in the order of [`Dispatch::ALL`](Dispatch::ALL).
+    pub times: Vec<(Dispatch, Duration)>
+}
+
+impl BenchResult {
+    /// Returns the time the game took under the design, or `None` if it was not run under it.
+    ///
+    /// # Parameters
+    ///
+    /// * `dispatch` - The design to look up.
+    #[must_use]
+    pub fn time(&self, dispatch: Dispatch) -> Option<Duration> {
+        self.times.iter().find(|(candidate, _)| *candidate == dispatch).map(|(_, time)| *time)
+    }
+}
+
+/// Stores how long every game in a directory took to run under each design.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BenchReport {
+    /// The number of frames each game was run for.
+    pub frames: u64,
+    /// The result of every game, in path order.
+    pub results: Vec<BenchResult>
+}
+
+impl BenchReport {
+    /// Returns the time every game took together under the design.
+    ///
+    /// # Parameters
+    ///
+    /// * `dispatch` - The design to total.
+    #[must_use]
+    pub fn total_time(&self, dispatch: Dispatch) -> Duration {
+        self.results.iter().filter_map(|result| result.time(dispatch)).sum()
+    }
+
+    /// Returns the report as a Markdown table with a row per game and a column per design, followed by the totals.
+    /// Each time is in milliseconds, along with how many times faster than the match-based design it ran (e.g. `12.50 (1.20x)`).
+    #[must_use]
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!("# Benchmark report\n\n{} games run for {} frames under each design, the fastest of {BENCH_RUNS} runs.\n\n| Game |", self.results.len(), self.frames);
+        for dispatch in Dispatch::ALL {
+            let _ = write!(markdown, " {} (ms) |", dispatch.name());
+        }
+        markdown.push_str("\n| --- |");
+        markdown.push_str(&" --- |".repeat(Dispatch::ALL.len()));
+        markdown.push('\n');
+
+        for result in &self.results {
+            let _ = write!(markdown, "| {} |", result.path.replace('|', "\\|"));
+            Self::write_times(&mut markdown, |dispatch| result.time(dispatch));
+        }
+        markdown.push_str("| **Total** |");
+        Self::write_times(&mut markdown, |dispatch| Some(self.total_time(dispatch)));
+
+        markdown
+    }
+
+    /// Writes the cells holding the time of each design to the end of a row, ending the row.
+    ///
+    /// # Parameters
+    ///
+    /// * `markdown` - The table being written.
+    /// * `time` - Returns the time of a design, or `None` if it was not run.
+    fn write_times(markdown: &mut String, time: impl Fn(Dispatch) -> Option<Duration>) {
+        let baseline = time(Dispatch::Match);
+        for dispatch in Dispatch::ALL {
+            match (time(dispatch), baseline) {
+                (Some(time), Some(baseline)) if !time.is_zero() => {
+                    let _ = write!(markdown, " {:.2} ({:.2}x) |", time.as_secs_f64() * 1000.0, baseline.as_secs_f64() / time.as_secs_f64());
+                },
+                (Some(time), _) => {
+                    let _ = write!(markdown, " {:.2} |", time.as_secs_f64() * 1000.0);
+                },
+                (None, _) => markdown.push_str(" - |")
+            }
+        }
+        markdown.push('\n');
+    }
+}
+
+/// Runs the loaded game for the provided number of frames under the design, returning how long the frames took.
+/// The queued game is loaded before the timing starts, so only the frames themselves are timed.
+///
+/// # Parameters
+///
+/// * `emulator` - The emulator with the game queued.
+/// * `dispatch` - The design to decode with.
+/// * `frames` - The number of frames to run.
+pub fn time_frames(emulator: &mut Emulator, dispatch: Dispatch, frames: u64) -> Duration {
+    emulator.interpreter_mut().set_dispatch(dispatch);
+    emulator.apply_pending_load();
+
+    let start = Instant::now();
+    for _ in 0..frames {
+        emulator.step_frame();
+    }
+
+    start.elapsed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::Interpreter;
+
+    #[test]
+    fn time_each_design() {
+        let mut emulator = Emulator::new(Interpreter::new(), 10);
+        emulator.queue_load(vec![0x70, 0x01, 0x12, 0x00]);
+        time_frames(&mut emulator, Dispatch::Predecoded, 5);
+        assert_eq!(emulator.interpreter().dispatch(), Dispatch::Predecoded, "Design not used.");
+        assert_eq!(emulator.interpreter().registers()[0x0], 25, "Frames not run.");
+    }
+
+    #[test]
+    fn write_report() {
+        let report = BenchReport {
+            frames: 600,
+            results: vec![BenchResult {
+                path: String::from("games/a.ch8"),
+                times: vec![(Dispatch::Match, Duration::from_millis(12)), (Dispatch::FunctionTable, Duration::from_millis(10)), (Dispatch::Predecoded, Duration::from_millis(8))]
+            }]
+        };
+
+        let markdown = report.to_markdown();
+        assert!(markdown.contains("| Game | Match (ms) | Function table (ms) | Predecoded (ms) |\n| --- | --- | --- | --- |\n"), "Header written incorrectly: {markdown}");
+        assert!(markdown.contains("| games/a.ch8 | 12.00 (1.00x) | 10.00 (1.20x) | 8.00 (1.50x) |\n"), "Game row written incorrectly: {markdown}");
+        assert!(markdown.ends_with("| **Total** | 12.00 (1.00x) | 10.00 (1.20x) | 8.00 (1.50x) |\n"), "Totals written incorrectly: {markdown}");
+    }
+}
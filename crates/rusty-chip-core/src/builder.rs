@@ -0,0 +1,226 @@
+//! A module to construct interpreters (and the emulators running them) one setting at a time, rather than through constructors which grow a parameter with every feature.
+//! Every setting has a default, so only those which differ need to be provided:
+//!
+//! ```
+//! use rusty_chip_core::builder::InterpreterBuilder;
+//! use rusty_chip_core::quirks::Platform;
+//!
+//! let emulator = InterpreterBuilder::new()
+//!     .quirks(Platform::Schip.quirk_config())
+//!     .seed(1234)
+//!     .load_address(0x200)
+//!     .cycles_per_frame(30)
+//!     .build_emulator()
+//!     .expect("The load address leaves room for the font.");
+//! assert_eq!(emulator.cycles_per_frame(), 30);
+//! ```
+
+#[cfg(not(feature = "std"))]
+use crate::alloc_prelude::*;
+use crate::backend::{AudioSink, Display};
+#[cfg(feature = "std")]
+use crate::emulator::{Emulator, DEFAULT_CYCLES_PER_FRAME};
+use crate::interpreter::{Interpreter, MemoryProtection, PROGRAM_START_ADDRESS};
+use crate::quirks::QuirkConfig;
+
+/// Collects the settings of an interpreter, then builds it.
+pub struct InterpreterBuilder {
+    quirk_config: QuirkConfig,
+    seed: Option<u64>,
+    load_address: u16,
+    is_banking: bool,
+    detects_hires: bool,
+    memory_protection: MemoryProtection,
+    #[cfg(feature = "std")]
+    cycles_per_frame: u32,
+    display: Option<Box<dyn Display>>,
+    audio: Option<Box<dyn AudioSink>>
+}
+
+impl Default for InterpreterBuilder {
+    fn default() -> Self {
+        InterpreterBuilder::new()
+    }
+}
+
+impl InterpreterBuilder {
+    /// Returns a builder with the default settings: the default quirks, a random seed, games loaded at `0x200` without banking or memory protection, HiRes CHIP-8 games recognized, [`DEFAULT_CYCLES_PER_FRAME`], and no display or audio.
+    #[must_use]
+    pub fn new() -> InterpreterBuilder {
+        InterpreterBuilder {
+            quirk_config: QuirkConfig::new(),
+            seed: None,
+            load_address: PROGRAM_START_ADDRESS,
+            is_banking: false,
+            detects_hires: true,
+            memory_protection: MemoryProtection::default(),
+            #[cfg(feature = "std")]
+            cycles_per_frame: DEFAULT_CYCLES_PER_FRAME,
+            display: None,
+            audio: None
+        }
+    }
+
+    /// Sets the quirks to run games with.
+    ///
+    /// # Parameters
+    ///
+    /// * `quirk_config` - The enabled/disabled status of all the quirks.
+    #[must_use]
+    pub fn quirks(mut self, quirk_config: QuirkConfig) -> InterpreterBuilder {
+        self.quirk_config = quirk_config;
+        self
+    }
+
+    /// Seeds the generator used by the random opcode, so that runs of a game can be reproduced exactly. Without a seed, the generator is seeded from the system's entropy.
+    ///
+    /// # Parameters
+    ///
+    /// * `seed` - The seed for the generator.
+    #[must_use]
+    pub fn seed(mut self, seed: u64) -> InterpreterBuilder {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Sets the address games are loaded at (see [`Interpreter::set_load_address`]).
+    ///
+    /// # Parameters
+    ///
+    /// * `load_address` - The address of the first byte of the game.
+    #[must_use]
+    pub fn load_address(mut self, load_address: u16) -> InterpreterBuilder {
+        self.load_address = load_address;
+        self
+    }
+
+    /// Sets whether games are split into banks (see [`Interpreter::set_banking`]).
+    ///
+    /// # Parameters
+    ///
+    /// * `enabled` - True to split games into banks, false to load them whole.
+    #[must_use]
+    pub fn banking(mut self, enabled: bool) -> InterpreterBuilder {
+        self.is_banking = enabled;
+        self
+    }
+
+    /// Sets whether HiRes CHIP-8 games are recognized when loaded (see [`Interpreter::set_hires_detection`]).
+    ///
+    /// # Parameters
+    ///
+    /// * `enabled` - True to recognize HiRes CHIP-8 games, false to run every game as a regular one.
+    #[must_use]
+    pub fn hires_detection(mut self, enabled: bool) -> InterpreterBuilder {
+        self.detects_hires = enabled;
+        self
+    }
+
+    /// Sets the regions of memory the game is prevented from writing to.
+    ///
+    /// # Parameters
+    ///
+    /// * `memory_protection` - The regions to protect.
+    #[must_use]
+    pub fn memory_protection(mut self, memory_protection: MemoryProtection) -> InterpreterBuilder {
+        self.memory_protection = memory_protection;
+        self
+    }
+
+    /// Sets the clock speed, as the number of instructions run in a frame. Only used by [`build_emulator`](InterpreterBuilder::build_emulator), as the interpreter itself runs an instruction at a time.
+    ///
+    /// # Parameters
+    ///
+    /// * `cycles_per_frame` - The number of instructions run in a single frame.
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn cycles_per_frame(mut self, cycles_per_frame: u32) -> InterpreterBuilder {
+        self.cycles_per_frame = cycles_per_frame;
+        self
+    }
+
+    /// Sets the window the display is drawn to. Without one, frontends draw the [framebuffer](Interpreter::framebuffer) themselves.
+    ///
+    /// # Parameters
+    ///
+    /// * `display` - The display backend.
+    #[must_use]
+    pub fn display(mut self, display: Box<dyn Display>) -> InterpreterBuilder {
+        self.display = Some(display);
+        self
+    }
+
+    /// Sets the output which plays the game's sound. Without one, the game is silent.
+    ///
+    /// # Parameters
+    ///
+    /// * `audio` - The audio backend.
+    #[must_use]
+    pub fn audio(mut self, audio: Box<dyn AudioSink>) -> InterpreterBuilder {
+        self.audio = Some(audio);
+        self
+    }
+
+    /// Returns the interpreter with the settings provided, ready to [load a game](Interpreter::load_game).
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the load address leaves no room for the font, or is past the bank window while banking.
+    pub fn build(self) -> Result<Interpreter, String> {
+        let mut interpreter = Interpreter::new_headless(self.quirk_config);
+        interpreter.set_load_address(self.load_address)?;
+        interpreter.set_banking(self.is_banking)?;
+        interpreter.set_hires_detection(self.detects_hires);
+        interpreter.set_memory_protection(self.memory_protection);
+        if let Some(seed) = self.seed {
+            interpreter.seed_random(seed);
+        }
+        interpreter.attach_backends(self.display, self.audio);
+
+        Ok(interpreter)
+    }
+
+    /// Returns an emulator running the interpreter with the settings provided, at the clock speed provided, ready to [load a game](Emulator::queue_load).
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the load address leaves no room for the font, or is past the bank window while banking.
+    #[cfg(feature = "std")]
+    pub fn build_emulator(self) -> Result<Emulator, String> {
+        let cycles_per_frame = self.cycles_per_frame;
+        Ok(Emulator::new(self.build()?, cycles_per_frame))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::quirks::DisplayWaitQuirk;
+
+    #[test]
+    fn build_with_settings() {
+        let mut quirk_config = QuirkConfig::new();
+        quirk_config.display_wait = DisplayWaitQuirk::NoWait;
+        let build = || InterpreterBuilder::new().quirks(quirk_config.clone()).seed(7).load_address(0x300).hires_detection(false).memory_protection(MemoryProtection::Font).build().unwrap();
+
+        let mut first = build();
+        assert_eq!(first.quirk_config(), &quirk_config, "Quirks not set.");
+        assert_eq!(first.load_address(), 0x300, "Load address not set.");
+        assert!(!first.detects_hires(), "HiRes detection not set.");
+        assert_eq!(first.memory_protection(), MemoryProtection::Font, "Memory protection not set.");
+
+        // Two interpreters with the same seed roll the same numbers
+        let mut second = build();
+        for interpreter in [&mut first, &mut second] {
+            interpreter.load_game(&[0xC0, 0xFF, 0xC1, 0xFF, 0xC2, 0xFF, 0xC3, 0xFF]);
+            for _ in 0..4 {
+                interpreter.handle_cycle();
+            }
+        }
+        assert_eq!(first.registers()[..4], second.registers()[..4], "Seed not set.");
+
+        let emulator = InterpreterBuilder::new().build_emulator().unwrap();
+        assert_eq!(emulator.cycles_per_frame(), DEFAULT_CYCLES_PER_FRAME, "Default clock speed not used.");
+        assert!(InterpreterBuilder::new().load_address(0x0).build().is_err(), "Load address over the font accepted.");
+    }
+}
@@ -0,0 +1,87 @@
+//! A module to put the interpreter's state into text which can be copied to the system clipboard, for pasting into bug reports and discussions about a game.
+
+use std::fmt::Write;
+
+use crate::interpreter::Interpreter;
+use crate::opcodes::{self, OpcodeBytes};
+
+/// The offset basis of the 64-bit FNV-1a hash.
+const FNV_OFFSET_BASIS: u64 = 0xCBF2_9CE4_8422_2325;
+
+/// The prime of the 64-bit FNV-1a hash.
+const FNV_PRIME: u64 = 0x0000_0100_0000_01B3;
+
+/// Returns the instruction at the program counter, e.g. `0x206: 6105 LD V1, 0x05`, labelled from the interpreter's [symbols](Interpreter::symbols).
+///
+/// # Parameters
+///
+/// * `interpreter` - The interpreter to read from.
+#[must_use]
+pub fn disassembly_line(interpreter: &Interpreter) -> String {
+    let address = interpreter.program_counter();
+    let start = usize::from(address);
+    let Some(bytes) = interpreter.ram().get(start..start + 2) else {
+        return format!("0x{address:03X}: (past the end of the RAM)");
+    };
+
+    // The XO-CHIP long load is followed by its address
+    let length = usize::from(OpcodeBytes::build(bytes).length());
+    let opcode_bytes = OpcodeBytes::build(interpreter.ram().get(start..start + length).unwrap_or(bytes));
+    opcodes::instruction_line(address, &opcode_bytes, interpreter.symbols())
+}
+
+/// Returns the program counter, register I, timers, and general purpose registers on a single line.
+///
+/// # Parameters
+///
+/// * `interpreter` - The interpreter to read from.
+#[must_use]
+pub fn register_dump(interpreter: &Interpreter) -> String {
+    let mut dump = format!("PC=0x{:03X} I=0x{:03X} DT={} ST={}", interpreter.program_counter(), interpreter.register_i(), interpreter.delay_timer(), interpreter.sound_timer());
+    for (register, value) in interpreter.registers().iter().enumerate() {
+        let _ = write!(dump, " V{register:X}=0x{value:02X}");
+    }
+
+    dump
+}
+
+/// Returns a hash of the pixels on the display, so that two people can check they are looking at the exact same frame.  
+/// The 64-bit FNV-1a hash is used as it is short, stable across platforms and versions, and trivial to reimplement.
+///
+/// # Parameters
+///
+/// * `interpreter` - The interpreter to read from.
+#[must_use]
+pub fn framebuffer_hash(interpreter: &Interpreter) -> String {
+    // Each pixel is hashed as its XO-CHIP planes, which is just whether it is on for other games
+    let pixels = interpreter.display_buffer().zip(interpreter.second_plane_buffer());
+    let hash = pixels.fold(FNV_OFFSET_BASIS, |hash, (first, second)| (hash ^ (u64::from(first) | (u64::from(second) << 1))).wrapping_mul(FNV_PRIME));
+
+    format!("{hash:016x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn copy_text() {
+        let mut interpreter = Interpreter::new();
+        interpreter.load_game(&[0x61, 0x05, 0xA2, 0x34, 0x51, 0x27]);
+        let blank_hash = framebuffer_hash(&interpreter);
+
+        assert_eq!(disassembly_line(&interpreter), "0x200: 6105 LD V1, 0x05", "Instruction copied incorrectly.");
+        interpreter.handle_cycle();
+        interpreter.handle_cycle();
+        assert_eq!(register_dump(&interpreter), "PC=0x204 I=0x234 DT=0 ST=0 V0=0x00 V1=0x05 V2=0x00 V3=0x00 V4=0x00 V5=0x00 V6=0x00 V7=0x00 V8=0x00 V9=0x00 VA=0x00 VB=0x00 VC=0x00 VD=0x00 VE=0x00 VF=0x00", "Registers copied incorrectly.");
+        assert_eq!(disassembly_line(&interpreter), "0x204: 5127 (unrecognized)", "Unrecognized instruction copied incorrectly.");
+        interpreter.load_game(&[0xF0, 0x00, 0x12, 0x34]);
+        assert_eq!(disassembly_line(&interpreter), "0x200: F000 1234 LD I, 0x1234", "Long instruction copied incorrectly.");
+
+        interpreter.load_game(&[0xD0, 0x05]);
+        interpreter.handle_cycle();
+        interpreter.handle_frame();
+        assert_ne!(framebuffer_hash(&interpreter), blank_hash, "Hash did not change with the display.");
+        assert_eq!(framebuffer_hash(&interpreter).len(), 16, "Hash not padded.");
+    }
+}
@@ -0,0 +1,250 @@
+//! A module to report how well the emulator runs a whole directory of games, so that compatibility can be tracked across releases.
+//! Each game is run without input for a number of frames under the settings the [database](crate::database) recommends, and is marked by how the run ended: still running with something on the display, showing a blank display, exited, or faulted.
+//! Reports are written as Markdown, or as JSON if the file ends in `.json`.
+
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::emulator::{Emulator, EmulatorMode};
+use crate::fuzz::{self, Fault};
+
+/// The names of the outcomes, in the order they are summarized.
+const OUTCOME_NAMES: [&str; 4] = ["passed", "blank-screen", "exited", "faulted"];
+
+/// Denotes the format the report is written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Markdown,
+    Json
+}
+
+impl ReportFormat {
+    /// Returns the format matching the extension of the provided path, defaulting to Markdown.
+    ///
+    /// # Parameters
+    ///
+    /// * `path` - The path of the report.
+    #[must_use]
+    pub fn from_path(path: &str) -> ReportFormat {
+        let is_json = Path::new(path).extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        if is_json { ReportFormat::Json } else { ReportFormat::Markdown }
+    }
+}
+
+/// Denotes how the run of a game ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// The game ran every frame and has something on the display.
+    Passed,
+    /// The game ran every frame, but nothing is on the display.
+    BlankScreen,
+    /// The game exited through the `0000` or SUPER-CHIP `00FD` opcode.
+    Exited,
+    /// The game halted with an [error](crate::interpreter::HaltReason::is_error) or the interpreter panicked.
+    Faulted(Fault)
+}
+
+impl Outcome {
+    /// Returns the short name of the outcome used in reports, e.g. `blank-screen`.
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        match self {
+            Outcome::Passed => "passed",
+            Outcome::BlankScreen => "blank-screen",
+            Outcome::Exited => "exited",
+            Outcome::Faulted(_) => "faulted"
+        }
+    }
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Faulted(fault) => write!(f, "{}: {fault}", self.name()),
+            _ => write!(f, "{}", self.name())
+        }
+    }
+}
+
+/// Stores how a single game fared.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameResult {
+    /// The path to the game file.
+    pub path: String,
+    /// The title of the game, if it is in the database.
+    pub title: Option<String>,
+    /// The platform the game was run as.
+    pub platform: String,
+    /// The number of frames run before the game stopped, or all of them if it did not.
+    pub frames_run: u64,
+    pub outcome: Outcome
+}
+
+/// Stores how every game in a directory fared.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CompatReport {
+    /// The number of frames each game was run for.
+    pub frames: u64,
+    /// The result of every game, in path order.
+    pub results: Vec<GameResult>
+}
+
+impl CompatReport {
+    /// Returns the number of games with each outcome, e.g. `3 passed, 1 blank-screen, 0 exited, 1 faulted`.
+    #[must_use]
+    pub fn summary(&self) -> String {
+        let counts: Vec<String> = OUTCOME_NAMES.iter()
+            .map(|name| format!("{} {name}", self.results.iter().filter(|result| result.outcome.name() == *name).count()))
+            .collect();
+
+        counts.join(", ")
+    }
+
+    /// Returns the report as a Markdown table with a row per game, headed by the summary.
+    #[must_use]
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!("# Compatibility report\n\n{} games run for {} frames each: {}.\n\n", self.results.len(), self.frames, self.summary());
+        markdown.push_str("| Game | Title | Platform | Frames | Outcome |\n| --- | --- | --- | --- | --- |\n");
+        for result in &self.results {
+            let title = result.title.as_deref().unwrap_or("-");
+            markdown.push_str(&format!("| {} | {} | {} | {} | {} |\n", escape_markdown(&result.path), escape_markdown(title), result.platform, result.frames_run, escape_markdown(&result.outcome.to_string())));
+        }
+
+        markdown
+    }
+
+    /// Returns the report as a JSON object, with a `results` array holding an object per game.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let results: Vec<String> = self.results.iter().map(|result| {
+            let title = result.title.as_deref().map_or_else(|| String::from("null"), |title| format!("\"{}\"", escape_json(title)));
+            let fault = match &result.outcome {
+                Outcome::Faulted(fault) => format!("\"{}\"", escape_json(&fault.to_string())),
+                _ => String::from("null")
+            };
+            format!(
+                "{{\"path\":\"{}\",\"title\":{title},\"platform\":\"{}\",\"frames_run\":{},\"outcome\":\"{}\",\"fault\":{fault}}}",
+                escape_json(&result.path), escape_json(&result.platform), result.frames_run, result.outcome.name()
+            )
+        }).collect();
+
+        format!("{{\"frames\":{},\"results\":[{}]}}\n", self.frames, results.join(","))
+    }
+
+    /// Writes the report to the provided path, in the format matching its extension.
+    ///
+    /// # Parameters
+    ///
+    /// * `path` - The path of the report.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the file cannot be written.
+    pub fn write(&self, path: &str) -> io::Result<()> {
+        let report = match ReportFormat::from_path(path) {
+            ReportFormat::Markdown => self.to_markdown(),
+            ReportFormat::Json => self.to_json()
+        };
+
+        fs::write(path, report)
+    }
+}
+
+/// Runs the loaded game without input for up to the provided number of frames, returning the number of frames run and how the run ended.
+///
+/// # Parameters
+///
+/// * `emulator` - The emulator with the game queued.
+/// * `frames` - The most frames to run.
+pub fn check(emulator: &mut Emulator, frames: u64) -> (u64, Outcome) {
+    for frame in 0..frames {
+        if let Err(fault) = fuzz::step_frame_catching_panics(emulator) {
+            return (frame + 1, Outcome::Faulted(fault));
+        }
+
+        match emulator.mode() {
+            EmulatorMode::Halted(reason) if reason.is_error() => return (frame + 1, Outcome::Faulted(Fault::Halted(reason))),
+            EmulatorMode::Halted(_) => return (frame + 1, Outcome::Exited),
+            _ => {}
+        }
+    }
+
+    let interpreter = emulator.interpreter();
+    let is_blank = !interpreter.display_buffer().chain(interpreter.second_plane_buffer()).any(|pixel| pixel);
+
+    (frames, if is_blank { Outcome::BlankScreen } else { Outcome::Passed })
+}
+
+/// Returns the text with the characters which would break out of a Markdown table cell escaped.
+///
+/// # Parameters
+///
+/// * `text` - The text to escape.
+fn escape_markdown(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+/// Returns the text with the characters which are special in JSON strings escaped.
+///
+/// # Parameters
+///
+/// * `text` - The text to escape.
+fn escape_json(text: &str) -> String {
+    text.chars().fold(String::with_capacity(text.len()), |mut escaped, character| {
+        match character {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            character if character.is_control() => escaped.push_str(&format!("\\u{:04x}", u32::from(character))),
+            character => escaped.push(character)
+        }
+        escaped
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::{HaltReason, Interpreter};
+
+    /// Runs the game for a second, returning how it ended.
+    fn check_game(game_data: &[u8]) -> (u64, Outcome) {
+        let mut emulator = Emulator::new(Interpreter::new(), 10);
+        emulator.queue_load(game_data.to_vec());
+        check(&mut emulator, 60)
+    }
+
+    #[test]
+    fn check_outcomes() {
+        assert_eq!(check_game(&[0x00, 0xE0, 0xD0, 0x15, 0x12, 0x04]), (60, Outcome::Passed), "Drawing game not passed.");
+        assert_eq!(check_game(&[0x12, 0x00]), (60, Outcome::BlankScreen), "Blank display not noticed.");
+        assert_eq!(check_game(&[0x00, 0x00]), (1, Outcome::Exited), "Exit not noticed.");
+        assert_eq!(check_game(&[0x00, 0xEE]), (1, Outcome::Faulted(Fault::Halted(HaltReason::StackUnderflow { address: 0x200 }))), "Fault not noticed.");
+    }
+
+    #[test]
+    fn write_reports() {
+        let report = CompatReport {
+            frames: 60,
+            results: vec![
+                GameResult { path: String::from("games/a.ch8"), title: Some(String::from("A \"Game\"")), platform: String::from("CHIP-8"), frames_run: 60, outcome: Outcome::Passed },
+                GameResult { path: String::from("games/b.ch8"), title: None, platform: String::from("CHIP-8"), frames_run: 1, outcome: Outcome::Faulted(Fault::Panicked(String::from("a|b"))) }
+            ]
+        };
+
+        assert_eq!(report.summary(), "1 passed, 0 blank-screen, 0 exited, 1 faulted", "Outcomes not counted.");
+        let markdown = report.to_markdown();
+        assert!(markdown.contains("| games/a.ch8 | A \"Game\" | CHIP-8 | 60 | passed |\n"), "Markdown row written incorrectly: {markdown}");
+        assert!(markdown.contains("| games/b.ch8 | - | CHIP-8 | 1 | faulted: The interpreter panicked: a\\|b |\n"), "Markdown not escaped: {markdown}");
+        assert_eq!(
+            report.to_json(),
+            "{\"frames\":60,\"results\":[{\"path\":\"games/a.ch8\",\"title\":\"A \\\"Game\\\"\",\"platform\":\"CHIP-8\",\"frames_run\":60,\"outcome\":\"passed\",\"fault\":null},\
+             {\"path\":\"games/b.ch8\",\"title\":null,\"platform\":\"CHIP-8\",\"frames_run\":1,\"outcome\":\"faulted\",\"fault\":\"The interpreter panicked: a|b\"}]}\n",
+            "JSON written incorrectly."
+        );
+        assert_eq!(ReportFormat::from_path("compat.JSON"), ReportFormat::Json, "JSON path detected incorrectly.");
+        assert_eq!(ReportFormat::from_path("compat.md"), ReportFormat::Markdown, "Markdown path detected incorrectly.");
+    }
+}
@@ -0,0 +1,256 @@
+//! A module to read the config file, which sets the palette games are drawn in and whose sections override the quirks, tick rate, and palette of specific games so that they always run with their intended settings.
+//! The config is kept in [`CONFIG_PATH`](CONFIG_PATH), written in a small subset of TOML: the settings for every game, then a `[rom]` section per game, named by its file name or by the SHA-1 hash the [database](crate::database) identifies it by, with `#` starting a comment line:
+//!
+//! ```text
+//! palette = "amber"
+//!
+//! [rom."PONG2.ch8"]
+//! platform = "schip"
+//! tick-rate = 30
+//!
+//! [rom.0d0d0b8f1d1c19b1ae5c1e0c9de2ec0e0e1d1a8b]
+//! quirks = "shifting=vx jumping=vx"
+//! palette = "#000000, #33FF66"
+//! ```
+//!
+//! The quirks start from the `platform`'s preset if there is one, and are then changed as written in `quirks` (as the quirks are [displayed](QuirkConfig)). A `palette` is a [preset](crate::palette::PalettePreset) name or colours, as parsed by [`Palette`](Palette).
+//! File names are matched regardless of case and directory, and a section for the game's hash is used over one for its name.
+
+use std::io::{self, ErrorKind};
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::database;
+use crate::names::Named;
+use crate::palette::Palette;
+use crate::quirks::{Platform, QuirkConfig};
+use crate::storage::StorageBackend;
+
+/// The path of the config file, relative to the working directory.
+pub const CONFIG_PATH: &str = "rusty_chip.toml";
+
+/// The character starting a comment line.
+const COMMENT_PREFIX: char = '#';
+
+/// The prefix of the name of a section overriding the settings of a game.
+const ROM_SECTION_PREFIX: &str = "rom.";
+
+/// The number of hexadecimal digits in a SHA-1 hash.
+const HASH_LENGTH: usize = 40;
+
+/// Denotes how a section of the config picks out the game it applies to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RomKey {
+    /// The file name of the game (e.g. `PONG2.ch8`), matched regardless of case and directory.
+    FileName(String),
+    /// The lowercase hexadecimal SHA-1 hash of the game.
+    Hash(String)
+}
+
+/// Stores the settings a section of the config overrides for a game. Settings left out keep those the game would otherwise run with.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RomOverrides {
+    /// The platform whose quirks the game runs with.
+    pub platform: Option<Platform>,
+    /// The quirks changed from the platform's, written as the quirks are [displayed](QuirkConfig) (e.g. `shifting=vx`).
+    pub quirks: Option<String>,
+    /// The number of instruction cycles run per frame.
+    pub tick_rate: Option<u32>,
+    /// The colours the game is drawn in.
+    pub palette: Option<Palette>
+}
+
+impl RomOverrides {
+    /// Returns the quirks the game runs with: the platform's preset (or the provided quirks without one), changed as the overrides say.
+    ///
+    /// # Parameters
+    ///
+    /// * `quirk_config` - The quirks the game would otherwise run with.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the quirks to change are malformed.
+    pub fn quirk_config(&self, quirk_config: &QuirkConfig) -> Result<QuirkConfig, String> {
+        let mut quirk_config = self.platform.map_or_else(|| quirk_config.clone(), Platform::quirk_config);
+        if let Some(quirks) = &self.quirks {
+            quirk_config.apply_settings(quirks)?;
+        }
+
+        Ok(quirk_config)
+    }
+
+    /// Changes the setting from a line of the section.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The name of the setting.
+    /// * `value` - The value of the setting, without any quotes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the setting is unknown or its value is malformed.
+    fn apply(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "platform" => self.platform = Some(Platform::from_name(value).ok_or_else(|| format!("Unknown platform \"{value}\"."))?),
+            "quirks" => {
+                QuirkConfig::new().apply_settings(value)?;
+                self.quirks = Some(value.to_string());
+            },
+            "tick-rate" => self.tick_rate = Some(value.parse().ok().filter(|tick_rate| *tick_rate > 0).ok_or_else(|| format!("The tick rate must be a whole number above 0, not \"{value}\"."))?),
+            "palette" => self.palette = Some(value.parse()?),
+            _ => return Err(format!("Unknown setting \"{key}\"."))
+        }
+
+        Ok(())
+    }
+}
+
+/// Stores the settings read from the config file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Config {
+    /// The colours games are drawn in, unless their section or their own saved palette says otherwise.
+    pub palette: Option<Palette>,
+    /// The games whose settings are overridden, in the order they appear in the file.
+    pub roms: Vec<(RomKey, RomOverrides)>
+}
+
+impl Config {
+    /// Loads the config saved at [`CONFIG_PATH`](CONFIG_PATH), returning an empty one if there is none.
+    ///
+    /// # Parameters
+    ///
+    /// * `storage` - Where the config is saved.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the config exists but cannot be read or is malformed.
+    pub fn load(storage: &dyn StorageBackend) -> io::Result<Config> {
+        let Some(text) = storage.read_to_string(CONFIG_PATH)? else {
+            return Ok(Config::default());
+        };
+
+        text.parse().map_err(|e| io::Error::new(ErrorKind::InvalidData, e))
+    }
+
+    /// Returns the overrides for the game, if the config has a section for it. A section for the game's hash is used over one for its file name.
+    ///
+    /// # Parameters
+    ///
+    /// * `game_path` - The path to the game file.
+    /// * `game_data` - The bytes of the game file.
+    #[must_use]
+    pub fn rom_overrides(&self, game_path: &str, game_data: &[u8]) -> Option<&RomOverrides> {
+        let hash = database::hash(game_data);
+        let file_name = Path::new(game_path).file_name().and_then(|file_name| file_name.to_str()).unwrap_or(game_path);
+
+        self.roms.iter().find(|(key, _)| matches!(key, RomKey::Hash(key_hash) if *key_hash == hash))
+            .or_else(|| self.roms.iter().find(|(key, _)| matches!(key, RomKey::FileName(name) if name.eq_ignore_ascii_case(file_name))))
+            .map(|(_, overrides)| overrides)
+    }
+}
+
+impl FromStr for Config {
+    type Err = String;
+
+    /// Parses the config format described in the [module documentation](self), returning an `Err` naming the first malformed line.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut config = Config::default();
+        for (index, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with(COMMENT_PREFIX) {
+                continue;
+            }
+
+            let line_error = |e: String| format!("line {}: {e}", index + 1);
+            if let Some(section) = line.strip_prefix('[').and_then(|section| section.strip_suffix(']')) {
+                config.roms.push((parse_rom_key(section.trim()).map_err(line_error)?, RomOverrides::default()));
+                continue;
+            }
+
+            let (key, value) = line.split_once('=').ok_or_else(|| line_error(format!("Setting \"{line}\" is not of the form key = value.")))?;
+            let (key, value) = (key.trim(), value.trim());
+            let value = value.strip_prefix('"').and_then(|value| value.strip_suffix('"')).unwrap_or(value);
+            match config.roms.last_mut() {
+                Some((_, overrides)) => overrides.apply(key, value).map_err(line_error)?,
+                None if key == "palette" => config.palette = Some(value.parse().map_err(line_error)?),
+                None => return Err(line_error(format!("Setting \"{key}\" is outside of a [rom] section.")))
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+/// Reads the name of a section (e.g. `rom."PONG2.ch8"`) into the game it applies to.
+///
+/// # Parameters
+///
+/// * `section` - The name of the section, without the brackets.
+///
+/// # Errors
+///
+/// Returns an `Err` if the section is not a `[rom]` section or names no game.
+fn parse_rom_key(section: &str) -> Result<RomKey, String> {
+    let name = section.strip_prefix(ROM_SECTION_PREFIX).ok_or_else(|| format!("Unknown section [{section}]."))?;
+    let name = name.strip_prefix('"').and_then(|name| name.strip_suffix('"')).unwrap_or(name);
+    if name.is_empty() {
+        return Err(String::from("The [rom] section names no game."));
+    }
+
+    if name.len() == HASH_LENGTH && name.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(RomKey::Hash(name.to_ascii_lowercase()))
+    } else {
+        Ok(RomKey::FileName(name.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::palette::{Colour, PalettePreset};
+    use crate::quirks::{JumpingQuirk, ShiftingQuirk};
+    use crate::storage::MemoryStorage;
+
+    #[test]
+    fn parse_config() {
+        let hash = database::hash(b"game");
+        let text = format!("palette = \"paper-white\"\n# Games which need help\n[rom.\"PONG2.ch8\"]\nplatform = \"schip\"\ntick-rate = 30\n\n[rom.{}]\nquirks = \"shifting=vx\"\npalette = \"#000000, #33FF66\"\n", hash.to_uppercase());
+        let config: Config = text.parse().unwrap();
+        assert_eq!(config.palette, Some(PalettePreset::PaperWhite.palette()), "Palette for every game not parsed.");
+
+        let overrides = config.rom_overrides("games/pong2.CH8", b"other game").unwrap();
+        assert_eq!(overrides.platform, Some(Platform::Schip), "Platform not parsed.");
+        assert_eq!(overrides.tick_rate, Some(30), "Tick rate not parsed.");
+        assert_eq!(overrides.quirk_config(&QuirkConfig::new()), Ok(Platform::Schip.quirk_config()), "Platform's quirks not used.");
+
+        let overrides = config.rom_overrides("PONG2.ch8", b"game").unwrap();
+        assert_eq!(overrides.tick_rate, None, "Hash not used over the file name.");
+        let quirk_config = overrides.quirk_config(&Platform::Schip.quirk_config()).unwrap();
+        assert_eq!((quirk_config.shifting, quirk_config.jumping), (ShiftingQuirk::Vx, JumpingQuirk::Vx), "Quirks not changed from the provided ones.");
+        assert_eq!(overrides.palette.as_ref().map(|palette| palette.colours[1]), Some(Colour::rgb(0x33, 0xFF, 0x66)), "Palette not parsed.");
+        assert_eq!(config.rom_overrides("BRIX.ch8", b"other game"), None, "Overrides found for another game.");
+    }
+
+    #[test]
+    fn parse_malformed_config() {
+        assert_eq!("tick-rate = 30".parse::<Config>(), Err(String::from("line 1: Setting \"tick-rate\" is outside of a [rom] section.")), "Setting outside of a section parsed.");
+        assert!("[settings]".parse::<Config>().is_err(), "Unknown section parsed.");
+        assert!("[rom.\"\"]".parse::<Config>().is_err(), "Section without a game parsed.");
+        assert!("[rom.a]\ntick-rate = 0".parse::<Config>().is_err(), "Tick rate of 0 parsed.");
+        assert!("[rom.a]\nplatform = \"chip-9\"".parse::<Config>().is_err(), "Unknown platform parsed.");
+        assert!("[rom.a]\nquirks = \"shifting=vz\"".parse::<Config>().is_err(), "Unknown quirk setting parsed.");
+        assert!("[rom.a]\nspeed = 2".parse::<Config>().is_err(), "Unknown setting parsed.");
+        assert!("[rom.a]\nplatform".parse::<Config>().is_err(), "Setting without a value parsed.");
+    }
+
+    #[test]
+    fn load_config() {
+        let mut storage = MemoryStorage::new();
+        assert_eq!(Config::load(&storage).unwrap(), Config::default(), "Config loaded without one saved.");
+
+        storage.write(CONFIG_PATH, b"[rom.\"PONG2.ch8\"]\ntick-rate = 30\n").unwrap();
+        assert_eq!(Config::load(&storage).unwrap().roms, vec![(RomKey::FileName(String::from("PONG2.ch8")), RomOverrides { tick_rate: Some(30), ..RomOverrides::default() })], "Saved config not loaded.");
+        storage.write(CONFIG_PATH, b"[rom.\"PONG2.ch8\"]\ntick-rate = fast\n").unwrap();
+        assert!(Config::load(&storage).is_err(), "Malformed config loaded.");
+    }
+}
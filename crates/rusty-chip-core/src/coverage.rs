@@ -0,0 +1,211 @@
+//! A module to track which instructions of a game have run, as a bitmap with a bit for every address in memory.  
+//! Recording is optional and costs a single bit set per instruction, so it can be left on for long fuzzing sessions. The fuzzer uses it to favour inputs which reach code no earlier input has.
+//! The kinds of opcode run are counted alongside, so that a [report](Coverage::report) can tell game authors which code never ran and test suite authors which opcodes were never exercised.
+
+use alloc::collections::BTreeMap;
+use core::fmt::Write;
+use core::ops::Range;
+
+#[cfg(not(feature = "std"))]
+use crate::alloc_prelude::*;
+use crate::interpreter::RAM_SIZE;
+use crate::opcodes::Opcode;
+
+/// The number of addresses covered by each word of the bitmap.
+const BITS_PER_WORD: usize = u64::BITS as usize;
+
+/// The most bytes between two instructions which are reported as a single range of code, allowing for the XO-CHIP long load.
+const RANGE_GAP: u16 = 4;
+
+/// Stores the addresses of every instruction which has run, along with how often each kind of opcode ran.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Coverage {
+    words: Vec<u64>,
+    opcodes: BTreeMap<&'static str, u64>
+}
+
+impl Coverage {
+    /// Returns an empty bitmap with no instructions covered.
+    #[must_use]
+    pub fn new() -> Coverage {
+        Coverage { words: vec![0; RAM_SIZE / BITS_PER_WORD], opcodes: BTreeMap::new() }
+    }
+
+    /// Marks the instruction at the address as run.
+    ///
+    /// # Parameters
+    ///
+    /// * `address` - The address of the instruction.
+    pub fn record(&mut self, address: u16) {
+        let address = usize::from(address);
+        self.words[address / BITS_PER_WORD] |= 1 << (address % BITS_PER_WORD);
+    }
+
+    /// Counts a run of the kind of opcode.
+    ///
+    /// # Parameters
+    ///
+    /// * `opcode` - The opcode which ran.
+    pub fn record_opcode(&mut self, opcode: &Opcode) {
+        *self.opcodes.entry(opcode.name()).or_default() += 1;
+    }
+
+    /// Returns the number of times the kind of opcode ran.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the [`Opcode`](Opcode) variant, e.g. `Draw`.
+    #[must_use]
+    pub fn opcode_count(&self, name: &str) -> u64 {
+        self.opcodes.get(name).copied().unwrap_or(0)
+    }
+
+    /// Returns the kinds of opcode which have run along with how often, the most frequent first.
+    #[must_use]
+    pub fn opcodes_used(&self) -> Vec<(&'static str, u64)> {
+        let mut opcodes: Vec<(&'static str, u64)> = self.opcodes.iter().map(|(name, count)| (*name, *count)).collect();
+        opcodes.sort_by(|(first_name, first), (second_name, second)| second.cmp(first).then(first_name.cmp(second_name)));
+
+        opcodes
+    }
+
+    /// Returns the kinds of opcode which have never run, in the order they are declared.
+    #[must_use]
+    pub fn opcodes_unused(&self) -> Vec<&'static str> {
+        Opcode::NAMES.into_iter().filter(|name| !self.opcodes.contains_key(name)).collect()
+    }
+
+    /// Returns the runs of instructions which have run as the addresses of their first and last instructions, in address order.  
+    /// Instructions up to 4 bytes apart are taken as a single run, so straight-line code forms one range.
+    #[must_use]
+    pub fn ranges(&self) -> Vec<(u16, u16)> {
+        let mut ranges: Vec<(u16, u16)> = Vec::new();
+        for address in self.addresses() {
+            match ranges.last_mut() {
+                Some((_, end)) if address - *end <= RANGE_GAP => *end = address,
+                _ => ranges.push((address, address))
+            }
+        }
+
+        ranges
+    }
+
+    /// Returns a summary of the coverage: how many instructions ran inside and outside the program, the ranges of code which ran, and the kinds of opcode used and never used.
+    ///
+    /// # Parameters
+    ///
+    /// * `program` - The addresses the program was loaded to.
+    #[must_use]
+    pub fn report(&self, program: &Range<usize>) -> String {
+        let inside = self.addresses().filter(|address| program.contains(&usize::from(*address))).count();
+        let mut report = format!("Instructions run: {inside} in the program, {} outside it
+Ranges run:", self.count() - inside);
+        for (start, end) in self.ranges() {
+            let _ = write!(report, " 0x{start:03X}-0x{end:03X}");
+        }
+
+        let used = self.opcodes_used();
+        let _ = writeln!(report, "\nOpcodes used: {} of {}", used.len(), Opcode::NAMES.len());
+        for (name, count) in used {
+            let _ = writeln!(report, "  {name:<28} {count:>12}");
+        }
+        let _ = writeln!(report, "Opcodes never used: {}", self.opcodes_unused().join(", "));
+
+        report
+    }
+
+    /// Returns whether the instruction at the address has run.
+    ///
+    /// # Parameters
+    ///
+    /// * `address` - The address of the instruction.
+    #[must_use]
+    pub fn contains(&self, address: u16) -> bool {
+        let address = usize::from(address);
+        self.words[address / BITS_PER_WORD] & (1 << (address % BITS_PER_WORD)) != 0
+    }
+
+    /// Returns the number of instructions which have run.
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    /// Returns the addresses of the instructions which have run, in address order.
+    pub fn addresses(&self) -> impl Iterator<Item = u16> + '_ {
+        (0..=u16::MAX).filter(|address| self.contains(*address))
+    }
+
+    /// Adds the instructions covered by the other bitmap, returning how many of them were not already covered.
+    ///
+    /// # Parameters
+    ///
+    /// * `other` - The bitmap to add.
+    pub fn merge(&mut self, other: &Coverage) -> usize {
+        let mut new_addresses = 0;
+        for (word, other_word) in self.words.iter_mut().zip(&other.words) {
+            new_addresses += (other_word & !*word).count_ones() as usize;
+            *word |= other_word;
+        }
+        for (name, count) in &other.opcodes {
+            *self.opcodes.entry(name).or_default() += count;
+        }
+
+        new_addresses
+    }
+
+    /// Clears every instruction from the bitmap, along with the opcode counts.
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+        self.opcodes.clear();
+    }
+}
+
+impl Default for Coverage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_and_merge() {
+        let mut coverage = Coverage::new();
+        coverage.record(0x200);
+        coverage.record(0xFFFF);
+        coverage.record(0x200);
+        assert!(coverage.contains(0x200), "Address not recorded.");
+        assert!(!coverage.contains(0x202), "Address recorded without running.");
+        assert_eq!(coverage.addresses().collect::<Vec<_>>(), vec![0x200, 0xFFFF], "Addresses listed incorrectly.");
+
+        let mut other = Coverage::new();
+        other.record(0x200);
+        other.record(0x204);
+        assert_eq!(coverage.merge(&other), 1, "New addresses counted incorrectly.");
+        assert_eq!(coverage.count(), 3, "Merge did not add the new address.");
+
+        coverage.clear();
+        assert_eq!(coverage.count(), 0, "Coverage not cleared.");
+    }
+
+    #[test]
+    fn report_coverage() {
+        let mut coverage = Coverage::new();
+        for address in [0x200, 0x202, 0x206, 0x210, 0x300] {
+            coverage.record(address);
+        }
+        coverage.record_opcode(&Opcode::LoadValue(0x1, 0x05));
+        coverage.record_opcode(&Opcode::LoadValue(0x2, 0x06));
+        coverage.record_opcode(&Opcode::Draw(0x0, 0x1, 5));
+
+        assert_eq!(coverage.ranges(), vec![(0x200, 0x206), (0x210, 0x210), (0x300, 0x300)], "Ranges grouped incorrectly.");
+        assert_eq!(coverage.opcodes_used(), vec![("LoadValue", 2), ("Draw", 1)], "Opcodes counted incorrectly.");
+        assert_eq!(coverage.opcodes_unused().len(), Opcode::NAMES.len() - 2, "Unused opcodes listed incorrectly.");
+        let report = coverage.report(&(0x200..0x220));
+        assert!(report.starts_with("Instructions run: 4 in the program, 1 outside it\nRanges run: 0x200-0x206 0x210-0x210 0x300-0x300\nOpcodes used: 2 of 48\n"), "Report written incorrectly: {report}");
+        assert!(report.contains("Opcodes never used: SystemAddr, ClearScreen, "), "Unused opcodes not reported: {report}");
+    }
+}
@@ -0,0 +1,255 @@
+//! A module to recognize known games by their hash, so the quirks and tick rate they need can be applied automatically.  
+//! The metadata is kept in `assets/chip8-database.txt`, using the platform names of the [chip-8-database](https://github.com/chip-8/chip-8-database). It lists the games bundled in `games`.
+
+use crate::quirks::{Platform, QuirkConfig};
+
+/// The bundled program metadata, one game per line.
+const DATABASE: &str = include_str!("../assets/chip8-database.txt");
+
+/// The character starting a comment line in the database.
+const COMMENT_PREFIX: char = '#';
+
+/// The tick rate written for games which run at the emulator's default.
+const DEFAULT_TICK_RATE: &str = "-";
+
+/// The load address written for games which are loaded at the usual `0x200`.
+const DEFAULT_LOAD_ADDRESS: &str = "-";
+
+/// The quirks written for games which run with their platform's quirks unchanged.
+const DEFAULT_QUIRKS: &str = "-";
+
+/// The character separating the quirks changed for a game, as the fields of a line are separated by whitespace.
+const QUIRK_SEPARATOR: char = ',';
+
+/// The number of bytes in a SHA-1 hash.
+const SHA1_LENGTH: usize = 20;
+
+/// Stores what the database knows about a game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DatabaseEntry {
+    /// The title of the game.
+    pub title: &'static str,
+    /// The platform the game was written for, if it is one the emulator has a [preset](Platform) for.
+    pub platform: Option<Platform>,
+    /// The number of instruction cycles the game expects per frame, if it differs from the emulator's default.
+    pub tick_rate: Option<u32>,
+    /// The address the game is loaded and started at, if it is not the usual `0x200` (e.g. `0x600` for the ETI-660).
+    pub load_address: Option<u16>,
+    /// The quirks changed from the platform's, written as the quirks are [displayed](QuirkConfig) but separated by commas (e.g. `shifting=vx,jumping=vx`).
+    pub quirks: Option<&'static str>
+}
+
+impl DatabaseEntry {
+    /// Returns the quirks the game needs, starting from the provided ones if its platform is not recognized and then changing those the entry lists.
+    ///
+    /// # Parameters
+    ///
+    /// * `quirk_config` - The quirks to use if the platform is unknown.
+    #[must_use]
+    pub fn quirk_config(&self, quirk_config: &QuirkConfig) -> QuirkConfig {
+        let mut quirk_config = self.platform.map_or_else(|| quirk_config.clone(), Platform::quirk_config);
+        if let Some(quirks) = self.quirks {
+            // The quirks were checked when the entry was read, so this cannot fail
+            if let Err(e) = quirk_config.apply_settings(&quirks.replace(QUIRK_SEPARATOR, " ")) {
+                eprintln!("Error applying the game database's quirks: {e}");
+            }
+        }
+
+        quirk_config
+    }
+}
+
+/// Returns the hexadecimal SHA-1 hash of the game, as the database identifies it.
+///
+/// # Parameters
+///
+/// * `game_data` - The bytes of the game file.
+#[must_use]
+pub fn hash(game_data: &[u8]) -> String {
+    sha1(game_data).iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Returns the database entry for the game, if it is a known one.
+///
+/// # Parameters
+///
+/// * `game_data` - The bytes of the game file.
+#[must_use]
+pub fn lookup(game_data: &[u8]) -> Option<DatabaseEntry> {
+    lookup_in(DATABASE, game_data)
+}
+
+/// Returns the entry for the game in the provided database, if it is a known one.
+///
+/// # Parameters
+///
+/// * `database` - The program metadata, one game per line.
+/// * `game_data` - The bytes of the game file.
+fn lookup_in(database: &'static str, game_data: &[u8]) -> Option<DatabaseEntry> {
+    let hash = hash(game_data);
+
+    database.lines()
+        .filter(|line| !line.is_empty() && !line.starts_with(COMMENT_PREFIX))
+        .find(|line| line.split_whitespace().next() == Some(hash.as_str()))
+        .and_then(|line| parse_entry(line).map_err(|e| eprintln!("Error reading the game database: {e}")).ok())
+}
+
+/// Reads a line of the database (hash, platform, tick rate, load address, quirks, and title) into an entry.
+///
+/// # Parameters
+///
+/// * `line` - The line to read.
+///
+/// # Errors
+///
+/// Returns an `Err` if the line is missing a field, the tick rate is not a number, the load address is not a hexadecimal address, or a quirk setting is invalid.
+fn parse_entry(line: &'static str) -> Result<DatabaseEntry, String> {
+    let mut fields = line.splitn(6, char::is_whitespace);
+    let (Some(_hash), Some(platform), Some(tick_rate), Some(load_address), Some(quirks), Some(title)) = (fields.next(), fields.next(), fields.next(), fields.next(), fields.next(), fields.next()) else {
+        return Err(format!("Incomplete entry: {line}"));
+    };
+
+    let tick_rate = match tick_rate {
+        DEFAULT_TICK_RATE => None,
+        tick_rate => Some(tick_rate.parse().map_err(|_| format!("Invalid tick rate: {tick_rate}"))?)
+    };
+
+    let load_address = match load_address {
+        DEFAULT_LOAD_ADDRESS => None,
+        load_address => Some(u16::from_str_radix(load_address.trim_start_matches("0x"), 16).map_err(|_| format!("Invalid load address: {load_address}"))?)
+    };
+
+    let quirks = match quirks {
+        DEFAULT_QUIRKS => None,
+        quirks => {
+            QuirkConfig::new().apply_settings(&quirks.replace(QUIRK_SEPARATOR, " "))?;
+            Some(quirks)
+        }
+    };
+
+    Ok(DatabaseEntry { title: title.trim(), platform: platform_preset(platform), tick_rate, load_address, quirks })
+}
+
+/// Returns the preset matching a platform named as in the database, if there is one.
+///
+/// # Parameters
+///
+/// * `platform` - The database's name for the platform.
+fn platform_preset(platform: &str) -> Option<Platform> {
+    match platform {
+        "originalChip8" | "hybridVIP" => Some(Platform::CosmacVip),
+        "chip48" => Some(Platform::Chip48),
+        "superchip1" | "superchip" => Some(Platform::Schip),
+        "xochip" => Some(Platform::XoChip),
+        // Modern CHIP-8 is what the default quirks already expect
+        _ => None
+    }
+}
+
+/// Returns the SHA-1 hash of the bytes, which the database identifies games by.
+///
+/// # Parameters
+///
+/// * `bytes` - The bytes to hash.
+fn sha1(bytes: &[u8]) -> [u8; SHA1_LENGTH] {
+    let mut state: [u32; 5] = [0x6745_2301, 0xEFCD_AB89, 0x98BA_DCFE, 0x1032_5476, 0xC3D2_E1F0];
+
+    // Pad to a whole number of 64-byte blocks, ending with the length in bits
+    let mut message = bytes.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((bytes.len() as u64) * 8).to_be_bytes());
+
+    for block in message.chunks_exact(64) {
+        let mut words = [0u32; 80];
+        for (i, word) in block.chunks_exact(4).enumerate() {
+            words[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..80 {
+            words[i] = (words[i - 3] ^ words[i - 8] ^ words[i - 14] ^ words[i - 16]).rotate_left(1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e] = state;
+        for (i, word) in words.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5A82_7999),
+                20..=39 => (b ^ c ^ d, 0x6ED9_EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1B_BCDC),
+                _ => (b ^ c ^ d, 0xCA62_C1D6)
+            };
+            let temp = a.rotate_left(5).wrapping_add(f).wrapping_add(e).wrapping_add(k).wrapping_add(*word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+
+        for (value, added) in state.iter_mut().zip([a, b, c, d, e]) {
+            *value = value.wrapping_add(added);
+        }
+    }
+
+    let mut hash = [0; SHA1_LENGTH];
+    for (bytes, value) in hash.chunks_exact_mut(4).zip(state) {
+        bytes.copy_from_slice(&value.to_be_bytes());
+    }
+
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::quirks::{JumpingQuirk, ShiftingQuirk};
+
+    #[test]
+    fn hash_games() {
+        assert_eq!(sha1(b"abc")[..4], [0xA9, 0x99, 0x3E, 0x36], "SHA-1 calculated incorrectly.");
+        assert_eq!(sha1(&[0x61; 64])[..4], [0x00, 0x98, 0xBA, 0x82], "SHA-1 of a whole block calculated incorrectly.");
+        assert_eq!(hash(b"abc"), "a9993e364706816aba3e25717850c26c9cd0d89d", "Hash written incorrectly.");
+
+        let database = "# A comment\n5f518084744bf3cb8733f6e5454dfd1634320563 originalChip8 - - - TETRIS\n";
+        let entry = lookup_in(database, include_bytes!("../../../games/TETRIS.chip8")).unwrap();
+        assert_eq!(entry.title, "TETRIS", "Wrong game found.");
+        assert_eq!(entry.platform, Some(Platform::CosmacVip), "Platform not read.");
+        assert_eq!(lookup_in(database, &[0x12, 0x00]), None, "Unknown game found.");
+    }
+
+    #[test]
+    fn lookup_bundled_games() {
+        let entry = lookup(include_bytes!("../../../games/PONG.chip8")).unwrap();
+        assert_eq!(entry, DatabaseEntry { title: "PONG", platform: Some(Platform::CosmacVip), tick_rate: None, load_address: None, quirks: None }, "Bundled entry read incorrectly.");
+
+        let entry = lookup(include_bytes!("../../../games/INVADERS.chip8")).unwrap();
+        assert_eq!(entry.title, "INVADERS", "Wrong bundled game found.");
+        let quirk_config = entry.quirk_config(&QuirkConfig::new());
+        assert_eq!(quirk_config.shifting, ShiftingQuirk::Vx, "Bundled quirks not applied.");
+        assert_eq!(quirk_config.reset_vf, Platform::CosmacVip.quirk_config().reset_vf, "Platform quirks not kept.");
+
+        for line in DATABASE.lines().filter(|line| !line.is_empty() && !line.starts_with(COMMENT_PREFIX)) {
+            assert!(parse_entry(line).is_ok(), "Bundled entry invalid: {line}");
+        }
+    }
+
+    #[test]
+    fn parse_entries() {
+        let entry = parse_entry("0000000000000000000000000000000000000000 superchip 30 - - Some Game").unwrap();
+        assert_eq!(entry, DatabaseEntry { title: "Some Game", platform: Some(Platform::Schip), tick_rate: Some(30), load_address: None, quirks: None }, "Entry read incorrectly.");
+        assert_eq!(entry.quirk_config(&QuirkConfig::new()).shifting, ShiftingQuirk::Vx, "Platform quirks not applied.");
+
+        let entry = parse_entry("0000000000000000000000000000000000000000 megachip8 - - - Other Game").unwrap();
+        assert_eq!(entry.quirk_config(&QuirkConfig::new()), QuirkConfig::new(), "Unknown platform not ignored.");
+        assert_eq!(parse_entry("0000000000000000000000000000000000000000 originalChip8 - 0x600 - ETI Game").unwrap().load_address, Some(0x600), "Load address not read.");
+        assert!(parse_entry("0000000000000000000000000000000000000000 chip48 fast - - Game").is_err(), "Invalid tick rate read.");
+        assert!(parse_entry("0000000000000000000000000000000000000000 chip48 - 0xZZZ - Game").is_err(), "Invalid load address read.");
+        assert!(parse_entry("0000000000000000000000000000000000000000 chip48 - - jumping=sideways Game").is_err(), "Invalid quirks read.");
+        assert!(parse_entry("0000000000000000000000000000000000000000 chip48").is_err(), "Incomplete entry read.");
+
+        let entry = parse_entry("0000000000000000000000000000000000000000 megachip8 - - shifting=vx,jumping=vx Game").unwrap();
+        let quirk_config = entry.quirk_config(&QuirkConfig::new());
+        assert_eq!((quirk_config.shifting, quirk_config.jumping), (ShiftingQuirk::Vx, JumpingQuirk::Vx), "Entry quirks not applied.");
+    }
+}
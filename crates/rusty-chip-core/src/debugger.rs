@@ -0,0 +1,207 @@
+//! A module to contain the breakpoints of the debugger, which pause the [emulator](crate::emulator::Emulator) before the instruction at an address runs.
+//! Breakpoints can also be conditions on the registers and memory (e.g. `V3 == 0x1F` or `I > 0xE00`), which are checked after every instruction and pause the game once they become met.
+//! Once paused, the game can be [stepped](crate::emulator::Emulator::step_instruction) a single instruction at a time or [resumed](crate::emulator::Emulator::resume).
+
+use std::collections::BTreeSet;
+
+use crate::interpreter::Interpreter;
+use crate::speedrun::Condition;
+
+/// Stores a conditional breakpoint, along with whether its condition was met after the previous instruction.
+#[derive(Debug, Clone)]
+struct ConditionalBreakpoint {
+    condition: Condition,
+    was_met: bool
+}
+
+/// Stores the breakpoints, along with the one the game is stopped at.
+#[derive(Debug, Clone, Default)]
+pub struct Debugger {
+    breakpoints: BTreeSet<u16>,
+    conditions: Vec<ConditionalBreakpoint>,
+    stopped_at: Option<u16>,
+    hit: Option<u16>,
+    met_condition: Option<Condition>
+}
+
+impl Debugger {
+    /// Returns a debugger without any breakpoints.
+    #[must_use]
+    pub fn new() -> Debugger {
+        Debugger::default()
+    }
+
+    /// Adds a breakpoint, returning whether it is new.
+    ///
+    /// # Parameters
+    ///
+    /// * `address` - The address of the instruction to break before.
+    pub fn add_breakpoint(&mut self, address: u16) -> bool {
+        self.breakpoints.insert(address)
+    }
+
+    /// Removes a breakpoint, returning whether there was one.
+    ///
+    /// # Parameters
+    ///
+    /// * `address` - The address of the breakpoint.
+    pub fn remove_breakpoint(&mut self, address: u16) -> bool {
+        self.breakpoints.remove(&address)
+    }
+
+    /// Adds the breakpoint if there is none at the address, or removes it otherwise. Returns whether a breakpoint was added.
+    ///
+    /// # Parameters
+    ///
+    /// * `address` - The address of the breakpoint.
+    pub fn toggle_breakpoint(&mut self, address: u16) -> bool {
+        self.add_breakpoint(address) || !self.remove_breakpoint(address)
+    }
+
+    /// Returns the addresses of the breakpoints in order.
+    pub fn breakpoints(&self) -> impl Iterator<Item = u16> + '_ {
+        self.breakpoints.iter().copied()
+    }
+
+    /// Removes every breakpoint.
+    pub fn clear_breakpoints(&mut self) {
+        self.breakpoints.clear();
+    }
+
+    /// Adds a conditional breakpoint, which pauses the game after the instruction which meets its condition.
+    /// The game does not pause again until the condition has stopped being met, so that it can be resumed.
+    ///
+    /// # Parameters
+    ///
+    /// * `condition` - The condition to break on.
+    pub fn add_condition(&mut self, condition: Condition) {
+        self.conditions.push(ConditionalBreakpoint { condition, was_met: false });
+    }
+
+    /// Returns the conditions of the conditional breakpoints in the order they were added.
+    pub fn conditions(&self) -> impl Iterator<Item = &Condition> + '_ {
+        self.conditions.iter().map(|breakpoint| &breakpoint.condition)
+    }
+
+    /// Removes every conditional breakpoint.
+    pub fn clear_conditions(&mut self) {
+        self.conditions.clear();
+    }
+
+    /// Returns the address of the breakpoint the game most recently stopped at, if it has not been taken yet.
+    /// For a conditional breakpoint this is the address of the next instruction.
+    /// Frontends can use this to report the break once.
+    pub fn take_hit(&mut self) -> Option<u16> {
+        self.hit.take()
+    }
+
+    /// Returns the condition the game most recently stopped for, if it has not been taken yet.
+    pub fn take_met_condition(&mut self) -> Option<Condition> {
+        self.met_condition.take()
+    }
+
+    /// Treats the game as stopped at the address, so that resuming runs the instruction there even if it has a breakpoint (e.g. after stepping onto it).
+    ///
+    /// # Parameters
+    ///
+    /// * `address` - The address of the next instruction.
+    pub(crate) fn stop_at(&mut self, address: u16) {
+        self.stopped_at = Some(address);
+    }
+
+    /// Returns whether the game should stop before running the instruction at the address.
+    /// A game stopped at a breakpoint does not stop there again until the program counter has moved off it, so that it can be resumed.
+    ///
+    /// # Parameters
+    ///
+    /// * `address` - The address of the instruction about to run.
+    pub(crate) fn should_break(&mut self, address: u16) -> bool {
+        if self.stopped_at == Some(address) {
+            return false;
+        }
+
+        self.stopped_at = None;
+        if self.breakpoints.contains(&address) {
+            self.stopped_at = Some(address);
+            self.hit = Some(address);
+            return true;
+        }
+
+        false
+    }
+
+    /// Checks the conditional breakpoints against the interpreter after an instruction, returning whether the game should stop.
+    /// Every condition is checked so that each knows whether it was met, though only the first to become met is reported.
+    ///
+    /// # Parameters
+    ///
+    /// * `interpreter` - The interpreter which ran the instruction.
+    pub(crate) fn should_break_after(&mut self, interpreter: &Interpreter) -> bool {
+        let mut met_condition = None;
+        for breakpoint in &mut self.conditions {
+            let is_met = breakpoint.condition.is_met(interpreter);
+            if is_met && !breakpoint.was_met && met_condition.is_none() {
+                met_condition = Some(breakpoint.condition.clone());
+            }
+            breakpoint.was_met = is_met;
+        }
+
+        let Some(condition) = met_condition else {
+            return false;
+        };
+
+        let address = interpreter.program_counter();
+        self.stopped_at = Some(address);
+        self.hit = Some(address);
+        self.met_condition = Some(condition);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn break_once_per_visit() {
+        let mut debugger = Debugger::new();
+        assert!(debugger.toggle_breakpoint(0x204), "Breakpoint not added.");
+        assert!(debugger.add_breakpoint(0x208), "Breakpoint not added.");
+        assert!(!debugger.toggle_breakpoint(0x208), "Breakpoint not removed.");
+        assert_eq!(debugger.breakpoints().collect::<Vec<_>>(), [0x204], "Wrong breakpoints kept.");
+
+        assert!(!debugger.should_break(0x202), "Stopped without a breakpoint.");
+        assert!(debugger.should_break(0x204), "Breakpoint not hit.");
+        assert_eq!(debugger.take_hit(), Some(0x204), "Hit not reported.");
+        assert_eq!(debugger.take_hit(), None, "Hit reported twice.");
+        assert!(!debugger.should_break(0x204), "Stopped again without moving on.");
+        assert!(!debugger.should_break(0x206), "Stopped without a breakpoint.");
+        assert!(debugger.should_break(0x204), "Breakpoint not hit on the next visit.");
+    }
+
+    #[test]
+    fn break_when_condition_met() {
+        let mut debugger = Debugger::new();
+        debugger.add_condition("V3 == 0x1F".parse().unwrap());
+        debugger.add_condition("I > 0xE00".parse().unwrap());
+        assert_eq!(debugger.conditions().map(ToString::to_string).collect::<Vec<_>>(), ["V3 == 0x1F", "I > 0xE00"], "Wrong conditions kept.");
+
+        let mut interpreter = Interpreter::new();
+        interpreter.load_game(&[0x63, 0x1F, 0x63, 0x1F, 0x63, 0x00, 0x63, 0x1F]);
+        interpreter.handle_cycle();
+        assert!(debugger.should_break_after(&interpreter), "Met condition not hit.");
+        assert_eq!(debugger.take_hit(), Some(0x202), "Hit not reported at the next instruction.");
+        assert_eq!(debugger.take_met_condition().map(|condition| condition.to_string()).as_deref(), Some("V3 == 0x1F"), "Met condition not reported.");
+        assert!(!debugger.should_break(0x202), "Stopped again at the address the condition stopped at.");
+
+        interpreter.handle_cycle();
+        assert!(!debugger.should_break_after(&interpreter), "Stopped again while the condition stayed met.");
+        interpreter.handle_cycle();
+        assert!(!debugger.should_break_after(&interpreter), "Unmet condition hit.");
+        interpreter.handle_cycle();
+        assert!(debugger.should_break_after(&interpreter), "Condition not hit once met again.");
+
+        debugger.clear_conditions();
+        assert_eq!(debugger.conditions().count(), 0, "Conditions not cleared.");
+    }
+}
@@ -0,0 +1,101 @@
+//! A module to contain the designs the interpreter can decode its instructions with, so that their speed can be compared by the [benchmarks](crate::bench) rather than guessed at.
+//! Every design decodes the same opcodes and runs them the same way, differing only in how the opcode is found for the bytes at the program counter.
+
+#[cfg(feature = "cli")]
+use clap::ValueEnum;
+
+#[cfg(not(feature = "std"))]
+use crate::alloc_prelude::*;
+use crate::opcodes::{Opcode, OpcodeBytes};
+
+/// Denotes how the interpreter decodes each instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+pub enum Dispatch {
+    /// Decode every instruction with a match on its first nibble.
+    #[default]
+    Match,
+    /// Decode every instruction with a table of decoders indexed by its first nibble.
+    FunctionTable,
+    /// Decode each instruction once, reusing the opcode for as long as its bytes are unchanged.
+    Predecoded
+}
+
+impl Dispatch {
+    /// Every design, in the order they are reported.
+    pub const ALL: [Dispatch; 3] = [Dispatch::Match, Dispatch::FunctionTable, Dispatch::Predecoded];
+
+    /// Returns the name of the design shown in reports, e.g. `Function table`.
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        match self {
+            Dispatch::Match => "Match",
+            Dispatch::FunctionTable => "Function table",
+            Dispatch::Predecoded => "Predecoded"
+        }
+    }
+}
+
+/// Stores an instruction decoded by the [`Predecoded`](Dispatch::Predecoded) design, along with the bytes it was decoded from.
+#[derive(Debug, Clone)]
+struct CachedOpcode {
+    bytes: [u8; 4],
+    opcode: Option<Opcode>
+}
+
+/// Stores the instructions decoded so far by the [`Predecoded`](Dispatch::Predecoded) design, by address.
+/// The bytes are checked against those the opcode was decoded from on every use, so games which modify themselves are decoded again.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DecodeCache {
+    entries: Vec<Option<CachedOpcode>>
+}
+
+impl DecodeCache {
+    /// Returns the opcode for the bytes of the instruction at the address, decoding them only if they changed since they were last decoded.
+    ///
+    /// # Parameters
+    ///
+    /// * `address` - The address of the instruction.
+    /// * `opcode_bytes` - The bytes of the instruction, 2 or 4 of them.
+    pub(crate) fn decode(&mut self, address: u16, opcode_bytes: &[u8]) -> Option<Opcode> {
+        let index = address as usize;
+        if let Some(Some(cached)) = self.entries.get(index) {
+            if cached.bytes[..opcode_bytes.len()] == *opcode_bytes {
+                return cached.opcode.clone();
+            }
+        }
+
+        let opcode = OpcodeBytes::build(opcode_bytes).try_get_opcode();
+        let mut bytes = [0; 4];
+        bytes[..opcode_bytes.len()].copy_from_slice(opcode_bytes);
+        if self.entries.len() <= index {
+            self.entries.resize(index + 1, None);
+        }
+        self.entries[index] = Some(CachedOpcode { bytes, opcode: opcode.clone() });
+
+        opcode
+    }
+
+    /// Discards every decoded instruction.
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_again_once_modified() {
+        let mut cache = DecodeCache::default();
+        assert_eq!(cache.decode(0x200, &[0x61, 0x05]), Some(Opcode::LoadValue(0x1, 0x05)), "Instruction not decoded.");
+        assert_eq!(cache.decode(0x200, &[0x61, 0x05]), Some(Opcode::LoadValue(0x1, 0x05)), "Cached instruction not reused.");
+        assert_eq!(cache.decode(0x200, &[0x62, 0x05]), Some(Opcode::LoadValue(0x2, 0x05)), "Modified instruction not decoded again.");
+        assert_eq!(cache.decode(0x202, &[0xF0, 0x00, 0x12, 0x34]), Some(Opcode::LongLoadRegisterI(0x1234)), "Long instruction not decoded.");
+        assert_eq!(cache.decode(0x202, &[0xF0, 0x00, 0x56, 0x78]), Some(Opcode::LongLoadRegisterI(0x5678)), "Modified long instruction not decoded again.");
+
+        cache.clear();
+        assert!(cache.entries.is_empty(), "Cache not cleared.");
+    }
+}
@@ -0,0 +1,1844 @@
+//! A module to contain the fixed-timestep driver of the emulator.  
+//! Frontends which own their own loop can hand the elapsed time to [`Emulator::advance`](Emulator::advance) rather than reimplementing the frame pacing.  
+//! Those without a clock of their own can call [`Emulator::tick`](Emulator::tick) whenever convenient instead, waiting [`Emulator::next_tick_delay`](Emulator::next_tick_delay) between calls if they have nothing else to do.  
+//! Games are loaded through [`Emulator::queue_load`](Emulator::queue_load) so that they only replace the running game between frames.  
+//! Running games can be patched through [`Emulator::write_ram`](Emulator::write_ram) and [`Emulator::freeze`](Emulator::freeze), the building blocks of cheats and trainers.  
+//! The [debugger](Emulator::debugger_mut) pauses the game at breakpoints, after which it can be [stepped](Emulator::step_instruction) an instruction at a time.  
+//! A [speedrun timer](Emulator::set_speedrun_timer) times the game frame by frame, splitting on conditions met by its memory.  
+//! Files kept alongside games (e.g. autosaves and palettes) go through the [storage backend](Emulator::set_storage), the filesystem unless another is set.  
+//! A game's [key profile](Emulator::set_key_profile) remaps the physical keys before they reach it.  
+//! Quirks changed through [`Emulator::change_quirks`](Emulator::change_quirks) can be compared against the previous settings from the same point in the game, either by switching between them or by [running both in lockstep](Emulator::toggle_lockstep).  
+//! In [pure mode](Emulator::set_pure) all of the above which change how a game behaves or looks are turned off, for compatibility validation.  
+//! The emulator is `Send` and `Sync`, so it can run on a thread of its own while the frontend draws and reads input on another.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+use crate::backend::InputSource;
+use crate::debugger::Debugger;
+use crate::frame_log::{self, Subsystem};
+use crate::input_display::InputTimeline;
+use crate::interpreter::{Counters, HaltReason, Interpreter, Snapshot, MAX_KEY};
+use crate::macros::{InputMacro, MacroPlayback, MacroRecorder};
+use crate::palette::Palette;
+use crate::poke::Poke;
+use crate::key_profile::KeyProfile;
+use crate::layout::OverlayLayout;
+use crate::quirks::QuirkConfig;
+use crate::renderer::Renderer;
+use crate::speedrun::SpeedrunTimer;
+use crate::storage::{FileStorage, StorageBackend};
+use crate::trace::TraceLogger;
+
+/// The number of instructions run in a single frame, unless configured otherwise.
+pub const DEFAULT_CYCLES_PER_FRAME: u32 = 10;
+
+/// The duration of a single emulated frame, as the CHIP-8 runs its timers and display at 60Hz.  
+/// A 60th of a second is not a whole number of nanoseconds, so this is rounded up to be long enough for any frame, while [`advance`](Emulator::advance) keeps to exactly 60 frames a second.
+pub const FRAME_DURATION: Duration = Duration::from_nanos(1_000_000_000_u64.div_ceil(60));
+
+/// The length of a group of frames which lasts a whole number of nanoseconds, as a single 60Hz frame does not.
+const FRAME_GROUP_DURATION: Duration = Duration::from_millis(50);
+
+/// The number of frames in a [group](FRAME_GROUP_DURATION).
+const FRAMES_PER_GROUP: u32 = 3;
+
+/// The most frames which will be run by a single call to [`advance`](Emulator::advance).  
+/// Any whole frames beyond this are dropped so that a long stall (e.g. a dragged window) does not cause a burst of catch-up frames, though the timers still tick for them so that beeps keep their length.
+pub const MAX_FRAMES_PER_ADVANCE: u32 = 10;
+
+/// The slowest [speed](Emulator::set_speed) the game can be run at, relative to its usual speed.
+pub const MIN_SPEED: f64 = 0.25;
+
+/// The fastest [speed](Emulator::set_speed) the game can be run at, relative to its usual speed.
+pub const MAX_SPEED: f64 = 4.0;
+
+/// Describes the result of advancing the emulator by some amount of time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameAdvance {
+    /// The number of whole frames which were run.
+    pub frames: u32,
+    /// The time carried over to the next call as it did not make up a whole frame.
+    pub leftover: Duration
+}
+
+impl FrameAdvance {
+    /// Returns how far into the next frame the leftover time reaches, from `0.0` up to (but excluding) `1.0`.  
+    /// Useful to frontends which interpolate their own rendering between emulated frames.
+    #[must_use]
+    pub fn alpha(&self) -> f32 {
+        self.leftover.as_secs_f32() / FRAME_DURATION.as_secs_f32()
+    }
+}
+
+/// Denotes what the emulator is currently doing, which decides whether frames are run and where input should go.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmulatorMode {
+    /// No game has been loaded yet, so there is nothing to run.
+    Idle,
+    /// Frames are run as time passes.
+    Running,
+    /// Frames are not run, so neither instructions nor timers advance.
+    Paused,
+    /// Paused, except that a single frame is run on the next advance before pausing again.
+    Stepping,
+    /// Frames are not run while an earlier state is being restored.
+    Rewinding,
+    /// Frames are not run and input belongs to a menu. Closing the menu returns to the previous mode.
+    Menu,
+    /// The game stopped for the provided reason, and nothing is run until a game is loaded.
+    Halted(HaltReason)
+}
+
+impl EmulatorMode {
+    /// Returns whether advancing the emulator in this mode runs any frames.
+    #[must_use]
+    pub fn runs_frames(self) -> bool {
+        matches!(self, EmulatorMode::Running | EmulatorMode::Stepping)
+    }
+}
+
+/// Denotes which quirk settings of a [`QuirkComparison`](QuirkComparison) are being run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuirkSide {
+    Before,
+    After
+}
+
+/// Stores the point in the game at which the quirks were first changed, along with the settings from before and after the change.  
+/// Switching sides returns the game to that point, so both settings can be seen playing out from the same state.
+#[derive(Debug, Clone)]
+pub struct QuirkComparison {
+    snapshot: Snapshot,
+    frame_count: u64,
+    before: QuirkConfig,
+    after: QuirkConfig,
+    side: QuirkSide
+}
+
+impl QuirkComparison {
+    /// Returns which settings are being run.
+    #[must_use]
+    pub fn side(&self) -> QuirkSide {
+        self.side
+    }
+
+    /// Returns the quirk settings from before the change.
+    #[must_use]
+    pub fn before(&self) -> &QuirkConfig {
+        &self.before
+    }
+
+    /// Returns the quirk settings from after the change.
+    #[must_use]
+    pub fn after(&self) -> &QuirkConfig {
+        &self.after
+    }
+
+    /// Returns a short description of the settings being run, suitable for the window title.
+    #[must_use]
+    pub fn describe(&self) -> String {
+        match self.side {
+            QuirkSide::Before => String::from("Comparing quirks: before"),
+            QuirkSide::After => String::from("Comparing quirks: after")
+        }
+    }
+}
+
+/// Stores the settings a game is run with which are applied along with its [queued load](Emulator::queue_load_with), so that the running game keeps its own until it is replaced.  
+/// Any setting left as `None` is kept from the previous game.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LoadSettings {
+    /// The quirks to run the game with.
+    pub quirk_config: Option<QuirkConfig>,
+    /// The number of instruction cycles to run per frame.
+    pub cycles_per_frame: Option<u32>,
+    /// The colours to draw the game with.
+    pub palette: Option<Palette>
+}
+
+/// Drives an [`Interpreter`](Interpreter) at a fixed 60Hz timestep.
+pub struct Emulator {
+    interpreter: Interpreter,
+    renderer: Renderer,
+    mode: EmulatorMode,
+    mode_before_menu: EmulatorMode,
+    cycles_per_frame: u32,
+    leftover: Duration,
+    /// The position of the next frame in its [group](FRAME_GROUP_DURATION), which decides its exact length.
+    frame_phase: u32,
+    last_tick: Option<Instant>,
+    frame_count: u64,
+    pending_load: Option<(Vec<u8>, LoadSettings)>,
+    game_data: Option<Vec<u8>>,
+    frozen_bytes: BTreeMap<u16, u8>,
+    quirk_comparison: Option<QuirkComparison>,
+    lockstep: Option<Interpreter>,
+    input_timeline: Option<InputTimeline>,
+    debugger: Debugger,
+    speedrun_timer: Option<SpeedrunTimer>,
+    storage: Box<dyn StorageBackend>,
+    trace_logger: Option<TraceLogger<Box<dyn Write + Send + Sync>>>,
+    key_profile: Option<KeyProfile>,
+    pending_restore: Option<Snapshot>,
+    macro_recorder: Option<MacroRecorder>,
+    macro_playback: Option<MacroPlayback>,
+    turbo: Option<u32>,
+    speed: f64,
+    is_pure: bool
+}
+
+impl Emulator {
+    /// Creates a new driver around the provided interpreter.
+    ///
+    /// # Parameters
+    ///
+    /// * `interpreter` - The interpreter to drive.
+    /// * `cycles_per_frame` - The number of instruction cycles to run per frame.
+    #[must_use]
+    pub fn new(interpreter: Interpreter, cycles_per_frame: u32) -> Emulator {
+        Emulator {
+            interpreter,
+            renderer: Renderer::new(),
+            mode: EmulatorMode::Idle,
+            mode_before_menu: EmulatorMode::Idle,
+            cycles_per_frame,
+            leftover: Duration::ZERO,
+            frame_phase: 0,
+            last_tick: None,
+            frame_count: 0,
+            pending_load: None,
+            game_data: None,
+            frozen_bytes: BTreeMap::new(),
+            quirk_comparison: None,
+            lockstep: None,
+            input_timeline: None,
+            debugger: Debugger::new(),
+            speedrun_timer: None,
+            storage: Box::new(FileStorage),
+            trace_logger: None,
+            key_profile: None,
+            pending_restore: None,
+            macro_recorder: None,
+            macro_playback: None,
+            turbo: None,
+            speed: 1.0,
+            is_pure: false
+        }
+    }
+
+    /// Returns the driven interpreter.
+    #[must_use]
+    pub fn interpreter(&self) -> &Interpreter {
+        &self.interpreter
+    }
+
+    /// Returns the driven interpreter mutably, e.g. to load games or forward input.
+    pub fn interpreter_mut(&mut self) -> &mut Interpreter {
+        &mut self.interpreter
+    }
+
+    /// Returns the renderer drawing the interpreter's display, which holds its palette and overlay layout.
+    #[must_use]
+    pub fn renderer(&self) -> &Renderer {
+        &self.renderer
+    }
+
+    /// Returns the renderer drawing the interpreter's display mutably, e.g. to change the palette.
+    pub fn renderer_mut(&mut self) -> &mut Renderer {
+        &mut self.renderer
+    }
+
+    /// Draws the interpreter's display without advancing the emulation.  
+    /// Used to keep the display up to date (e.g. palette changes) while frames are not being run.
+    pub fn present(&mut self) {
+        self.renderer.present(&mut self.interpreter);
+    }
+
+    /// Returns what the emulator is currently doing.
+    #[must_use]
+    pub fn mode(&self) -> EmulatorMode {
+        self.mode
+    }
+
+    /// Pauses a running (or stepping) emulator.
+    pub fn pause(&mut self) {
+        if matches!(self.mode, EmulatorMode::Running | EmulatorMode::Stepping) {
+            self.set_mode(EmulatorMode::Paused);
+        }
+    }
+
+    /// Resumes running a paused, stepping, or rewinding emulator.
+    pub fn resume(&mut self) {
+        if matches!(self.mode, EmulatorMode::Paused | EmulatorMode::Stepping | EmulatorMode::Rewinding) {
+            self.set_mode(EmulatorMode::Running);
+        }
+    }
+
+    /// Runs a single instruction cycle of a paused game (e.g. one stopped at a breakpoint), ignoring any breakpoint on it.  
+    /// The frame is not finished, so the timers only tick and a draw waiting for the display refresh only completes once frames are run again.
+    pub fn step_instruction(&mut self) {
+        if self.mode != EmulatorMode::Paused {
+            return;
+        }
+
+        self.run_cycle();
+        self.debugger.stop_at(self.interpreter.program_counter());
+        self.debugger.should_break_after(&self.interpreter);
+        self.sync_halt();
+    }
+
+    /// Undoes the most recent instruction of a paused game through the interpreter's [event log](Interpreter::event_log), returning whether there was one to undo.  
+    /// Nothing is undone if the game is not paused or its changes are not being recorded.
+    pub fn step_back_instruction(&mut self) -> bool {
+        if self.mode != EmulatorMode::Paused || !self.interpreter.step_back() {
+            return false;
+        }
+
+        self.debugger.stop_at(self.interpreter.program_counter());
+        true
+    }
+
+    /// Edits the registers or memory of a paused game, returning a description of what was written (e.g. `V3 = 0x1F`).  
+    /// Resuming runs the game from its edited state, starting at the new program counter if it was poked.
+    ///
+    /// # Parameters
+    ///
+    /// * `poke` - The edit to make.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` without editing anything if the emulator is in [pure mode](Emulator::set_pure), the game is not paused, or the poke cannot be [applied](Poke::apply).
+    pub fn poke(&mut self, poke: &Poke) -> Result<String, String> {
+        if self.is_pure {
+            return Err(String::from("The game cannot be edited in pure mode."));
+        }
+        if self.mode != EmulatorMode::Paused {
+            return Err(String::from("Pause the game before editing it."));
+        }
+
+        let description = poke.apply(&mut self.interpreter)?;
+        self.debugger.stop_at(self.interpreter.program_counter());
+
+        Ok(description)
+    }
+
+    /// Returns how much of each kind of work the interpreter has done since the game was loaded, e.g. to check in a test that a game never waits for a key.
+    #[must_use]
+    pub fn counters(&self) -> Counters {
+        self.interpreter.counters()
+    }
+
+    /// Returns the debugger, with the breakpoints the game pauses at.
+    #[must_use]
+    pub fn debugger(&self) -> &Debugger {
+        &self.debugger
+    }
+
+    /// Returns the debugger, to change the breakpoints the game pauses at.
+    pub fn debugger_mut(&mut self) -> &mut Debugger {
+        &mut self.debugger
+    }
+
+    /// Sets the speedrun timer, which checks its conditions after every frame and restarts whenever a game is loaded or reset. `None` removes it.
+    ///
+    /// # Parameters
+    ///
+    /// * `speedrun_timer` - The timer to run.
+    pub fn set_speedrun_timer(&mut self, speedrun_timer: Option<SpeedrunTimer>) {
+        self.speedrun_timer = speedrun_timer;
+    }
+
+    /// Returns the speedrun timer, if one is set.
+    #[must_use]
+    pub fn speedrun_timer(&self) -> Option<&SpeedrunTimer> {
+        self.speedrun_timer.as_ref()
+    }
+
+    /// Returns the speedrun timer mutably, e.g. to take the finished run.
+    pub fn speedrun_timer_mut(&mut self) -> Option<&mut SpeedrunTimer> {
+        self.speedrun_timer.as_mut()
+    }
+
+    /// Sets where the files kept alongside games (autosaves, RPL flags, palettes, key profiles, notes, splits, and split times) are stored.
+    ///
+    /// # Parameters
+    ///
+    /// * `storage` - The backend to store the files in.
+    pub fn set_storage(&mut self, storage: Box<dyn StorageBackend>) {
+        self.storage = storage;
+    }
+
+    /// Returns where the files kept alongside games are stored.
+    #[must_use]
+    pub fn storage(&self) -> &dyn StorageBackend {
+        self.storage.as_ref()
+    }
+
+    /// Returns where the files kept alongside games are stored mutably, to save to it.
+    pub fn storage_mut(&mut self) -> &mut dyn StorageBackend {
+        self.storage.as_mut()
+    }
+
+    /// Sets the trace log, which every instruction the game runs is written to. `None` stops tracing.
+    ///
+    /// # Parameters
+    ///
+    /// * `trace_logger` - The log to write to.
+    pub fn set_trace_logger(&mut self, trace_logger: Option<TraceLogger<Box<dyn Write + Send + Sync>>>) {
+        self.trace_logger = trace_logger;
+    }
+
+    /// Sets the key profile, which maps physical keys to CHIP-8 keys in place of the usual mapping. `None` restores the usual mapping.
+    ///
+    /// # Parameters
+    ///
+    /// * `key_profile` - The profile to apply.
+    pub fn set_key_profile(&mut self, key_profile: Option<KeyProfile>) {
+        self.key_profile = key_profile;
+    }
+
+    /// Returns the key profile, if one is applied.
+    #[must_use]
+    pub fn key_profile(&self) -> Option<&KeyProfile> {
+        self.key_profile.as_ref()
+    }
+
+    /// Returns the CHIP-8 key the physical key presses, from the [key profile](Emulator::set_key_profile) if it maps the key and the usual mapping otherwise.
+    ///
+    /// # Parameters
+    ///
+    /// * `input` - The device the key is on.
+    /// * `host_key` - The physical key.
+    fn key_mapping<I: InputSource>(&self, input: &I, host_key: I::Key) -> Option<u8> {
+        self.key_profile.as_ref()
+            .and_then(|key_profile| key_profile.key_for(&input.key_name(host_key)))
+            .or_else(|| input.keypad_key(host_key))
+    }
+
+    /// Runs a single instruction cycle, writing the instruction to the [trace log](Emulator::set_trace_logger) if there is one.  
+    /// Tracing stops if the log cannot be written, as the game is better left running than stopped over a full disk.
+    fn run_cycle(&mut self) {
+        self.update_log_stamp();
+        let Some(trace_logger) = self.trace_logger.as_mut() else {
+            self.interpreter.handle_cycle();
+            return;
+        };
+
+        if let Err(e) = trace_logger.trace_cycle(&mut self.interpreter) {
+            frame_log::warn(Subsystem::Emulator, format_args!("Error writing trace, stopping tracing: {e}"));
+            self.trace_logger = None;
+        }
+    }
+
+    /// Has a paused emulator run a single frame on the next advance, after which it pauses again.
+    pub fn step(&mut self) {
+        if self.mode == EmulatorMode::Paused {
+            self.set_mode(EmulatorMode::Stepping);
+        }
+    }
+
+    /// Stops running frames while an earlier state is restored. Resuming returns to running.
+    pub fn rewind(&mut self) {
+        if matches!(self.mode, EmulatorMode::Running | EmulatorMode::Paused) {
+            self.set_mode(EmulatorMode::Rewinding);
+        }
+    }
+
+    /// Stops the game at the user's request. A game must be loaded (or reset) to run again.
+    pub fn stop(&mut self) {
+        self.interpreter.halt(HaltReason::UserStop);
+        self.sync_halt();
+    }
+
+    /// Enters the halted mode if the interpreter has halted, so that no more frames are run.  
+    /// A menu open at the time returns to the halted mode when closed.
+    fn sync_halt(&mut self) {
+        let Some(reason) = self.interpreter.halt_reason() else {
+            return;
+        };
+
+        if !matches!(self.mode, EmulatorMode::Halted(_)) {
+            frame_log::event(Subsystem::Emulator, format_args!("Game halted: {reason}"));
+        }
+        if self.mode == EmulatorMode::Menu {
+            self.mode_before_menu = EmulatorMode::Halted(reason);
+        } else {
+            self.set_mode(EmulatorMode::Halted(reason));
+        }
+    }
+
+    /// Hands input to a menu, stopping frames from running until the menu is closed.
+    pub fn open_menu(&mut self) {
+        if self.mode != EmulatorMode::Menu {
+            self.mode_before_menu = self.mode;
+            self.set_mode(EmulatorMode::Menu);
+        }
+    }
+
+    /// Closes the menu, returning to the mode the emulator was in when it was opened.
+    pub fn close_menu(&mut self) {
+        if self.mode == EmulatorMode::Menu {
+            self.set_mode(self.mode_before_menu);
+        }
+    }
+
+    /// Changes the mode, silencing the sound while frames are not being run so that a beep does not play forever.
+    ///
+    /// # Parameters
+    ///
+    /// * `mode` - The new mode.
+    fn set_mode(&mut self, mode: EmulatorMode) {
+        self.mode = mode;
+        self.interpreter.set_audio_paused(!mode.runs_frames());
+    }
+
+    /// Returns the number of instruction cycles run per frame.
+    #[must_use]
+    pub fn cycles_per_frame(&self) -> u32 {
+        self.cycles_per_frame
+    }
+
+    /// Changes the number of instruction cycles run per frame, taking effect from the next frame.
+    ///
+    /// # Parameters
+    ///
+    /// * `cycles_per_frame` - The number of instruction cycles to run per frame.
+    pub fn set_cycles_per_frame(&mut self, cycles_per_frame: u32) {
+        self.cycles_per_frame = cycles_per_frame;
+    }
+
+    /// Returns the factor the cycles per frame are multiplied by while fast-forwarding, or `None` if the game is running at its usual speed.
+    #[must_use]
+    pub fn turbo(&self) -> Option<u32> {
+        self.turbo
+    }
+
+    /// Fast-forwards the game, multiplying the cycles run per frame and muting the sound (whose beeps would be cut short anyway), or returns it to its usual speed.  
+    /// Frames are still run as time is [advanced](Emulator::advance), so front ends should advance by a whole frame at a time without waiting in between to run them as fast as the host allows.
+    ///
+    /// # Parameters
+    ///
+    /// * `turbo` - The factor to multiply the cycles per frame by, or `None` to return to the usual speed.
+    pub fn set_turbo(&mut self, turbo: Option<u32>) {
+        self.turbo = turbo;
+        self.interpreter.set_muted(turbo.is_some());
+        if turbo.is_none() {
+            self.interpreter.set_audio_paused(!self.mode.runs_frames());
+        }
+    }
+
+    /// Returns how fast the game runs relative to its usual speed, e.g. `0.5` for half speed.
+    #[must_use]
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    /// Changes how fast the game runs by scaling the time it is [advanced](Emulator::advance) by, so that the cycles, timers, and frames all keep their usual pace relative to each other.  
+    /// Front ends should scale the time they wait between frames by the same amount. The speed is ignored while [fast-forwarding](Emulator::set_turbo), which runs a frame per advance.
+    ///
+    /// # Parameters
+    ///
+    /// * `speed` - The speed relative to the usual speed, clamped to between [`MIN_SPEED`](MIN_SPEED) and [`MAX_SPEED`](MAX_SPEED).
+    pub fn set_speed(&mut self, speed: f64) {
+        self.speed = speed.clamp(MIN_SPEED, MAX_SPEED);
+    }
+
+    /// Returns the number of frames run since the emulator was created.
+    #[must_use]
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    /// Queues a game to be loaded at the start of the next frame, replacing any game already queued.  
+    /// Loading between frames guarantees that the running game is never interrupted part way through a frame, e.g. while waiting for a display refresh or key release.
+    ///
+    /// # Parameters
+    ///
+    /// * `game_data` - The bytes which were read from the game file.
+    pub fn queue_load(&mut self, game_data: Vec<u8>) {
+        self.queue_load_with(game_data, LoadSettings::default());
+    }
+
+    /// Queues a game to be loaded at the start of the next frame along with the settings it runs with, replacing any game already queued.  
+    /// The settings are only applied when the game is loaded, so the running game keeps its own until the frame boundary.
+    ///
+    /// # Parameters
+    ///
+    /// * `game_data` - The bytes which were read from the game file.
+    /// * `settings` - The settings to apply when the game is loaded.
+    pub fn queue_load_with(&mut self, game_data: Vec<u8>, settings: LoadSettings) {
+        self.pending_load = Some((game_data, settings));
+    }
+
+    /// Queues the most recently loaded game to be loaded again at the start of the next frame, restarting it from scratch.  
+    /// Does nothing if no game has been loaded yet.
+    pub fn reset(&mut self) {
+        if self.pending_load.is_none() {
+            self.pending_load = self.game_data.clone().map(|game_data| (game_data, LoadSettings::default()));
+        }
+    }
+
+    /// Queues a snapshot to be restored at the start of the next frame, after any queued game is loaded (e.g. to resume a game from its autosave).  
+    /// The snapshot is dropped if no game has been loaded by then.
+    ///
+    /// # Parameters
+    ///
+    /// * `snapshot` - The state to restore.
+    pub fn queue_restore(&mut self, snapshot: Snapshot) {
+        self.pending_restore = Some(snapshot);
+    }
+
+    /// Returns the bytes of the most recently loaded game, or `None` if no game has been loaded yet.
+    #[must_use]
+    pub fn game_data(&self) -> Option<&[u8]> {
+        self.game_data.as_deref()
+    }
+
+    /// Returns whether a game is waiting to be loaded at the start of the next frame.
+    #[must_use]
+    pub fn has_pending_load(&self) -> bool {
+        self.pending_load.is_some()
+    }
+
+    /// Forwards a key press to the game through the [key profile](Emulator::set_key_profile), and to the game running in [lockstep](Emulator::toggle_lockstep) if there is one.  
+    /// The press is recorded for the next frame on the [input display](Emulator::toggle_input_display) if it is shown.
+    ///
+    /// # Parameters
+    ///
+    /// * `input` - The device the key is on.
+    /// * `host_key` - The physical key pressed.
+    pub fn handle_key_press<I: InputSource>(&mut self, input: &I, host_key: I::Key) {
+        let Some(key) = self.key_mapping(input, host_key) else {
+            return;
+        };
+
+        self.press_key(key);
+    }
+
+    /// Forwards a key release to the game through the [key profile](Emulator::set_key_profile), and to the game running in [lockstep](Emulator::toggle_lockstep) if there is one.  
+    /// The release is recorded for the next frame on the [input display](Emulator::toggle_input_display) if it is shown.
+    ///
+    /// # Parameters
+    ///
+    /// * `input` - The device the key is on.
+    /// * `host_key` - The physical key released.
+    pub fn handle_key_release<I: InputSource>(&mut self, input: &I, host_key: I::Key) {
+        let Some(key) = self.key_mapping(input, host_key) else {
+            return;
+        };
+
+        self.release_key(key);
+    }
+
+    /// Presses a CHIP-8 key directly, bypassing the key profile, for frontends and tests which map their own input onto the keypad rather than implementing an [input source](InputSource).  
+    /// Otherwise the same as [`handle_key_press`](Emulator::handle_key_press), so the press reaches the game running in lockstep, the input display, and any macro being recorded. Keys past `0xF` are ignored.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The CHIP-8 key (`0x0` to `0xF`).
+    pub fn press_key(&mut self, key: u8) {
+        if key > MAX_KEY {
+            return;
+        }
+
+        if let Some(macro_recorder) = self.macro_recorder.as_mut() {
+            macro_recorder.record(self.frame_count, key, true);
+        }
+        self.apply_key_press(key);
+    }
+
+    /// Releases a CHIP-8 key directly, as [`press_key`](Emulator::press_key) presses it. Keys past `0xF` are ignored.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The CHIP-8 key (`0x0` to `0xF`).
+    pub fn release_key(&mut self, key: u8) {
+        if key > MAX_KEY {
+            return;
+        }
+
+        if let Some(macro_recorder) = self.macro_recorder.as_mut() {
+            macro_recorder.record(self.frame_count, key, false);
+        }
+        self.apply_key_release(key);
+    }
+
+    /// Presses the key on the game, the game running in lockstep, and the input display.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The CHIP-8 key (`0x0` to `0xF`).
+    fn apply_key_press(&mut self, key: u8) {
+        self.update_log_stamp();
+        frame_log::event(Subsystem::Input, format_args!("Key {key:X} pressed"));
+        self.interpreter.press_key(key);
+        if let Some(lockstep) = self.lockstep.as_mut() {
+            lockstep.press_key(key);
+        }
+        if let Some(input_timeline) = self.input_timeline.as_mut() {
+            input_timeline.press(key);
+        }
+    }
+
+    /// Releases the key on the game, the game running in lockstep, and the input display.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The CHIP-8 key (`0x0` to `0xF`).
+    fn apply_key_release(&mut self, key: u8) {
+        self.update_log_stamp();
+        frame_log::event(Subsystem::Input, format_args!("Key {key:X} released"));
+        self.interpreter.release_key(key);
+        if let Some(lockstep) = self.lockstep.as_mut() {
+            lockstep.release_key(key);
+        }
+        if let Some(input_timeline) = self.input_timeline.as_mut() {
+            input_timeline.release(key);
+        }
+    }
+
+    /// Stamps the messages logged from now on with the current frame and instruction cycle.
+    fn update_log_stamp(&self) {
+        frame_log::set_stamp(self.frame_count, self.interpreter.counters().instructions);
+    }
+
+    /// Starts recording the keys pressed from the next frame onwards into a [macro](crate::macros), replacing any recording in progress.
+    pub fn start_macro_recording(&mut self) {
+        self.macro_recorder = Some(MacroRecorder::new(self.frame_count));
+    }
+
+    /// Stops recording, returning the recorded macro, or `None` if nothing was being recorded.
+    pub fn finish_macro_recording(&mut self) -> Option<InputMacro> {
+        self.macro_recorder.take().map(|macro_recorder| macro_recorder.finish(self.frame_count))
+    }
+
+    /// Returns true while a macro is being recorded.
+    #[must_use]
+    pub fn is_recording_macro(&self) -> bool {
+        self.macro_recorder.is_some()
+    }
+
+    /// Plays the macro from the next frame onwards, pressing and releasing its keys at the start of the frames they were recorded on.  
+    /// Any macro already playing is stopped, though the keys it is holding stay held until the player releases them. Macros stop playing when a new game is loaded.  
+    /// Does nothing in [pure mode](Emulator::set_pure).
+    ///
+    /// # Parameters
+    ///
+    /// * `input_macro` - The macro to play.
+    pub fn play_macro(&mut self, input_macro: InputMacro) {
+        if self.is_pure {
+            return;
+        }
+
+        self.macro_playback = Some(MacroPlayback::new(input_macro, self.frame_count));
+    }
+
+    /// Returns true while a macro is being played.
+    #[must_use]
+    pub fn is_playing_macro(&self) -> bool {
+        self.macro_playback.is_some()
+    }
+
+    /// Shows the input display, a timeline along the bottom of the window of the frames each key was pressed, held, and released on, or hides it if it is already shown.  
+    /// Only keys pressed while it is shown are recorded. It cannot be shown in [pure mode](Emulator::set_pure).
+    pub fn toggle_input_display(&mut self) {
+        if self.input_timeline.take().is_none() && !self.is_pure {
+            self.input_timeline = Some(InputTimeline::new());
+        }
+        self.renderer.set_input_timeline(self.input_timeline.as_ref());
+    }
+
+    /// Returns the keys of the frames on the [input display](Emulator::toggle_input_display), if it is shown.
+    #[must_use]
+    pub fn input_timeline(&self) -> Option<&InputTimeline> {
+        self.input_timeline.as_ref()
+    }
+
+    /// Overwrites the RAM of the running game starting at the provided address.
+    ///
+    /// # Parameters
+    ///
+    /// * `address` - The address of the first byte to write.
+    /// * `bytes` - The bytes to write.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` without writing anything if the bytes would run past the end of the RAM.
+    pub fn write_ram(&mut self, address: u16, bytes: &[u8]) -> Result<(), String> {
+        self.interpreter.write_ram(address, bytes)
+    }
+
+    /// Returns true if the emulator is in pure mode.
+    #[must_use]
+    pub fn is_pure(&self) -> bool {
+        self.is_pure
+    }
+
+    /// Sets whether the emulator runs games exactly as the selected quirks describe, for compatibility validation and comparisons against other emulators.  
+    /// Entering pure mode releases every frozen byte, hides the input display, stops any playing macro, puts the overlays back in their default places, ends any quirk comparison (keeping the quirks being run), and turns off [banking](crate::banking) from the next load.  
+    /// While in it, bytes cannot be frozen, the game cannot be [poked](Emulator::poke), macros cannot be played, the quirks cannot be changed, the overlays cannot be moved, and the input display cannot be shown.
+    ///
+    /// # Parameters
+    ///
+    /// * `pure` - True to enter pure mode, false to leave it.
+    pub fn set_pure(&mut self, pure: bool) {
+        self.is_pure = pure;
+        if pure {
+            self.frozen_bytes.clear();
+            if self.input_timeline.is_some() {
+                self.toggle_input_display();
+            }
+            self.macro_playback = None;
+            self.renderer.set_overlay_layout(OverlayLayout::new());
+            self.end_quirk_comparison();
+            let _ = self.interpreter.set_banking(false);
+        }
+    }
+
+    /// Sets where the overlays are drawn over the display (see [`Renderer::set_overlay_layout`]).  
+    /// Does nothing in [pure mode](Emulator::set_pure), which keeps the overlays in their default places.
+    ///
+    /// # Parameters
+    ///
+    /// * `overlay_layout` - The new layout.
+    pub fn set_overlay_layout(&mut self, overlay_layout: OverlayLayout) {
+        if !self.is_pure {
+            self.renderer.set_overlay_layout(overlay_layout);
+        }
+    }
+
+    /// Freezes the byte at the provided address to a value, writing it immediately and again at the start of every frame.  
+    /// Freezing an address which is already frozen replaces its value. Freezes are cleared when a new game is loaded.
+    ///
+    /// # Parameters
+    ///
+    /// * `address` - The address to freeze.
+    /// * `value` - The value to hold the address at.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the address is outside the RAM, or the emulator is in [pure mode](Emulator::set_pure).
+    pub fn freeze(&mut self, address: u16, value: u8) -> Result<(), String> {
+        if self.is_pure {
+            return Err(String::from("Bytes cannot be frozen in pure mode."));
+        }
+
+        self.write_ram(address, &[value])?;
+        self.frozen_bytes.insert(address, value);
+
+        Ok(())
+    }
+
+    /// Releases a frozen address, returning the value it was held at (if it was frozen).
+    ///
+    /// # Parameters
+    ///
+    /// * `address` - The address to release.
+    pub fn unfreeze(&mut self, address: u16) -> Option<u8> {
+        self.frozen_bytes.remove(&address)
+    }
+
+    /// Returns the frozen addresses along with the values they are held at, in address order.
+    pub fn frozen_bytes(&self) -> impl Iterator<Item = (u16, u8)> + '_ {
+        self.frozen_bytes.iter().map(|(address, value)| (*address, *value))
+    }
+
+    /// Changes the quirks of the running game.  
+    /// The first change snapshots the game beforehand, and every change returns the game to that snapshot, so the settings from before and after can be [switched between](Emulator::switch_quirk_side) from the same point.  
+    /// Does nothing in [pure mode](Emulator::set_pure).
+    ///
+    /// # Parameters
+    ///
+    /// * `quirk_config` - The new quirk settings.
+    pub fn change_quirks(&mut self, quirk_config: QuirkConfig) {
+        if self.is_pure {
+            return;
+        }
+
+        let comparison = self.quirk_comparison.get_or_insert_with(|| QuirkComparison {
+            snapshot: self.interpreter.snapshot(),
+            frame_count: self.frame_count,
+            before: self.interpreter.quirk_config().clone(),
+            after: self.interpreter.quirk_config().clone(),
+            side: QuirkSide::After
+        });
+        comparison.after = quirk_config;
+        comparison.side = QuirkSide::After;
+        self.restore_quirk_comparison();
+    }
+
+    /// Switches between the quirk settings from before and after the change, returning the game to the point at which the quirks were first changed.  
+    /// Does nothing if the quirks have not been changed.
+    pub fn switch_quirk_side(&mut self) {
+        if let Some(comparison) = self.quirk_comparison.as_mut() {
+            comparison.side = match comparison.side {
+                QuirkSide::Before => QuirkSide::After,
+                QuirkSide::After => QuirkSide::Before
+            };
+            self.restore_quirk_comparison();
+        }
+    }
+
+    /// Returns the quirk comparison, if the quirks have been changed since the game was loaded.
+    #[must_use]
+    pub fn quirk_comparison(&self) -> Option<&QuirkComparison> {
+        self.quirk_comparison.as_ref()
+    }
+
+    /// Stops comparing quirks, keeping the settings currently being run.
+    pub fn end_quirk_comparison(&mut self) {
+        self.quirk_comparison = None;
+        self.stop_lockstep();
+    }
+
+    /// Starts running the quirk settings which are not being run alongside the game, from the current point and with the same input, or stops if they are already running.  
+    /// While running in lockstep, the pixels which differ between the two displays are highlighted, showing exactly where the quirks make a difference.  
+    /// Does nothing if the quirks have not been [changed](Emulator::change_quirks).
+    pub fn toggle_lockstep(&mut self) {
+        if self.lockstep.is_some() {
+            self.stop_lockstep();
+        } else {
+            self.start_lockstep();
+        }
+    }
+
+    /// Returns the interpreter running the other quirk settings in lockstep with the game, if there is one.
+    #[must_use]
+    pub fn lockstep(&self) -> Option<&Interpreter> {
+        self.lockstep.as_ref()
+    }
+
+    /// Forks the game with the quirk settings of the side not being run, replacing any game already running in lockstep.
+    fn start_lockstep(&mut self) {
+        let Some(comparison) = &self.quirk_comparison else {
+            return;
+        };
+
+        let mut lockstep = self.interpreter.fork();
+        lockstep.set_quirk_config(match comparison.side {
+            QuirkSide::Before => comparison.after.clone(),
+            QuirkSide::After => comparison.before.clone()
+        });
+        self.renderer.set_compared_frame(self.interpreter.framebuffer(), Some(lockstep.framebuffer()));
+        self.lockstep = Some(lockstep);
+    }
+
+    /// Stops running the game in lockstep, no longer highlighting the differences.
+    fn stop_lockstep(&mut self) {
+        self.lockstep = None;
+        self.renderer.set_compared_frame(self.interpreter.framebuffer(), None);
+    }
+
+    /// Returns the game to the point at which the quirks were first changed, running the settings of the current side.  
+    /// A game which halted since that point is running again afterwards.
+    fn restore_quirk_comparison(&mut self) {
+        let Some(comparison) = &self.quirk_comparison else {
+            return;
+        };
+
+        self.interpreter.restore(&comparison.snapshot);
+        self.interpreter.set_quirk_config(match comparison.side {
+            QuirkSide::Before => comparison.before.clone(),
+            QuirkSide::After => comparison.after.clone()
+        });
+        self.frame_count = comparison.frame_count;
+        if self.lockstep.is_some() {
+            self.start_lockstep();
+        }
+
+        if matches!(self.mode_before_menu, EmulatorMode::Halted(_)) {
+            self.mode_before_menu = EmulatorMode::Running;
+        }
+        if matches!(self.mode, EmulatorMode::Halted(_)) {
+            self.mode = EmulatorMode::Running;
+        }
+        self.sync_halt();
+        self.set_mode(self.mode);
+    }
+
+    /// Loads the queued game and restores the queued snapshot, if there are any.  
+    /// Loading a game leaves the idle and halted modes, while any other mode (e.g. paused) is kept.
+    pub(crate) fn apply_pending_load(&mut self) {
+        if let Some((game_data, settings)) = self.pending_load.take() {
+            if let Some(quirk_config) = settings.quirk_config {
+                self.interpreter.set_quirk_config(quirk_config);
+            }
+            if let Some(cycles_per_frame) = settings.cycles_per_frame {
+                self.cycles_per_frame = cycles_per_frame;
+            }
+            if let Some(palette) = settings.palette {
+                self.renderer.set_palette(palette);
+            }
+            self.load(game_data);
+        }
+
+        if let Some(snapshot) = self.pending_restore.take() {
+            if self.mode != EmulatorMode::Idle {
+                self.interpreter.restore(&snapshot);
+                self.set_mode(self.mode);
+                if self.lockstep.is_some() {
+                    self.start_lockstep();
+                }
+            }
+        }
+    }
+
+    /// Loads the game, leaving the idle and halted modes.
+    ///
+    /// # Parameters
+    ///
+    /// * `game_data` - The bytes which were read from the game file.
+    fn load(&mut self, game_data: Vec<u8>) {
+        self.interpreter.load_game(&game_data);
+        self.game_data = Some(game_data);
+        self.frozen_bytes.clear();
+        self.quirk_comparison = None;
+        self.macro_playback = None;
+        self.stop_lockstep();
+        if let Some(speedrun_timer) = self.speedrun_timer.as_mut() {
+            speedrun_timer.reset();
+        }
+
+        if matches!(self.mode, EmulatorMode::Idle | EmulatorMode::Halted(_)) {
+            self.set_mode(EmulatorMode::Running);
+        }
+        if matches!(self.mode_before_menu, EmulatorMode::Idle | EmulatorMode::Halted(_)) {
+            self.mode_before_menu = EmulatorMode::Running;
+        }
+    }
+
+    /// Runs a single whole frame regardless of the mode: any queued game load, the inputs of a playing [macro](Emulator::play_macro), the frozen bytes, the configured number of instruction cycles (multiplied while [fast-forwarding](Emulator::set_turbo)), then the frame itself.  
+    /// The game running in [lockstep](Emulator::toggle_lockstep) (if any) runs the same frame first, so that its display can be compared when the game's is drawn.  
+    /// Nothing is run if no game has been loaded. If the game halts during the frame, the emulator enters the halted mode.  
+    /// If the game reaches a [breakpoint](Emulator::debugger_mut) or meets the condition of one, the emulator pauses there, finishing the frame without its remaining cycles.
+    pub fn step_frame(&mut self) {
+        self.apply_pending_load();
+        if self.mode == EmulatorMode::Idle {
+            return;
+        }
+
+        let cycles_per_frame = self.cycles_per_frame.saturating_mul(self.turbo.unwrap_or(1));
+        if let Some(mut macro_playback) = self.macro_playback.take() {
+            for event in macro_playback.take_due(self.frame_count) {
+                if event.pressed {
+                    self.apply_key_press(event.key);
+                } else {
+                    self.apply_key_release(event.key);
+                }
+            }
+            if !macro_playback.is_finished() {
+                self.macro_playback = Some(macro_playback);
+            }
+        }
+
+        if let Some(lockstep) = self.lockstep.as_mut() {
+            for (address, value) in &self.frozen_bytes {
+                lockstep.write_ram(*address, &[*value]).expect("Frozen address outside the RAM.");
+            }
+
+            for _ in 0..cycles_per_frame {
+                lockstep.handle_cycle();
+            }
+
+            lockstep.handle_frame();
+            self.renderer.set_compared_frame(self.interpreter.framebuffer(), Some(lockstep.framebuffer()));
+        }
+
+        for (address, value) in &self.frozen_bytes {
+            self.interpreter.write_ram(*address, &[*value]).expect("Frozen address outside the RAM.");
+        }
+
+        for _ in 0..cycles_per_frame {
+            if self.debugger.should_break(self.interpreter.program_counter()) {
+                self.pause();
+                break;
+            }
+
+            self.run_cycle();
+            if self.debugger.should_break_after(&self.interpreter) {
+                self.pause();
+                break;
+            }
+        }
+
+        if let Some(input_timeline) = self.input_timeline.as_mut() {
+            input_timeline.end_frame();
+            self.renderer.set_input_timeline(Some(input_timeline));
+        }
+        self.update_log_stamp();
+        self.renderer.present(&mut self.interpreter);
+        self.interpreter.handle_frame();
+        self.frame_count += 1;
+        if let Some(speedrun_timer) = self.speedrun_timer.as_mut() {
+            speedrun_timer.update(self.frame_count, &self.interpreter);
+        }
+        self.sync_halt();
+    }
+
+    /// Advances the emulation by the provided amount of time, running as many whole frames as fit.  
+    /// Time which does not make up a whole frame is carried over to the next call.  
+    /// At most [`MAX_FRAMES_PER_ADVANCE`](MAX_FRAMES_PER_ADVANCE) frames are run, with any excess time dropped.  
+    /// Frames are only run in the [modes which allow it](EmulatorMode::runs_frames), though a queued game is loaded in any mode.
+    ///
+    /// # Parameters
+    ///
+    /// * `dt` - The time elapsed since the previous call, which is scaled by the [speed](Emulator::set_speed).
+    pub fn advance(&mut self, dt: Duration) -> FrameAdvance {
+        self.advance_with(dt, |_| {})
+    }
+
+    /// Behaves like [`advance`](Emulator::advance), additionally calling the provided function after every frame which is run.  
+    /// Useful for anything which should observe every frame, such as logging, even when several frames are run at once.
+    ///
+    /// # Parameters
+    ///
+    /// * `dt` - The time elapsed since the previous call.
+    /// * `on_frame` - Called with the emulator after each frame.
+    pub fn advance_with(&mut self, dt: Duration, mut on_frame: impl FnMut(&Emulator)) -> FrameAdvance {
+        self.apply_pending_load();
+        match self.mode {
+            EmulatorMode::Running => {},
+            EmulatorMode::Stepping => {
+                self.step_frame();
+                on_frame(self);
+                if self.mode == EmulatorMode::Stepping {
+                    self.set_mode(EmulatorMode::Paused);
+                }
+                self.leftover = Duration::ZERO;
+                return FrameAdvance { frames: 1, leftover: self.leftover };
+            },
+            _ => {
+                self.leftover = Duration::ZERO;
+                return FrameAdvance { frames: 0, leftover: self.leftover };
+            }
+        }
+
+        self.leftover += if self.turbo.is_some() { dt } else { dt.mul_f64(self.speed) };
+
+        let mut frames = 0;
+        while self.leftover >= self.next_frame_duration() {
+            if frames == MAX_FRAMES_PER_ADVANCE {
+                // The timers still tick for the dropped frames, so that a stall does not stretch a beep
+                let dropped_frames = self.drop_whole_frames();
+                self.interpreter.handle_dropped_frames(dropped_frames);
+                break;
+            }
+
+            self.leftover -= self.next_frame_duration();
+            self.frame_phase = (self.frame_phase + 1) % FRAMES_PER_GROUP;
+            self.step_frame();
+            on_frame(self);
+            frames += 1;
+
+            if !self.mode.runs_frames() {
+                self.leftover = Duration::ZERO;
+                break;
+            }
+        }
+
+        FrameAdvance { frames, leftover: self.leftover }
+    }
+
+    /// Returns the exact length of the next frame, which is a nanosecond shorter than [`FRAME_DURATION`](FRAME_DURATION) for one frame in every three so that 60 frames last exactly a second.
+    fn next_frame_duration(&self) -> Duration {
+        let length = frame_group_offset(u128::from(self.frame_phase) + 1) - frame_group_offset(u128::from(self.frame_phase));
+
+        // A frame is shorter than a second, so its length always fits
+        Duration::from_nanos(length as u64)
+    }
+
+    /// Removes every whole frame from the leftover time without running them, returning how many there were.
+    fn drop_whole_frames(&mut self) -> u32 {
+        let frames_per_group = u128::from(FRAMES_PER_GROUP);
+        let phase = u128::from(self.frame_phase);
+        let elapsed = frame_group_offset(phase) + self.leftover.as_nanos();
+        let frames = elapsed * frames_per_group / FRAME_GROUP_DURATION.as_nanos();
+
+        // The phase is below the group size and the remainder is shorter than a frame, so both fit
+        self.frame_phase = (frames % frames_per_group) as u32;
+        self.leftover = Duration::from_nanos((elapsed - frame_group_offset(frames)) as u64);
+        u32::try_from(frames - phase).unwrap_or(u32::MAX)
+    }
+
+    /// Advances the emulation by the time which has passed since the previous tick (nothing on the first), keeping the time itself rather than leaving it to the caller.  
+    /// Never blocks, so it can be called from any loop (e.g. a GUI's repaint or a test); see [`next_tick_delay`](Emulator::next_tick_delay) for how long to wait before calling it again.  
+    /// While [fast-forwarding](Emulator::set_turbo), every tick runs a whole frame, so the game runs as quickly as the caller ticks.
+    pub fn tick(&mut self) -> FrameAdvance {
+        self.tick_with(|_| {})
+    }
+
+    /// Behaves like [`tick`](Emulator::tick), additionally calling the provided function after every frame which is run.
+    ///
+    /// # Parameters
+    ///
+    /// * `on_frame` - Called with the emulator after each frame.
+    pub fn tick_with(&mut self, on_frame: impl FnMut(&Emulator)) -> FrameAdvance {
+        let now = Instant::now();
+        let dt = match (self.turbo, self.last_tick) {
+            (Some(_), _) => FRAME_DURATION,
+            (None, Some(last_tick)) => now.duration_since(last_tick),
+            (None, None) => Duration::ZERO
+        };
+        self.last_tick = Some(now);
+
+        self.advance_with(dt, on_frame)
+    }
+
+    /// Returns how long callers of [`tick`](Emulator::tick) can wait before the next frame is due, stretched or shrunk by the [speed](Emulator::set_speed).  
+    /// Zero while fast-forwarding a running game, which should be ticked again straight away.
+    #[must_use]
+    pub fn next_tick_delay(&self) -> Duration {
+        if self.turbo.is_some() && self.mode.runs_frames() {
+            return Duration::ZERO;
+        }
+
+        self.next_frame_duration().saturating_sub(self.leftover).div_f64(self.speed)
+    }
+}
+
+/// Returns the time in nanoseconds from the start of a [group of frames](FRAME_GROUP_DURATION) to the end of the provided number of frames, rounded up so that the first frame lasts [`FRAME_DURATION`](FRAME_DURATION).
+///
+/// # Parameters
+///
+/// * `frames` - The number of frames since the start of the group.
+fn frame_group_offset(frames: u128) -> u128 {
+    (FRAME_GROUP_DURATION.as_nanos() * frames).div_ceil(u128::from(FRAMES_PER_GROUP))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backend::CharKeyboard;
+    use crate::events::EventLog;
+    use crate::palette::PalettePreset;
+    use crate::quirks::{Platform, ShiftingQuirk};
+
+    use super::*;
+
+    /// Returns an emulator which will run a program which jumps to itself forever.
+    fn looping_emulator() -> Emulator {
+        let mut emulator = Emulator::new(Interpreter::new(), 10);
+        emulator.queue_load(vec![0x12, 0x00]);
+
+        emulator
+    }
+
+    /// Returns a game which draws the "0" sprite, waits for a key (storing it in V1), then loops forever.
+    fn drawing_game() -> Vec<u8> {
+        vec![0xA0, 0x00, 0xD0, 0x05, 0xF1, 0x0A, 0x12, 0x08, 0x12, 0x08]
+    }
+
+    #[test]
+    fn queued_load_waits_for_frame() {
+        let mut emulator = looping_emulator();
+
+        emulator.queue_load(vec![0x61, 0x05, 0x12, 0x02]);
+        emulator.queue_load(drawing_game());
+        assert!(emulator.has_pending_load(), "Load not queued.");
+        assert_eq!(emulator.interpreter().ram()[0x200], 0x00, "Game loaded before the frame boundary.");
+
+        emulator.step_frame();
+        assert!(!emulator.has_pending_load(), "Queued load not processed.");
+        assert_eq!(emulator.interpreter().ram()[0x200], 0xA0, "Latest queued game not loaded.");
+        assert_eq!(emulator.interpreter().frame_draw_count(), 1, "Loaded game not run.");
+    }
+
+    #[test]
+    fn queued_settings_wait_for_frame() {
+        let mut emulator = looping_emulator();
+        emulator.step_frame();
+
+        let palette = PalettePreset::Amber.palette();
+        let settings = LoadSettings { quirk_config: Some(Platform::Schip.quirk_config()), cycles_per_frame: Some(30), palette: Some(palette.clone()) };
+        emulator.queue_load_with(drawing_game(), settings);
+        assert_eq!((emulator.interpreter().quirk_config(), emulator.cycles_per_frame()), (&QuirkConfig::new(), 10), "Settings changed before the frame boundary.");
+        assert_eq!(emulator.renderer().palette(), &Palette::new(), "Palette changed before the frame boundary.");
+
+        emulator.step_frame();
+        assert_eq!((emulator.interpreter().quirk_config(), emulator.cycles_per_frame()), (&Platform::Schip.quirk_config(), 30), "Settings not updated.");
+        assert_eq!(emulator.renderer().palette(), &palette, "Palette not updated.");
+
+        emulator.reset();
+        emulator.step_frame();
+        assert_eq!((emulator.interpreter().quirk_config(), emulator.cycles_per_frame()), (&Platform::Schip.quirk_config(), 30), "Settings not kept through a reset.");
+        assert_eq!(emulator.renderer().palette(), &palette, "Palette not kept through a reset.");
+    }
+
+    #[test]
+    fn reset_reloads_game() {
+        let mut emulator = looping_emulator();
+
+        let mut idle_emulator = Emulator::new(Interpreter::new(), 10);
+        idle_emulator.reset();
+        assert!(!idle_emulator.has_pending_load(), "Reset queued a load without a game.");
+
+        emulator.queue_load(drawing_game());
+        emulator.step_frame();
+        emulator.step_frame();
+        assert_eq!(emulator.interpreter().program_counter(), 0x206, "Game did not run.");
+
+        emulator.reset();
+        emulator.step_frame();
+        assert_eq!(emulator.interpreter().program_counter(), 0x204, "Game not restarted.");
+    }
+
+    #[test]
+    fn load_while_waiting_for_display() {
+        let mut emulator = looping_emulator();
+        emulator.queue_load(drawing_game());
+        emulator.step_frame();
+
+        // Stop partway through a frame while the next draw waits for the display
+        let interpreter = emulator.interpreter_mut();
+        interpreter.load_game(&[0xA0, 0x00, 0xD0, 0x05]);
+        interpreter.handle_cycle();
+        interpreter.handle_cycle();
+
+        emulator.queue_load(drawing_game());
+        emulator.step_frame();
+        assert_eq!(emulator.interpreter().frame_draw_count(), 1, "Stale draw completed after load.");
+        assert_eq!(emulator.interpreter().program_counter(), 0x204, "Loaded game did not run up to its key wait.");
+    }
+
+    #[test]
+    fn load_while_waiting_for_key() {
+        let mut emulator = looping_emulator();
+        emulator.queue_load(drawing_game());
+        emulator.step_frame();
+
+        // Hold a key through the load, then release it once the new game is waiting
+        emulator.interpreter_mut().press_key(0x0);
+        emulator.queue_load(drawing_game());
+        emulator.step_frame();
+        emulator.step_frame();
+        assert_eq!(emulator.interpreter().program_counter(), 0x206, "Loaded game is not waiting for a key.");
+
+        emulator.interpreter_mut().release_key(0x0);
+        emulator.step_frame();
+        assert_eq!(emulator.interpreter().program_counter(), 0x206, "Key held before the load ended the new game's wait.");
+
+        emulator.interpreter_mut().press_key(0x0);
+        emulator.interpreter_mut().release_key(0x0);
+        emulator.step_frame();
+        assert_eq!(emulator.interpreter().program_counter(), 0x208, "Fresh key press did not end the wait.");
+    }
+
+    #[test]
+    fn freeze_reapplied_every_frame() {
+        let mut emulator = looping_emulator();
+
+        // Repeatedly reads the byte at 0x300 into V0
+        emulator.queue_load(vec![0xA3, 0x00, 0xF0, 0x65, 0x12, 0x00]);
+        emulator.step_frame();
+        assert!(emulator.freeze(0x300, 0x42).is_ok(), "Valid freeze failed.");
+        assert_eq!(emulator.interpreter().ram()[0x300], 0x42, "Freeze not applied immediately.");
+
+        assert!(emulator.write_ram(0x300, &[0x0]).is_ok(), "Valid write failed.");
+        emulator.step_frame();
+        assert_eq!(emulator.interpreter().registers()[0x0], 0x42, "Freeze not reapplied at the start of the frame.");
+        assert_eq!(emulator.frozen_bytes().collect::<Vec<_>>(), vec![(0x300, 0x42)], "Frozen bytes reported incorrectly.");
+        assert_eq!(emulator.unfreeze(0x300), Some(0x42), "Frozen value not returned.");
+        assert_eq!(emulator.unfreeze(0x300), None, "Address still frozen.");
+
+        assert!(emulator.freeze(0xFFFF, 0x42).is_ok(), "Freeze of the last XO-CHIP address failed.");
+        assert!(emulator.freeze(0x300, 0x42).is_ok(), "Valid freeze failed.");
+        emulator.queue_load(vec![0x12, 0x00]);
+        emulator.step_frame();
+        assert_eq!(emulator.frozen_bytes().count(), 0, "Freezes not cleared by a new game.");
+    }
+
+    #[test]
+    fn pure_mode() {
+        let mut emulator = looping_emulator();
+        emulator.queue_load(vec![0x12, 0x00]);
+        emulator.step_frame();
+        emulator.freeze(0x300, 0x42).unwrap();
+        emulator.toggle_input_display();
+        emulator.change_quirks(Platform::Schip.quirk_config());
+        let input_macro: InputMacro = "1 press 5".parse().unwrap();
+        emulator.play_macro(input_macro.clone());
+        let mut overlay_layout = OverlayLayout::new();
+        overlay_layout.apply("input-display anchor=top").unwrap();
+        emulator.set_overlay_layout(overlay_layout.clone());
+
+        emulator.set_pure(true);
+        assert!(emulator.is_pure(), "Pure mode not entered.");
+        assert_eq!(emulator.frozen_bytes().count(), 0, "Freezes not released.");
+        assert!(emulator.input_timeline().is_none(), "Input display not hidden.");
+        assert!(emulator.quirk_comparison().is_none(), "Quirk comparison not ended.");
+        assert!(!emulator.is_playing_macro(), "Macro not stopped.");
+        assert_eq!(*emulator.renderer().overlay_layout(), OverlayLayout::new(), "Overlays not put back.");
+
+        assert!(emulator.freeze(0x300, 0x42).is_err(), "Byte frozen in pure mode.");
+        emulator.toggle_input_display();
+        assert!(emulator.input_timeline().is_none(), "Input display shown in pure mode.");
+        emulator.change_quirks(QuirkConfig::new());
+        assert_eq!(*emulator.interpreter().quirk_config(), Platform::Schip.quirk_config(), "Quirks changed in pure mode.");
+        emulator.play_macro(input_macro);
+        assert!(!emulator.is_playing_macro(), "Macro played in pure mode.");
+        emulator.set_overlay_layout(overlay_layout);
+        assert_eq!(*emulator.renderer().overlay_layout(), OverlayLayout::new(), "Overlays moved in pure mode.");
+        emulator.pause();
+        assert!(emulator.poke(&"V3 = 0x1F".parse().unwrap()).is_err(), "Game poked in pure mode.");
+        assert_eq!(emulator.interpreter().registers()[3], 0x00, "Register poked in pure mode.");
+    }
+
+    #[test]
+    fn idle_until_loaded() {
+        let mut emulator = Emulator::new(Interpreter::new(), 10);
+
+        assert_eq!(emulator.advance(FRAME_DURATION).frames, 0, "Frame run without a game.");
+        emulator.step_frame();
+        assert_eq!(emulator.frame_count(), 0, "Frame stepped without a game.");
+        assert_eq!(emulator.mode(), EmulatorMode::Idle, "Emulator left idle without a game.");
+
+        emulator.queue_load(vec![0x12, 0x00]);
+        assert_eq!(emulator.advance(FRAME_DURATION).frames, 1, "Loaded game not run.");
+        assert_eq!(emulator.mode(), EmulatorMode::Running, "Emulator not running after a load.");
+    }
+
+    #[test]
+    fn pause_and_step() {
+        let mut emulator = looping_emulator();
+        emulator.advance(FRAME_DURATION);
+
+        emulator.step();
+        assert_eq!(emulator.mode(), EmulatorMode::Running, "Running emulator started stepping.");
+
+        emulator.pause();
+        let result = emulator.advance(FRAME_DURATION * 3);
+        assert_eq!(result.frames, 0, "Frames run while paused.");
+        assert_eq!(result.leftover, Duration::ZERO, "Time accumulated while paused.");
+
+        emulator.step();
+        assert_eq!(emulator.mode(), EmulatorMode::Stepping, "Paused emulator not stepping.");
+        assert_eq!(emulator.advance(Duration::ZERO).frames, 1, "Step did not run a single frame.");
+        assert_eq!(emulator.mode(), EmulatorMode::Paused, "Emulator not paused after a step.");
+
+        emulator.resume();
+        assert_eq!(emulator.advance(FRAME_DURATION * 2).frames, 2, "Frames not run after resuming.");
+    }
+
+    #[test]
+    fn uneven_steps() {
+        let mut emulator = looping_emulator();
+        let step = Duration::from_micros(5500);
+
+        let frames: u32 = (0..3).map(|_| emulator.advance(step).frames).sum();
+        assert_eq!(frames, 0, "Frame run before a whole frame passed.");
+        assert_eq!(emulator.advance(Duration::ZERO).leftover, step * 3, "Time lost between steps.");
+
+        // 2000 steps of 5.5ms make 11 seconds, which is exactly 660 frames
+        let frames: u32 = (3..2000).map(|_| emulator.advance(step).frames).sum();
+        assert_eq!(frames, 660, "Frames not run at exactly 60Hz.");
+        assert_eq!(emulator.advance(Duration::ZERO).leftover, Duration::ZERO, "Time left over after whole frames.");
+
+        // Dropped frames keep to 60Hz as well
+        let result = emulator.advance(Duration::from_secs(1) + step);
+        assert_eq!(result.frames, MAX_FRAMES_PER_ADVANCE, "Frames not capped.");
+        assert_eq!(result.leftover, step, "Dropped frames not whole.");
+    }
+
+    #[test]
+    fn menu_returns_to_previous_mode() {
+        let mut emulator = Emulator::new(Interpreter::new(), 10);
+
+        emulator.open_menu();
+        emulator.queue_load(vec![0x12, 0x00]);
+        assert_eq!(emulator.advance(FRAME_DURATION).frames, 0, "Frame run while the menu is open.");
+        assert!(!emulator.has_pending_load(), "Game not loaded while the menu is open.");
+        emulator.close_menu();
+        assert_eq!(emulator.mode(), EmulatorMode::Running, "Closing the menu did not run the loaded game.");
+
+        emulator.pause();
+        emulator.open_menu();
+        emulator.open_menu();
+        emulator.close_menu();
+        assert_eq!(emulator.mode(), EmulatorMode::Paused, "Closing the menu did not return to pause.");
+
+        emulator.rewind();
+        assert_eq!(emulator.mode(), EmulatorMode::Rewinding, "Paused emulator not rewinding.");
+        assert_eq!(emulator.advance(FRAME_DURATION).frames, 0, "Frame run while rewinding.");
+        emulator.resume();
+        assert_eq!(emulator.mode(), EmulatorMode::Running, "Rewinding emulator not resumed.");
+    }
+
+    #[test]
+    fn halt_stops_frames() {
+        let mut emulator = Emulator::new(Interpreter::new(), 10);
+
+        // Halts on the first cycle of the second frame
+        emulator.queue_load(vec![0x00, 0xE0, 0x00, 0xE0, 0x00, 0xE0, 0x00, 0xE0, 0x00, 0xE0, 0x00, 0xE0, 0x00, 0xE0, 0x00, 0xE0, 0x00, 0xE0, 0x00, 0xE0, 0x00, 0x00]);
+        let result = emulator.advance(FRAME_DURATION * 5);
+        assert_eq!(result.frames, 2, "Frames run after halting.");
+        assert_eq!(emulator.mode(), EmulatorMode::Halted(HaltReason::Exit), "Emulator not halted.");
+        assert_eq!(emulator.advance(FRAME_DURATION).frames, 0, "Frames run while halted.");
+
+        emulator.reset();
+        emulator.advance(Duration::ZERO);
+        assert_eq!(emulator.mode(), EmulatorMode::Running, "Reset did not leave the halted mode.");
+
+        emulator.open_menu();
+        emulator.stop();
+        assert_eq!(emulator.mode(), EmulatorMode::Menu, "Stop closed the menu.");
+        emulator.close_menu();
+        assert_eq!(emulator.mode(), EmulatorMode::Halted(HaltReason::UserStop), "Stop not kept while the menu was open.");
+    }
+
+    #[test]
+    fn queued_restore_follows_load() {
+        let mut emulator = Emulator::new(Interpreter::new(), 10);
+        emulator.queue_load(vec![0x71, 0x01, 0x12, 0x00]);
+        emulator.step_frame();
+        let snapshot = emulator.interpreter().snapshot();
+
+        emulator.queue_restore(snapshot.clone());
+        emulator.queue_load(vec![0x71, 0x01, 0x12, 0x00]);
+        emulator.step_frame();
+        assert_eq!(emulator.interpreter().registers()[0x1], 10, "Snapshot not restored after the load.");
+
+        let mut idle_emulator = Emulator::new(Interpreter::new(), 10);
+        idle_emulator.queue_restore(snapshot);
+        idle_emulator.step_frame();
+        assert_eq!(idle_emulator.interpreter().registers()[0x1], 0, "Snapshot restored without a game.");
+    }
+
+    #[test]
+    fn quirk_comparison_from_same_point() {
+        let mut emulator = Emulator::new(Interpreter::new(), 10);
+
+        // Shifts V1 right, which is 8 when shifting vY and 1 when shifting vX
+        let shifting_game = vec![0x61, 0x03, 0x62, 0x10, 0x81, 0x26, 0x12, 0x06];
+        emulator.queue_load(shifting_game.clone());
+        emulator.switch_quirk_side();
+        assert!(emulator.quirk_comparison().is_none(), "Comparison started without a change.");
+
+        emulator.apply_pending_load();
+        let mut quirk_config = QuirkConfig::new();
+        quirk_config.shifting = ShiftingQuirk::Vx;
+        emulator.change_quirks(quirk_config.clone());
+        emulator.step_frame();
+        assert_eq!(emulator.interpreter().registers()[0x1], 0x1, "Changed quirk not run.");
+
+        emulator.switch_quirk_side();
+        assert_eq!(emulator.quirk_comparison().map(QuirkComparison::side), Some(QuirkSide::Before), "Side not switched.");
+        assert_eq!(emulator.interpreter().registers()[0x1], 0x0, "Game not returned to the comparison point.");
+        assert_eq!(emulator.frame_count(), 0, "Frame count not returned to the comparison point.");
+        emulator.step_frame();
+        assert_eq!(emulator.interpreter().registers()[0x1], 0x8, "Previous quirk not run.");
+
+        emulator.switch_quirk_side();
+        assert_eq!(emulator.interpreter().quirk_config(), &quirk_config, "Changed quirk not restored.");
+
+        emulator.queue_load(shifting_game);
+        emulator.step_frame();
+        assert!(emulator.quirk_comparison().is_none(), "Comparison kept after loading a game.");
+    }
+
+    #[test]
+    fn lockstep_runs_other_quirks() {
+        let mut emulator = Emulator::new(Interpreter::new(), 10);
+
+        // Shifts V1 right (8 when shifting vY, 1 when shifting vX), then waits for a key
+        emulator.queue_load(vec![0x61, 0x03, 0x62, 0x10, 0x81, 0x26, 0xF3, 0x0A, 0x12, 0x08]);
+        emulator.apply_pending_load();
+        emulator.toggle_lockstep();
+        assert!(emulator.lockstep().is_none(), "Lockstep started without a quirk change.");
+
+        let mut quirk_config = QuirkConfig::new();
+        quirk_config.shifting = ShiftingQuirk::Vx;
+        emulator.change_quirks(quirk_config);
+        emulator.toggle_lockstep();
+        emulator.step_frame();
+        let lockstep = emulator.lockstep().expect("Lockstep not started.");
+        assert_eq!(lockstep.quirk_config(), &QuirkConfig::new(), "Lockstep not running the other quirks.");
+        assert_eq!((emulator.interpreter().registers()[0x1], lockstep.registers()[0x1]), (0x1, 0x8), "Quirks not run side by side.");
+
+        emulator.handle_key_press(&CharKeyboard, 'x');
+        emulator.handle_key_release(&CharKeyboard, 'x');
+        emulator.step_frame();
+        assert_eq!(emulator.lockstep().map(Interpreter::program_counter), Some(emulator.interpreter().program_counter()), "Input not shared with the lockstep.");
+
+        emulator.switch_quirk_side();
+        assert_eq!(emulator.lockstep().map(|lockstep| lockstep.quirk_config().shifting.clone()), Some(ShiftingQuirk::Vx), "Lockstep not switched with the sides.");
+        emulator.toggle_lockstep();
+        assert!(emulator.lockstep().is_none(), "Lockstep not stopped.");
+    }
+
+    #[test]
+    fn input_display_stamps_frames() {
+        let mut emulator = looping_emulator();
+        emulator.handle_key_press(&CharKeyboard, 'x');
+        emulator.step_frame();
+        assert!(emulator.input_timeline().is_none(), "Input recorded without the input display.");
+
+        emulator.toggle_input_display();
+        emulator.handle_key_press(&CharKeyboard, 'w');
+        emulator.step_frame();
+        emulator.handle_key_release(&CharKeyboard, 'w');
+        emulator.handle_key_press(&CharKeyboard, 'p');
+        emulator.step_frame();
+        let frames: Vec<_> = emulator.input_timeline().expect("Input display not shown.").frames().copied().collect();
+        assert_eq!(frames.len(), 2, "Frames not recorded.");
+        assert_eq!((frames[0].pressed, frames[0].held), (1 << 0x5, 1 << 0x5), "Press not stamped with its frame.");
+        assert_eq!((frames[1].released, frames[1].held), (1 << 0x5, 0), "Release not stamped with its frame.");
+
+        emulator.toggle_input_display();
+        assert!(emulator.input_timeline().is_none(), "Input display not hidden.");
+    }
+
+    #[test]
+    fn key_profile_remaps_keys() {
+        let mut emulator = looping_emulator();
+        emulator.set_key_profile(Some("G = 4\nx = 6".parse().unwrap()));
+        emulator.toggle_input_display();
+        emulator.handle_key_press(&CharKeyboard, 'g');
+        emulator.handle_key_press(&CharKeyboard, 'x');
+        emulator.handle_key_press(&CharKeyboard, 'w');
+        emulator.step_frame();
+        assert_eq!(emulator.input_timeline().and_then(|timeline| timeline.frames().next()).map(|frame| frame.held), Some(1 << 0x4 | 1 << 0x5 | 1 << 0x6), "Keys not remapped by the profile.");
+
+        emulator.set_key_profile(None);
+        emulator.handle_key_press(&CharKeyboard, 'x');
+        emulator.step_frame();
+        assert_eq!(emulator.input_timeline().and_then(|timeline| timeline.frames().nth(1)).map(|frame| frame.pressed), Some(1 << 0x0), "Usual mapping not restored.");
+    }
+
+    #[test]
+    fn record_and_play_macros() {
+        let mut emulator = looping_emulator();
+        emulator.step_frame();
+        emulator.start_macro_recording();
+        emulator.handle_key_press(&CharKeyboard, 'w');
+        emulator.step_frame();
+        emulator.step_frame();
+        emulator.handle_key_release(&CharKeyboard, 'w');
+        emulator.handle_key_press(&CharKeyboard, 'q');
+        let input_macro = emulator.finish_macro_recording().expect("Macro not recorded.");
+        assert!(!emulator.is_recording_macro(), "Recording not stopped.");
+        assert_eq!(input_macro.to_string(), "0 press 5\n2 release 5\n2 press 4\n2 release 4\n", "Macro recorded incorrectly.");
+
+        emulator.handle_key_release(&CharKeyboard, 'q');
+        emulator.toggle_input_display();
+        emulator.play_macro(input_macro);
+        for _ in 0..3 {
+            emulator.step_frame();
+        }
+        let pressed: Vec<u16> = emulator.input_timeline().expect("Input display not shown.").frames().map(|frame| frame.pressed).collect();
+        assert_eq!(pressed, [1 << 0x5, 0, 1 << 0x4], "Macro not played on its frames.");
+        assert!(!emulator.is_playing_macro(), "Playback not finished.");
+        assert_eq!(emulator.interpreter().pressed_keys(), 0, "Keys left held by the macro.");
+    }
+
+    #[test]
+    fn press_logical_keys() {
+        let mut emulator = looping_emulator();
+        emulator.step_frame();
+        emulator.start_macro_recording();
+        emulator.press_key(0xA);
+        emulator.press_key(0x10);
+        assert_eq!(emulator.interpreter().pressed_keys(), 1 << 0xA, "Logical key not pressed, or key past 0xF pressed.");
+        emulator.release_key(0xA);
+        emulator.release_key(0x10);
+        assert_eq!(emulator.interpreter().pressed_keys(), 0, "Logical key not released.");
+        assert_eq!(emulator.finish_macro_recording().expect("Macro not recorded.").to_string(), "0 press A\n0 release A\n", "Logical keys not recorded.");
+    }
+
+    #[test]
+    fn break_and_step() {
+        let mut emulator = Emulator::new(Interpreter::new(), 10);
+        emulator.queue_load(vec![0x60, 0x01, 0x61, 0x02, 0x62, 0x03, 0x12, 0x06]);
+        emulator.debugger_mut().add_breakpoint(0x202);
+
+        emulator.advance(FRAME_DURATION);
+        assert_eq!(emulator.mode(), EmulatorMode::Paused, "Breakpoint did not pause.");
+        assert_eq!(emulator.interpreter().program_counter(), 0x202, "Instruction at the breakpoint run.");
+        assert_eq!(emulator.debugger_mut().take_hit(), Some(0x202), "Breakpoint hit not reported.");
+
+        emulator.step_instruction();
+        assert_eq!((emulator.interpreter().program_counter(), emulator.interpreter().registers()[0x1]), (0x204, 0x2), "Instruction not stepped.");
+
+        emulator.debugger_mut().add_breakpoint(0x204);
+        emulator.resume();
+        emulator.advance(FRAME_DURATION);
+        assert_eq!(emulator.mode(), EmulatorMode::Running, "Stopped at the breakpoint stepped onto.");
+        assert_eq!(emulator.interpreter().registers()[0x2], 0x3, "Game not resumed.");
+    }
+
+    #[test]
+    fn poke_while_paused() {
+        let mut emulator = Emulator::new(Interpreter::new(), 10);
+        emulator.queue_load(vec![0x60, 0x01, 0x30, 0x05, 0x12, 0x00, 0x61, 0x01, 0x12, 0x08]);
+        let poke: Poke = "V0 = 5".parse().unwrap();
+        emulator.advance(FRAME_DURATION);
+        assert!(emulator.poke(&poke).is_err(), "Poked while running.");
+
+        emulator.debugger_mut().add_breakpoint(0x202);
+        emulator.advance(FRAME_DURATION);
+        assert_eq!(emulator.poke(&poke), Ok(String::from("V0 = 0x5")), "Register not poked.");
+        emulator.resume();
+        emulator.advance(FRAME_DURATION);
+        assert_eq!(emulator.interpreter().registers()[0x1], 0x1, "Game not resumed with the poked register.");
+    }
+
+    #[test]
+    fn step_back() {
+        let mut emulator = Emulator::new(Interpreter::new(), 10);
+        emulator.interpreter_mut().set_event_log(Some(EventLog::default()));
+        emulator.queue_load(vec![0x60, 0x01, 0x61, 0x02, 0x62, 0x03, 0x12, 0x06]);
+        emulator.debugger_mut().add_breakpoint(0x204);
+
+        assert!(!emulator.step_back_instruction(), "Stepped back without being paused.");
+        emulator.advance(FRAME_DURATION);
+        assert!(emulator.step_back_instruction(), "Instruction not undone.");
+        assert_eq!((emulator.interpreter().program_counter(), emulator.interpreter().registers()[0x1]), (0x202, 0x0), "Instruction not undone.");
+
+        emulator.debugger_mut().clear_breakpoints();
+        emulator.debugger_mut().add_breakpoint(0x202);
+        emulator.resume();
+        emulator.advance(FRAME_DURATION);
+        assert_eq!(emulator.interpreter().registers()[0x2], 0x3, "Stopped at the breakpoint stepped back onto.");
+    }
+
+    #[test]
+    fn break_on_condition() {
+        let mut emulator = Emulator::new(Interpreter::new(), 10);
+        emulator.queue_load(vec![0x70, 0x01, 0x12, 0x00]);
+        emulator.debugger_mut().add_condition("V0 == 3".parse().unwrap());
+
+        emulator.advance(FRAME_DURATION);
+        assert_eq!(emulator.mode(), EmulatorMode::Paused, "Met condition did not pause.");
+        assert_eq!((emulator.interpreter().program_counter(), emulator.interpreter().registers()[0x0]), (0x202, 0x3), "Paused at the wrong instruction.");
+        assert_eq!(emulator.debugger_mut().take_met_condition().map(|condition| condition.to_string()).as_deref(), Some("V0 == 3"), "Met condition not reported.");
+
+        emulator.resume();
+        emulator.advance(FRAME_DURATION);
+        assert_eq!(emulator.mode(), EmulatorMode::Running, "Stopped again while the condition stayed met.");
+    }
+
+    #[test]
+    fn advance_partial_frame() {
+        let mut emulator = looping_emulator();
+
+        let half_frame = FRAME_DURATION / 2;
+        let result = emulator.advance(half_frame);
+        assert_eq!(result.frames, 0, "Frame run before a whole frame elapsed.");
+        assert_eq!(result.leftover, half_frame, "Partial frame time not carried over.");
+
+        let result = emulator.advance(FRAME_DURATION - half_frame);
+        assert_eq!(result.frames, 1, "Carried over time not used.");
+        assert_eq!(result.leftover, Duration::ZERO, "Wrong time carried over.");
+    }
+
+    #[test]
+    fn advance_multiple_frames() {
+        let mut emulator = looping_emulator();
+
+        let quarter_frame = FRAME_DURATION / 4;
+        let result = emulator.advance(FRAME_GROUP_DURATION + quarter_frame);
+        assert_eq!(result.frames, 3, "Wrong number of frames run.");
+        assert_eq!(result.leftover, quarter_frame, "Wrong time carried over.");
+        assert!((result.alpha() - 0.25).abs() < 0.001, "Wrong interpolation factor.");
+        assert_eq!(emulator.frame_count(), 3, "Frame count not updated.");
+    }
+
+    #[test]
+    fn advance_with_observes_each_frame() {
+        let mut emulator = looping_emulator();
+
+        let mut observed = Vec::new();
+        let result = emulator.advance_with(FRAME_DURATION * 3, |emulator| observed.push(emulator.frame_count()));
+        assert_eq!(result.frames, 3, "Wrong number of frames run.");
+        assert_eq!(observed, vec![1, 2, 3], "Frames not observed individually.");
+    }
+
+    #[test]
+    fn turbo_multiplies_cycles() {
+        let mut emulator = looping_emulator();
+        emulator.step_frame();
+        assert_eq!(emulator.counters().instructions, 10, "Usual cycles not run.");
+
+        emulator.set_turbo(Some(4));
+        emulator.step_frame();
+        assert_eq!(emulator.counters().instructions, 50, "Cycles not multiplied while fast-forwarding.");
+
+        emulator.set_turbo(None);
+        emulator.step_frame();
+        assert_eq!((emulator.turbo(), emulator.counters().instructions), (None, 60), "Usual speed not restored.");
+    }
+
+    #[test]
+    fn speed_scales_advanced_time() {
+        let mut emulator = looping_emulator();
+        emulator.set_speed(0.5);
+        assert_eq!(emulator.advance(FRAME_DURATION).frames, 0, "Frame run early at half speed.");
+        assert_eq!(emulator.advance(FRAME_DURATION * 2).frames, 1, "Frame not run at half speed.");
+
+        emulator.set_speed(2.0);
+        assert_eq!(emulator.advance(FRAME_DURATION).frames, 2, "Frames not doubled at double speed.");
+
+        emulator.set_turbo(Some(4));
+        assert_eq!(emulator.advance(FRAME_DURATION).frames, 1, "Speed not ignored while fast-forwarding.");
+
+        emulator.set_speed(100.0);
+        assert_eq!(emulator.speed(), MAX_SPEED, "Speed not clamped.");
+    }
+
+    #[test]
+    fn tick_keeps_time() {
+        let mut emulator = looping_emulator();
+
+        assert_eq!(emulator.tick().frames, 0, "Frame run on the first tick.");
+        assert_eq!(emulator.next_tick_delay(), FRAME_DURATION, "Delay not a whole frame before any time passed.");
+        std::thread::sleep(FRAME_DURATION);
+        assert!(emulator.tick().frames >= 1, "Frame not run once its time passed.");
+        assert!(emulator.next_tick_delay() <= FRAME_DURATION, "Delay longer than a frame.");
+
+        emulator.set_speed(0.5);
+        assert!(emulator.next_tick_delay() > FRAME_DURATION, "Delay not stretched by the speed.");
+
+        // Fast-forwarding runs a frame every tick, without waiting
+        emulator.set_turbo(Some(2));
+        let frame_count = emulator.frame_count();
+        emulator.tick();
+        emulator.tick();
+        assert_eq!(emulator.frame_count(), frame_count + 2, "Fast-forward did not run a frame every tick.");
+        assert_eq!(emulator.next_tick_delay(), Duration::ZERO, "Fast-forward waited between ticks.");
+    }
+
+    #[test]
+    fn send_and_sync() {
+        /// Fails to compile unless the type can be sent to and shared between threads.
+        fn assert_send_sync<T: Send + Sync>() {}
+
+        assert_send_sync::<Emulator>();
+        assert_send_sync::<Interpreter>();
+    }
+
+    #[test]
+    fn advance_ticks_timers_for_dropped_frames() {
+        let mut emulator = Emulator::new(Interpreter::new(), 10);
+
+        // Starts a half second beep, then loops forever
+        emulator.queue_load(vec![0x60, 0x1E, 0xF0, 0x18, 0x12, 0x04]);
+        emulator.advance(FRAME_DURATION);
+        assert_eq!(emulator.interpreter().sound_timer(), 29, "Beep not started.");
+
+        // The rest of the first 15 frames, which make up a quarter of a second, then half a frame
+        let half_frame = FRAME_DURATION / 2;
+        let result = emulator.advance(Duration::from_millis(250) - FRAME_DURATION + half_frame);
+        assert_eq!(result.frames, MAX_FRAMES_PER_ADVANCE, "Frames not capped.");
+        assert_eq!(emulator.interpreter().sound_timer(), 15, "Timers not ticked for the dropped frames.");
+        assert_eq!(result.leftover, half_frame, "Partial frame not carried over after dropping frames.");
+    }
+
+    #[test]
+    fn advance_caps_frames() {
+        let mut emulator = looping_emulator();
+
+        let result = emulator.advance(Duration::from_millis(250));
+        assert_eq!(result.frames, MAX_FRAMES_PER_ADVANCE, "Frames not capped.");
+        assert_eq!(result.leftover, Duration::ZERO, "Excess time not dropped.");
+    }
+}
@@ -0,0 +1,334 @@
+//! A module to record every change the game makes to the state of the interpreter as an event (register, stack, memory, and timer writes), for a bounded window of frames.
+//! The events answer precise questions such as "what changed between frame 100 and 101", and can be undone one instruction at a time to [step backwards](crate::emulator::Emulator::step_back_instruction) without keeping full snapshots.
+//! The display is not recorded, so stepping back over a completed draw leaves its pixels on the display until the game redraws them.
+
+use alloc::collections::VecDeque;
+use core::fmt::{Display, Formatter};
+
+#[cfg(not(feature = "std"))]
+use crate::alloc_prelude::*;
+use crate::interpreter::{REGISTERS_SIZE, STACK_SIZE};
+
+/// The number of frames of events kept by default, ten seconds of play.
+pub const DEFAULT_EVENT_WINDOW: u64 = 600;
+
+/// Denotes a single write to the state of the interpreter, along with the value it replaced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    Register { register: usize, old: u8, new: u8 },
+    RegisterI { old: u16, new: u16 },
+    ProgramCounter { old: u16, new: u16 },
+    StackPointer { old: usize, new: usize },
+    Stack { slot: usize, old: u16, new: u16 },
+    Memory { address: u16, old: u8, new: u8 },
+    DelayTimer { old: u8, new: u8 },
+    SoundTimer { old: u8, new: u8 }
+}
+
+impl Change {
+    /// Returns whether both changes write to the same place (e.g. the same register), regardless of the values.
+    ///
+    /// # Parameters
+    ///
+    /// * `other` - The change to compare with.
+    fn has_same_target(&self, other: &Change) -> bool {
+        match (self, other) {
+            (Change::Register { register, .. }, Change::Register { register: other, .. }) => register == other,
+            (Change::Stack { slot, .. }, Change::Stack { slot: other, .. }) => slot == other,
+            (Change::Memory { address, .. }, Change::Memory { address: other, .. }) => address == other,
+            _ => core::mem::discriminant(self) == core::mem::discriminant(other)
+        }
+    }
+
+    /// Returns this change with its new value taken from a later change to the same target.
+    ///
+    /// # Parameters
+    ///
+    /// * `later` - The later change to the same target.
+    fn followed_by(self, later: &Change) -> Change {
+        match (self, *later) {
+            (Change::Register { register, old, .. }, Change::Register { new, .. }) => Change::Register { register, old, new },
+            (Change::RegisterI { old, .. }, Change::RegisterI { new, .. }) => Change::RegisterI { old, new },
+            (Change::ProgramCounter { old, .. }, Change::ProgramCounter { new, .. }) => Change::ProgramCounter { old, new },
+            (Change::StackPointer { old, .. }, Change::StackPointer { new, .. }) => Change::StackPointer { old, new },
+            (Change::Stack { slot, old, .. }, Change::Stack { new, .. }) => Change::Stack { slot, old, new },
+            (Change::Memory { address, old, .. }, Change::Memory { new, .. }) => Change::Memory { address, old, new },
+            (Change::DelayTimer { old, .. }, Change::DelayTimer { new, .. }) => Change::DelayTimer { old, new },
+            (Change::SoundTimer { old, .. }, Change::SoundTimer { new, .. }) => Change::SoundTimer { old, new },
+            _ => self
+        }
+    }
+
+    /// Returns whether the change leaves the value as it was.
+    fn is_unchanged(&self) -> bool {
+        match *self {
+            Change::Register { old, new, .. } | Change::Memory { old, new, .. } | Change::DelayTimer { old, new } | Change::SoundTimer { old, new } => old == new,
+            Change::RegisterI { old, new } | Change::ProgramCounter { old, new } | Change::Stack { old, new, .. } => old == new,
+            Change::StackPointer { old, new } => old == new
+        }
+    }
+}
+
+impl Display for Change {
+    /// Formats the change as the target along with the old and new values, e.g. `V3: 00 -> 1F` or `[0x3F0]: 00 -> 01`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Change::Register { register, old, new } => write!(f, "V{register:X}: {old:02X} -> {new:02X}"),
+            Change::RegisterI { old, new } => write!(f, "I: 0x{old:03X} -> 0x{new:03X}"),
+            Change::ProgramCounter { old, new } => write!(f, "PC: 0x{old:03X} -> 0x{new:03X}"),
+            Change::StackPointer { old, new } => write!(f, "SP: {old} -> {new}"),
+            Change::Stack { slot, old, new } => write!(f, "Stack #{slot}: 0x{old:03X} -> 0x{new:03X}"),
+            Change::Memory { address, old, new } => write!(f, "[0x{address:03X}]: {old:02X} -> {new:02X}"),
+            Change::DelayTimer { old, new } => write!(f, "DT: {old} -> {new}"),
+            Change::SoundTimer { old, new } => write!(f, "ST: {old} -> {new}")
+        }
+    }
+}
+
+/// Stores a change along with when it happened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Event {
+    /// The frame the change happened on, counted from when the game was loaded.
+    pub frame: u64,
+    /// The address of the instruction which made the change, or `None` for the timers ticking at the end of a frame.
+    pub instruction: Option<u16>,
+    /// The change itself.
+    pub change: Change,
+    /// The number of the step (an instruction or a timer tick) which made the change, so that a step's changes can be undone together.
+    step: u64
+}
+
+/// Stores the parts of the interpreter's state which are compared before and after each instruction, as they are written from too many places to record each write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct CpuState {
+    pub(crate) registers: [u8; REGISTERS_SIZE],
+    pub(crate) register_i: u16,
+    pub(crate) program_counter: u16,
+    pub(crate) stack_pointer: usize,
+    pub(crate) stack: [u16; STACK_SIZE],
+    pub(crate) delay_timer: u8,
+    pub(crate) sound_timer: u8
+}
+
+impl CpuState {
+    /// Returns the changes between this state and a later one.
+    ///
+    /// # Parameters
+    ///
+    /// * `after` - The later state.
+    fn changes_to(&self, after: &CpuState) -> Vec<Change> {
+        let registers = self.registers.iter().zip(after.registers).enumerate()
+            .map(|(register, (old, new))| Change::Register { register, old: *old, new });
+        let stack = self.stack.iter().zip(after.stack).enumerate()
+            .map(|(slot, (old, new))| Change::Stack { slot, old: *old, new });
+        let others = [
+            Change::RegisterI { old: self.register_i, new: after.register_i },
+            Change::ProgramCounter { old: self.program_counter, new: after.program_counter },
+            Change::StackPointer { old: self.stack_pointer, new: after.stack_pointer },
+            Change::DelayTimer { old: self.delay_timer, new: after.delay_timer },
+            Change::SoundTimer { old: self.sound_timer, new: after.sound_timer }
+        ];
+
+        // The program counter is kept even when unchanged (e.g. a jump to itself), so that every instruction has an event to step back over
+        registers.chain(stack).chain(others).filter(|change| matches!(change, Change::ProgramCounter { .. }) || !change.is_unchanged()).collect()
+    }
+}
+
+/// Stores the events of the most recent frames, dropping older ones as new frames end.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventLog {
+    events: VecDeque<Event>,
+    window: u64,
+    frame: u64,
+    step: u64,
+    instruction: Option<u16>
+}
+
+impl EventLog {
+    /// Returns an empty log keeping the events of the provided number of frames.
+    ///
+    /// # Parameters
+    ///
+    /// * `window` - The number of most recent frames to keep the events of, at least 1.
+    #[must_use]
+    pub fn new(window: u64) -> EventLog {
+        EventLog { events: VecDeque::new(), window: window.max(1), frame: 0, step: 0, instruction: None }
+    }
+
+    /// Returns the number of frames the events are kept for.
+    #[must_use]
+    pub fn window(&self) -> u64 {
+        self.window
+    }
+
+    /// Returns the frame currently running, counted from when the game was loaded.
+    #[must_use]
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Returns every event kept, the oldest first.
+    pub fn events(&self) -> impl Iterator<Item = &Event> + '_ {
+        self.events.iter()
+    }
+
+    /// Returns the events which happened from the start of the first frame up to (but not including) the last frame, the oldest first.
+    ///
+    /// # Parameters
+    ///
+    /// * `from_frame` - The first frame to include.
+    /// * `to_frame` - The frame to stop before.
+    pub fn events_between(&self, from_frame: u64, to_frame: u64) -> impl Iterator<Item = &Event> + '_ {
+        self.events.iter().filter(move |event| (from_frame..to_frame).contains(&event.frame))
+    }
+
+    /// Returns the overall changes from the start of the first frame to the start of the last one, with writes to the same place combined and those which put the value back left out.
+    /// The changes are in the order their targets were first written, so e.g. `changes_between(100, 101)` answers what frame 100 changed.
+    ///
+    /// # Parameters
+    ///
+    /// * `from_frame` - The first frame to include.
+    /// * `to_frame` - The frame to stop before.
+    #[must_use]
+    pub fn changes_between(&self, from_frame: u64, to_frame: u64) -> Vec<Change> {
+        let mut changes: Vec<Change> = Vec::new();
+        for event in self.events_between(from_frame, to_frame) {
+            match changes.iter_mut().find(|change| change.has_same_target(&event.change)) {
+                Some(change) => *change = change.followed_by(&event.change),
+                None => changes.push(event.change)
+            }
+        }
+        changes.retain(|change| !change.is_unchanged());
+
+        changes
+    }
+
+    /// Discards every event and restarts the frame count, e.g. when a game is loaded.
+    pub fn clear(&mut self) {
+        self.events.clear();
+        self.frame = 0;
+        self.step = 0;
+        self.instruction = None;
+    }
+
+    /// Starts a new step for the instruction at the address, which the following changes are attributed to.
+    ///
+    /// # Parameters
+    ///
+    /// * `address` - The address of the instruction.
+    pub(crate) fn begin_instruction(&mut self, address: u16) {
+        self.step += 1;
+        self.instruction = Some(address);
+    }
+
+    /// Records a change made by the current step.
+    ///
+    /// # Parameters
+    ///
+    /// * `change` - The change made.
+    pub(crate) fn record(&mut self, change: Change) {
+        self.events.push_back(Event { frame: self.frame, instruction: self.instruction, change, step: self.step });
+    }
+
+    /// Records the changes made by the current instruction to the registers, stack, and timers.
+    ///
+    /// # Parameters
+    ///
+    /// * `before` - The state before the instruction ran.
+    /// * `after` - The state after the instruction ran.
+    pub(crate) fn record_instruction(&mut self, before: &CpuState, after: &CpuState) {
+        for change in before.changes_to(after) {
+            self.record(change);
+        }
+    }
+
+    /// Records the timers ticking outside of any instruction (e.g. at the end of a frame) as a step of its own.
+    ///
+    /// # Parameters
+    ///
+    /// * `delay_timer` - The old and new values of the delay timer.
+    /// * `sound_timer` - The old and new values of the sound timer.
+    pub(crate) fn record_timers(&mut self, delay_timer: (u8, u8), sound_timer: (u8, u8)) {
+        self.step += 1;
+        self.instruction = None;
+        for change in [Change::DelayTimer { old: delay_timer.0, new: delay_timer.1 }, Change::SoundTimer { old: sound_timer.0, new: sound_timer.1 }] {
+            if !change.is_unchanged() {
+                self.record(change);
+            }
+        }
+    }
+
+    /// Starts the next frame, dropping the events which fall out of the window.
+    pub(crate) fn end_frame(&mut self) {
+        self.frame += 1;
+        let oldest_frame = self.frame.saturating_sub(self.window);
+        while self.events.front().is_some_and(|event| event.frame < oldest_frame) {
+            self.events.pop_front();
+        }
+    }
+
+    /// Removes the changes of the most recent instruction, along with those of any timer ticks since, returning them the most recent first so that they can be undone in order.
+    /// Returns `None` without removing anything if no instruction is left in the window.
+    pub(crate) fn take_last_instruction(&mut self) -> Option<Vec<Change>> {
+        let position = self.events.iter().rposition(|event| event.instruction.is_some())?;
+        let step = self.events[position].step;
+        let first = self.events.iter().position(|event| event.step == step).unwrap_or(position);
+        let changes = self.events.drain(first..).rev().map(|event| event.change).collect();
+        self.instruction = None;
+
+        Some(changes)
+    }
+}
+
+impl Default for EventLog {
+    fn default() -> Self {
+        EventLog::new(DEFAULT_EVENT_WINDOW)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn combine_changes_between_frames() {
+        let mut log = EventLog::new(2);
+        log.begin_instruction(0x200);
+        log.record(Change::Register { register: 0x3, old: 0x00, new: 0x01 });
+        log.record(Change::Memory { address: 0x3F0, old: 0x00, new: 0x05 });
+        log.begin_instruction(0x202);
+        log.record(Change::Register { register: 0x3, old: 0x01, new: 0x1F });
+        log.record(Change::Memory { address: 0x3F0, old: 0x05, new: 0x00 });
+        log.record_timers((3, 2), (0, 0));
+        log.end_frame();
+
+        assert_eq!(log.changes_between(0, 1), [Change::Register { register: 0x3, old: 0x00, new: 0x1F }, Change::DelayTimer { old: 3, new: 2 }], "Changes not combined.");
+        assert_eq!(log.changes_between(0, 1)[0].to_string(), "V3: 00 -> 1F", "Change formatted incorrectly.");
+        assert_eq!(log.events_between(0, 1).filter(|event| event.instruction == Some(0x202)).count(), 2, "Events not attributed to their instruction.");
+
+        log.end_frame();
+        log.end_frame();
+        assert_eq!(log.frame(), 3, "Frames not counted.");
+        assert!(log.events().all(|event| event.frame >= 1), "Events outside the window kept.");
+    }
+
+    #[test]
+    fn take_last_instruction() {
+        let mut log = EventLog::default();
+        log.begin_instruction(0x200);
+        log.record(Change::Register { register: 0x0, old: 0x00, new: 0x01 });
+        log.begin_instruction(0x202);
+        log.record(Change::Register { register: 0x1, old: 0x00, new: 0x02 });
+        log.record(Change::ProgramCounter { old: 0x202, new: 0x204 });
+        log.record_timers((1, 0), (0, 0));
+        log.end_frame();
+
+        assert_eq!(log.take_last_instruction(), Some(vec![
+            Change::DelayTimer { old: 1, new: 0 },
+            Change::ProgramCounter { old: 0x202, new: 0x204 },
+            Change::Register { register: 0x1, old: 0x00, new: 0x02 }
+        ]), "Last instruction not taken along with the tick after it.");
+        assert_eq!(log.take_last_instruction(), Some(vec![Change::Register { register: 0x0, old: 0x00, new: 0x01 }]), "Earlier instruction not taken.");
+        assert_eq!(log.take_last_instruction(), None, "Instruction taken from an empty log.");
+    }
+}
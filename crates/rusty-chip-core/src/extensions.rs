@@ -0,0 +1,134 @@
+//! A module to recognize the opcodes of CHIP-8 extensions which are not supported, so that games written for them are reported as such rather than as containing an unrecognized opcode.
+//! Further extensions can be recognized by [registering](ExtensionRegistry::register) their opcodes.
+
+#[cfg(not(feature = "std"))]
+use crate::alloc_prelude::*;
+
+/// Matches the opcodes which are equal to the value once masked (e.g. a mask of `0xF0FF` and a value of `0xE0F2` matches `EXF2`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpcodePattern {
+    pub mask: u16,
+    pub value: u16
+}
+
+impl OpcodePattern {
+    /// Returns a pattern matching the opcodes which are equal to the value once masked.
+    ///
+    /// # Parameters
+    ///
+    /// * `mask` - The bits of the opcode which are checked.
+    /// * `value` - The value the checked bits must have.
+    #[must_use]
+    pub const fn new(mask: u16, value: u16) -> OpcodePattern {
+        OpcodePattern { mask, value }
+    }
+
+    /// Returns whether the opcode matches the pattern.
+    ///
+    /// # Parameters
+    ///
+    /// * `opcode` - The first two bytes of the instruction.
+    #[must_use]
+    pub fn matches(self, opcode: u16) -> bool {
+        opcode & self.mask == self.value
+    }
+}
+
+/// Stores an extension of the CHIP-8 along with the opcodes which identify it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Extension {
+    /// The name of the extension, as shown to the user.
+    pub name: &'static str,
+    /// The opcodes which only the extension uses.
+    pub opcodes: &'static [OpcodePattern]
+}
+
+/// The CHIP-8X extension of the COSMAC VIP, which adds colour and a second keypad.
+/// Its `BXYN` colour opcode is not included as it cannot be told apart from the `BNNN` jump.
+pub const CHIP_8X: Extension = Extension {
+    name: "CHIP-8X",
+    opcodes: &[
+        OpcodePattern::new(0xFFFF, 0x02A0),
+        OpcodePattern::new(0xF00F, 0x5001),
+        OpcodePattern::new(0xF0FF, 0xE0F2),
+        OpcodePattern::new(0xF0FF, 0xE0F5),
+        OpcodePattern::new(0xF0FF, 0xF0F8),
+        OpcodePattern::new(0xF0FF, 0xF0FB)
+    ]
+};
+
+/// The MegaChip extension, which adds a 256x192 colour display, sprites with alpha, and sampled sound.
+pub const MEGA_CHIP: Extension = Extension {
+    name: "MegaChip",
+    opcodes: &[
+        OpcodePattern::new(0xFFFF, 0x0010),
+        OpcodePattern::new(0xFFFF, 0x0011),
+        OpcodePattern::new(0xFFF0, 0x00B0),
+        OpcodePattern::new(0xFF00, 0x0100),
+        OpcodePattern::new(0xFF00, 0x0200),
+        OpcodePattern::new(0xFF00, 0x0300),
+        OpcodePattern::new(0xFF00, 0x0400),
+        OpcodePattern::new(0xFF00, 0x0500),
+        OpcodePattern::new(0xFFF0, 0x0600),
+        OpcodePattern::new(0xFFFF, 0x0700),
+        OpcodePattern::new(0xFFF0, 0x0800)
+    ]
+};
+
+/// Stores the unsupported extensions whose opcodes are recognized, checked in the order they were registered.
+#[derive(Debug, Clone)]
+pub struct ExtensionRegistry {
+    extensions: Vec<Extension>
+}
+
+impl ExtensionRegistry {
+    /// Returns a registry of the known extensions.
+    /// CHIP-8X is checked first, as its `02A0` opcode would otherwise be taken for a MegaChip palette load.
+    #[must_use]
+    pub fn new() -> ExtensionRegistry {
+        ExtensionRegistry { extensions: vec![CHIP_8X, MEGA_CHIP] }
+    }
+
+    /// Adds an extension to be recognized, checked after those already registered.
+    ///
+    /// # Parameters
+    ///
+    /// * `extension` - The extension to recognize.
+    pub fn register(&mut self, extension: Extension) {
+        self.extensions.push(extension);
+    }
+
+    /// Returns the first registered extension which uses the opcode, if any.
+    ///
+    /// # Parameters
+    ///
+    /// * `opcode` - The first two bytes of the instruction.
+    #[must_use]
+    pub fn find(&self, opcode: u16) -> Option<&Extension> {
+        self.extensions.iter().find(|extension| extension.opcodes.iter().any(|pattern| pattern.matches(opcode)))
+    }
+}
+
+impl Default for ExtensionRegistry {
+    fn default() -> Self {
+        ExtensionRegistry::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_extensions() {
+        let mut registry = ExtensionRegistry::new();
+        assert_eq!(registry.find(0x0011), Some(&MEGA_CHIP), "MegaChip opcode not recognized.");
+        assert_eq!(registry.find(0x02A0), Some(&CHIP_8X), "CHIP-8X opcode not recognized ahead of MegaChip.");
+        assert_eq!(registry.find(0xE3F2), Some(&CHIP_8X), "CHIP-8X opcode not recognized.");
+        assert_eq!(registry.find(0x00E0), None, "Supported opcode recognized as an extension.");
+
+        const CUSTOM: Extension = Extension { name: "Custom", opcodes: &[OpcodePattern::new(0xF0FF, 0xF0FF)] };
+        registry.register(CUSTOM);
+        assert_eq!(registry.find(0xF4FF).map(|extension| extension.name), Some("Custom"), "Registered extension not recognized.");
+    }
+}
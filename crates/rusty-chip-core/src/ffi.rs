@@ -0,0 +1,270 @@
+//! A module exposing the emulator through the C ABI, so that it can be embedded in C or C++ frontends and game engines.
+//! Only built with the `ffi` feature. The declarations are in `include/rusty_chip.h`, and the library is built with e.g. `cargo rustc --release -p rusty-chip-core --features ffi --crate-type staticlib` (or `cdylib` for a shared library).
+//!
+//! Every emulator is created with [`rusty_chip_create`] and must be freed with [`rusty_chip_destroy`]. The other functions take the pointer it returned, and do nothing (or return zero) when given a null pointer.
+//! An emulator must only be used from one thread at a time.
+
+use std::ptr;
+use std::slice;
+use std::time::Duration;
+
+use crate::emulator::{Emulator, EmulatorMode, DEFAULT_CYCLES_PER_FRAME};
+use crate::interpreter::Interpreter;
+use crate::quirks::{Platform, QuirkConfig};
+
+/// The platform code for the default quirks.
+pub const RUSTY_CHIP_PLATFORM_DEFAULT: u32 = 0;
+/// The platform code for the quirks of the COSMAC VIP.
+pub const RUSTY_CHIP_PLATFORM_COSMAC_VIP: u32 = 1;
+/// The platform code for the quirks of CHIP-48.
+pub const RUSTY_CHIP_PLATFORM_CHIP_48: u32 = 2;
+/// The platform code for the quirks of SUPER-CHIP 1.1.
+pub const RUSTY_CHIP_PLATFORM_SCHIP: u32 = 3;
+/// The platform code for the quirks of XO-CHIP.
+pub const RUSTY_CHIP_PLATFORM_XO_CHIP: u32 = 4;
+
+/// The number of bytes each pixel takes up in the frame buffer (red, green, blue, and alpha).
+const BYTES_PER_PIXEL: usize = 4;
+
+/// Stores an emulator along with the frame buffer handed to the caller. Opaque to C.
+pub struct RustyChip {
+    emulator: Box<Emulator>,
+    /// The display as RGBA pixels, kept alive until the next call which draws it.
+    frame: Vec<u8>
+}
+
+impl RustyChip {
+    /// Returns the display as RGBA pixels in the colours of the palette, row by row.
+    fn frame_buffer(&mut self) -> &[u8] {
+        let colours = &self.emulator.renderer().palette().colours;
+        let framebuffer = self.emulator.interpreter().framebuffer();
+        self.frame.clear();
+        self.frame.reserve(framebuffer.width() as usize * framebuffer.height() as usize * BYTES_PER_PIXEL);
+        for index in framebuffer.colour_indices() {
+            let colour = colours[index];
+            self.frame.extend_from_slice(&[colour.r, colour.g, colour.b, u8::MAX]);
+        }
+
+        &self.frame
+    }
+}
+
+/// Returns the quirks of the platform with the provided code, or `None` if the code is unknown.
+///
+/// # Parameters
+///
+/// * `platform` - One of the `RUSTY_CHIP_PLATFORM_` codes.
+fn platform_quirks(platform: u32) -> Option<QuirkConfig> {
+    match platform {
+        RUSTY_CHIP_PLATFORM_DEFAULT => Some(QuirkConfig::new()),
+        RUSTY_CHIP_PLATFORM_COSMAC_VIP => Some(Platform::CosmacVip.quirk_config()),
+        RUSTY_CHIP_PLATFORM_CHIP_48 => Some(Platform::Chip48.quirk_config()),
+        RUSTY_CHIP_PLATFORM_SCHIP => Some(Platform::Schip.quirk_config()),
+        RUSTY_CHIP_PLATFORM_XO_CHIP => Some(Platform::XoChip.quirk_config()),
+        _ => None
+    }
+}
+
+/// Creates an emulator with the quirks of the platform and no game, returning null if the platform code is unknown. Free it with [`rusty_chip_destroy`].
+///
+/// # Parameters
+///
+/// * `platform` - One of the `RUSTY_CHIP_PLATFORM_` codes.
+#[no_mangle]
+pub extern "C" fn rusty_chip_create(platform: u32) -> *mut RustyChip {
+    let Some(quirk_config) = platform_quirks(platform) else {
+        return ptr::null_mut();
+    };
+
+    // The emulator is boxed straight away, as it is too large to pass around the stack of the caller's thread
+    let emulator = match Interpreter::builder().quirks(quirk_config).build() {
+        Ok(interpreter) => Box::new(Emulator::new(interpreter, DEFAULT_CYCLES_PER_FRAME)),
+        Err(_) => return ptr::null_mut()
+    };
+
+    Box::into_raw(Box::new(RustyChip { emulator, frame: Vec::new() }))
+}
+
+/// Frees the emulator. Null is ignored.
+///
+/// # Safety
+///
+/// `chip` must be null or a pointer returned by [`rusty_chip_create`] which has not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn rusty_chip_destroy(chip: *mut RustyChip) {
+    if !chip.is_null() {
+        drop(Box::from_raw(chip));
+    }
+}
+
+/// Loads the game, which starts running from the next frame. Returns false if no game was given.
+///
+/// # Safety
+///
+/// `chip` must be null or a live pointer returned by [`rusty_chip_create`], and `data` must be null or point to `len` readable bytes. The bytes are copied, so they can be freed afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn rusty_chip_load(chip: *mut RustyChip, data: *const u8, len: usize) -> bool {
+    let Some(chip) = chip.as_mut() else {
+        return false;
+    };
+    if data.is_null() || len == 0 {
+        return false;
+    }
+
+    chip.emulator.queue_load(slice::from_raw_parts(data, len).to_vec());
+    true
+}
+
+/// Changes the number of instructions run in every frame, 10 unless changed.
+///
+/// # Safety
+///
+/// `chip` must be null or a live pointer returned by [`rusty_chip_create`].
+#[no_mangle]
+pub unsafe extern "C" fn rusty_chip_set_cycles_per_frame(chip: *mut RustyChip, cycles_per_frame: u32) {
+    if let Some(chip) = chip.as_mut() {
+        chip.emulator.set_cycles_per_frame(cycles_per_frame);
+    }
+}
+
+/// Runs a single frame of the game (its instructions, then its timers and display), for callers which pace the frames themselves at 60Hz.
+///
+/// # Safety
+///
+/// `chip` must be null or a live pointer returned by [`rusty_chip_create`].
+#[no_mangle]
+pub unsafe extern "C" fn rusty_chip_step_frame(chip: *mut RustyChip) {
+    if let Some(chip) = chip.as_mut() {
+        chip.emulator.step_frame();
+    }
+}
+
+/// Advances the game by the time elapsed, running as many frames as fit and carrying the rest over. Returns the number of frames run.
+///
+/// # Safety
+///
+/// `chip` must be null or a live pointer returned by [`rusty_chip_create`].
+#[no_mangle]
+pub unsafe extern "C" fn rusty_chip_advance(chip: *mut RustyChip, elapsed_ms: f64) -> u32 {
+    let Some(chip) = chip.as_mut() else {
+        return 0;
+    };
+
+    // Negative or absurd times are clamped rather than panicking
+    let elapsed = Duration::try_from_secs_f64(elapsed_ms / 1000.0).unwrap_or_default();
+    chip.emulator.advance(elapsed).frames
+}
+
+/// Presses the CHIP-8 key (`0x0` to `0xF`). Other keys are ignored.
+///
+/// # Safety
+///
+/// `chip` must be null or a live pointer returned by [`rusty_chip_create`].
+#[no_mangle]
+pub unsafe extern "C" fn rusty_chip_key_down(chip: *mut RustyChip, key: u8) {
+    if let Some(chip) = chip.as_mut() {
+        chip.emulator.press_key(key);
+    }
+}
+
+/// Releases the CHIP-8 key (`0x0` to `0xF`). Other keys are ignored.
+///
+/// # Safety
+///
+/// `chip` must be null or a live pointer returned by [`rusty_chip_create`].
+#[no_mangle]
+pub unsafe extern "C" fn rusty_chip_key_up(chip: *mut RustyChip, key: u8) {
+    if let Some(chip) = chip.as_mut() {
+        chip.emulator.release_key(key);
+    }
+}
+
+/// Draws the display into the emulator's frame buffer, returning a pointer to its RGBA pixels, row by row.
+/// The buffer holds [`rusty_chip_framebuffer_width`] by [`rusty_chip_framebuffer_height`] pixels, and stays valid until the next call to this function or [`rusty_chip_destroy`].
+///
+/// # Safety
+///
+/// `chip` must be null or a live pointer returned by [`rusty_chip_create`].
+#[no_mangle]
+pub unsafe extern "C" fn rusty_chip_framebuffer(chip: *mut RustyChip) -> *const u8 {
+    match chip.as_mut() {
+        Some(chip) => chip.frame_buffer().as_ptr(),
+        None => ptr::null()
+    }
+}
+
+/// Returns the width of the display in pixels, which changes with the resolution the game uses.
+///
+/// # Safety
+///
+/// `chip` must be null or a live pointer returned by [`rusty_chip_create`].
+#[no_mangle]
+pub unsafe extern "C" fn rusty_chip_framebuffer_width(chip: *const RustyChip) -> u32 {
+    chip.as_ref().map_or(0, |chip| chip.emulator.interpreter().display_width())
+}
+
+/// Returns the height of the display in pixels, which changes with the resolution the game uses.
+///
+/// # Safety
+///
+/// `chip` must be null or a live pointer returned by [`rusty_chip_create`].
+#[no_mangle]
+pub unsafe extern "C" fn rusty_chip_framebuffer_height(chip: *const RustyChip) -> u32 {
+    chip.as_ref().map_or(0, |chip| chip.emulator.interpreter().display_height())
+}
+
+/// Returns true while the game is making a sound, for callers playing their own tone.
+///
+/// # Safety
+///
+/// `chip` must be null or a live pointer returned by [`rusty_chip_create`].
+#[no_mangle]
+pub unsafe extern "C" fn rusty_chip_is_sounding(chip: *const RustyChip) -> bool {
+    chip.as_ref().is_some_and(|chip| chip.emulator.interpreter().sound_timer() > 0)
+}
+
+/// Returns true if the game has halted, either through an error or by exiting.
+///
+/// # Safety
+///
+/// `chip` must be null or a live pointer returned by [`rusty_chip_create`].
+#[no_mangle]
+pub unsafe extern "C" fn rusty_chip_is_halted(chip: *const RustyChip) -> bool {
+    chip.as_ref().is_some_and(|chip| matches!(chip.emulator.mode(), EmulatorMode::Halted(_)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_through_ffi() {
+        assert!(rusty_chip_create(99).is_null(), "Unknown platform accepted.");
+
+        let chip = rusty_chip_create(RUSTY_CHIP_PLATFORM_SCHIP);
+        assert!(!chip.is_null(), "Emulator not created.");
+        unsafe {
+            assert!(!rusty_chip_load(chip, ptr::null(), 0), "Missing game loaded.");
+
+            // Draws the "0" sprite at the origin, waits for a key into V1, then exits
+            let game = [0xA0, 0x00, 0xD0, 0x05, 0xF1, 0x0A, 0x00, 0xFD];
+            assert!(rusty_chip_load(chip, game.as_ptr(), game.len()), "Game not loaded.");
+            rusty_chip_step_frame(chip);
+            assert_eq!((rusty_chip_framebuffer_width(chip), rusty_chip_framebuffer_height(chip)), (64, 32), "Display size incorrect.");
+            let frame = slice::from_raw_parts(rusty_chip_framebuffer(chip), 64 * 32 * BYTES_PER_PIXEL);
+            let colours = (*chip).emulator.renderer().palette().colours;
+            assert_eq!(frame[..BYTES_PER_PIXEL], [colours[1].r, colours[1].g, colours[1].b, u8::MAX], "Drawn pixel not in the frame buffer.");
+
+            rusty_chip_key_down(chip, 0xA);
+            rusty_chip_key_up(chip, 0xA);
+            assert_eq!((*chip).emulator.interpreter().registers()[1], 0xA, "Key not passed on.");
+            assert!(!rusty_chip_is_halted(chip), "Halted before exiting.");
+            rusty_chip_step_frame(chip);
+            assert!(rusty_chip_is_halted(chip), "Exit not reported.");
+
+            rusty_chip_destroy(chip);
+            assert!(!rusty_chip_is_halted(ptr::null()), "Null emulator reported as halted.");
+            rusty_chip_destroy(ptr::null_mut());
+        }
+    }
+}
@@ -0,0 +1,221 @@
+//! A module to stamp the messages logged while a game runs with the frame and instruction cycle they happened on, so that the logs of several subsystems (e.g. input, drawing, and audio) can be lined up on a single timeline.
+//! Warnings are always logged, while the events of each subsystem (every key press, sprite drawn, and sound started or stopped) are only logged for the frames of a [range](FrameRange) set with [`set_frame_range`](set_frame_range), which limits the warnings to those frames as well.
+//! The stamp is kept per thread and updated by the [emulator](crate::emulator::Emulator) as it runs, so every message logged from the thread running a game carries it:
+//!
+//! ```text
+//! [frame 120 cycle 5316] input: Key 5 pressed
+//! [frame 120 cycle 5329] draw: Sprite of 5 rows from 0x2EA drawn at (12, 20) with a collision
+//! ```
+
+use core::fmt::{Display, Formatter};
+use core::str::FromStr;
+#[cfg(feature = "std")]
+use std::cell::Cell;
+
+#[cfg(not(feature = "std"))]
+use crate::alloc_prelude::*;
+
+#[cfg(feature = "std")]
+thread_local! {
+    static STAMP: Cell<LogStamp> = const { Cell::new(LogStamp { frame: 0, cycle: 0 }) };
+    static FRAME_RANGE: Cell<Option<FrameRange>> = const { Cell::new(None) };
+}
+
+/// Denotes the part of the emulator a message comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Subsystem {
+    Input,
+    Draw,
+    Audio,
+    Memory,
+    Storage,
+    Emulator
+}
+
+impl Display for Subsystem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let name = match self {
+            Subsystem::Input => "input",
+            Subsystem::Draw => "draw",
+            Subsystem::Audio => "audio",
+            Subsystem::Memory => "memory",
+            Subsystem::Storage => "storage",
+            Subsystem::Emulator => "emulator"
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+/// Stores the point of the run a message was logged at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LogStamp {
+    /// The number of frames run before the message, so a key pressed between frames is stamped with the frame it is first seen on.
+    pub frame: u64,
+    /// The number of instructions the game had run before the message.
+    pub cycle: u64
+}
+
+impl Display for LogStamp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "frame {} cycle {}", self.frame, self.cycle)
+    }
+}
+
+/// Stores the frames to log, written as `a..b` (from frame a up to but not including frame b), `a..`, or `..b`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameRange {
+    pub start: u64,
+    /// The first frame past the range, or `None` if it runs until the game stops.
+    pub end: Option<u64>
+}
+
+impl FrameRange {
+    /// Returns true if the frame is in the range.
+    ///
+    /// # Parameters
+    ///
+    /// * `frame` - The frame to check.
+    #[must_use]
+    pub fn contains(&self, frame: u64) -> bool {
+        frame >= self.start && self.end.is_none_or(|end| frame < end)
+    }
+}
+
+impl FromStr for FrameRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid_range = || format!("Invalid frame range \"{s}\", expected e.g. 120..180.");
+        let (start, end) = s.trim().split_once("..").ok_or_else(invalid_range)?;
+        let start = if start.is_empty() { 0 } else { start.parse().map_err(|_| invalid_range())? };
+        let end = if end.is_empty() { None } else { Some(end.parse().map_err(|_| invalid_range())?) };
+        if end.is_some_and(|end| end <= start) {
+            return Err(format!("The frame range {s} is empty."));
+        }
+
+        Ok(FrameRange { start, end })
+    }
+}
+
+/// Sets the frames to log on this thread, logging the events of every subsystem during them and only the warnings from them. `None` logs every warning and no events.
+///
+/// # Parameters
+///
+/// * `frame_range` - The frames to log.
+#[cfg(feature = "std")]
+pub fn set_frame_range(frame_range: Option<FrameRange>) {
+    FRAME_RANGE.with(|range| range.set(frame_range));
+}
+
+/// Returns the frames being logged on this thread, if limited to a range.
+#[cfg(feature = "std")]
+#[must_use]
+pub fn frame_range() -> Option<FrameRange> {
+    FRAME_RANGE.with(Cell::get)
+}
+
+/// Updates the stamp of the messages logged on this thread from now on.
+///
+/// # Parameters
+///
+/// * `frame` - The number of frames run so far.
+/// * `cycle` - The number of instructions run so far.
+#[cfg(feature = "std")]
+pub(crate) fn set_stamp(frame: u64, cycle: u64) {
+    STAMP.with(|stamp| stamp.set(LogStamp { frame, cycle }));
+}
+
+/// Returns the stamp of the messages logged on this thread.
+#[cfg(feature = "std")]
+#[must_use]
+pub fn stamp() -> LogStamp {
+    STAMP.with(Cell::get)
+}
+
+/// Returns a message as it is logged, e.g. `[frame 120 cycle 5316] input: Key 5 pressed`.
+///
+/// # Parameters
+///
+/// * `stamp` - The point of the run the message was logged at.
+/// * `subsystem` - The part of the emulator the message comes from.
+/// * `message` - The message.
+#[must_use]
+pub fn format_line(stamp: LogStamp, subsystem: Subsystem, message: impl Display) -> String {
+    format!("[{stamp}] {subsystem}: {message}")
+}
+
+/// Logs a warning to stderr, unless a range is set which the current frame is outside of.
+///
+/// # Parameters
+///
+/// * `subsystem` - The part of the emulator the warning comes from.
+/// * `message` - The warning.
+#[cfg(feature = "std")]
+pub fn warn(subsystem: Subsystem, message: impl Display) {
+    let stamp = stamp();
+    if frame_range().is_none_or(|range| range.contains(stamp.frame)) {
+        eprintln!("{}", format_line(stamp, subsystem, message));
+    }
+}
+
+/// Logs an event to stderr if the current frame is inside the range being logged. The message is only formatted if it is logged.
+///
+/// # Parameters
+///
+/// * `subsystem` - The part of the emulator the event comes from.
+/// * `message` - The event.
+#[cfg(feature = "std")]
+pub fn event(subsystem: Subsystem, message: impl Display) {
+    let stamp = stamp();
+    if frame_range().is_some_and(|range| range.contains(stamp.frame)) {
+        eprintln!("{}", format_line(stamp, subsystem, message));
+    }
+}
+
+/// Drops the warning, as there is nowhere to log it without `std`.
+///
+/// # Parameters
+///
+/// * `subsystem` - The part of the emulator the warning comes from.
+/// * `message` - The warning.
+#[cfg(not(feature = "std"))]
+pub fn warn(_subsystem: Subsystem, _message: impl Display) {}
+
+/// Drops the event, as there is nowhere to log it without `std`.
+///
+/// # Parameters
+///
+/// * `subsystem` - The part of the emulator the event comes from.
+/// * `message` - The event.
+#[cfg(not(feature = "std"))]
+pub fn event(_subsystem: Subsystem, _message: impl Display) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_frame_ranges() {
+        assert_eq!("120..180".parse(), Ok(FrameRange { start: 120, end: Some(180) }), "Range parsed incorrectly.");
+        assert_eq!("600..".parse(), Ok(FrameRange { start: 600, end: None }), "Open range parsed incorrectly.");
+        assert_eq!("..60".parse(), Ok(FrameRange { start: 0, end: Some(60) }), "Range from the start parsed incorrectly.");
+        assert!("180..120".parse::<FrameRange>().is_err(), "Empty range parsed.");
+        assert!("120".parse::<FrameRange>().is_err(), "Single frame parsed.");
+
+        let range = FrameRange { start: 120, end: Some(180) };
+        assert!(range.contains(120) && range.contains(179), "Frames in the range excluded.");
+        assert!(!range.contains(119) && !range.contains(180), "Frames outside the range included.");
+    }
+
+    #[test]
+    fn stamp_messages() {
+        set_stamp(120, 5316);
+        assert_eq!(stamp(), LogStamp { frame: 120, cycle: 5316 }, "Stamp not updated.");
+        assert_eq!(format_line(stamp(), Subsystem::Input, "Key 5 pressed"), "[frame 120 cycle 5316] input: Key 5 pressed", "Message stamped incorrectly.");
+
+        set_frame_range(Some("..60".parse().unwrap()));
+        assert_eq!(frame_range(), Some(FrameRange { start: 0, end: Some(60) }), "Frame range not updated.");
+        set_frame_range(None);
+    }
+}
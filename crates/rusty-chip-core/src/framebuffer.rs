@@ -0,0 +1,215 @@
+//! A module holding a read-only view of the display, for embedders rendering games with their own graphics stack.
+//! The [view](Framebuffer) is taken with [`Interpreter::framebuffer`](crate::interpreter::Interpreter::framebuffer), and [`Interpreter::take_display_changed`](crate::interpreter::Interpreter::take_display_changed) says when it is worth drawing again:
+//!
+//! ```
+//! use rusty_chip_core::interpreter::Interpreter;
+//! use rusty_chip_core::palette::{Colour, Palette};
+//!
+//! fn render(interpreter: &mut Interpreter, palette: &Palette, pixels: &mut Vec<Colour>) {
+//!     if !interpreter.take_display_changed() {
+//!         return;
+//!     }
+//!
+//!     let colours = palette.colours;
+//!     pixels.clear();
+//!     pixels.extend(interpreter.framebuffer().colour_indices().map(|index| colours[index]));
+//! }
+//! ```
+
+use core::ops::Index;
+
+use crate::interpreter::DRAWING_BUFFER_SIZE;
+
+/// The number of pixels stored in each word of a [`PlaneBuffer`].
+const PIXELS_PER_WORD: usize = u64::BITS as usize;
+
+/// Stores the pixels of a display plane as one bit each, row by row with the first pixel of each word in its lowest bit.  
+/// The buffer is sized for the largest display, so only the start of it is used outside of high resolution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
+pub(crate) struct PlaneBuffer {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::array"))]
+    words: [u64; DRAWING_BUFFER_SIZE / PIXELS_PER_WORD]
+}
+
+impl PlaneBuffer {
+    /// Returns a buffer with every pixel off.
+    pub(crate) const fn new() -> PlaneBuffer {
+        PlaneBuffer { words: [0; DRAWING_BUFFER_SIZE / PIXELS_PER_WORD] }
+    }
+
+    /// Returns true if the pixel is on.
+    ///
+    /// # Parameters
+    ///
+    /// * `index` - The index of the pixel, which must be below [`DRAWING_BUFFER_SIZE`](DRAWING_BUFFER_SIZE).
+    pub(crate) fn get(&self, index: usize) -> bool {
+        self.words[index / PIXELS_PER_WORD] & (1 << (index % PIXELS_PER_WORD)) != 0
+    }
+
+    /// Turns the pixel on or off.
+    ///
+    /// # Parameters
+    ///
+    /// * `index` - The index of the pixel, which must be below [`DRAWING_BUFFER_SIZE`](DRAWING_BUFFER_SIZE).
+    /// * `is_on` - True to turn the pixel on, false to turn it off.
+    pub(crate) fn set(&mut self, index: usize, is_on: bool) {
+        let mask = 1 << (index % PIXELS_PER_WORD);
+        let word = &mut self.words[index / PIXELS_PER_WORD];
+        if is_on {
+            *word |= mask;
+        } else {
+            *word &= !mask;
+        }
+    }
+
+    /// Turns every pixel on or off.
+    ///
+    /// # Parameters
+    ///
+    /// * `is_on` - True to turn the pixels on, false to turn them off.
+    pub(crate) fn fill(&mut self, is_on: bool) {
+        self.words.fill(if is_on { u64::MAX } else { 0 });
+    }
+
+    /// Returns every pixel of the buffer in order, with `true` for pixels which are on.
+    pub(crate) fn iter(&self) -> impl ExactSizeIterator<Item = bool> + '_ {
+        (0..DRAWING_BUFFER_SIZE).map(|index| self.get(index))
+    }
+}
+
+impl Default for PlaneBuffer {
+    fn default() -> PlaneBuffer {
+        PlaneBuffer::new()
+    }
+}
+
+impl Index<usize> for PlaneBuffer {
+    type Output = bool;
+
+    /// Returns whether the pixel is on, so that the buffer reads like an array of pixels.
+    fn index(&self, index: usize) -> &bool {
+        if self.get(index) { &true } else { &false }
+    }
+}
+
+/// Borrows the pixels of the display along with its size, which depends on the resolution the game is in.
+/// Pixels are stored row by row, with `true` for pixels which are on. XO-CHIP games draw to two planes, which are combined into an index into the [palette's colours](crate::palette::Palette::colours).
+#[derive(Debug, Clone, Copy)]
+pub struct Framebuffer<'a> {
+    width: u32,
+    height: u32,
+    first_plane: &'a PlaneBuffer,
+    second_plane: &'a PlaneBuffer
+}
+
+impl<'a> Framebuffer<'a> {
+    /// Returns a view of the display.
+    ///
+    /// # Parameters
+    ///
+    /// * `width` - The width of the display in pixels.
+    /// * `height` - The height of the display in pixels.
+    /// * `first_plane` - The pixels of the first plane, of which the first `width * height` are shown.
+    /// * `second_plane` - The pixels of the second XO-CHIP plane, laid out as the first.
+    pub(crate) fn new(width: u32, height: u32, first_plane: &'a PlaneBuffer, second_plane: &'a PlaneBuffer) -> Framebuffer<'a> {
+        Framebuffer { width, height, first_plane, second_plane }
+    }
+
+    /// Returns the width of the display in pixels: 128 in high resolution, 64 otherwise.
+    #[must_use]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height of the display in pixels: 64 in high resolution or on the HiRes CHIP-8 two page display, 32 otherwise.
+    #[must_use]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the pixels of the first plane row by row, which is the whole display for games which are not XO-CHIP.
+    pub fn pixels(&self) -> impl ExactSizeIterator<Item = bool> + 'a {
+        let plane = self.first_plane;
+        (0..self.pixel_count()).map(move |index| plane.get(index))
+    }
+
+    /// Returns the pixels of the second XO-CHIP plane row by row.
+    pub fn second_plane(&self) -> impl ExactSizeIterator<Item = bool> + 'a {
+        let plane = self.second_plane;
+        (0..self.pixel_count()).map(move |index| plane.get(index))
+    }
+
+    /// Returns the index into the [palette's colours](crate::palette::Palette::colours) of a pixel: bit 0 is set if it is on in the first plane and bit 1 if it is on in the second.
+    /// Pixels outside the display are given the background.
+    ///
+    /// # Parameters
+    ///
+    /// * `x` - The column of the pixel.
+    /// * `y` - The row of the pixel.
+    #[must_use]
+    pub fn colour_index(&self, x: u32, y: u32) -> usize {
+        if x >= self.width || y >= self.height {
+            return crate::palette::BACKGROUND;
+        }
+
+        let index = (y * self.width + x) as usize;
+        usize::from(self.first_plane.get(index)) | (usize::from(self.second_plane.get(index)) << 1)
+    }
+
+    /// Returns the [colour index](Framebuffer::colour_index) of every pixel, row by row.
+    pub fn colour_indices(&self) -> impl Iterator<Item = usize> + 'a {
+        self.pixels().zip(self.second_plane()).map(|(first, second)| usize::from(first) | (usize::from(second) << 1))
+    }
+
+    /// Returns the number of pixels on the display.
+    fn pixel_count(&self) -> usize {
+        (self.width * self.height) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a plane with the provided pixels at its start and every other pixel off.
+    ///
+    /// # Parameters
+    ///
+    /// * `pixels` - The first pixels of the plane.
+    fn plane(pixels: &[bool]) -> PlaneBuffer {
+        let mut plane = PlaneBuffer::new();
+        for (index, is_on) in pixels.iter().enumerate() {
+            plane.set(index, *is_on);
+        }
+
+        plane
+    }
+
+    #[test]
+    fn plane_buffer() {
+        let mut plane = PlaneBuffer::new();
+        plane.set(0, true);
+        plane.set(PIXELS_PER_WORD + 1, true);
+        plane.set(DRAWING_BUFFER_SIZE - 1, true);
+        assert!(plane[0] && plane[PIXELS_PER_WORD + 1] && plane[DRAWING_BUFFER_SIZE - 1], "Pixels not turned on.");
+        assert_eq!(plane.iter().filter(|is_on| *is_on).count(), 3, "Other pixels turned on.");
+
+        plane.set(PIXELS_PER_WORD + 1, false);
+        assert!(!plane[PIXELS_PER_WORD + 1], "Pixel not turned off.");
+        plane.fill(true);
+        assert!(plane.iter().all(|is_on| is_on), "Plane not filled.");
+        plane.fill(false);
+        assert_eq!(plane, PlaneBuffer::new(), "Plane not cleared.");
+    }
+
+    #[test]
+    fn colour_indices() {
+        let first_plane = plane(&[true, false, true, false]);
+        let second_plane = plane(&[false, false, true, true]);
+        let framebuffer = Framebuffer::new(2, 2, &first_plane, &second_plane);
+        assert_eq!(framebuffer.colour_indices().collect::<Vec<usize>>(), [1, 0, 3, 2], "Planes combined incorrectly.");
+        assert_eq!(framebuffer.colour_index(0, 1), 3, "Pixel read from the wrong row.");
+        assert_eq!(framebuffer.colour_index(2, 0), 0, "Pixel outside the display not given the background.");
+    }
+}
@@ -0,0 +1,381 @@
+//! A module to fuzz games with random key inputs, looking for faults in the interpreter rather than bugs in the game.
+//! Every run is deterministic: the game's random numbers are seeded and the inputs are recorded as an [`InputScript`](InputScript), so any fault can be replayed exactly.  
+//! Runs which reach instructions no earlier run has are kept, and later runs often continue from one of them with new inputs, steering the session towards unexplored code.
+
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::panic::{self, AssertUnwindSafe};
+use std::str::FromStr;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::coverage::Coverage;
+use crate::emulator::{Emulator, EmulatorMode};
+use crate::interpreter::{HaltReason, Interpreter};
+use crate::quirks::QuirkConfig;
+
+/// The chance of a key being pressed or released on any given frame of a fuzzing run.
+const KEY_EVENT_CHANCE: f64 = 0.25;
+
+/// The chance of a fuzzing run continuing from a run which found new instructions, rather than starting from scratch.
+const MUTATION_CHANCE: f64 = 0.5;
+
+/// Stores a single key being pressed or released.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InputEvent {
+    /// The frame before which the event happens.
+    pub frame: u64,
+    /// The CHIP-8 key (`0x0` to `0xF`).
+    pub key: u8,
+    /// True if the key is pressed, false if it is released.
+    pub pressed: bool
+}
+
+/// Stores everything needed to replay a run of a game: the seed of its random numbers, how many frames it ran for, and the key inputs in frame order.
+///
+/// Scripts are saved as text, with one entry per line and `#` starting a comment:
+///
+/// ```text
+/// seed 1234
+/// frames 600
+/// 12 press 5
+/// 20 release 5
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputScript {
+    pub seed: u64,
+    pub frames: u64,
+    pub events: Vec<InputEvent>
+}
+
+impl InputScript {
+    /// Returns a script of random key inputs.
+    ///
+    /// # Parameters
+    ///
+    /// * `rng` - The generator to draw the seed and inputs from.
+    /// * `frames` - The number of frames to generate inputs for.
+    pub fn random(rng: &mut impl Rng, frames: u64) -> InputScript {
+        let events = random_events(rng, 0, frames, HashSet::new());
+
+        InputScript { seed: rng.gen(), frames, events }
+    }
+
+    /// Returns a copy of the script which keeps its inputs up to a random frame, followed by random inputs from there on.  
+    /// The seed is kept as well, so the copy plays out exactly as the original until their inputs differ.
+    ///
+    /// # Parameters
+    ///
+    /// * `rng` - The generator to draw the frame and inputs from.
+    /// * `frames` - The number of frames to generate inputs for.
+    pub fn mutate(&self, rng: &mut impl Rng, frames: u64) -> InputScript {
+        let kept_frames = rng.gen_range(0..=self.frames.min(frames));
+        let mut events: Vec<InputEvent> = self.events.iter().copied().filter(|event| event.frame < kept_frames).collect();
+        let mut held = HashSet::new();
+        for event in &events {
+            if event.pressed {
+                held.insert(event.key);
+            } else {
+                held.remove(&event.key);
+            }
+        }
+        events.extend(random_events(rng, kept_frames, frames, held));
+
+        InputScript { seed: self.seed, frames, events }
+    }
+}
+
+/// Returns random key inputs for a range of frames, only releasing keys which are held and pressing keys which are not.
+///
+/// # Parameters
+///
+/// * `rng` - The generator to draw the inputs from.
+/// * `start` - The first frame to generate inputs for.
+/// * `end` - The frame to stop before.
+/// * `held` - The keys held at the start.
+fn random_events(rng: &mut impl Rng, start: u64, end: u64, mut held: HashSet<u8>) -> Vec<InputEvent> {
+    let mut events = Vec::new();
+    for frame in start..end {
+        if rng.gen_bool(KEY_EVENT_CHANCE) {
+            let key = rng.gen_range(0..0x10);
+            let pressed = held.insert(key);
+            if !pressed {
+                held.remove(&key);
+            }
+            events.push(InputEvent { frame, key, pressed });
+        }
+    }
+
+    events
+}
+
+impl Display for InputScript {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "seed {}", self.seed)?;
+        writeln!(f, "frames {}", self.frames)?;
+        for event in &self.events {
+            writeln!(f, "{} {} {:X}", event.frame, if event.pressed { "press" } else { "release" }, event.key)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for InputScript {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut script = InputScript { seed: 0, frames: 0, events: Vec::new() };
+        for (number, line) in s.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let invalid_line = || format!("Invalid input script line {}: \"{line}\".", number + 1);
+            match parts.as_slice() {
+                [] => {},
+                ["seed", seed] => script.seed = seed.parse().map_err(|_| invalid_line())?,
+                ["frames", frames] => script.frames = frames.parse().map_err(|_| invalid_line())?,
+                [frame, action @ ("press" | "release"), key] => {
+                    let frame = frame.parse().map_err(|_| invalid_line())?;
+                    let key = u8::from_str_radix(key, 16).ok().filter(|key| *key < 0x10).ok_or_else(invalid_line)?;
+                    script.events.push(InputEvent { frame, key, pressed: *action == "press" });
+                },
+                _ => return Err(invalid_line())
+            }
+        }
+
+        if script.events.windows(2).any(|pair| pair[0].frame > pair[1].frame) {
+            return Err(String::from("Input script events are not in frame order."));
+        }
+
+        Ok(script)
+    }
+}
+
+/// Denotes a fault of the interpreter found while fuzzing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fault {
+    /// The game halted with an [error](HaltReason::is_error).
+    Halted(HaltReason),
+    /// The interpreter panicked, with the provided message.
+    Panicked(String)
+}
+
+impl Display for Fault {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Fault::Halted(reason) => write!(f, "{reason}"),
+            Fault::Panicked(message) => write!(f, "The interpreter panicked: {message}")
+        }
+    }
+}
+
+/// Stores the settings of a fuzzing session.
+#[derive(Debug, Clone)]
+pub struct FuzzConfig {
+    /// The number of runs, each with its own random inputs.
+    pub runs: u32,
+    /// The most frames a single run lasts.
+    pub frames: u64,
+    /// The seed from which every run's inputs are generated.
+    pub seed: u64,
+    pub cycles_per_frame: u32,
+    pub quirk_config: QuirkConfig
+}
+
+/// Stores a fault found while fuzzing along with the script which reproduces it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzFault {
+    /// The number of the run, starting from 0.
+    pub run: u32,
+    /// The frame during which the fault happened.
+    pub frame: u64,
+    pub fault: Fault,
+    /// The inputs up to and including the faulting frame.
+    pub script: InputScript
+}
+
+/// Stores the results of a fuzzing session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzReport {
+    /// Every fault found, in run order.
+    pub faults: Vec<FuzzFault>,
+    /// The instructions reached by any run.
+    pub coverage: Coverage
+}
+
+/// Replays the script against the game without any display or audio.  
+/// Returns the frame during which the interpreter faulted along with the fault, or `None` if the script ran to the end (or the game exited) without one.
+///
+/// # Parameters
+///
+/// * `game_data` - The game to run.
+/// * `script` - The inputs to replay.
+/// * `cycles_per_frame` - The number of instruction cycles run per frame.
+/// * `quirk_config` - The enabled/disabled status of all the quirks.
+#[must_use]
+pub fn replay(game_data: &[u8], script: &InputScript, cycles_per_frame: u32, quirk_config: QuirkConfig) -> Option<(u64, Fault)> {
+    replay_with_coverage(game_data, script, cycles_per_frame, quirk_config).0
+}
+
+/// Replays the script as [`replay`](replay) does, also returning the instructions which ran.
+///
+/// # Parameters
+///
+/// * `game_data` - The game to run.
+/// * `script` - The inputs to replay.
+/// * `cycles_per_frame` - The number of instruction cycles run per frame.
+/// * `quirk_config` - The enabled/disabled status of all the quirks.
+#[must_use]
+pub fn replay_with_coverage(game_data: &[u8], script: &InputScript, cycles_per_frame: u32, quirk_config: QuirkConfig) -> (Option<(u64, Fault)>, Coverage) {
+    let mut emulator = Emulator::new(Interpreter::new_headless(quirk_config), cycles_per_frame);
+    emulator.interpreter_mut().seed_random(script.seed);
+    emulator.interpreter_mut().set_coverage_enabled(true);
+    emulator.queue_load(game_data.to_vec());
+    emulator.apply_pending_load();
+    let fault = run_script(&mut emulator, script);
+    let coverage = emulator.interpreter().coverage().cloned().unwrap_or_default();
+
+    (fault, coverage)
+}
+
+/// Runs the script against the loaded game, returning the frame during which the interpreter faulted along with the fault.
+///
+/// # Parameters
+///
+/// * `emulator` - The emulator with the game loaded.
+/// * `script` - The inputs to replay.
+fn run_script(emulator: &mut Emulator, script: &InputScript) -> Option<(u64, Fault)> {
+    let mut events = script.events.iter().peekable();
+    for frame in 0..script.frames {
+        while let Some(event) = events.next_if(|event| event.frame <= frame) {
+            if event.pressed {
+                emulator.interpreter_mut().press_key(event.key);
+            } else {
+                emulator.interpreter_mut().release_key(event.key);
+            }
+        }
+
+        if let Err(fault) = step_frame_catching_panics(emulator) {
+            return Some((frame, fault));
+        }
+
+        if let EmulatorMode::Halted(reason) = emulator.mode() {
+            return reason.is_error().then_some((frame, Fault::Halted(reason)));
+        }
+    }
+
+    None
+}
+
+/// Runs a single frame, returning the panic as a fault if the interpreter panicked.
+///
+/// # Parameters
+///
+/// * `emulator` - The emulator with the game loaded.
+///
+/// # Errors
+///
+/// Returns an `Err` with the [`Panicked`](Fault::Panicked) fault if the interpreter panicked.
+pub(crate) fn step_frame_catching_panics(emulator: &mut Emulator) -> Result<(), Fault> {
+    panic::catch_unwind(AssertUnwindSafe(|| emulator.step_frame())).map_err(|payload| {
+        let message = payload.downcast_ref::<&str>().map(ToString::to_string)
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| String::from("Unknown panic."));
+        Fault::Panicked(message)
+    })
+}
+
+/// Fuzzes the game with random inputs, returning every fault found along with the instructions reached.  
+/// The same configuration always produces the same runs, so a session can be repeated by reusing its seed.
+///
+/// # Parameters
+///
+/// * `game_data` - The game to fuzz.
+/// * `config` - The settings of the session.
+#[must_use]
+pub fn fuzz(game_data: &[u8], config: &FuzzConfig) -> FuzzReport {
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let mut faults = Vec::new();
+    let mut coverage = Coverage::new();
+    let mut corpus: Vec<InputScript> = Vec::new();
+    for run in 0..config.runs {
+        let mut script = if !corpus.is_empty() && rng.gen_bool(MUTATION_CHANCE) {
+            corpus[rng.gen_range(0..corpus.len())].mutate(&mut rng, config.frames)
+        } else {
+            InputScript::random(&mut rng, config.frames)
+        };
+
+        let (fault, run_coverage) = replay_with_coverage(game_data, &script, config.cycles_per_frame, config.quirk_config.clone());
+        if coverage.merge(&run_coverage) > 0 {
+            corpus.push(script.clone());
+        }
+        if let Some((frame, fault)) = fault {
+            script.frames = frame + 1;
+            script.events.retain(|event| event.frame <= frame);
+            faults.push(FuzzFault { run, frame, fault, script });
+        }
+    }
+
+    FuzzReport { faults, coverage }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Waits for a key and, if it is key 5, calls a subroutine which recurses until the stack overflows.
+    const FRAGILE_GAME: [u8; 10] = [0xF0, 0x0A, 0x30, 0x05, 0x12, 0x00, 0x22, 0x08, 0x22, 0x08];
+
+    #[test]
+    fn script_text_round_trip() {
+        let script = InputScript {
+            seed: 42,
+            frames: 30,
+            events: vec![InputEvent { frame: 3, key: 0xA, pressed: true }, InputEvent { frame: 9, key: 0xA, pressed: false }]
+        };
+
+        assert_eq!(script.to_string(), "seed 42\nframes 30\n3 press A\n9 release A\n", "Script written incorrectly.");
+        assert_eq!(script.to_string().parse(), Ok(script), "Script read incorrectly.");
+        assert!("# Comment\n\nseed 1".parse::<InputScript>().is_ok(), "Comments and blank lines not ignored.");
+        assert!("3 press 10".parse::<InputScript>().is_err(), "Invalid key read.");
+        assert!("9 press 1\n3 release 1".parse::<InputScript>().is_err(), "Out of order events read.");
+    }
+
+    #[test]
+    fn mutate_keeps_prefix() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let script = InputScript::random(&mut rng, 100);
+        for _ in 0..20 {
+            let mutated = script.mutate(&mut rng, 100);
+            let kept = mutated.events.iter().zip(&script.events).take_while(|(mutated, original)| mutated == original).count();
+
+            assert_eq!(mutated.seed, script.seed, "Seed not kept.");
+            assert!(mutated.to_string().parse::<InputScript>().is_ok(), "Mutated script invalid.");
+            assert!(mutated.events[kept..].iter().all(|event| kept == 0 || event.frame > script.events[kept - 1].frame), "Mutated events not after the prefix.");
+        }
+    }
+
+    #[test]
+    fn replay_finds_fault() {
+        let event = |frame, key, pressed| InputEvent { frame, key, pressed };
+        let harmless = InputScript { seed: 0, frames: 10, events: vec![event(1, 0x3, true), event(2, 0x3, false)] };
+        let fatal = InputScript { seed: 0, frames: 10, events: vec![event(1, 0x5, true), event(2, 0x5, false)] };
+
+        assert_eq!(replay(&FRAGILE_GAME, &harmless, 10, QuirkConfig::new()), None, "Fault found in a harmless script.");
+        assert_eq!(replay(&FRAGILE_GAME, &fatal, 10, QuirkConfig::new()), Some((3, Fault::Halted(HaltReason::StackOverflow { address: 0x208 }))), "Fault not found.");
+    }
+
+    #[test]
+    fn fuzz_is_reproducible() {
+        let config = FuzzConfig { runs: 20, frames: 60, seed: 7, cycles_per_frame: 10, quirk_config: QuirkConfig::new() };
+        let report = fuzz(&FRAGILE_GAME, &config);
+
+        assert!(!report.faults.is_empty(), "No faults found.");
+        assert!(report.coverage.contains(0x208), "Coverage of the faulting subroutine not reported.");
+        assert_eq!(fuzz(&FRAGILE_GAME, &config), report, "Fuzzing not reproducible.");
+        for fault in &report.faults {
+            assert_eq!(replay(&FRAGILE_GAME, &fault.script, 10, QuirkConfig::new()), Some((fault.frame, fault.fault.clone())), "Saved script does not reproduce the fault.");
+        }
+    }
+}
@@ -0,0 +1,167 @@
+//! A module to graph watch expressions over the recent frames as sparklines, for studying how a game's speed and physics variables change while it is played.
+//! The graphs are drawn as a panel of text, redrawn in place in the terminal the emulator was started from while the game runs.
+
+use std::collections::VecDeque;
+use std::fmt::Write;
+
+use crate::interpreter::Interpreter;
+use crate::watch::WatchExpression;
+
+/// The number of frames each graph covers by default, a second of play.
+pub const DEFAULT_GRAPH_WIDTH: usize = 60;
+
+/// The number of frames between redraws of the panel, so that the terminal is not flooded.
+pub const GRAPH_REFRESH_FRAMES: u64 = 6;
+
+/// The characters of a sparkline, from the lowest value to the highest.
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Stores the values of a watch expression over the recent frames.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Graph {
+    expression: WatchExpression,
+    history: VecDeque<Option<i64>>,
+    width: usize
+}
+
+impl Graph {
+    /// Returns an empty graph of the expression.
+    ///
+    /// # Parameters
+    ///
+    /// * `expression` - The expression to graph.
+    /// * `width` - The number of frames to keep, at least 1.
+    #[must_use]
+    pub fn new(expression: WatchExpression, width: usize) -> Graph {
+        let width = width.max(1);
+        Graph { expression, history: VecDeque::with_capacity(width), width }
+    }
+
+    /// Returns the expression being graphed.
+    #[must_use]
+    pub fn expression(&self) -> &WatchExpression {
+        &self.expression
+    }
+
+    /// Returns the value of the expression at each of the recent frames, the oldest first, with `None` for the frames it could not be evaluated in.
+    #[must_use]
+    pub fn history(&self) -> &VecDeque<Option<i64>> {
+        &self.history
+    }
+
+    /// Evaluates the expression for the frame which just ran, dropping the oldest value once the graph is full.
+    ///
+    /// # Parameters
+    ///
+    /// * `interpreter` - The interpreter to read from.
+    pub fn record(&mut self, interpreter: &Interpreter) {
+        if self.history.len() == self.width {
+            self.history.pop_front();
+        }
+        self.history.push_back(self.expression.evaluate(interpreter));
+    }
+
+    /// Returns the lowest and highest values in the graph, or `None` if it has none.
+    #[must_use]
+    pub fn range(&self) -> Option<(i64, i64)> {
+        let values = self.history.iter().flatten();
+        Some((*values.clone().min()?, *values.max()?))
+    }
+
+    /// Returns the graph as a sparkline with a character per frame, scaled between its lowest and highest values.
+    /// Frames the expression could not be evaluated in are left blank.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn sparkline(&self) -> String {
+        let Some((min, max)) = self.range() else {
+            return " ".repeat(self.history.len());
+        };
+
+        let span = i128::from(max) - i128::from(min);
+        self.history.iter()
+            .map(|value| match value {
+                Some(_) if span == 0 => SPARKS[0],
+                Some(value) => SPARKS[((i128::from(*value) - i128::from(min)) * (SPARKS.len() as i128 - 1) / span) as usize],
+                None => ' '
+            })
+            .collect()
+    }
+}
+
+/// Stores a graph for each expression being graphed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GraphPanel {
+    graphs: Vec<Graph>
+}
+
+impl GraphPanel {
+    /// Returns a panel with an empty graph for each of the expressions.
+    ///
+    /// # Parameters
+    ///
+    /// * `expressions` - The expressions to graph.
+    /// * `width` - The number of frames each graph covers.
+    #[must_use]
+    pub fn new(expressions: &[WatchExpression], width: usize) -> GraphPanel {
+        GraphPanel { graphs: expressions.iter().map(|expression| Graph::new(expression.clone(), width)).collect() }
+    }
+
+    /// Returns the graphs, in the order their expressions were provided.
+    #[must_use]
+    pub fn graphs(&self) -> &[Graph] {
+        &self.graphs
+    }
+
+    /// Evaluates every expression for the frame which just ran.
+    ///
+    /// # Parameters
+    ///
+    /// * `interpreter` - The interpreter to read from.
+    pub fn record(&mut self, interpreter: &Interpreter) {
+        for graph in &mut self.graphs {
+            graph.record(interpreter);
+        }
+    }
+
+    /// Returns the panel as text, with a line per graph giving its expression, its sparkline, and its latest value along with its range.
+    #[must_use]
+    pub fn render(&self) -> String {
+        let label_width = self.graphs.iter().map(|graph| graph.expression.source().len()).max().unwrap_or(0);
+        let mut panel = String::new();
+        for graph in &self.graphs {
+            let _ = write!(panel, "{:<label_width$} |{}| ", graph.expression.source(), graph.sparkline());
+            match (graph.history.back(), graph.range()) {
+                (Some(Some(latest)), Some((min, max))) => {
+                    let _ = writeln!(panel, "{latest:#X} ({min:#X} to {max:#X})");
+                },
+                (Some(None), _) => panel.push_str("ERR\n"),
+                _ => panel.push_str("-\n")
+            }
+        }
+
+        panel
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn graph_recent_frames() {
+        let mut interpreter = Interpreter::new();
+        interpreter.load_game(&[0x70, 0x01, 0x12, 0x00]);
+        let mut panel = GraphPanel::new(&["V0".parse().unwrap(), "V0 / 0".parse().unwrap()], 8);
+        for _ in 0..10 {
+            interpreter.handle_cycle();
+            interpreter.handle_cycle();
+            panel.record(&interpreter);
+        }
+
+        let graph = &panel.graphs()[0];
+        assert_eq!(graph.history().len(), 8, "Oldest frames not dropped.");
+        assert_eq!(graph.range(), Some((3, 10)), "Range of the recent frames incorrect.");
+        assert_eq!(graph.sparkline(), "▁▂▃▄▅▆▇█", "Sparkline not scaled to the range.");
+        assert_eq!(panel.render(), "V0     |▁▂▃▄▅▆▇█| 0xA (0x3 to 0xA)\nV0 / 0 |        | ERR\n", "Panel rendered incorrectly.");
+    }
+}
@@ -0,0 +1,154 @@
+//! A module to call the embedder's functions around every instruction and frame, so that tooling (e.g. tracers, profilers, scripting, or achievements) can observe the game without changing the interpreter.
+//! Hooks are [added](crate::interpreter::Interpreter::add_pre_instruction_hook) to the interpreter, and only see it read-only:
+//!
+//! ```
+//! use std::sync::atomic::{AtomicUsize, Ordering};
+//! use std::sync::Arc;
+//!
+//! use rusty_chip_core::interpreter::Interpreter;
+//! use rusty_chip_core::opcodes::Opcode;
+//! use rusty_chip_core::quirks::QuirkConfig;
+//!
+//! let draws = Arc::new(AtomicUsize::new(0));
+//! let mut interpreter = Interpreter::new_headless(QuirkConfig::new());
+//! let counter = Arc::clone(&draws);
+//! interpreter.add_post_instruction_hook(move |_, opcode| {
+//!     if matches!(opcode, Opcode::Draw(..)) {
+//!         counter.fetch_add(1, Ordering::Relaxed);
+//!     }
+//! });
+//!
+//! interpreter.load_game(&[0xD0, 0x15]);
+//! interpreter.handle_cycle();
+//! assert_eq!(draws.load(Ordering::Relaxed), 1);
+//! ```
+
+#[cfg(not(feature = "std"))]
+use crate::alloc_prelude::*;
+use crate::interpreter::Interpreter;
+use crate::opcodes::Opcode;
+
+/// A function called with the interpreter and the opcode it is about to run (or has just run).
+/// Hooks must be `Send` and `Sync`, as the interpreter may run on its own thread.
+pub type InstructionHook = Box<dyn FnMut(&Interpreter, &Opcode) + Send + Sync>;
+
+/// A function called with the interpreter at the end of every frame.
+pub type FrameHook = Box<dyn FnMut(&Interpreter) + Send + Sync>;
+
+/// Identifies a hook, so that it can be [removed](crate::interpreter::Interpreter::remove_hook) later.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HookId(u64);
+
+/// Stores the hooks added to an interpreter, each kind called in the order they were added.
+#[derive(Default)]
+pub(crate) struct Hooks {
+    next_id: u64,
+    pre_instruction: Vec<(HookId, InstructionHook)>,
+    post_instruction: Vec<(HookId, InstructionHook)>,
+    frame: Vec<(HookId, FrameHook)>
+}
+
+impl Hooks {
+    /// Returns an identifier which has not been given to any other hook.
+    fn next_id(&mut self) -> HookId {
+        let id = HookId(self.next_id);
+        self.next_id += 1;
+        id
+    }
+
+    /// Adds a hook called before every instruction, returning its identifier.
+    ///
+    /// # Parameters
+    ///
+    /// * `hook` - The function to call.
+    pub(crate) fn add_pre_instruction(&mut self, hook: InstructionHook) -> HookId {
+        let id = self.next_id();
+        self.pre_instruction.push((id, hook));
+        id
+    }
+
+    /// Adds a hook called after every instruction, returning its identifier.
+    ///
+    /// # Parameters
+    ///
+    /// * `hook` - The function to call.
+    pub(crate) fn add_post_instruction(&mut self, hook: InstructionHook) -> HookId {
+        let id = self.next_id();
+        self.post_instruction.push((id, hook));
+        id
+    }
+
+    /// Adds a hook called at the end of every frame, returning its identifier.
+    ///
+    /// # Parameters
+    ///
+    /// * `hook` - The function to call.
+    pub(crate) fn add_frame(&mut self, hook: FrameHook) -> HookId {
+        let id = self.next_id();
+        self.frame.push((id, hook));
+        id
+    }
+
+    /// Removes the hook, returning whether it had been added.
+    ///
+    /// # Parameters
+    ///
+    /// * `id` - The identifier returned when the hook was added.
+    pub(crate) fn remove(&mut self, id: HookId) -> bool {
+        let hook_count = self.len();
+        self.pre_instruction.retain(|(hook_id, _)| *hook_id != id);
+        self.post_instruction.retain(|(hook_id, _)| *hook_id != id);
+        self.frame.retain(|(hook_id, _)| *hook_id != id);
+        self.len() != hook_count
+    }
+
+    /// Returns the number of hooks of every kind.
+    pub(crate) fn len(&self) -> usize {
+        self.pre_instruction.len() + self.post_instruction.len() + self.frame.len()
+    }
+
+    /// Returns whether no hooks have been added.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns whether any hooks are called around instructions, so that the interpreter can skip them cheaply otherwise.
+    pub(crate) fn has_instruction_hooks(&self) -> bool {
+        !self.pre_instruction.is_empty() || !self.post_instruction.is_empty()
+    }
+
+    /// Calls the hooks which run before an instruction.
+    ///
+    /// # Parameters
+    ///
+    /// * `interpreter` - The interpreter about to run the opcode.
+    /// * `opcode` - The opcode about to run.
+    pub(crate) fn run_pre_instruction(&mut self, interpreter: &Interpreter, opcode: &Opcode) {
+        for (_, hook) in &mut self.pre_instruction {
+            hook(interpreter, opcode);
+        }
+    }
+
+    /// Calls the hooks which run after an instruction.
+    ///
+    /// # Parameters
+    ///
+    /// * `interpreter` - The interpreter which ran the opcode.
+    /// * `opcode` - The opcode which ran.
+    pub(crate) fn run_post_instruction(&mut self, interpreter: &Interpreter, opcode: &Opcode) {
+        for (_, hook) in &mut self.post_instruction {
+            hook(interpreter, opcode);
+        }
+    }
+
+    /// Calls the hooks which run at the end of a frame.
+    ///
+    /// # Parameters
+    ///
+    /// * `interpreter` - The interpreter which finished the frame.
+    pub(crate) fn run_frame(&mut self, interpreter: &Interpreter) {
+        for (_, hook) in &mut self.frame {
+            hook(interpreter);
+        }
+    }
+}
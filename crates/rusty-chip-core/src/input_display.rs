@@ -0,0 +1,171 @@
+//! A module to keep a frame-by-frame record of the keypad for the input display, a timeline strip along the bottom of the window.
+//! Each column of the strip is a frame (the newest on the right) and each row a CHIP-8 key, showing exactly which frames a key was pressed, held, and released on, e.g. for practising frame-perfect tricks or checking input playback.
+//! The strip can be moved, scaled, and faded through the [overlay layout](crate::layout).
+
+use alloc::collections::VecDeque;
+
+#[cfg(not(feature = "std"))]
+use crate::alloc_prelude::*;
+use crate::backend::Rect;
+use crate::interpreter::{SCALED_HEIGHT, SCALED_WIDTH};
+use crate::layout::Placement;
+
+/// The number of frames shown in the timeline.
+pub const TIMELINE_FRAMES: usize = 128;
+
+/// The number of CHIP-8 keys, each with a row of the timeline.
+const KEY_COUNT: u32 = 16;
+
+const COLUMN_WIDTH: u32 = SCALED_WIDTH / TIMELINE_FRAMES as u32;
+const ROW_HEIGHT: u32 = 3;
+pub(crate) const TIMELINE_HEIGHT: u32 = KEY_COUNT * ROW_HEIGHT;
+
+/// Stores the keys of a single frame as bitmasks, with bit `n` for the CHIP-8 key `n`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FrameInputs {
+    /// The keys held when the frame ran.
+    pub held: u16,
+    /// The keys pressed before the frame ran, since the previous frame.
+    pub pressed: u16,
+    /// The keys released before the frame ran, since the previous frame.
+    pub released: u16
+}
+
+/// Stores the keys of the most recent frames, oldest first.
+#[derive(Debug, Clone, Default)]
+pub struct InputTimeline {
+    frames: VecDeque<FrameInputs>,
+    pending: FrameInputs
+}
+
+impl InputTimeline {
+    /// Returns an empty timeline.
+    #[must_use]
+    pub fn new() -> InputTimeline {
+        InputTimeline { frames: VecDeque::with_capacity(TIMELINE_FRAMES), pending: FrameInputs::default() }
+    }
+
+    /// Records a key press for the next frame. Presses of a key which is already held (e.g. key repeats) are ignored.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The CHIP-8 key (`0x0` to `0xF`).
+    pub fn press(&mut self, key: u8) {
+        let bit = 1 << key;
+        if self.pending.held & bit == 0 {
+            self.pending.held |= bit;
+            self.pending.pressed |= bit;
+        }
+    }
+
+    /// Records a key release for the next frame.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The CHIP-8 key (`0x0` to `0xF`).
+    pub fn release(&mut self, key: u8) {
+        let bit = 1 << key;
+        if self.pending.held & bit != 0 {
+            self.pending.held &= !bit;
+            self.pending.released |= bit;
+        }
+    }
+
+    /// Stamps the keys recorded since the previous frame with the frame which is about to run, dropping the oldest frame once the timeline is full.
+    pub fn end_frame(&mut self) {
+        if self.frames.len() == TIMELINE_FRAMES {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(self.pending);
+        self.pending.pressed = 0;
+        self.pending.released = 0;
+    }
+
+    /// Returns the keys of the recorded frames, oldest first.
+    pub fn frames(&self) -> impl Iterator<Item = &FrameInputs> {
+        self.frames.iter()
+    }
+
+    /// Refills the rectangles of the timeline strip: the frames keys were held on, then the frames keys were pressed or released on.
+    /// The rectangles are kept between frames so that no allocations are made once they have grown to fit.
+    ///
+    /// # Parameters
+    ///
+    /// * `rects` - The held and changed rectangles to fill.
+    /// * `placement` - Where the strip is drawn in the window.
+    pub(crate) fn fill_rects(&self, rects: &mut [Vec<Rect>; 2], placement: &Placement) {
+        rects.iter_mut().for_each(Vec::clear);
+        let strip = strip_rect(placement);
+
+        // The newest frame is drawn in the rightmost column
+        let first_column = TIMELINE_FRAMES - self.frames.len();
+        for (column, frame) in (first_column..).zip(&self.frames) {
+            for key in 0..KEY_COUNT {
+                let bit = 1 << key;
+                let is_changed = (frame.pressed | frame.released) & bit != 0;
+                if frame.held & bit == 0 && !is_changed {
+                    continue;
+                }
+
+                // The edges are scaled rather than the sizes, so that the cells stay flush with each other
+                #[allow(clippy::cast_possible_truncation)]
+                let (left, right) = (placement.scaled(column as u32 * COLUMN_WIDTH), placement.scaled((column as u32 + 1) * COLUMN_WIDTH));
+                let (top, bottom) = (placement.scaled(key * ROW_HEIGHT), placement.scaled((key + 1) * ROW_HEIGHT));
+                #[allow(clippy::cast_possible_wrap)]
+                let rect = Rect::new(strip.x + left as i32, strip.y + top as i32, (right - left).max(1), (bottom - top).max(1));
+                rects[usize::from(is_changed)].push(rect);
+            }
+        }
+    }
+}
+
+/// Returns the area of the window the timeline strip covers.
+///
+/// # Parameters
+///
+/// * `placement` - Where the strip is drawn in the window.
+pub(crate) fn strip_rect(placement: &Placement) -> Rect {
+    let (x, y) = placement.origin((SCALED_WIDTH, TIMELINE_HEIGHT), (SCALED_WIDTH, SCALED_HEIGHT));
+    Rect::new(x, y, placement.scaled(SCALED_WIDTH).max(1), placement.scaled(TIMELINE_HEIGHT).max(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::{Anchor, Overlay};
+
+    #[test]
+    fn record_frames() {
+        let mut timeline = InputTimeline::new();
+        timeline.press(0x5);
+        timeline.end_frame();
+        timeline.press(0x5);
+        timeline.end_frame();
+        timeline.release(0x5);
+        timeline.press(0xA);
+        timeline.release(0xA);
+        timeline.end_frame();
+
+        let frames: Vec<FrameInputs> = timeline.frames().copied().collect();
+        assert_eq!(frames[0], FrameInputs { held: 1 << 0x5, pressed: 1 << 0x5, released: 0 }, "Press not recorded.");
+        assert_eq!(frames[1], FrameInputs { held: 1 << 0x5, pressed: 0, released: 0 }, "Key repeat recorded as a press.");
+        assert_eq!(frames[2], FrameInputs { held: 0, pressed: 1 << 0xA, released: 1 << 0x5 | 1 << 0xA }, "Release or tap within a frame not recorded.");
+
+        let mut rects = [Vec::new(), Vec::new()];
+        timeline.fill_rects(&mut rects, &Overlay::InputDisplay.default_placement());
+        assert_eq!(rects[0].len(), 1, "Held frames not drawn.");
+        assert_eq!(rects[1].len(), 3, "Changed frames not drawn.");
+        assert_eq!(rects[1].last().unwrap().right(), SCALED_WIDTH as i32, "Newest frame not drawn on the right.");
+        assert_eq!(rects[0][0].bottom(), SCALED_HEIGHT as i32 - 10 * ROW_HEIGHT as i32, "Held key not drawn in its row.");
+
+        let placement = Placement { anchor: Anchor::TopLeft, scale: 0.5, opacity: 1.0 };
+        timeline.fill_rects(&mut rects, &placement);
+        assert_eq!(rects[1].last().unwrap().right(), (SCALED_WIDTH / 2) as i32, "Newest frame not drawn on the right of the moved strip.");
+        assert!(rects.iter().flatten().all(|rect| strip_rect(&placement).contains(rect)), "Frames drawn outside the moved strip.");
+
+        for _ in 0..TIMELINE_FRAMES {
+            timeline.end_frame();
+        }
+        assert_eq!(timeline.frames().count(), TIMELINE_FRAMES, "Old frames not dropped.");
+    }
+}
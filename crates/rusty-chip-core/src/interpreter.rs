@@ -0,0 +1,4495 @@
+//! A module to contain the functionality which actually emulates a CHIP-8.  
+//! The various bits of emulated hardware and the execution of opcodes and cycles happen here. 
+
+use alloc::collections::BTreeSet;
+use alloc::sync::Arc;
+use core::fmt::{Display, Formatter};
+use core::ops::Range;
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+#[cfg(feature = "cli")]
+use clap::ValueEnum;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+#[cfg(not(feature = "std"))]
+use crate::alloc_prelude::*;
+use crate::backend::{self, AudioSink, InputSource, MessageKind};
+use crate::banking::{self, BANK_SELECT_ADDRESS, BANK_WINDOW_ADDRESS};
+use crate::builder::InterpreterBuilder;
+use crate::audio::{self, AudioCommand, DEFAULT_FREQUENCY, DEFAULT_PITCH, PATTERN_LENGTH, PRIMARY_VOICE};
+use crate::coverage::Coverage;
+#[cfg(feature = "std")]
+use crate::profiler::Profile;
+use crate::dispatch::{DecodeCache, Dispatch};
+use crate::events::{Change, CpuState, EventLog};
+use crate::symbols::SymbolTable;
+use crate::extensions::{Extension, ExtensionRegistry};
+use crate::frame_log::{self, Subsystem};
+use crate::framebuffer::{Framebuffer, PlaneBuffer};
+use crate::hooks::{HookId, Hooks};
+use crate::keypad;
+use crate::opcodes::{Opcode, OpcodeBytes};
+use crate::provenance::{DrawProvenance, DrawRecord};
+use crate::quirks::{ClippingQuirk, DisplayWaitQuirk, JumpingQuirk, KeyGhostingQuirk, MemoryIncrementQuirk, MemoryTimingQuirk, QuirkConfig, ResetVfQuirk, ShiftingQuirk};
+
+pub const SCALED_WIDTH: u32 = SCREEN_WIDTH * SCREEN_SCALE;
+pub const SCALED_HEIGHT: u32 = SCREEN_HEIGHT * SCREEN_SCALE;
+
+pub(crate) const RAM_SIZE: usize = 0x10000;
+pub(crate) const STACK_SIZE: usize = 16;
+pub(crate) const REGISTERS_SIZE: usize = 16;
+pub(crate) const RPL_FLAGS_SIZE: usize = 8;
+pub const PROGRAM_START_ADDRESS: u16 = 0x200;
+const PROGRAM_COUNTER_INCREMENT: u16 = 0x2;
+const EXIT_ADDRESS: u16 = 0x000;
+const BYTE_MASK: u16 = u8::MAX as u16;
+const LEAST_SIGNIFICANT_BIT_MASK: u8 = 0x1;
+const MOST_SIGNIFICANT_BIT_MASK: u8 = 0x80;
+const REGISTER_F: usize = 0xF;
+pub(crate) const MAX_KEY: u8 = 0xF;
+pub(crate) const SCREEN_WIDTH: u32 = 64;
+pub(crate) const SCREEN_HEIGHT: u32 = 32;
+const HIGH_RESOLUTION_SCREEN_WIDTH: u32 = 128;
+const HIGH_RESOLUTION_SCREEN_HEIGHT: u32 = 64;
+const TWO_PAGE_SCREEN_HEIGHT: u32 = 64;
+// HiRes CHIP-8 games start by jumping to 0x260, where the display patch to the interpreter is loaded along with the game
+const TWO_PAGE_SIGNATURE: [u8; 2] = [0x12, 0x60];
+const TWO_PAGE_PROGRAM_START_ADDRESS: u16 = 0x2C0;
+const TWO_PAGE_CLEAR_SCREEN_ADDRESS: u16 = 0x230;
+const SCREEN_SCALE: u32 = 10;
+pub(crate) const DRAWING_BUFFER_SIZE: usize = (HIGH_RESOLUTION_SCREEN_WIDTH * HIGH_RESOLUTION_SCREEN_HEIGHT) as usize;
+const LARGE_SPRITE_SIZE: u16 = 16;
+const HORIZONTAL_SCROLL_DISTANCE: usize = 4;
+pub(crate) const FIRST_PLANE_MASK: u8 = 0b01;
+const SECOND_PLANE_MASK: u8 = 0b10;
+const ALL_PLANES_MASK: u8 = FIRST_PLANE_MASK | SECOND_PLANE_MASK;
+// Outlines pixels lit only on this display, then pixels lit only on the compared display
+// The input display's strip, then the frames keys were held on, then the frames keys were pressed or released on
+const HEXADECIMAL_DIGIT_SPRITE_LENGTH: u8 = 0x5;
+const HEXADECIMAL_DIGIT_SPRITES_LENGTH: usize = 80;
+const HEXADECIMAL_DIGIT_SPRITES: [u8; HEXADECIMAL_DIGIT_SPRITES_LENGTH] = [
+    0xF0, 0x90, 0x90, 0x90, 0xF0,
+    0x20, 0x60, 0x20, 0x20, 0x70,
+    0xF0, 0x10, 0xF0, 0x80, 0xF0,
+    0xF0, 0x10, 0xF0, 0x10, 0xF0,
+    0x90, 0x90, 0xF0, 0x10, 0x10,
+    0xF0, 0x80, 0xF0, 0x10, 0xF0,
+    0xF0, 0x80, 0xF0, 0x90, 0xF0,
+    0xF0, 0x10, 0x20, 0x40, 0x40,
+    0xF0, 0x90, 0xF0, 0x90, 0xF0,
+    0xF0, 0x90, 0xF0, 0x10, 0xF0,
+    0xF0, 0x90, 0xF0, 0x90, 0x90,
+    0xE0, 0x90, 0xE0, 0x90, 0xE0,
+    0xF0, 0x80, 0x80, 0x80, 0xF0,
+    0xE0, 0x90, 0x90, 0x90, 0xE0,
+    0xF0, 0x80, 0xF0, 0x80, 0xF0,
+    0xF0, 0x80, 0xF0, 0x80, 0x80
+];
+const LARGE_HEXADECIMAL_DIGIT_SPRITE_LENGTH: u8 = 0xA;
+const LARGE_HEXADECIMAL_DIGIT_SPRITES_LENGTH: usize = 160;
+pub(crate) const LARGE_HEXADECIMAL_DIGIT_SPRITES: [u8; LARGE_HEXADECIMAL_DIGIT_SPRITES_LENGTH] = [
+    0xFF, 0xFF, 0xC3, 0xC3, 0xC3, 0xC3, 0xC3, 0xC3, 0xFF, 0xFF,
+    0x18, 0x78, 0x78, 0x18, 0x18, 0x18, 0x18, 0x18, 0xFF, 0xFF,
+    0xFF, 0xFF, 0x03, 0x03, 0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF,
+    0xFF, 0xFF, 0x03, 0x03, 0xFF, 0xFF, 0x03, 0x03, 0xFF, 0xFF,
+    0xC3, 0xC3, 0xC3, 0xC3, 0xFF, 0xFF, 0x03, 0x03, 0x03, 0x03,
+    0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, 0x03, 0x03, 0xFF, 0xFF,
+    0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, 0xC3, 0xC3, 0xFF, 0xFF,
+    0xFF, 0xFF, 0x03, 0x03, 0x06, 0x0C, 0x18, 0x18, 0x18, 0x18,
+    0xFF, 0xFF, 0xC3, 0xC3, 0xFF, 0xFF, 0xC3, 0xC3, 0xFF, 0xFF,
+    0xFF, 0xFF, 0xC3, 0xC3, 0xFF, 0xFF, 0x03, 0x03, 0xFF, 0xFF,
+    0x7E, 0xFF, 0xC3, 0xC3, 0xC3, 0xFF, 0xFF, 0xC3, 0xC3, 0xC3,
+    0xFC, 0xFC, 0xC3, 0xC3, 0xFC, 0xFC, 0xC3, 0xC3, 0xFC, 0xFC,
+    0x3C, 0xFF, 0xC3, 0xC0, 0xC0, 0xC0, 0xC0, 0xC3, 0xFF, 0x3C,
+    0xFC, 0xFE, 0xC3, 0xC3, 0xC3, 0xC3, 0xC3, 0xC3, 0xFE, 0xFC,
+    0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF,
+    0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, 0xC0, 0xC0, 0xC0, 0xC0
+];
+pub(crate) const LARGE_HEXADECIMAL_DIGIT_SPRITES_ADDRESS: usize = HEXADECIMAL_DIGIT_SPRITES_LENGTH;
+const FONT_LENGTH: usize = HEXADECIMAL_DIGIT_SPRITES_LENGTH + LARGE_HEXADECIMAL_DIGIT_SPRITES_LENGTH;
+/// The addresses of the small and large built-in fonts, which games may protect or be warned about overwriting.
+const FONT_RANGES: [Range<usize>; 2] = [0..HEXADECIMAL_DIGIT_SPRITES_LENGTH, LARGE_HEXADECIMAL_DIGIT_SPRITES_ADDRESS..FONT_LENGTH];
+
+/// Denotes why the interpreter stopped running the game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HaltReason {
+    /// The opcode at the address is not recognized.
+    InvalidOpcode { address: u16, opcode: u16 },
+    /// The subroutine call at the address had no room left on the stack.
+    StackOverflow { address: u16 },
+    /// The return at the address had no subroutine to return from.
+    StackUnderflow { address: u16 },
+    /// The program counter ran past the end of the RAM.
+    ProgramCounterOutOfBounds { address: u16 },
+    /// The instruction at the address wrote to the target address, which is protected by the [memory protection](MemoryProtection).
+    ProtectedWrite { address: u16, target: u16 },
+    /// The opcode at the address belongs to a [known extension](ExtensionRegistry) which is not supported.
+    UnsupportedExtension { address: u16, opcode: u16, extension: &'static str },
+    /// The instruction at the address switched to a [bank](banking) which the game does not have.
+    InvalidBank { address: u16, bank: u8 },
+    /// The game exited through the `0000` or SUPER-CHIP `00FD` opcode.
+    Exit,
+    /// The user stopped the game.
+    UserStop
+}
+
+impl HaltReason {
+    /// Returns whether the halt was caused by a fault in the game, rather than it exiting or being stopped.
+    #[must_use]
+    pub fn is_error(self) -> bool {
+        !matches!(self, HaltReason::Exit | HaltReason::UserStop)
+    }
+}
+
+impl Display for HaltReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            HaltReason::InvalidOpcode { address, opcode } => write!(f, "Unrecognized opcode {opcode:04X} at {address:#05X}."),
+            HaltReason::StackOverflow { address } => write!(f, "Stack overflow from the subroutine call at {address:#05X}."),
+            HaltReason::StackUnderflow { address } => write!(f, "Stack underflow from the return at {address:#05X}."),
+            HaltReason::ProgramCounterOutOfBounds { address } => write!(f, "Program counter ran past the end of the RAM at {address:#05X}."),
+            HaltReason::ProtectedWrite { address, target } => write!(f, "The instruction at {address:#05X} wrote to protected memory at {target:#05X}."),
+            HaltReason::UnsupportedExtension { address, opcode, extension } => write!(f, "Unsupported extension: {extension} (opcode {opcode:04X} at {address:#05X}). The game was written for {extension}, which this emulator does not support."),
+            HaltReason::InvalidBank { address, bank } => write!(f, "The instruction at {address:#05X} switched to bank {bank}, which the game does not have."),
+            HaltReason::Exit => write!(f, "The game exited."),
+            HaltReason::UserStop => write!(f, "The game was stopped.")
+        }
+    }
+}
+
+/// Denotes which regions of memory the game is prevented from writing to.  
+/// Writes to a protected region halt the game, rather than only warning as an unprotected font write does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MemoryProtection {
+    /// The game may write anywhere, as on the original hardware.
+    #[default]
+    Off,
+    /// The built-in fonts are read-only.
+    Font,
+    /// Both the built-in font and the loaded program are read-only, for games which are not meant to modify themselves.
+    Program
+}
+
+/// Denotes a suspicious memory access by the game which did not stop it from running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryWarning {
+    /// The instruction at the address wrote to the target address, overwriting the built-in font.
+    FontWrite { address: u16, target: u16 }
+}
+
+impl Display for MemoryWarning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            MemoryWarning::FontWrite { address, target } => write!(f, "The instruction at {address:#05X} wrote to {target:#05X}, overwriting the built-in font.")
+        }
+    }
+}
+
+/// Stores a subroutine call waiting on the stack to be returned from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StackFrame {
+    /// The address of the call instruction which pushed the frame.
+    pub call_address: u16,
+    /// The address the subroutine returns to, which is what the stack actually holds.
+    pub return_address: u16,
+    /// The call instruction, as it currently is in memory (self-modifying games may have changed it since it ran).
+    pub opcode: u16
+}
+
+impl Display for StackFrame {
+    /// Formats the frame as its call instruction, e.g. `0x206: 2300 CALL 0x300`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let opcode_bytes = OpcodeBytes::build(&self.opcode.to_be_bytes());
+        match opcode_bytes.try_get_opcode() {
+            Some(opcode) => write!(f, "0x{:03X}: {opcode_bytes} {opcode}", self.call_address),
+            None => write!(f, "0x{:03X}: {opcode_bytes} (unrecognized)", self.call_address)
+        }
+    }
+}
+
+/// Denotes why the interpreter's next cycle will not run an instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitReason {
+    /// The game is waiting for a key to be pressed and released (see [`LoadKeyPress`](Opcode::LoadKeyPress)).
+    Key,
+    /// A draw is waiting for the display to refresh at the end of the frame (see [`DisplayWaitQuirk`]).
+    Display,
+    /// A slow opcode is still taking up cycles (see [`MemoryTimingQuirk`]).
+    Busy,
+    /// The interpreter has halted, and runs nothing until a game is loaded.
+    Halted(HaltReason)
+}
+
+/// Stores what a single [step](Interpreter::step) of the interpreter did, so that tooling does not have to decode the RAM again to find out.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Step {
+    /// The instruction which ran, or `None` if the cycle ran nothing (e.g. while waiting, or on an unrecognized opcode).
+    pub opcode: Option<Opcode>,
+    /// The program counter before the cycle, the address of the instruction if one ran.
+    pub program_counter_before: u16,
+    /// The program counter after the cycle.
+    pub program_counter_after: u16,
+    /// Why the next cycle will not run an instruction, or `None` if it will.
+    pub wait: Option<WaitReason>
+}
+
+/// Stores how the draw opcodes of a frame went, to tell whether a game is held back by the [display wait quirk](DisplayWaitQuirk).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DrawStats {
+    /// The number of sprites drawn.
+    pub draws: u32,
+    /// The number of draws which had to wait for the display to refresh.
+    pub stalled_draws: u32,
+    /// The number of instruction cycles lost waiting for the display to refresh.
+    pub stalled_cycles: u32
+}
+
+/// Stores how much of each kind of work the interpreter has done since the game was loaded, so that embedders and tests can check how a game behaves without parsing logs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Counters {
+    /// The number of instructions run.
+    pub instructions: u64,
+    /// The number of sprites drawn.
+    pub draws: u64,
+    /// The number of draws which had to wait for the display to refresh.
+    pub stalled_draws: u64,
+    /// The number of instruction cycles lost waiting for the display to refresh.
+    pub stalled_cycles: u64,
+    /// The number of unrecognized opcodes (including those of unsupported extensions) the game tried to run.
+    pub invalid_opcodes: u64,
+    /// The number of times the game waited for a key press.
+    pub key_waits: u64
+}
+
+/// Stores a copy of the emulated hardware at a point in time, so that the game can be rewound to it.  
+/// The keys being held are not stored as they belong to the player rather than the game.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Snapshot {
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) halt_reason: Option<HaltReason>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::array"))]
+    pub(crate) ram: [u8; RAM_SIZE],
+    pub(crate) registers: [u8; REGISTERS_SIZE],
+    pub(crate) register_i: u16,
+    pub(crate) delay_timer: u8,
+    pub(crate) sound_timer: u8,
+    pub(crate) program_counter: u16,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::serialization::below::<_, usize, { STACK_SIZE + 1 }>"))]
+    pub(crate) stack_pointer: usize,
+    pub(crate) stack: [u16; STACK_SIZE],
+    pub(crate) should_wait_for_key: bool,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::serialization::below::<_, usize, REGISTERS_SIZE>"))]
+    pub(crate) wait_for_key_register: usize,
+    pub(crate) should_wait_for_display_refresh: bool,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::serialization::pending_draw"))]
+    pub(crate) wait_for_display_refresh_data: (usize, usize, u8),
+    pub(crate) busy_cycles: u32,
+    pub(crate) drawing_buffer: PlaneBuffer,
+    pub(crate) second_plane_buffer: PlaneBuffer,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::serialization::below::<_, u8, { ALL_PLANES_MASK as usize + 1 }>"))]
+    pub(crate) selected_planes: u8,
+    pub(crate) is_high_resolution: bool,
+    pub(crate) is_two_page_display: bool,
+    pub(crate) audio_pattern: Option<[u8; PATTERN_LENGTH]>,
+    pub(crate) pitch: u8,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::rng"))]
+    pub(crate) rng: StdRng
+}
+
+/// Stores all the emulated hardware and state for the emulator.
+#[derive(Clone)]
+pub struct Interpreter {
+    halt_reason: Option<HaltReason>,
+    ram: [u8; RAM_SIZE],
+    registers: [u8; REGISTERS_SIZE],
+    register_i: u16,
+    delay_timer: u8,
+    sound_timer: u8,
+    program_counter: u16,
+    stack_pointer: usize,
+    stack: [u16; STACK_SIZE],
+    /// The keys held down, with bit N set if key N is pressed.
+    keyboard: u16,
+    should_wait_for_key: bool,
+    wait_for_key_register: usize,
+    should_wait_for_display_refresh: bool,
+    wait_for_display_refresh_data: (usize, usize, u8),
+    busy_cycles: u32,
+    drawing_buffer: PlaneBuffer,
+    second_plane_buffer: PlaneBuffer,
+    selected_planes: u8,
+    is_high_resolution: bool,
+    is_two_page_display: bool,
+    has_display_changed: bool,
+    audio_pattern: Option<[u8; PATTERN_LENGTH]>,
+    pitch: u8,
+    rpl_flags: [u8; RPL_FLAGS_SIZE],
+    have_rpl_flags_changed: bool,
+    draw_stats: DrawStats,
+    frame_draw_stats: DrawStats,
+    counters: Counters,
+    memory_protection: MemoryProtection,
+    load_address: u16,
+    is_banking: bool,
+    detects_hires: bool,
+    bank_count: u8,
+    program_length: usize,
+    memory_warnings: Vec<MemoryWarning>,
+    warned_addresses: BTreeSet<u16>,
+    coverage: Option<Arc<Coverage>>,
+    #[cfg(feature = "std")]
+    profile: Option<Arc<Profile>>,
+    dispatch: Dispatch,
+    decode_cache: Arc<DecodeCache>,
+    events: Option<Arc<EventLog>>,
+    symbols: Arc<SymbolTable>,
+    provenance: Option<Arc<DrawProvenance>>,
+    extensions: ExtensionRegistry,
+    frontend: Frontend,
+    quirk_config: QuirkConfig,
+    rng: StdRng
+}
+
+/// Stores what the embedder attached to an interpreter, which belongs to it alone.
+#[derive(Default)]
+struct Frontend {
+    hooks: Hooks,
+    audio: Option<Box<dyn AudioSink>>,
+    is_muted: bool,
+    display: Option<Box<dyn backend::Display>>
+}
+
+impl Clone for Frontend {
+    /// Returns a frontend with nothing attached, as the hooks and backends cannot be copied and a [fork](Interpreter::fork) should not draw to the same window or play through the same speakers.
+    fn clone(&self) -> Self {
+        Frontend::default()
+    }
+}
+
+/// Returns the bit of the key in the [held keys](Interpreter::pressed_keys), or no bits for keys past `0xF`.
+///
+/// # Parameters
+///
+/// * `key` - The CHIP-8 key.
+fn key_mask(key: u8) -> u16 {
+    if key <= MAX_KEY { 1 << key } else { 0 }
+}
+
+/// Returns a generator seeded from the entropy of the system.
+#[cfg(not(any(target_os = "none", all(target_arch = "wasm32", target_os = "unknown"))))]
+fn entropy_rng() -> StdRng {
+    StdRng::from_entropy()
+}
+
+/// Returns a generator with a fixed seed, as the browser's entropy can only be reached through JavaScript and bare-metal targets have none.  
+/// Browser frontends should [seed it](Interpreter::seed_random) themselves (e.g. from `crypto.getRandomValues`), as should firmware (e.g. from a hardware random number generator).
+#[cfg(any(target_os = "none", all(target_arch = "wasm32", target_os = "unknown")))]
+fn entropy_rng() -> StdRng {
+    StdRng::seed_from_u64(0)
+}
+
+impl Interpreter {
+    /// Returns a [builder](InterpreterBuilder) for an interpreter, for setting the quirks, seed, load address, and [backends](backend) among others.
+    #[must_use]
+    pub fn builder() -> InterpreterBuilder {
+        InterpreterBuilder::new()
+    }
+
+    /// Creates a new instance with default values for the hardware, without a display or audio (e.g. for command line tools, or frontends drawing the display themselves).  
+    /// Use the [builder](Interpreter::builder) for any other settings.
+    ///
+    /// # Parameters
+    ///
+    /// * `quirk_config` - The enabled/disabled status of all the quirks.
+    #[must_use]
+    pub fn new_headless(quirk_config: QuirkConfig) -> Interpreter {
+        let mut ram = [0; RAM_SIZE];
+        ram[..HEXADECIMAL_DIGIT_SPRITES.len()].copy_from_slice(&HEXADECIMAL_DIGIT_SPRITES[..]);
+        ram[LARGE_HEXADECIMAL_DIGIT_SPRITES_ADDRESS..FONT_LENGTH].copy_from_slice(&LARGE_HEXADECIMAL_DIGIT_SPRITES[..]);
+
+        Interpreter {
+            halt_reason: None,
+            ram,
+            registers: [0; REGISTERS_SIZE],
+            register_i: 0,
+            delay_timer: 0,
+            sound_timer: 0,
+            program_counter: 0,
+            stack_pointer: 0,
+            stack: [0; STACK_SIZE],
+            keyboard: 0,
+            should_wait_for_key: false,
+            wait_for_key_register: 0,
+            should_wait_for_display_refresh: false,
+            wait_for_display_refresh_data: (0, 0, 0),
+            busy_cycles: 0,
+            drawing_buffer: PlaneBuffer::new(),
+            second_plane_buffer: PlaneBuffer::new(),
+            selected_planes: FIRST_PLANE_MASK,
+            is_high_resolution: false,
+            is_two_page_display: false,
+            has_display_changed: true,
+            audio_pattern: None,
+            pitch: DEFAULT_PITCH,
+            rpl_flags: [0; RPL_FLAGS_SIZE],
+            have_rpl_flags_changed: false,
+            draw_stats: DrawStats::default(),
+            frame_draw_stats: DrawStats::default(),
+            counters: Counters::default(),
+            memory_protection: MemoryProtection::default(),
+            load_address: PROGRAM_START_ADDRESS,
+            is_banking: false,
+            detects_hires: true,
+            bank_count: 0,
+            program_length: 0,
+            memory_warnings: Vec::new(),
+            warned_addresses: BTreeSet::new(),
+            coverage: None,
+            #[cfg(feature = "std")]
+            profile: None,
+            dispatch: Dispatch::default(),
+            decode_cache: Arc::default(),
+            events: None,
+            symbols: Arc::new(SymbolTable::new()),
+            provenance: None,
+            extensions: ExtensionRegistry::new(),
+            frontend: Frontend::default(),
+            quirk_config,
+            rng: entropy_rng()
+        }
+    }
+
+    /// Draws to and plays sound through the provided [backends](backend), clearing the display to the background colour.
+    ///
+    /// # Parameters
+    ///
+    /// * `display` - The window to which to draw the display (if it exists).
+    /// * `audio` - The output which plays the single tone required by the CHIP-8 (if it exists).
+    pub(crate) fn attach_backends(&mut self, display: Option<Box<dyn backend::Display>>, audio: Option<Box<dyn AudioSink>>) {
+        self.frontend.display = display;
+        self.frontend.audio = audio;
+        self.clear_screen();
+    }
+
+    /// Returns a new `QuirkConfig` with default values for all members.  
+    /// This is used solely for testing as there will be no audiovisual components there.
+    #[cfg(test)]
+    #[must_use]
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Interpreter {
+        Self::new_headless(QuirkConfig::new())
+    }
+
+    /// Displays a simple message box to the user, if the display can show one.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `kind` - The [kind of message](MessageKind) we are displaying.
+    /// * `title` - The title of the message box.
+    /// * `message` - The body of the message box.
+    /// 
+    /// # Errors
+    /// 
+    /// Returns an `Err` if the message box could not be shown.
+    pub fn show_message(&self, kind: MessageKind, title: &str, message: &str) -> Result<(), String> {
+        match &self.frontend.display {
+            Some(display) => display.show_message(kind, title, message),
+            None => Ok(())
+        }
+    }
+
+    /// Displays a message box offering the user a choice between the provided buttons.  
+    /// Returns the index of the chosen button, or `None` if the box was closed without choosing (or there is no window).
+    ///
+    /// # Parameters
+    ///
+    /// * `kind` - The [kind of message](MessageKind) we are displaying.
+    /// * `title` - The title of the message box.
+    /// * `message` - The body of the message box.
+    /// * `choices` - The text of each button, in order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the message box could not be shown.
+    pub fn show_choice(&self, kind: MessageKind, title: &str, message: &str, choices: &[&str]) -> Result<Option<usize>, String> {
+        match &self.frontend.display {
+            Some(display) => display.show_choice(kind, title, message, choices),
+            None => Ok(None)
+        }
+    }
+
+    /// Sets the title of the window the display is drawn to (if it exists).
+    ///
+    /// # Parameters
+    ///
+    /// * `title` - The new title.
+    pub fn set_window_title(&mut self, title: &str) {
+        if let Some(display) = self.frontend.display.as_mut() {
+            if let Err(e) = display.set_title(title) {
+                frame_log::warn(Subsystem::Emulator, format_args!("Error setting window title: {e}"));
+            }
+        }
+    }
+
+    /// Returns the window the display is drawn to (if it exists), for the [renderer](crate::renderer::Renderer) to draw the display to.
+    pub(crate) fn display_mut(&mut self) -> Option<&mut (dyn backend::Display + 'static)> {
+        self.frontend.display.as_deref_mut()
+    }
+
+    /// Loads the provided game into memory at the [load address](Interpreter::set_load_address), dropping any bytes which do not fit in the RAM.  
+    /// With [banking](Interpreter::set_banking), the game is split into banks instead.  
+    /// HiRes CHIP-8 games (starting with `1260` at `0x200`) switch to the [64x64 display](Interpreter::is_two_page_display) and start at `0x2C0`, skipping the machine code patch to the original interpreter, unless [detecting them](Interpreter::set_hires_detection) is turned off.  
+    /// Additionally, sets the program counter to the start of the program to be ready for execution.  
+    /// All other values are set to their defaults to allow for repeated loads of games, except for the RPL flags, which are persistent storage and [set separately](Interpreter::set_rpl_flags).
+    ///
+    /// # Parameters
+    /// * `game_data` - The bytes which were read from the game file to be loaded into memory.
+    pub fn load_game(&mut self, game_data: &[u8]) {
+        self.ram[FONT_LENGTH..].fill(0);
+        if self.is_banking {
+            self.bank_count = banking::load(&mut self.ram, usize::from(self.load_address), game_data);
+        } else {
+            for (ram_byte, byte) in self.ram[usize::from(self.load_address)..].iter_mut().zip(game_data) {
+                *ram_byte = *byte;
+            }
+        }
+
+        self.registers.fill(0);
+        self.register_i = 0;
+        self.delay_timer = 0;
+        self.sound_timer = 0;
+        self.stack_pointer = 0;
+        self.stack.fill(0);
+        self.keyboard = 0;
+        self.should_wait_for_key = false;
+        self.wait_for_key_register = 0;
+        self.should_wait_for_display_refresh = false;
+        self.wait_for_display_refresh_data = (0, 0, 0);
+        self.busy_cycles = 0;
+        self.draw_stats = DrawStats::default();
+        self.frame_draw_stats = DrawStats::default();
+        self.counters = Counters::default();
+        self.program_length = game_data.len();
+        self.memory_warnings.clear();
+        self.warned_addresses.clear();
+        if let Some(coverage) = self.coverage.as_mut().map(Arc::make_mut) {
+            coverage.clear();
+        }
+        #[cfg(feature = "std")]
+        if let Some(profile) = self.profile.as_mut().map(Arc::make_mut) {
+            profile.clear();
+        }
+        Arc::make_mut(&mut self.decode_cache).clear();
+        if let Some(events) = self.events.as_mut().map(Arc::make_mut) {
+            events.clear();
+        }
+        if let Some(provenance) = self.provenance.as_mut().map(Arc::make_mut) {
+            provenance.clear();
+        }
+        self.audio_pattern = None;
+        self.pitch = DEFAULT_PITCH;
+        self.set_audio_tone();
+        self.set_audio_status();
+        self.is_high_resolution = false;
+        self.is_two_page_display = self.detects_hires && self.load_address == PROGRAM_START_ADDRESS && game_data.starts_with(&TWO_PAGE_SIGNATURE);
+        self.selected_planes = FIRST_PLANE_MASK;
+        self.clear_planes(ALL_PLANES_MASK);
+
+        self.program_counter = if self.is_two_page_display { TWO_PAGE_PROGRAM_START_ADDRESS } else { self.load_address };
+        self.halt_reason = None;
+    }
+
+    /// Processes a pressed key and stores its state.  
+    /// In the event that we are currently waiting for a key press (see [`LoadKeyPress`](Opcode::LoadKeyPress)), sets this key as the one waiting for the associated release event.
+    ///
+    /// # Params
+    ///
+    /// * `input` - The device the key is on, which maps it onto the keypad.
+    /// * `host_key` - The physical key pressed.
+    pub fn handle_key_press<I: InputSource>(&mut self, input: &I, host_key: I::Key) {
+        if let Some(key) = input.keypad_key(host_key) {
+            self.press_key(key);
+        }
+    }
+
+    /// Stores the CHIP-8 key as pressed, as [`handle_key_press`](Interpreter::handle_key_press) does for physical keys.  
+    /// For frontends and tests which map their own input onto the keypad rather than implementing an [input source](InputSource). Keys past `0xF` are ignored.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The CHIP-8 key (`0x0` to `0xF`).
+    pub fn press_key(&mut self, key: u8) {
+        if key > MAX_KEY {
+            return;
+        }
+
+        if self.should_wait_for_key {
+            self.registers[self.wait_for_key_register] = key;
+        }
+
+        self.keyboard |= key_mask(key);
+    }
+
+    /// Processes a released key and stores its state.  
+    /// In the event that we are currently waiting for a key release (see [`LoadKeyPress`](Opcode::LoadKeyPress)), clear that behaviour to allow continuation if this is the correct key.  
+    /// Keys which were not recorded as pressed (e.g. held since before a game was loaded) are ignored so that they cannot end the wait.
+    ///
+    /// # Params
+    ///
+    /// * `input` - The device the key is on, which maps it onto the keypad.
+    /// * `host_key` - The physical key released.
+    pub fn handle_key_release<I: InputSource>(&mut self, input: &I, host_key: I::Key) {
+        if let Some(key) = input.keypad_key(host_key) {
+            self.release_key(key);
+        }
+    }
+
+    /// Stores the CHIP-8 key as released, as [`handle_key_release`](Interpreter::handle_key_release) does for physical keys.  
+    /// For frontends and tests which map their own input onto the keypad rather than implementing an [input source](InputSource). Keys which are not pressed are ignored.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The CHIP-8 key (`0x0` to `0xF`).
+    pub fn release_key(&mut self, key: u8) {
+        let was_pressed = self.keyboard & key_mask(key) != 0;
+        self.keyboard &= !key_mask(key);
+        if was_pressed && self.should_wait_for_key && self.registers[self.wait_for_key_register] == key {
+            self.should_wait_for_key = false;
+        }
+    }
+
+    /// Seeds the generator used by the [`Random`](Opcode::Random) opcode, so that runs of a game can be reproduced exactly.
+    ///
+    /// # Parameters
+    ///
+    /// * `seed` - The seed for the generator.
+    pub fn seed_random(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    /// Processes a single instruction cycle.  
+    /// Nothing is processed once the interpreter has [halted](Interpreter::halt_reason).
+    pub fn handle_cycle(&mut self) {
+        self.run_cycle();
+    }
+
+    /// Processes a single instruction cycle as [`handle_cycle`](Interpreter::handle_cycle) does, returning the instruction which ran (if any), where the program counter went, and what the interpreter is waiting for afterwards.
+    pub fn step(&mut self) -> Step {
+        let program_counter_before = self.program_counter;
+        let opcode = self.run_cycle();
+
+        Step { opcode, program_counter_before, program_counter_after: self.program_counter, wait: self.wait_reason() }
+    }
+
+    /// Processes a single instruction cycle, returning the opcode which ran if there was one.
+    fn run_cycle(&mut self) -> Option<Opcode> {
+        if self.halt_reason.is_some() || self.should_wait_for_key {
+            return None;
+        }
+
+        if self.should_wait_for_display_refresh {
+            self.draw_stats.stalled_cycles += 1;
+            self.counters.stalled_cycles += 1;
+            return None;
+        }
+
+        if self.busy_cycles > 0 {
+            self.busy_cycles -= 1;
+            return None;
+        }
+
+        let address = self.program_counter;
+        let length = self.instruction_length(address);
+        let Some(opcode_bytes) = self.ram.get(address as usize..address as usize + length as usize) else {
+            self.halt(HaltReason::ProgramCounterOutOfBounds { address });
+            return None;
+        };
+
+        let opcode = match self.dispatch {
+            Dispatch::Match => OpcodeBytes::build(opcode_bytes).try_get_opcode(),
+            Dispatch::FunctionTable => OpcodeBytes::build(opcode_bytes).try_get_opcode_from_table(),
+            Dispatch::Predecoded => Arc::make_mut(&mut self.decode_cache).decode(address, opcode_bytes)
+        };
+        let opcode = match opcode {
+            // The HiRes CHIP-8 patch clears the taller display through a machine code call
+            Some(Opcode::SystemAddr(TWO_PAGE_CLEAR_SCREEN_ADDRESS)) if self.is_two_page_display => Some(Opcode::ClearScreen),
+            opcode => opcode
+        };
+        // Unsupported extensions are only checked for opcodes which would otherwise be unrecognized or taken for machine code calls
+        if matches!(opcode, None | Some(Opcode::SystemAddr(_))) {
+            let opcode = u16::from_be_bytes([opcode_bytes[0], opcode_bytes[1]]);
+            if let Some(extension) = self.extensions.find(opcode) {
+                self.counters.invalid_opcodes += 1;
+                self.halt(HaltReason::UnsupportedExtension { address, opcode, extension: extension.name });
+                return None;
+            }
+        }
+        let Some(opcode) = opcode else {
+            let opcode = u16::from_be_bytes([opcode_bytes[0], opcode_bytes[1]]);
+            self.counters.invalid_opcodes += 1;
+            self.halt(HaltReason::InvalidOpcode { address, opcode });
+            return None;
+        };
+        self.counters.instructions += 1;
+
+        if let Some(coverage) = self.coverage.as_mut().map(Arc::make_mut) {
+            coverage.record(address);
+            coverage.record_opcode(&opcode);
+        }
+        let state_before = self.events.is_some().then(|| self.cpu_state());
+        if let Some(events) = self.events.as_mut().map(Arc::make_mut) {
+            events.begin_instruction(address);
+        }
+        if self.frontend.hooks.has_instruction_hooks() {
+            let mut hooks = core::mem::take(&mut self.frontend.hooks);
+            hooks.run_pre_instruction(self, &opcode);
+            self.frontend.hooks = hooks;
+        }
+        self.program_counter = address.saturating_add(length);
+        #[cfg(feature = "std")]
+        if self.profile.is_some() {
+            let start = Instant::now();
+            self.handle_opcode(&opcode);
+            let time = start.elapsed();
+            if let Some(profile) = self.profile.as_mut().map(Arc::make_mut) {
+                profile.record(&opcode, time);
+            }
+        } else {
+            self.handle_opcode(&opcode);
+        }
+        // Without a clock the opcodes cannot be timed, so there is no profiling to do
+        #[cfg(not(feature = "std"))]
+        self.handle_opcode(&opcode);
+        if let Some(state_before) = state_before {
+            let state_after = self.cpu_state();
+            if let Some(events) = self.events.as_mut().map(Arc::make_mut) {
+                events.record_instruction(&state_before, &state_after);
+            }
+        }
+        if self.frontend.hooks.has_instruction_hooks() {
+            let mut hooks = core::mem::take(&mut self.frontend.hooks);
+            hooks.run_post_instruction(self, &opcode);
+            self.frontend.hooks = hooks;
+        }
+
+        Some(opcode)
+    }
+
+    /// Returns the parts of the state which the [event log](Interpreter::event_log) compares before and after each instruction.
+    fn cpu_state(&self) -> CpuState {
+        CpuState {
+            registers: self.registers,
+            register_i: self.register_i,
+            program_counter: self.program_counter,
+            stack_pointer: self.stack_pointer,
+            stack: self.stack,
+            delay_timer: self.delay_timer,
+            sound_timer: self.sound_timer
+        }
+    }
+
+    /// Returns whether the next cycle will run the instruction at the program counter, rather than waiting for a key, the display, or a slow opcode to finish (or doing nothing once halted).
+    #[must_use]
+    pub fn is_ready_to_execute(&self) -> bool {
+        self.wait_reason().is_none()
+    }
+
+    /// Returns why the next cycle will not run the instruction at the program counter, or `None` if it will. Halting takes precedence over the other reasons, as it is the only one which a cycle cannot end.
+    #[must_use]
+    pub fn wait_reason(&self) -> Option<WaitReason> {
+        if let Some(reason) = self.halt_reason {
+            Some(WaitReason::Halted(reason))
+        } else if self.should_wait_for_key {
+            Some(WaitReason::Key)
+        } else if self.should_wait_for_display_refresh {
+            Some(WaitReason::Display)
+        } else if self.busy_cycles > 0 {
+            Some(WaitReason::Busy)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the number of bytes making up the instruction at the address: 4 for the XO-CHIP long load, and 2 otherwise (including past the end of the RAM).
+    ///
+    /// # Parameters
+    ///
+    /// * `address` - The address of the instruction.
+    fn instruction_length(&self, address: u16) -> u16 {
+        self.ram.get(address as usize..address as usize + 2).map_or(PROGRAM_COUNTER_INCREMENT, |bytes| OpcodeBytes::build(bytes).length())
+    }
+
+    /// Skips over the instruction at the program counter, which takes two instruction slots for the XO-CHIP long load.
+    fn skip_instruction(&mut self) {
+        self.program_counter = self.program_counter.saturating_add(self.instruction_length(self.program_counter));
+    }
+
+    /// Returns why the interpreter stopped running the game, or `None` if it has not halted.  
+    /// A halted interpreter stays halted until a game is loaded.
+    #[must_use]
+    pub fn halt_reason(&self) -> Option<HaltReason> {
+        self.halt_reason
+    }
+
+    /// Returns the memory warnings raised since the last call, leaving none behind.  
+    /// Each instruction is only warned about once per game, so a game overwriting the font every frame does not flood the warnings.
+    pub fn take_memory_warnings(&mut self) -> Vec<MemoryWarning> {
+        core::mem::take(&mut self.memory_warnings)
+    }
+
+    /// Returns the SUPER-CHIP RPL flags, which games use to save progress (e.g. high scores).
+    #[must_use]
+    pub fn rpl_flags(&self) -> &[u8; RPL_FLAGS_SIZE] {
+        &self.rpl_flags
+    }
+
+    /// Sets the SUPER-CHIP RPL flags, such as those saved the last time the game was played.
+    ///
+    /// # Parameters
+    ///
+    /// * `rpl_flags` - The new flags.
+    pub fn set_rpl_flags(&mut self, rpl_flags: [u8; RPL_FLAGS_SIZE]) {
+        self.rpl_flags = rpl_flags;
+        self.have_rpl_flags_changed = false;
+    }
+
+    /// Returns whether the game has stored to the RPL flags since the last call, so that they can be saved.
+    pub fn take_rpl_flags_changed(&mut self) -> bool {
+        core::mem::take(&mut self.have_rpl_flags_changed)
+    }
+
+    /// Returns the addresses of the instructions run since the game was loaded, or `None` if coverage is not being recorded.
+    #[must_use]
+    pub fn coverage(&self) -> Option<&Coverage> {
+        self.coverage.as_deref()
+    }
+
+    /// Starts or stops recording which instructions run. Stopping discards what was recorded.
+    ///
+    /// # Parameters
+    ///
+    /// * `enabled` - True to record coverage, false to stop.
+    pub fn set_coverage_enabled(&mut self, enabled: bool) {
+        if enabled != self.coverage.is_some() {
+            self.coverage = enabled.then(|| Arc::new(Coverage::new()));
+        }
+    }
+
+    /// Returns how often each kind of opcode has run since the game was loaded and how long it took, or `None` if they are not being profiled.
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn profile(&self) -> Option<&Profile> {
+        self.profile.as_deref()
+    }
+
+    /// Starts or stops profiling the opcodes run. Stopping discards what was recorded.
+    ///
+    /// # Parameters
+    ///
+    /// * `enabled` - True to profile the opcodes, false to stop.
+    #[cfg(feature = "std")]
+    pub fn set_profiling_enabled(&mut self, enabled: bool) {
+        if enabled != self.profile.is_some() {
+            self.profile = enabled.then(|| Arc::new(Profile::new()));
+        }
+    }
+
+    /// Returns the changes the game made to the state over the most recent frames, or `None` if they are not being recorded.
+    #[must_use]
+    pub fn event_log(&self) -> Option<&EventLog> {
+        self.events.as_deref()
+    }
+
+    /// Starts recording the changes the game makes to the state into the provided log, or stops recording if `None`.
+    ///
+    /// # Parameters
+    ///
+    /// * `event_log` - The log to record into, e.g. `EventLog::new(600)` to keep ten seconds of changes.
+    pub fn set_event_log(&mut self, event_log: Option<EventLog>) {
+        self.events = event_log.map(Arc::new);
+    }
+
+    /// Returns the labels of the game, shown by the debugger and trace log alongside the addresses they mark.
+    #[must_use]
+    pub fn symbols(&self) -> &SymbolTable {
+        &self.symbols
+    }
+
+    /// Sets the labels of the game, e.g. those read from its [symbol file](SymbolTable::load). They are kept when another game is loaded.
+    ///
+    /// # Parameters
+    ///
+    /// * `symbols` - The labels of the game.
+    pub fn set_symbols(&mut self, symbols: SymbolTable) {
+        self.symbols = Arc::new(symbols);
+    }
+
+    /// Undoes the most recent instruction recorded in the [event log](Interpreter::event_log), along with any timer ticks since, returning whether there was one to undo.  
+    /// The interpreter is left ready to run the instruction again, no longer waiting for a key, the display, or a slow opcode. The display is not restored.
+    pub fn step_back(&mut self) -> bool {
+        let Some(changes) = self.events.as_mut().map(Arc::make_mut).and_then(EventLog::take_last_instruction) else {
+            return false;
+        };
+
+        for change in changes {
+            match change {
+                Change::Register { register, old, .. } => self.registers[register] = old,
+                Change::RegisterI { old, .. } => self.register_i = old,
+                Change::ProgramCounter { old, .. } => self.program_counter = old,
+                Change::StackPointer { old, .. } => self.stack_pointer = old,
+                Change::Stack { slot, old, .. } => self.stack[slot] = old,
+                Change::Memory { address, old, .. } => self.ram[usize::from(address)] = old,
+                Change::DelayTimer { old, .. } => self.delay_timer = old,
+                Change::SoundTimer { old, .. } => self.sound_timer = old
+            }
+        }
+        self.should_wait_for_key = false;
+        self.should_wait_for_display_refresh = false;
+        self.busy_cycles = 0;
+        self.set_audio_status();
+
+        true
+    }
+
+    /// Returns the design the instructions are decoded with.
+    #[must_use]
+    pub fn dispatch(&self) -> Dispatch {
+        self.dispatch
+    }
+
+    /// Sets the design the instructions are decoded with, which only changes how quickly they run.
+    ///
+    /// # Parameters
+    ///
+    /// * `dispatch` - The design to decode with.
+    pub fn set_dispatch(&mut self, dispatch: Dispatch) {
+        self.dispatch = dispatch;
+        Arc::make_mut(&mut self.decode_cache).clear();
+    }
+
+    /// Returns the draw which last changed each pixel, or `None` if draws are not being recorded.
+    #[must_use]
+    pub fn provenance(&self) -> Option<&DrawProvenance> {
+        self.provenance.as_deref()
+    }
+
+    /// Starts or stops recording which draw last changed each pixel. Stopping discards what was recorded.
+    ///
+    /// # Parameters
+    ///
+    /// * `enabled` - True to record draws, false to stop.
+    pub fn set_provenance_enabled(&mut self, enabled: bool) {
+        if enabled != self.provenance.is_some() {
+            self.provenance = enabled.then(|| Arc::new(DrawProvenance::new()));
+        }
+    }
+
+    /// Returns the address games are loaded at, where the program counter starts.
+    #[must_use]
+    pub fn load_address(&self) -> u16 {
+        self.load_address
+    }
+
+    /// Returns the addresses the game was loaded to, which are empty before a game is loaded.
+    #[must_use]
+    pub fn program_range(&self) -> Range<usize> {
+        let start = usize::from(self.load_address);
+        start..start + self.program_length
+    }
+
+    /// Sets the address games are loaded at, taking effect from the next [load](Interpreter::load_game).  
+    /// Most games are loaded at `0x200`, though some expect another address (e.g. `0x600` for the ETI-660).
+    ///
+    /// # Parameters
+    ///
+    /// * `load_address` - The address of the first byte of the game.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` without changing the address if it would overwrite the built-in font, or is past the bank window while [banking](Interpreter::set_banking).
+    pub fn set_load_address(&mut self, load_address: u16) -> Result<(), String> {
+        if usize::from(load_address) < FONT_LENGTH {
+            return Err(format!("Games cannot be loaded at {load_address:#05X}, as the built-in font takes up the memory below {FONT_LENGTH:#05X}."));
+        }
+        if self.is_banking && usize::from(load_address) > BANK_WINDOW_ADDRESS {
+            return Err(format!("Games cannot be loaded at {load_address:#05X} while banking, as the bank window starts at {BANK_WINDOW_ADDRESS:#05X}."));
+        }
+
+        self.load_address = load_address;
+        Ok(())
+    }
+
+    /// Returns the number of [banks](banking) the loaded game was split into, or 0 if it was loaded whole.
+    #[cfg(feature = "serde")]
+    pub(crate) fn bank_count(&self) -> u8 {
+        self.bank_count
+    }
+
+    /// Restores the length of the loaded game and the number of banks it was split into, which a [snapshot](Snapshot) of the memory does not say.
+    ///
+    /// # Parameters
+    ///
+    /// * `program_length` - The number of bytes in the game file.
+    /// * `bank_count` - The number of banks the game was split into, or 0 if it was loaded whole.
+    #[cfg(feature = "serde")]
+    pub(crate) fn restore_program(&mut self, program_length: usize, bank_count: u8) {
+        self.program_length = program_length;
+        self.bank_count = bank_count;
+    }
+
+    /// Returns whether games are split into [banks](banking) when loaded.
+    #[must_use]
+    pub fn is_banking(&self) -> bool {
+        self.is_banking
+    }
+
+    /// Sets whether games are split into [banks](banking) which they switch between by writing to [`BANK_SELECT_ADDRESS`], taking effect from the next [load](Interpreter::load_game).  
+    /// This is an experimental scheme for homebrew which does not fit in the 4K the CHIP-8 can address.
+    ///
+    /// # Parameters
+    ///
+    /// * `enabled` - True to split games into banks, false to load them whole.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` without enabling banking if the load address is past the bank window.
+    pub fn set_banking(&mut self, enabled: bool) -> Result<(), String> {
+        if enabled && usize::from(self.load_address) > BANK_WINDOW_ADDRESS {
+            return Err(format!("Banking needs games to be loaded at or below the bank window at {BANK_WINDOW_ADDRESS:#05X}, rather than {:#05X}.", self.load_address));
+        }
+
+        self.is_banking = enabled;
+        Ok(())
+    }
+
+    /// Returns whether HiRes CHIP-8 games are recognized when loaded.
+    #[must_use]
+    pub fn detects_hires(&self) -> bool {
+        self.detects_hires
+    }
+
+    /// Sets whether HiRes CHIP-8 games (starting with `1260`) are recognized when loaded and run on the [64x64 display](Interpreter::is_two_page_display), taking effect from the next [load](Interpreter::load_game).  
+    /// Turning this off runs them as regular CHIP-8 games, for other games which happen to start with the same jump.
+    ///
+    /// # Parameters
+    ///
+    /// * `enabled` - True to recognize HiRes CHIP-8 games, false to run every game as a regular one.
+    pub fn set_hires_detection(&mut self, enabled: bool) {
+        self.detects_hires = enabled;
+    }
+
+    /// Returns which regions of memory the game is prevented from writing to.
+    #[must_use]
+    pub fn memory_protection(&self) -> MemoryProtection {
+        self.memory_protection
+    }
+
+    /// Sets which regions of memory the game is prevented from writing to.
+    ///
+    /// # Parameters
+    ///
+    /// * `memory_protection` - The regions to protect.
+    pub fn set_memory_protection(&mut self, memory_protection: MemoryProtection) {
+        self.memory_protection = memory_protection;
+    }
+
+    /// Recognizes the opcodes of another unsupported extension, halting games which use them with the name of the extension rather than as an unrecognized opcode.
+    ///
+    /// # Parameters
+    ///
+    /// * `extension` - The extension to recognize.
+    pub fn register_extension(&mut self, extension: Extension) {
+        self.extensions.register(extension);
+    }
+
+    /// Calls the function before every instruction is run, with the interpreter (its program counter still at the instruction) and the decoded opcode. Hooks are called in the order they were added.
+    /// Instructions which do not run (e.g. while waiting for a key or the display) do not call hooks.
+    ///
+    /// # Parameters
+    ///
+    /// * `hook` - The function to call.
+    pub fn add_pre_instruction_hook(&mut self, hook: impl FnMut(&Interpreter, &Opcode) + Send + Sync + 'static) -> HookId {
+        self.frontend.hooks.add_pre_instruction(Box::new(hook))
+    }
+
+    /// Calls the function after every instruction is run, with the interpreter and the opcode which ran. Hooks are called in the order they were added.
+    ///
+    /// # Parameters
+    ///
+    /// * `hook` - The function to call.
+    pub fn add_post_instruction_hook(&mut self, hook: impl FnMut(&Interpreter, &Opcode) + Send + Sync + 'static) -> HookId {
+        self.frontend.hooks.add_post_instruction(Box::new(hook))
+    }
+
+    /// Calls the function at the end of every [frame](Interpreter::handle_frame), once the timers have ticked and the display has been drawn.
+    ///
+    /// # Parameters
+    ///
+    /// * `hook` - The function to call.
+    pub fn add_frame_hook(&mut self, hook: impl FnMut(&Interpreter) + Send + Sync + 'static) -> HookId {
+        self.frontend.hooks.add_frame(Box::new(hook))
+    }
+
+    /// Stops calling the hook, returning whether it had been added.
+    ///
+    /// # Parameters
+    ///
+    /// * `id` - The identifier returned when the hook was added.
+    pub fn remove_hook(&mut self, id: HookId) -> bool {
+        self.frontend.hooks.remove(id)
+    }
+
+    /// Writes the value to memory on behalf of the game.  
+    /// Writes to a region protected by the [memory protection](MemoryProtection) halt the game instead, along with any later writes of the same instruction. Unprotected writes over the built-in font are warned about.  
+    /// While [banking](Interpreter::set_banking), writes to [`BANK_SELECT_ADDRESS`] switch banks rather than changing the memory, halting the game if it has no such bank.
+    ///
+    /// # Parameters
+    ///
+    /// * `target` - The address to write to.
+    /// * `value` - The value to write.
+    fn write_memory(&mut self, target: usize, value: u8) {
+        if self.halt_reason.is_some() {
+            return;
+        }
+
+        let address = self.program_counter.wrapping_sub(PROGRAM_COUNTER_INCREMENT);
+        if self.is_banking && target == BANK_SELECT_ADDRESS {
+            if value < self.bank_count {
+                banking::switch(&mut self.ram, value);
+            } else {
+                self.halt(HaltReason::InvalidBank { address, bank: value });
+            }
+            return;
+        }
+
+        let program_start = usize::from(self.load_address);
+        let is_font = FONT_RANGES.iter().any(|range| range.contains(&target));
+        let is_protected = match self.memory_protection {
+            MemoryProtection::Off => false,
+            MemoryProtection::Font => is_font,
+            MemoryProtection::Program => target < program_start + self.program_length
+        };
+        #[allow(clippy::cast_possible_truncation)]
+        let target_u16 = target as u16;
+        if is_protected {
+            self.halt(HaltReason::ProtectedWrite { address, target: target_u16 });
+            return;
+        }
+        if is_font && self.warned_addresses.insert(address) {
+            self.memory_warnings.push(MemoryWarning::FontWrite { address, target: target_u16 });
+        }
+
+        if let Some(events) = self.events.as_mut().map(Arc::make_mut) {
+            events.record(Change::Memory { address: target_u16, old: self.ram[target], new: value });
+        }
+        self.ram[target] = value;
+    }
+
+    /// Stops running the game, silencing the sound. Only the first reason is kept if halted repeatedly.
+    ///
+    /// # Parameters
+    ///
+    /// * `reason` - Why the game stopped.
+    pub fn halt(&mut self, reason: HaltReason) {
+        if self.halt_reason.is_none() {
+            self.halt_reason = Some(reason);
+            self.set_audio_paused(true);
+        }
+    }
+
+    /// Ends the frame, [decrementing all timers](self.handle_timers) as they are linked to the framerate and decrease at the same rate.  
+    /// The display is drawn separately by a [renderer](crate::renderer::Renderer).
+    pub fn handle_frame(&mut self) {
+        if self.halt_reason.is_none() {
+            self.handle_timers();
+        }
+
+        if self.should_wait_for_display_refresh {
+            self.complete_draw(self.wait_for_display_refresh_data.0, self.wait_for_display_refresh_data.1, self.wait_for_display_refresh_data.2);
+            self.should_wait_for_display_refresh = false;
+        }
+        if let Some(provenance) = self.provenance.as_mut().map(Arc::make_mut) {
+            provenance.end_frame();
+        }
+        if let Some(events) = self.events.as_mut().map(Arc::make_mut) {
+            events.end_frame();
+        }
+
+        self.frame_draw_stats = core::mem::take(&mut self.draw_stats);
+        if !self.frontend.hooks.is_empty() {
+            let mut hooks = core::mem::take(&mut self.frontend.hooks);
+            hooks.run_frame(self);
+            self.frontend.hooks = hooks;
+        }
+    }
+
+    /// Decrements the timers for frames which were never run (e.g. dropped after the host stalled), as they run on wall-clock time rather than the game's.  
+    /// Without this, the sound would keep playing through the stall and the beep would run long.
+    ///
+    /// # Parameters
+    ///
+    /// * `frames` - The number of frames which were dropped.
+    pub fn handle_dropped_frames(&mut self, frames: u32) {
+        if self.halt_reason.is_none() {
+            self.decrement_timers(u8::try_from(frames).unwrap_or(u8::MAX));
+        }
+    }
+
+    /// Decrements all timers.  
+    /// If the sound timer has just hit 0, stop playing the audio.
+    fn handle_timers(&mut self) {
+        self.decrement_timers(1);
+    }
+
+    /// Decrements all timers by the provided number of ticks, stopping at 0.  
+    /// If the sound timer has just hit 0, stop playing the audio.
+    ///
+    /// # Parameters
+    ///
+    /// * `ticks` - The number of 60Hz ticks which have passed.
+    fn decrement_timers(&mut self, ticks: u8) {
+        let (old_delay_timer, old_sound_timer) = (self.delay_timer, self.sound_timer);
+        self.sound_timer = self.sound_timer.saturating_sub(ticks);
+        self.delay_timer = self.delay_timer.saturating_sub(ticks);
+        if let Some(events) = self.events.as_mut().map(Arc::make_mut) {
+            events.record_timers((old_delay_timer, self.delay_timer), (old_sound_timer, self.sound_timer));
+        }
+
+        if old_sound_timer != 0 && self.sound_timer == 0 {
+            frame_log::event(Subsystem::Audio, "Sound stopped");
+            self.set_audio_status();
+        }
+    }
+
+    /// Either pause or resume the audio based on the status of the sound timer.  
+    /// Sound should only play when the timer is > 0 and the interpreter is not [muted](Interpreter::set_muted).
+    fn set_audio_status(&self) {
+        if let Some(audio) = &self.frontend.audio {
+            if let Err(e) = audio.set_gate(PRIMARY_VOICE, self.sound_timer > 0 && !self.frontend.is_muted) {
+                frame_log::warn(Subsystem::Audio, format_args!("Error setting audio: {e}"));
+            }
+        }
+    }
+
+    /// Sends the XO-CHIP sample pattern and pitch to the audio, or the default tone if the game has not loaded a pattern.
+    fn set_audio_tone(&self) {
+        if let Some(audio) = &self.frontend.audio {
+            let frequency = self.audio_pattern.map_or(DEFAULT_FREQUENCY, |_| audio::pattern_frequency(self.pitch));
+            let result = audio.send(AudioCommand::SetPattern(PRIMARY_VOICE, self.audio_pattern))
+                .and_then(|()| audio.send(AudioCommand::SetFrequency(PRIMARY_VOICE, frequency)));
+            if let Err(e) = result {
+                frame_log::warn(Subsystem::Audio, format_args!("Error setting audio: {e}"));
+            }
+        }
+    }
+
+    /// Silences the sound (without touching the sound timer) while the emulation is not running, or restores it based on the sound timer once it is.
+    ///
+    /// # Parameters
+    ///
+    /// * `is_paused` - True if the emulation has stopped running frames, false if it has resumed.
+    pub fn set_audio_paused(&self, is_paused: bool) {
+        if !is_paused {
+            self.set_audio_status();
+        } else if let Some(audio) = &self.frontend.audio {
+            if let Err(e) = audio.set_gate(PRIMARY_VOICE, false) {
+                frame_log::warn(Subsystem::Audio, format_args!("Error setting audio: {e}"));
+            }
+        }
+    }
+
+    /// Silences the sound while muted (e.g. while fast-forwarding), without touching the sound timer.  
+    /// Unmuting leaves the sound off until it is next updated, so callers should [update it](Interpreter::set_audio_paused) afterwards.
+    ///
+    /// # Parameters
+    ///
+    /// * `is_muted` - True to mute the sound, false to let it play again.
+    pub fn set_muted(&mut self, is_muted: bool) {
+        self.frontend.is_muted = is_muted;
+        if is_muted {
+            self.set_audio_status();
+        }
+    }
+
+    /// Returns a copy of the emulated hardware, which can later be [restored](Interpreter::restore).
+    #[must_use]
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            halt_reason: self.halt_reason,
+            ram: self.ram,
+            registers: self.registers,
+            register_i: self.register_i,
+            delay_timer: self.delay_timer,
+            sound_timer: self.sound_timer,
+            program_counter: self.program_counter,
+            stack_pointer: self.stack_pointer,
+            stack: self.stack,
+            should_wait_for_key: self.should_wait_for_key,
+            wait_for_key_register: self.wait_for_key_register,
+            should_wait_for_display_refresh: self.should_wait_for_display_refresh,
+            wait_for_display_refresh_data: self.wait_for_display_refresh_data,
+            busy_cycles: self.busy_cycles,
+            drawing_buffer: self.drawing_buffer,
+            second_plane_buffer: self.second_plane_buffer,
+            selected_planes: self.selected_planes,
+            is_high_resolution: self.is_high_resolution,
+            is_two_page_display: self.is_two_page_display,
+            audio_pattern: self.audio_pattern,
+            pitch: self.pitch,
+            rng: self.rng.clone()
+        }
+    }
+
+    /// Returns the emulated hardware to the state stored in the snapshot, taking effect on the next frame.  
+    /// The random numbers continue from the snapshot as well, so the game plays out the same way from that point for the same inputs.  
+    /// The sound is left as is, so callers should [update it](Interpreter::set_audio_paused) afterwards.
+    ///
+    /// # Parameters
+    ///
+    /// * `snapshot` - The state to return to.
+    pub fn restore(&mut self, snapshot: &Snapshot) {
+        self.halt_reason = snapshot.halt_reason;
+        self.ram = snapshot.ram;
+        self.registers = snapshot.registers;
+        self.register_i = snapshot.register_i;
+        self.delay_timer = snapshot.delay_timer;
+        self.sound_timer = snapshot.sound_timer;
+        self.program_counter = snapshot.program_counter;
+        self.stack_pointer = snapshot.stack_pointer;
+        self.stack = snapshot.stack;
+        self.should_wait_for_key = snapshot.should_wait_for_key;
+        self.wait_for_key_register = snapshot.wait_for_key_register;
+        self.should_wait_for_display_refresh = snapshot.should_wait_for_display_refresh;
+        self.wait_for_display_refresh_data = snapshot.wait_for_display_refresh_data;
+        self.busy_cycles = snapshot.busy_cycles;
+        self.drawing_buffer = snapshot.drawing_buffer;
+        self.second_plane_buffer = snapshot.second_plane_buffer;
+        self.selected_planes = snapshot.selected_planes;
+        self.is_high_resolution = snapshot.is_high_resolution;
+        self.is_two_page_display = snapshot.is_two_page_display;
+        self.has_display_changed = true;
+        self.audio_pattern = snapshot.audio_pattern;
+        self.pitch = snapshot.pitch;
+        self.set_audio_tone();
+        self.rng = snapshot.rng.clone();
+        self.draw_stats = DrawStats::default();
+        self.frame_draw_stats = DrawStats::default();
+    }
+
+    /// Returns an independent copy of the interpreter without the display or audio, so that tools can branch execution from a common state (e.g. to try out quirks or inputs).  
+    /// Unlike a [snapshot](Interpreter::snapshot), everything else is kept, including the held keys, the quirks, and the coverage. The hardware is stored in fixed-size arrays with the display packed into bits, and the recordings (e.g. the coverage or event log) and symbols are shared until either interpreter changes them, so forking copies little and allocates less.
+    #[must_use]
+    pub fn fork(&self) -> Interpreter {
+        self.clone()
+    }
+
+    /// Returns the enabled/disabled status of all the quirks.
+    #[must_use]
+    pub fn quirk_config(&self) -> &QuirkConfig {
+        &self.quirk_config
+    }
+
+    /// Changes the enabled/disabled status of the quirks, taking effect from the next instruction.
+    ///
+    /// # Parameters
+    ///
+    /// * `quirk_config` - The new quirk settings.
+    pub fn set_quirk_config(&mut self, quirk_config: QuirkConfig) {
+        self.quirk_config = quirk_config;
+    }
+
+    /// Returns the contents of the RAM.
+    #[must_use]
+    pub fn ram(&self) -> &[u8] {
+        &self.ram
+    }
+
+    /// Overwrites the RAM starting at the provided address, e.g. to patch a running game.
+    ///
+    /// # Parameters
+    ///
+    /// * `address` - The address of the first byte to write.
+    /// * `bytes` - The bytes to write.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` without writing anything if the bytes would run past the end of the RAM.
+    pub fn write_ram(&mut self, address: u16, bytes: &[u8]) -> Result<(), String> {
+        let start = usize::from(address);
+        let target = self.ram.get_mut(start..start + bytes.len())
+            .ok_or_else(|| format!("Cannot write {} byte(s) at {address:#05X}, past the end of the RAM.", bytes.len()))?;
+        target.copy_from_slice(bytes);
+
+        Ok(())
+    }
+
+    /// Sets the value of a general purpose register, e.g. to test a fix while the game is paused.
+    ///
+    /// # Parameters
+    ///
+    /// * `register` - The register to set, from `0x0` to `0xF`.
+    /// * `value` - The value to set it to.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the register is past VF.
+    pub fn set_register(&mut self, register: usize, value: u8) {
+        self.registers[register] = value;
+    }
+
+    /// Sets the value of register I.
+    ///
+    /// # Parameters
+    ///
+    /// * `value` - The value to set it to.
+    pub fn set_register_i(&mut self, value: u16) {
+        self.register_i = value;
+    }
+
+    /// Sets the address of the next instruction to run.
+    ///
+    /// # Parameters
+    ///
+    /// * `address` - The address of the instruction.
+    pub fn set_program_counter(&mut self, address: u16) {
+        self.program_counter = address;
+    }
+
+    /// Sets the value of the delay timer.
+    ///
+    /// # Parameters
+    ///
+    /// * `value` - The value to set it to.
+    pub fn set_delay_timer_value(&mut self, value: u8) {
+        self.delay_timer = value;
+    }
+
+    /// Sets the value of the sound timer, starting or stopping the sound to match.
+    ///
+    /// # Parameters
+    ///
+    /// * `value` - The value to set it to.
+    pub fn set_sound_timer_value(&mut self, value: u8) {
+        self.sound_timer = value;
+        self.set_audio_status();
+    }
+
+    /// Returns the general purpose registers V0 through VF.
+    #[must_use]
+    pub fn registers(&self) -> &[u8; REGISTERS_SIZE] {
+        &self.registers
+    }
+
+    /// Returns the value of register I.
+    #[must_use]
+    pub fn register_i(&self) -> u16 {
+        self.register_i
+    }
+
+    /// Returns the address of the next instruction to run.
+    #[must_use]
+    pub fn program_counter(&self) -> u16 {
+        self.program_counter
+    }
+
+    /// Returns the return addresses on the stack, the oldest first.
+    #[must_use]
+    pub fn stack(&self) -> &[u16] {
+        &self.stack[..self.stack_pointer]
+    }
+
+    /// Returns the subroutine calls on the stack as a backtrace, the most recent first.  
+    /// Each frame's call instruction sits just before the address it returns to, as calls are never the 4-byte XO-CHIP long load.
+    #[must_use]
+    pub fn call_stack(&self) -> Vec<StackFrame> {
+        self.stack().iter().rev().map(|&return_address| {
+            let call_address = return_address.wrapping_sub(PROGRAM_COUNTER_INCREMENT);
+            let start = usize::from(call_address);
+            let opcode = self.ram.get(start..start + 2).map_or(0, |bytes| u16::from_be_bytes([bytes[0], bytes[1]]));
+            StackFrame { call_address, return_address, opcode }
+        }).collect()
+    }
+
+    /// Returns the value of the delay timer.
+    #[must_use]
+    pub fn delay_timer(&self) -> u8 {
+        self.delay_timer
+    }
+
+    /// Returns the value of the sound timer.
+    #[must_use]
+    pub fn sound_timer(&self) -> u8 {
+        self.sound_timer
+    }
+
+    /// Returns the pixels of the display row by row, with `true` for pixels which are on.  
+    /// The display is [`display_width`](Interpreter::display_width) pixels wide, which depends on the resolution.  
+    /// Only the first XO-CHIP plane is included, which is the whole display for other games.
+    pub fn display_buffer(&self) -> impl ExactSizeIterator<Item = bool> + '_ {
+        self.framebuffer().pixels()
+    }
+
+    /// Returns the pixels of the second XO-CHIP plane row by row, laid out as the [display buffer](Interpreter::display_buffer).
+    pub fn second_plane_buffer(&self) -> impl ExactSizeIterator<Item = bool> + '_ {
+        self.framebuffer().second_plane()
+    }
+
+    /// Returns a view of the display's pixels in both planes along with its size, for rendering the game with another graphics stack.
+    #[must_use]
+    pub fn framebuffer(&self) -> Framebuffer<'_> {
+        Framebuffer::new(self.display_width(), self.display_height(), &self.drawing_buffer, &self.second_plane_buffer)
+    }
+
+    /// Returns whether the display may have changed since it was last [taken](Interpreter::take_display_changed), from a draw, clear, scroll, resolution switch, or restored state.
+    #[must_use]
+    pub fn has_display_changed(&self) -> bool {
+        self.has_display_changed
+    }
+
+    /// Returns whether the display may have changed since the last call, so that renderers can skip frames in which nothing was drawn.
+    pub fn take_display_changed(&mut self) -> bool {
+        core::mem::take(&mut self.has_display_changed)
+    }
+
+    /// Returns whether the SUPER-CHIP high resolution mode is on.
+    #[must_use]
+    pub fn is_high_resolution(&self) -> bool {
+        self.is_high_resolution
+    }
+
+    /// Returns whether the HiRes CHIP-8 64x64 display is on, which is drawn as two pages of the original display stacked on top of each other.
+    #[must_use]
+    pub fn is_two_page_display(&self) -> bool {
+        self.is_two_page_display
+    }
+
+    /// Returns the width of the display in pixels: 128 in high resolution, 64 otherwise.
+    #[must_use]
+    pub fn display_width(&self) -> u32 {
+        if self.is_high_resolution { HIGH_RESOLUTION_SCREEN_WIDTH } else { SCREEN_WIDTH }
+    }
+
+    /// Returns the height of the display in pixels: 64 in high resolution or on the [two page display](Interpreter::is_two_page_display), 32 otherwise.
+    #[must_use]
+    pub fn display_height(&self) -> u32 {
+        if self.is_high_resolution {
+            HIGH_RESOLUTION_SCREEN_HEIGHT
+        } else if self.is_two_page_display {
+            TWO_PAGE_SCREEN_HEIGHT
+        } else {
+            SCREEN_HEIGHT
+        }
+    }
+
+    /// Returns the CHIP-8 keys currently held down as a bitmask, with bit N set if key N is pressed.
+    #[must_use]
+    pub fn pressed_keys(&self) -> u16 {
+        self.keyboard
+    }
+
+    /// Returns the number of sprites drawn during the most recent frame.
+    #[must_use]
+    pub fn frame_draw_count(&self) -> u32 {
+        self.frame_draw_stats.draws
+    }
+
+    /// Returns how the draw opcodes of the most recent frame went, including those which stalled waiting for the display.
+    #[must_use]
+    pub fn frame_draw_stats(&self) -> DrawStats {
+        self.frame_draw_stats
+    }
+
+    /// Returns how much of each kind of work the interpreter has done since the game was loaded.
+    #[must_use]
+    pub fn counters(&self) -> Counters {
+        self.counters
+    }
+
+    /// Conditionally resets register F based on the status of the [reset quirk](ResetVfQuirk).
+    fn handle_reset_quirk(&mut self) {
+        match self.quirk_config.reset_vf {
+            ResetVfQuirk::Reset => { self.registers[REGISTER_F] = 0x0; }
+            ResetVfQuirk::NoReset => {}
+        }
+    }
+
+    /// Conditionally increments register I based on the status of the [memory increment quirk](MemoryIncrementQuirk). 
+    fn handle_memory_increment_quirk(&mut self) {
+        match self.quirk_config.memory {
+            MemoryIncrementQuirk::Increment => { self.register_i = self.register_i.wrapping_add(1); }
+            MemoryIncrementQuirk::NoIncrement => {}
+        }
+    }
+
+    /// Processes the provided opcode, handing off to the appropriate method.
+    ///
+    /// # Parameters
+    ///
+    /// * `opcode` - A reference to the opcode to process.
+    fn handle_opcode(&mut self, opcode: &Opcode) {
+        match opcode {
+            Opcode::ClearScreen => self.clear_screen(),
+            Opcode::ScrollDown(rows) => self.scroll_down(*rows),
+            Opcode::ScrollRight => self.scroll_right(),
+            Opcode::ScrollLeft => self.scroll_left(),
+            Opcode::LongLoadRegisterI(address) => self.long_load_register_i(*address),
+            Opcode::SelectPlanes(planes) => self.select_planes(*planes),
+            Opcode::LoadAudioPattern => self.load_audio_pattern(),
+            Opcode::SetPitch(register) => self.set_pitch(*register),
+            Opcode::StoreRplFlags(register) => self.store_rpl_flags(*register),
+            Opcode::LoadRplFlags(register) => self.load_rpl_flags(*register),
+            Opcode::LowResolution => self.set_high_resolution(false),
+            Opcode::HighResolution => self.set_high_resolution(true),
+            Opcode::Return => self.return_from_subroutine(),
+            Opcode::JumpAddr(address) => self.jump_addr(*address),
+            Opcode::Exit | Opcode::SystemAddr(EXIT_ADDRESS) => self.halt(HaltReason::Exit),
+            Opcode::SystemAddr(address) | Opcode::CallAddr(address) => self.call_addr(*address),
+            Opcode::SkipRegisterEqualsValue(register, value) => self.skip_register_equals_value(*register, *value),
+            Opcode::SkipRegisterNotEqualsValue(register, value) => self.skip_register_not_equals_value(*register, *value),
+            Opcode::SkipRegistersEqual(first_register, second_register) => self.skip_registers_equal(*first_register, *second_register),
+            Opcode::LoadValue(register, value) => self.load_value(*register, *value),
+            Opcode::AddValue(register, value) => self.add_value(*register, *value),
+            Opcode::LoadRegisterValue(first_register, second_register) => self.load_register_value(*first_register, *second_register),
+            Opcode::Or(first_register, second_register) => self.or(*first_register, *second_register),
+            Opcode::And(first_register, second_register) => self.and(*first_register, *second_register),
+            Opcode::Xor(first_register, second_register) => self.xor(*first_register, *second_register),
+            Opcode::AddRegisters(first_register, second_register) => self.add_registers(*first_register, *second_register),
+            Opcode::SubtractFromFirstRegister(first_register, second_register) => self.bounded_subtraction(*first_register, *second_register, *first_register),
+            Opcode::BitShiftRight(first_register, second_register) => self.bit_shift_right(*first_register, *second_register),
+            Opcode::SubtractFromSecondRegister(first_register, second_register) => self.bounded_subtraction(*second_register, *first_register, *first_register),
+            Opcode::BitShiftLeft(first_register, second_register) => self.bit_shift_left(*first_register, *second_register),
+            Opcode::SkipRegistersNotEqual(first_register, second_register) => self.skip_registers_not_equal(*first_register, *second_register),
+            Opcode::LoadRegisterI(address) => self.load_register_i(*address),
+            Opcode::JumpAddrV0(address) => self.jump_address_v0(*address),
+            Opcode::Random(register, value) => self.random(*register, *value),
+            Opcode::Draw(first_register, second_register, length) => {
+                match self.quirk_config.display_wait {
+                    DisplayWaitQuirk::Wait => self.draw(*first_register, *second_register, *length),
+                    DisplayWaitQuirk::NoWait => self.complete_draw(*first_register, *second_register, *length)
+                }
+            },
+            Opcode::SkipKeyPressed(register) => self.skip_key_pressed(*register),
+            Opcode::SkipKeyNotPressed(register) => self.skip_key_not_pressed(*register),
+            Opcode::LoadDelayTimer(register) => self.load_delay_timer(*register),
+            Opcode::LoadKeyPress(register) => self.load_key_press(*register),
+            Opcode::SetDelayTimer(register) => self.set_delay_timer(*register),
+            Opcode::SetSoundTimer(register) => self.set_sound_timer(*register),
+            Opcode::AddRegisterI(register) => self.add_register_i(*register),
+            Opcode::SetIHexSpriteLocation(register) => self.set_register_i_hex_sprite_location(*register),
+            Opcode::SetILargeHexSpriteLocation(register) => self.set_register_i_large_hex_sprite_location(*register),
+            Opcode::BinaryCodedDecimal(register) => self.binary_coded_decimal(*register),
+            Opcode::StoreRegisters(register) => self.store_registers(*register),
+            Opcode::LoadRegisters(register) => self.load_registers(*register)
+        }
+    }
+
+    /// Handles the [`JumpAddr`](Opcode::JumpAddr) opcode, setting to the program counter to the provided address.  
+    /// Equivalent to: `PC = nnn`
+    ///
+    /// # Parameters
+    ///
+    /// * `address` - The address to which we want to jump.
+    fn jump_addr(&mut self, address: u16) {
+        self.program_counter = address;
+    }
+
+    /// Handles the [`SkipRegisterEqualsValue`](Opcode::SkipRegisterEqualsValue) opcode, skipping the next instruction if the provided register contains the provided value.  
+    /// Equivalent to: `if Vx == kk`
+    ///
+    /// # Parameters
+    ///
+    /// * `register` - The register whose value we want to check.
+    /// * `value` - The value against which to check.
+    fn skip_register_equals_value(&mut self, register: usize, value: u8) {
+        if self.registers[register] == value {
+            self.skip_instruction();
+        }
+    }
+
+    /// Handles the [`SkipRegisterNotEqualsValue`](Opcode::SkipRegisterNotEqualsValue) opcode, skipping the next instruction if the provided register doesn't contain the provided value.  
+    /// Equivalent to: `if Vx != kk`
+    ///
+    /// # Parameters
+    ///
+    /// * `register` - The register whose value we want to check.
+    /// * `value` - The value against which to check.
+    fn skip_register_not_equals_value(&mut self, register: usize, value: u8) {
+        if self.registers[register] != value {
+            self.skip_instruction();
+        }
+    }
+
+    /// Handles the [`SkipRegistersEqual`](Opcode::SkipRegistersEqual) opcode, skipping the next instruction if the values of the provided registers match.  
+    /// Equivalent to: `if Vx == Vy`
+    ///
+    /// # Parameters
+    ///
+    /// * `first_register` - The first register whose value we want to check.
+    /// * `second_register` - The second register against which to check.
+    fn skip_registers_equal(&mut self, first_register: usize, second_register: usize) {
+        if self.registers[first_register] == self.registers[second_register] {
+            self.skip_instruction();
+        }
+    }
+
+    /// Handles the [`SkipRegistersNotEqual`](Opcode::SkipRegistersNotEqual) opcode, skipping the next instruction if the values of the provided registers do not match.  
+    /// Equivalent to: `if Vx != Vy`
+    ///
+    /// # Parameters
+    ///
+    /// * `first_register` - The first register whose value we want to check.
+    /// * `second_register` - The second register against which to check.
+    fn skip_registers_not_equal(&mut self, first_register: usize, second_register: usize) {
+        if self.registers[first_register] != self.registers[second_register] {
+            self.skip_instruction();
+        }
+    }
+
+    /// Handles the [`LoadValue`](Opcode::LoadValue) opcode, placing the provided value into the provided register.  
+    /// Equivalent to: `Vx = kk`
+    ///
+    /// # Parameters
+    ///
+    /// * `register` - The register into which we will place the value.
+    /// * `value` - The value to place into the register.
+    fn load_value(&mut self, register: usize, value: u8) {
+        self.registers[register] = value;
+    }
+
+    /// Handles the [`AddValue`](Opcode::AddValue) opcode, adding the provided value to the that of the provided register, storing it back into that register.  
+    /// Equivalent to: `Vx += kk`
+    ///
+    /// # Parameters
+    ///
+    /// * `register` - The register into which we will place the sum.
+    /// * `value` - The value to add.
+    fn add_value(&mut self, register: usize, value: u8) {
+        self.registers[register] = ((u16::from(self.registers[register]) + u16::from(value)) & BYTE_MASK) as u8;
+    }
+
+    /// Handles the [`LoadRegisterValue`](Opcode::LoadRegisterValue) opcode, placing the value of the second register into the first.  
+    /// Equivalent to: `Vx = Vy`
+    ///
+    /// # Parameters
+    ///
+    /// * `first_register` - The register into which we will place the value.
+    /// * `second_register` - The register from which we will read the value.
+    fn load_register_value(&mut self, first_register: usize, second_register: usize) {
+        self.registers[first_register] = self.registers[second_register];
+    }
+
+    /// Handles the [`Or`](Opcode::Or) opcode, performing a binary OR operation on two registers and placing the result into the first.  
+    /// Equivalent to: `Vx |= Vy`
+    ///
+    /// # Parameters
+    ///
+    /// * `first_register` - The register into which we will place the result of the OR.
+    /// * `second_register` - The register with which to OR the value.
+    fn or(&mut self, first_register: usize, second_register: usize) {
+        self.registers[first_register] |= self.registers[second_register];
+        self.handle_reset_quirk();
+    }
+
+    /// Handles the [`And`](Opcode::And) opcode, performing a binary OR operation on two registers and placing the result into the first.  
+    /// Equivalent to: `Vx &= Vy`
+    ///
+    /// # Parameters
+    ///
+    /// * `first_register` - The register into which we will place the result of the OR.
+    /// * `second_register` - The register with which to OR the value.
+    fn and(&mut self, first_register: usize, second_register: usize) {
+        self.registers[first_register] &= self.registers[second_register];
+        self.handle_reset_quirk();
+    }
+
+    /// Handles the [`Xor`](Opcode::Xor) opcode, performing a binary XOR operation on two registers and placing the result into the first.  
+    /// Equivalent to: `Vx ^= Vy`
+    ///
+    /// # Parameters
+    ///
+    /// * `first_register` - The register into which we will place the result of the XOR.
+    /// * `second_register` - The register with which to XOR the value.
+    fn xor(&mut self, first_register: usize, second_register: usize) {
+        self.registers[first_register] ^= self.registers[second_register];
+        self.handle_reset_quirk();
+    }
+
+    /// Handles the [`Random`](Opcode::Random) opcode, performing a binary AND between the provided value and a randomly generated byte, placing the result in the provided register.  
+    /// Equivalent to: `Vx = rand() & kk`
+    ///
+    /// # Parameters
+    ///
+    /// * `register` - The register into which we will place the result.
+    /// * `value` - The value with which to AND the random value.
+    fn random(&mut self, register: usize, value: u8) {
+        let random_byte: u8 = self.rng.gen();
+        self.registers[register] = random_byte & value;
+    }
+
+    /// Handles the [`StoreRegisters`](Opcode::StoreRegisters) opcode, storing the values of the registers up to and including the provided one in memory at the address of register I.  
+    /// Equivalent to: `reg_dump(Vx, &I)`
+    ///
+    /// # Parameters
+    ///
+    /// * `register` - The register up to and including which we wish to store.
+    fn store_registers(&mut self, register: usize) {
+        for i in 0..=register {
+            let index_adjustment = match self.quirk_config.memory {
+                MemoryIncrementQuirk::Increment => 0,
+                MemoryIncrementQuirk::NoIncrement => i
+            };
+
+            #[allow(clippy::cast_possible_truncation)]
+            self.write_memory(usize::from(self.register_i.wrapping_add(index_adjustment as u16)), self.registers[i]);
+            self.handle_memory_increment_quirk();
+        }
+        #[allow(clippy::cast_possible_truncation)]
+        self.handle_memory_timing_quirk(register as u32 + 1);
+    }
+
+    /// Handles the [`StoreRplFlags`](Opcode::StoreRplFlags) opcode, storing the values of the registers up to and including the provided one in the RPL flags.  
+    /// Only the first 8 registers have a flag, so any beyond those are ignored.  
+    /// Equivalent to: `save_flags(Vx)`
+    ///
+    /// # Parameters
+    ///
+    /// * `register` - The register up to and including which we wish to store.
+    fn store_rpl_flags(&mut self, register: usize) {
+        let count = (register + 1).min(RPL_FLAGS_SIZE);
+        self.rpl_flags[..count].copy_from_slice(&self.registers[..count]);
+        self.have_rpl_flags_changed = true;
+    }
+
+    /// Handles the [`LoadRplFlags`](Opcode::LoadRplFlags) opcode, loading the values of the registers up to and including the provided one from the RPL flags.  
+    /// Only the first 8 registers have a flag, so any beyond those are left as is.  
+    /// Equivalent to: `load_flags(Vx)`
+    ///
+    /// # Parameters
+    ///
+    /// * `register` - The register up to and including which we wish to load.
+    fn load_rpl_flags(&mut self, register: usize) {
+        let count = (register + 1).min(RPL_FLAGS_SIZE);
+        self.registers[..count].copy_from_slice(&self.rpl_flags[..count]);
+    }
+
+    /// Handles the [`LoadRegisters`](Opcode::LoadRegisters) opcode, loading the values of the registers up to and including the provided one from memory at the address of register I.  
+    /// Equivalent to: `reg_load(Vx, &I)`
+    ///
+    /// # Parameters
+    ///
+    /// * `register` - The register up to and including which we wish to load.
+    fn load_registers(&mut self, register: usize) {
+        for i in 0..=register {
+            let index_adjustment = match self.quirk_config.memory {
+                MemoryIncrementQuirk::Increment => 0,
+                MemoryIncrementQuirk::NoIncrement => i
+            };
+
+            #[allow(clippy::cast_possible_truncation)]
+            let address = self.register_i.wrapping_add(index_adjustment as u16);
+            self.registers[i] = self.ram[usize::from(address)];
+            self.handle_memory_increment_quirk();
+        }
+        #[allow(clippy::cast_possible_truncation)]
+        self.handle_memory_timing_quirk(register as u32 + 1);
+    }
+
+    /// Handles the [`LoadRegisterI`](Opcode::LoadRegisterI) opcode, placing the provided address into register I.  
+    /// Equivalent to: `I = nnn`
+    ///
+    /// # Parameters
+    ///
+    /// * `address` - The address to place into register I.
+    fn load_register_i(&mut self, address: u16) {
+        self.register_i = address;
+    }
+
+    /// Handles the [`JumpAddrV0`](Opcode::JumpAddrV0) opcode, setting the program counter to the provided address added to the value stored in V0 (or VX depending on the status of the [`JumpingQuirk`](JumpingQuirk)).  
+    /// Equivalent to: `PC = V0 + nnn` or `PC = Vx + nnn`
+    ///
+    /// # Parameters
+    ///
+    /// * `address` - The address to use in the addition.
+    fn jump_address_v0(&mut self, address: u16) {
+        let target_register = match self.quirk_config.jumping {
+            JumpingQuirk::V0 => 0,
+            JumpingQuirk::Vx => (address & 0xF00) >> 0x8
+        };
+        self.jump_addr(address + u16::from(self.registers[target_register as usize]));
+    }
+
+    /// Handles the [`LoadDelayTimer`](Opcode::LoadDelayTimer) opcode, placing the value of the delay timer into the provided register.  
+    /// Equivalent to: `Vx = get_delay()`
+    ///
+    /// # Parameters
+    ///
+    /// * `register` - The register into which to store.
+    fn load_delay_timer(&mut self, register: usize) {
+        self.registers[register] = self.delay_timer;
+    }
+
+    /// Handles the [`SetDelayTimer`](Opcode::SetDelayTimer) opcode, placing the value of the provided register into the delay timer.  
+    /// Equivalent to: `delay_timer(Vx)`
+    ///
+    /// # Parameters
+    ///
+    /// * `register` - The register from which to read the value.
+    fn set_delay_timer(&mut self, register: usize) {
+        self.delay_timer = self.registers[register];
+    }
+
+    /// Handles the [`SetSoundTimer`](Opcode::SetSoundTimer) opcode, placing the value of the provided register into the sound timer.  
+    /// This will also resume audio playback if the value is sufficiently large (see [`set_sound_timer`](self.set_sound_timer)).  
+    /// Equivalent to: `sound_timer(Vx)`
+    ///
+    /// # Parameters
+    ///
+    /// * `register` - The register from which to read the value.
+    fn set_sound_timer(&mut self, register: usize) {
+        match (self.sound_timer, self.registers[register]) {
+            (0, 0) => {},
+            (0, ticks) => frame_log::event(Subsystem::Audio, format_args!("Sound started for {ticks} frames")),
+            (_, 0) => frame_log::event(Subsystem::Audio, "Sound stopped"),
+            _ => {}
+        }
+        self.sound_timer = self.registers[register];
+        self.set_audio_status();
+    }
+
+    /// Handles the [`AddRegisterI`](Opcode::AddRegisterI) opcode, adding the value of the provided register to register I.  
+    /// Equivalent to: `I += Vx`
+    ///
+    /// # Parameters
+    ///
+    /// * `register` - The register from which to read the value.
+    fn add_register_i(&mut self, register: usize) {
+        self.register_i = self.register_i.wrapping_add(u16::from(self.registers[register]));
+    }
+
+    /// Handles the [`AddRegisters`](Opcode::AddRegisters) opcode, adding the values of the provided registers together and storing the result in the first.  
+    /// This will set register F to 1 in the case of an overflow, and 0 otherwise.  
+    /// Equivalent to: `Vx += Vy`
+    ///
+    /// # Parameters
+    ///
+    /// * `first_register` - The register into which to store the result.
+    /// * `second_register` - The register which contains the value to add.
+    #[allow(clippy::cast_possible_truncation)]
+    fn add_registers(&mut self, first_register: usize, second_register: usize) {
+        let sum: u16 = u16::from(self.registers[first_register]) + u16::from(self.registers[second_register]);
+        let max_u8 = BYTE_MASK;
+        self.registers[first_register] = (sum & max_u8) as u8;
+        self.registers[REGISTER_F] = u8::from(sum > max_u8);
+    }
+
+    /// Handles the [`SubtractFromFirstRegister`](Opcode::SubtractFromSecondRegister) and [`SubtractFromSecondRegister`](Opcode::SubtractFromSecondRegister) opcodes, subtracting the value of one register from another and storing the result in the provided register.  
+    /// This will set register F to 1 in the case of no borrow, and 0 otherwise.  
+    /// Equivalent to: `Vx -= Vy` or `Vy -= Vx`
+    ///
+    /// # Parameters
+    ///
+    /// * `minuend_register` - The register from which to subtract.
+    /// * `subtrahend_register` - The register with the value we are subtracting.
+    /// * `result_register` - The register into which to store the result.
+    fn bounded_subtraction(&mut self, minuend_register: usize, subtrahend_register: usize, result_register: usize) {
+        let (difference, did_underflow) = self.registers[minuend_register].overflowing_sub(self.registers[subtrahend_register]);
+        self.registers[result_register] = difference;
+        self.registers[REGISTER_F] = u8::from(!did_underflow);
+    }
+
+    /// Handles the [`BitShiftRight`](Opcode::BitShiftRight) opcode, performing a bitshift right of the provided register and storing the result in the first.  
+    /// This will set register F to 1 in the case of a bit running off, and 0 otherwise.
+    /// The register which holds the value to shift will depend on the status of the [shifting quirk](ShiftingQuirk).  
+    /// Equivalent to: `Vx >>= 1` or `Vx = Vy >> 1`
+    ///
+    /// # Parameters
+    ///
+    /// * `first_register` - The register into which to store the result (and possibly the value to shift).
+    /// * `second_register` - The register which possibly contains the value to shift.
+    fn bit_shift_right(&mut self, first_register: usize, second_register: usize) {
+        let target_shift_register = match self.quirk_config.shifting {
+            ShiftingQuirk::Vy => second_register,
+            ShiftingQuirk::Vx => first_register
+        };
+        let will_bit_run_off = u8::from((self.registers[target_shift_register] & LEAST_SIGNIFICANT_BIT_MASK) == 0x1);
+        self.registers[first_register] = self.registers[target_shift_register] >> 0x1;
+        self.registers[REGISTER_F] = will_bit_run_off;
+    }
+
+    /// Handles the [`BitShiftLeft`](Opcode::BitShiftLeft) opcode, performing a bitshift left of the provided register and storing the result in the first.  
+    /// This will set register F to 1 in the case of a bit running off, and 0 otherwise.
+    /// The register which holds the value to shift will depend on the status of the [shifting quirk](ShiftingQuirk).  
+    /// Equivalent to: `Vx <<= 1` or `Vx = Vy << 1`
+    ///
+    /// # Parameters
+    ///
+    /// * `first_register` - The register into which to store the result (and possibly the value to shift).
+    /// * `second_register` - The register which possibly contains the value to shift.
+    fn bit_shift_left(&mut self, first_register: usize, second_register: usize) {
+        let target_shift_register = match self.quirk_config.shifting {
+            ShiftingQuirk::Vy => second_register,
+            ShiftingQuirk::Vx => first_register
+        };
+        let will_bit_run_off = u8::from(((self.registers[target_shift_register] & MOST_SIGNIFICANT_BIT_MASK) >> 7) == 0x1);
+        self.registers[first_register] = self.registers[target_shift_register] << 0x1;
+        self.registers[REGISTER_F] = will_bit_run_off;
+    }
+
+    /// Handles the [`BinaryCodedDecimal`](Opcode::BinaryCodedDecimal) opcode, storing the binary coded decimal (digits of the value in decimal) into memory starting at the address stored in register I.  
+    /// Equivalent to: `*(I) = BCD(3); *(I+1) = BCD(2); *(I+2) = BCD(1);`
+    ///
+    /// # Parameters
+    ///
+    /// * `register` - The register which holds the value we wish to store.
+    fn binary_coded_decimal(&mut self, register: usize) {
+        let mut value = self.registers[register];
+
+        for i in (0..=2).rev() {
+            self.write_memory(usize::from(self.register_i.wrapping_add(i)), value % 10);
+            value /= 10;
+        }
+        self.handle_memory_timing_quirk(3);
+    }
+
+    /// Keeps the interpreter busy for the rest of an opcode's cycles if the [memory timing quirk](MemoryTimingQuirk) is enabled.
+    ///
+    /// # Parameters
+    ///
+    /// * `cycles` - The number of instruction cycles the opcode takes, including the one it ran in.
+    fn handle_memory_timing_quirk(&mut self, cycles: u32) {
+        if self.quirk_config.memory_timing == MemoryTimingQuirk::PerRegister {
+            self.busy_cycles = cycles.saturating_sub(1);
+        }
+    }
+
+    /// Handles the [`CallAddr`](Opcode::CallAddr) opcode, calling the subroutine at the provided address.  
+    /// The current address is stored onto the stack for returning, halting if the stack is full.  
+    /// Equivalent to: `*(0xnnn)()`
+    ///
+    /// # Parameters
+    ///
+    /// * `address` - The address of the target subroutine.
+    fn call_addr(&mut self, address: u16) {
+        if self.stack_pointer == STACK_SIZE {
+            self.halt(HaltReason::StackOverflow { address: self.program_counter.wrapping_sub(PROGRAM_COUNTER_INCREMENT) });
+            return;
+        }
+
+        self.stack[self.stack_pointer] = self.program_counter;
+        self.stack_pointer += 1;
+        self.program_counter = address;
+    }
+
+    /// Handles the [`Return`](Opcode::Return) opcode, returning from the current subroutine.  
+    /// We return to the most recently stored address on the stack, halting if it is empty.  
+    /// Equivalent to: `return;`
+    fn return_from_subroutine(&mut self) {
+        if self.stack_pointer == 0 {
+            self.halt(HaltReason::StackUnderflow { address: self.program_counter.wrapping_sub(PROGRAM_COUNTER_INCREMENT) });
+            return;
+        }
+
+        self.program_counter = self.stack[self.stack_pointer - 1];
+        self.stack_pointer -= 1;
+    }
+
+    /// Handles the [`SetIHexSpriteLocation`](Opcode::SetIHexSpriteLocation) opcode, storing the address of the provided register's value's hex sprite into register I.  
+    /// Equivalent to: `I = sprite_addr[Vx]`  
+    ///
+    /// # Parameters
+    ///
+    /// * `register` - The register which contains the value whose sprite we will store.
+    fn set_register_i_hex_sprite_location(&mut self, register: usize) {
+        self.register_i = u16::from(self.registers[register] * HEXADECIMAL_DIGIT_SPRITE_LENGTH);
+    }
+
+    /// Handles the [`SetILargeHexSpriteLocation`](Opcode::SetILargeHexSpriteLocation) opcode, storing the address of the provided register's value's 10-byte high resolution hex sprite into register I.  
+    /// Equivalent to: `I = large_sprite_addr[Vx]`
+    ///
+    /// # Parameters
+    ///
+    /// * `register` - The register which contains the value whose sprite we will store.
+    fn set_register_i_large_hex_sprite_location(&mut self, register: usize) {
+        #[allow(clippy::cast_possible_truncation)]
+        let sprites_address = LARGE_HEXADECIMAL_DIGIT_SPRITES_ADDRESS as u16;
+        self.register_i = sprites_address + u16::from(self.registers[register]) * u16::from(LARGE_HEXADECIMAL_DIGIT_SPRITE_LENGTH);
+    }
+
+    /// Returns whether the keypad senses the key as pressed, which includes keys ghosted by the held ones if the [key ghosting quirk](KeyGhostingQuirk) is enabled.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The key to check, which is never sensed if it is past `0xF`.
+    fn is_key_sensed(&self, key: u8) -> bool {
+        match self.quirk_config.key_ghosting {
+            KeyGhostingQuirk::Independent => self.keyboard & key_mask(key) != 0,
+            KeyGhostingQuirk::Ghosting => key < 16 && keypad::sensed_keys(self.pressed_keys()) & (1 << key) != 0
+        }
+    }
+
+    /// Handles the [`SkipKeyPressed`](Opcode::SkipKeyPressed) opcode, skipping the next instruction if the provided key is currently pressed.  
+    /// Equivalent to: `if key() == Vx`
+    ///
+    /// # Parameters
+    ///
+    /// * `register` - The register which contains the key we are checking.
+    fn skip_key_pressed(&mut self, register: usize) {
+        if self.is_key_sensed(self.registers[register]) {
+            self.skip_instruction();
+        }
+    }
+
+    /// Handles the [`SkipKeyNotPressed`](Opcode::SkipKeyNotPressed) opcode, skipping the next instruction if the provided key is not currently pressed.  
+    /// Equivalent to: `if key() != Vx`
+    ///
+    /// # Parameters
+    ///
+    /// * `register` - The register which contains the key we are checking.
+    fn skip_key_not_pressed(&mut self, register: usize) {
+        if !self.is_key_sensed(self.registers[register]) {
+            self.skip_instruction();
+        }
+    }
+
+    /// Handles the [`LoadKeyPress`](Opcode::LoadKeyPress) opcode, halting further processing until a key is pressed (and released).  
+    /// The key which is pressed is stored into the provided register.  
+    /// Equivalent to: `Vx = get_key()`
+    ///
+    /// # Parameters
+    ///
+    /// * `register` - The register which will store the pressed key.
+    fn load_key_press(&mut self, register: usize) {
+        self.counters.key_waits += 1;
+        self.should_wait_for_key = true;
+        self.wait_for_key_register = register;
+    }
+
+    /// Handles the [`ClearScreen`](Opcode::ClearScreen) opcode, wiping all the data in the drawing buffer of the selected XO-CHIP planes.  
+    /// Note that the display is not actually updated until the refresh rate is triggered.  
+    /// Equivalent to: `disp_clear()`
+    fn clear_screen(&mut self) {
+        self.clear_planes(self.selected_planes);
+    }
+
+    /// Wipes all the data in the drawing buffers of the provided planes.
+    ///
+    /// # Parameters
+    ///
+    /// * `planes` - The planes to clear, as a mask with a bit per plane.
+    fn clear_planes(&mut self, planes: u8) {
+        for buffer in plane_buffers(&mut self.drawing_buffer, &mut self.second_plane_buffer, planes) {
+            buffer.fill(false);
+        }
+        self.has_display_changed = true;
+        self.forget_cleared_draws();
+    }
+
+    /// Forgets the draws which last changed the pixels that are now off on every plane, if draws are being [recorded](Interpreter::set_provenance_enabled).
+    fn forget_cleared_draws(&mut self) {
+        if let Some(provenance) = self.provenance.as_mut().map(Arc::make_mut) {
+            for (i, (first, second)) in self.drawing_buffer.iter().zip(self.second_plane_buffer.iter()).enumerate() {
+                if !first && !second {
+                    provenance.forget(i);
+                }
+            }
+        }
+    }
+
+    /// Handles the [`ScrollDown`](Opcode::ScrollDown) opcode, moving the display down by the provided number of pixels.  
+    /// Rows scrolled off the bottom are lost and the rows uncovered at the top are blank.  
+    /// Equivalent to: `scroll_down(n)`
+    ///
+    /// # Parameters
+    ///
+    /// * `rows` - The number of pixels to scroll by.
+    fn scroll_down(&mut self, rows: u8) {
+        self.scroll(0, usize::from(rows), true);
+    }
+
+    /// Handles the [`ScrollRight`](Opcode::ScrollRight) opcode, moving the display 4 pixels to the right.  
+    /// Equivalent to: `scroll_right()`
+    fn scroll_right(&mut self) {
+        self.scroll(HORIZONTAL_SCROLL_DISTANCE, 0, true);
+    }
+
+    /// Handles the [`ScrollLeft`](Opcode::ScrollLeft) opcode, moving the display 4 pixels to the left.  
+    /// Equivalent to: `scroll_left()`
+    fn scroll_left(&mut self) {
+        self.scroll(HORIZONTAL_SCROLL_DISTANCE, 0, false);
+    }
+
+    /// Shifts the contents of the selected XO-CHIP planes, blanking the pixels uncovered by the shift.  
+    /// Distances are in pixels of the current resolution, so a scroll covers twice as much of the screen in low resolution.  
+    /// Any [recorded draws](Interpreter::provenance) move along with their pixels.
+    ///
+    /// # Parameters
+    ///
+    /// * `columns` - The number of pixels to shift horizontally.
+    /// * `rows` - The number of pixels to shift vertically.
+    /// * `is_forward` - True to shift right and down, false to shift left and up.
+    fn scroll(&mut self, columns: usize, rows: usize, is_forward: bool) {
+        let width = self.display_width() as usize;
+        let height = self.display_height() as usize;
+        for buffer in plane_buffers(&mut self.drawing_buffer, &mut self.second_plane_buffer, self.selected_planes) {
+            shift_pixels(buffer, false, (width, height), (columns, rows), is_forward);
+        }
+        self.has_display_changed = true;
+        if let Some(provenance) = self.provenance.as_mut().map(Arc::make_mut) {
+            shift_pixels(provenance.records_mut(), None, (width, height), (columns, rows), is_forward);
+            self.forget_cleared_draws();
+        }
+    }
+
+    /// Handles the [`LowResolution`](Opcode::LowResolution) and [`HighResolution`](Opcode::HighResolution) opcodes, switching between the 64x32 and SUPER-CHIP 128x64 displays.  
+    /// Every plane is cleared, as what was drawn in one resolution would be scrambled in the other.  
+    /// Equivalent to: `low()` / `high()`
+    ///
+    /// # Parameters
+    ///
+    /// * `is_high_resolution` - True to switch to 128x64, false to switch to 64x32.
+    fn set_high_resolution(&mut self, is_high_resolution: bool) {
+        self.is_high_resolution = is_high_resolution;
+        self.clear_planes(ALL_PLANES_MASK);
+    }
+
+    /// Handles the [`LongLoadRegisterI`](Opcode::LongLoadRegisterI) opcode, setting register I to any address in the XO-CHIP's 64K of memory.  
+    /// Equivalent to: `I = nnnn`
+    ///
+    /// # Parameters
+    ///
+    /// * `address` - The address to set register I to.
+    fn long_load_register_i(&mut self, address: u16) {
+        self.register_i = address;
+    }
+
+    /// Handles the [`SelectPlanes`](Opcode::SelectPlanes) opcode, choosing which XO-CHIP planes are drawn to, cleared, and scrolled.  
+    /// Equivalent to: `plane(n)`
+    ///
+    /// # Parameters
+    ///
+    /// * `planes` - The planes to select, as a mask with a bit per plane (e.g. 3 for both).
+    fn select_planes(&mut self, planes: u8) {
+        self.selected_planes = planes & ALL_PLANES_MASK;
+    }
+
+    /// Handles the [`LoadAudioPattern`](Opcode::LoadAudioPattern) opcode, playing the 16-byte 1-bit sample pattern starting at register I in memory in place of the tone.  
+    /// Equivalent to: `audio(I)`
+    fn load_audio_pattern(&mut self) {
+        let mut pattern = [0; PATTERN_LENGTH];
+        for (offset, byte) in (0..).zip(pattern.iter_mut()) {
+            *byte = self.ram[usize::from(self.register_i.wrapping_add(offset))];
+        }
+
+        self.audio_pattern = Some(pattern);
+        self.set_audio_tone();
+    }
+
+    /// Handles the [`SetPitch`](Opcode::SetPitch) opcode, setting the rate at which the sample pattern is played.  
+    /// Equivalent to: `pitch = Vx`
+    ///
+    /// # Parameters
+    ///
+    /// * `register` - The register containing the pitch.
+    fn set_pitch(&mut self, register: usize) {
+        self.pitch = self.registers[register];
+        self.set_audio_tone();
+    }
+
+    /// Halts further processing until the display is refreshed, upon which time the sprite starting at register I in memory with the provided height will be drawn at the coordinates of the provided registers.  
+    /// This method will be called depending on the status of the [display wait quirk](DisplayWaitQuirk).
+    ///
+    /// # Parameters
+    ///
+    /// * `first_register` - The X coordinate of the drawn sprite.
+    /// * `second_register` - The Y coordinate of the drawn sprite.
+    /// * `length` - The number of bytes to specify the sprite, equating to its drawn height.
+    fn draw(&mut self, first_register: usize, second_register: usize, length: u8) {
+        self.draw_stats.stalled_draws += 1;
+        self.counters.stalled_draws += 1;
+        self.should_wait_for_display_refresh = true;
+        self.wait_for_display_refresh_data = (first_register, second_register, length);
+    }
+
+    /// Handles the [`Draw`](Opcode::Draw) opcode, drawing the sprite starting at register I in memory with the provided height at the coordinates of the provided registers.
+    /// This will set register F to 1 in the case of a pixel being turned off by the draw operation (as the draw is an XOR, this will occur if the pixel was on, and we are drawing over it again with an "on"), and 0 otherwise.  
+    /// This method will be called directly or indirectly depending on the status of the [display wait quirk](DisplayWaitQuirk).  
+    /// The sprite will either be clipped or wrap around the screen on each axis depending on the status of the [clipping quirks](ClippingQuirk).  
+    /// In high resolution, a length of 0 draws a SUPER-CHIP 16x16 sprite made of two bytes per row.  
+    /// The sprite is drawn to each selected XO-CHIP plane, with the sprite for the second plane following the first in memory when both are selected.  
+    /// Equivalent to: `draw(Vx, Vy, n)`
+    ///
+    /// # Parameters
+    ///
+    /// * `first_register` - The X coordinate of the drawn sprite.
+    /// * `second_register` - The Y coordinate of the drawn sprite.
+    /// * `length` - The number of bytes to specify the sprite, equating to its drawn height.
+    fn complete_draw(&mut self, first_register: usize, second_register: usize, length: u8) {
+        self.draw_stats.draws += 1;
+        self.counters.draws += 1;
+        let width = self.display_width();
+        let height = self.display_height();
+        let is_large_sprite = self.is_high_resolution && length == 0;
+        let (sprite_width, sprite_height) = if is_large_sprite { (LARGE_SPRITE_SIZE, LARGE_SPRITE_SIZE) } else { (8, u16::from(length)) };
+        let sprite_size = if is_large_sprite { sprite_height * 2 } else { sprite_height };
+        let base_x = u32::from(self.registers[first_register]) % width;
+        let base_y = u32::from(self.registers[second_register]) % height;
+        self.registers[REGISTER_F] = 0;
+
+        // The program counter has moved past the draw, even if it waited for the display refresh
+        let draw_address = self.program_counter.wrapping_sub(PROGRAM_COUNTER_INCREMENT);
+        let draw_record = DrawRecord {
+            address: draw_address,
+            opcode: u16::from_be_bytes([self.ram[usize::from(draw_address)], self.ram[usize::from(draw_address.wrapping_add(1))]]),
+            frame: self.provenance.as_deref().map_or(0, DrawProvenance::frame)
+        };
+
+        // Each selected plane is drawn with its own sprite, stored one after the other
+        self.has_display_changed = true;
+        let mut sprite_address = self.register_i;
+        for buffer in plane_buffers(&mut self.drawing_buffer, &mut self.second_plane_buffer, self.selected_planes) {
+            for i in 0..sprite_height {
+                let mut buffer_y = base_y + u32::from(i);
+                match self.quirk_config.vertical_clipping {
+                    ClippingQuirk::Clip => {
+                        if buffer_y >= height {
+                            continue;
+                        }
+                    }
+                    ClippingQuirk::Wrap => {
+                        buffer_y %= height;
+                    }
+                }
+
+                // Sprite rows are left-aligned in 16 bits, as large sprites are two bytes wide
+                let read = |offset: u16| self.ram[usize::from(sprite_address.wrapping_add(offset))];
+                let sprite_row = if is_large_sprite {
+                    u16::from_be_bytes([read(i * 2), read(i * 2 + 1)])
+                } else {
+                    u16::from(read(i)) << 8
+                };
+                for j in 0..u32::from(sprite_width) {
+                    let mut buffer_x = base_x + j;
+                    match self.quirk_config.horizontal_clipping {
+                        ClippingQuirk::Clip => {
+                            if buffer_x >= width {
+                                continue;
+                            }
+                        }
+                        ClippingQuirk::Wrap => {
+                            buffer_x %= width;
+                        }
+                    }
+
+                    let target_bit = (sprite_row >> (15 - j)) & 1;
+                    let drawing_buffer_index = (buffer_y * width + buffer_x) as usize;
+                    let display_bit = buffer[drawing_buffer_index];
+
+                    if display_bit && target_bit == 1 {
+                        self.registers[REGISTER_F] = 1;
+                    }
+                    if let (Some(provenance), 1) = (self.provenance.as_mut().map(Arc::make_mut), target_bit) {
+                        provenance.record(drawing_buffer_index, draw_record);
+                    }
+
+                    let is_set = display_bit ^ (target_bit == 1);
+                    buffer.set(drawing_buffer_index, is_set);
+                }
+            }
+
+            sprite_address = sprite_address.wrapping_add(sprite_size);
+        }
+
+        let collision = if self.registers[REGISTER_F] == 1 { " with a collision" } else { "" };
+        frame_log::event(Subsystem::Draw, format_args!("Sprite of {sprite_height} rows from 0x{:03X} drawn at ({base_x}, {base_y}){collision}", self.register_i));
+    }
+}
+
+/// A buffer laid out as the display, which can be [shifted](shift_pixels) whether it stores the pixels themselves or something about each of them.
+trait PixelGrid {
+    /// The value stored for each pixel.
+    type Pixel: Copy;
+
+    /// Returns the value stored for a pixel.
+    ///
+    /// # Parameters
+    ///
+    /// * `index` - The index of the pixel.
+    fn pixel(&self, index: usize) -> Self::Pixel;
+
+    /// Changes the value stored for a pixel.
+    ///
+    /// # Parameters
+    ///
+    /// * `index` - The index of the pixel.
+    /// * `pixel` - The new value.
+    fn set_pixel(&mut self, index: usize, pixel: Self::Pixel);
+}
+
+impl<T: Copy> PixelGrid for [T] {
+    type Pixel = T;
+
+    fn pixel(&self, index: usize) -> T {
+        self[index]
+    }
+
+    fn set_pixel(&mut self, index: usize, pixel: T) {
+        self[index] = pixel;
+    }
+}
+
+impl PixelGrid for PlaneBuffer {
+    type Pixel = bool;
+
+    fn pixel(&self, index: usize) -> bool {
+        self.get(index)
+    }
+
+    fn set_pixel(&mut self, index: usize, pixel: bool) {
+        self.set(index, pixel);
+    }
+}
+
+/// Shifts the pixels of a buffer laid out as the display, filling the pixels uncovered by the shift with the blank value.  
+/// The pixels are moved in place, working against the direction of the shift so that every pixel is read before it is overwritten.
+///
+/// # Parameters
+///
+/// * `buffer` - The buffer to shift.
+/// * `blank` - The value of the uncovered pixels.
+/// * `(width, height)` - The size of the display.
+/// * `(columns, rows)` - The number of pixels to shift by horizontally and vertically.
+/// * `is_forward` - True to shift right and down, false to shift left and up.
+fn shift_pixels<G: PixelGrid + ?Sized>(buffer: &mut G, blank: G::Pixel, (width, height): (usize, usize), (columns, rows): (usize, usize), is_forward: bool) {
+    let pixel_count = width * height;
+    for i in 0..pixel_count {
+        let i = if is_forward { pixel_count - 1 - i } else { i };
+        let (x, y) = (i % width, i / width);
+        let source = if is_forward {
+            x.checked_sub(columns).zip(y.checked_sub(rows))
+        } else {
+            Some((x + columns, y + rows)).filter(|(x, y)| *x < width && *y < height)
+        };
+        let pixel = source.map_or(blank, |(x, y)| buffer.pixel(y * width + x));
+        buffer.set_pixel(y * width + x, pixel);
+    }
+}
+
+/// Returns the drawing buffers of the provided XO-CHIP planes, first plane first.
+///
+/// # Parameters
+///
+/// * `first_plane` - The drawing buffer of the first plane.
+/// * `second_plane` - The drawing buffer of the second plane.
+/// * `planes` - The planes to return, as a mask with a bit per plane.
+fn plane_buffers<'b>(first_plane: &'b mut PlaneBuffer, second_plane: &'b mut PlaneBuffer, planes: u8) -> impl Iterator<Item = &'b mut PlaneBuffer> {
+    [(FIRST_PLANE_MASK, first_plane), (SECOND_PLANE_MASK, second_plane)].into_iter()
+        .filter(move |(mask, _)| planes & mask != 0)
+        .map(|(_, buffer)| buffer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::CharKeyboard;
+    use crate::testing::MockAudio;
+
+    /// The number of pixels on the display outside of high resolution.
+    const LOW_RESOLUTION_PIXEL_COUNT: usize = (SCREEN_WIDTH * SCREEN_HEIGHT) as usize;
+
+    #[test]
+    fn create_interpreter() {
+        let interpreter = Interpreter::new();
+        assert_eq!(interpreter.register_i, 0, "Register I initialized incorrectly.");
+        assert_eq!(interpreter.delay_timer, 0, "Delay timer initialized incorrectly.");
+        assert_eq!(interpreter.sound_timer, 0, "Sound timer initialized incorrectly.");
+        assert_eq!(interpreter.program_counter, 0, "Program counter initialized incorrectly.");
+        assert_eq!(interpreter.stack_pointer, 0, "Stack pointer initialized incorrectly.");
+        assert_eq!(interpreter.keyboard, 0, "Keyboard initialized incorrectly.");
+        assert!(!interpreter.should_wait_for_key, "Should wait for key initialized incorrectly.");
+        assert_eq!(interpreter.wait_for_key_register, 0, "Wait for key register initialized incorrectly.");
+        assert!(!interpreter.should_wait_for_display_refresh, "Wait for display refresh initialized incorrectly.");
+        assert_eq!(interpreter.wait_for_display_refresh_data, (0x0, 0x0, 0x0), "Wait for display refresh data initialized incorrectly.");
+        assert!(interpreter.frontend.audio.is_none(), "Audio initialized incorrectly (for tests).");
+        assert!(interpreter.frontend.display.is_none(), "Display initialized incorrectly (for tests).");
+        assert_eq!(interpreter.quirk_config.reset_vf, ResetVfQuirk::default(), "Reset quirk initialized incorrectly");
+        assert_eq!(interpreter.quirk_config.memory, MemoryIncrementQuirk::default(), "Memory increment quirk initialized incorrectly");
+        assert_eq!(interpreter.quirk_config.display_wait, DisplayWaitQuirk::default(), "Display wait quirk initialized incorrectly");
+        assert_eq!(interpreter.quirk_config.horizontal_clipping, ClippingQuirk::default(), "Horizontal clipping quirk initialized incorrectly");
+        assert_eq!(interpreter.quirk_config.vertical_clipping, ClippingQuirk::default(), "Vertical clipping quirk initialized incorrectly");
+        assert_eq!(interpreter.quirk_config.shifting, ShiftingQuirk::default(), "Shifting quirk initialized incorrectly");
+        assert_eq!(interpreter.quirk_config.jumping, JumpingQuirk::default(), "Jumping quirk initialized incorrectly");
+
+        let hex_digit_sprite_length = HEXADECIMAL_DIGIT_SPRITES.len();
+        for (i, byte) in interpreter.ram.iter().enumerate() {
+            let expected = if i < hex_digit_sprite_length {
+                &HEXADECIMAL_DIGIT_SPRITES[i]
+            } else if i < FONT_LENGTH {
+                &LARGE_HEXADECIMAL_DIGIT_SPRITES[i - LARGE_HEXADECIMAL_DIGIT_SPRITES_ADDRESS]
+            } else {
+                &0
+            };
+            assert_eq!(byte, expected, "RAM initialized incorrectly.");
+        }
+
+        assert!(interpreter.registers.iter().eq([0; REGISTERS_SIZE].iter()), "Registers initialized incorrectly.");
+        assert!(interpreter.stack.iter().eq([0; STACK_SIZE].iter()), "Stack initialized incorrectly.");
+        assert!(interpreter.drawing_buffer == PlaneBuffer::new(), "Drawing buffer initialized incorrectly.");
+    }
+
+    #[test]
+    fn snapshot_and_restore() {
+        let mut interpreter = Interpreter::new();
+        interpreter.load_game(&[0xC0, 0xFF, 0x71, 0x01, 0xA2, 0x34, 0x12, 0x00]);
+        interpreter.seed_random(3);
+        interpreter.handle_cycle();
+        let snapshot = interpreter.snapshot();
+        let expected_registers = interpreter.registers;
+
+        for _ in 0..4 {
+            interpreter.handle_cycle();
+        }
+        let random_after_snapshot = interpreter.registers[0x0];
+
+        interpreter.restore(&snapshot);
+        assert_eq!(interpreter.registers, expected_registers, "Registers not restored.");
+        assert_eq!(interpreter.register_i, 0x0, "Register I not restored.");
+        assert_eq!(interpreter.program_counter, 0x202, "Program counter not restored.");
+
+        for _ in 0..4 {
+            interpreter.handle_cycle();
+        }
+        assert_eq!(interpreter.registers[0x0], random_after_snapshot, "Random numbers not restored.");
+    }
+
+    #[test]
+    fn fork() {
+        let mut interpreter = Interpreter::new();
+        interpreter.load_game(&[0xC0, 0xFF, 0x71, 0x01, 0xA2, 0x34, 0x12, 0x00]);
+        interpreter.seed_random(3);
+        interpreter.handle_cycle();
+        interpreter.press_key(0x5);
+
+        let mut fork = interpreter.fork();
+        assert!(fork.keyboard & (1 << 0x5) != 0, "Held keys not forked.");
+        for _ in 0..4 {
+            interpreter.handle_cycle();
+            fork.handle_cycle();
+        }
+        assert_eq!(fork.registers, interpreter.registers, "Fork ran differently.");
+        assert_eq!(fork.program_counter, interpreter.program_counter, "Fork ran differently.");
+
+        fork.handle_cycle();
+        assert_ne!(fork.program_counter, interpreter.program_counter, "Fork shares state with the original.");
+        assert_eq!(interpreter.clone().program_counter, interpreter.program_counter, "Clone not forked.");
+    }
+
+    #[test]
+    fn fork_independent() {
+        let mut interpreter = Interpreter::new();
+        interpreter.quirk_config.display_wait = DisplayWaitQuirk::NoWait;
+        interpreter.load_game(&[0x60, 0x00, 0xA0, 0x00, 0xD0, 0x05, 0x12, 0x06]);
+        interpreter.set_coverage_enabled(true);
+        interpreter.set_provenance_enabled(true);
+        interpreter.handle_cycle();
+
+        let mut fork = interpreter.fork();
+        assert!(Arc::ptr_eq(fork.coverage.as_ref().unwrap(), interpreter.coverage.as_ref().unwrap()), "Coverage copied before it changed.");
+        fork.ram[0x300] = 0xAB;
+        fork.registers[0x1] = 0xCD;
+        fork.drawing_buffer.set(10, true);
+        for _ in 0..3 {
+            fork.handle_cycle();
+        }
+        fork.set_symbols(SymbolTable::new());
+
+        assert_eq!(interpreter.ram[0x300], 0, "Fork's memory write reached the original.");
+        assert_eq!(interpreter.registers[0x1], 0, "Fork's register write reached the original.");
+        assert_eq!(interpreter.program_counter, PROGRAM_START_ADDRESS + 2, "Fork's cycles ran the original.");
+        assert!(interpreter.display_buffer().all(|pixel| !pixel), "Fork's draw reached the original.");
+        assert_eq!(interpreter.coverage().map(Coverage::count), Some(1), "Fork's coverage reached the original.");
+        assert_eq!(interpreter.provenance().unwrap().get(0), None, "Fork's draw recorded in the original.");
+        assert!(fork.provenance().unwrap().get(0).is_some(), "Fork's draw not recorded.");
+        assert!(!Arc::ptr_eq(fork.coverage.as_ref().unwrap(), interpreter.coverage.as_ref().unwrap()), "Coverage still shared after it changed.");
+    }
+
+    #[test]
+    pub fn load_game() {
+        let mut interpreter = Interpreter::new();
+
+        interpreter.registers[0x3] = 0xF;
+        interpreter.registers[0x9] = 0xAA;
+        interpreter.register_i = 0x732;
+        interpreter.delay_timer = 0x4;
+        interpreter.sound_timer = 0x7;
+        interpreter.stack_pointer = 0x2;
+        interpreter.stack[0x0] = 0x943;
+        interpreter.stack[0x1] = 0x239;
+        interpreter.keyboard |= 1 << 0x3;
+        interpreter.keyboard |= 1 << 0x6;
+        interpreter.should_wait_for_key = true;
+        interpreter.wait_for_key_register = 0x9;
+        interpreter.should_wait_for_display_refresh = true;
+        interpreter.wait_for_display_refresh_data = (10, 10, 2);
+        interpreter.drawing_buffer.fill(true);
+        interpreter.program_counter = 0x783;
+
+        let fake_game_data = vec![0x23, 0x78, 0x93];
+        let fake_game_data_len = fake_game_data.len();
+        let program_start_address_usize = usize::from(PROGRAM_START_ADDRESS);
+        interpreter.load_game(&fake_game_data);
+        for (i, fake_game_element) in fake_game_data.iter().enumerate() {
+            assert_eq!(interpreter.ram[PROGRAM_START_ADDRESS as usize + i], *fake_game_element, "Loaded game data does not match the original game data.");
+        }
+
+        for (i, byte) in interpreter.ram.iter().enumerate() {
+            if (i >= FONT_LENGTH && i < program_start_address_usize) || i > (program_start_address_usize + fake_game_data_len) {
+                assert_eq!(byte, &0, "Ram not reset after game load.");
+            }
+        }
+
+        assert!(interpreter.registers.iter().eq([0; REGISTERS_SIZE].iter()), "Registers not reset after game load.");
+        assert_eq!(interpreter.register_i, 0x0, "Register I not reset after game load.");
+        assert_eq!(interpreter.delay_timer, 0x0, "Delay timer not reset after game load.");
+        assert_eq!(interpreter.sound_timer, 0x0, "Sound timer not reset after game load.");
+        assert_eq!(interpreter.stack_pointer, 0x0, "Stack pointer not reset after game load.");
+        assert!(interpreter.stack.iter().eq([0; STACK_SIZE].iter()), "Stack not reset after game load.");
+        assert_eq!(interpreter.keyboard, 0, "Keyboard not reset after game load.");
+        assert!(!interpreter.should_wait_for_key, "Waiting for key state not reset after game load.");
+        assert_eq!(interpreter.wait_for_key_register, 0, "Waiting for key register not reset after game load.");
+        assert!(!interpreter.should_wait_for_display_refresh, "Waiting for display refresh state not reset after game load.");
+        assert_eq!(interpreter.wait_for_display_refresh_data, (0x0, 0x0, 0x0), "Waiting for display refresh data not reset after game load.");
+        assert!(interpreter.drawing_buffer == PlaneBuffer::new(), "Drawing buffer not reset after game load.");
+        assert_eq!(interpreter.program_counter, PROGRAM_START_ADDRESS, "Program counter not reset after game load.");
+    }
+
+    #[test]
+    pub fn handle_cycle() {
+        let mut interpreter = Interpreter::new();
+
+        let program_start_usize = PROGRAM_START_ADDRESS as usize;
+        interpreter.ram[program_start_usize] = 0xAA;
+        interpreter.ram[program_start_usize + 1] = 0xAA;
+        interpreter.ram[program_start_usize + 2] = 0x1B;
+        interpreter.ram[program_start_usize + 3] = 0xBB;
+        interpreter.program_counter = PROGRAM_START_ADDRESS;
+        interpreter.handle_cycle();
+        assert_eq!(interpreter.register_i, 0xAAA, "Opcode not handled.");
+        assert_eq!(interpreter.program_counter, PROGRAM_START_ADDRESS + PROGRAM_COUNTER_INCREMENT, "Program counter not incremented.");
+
+        interpreter.handle_cycle();
+        assert_eq!(interpreter.program_counter, 0xBBB, "Program counter incremented after jump.");
+    }
+
+    #[test]
+    fn step() {
+        let mut interpreter = Interpreter::new();
+
+        // Sets a register, draws, waits for a key, then exits
+        interpreter.load_game(&[0x60, 0x05, 0xD0, 0x15, 0xF1, 0x0A, 0x00, 0x00]);
+        let step = interpreter.step();
+        assert_eq!(step.opcode.map(|opcode| opcode.to_string()).as_deref(), Some("LD V0, 0x05"), "Opcode not returned.");
+        assert_eq!((step.program_counter_before, step.program_counter_after), (0x200, 0x202), "Program counters not returned.");
+        assert_eq!(step.wait, None, "Waiting reported before any wait.");
+
+        assert_eq!(interpreter.step().wait, Some(WaitReason::Display), "Display wait not reported.");
+        let step = interpreter.step();
+        assert_eq!((step.opcode, step.program_counter_before, step.program_counter_after), (None, 0x204, 0x204), "Opcode returned while waiting.");
+        interpreter.handle_frame();
+
+        assert_eq!(interpreter.step().wait, Some(WaitReason::Key), "Key wait not reported.");
+        interpreter.press_key(0x1);
+        interpreter.release_key(0x1);
+        let step = interpreter.step();
+        assert!(step.opcode.is_some(), "Exit opcode not returned.");
+        assert_eq!(step.wait, Some(WaitReason::Halted(HaltReason::Exit)), "Halt not reported.");
+    }
+
+    #[test]
+    fn halt_on_invalid_opcode() {
+        let mut interpreter = Interpreter::new();
+
+        interpreter.load_game(&[0xF0, 0x15, 0x51, 0xC7, 0x60, 0x01]);
+        interpreter.registers[0x0] = 0x5;
+        interpreter.handle_cycle();
+        interpreter.handle_cycle();
+        let halt_reason = HaltReason::InvalidOpcode { address: PROGRAM_START_ADDRESS + 2, opcode: 0x51C7 };
+        assert_eq!(interpreter.halt_reason(), Some(halt_reason), "Invalid opcode did not halt.");
+        assert!(halt_reason.is_error(), "Invalid opcode not treated as an error.");
+
+        interpreter.handle_cycle();
+        interpreter.handle_frame();
+        assert_eq!(interpreter.registers[0x0], 0x5, "Cycle run after halting.");
+        assert_eq!(interpreter.delay_timer, 0x5, "Timers ticked after halting.");
+
+        interpreter.halt(HaltReason::UserStop);
+        assert_eq!(interpreter.halt_reason(), Some(halt_reason), "First halt reason replaced.");
+        interpreter.load_game(&[0x12, 0x00]);
+        assert_eq!(interpreter.halt_reason(), None, "Halt not cleared by a load.");
+    }
+
+    #[test]
+    fn load_game_at_address() {
+        let mut interpreter = Interpreter::new();
+
+        assert!(interpreter.set_load_address(0x010).is_err(), "Load address over the font accepted.");
+        assert_eq!(interpreter.load_address(), PROGRAM_START_ADDRESS, "Invalid load address kept.");
+        interpreter.set_load_address(0x600).unwrap();
+        interpreter.load_game(&[0xA6, 0x01, 0xF0, 0x55]);
+        assert_eq!(interpreter.ram[0x600..0x604], [0xA6, 0x01, 0xF0, 0x55], "Game not loaded at the load address.");
+        assert_eq!(interpreter.ram[usize::from(PROGRAM_START_ADDRESS)], 0x0, "Game loaded at the default address.");
+        assert_eq!(interpreter.program_counter, 0x600, "Program counter not started at the load address.");
+
+        interpreter.set_memory_protection(MemoryProtection::Program);
+        interpreter.handle_cycle();
+        interpreter.handle_cycle();
+        assert_eq!(interpreter.halt_reason(), Some(HaltReason::ProtectedWrite { address: 0x602, target: 0x601 }), "Program at the load address not protected.");
+
+        interpreter.set_load_address(0xFFFE).unwrap();
+        interpreter.load_game(&[0x12, 0x00, 0x12, 0x00]);
+        assert_eq!(interpreter.ram[0xFFFE..], [0x12, 0x00], "Game not cut off at the end of the RAM.");
+    }
+
+    #[test]
+    fn switch_banks() {
+        let mut interpreter = Interpreter::new();
+        interpreter.set_banking(true).unwrap();
+        assert!(interpreter.set_load_address(0x900).is_err(), "Load address past the bank window accepted.");
+
+        let mut game_data = vec![0xA1, 0xFF, 0x60, 0x01, 0xF0, 0x55, 0x18, 0x00];
+        game_data.resize(0x600, 0);
+        game_data.extend([0xB0, 0x00]);
+        game_data.resize(0xE00, 0);
+        game_data.extend([0x6A, 0x07]);
+        game_data.resize(0x1700, 0);
+        interpreter.load_game(&game_data);
+        assert_eq!(interpreter.bank_count, 3, "Banks not counted.");
+        assert_eq!(interpreter.ram[0x800], 0xB0, "First bank not in the window.");
+
+        for _ in 0..3 {
+            interpreter.handle_cycle();
+        }
+        assert_eq!(interpreter.ram[BANK_SELECT_ADDRESS], 1, "Bank not selected.");
+        assert_eq!(interpreter.ram[0x800..0x802], [0x6A, 0x07], "Bank not swapped into the window.");
+        interpreter.ram[0x801] = 0x08;
+        interpreter.handle_cycle();
+        interpreter.handle_cycle();
+        assert_eq!(interpreter.registers[0xA], 0x08, "Code in the bank not run.");
+
+        interpreter.registers[0] = 0;
+        interpreter.register_i = 0x1FF;
+        interpreter.program_counter = 0x204;
+        interpreter.handle_cycle();
+        assert_eq!(interpreter.ram[0x800], 0xB0, "Bank not swapped back.");
+        assert_eq!(interpreter.ram[0x1800..0x1802], [0x6A, 0x08], "Changes to the bank not kept.");
+
+        interpreter.registers[0] = 3;
+        interpreter.register_i = 0x1FF;
+        interpreter.program_counter = 0x204;
+        interpreter.handle_cycle();
+        assert_eq!(interpreter.halt_reason(), Some(HaltReason::InvalidBank { address: 0x204, bank: 3 }), "Missing bank selected.");
+
+        interpreter.set_banking(false).unwrap();
+        interpreter.set_load_address(0x900).unwrap();
+        assert!(interpreter.set_banking(true).is_err(), "Banking enabled past the bank window.");
+    }
+
+    #[test]
+    fn switch_banks_with_font_protection() {
+        let mut interpreter = Interpreter::new();
+        interpreter.set_banking(true).unwrap();
+        interpreter.set_memory_protection(MemoryProtection::Font);
+
+        let mut game_data = vec![0xA1, 0xFF, 0x60, 0x01, 0xF0, 0x55, 0xA0, 0xEF, 0xF0, 0x55];
+        game_data.resize(0x600 + banking::BANK_SIZE, 0);
+        game_data.extend([0x6A, 0x07]);
+        interpreter.load_game(&game_data);
+
+        for _ in 0..3 {
+            interpreter.handle_cycle();
+        }
+        assert_eq!(interpreter.halt_reason(), None, "Bank switch halted as a write to the font.");
+        assert_eq!(interpreter.ram[BANK_SELECT_ADDRESS], 1, "Bank not selected.");
+        assert_eq!(interpreter.ram[0x800..0x802], [0x6A, 0x07], "Bank not swapped into the window.");
+
+        interpreter.handle_cycle();
+        interpreter.handle_cycle();
+        assert_eq!(interpreter.halt_reason(), Some(HaltReason::ProtectedWrite { address: 0x208, target: 0x0EF }), "Font not protected while banking.");
+    }
+
+    #[test]
+    fn halt_on_unsupported_extension() {
+        let mut interpreter = Interpreter::new();
+
+        interpreter.load_game(&[0x00, 0x11]);
+        interpreter.handle_cycle();
+        let halt_reason = HaltReason::UnsupportedExtension { address: PROGRAM_START_ADDRESS, opcode: 0x0011, extension: "MegaChip" };
+        assert_eq!(interpreter.halt_reason(), Some(halt_reason), "MegaChip opcode not reported as an extension.");
+        assert!(halt_reason.is_error(), "Unsupported extension not treated as an error.");
+        assert!(halt_reason.to_string().starts_with("Unsupported extension: MegaChip"), "Extension not named.");
+
+        interpreter.load_game(&[0x00, 0xE0, 0xF0, 0x99]);
+        const CUSTOM: Extension = Extension { name: "Custom", opcodes: &[crate::extensions::OpcodePattern::new(0xF0FF, 0xF099)] };
+        interpreter.register_extension(CUSTOM);
+        interpreter.handle_cycle();
+        interpreter.handle_cycle();
+        assert_eq!(interpreter.halt_reason(), Some(HaltReason::UnsupportedExtension { address: PROGRAM_START_ADDRESS + 2, opcode: 0xF099, extension: "Custom" }), "Registered extension not reported.");
+    }
+
+    #[test]
+    fn record_coverage() {
+        let mut interpreter = Interpreter::new();
+        interpreter.load_game(&[0x30, 0x00, 0x60, 0x01, 0x12, 0x04]);
+        interpreter.handle_cycle();
+        assert!(interpreter.coverage().is_none(), "Coverage recorded without being enabled.");
+
+        interpreter.set_coverage_enabled(true);
+        for _ in 0..3 {
+            interpreter.handle_cycle();
+        }
+        let coverage = interpreter.coverage().unwrap();
+        assert_eq!(coverage.addresses().collect::<Vec<_>>(), vec![0x204], "Coverage recorded incorrectly.");
+
+        interpreter.load_game(&[0x12, 0x00]);
+        assert_eq!(interpreter.coverage().map(Coverage::count), Some(0), "Coverage not cleared by a load.");
+    }
+
+    #[test]
+    fn inspect_call_stack() {
+        let mut interpreter = Interpreter::new();
+        interpreter.load_game(&[0x22, 0x04, 0x00, 0x00, 0x22, 0x08, 0x00, 0x00, 0x12, 0x08]);
+        assert!(interpreter.call_stack().is_empty(), "Call stack not empty at the start.");
+        for _ in 0..3 {
+            interpreter.handle_cycle();
+        }
+
+        assert_eq!(interpreter.stack(), [0x202, 0x206], "Return addresses not exposed.");
+        let call_stack = interpreter.call_stack();
+        assert_eq!(call_stack, [
+            StackFrame { call_address: 0x204, return_address: 0x206, opcode: 0x2208 },
+            StackFrame { call_address: 0x200, return_address: 0x202, opcode: 0x2204 }
+        ], "Call stack not the most recent first.");
+        assert_eq!(call_stack[0].to_string(), "0x204: 2208 CALL 0x208", "Stack frame formatted incorrectly.");
+    }
+
+    #[test]
+    fn profile_opcodes() {
+        let mut interpreter = Interpreter::new();
+        interpreter.load_game(&[0x60, 0x01, 0x61, 0x02, 0x12, 0x04]);
+        interpreter.handle_cycle();
+        assert!(interpreter.profile().is_none(), "Opcodes profiled without being enabled.");
+
+        interpreter.set_profiling_enabled(true);
+        for _ in 0..3 {
+            interpreter.handle_cycle();
+        }
+        let profile = interpreter.profile().unwrap();
+        assert_eq!((profile.get("LoadValue").map(|stats| stats.count), profile.get("JumpAddr").map(|stats| stats.count)), (Some(1), Some(2)), "Opcodes profiled incorrectly.");
+
+        interpreter.load_game(&[0x12, 0x00]);
+        assert_eq!(interpreter.profile().map(Profile::total_count), Some(0), "Profile not cleared by a load.");
+    }
+
+    #[test]
+    fn record_and_undo_events() {
+        let mut interpreter = Interpreter::new();
+        interpreter.set_event_log(Some(EventLog::new(10)));
+        interpreter.load_game(&[0x63, 0x1F, 0xA3, 0x00, 0xF3, 0x33, 0xF3, 0x15, 0x22, 0x0C, 0x00, 0x00, 0x00, 0xEE]);
+        for _ in 0..5 {
+            interpreter.handle_cycle();
+        }
+        interpreter.handle_frame();
+
+        let log = interpreter.event_log().unwrap();
+        assert_eq!(log.changes_between(0, 1).iter().map(ToString::to_string).collect::<Vec<_>>(), [
+            "V3: 00 -> 1F", "PC: 0x200 -> 0x20C", "I: 0x000 -> 0x300", "[0x302]: 00 -> 01", "[0x301]: 00 -> 03", "DT: 0 -> 30", "Stack #0: 0x000 -> 0x20A", "SP: 0 -> 1"
+        ], "Changes recorded incorrectly.");
+
+        let before_call = (interpreter.registers()[0x3], interpreter.register_i(), interpreter.ram()[0x301]);
+        assert!(interpreter.step_back(), "Call not undone.");
+        assert_eq!((interpreter.program_counter(), interpreter.stack_pointer, interpreter.delay_timer()), (0x208, 0, 31), "Call and timer tick not undone.");
+        for _ in 0..3 {
+            assert!(interpreter.step_back(), "Instruction not undone.");
+        }
+        assert_eq!((interpreter.program_counter(), interpreter.register_i(), interpreter.ram()[0x301], interpreter.delay_timer()), (0x202, 0x000, 0x00, 0), "Instructions not undone.");
+
+        for _ in 0..3 {
+            interpreter.handle_cycle();
+        }
+        assert_eq!((interpreter.registers()[0x3], interpreter.register_i(), interpreter.ram()[0x301]), before_call, "Instructions not run again after stepping back.");
+        assert!(interpreter.step_back() && interpreter.step_back() && interpreter.step_back() && interpreter.step_back(), "Instructions run again not undone.");
+        assert!(!interpreter.step_back(), "Stepped back past the start of the log.");
+        assert_eq!(interpreter.program_counter(), 0x200, "Not back at the start.");
+    }
+
+    #[test]
+    fn dispatch_designs_agree() {
+        // Counts V0 up, rewriting the immediate of the instruction at 0x204 with it so that it runs differently every loop
+        let game = [0x70, 0x01, 0xA2, 0x05, 0x61, 0x00, 0xF0, 0x55, 0x81, 0x14, 0x12, 0x00];
+        let states: Vec<(u16, [u8; REGISTERS_SIZE], u16)> = Dispatch::ALL.iter().map(|dispatch| {
+            let mut interpreter = Interpreter::new();
+            interpreter.set_dispatch(*dispatch);
+            interpreter.load_game(&game);
+            for _ in 0..100 {
+                interpreter.handle_cycle();
+            }
+            assert_eq!(interpreter.dispatch(), *dispatch, "Dispatch not set.");
+            (interpreter.program_counter(), *interpreter.registers(), interpreter.register_i())
+        }).collect();
+
+        assert!(states.windows(2).all(|pair| pair[0] == pair[1]), "Designs ran the game differently: {states:?}");
+        assert_eq!(states[0].1[0x1], 0x10, "Modified instruction not run.");
+    }
+
+    #[test]
+    fn record_draw_provenance() {
+        let mut interpreter = Interpreter::new();
+        interpreter.load_game(&[0xA0, 0x00, 0x61, 0x00, 0xD1, 0x15, 0x00, 0xFB, 0x00, 0xE0]);
+        interpreter.set_provenance_enabled(true);
+        for _ in 0..3 {
+            interpreter.handle_cycle();
+        }
+        interpreter.handle_frame();
+        let provenance = interpreter.provenance().unwrap();
+        assert_eq!(provenance.get(0), Some(DrawRecord { address: 0x204, opcode: 0xD115, frame: 0 }), "Draw not recorded.");
+        assert_eq!(provenance.frame(), 1, "Frame not advanced.");
+        assert_eq!(provenance.get(4), None, "Pixel left alone by the sprite recorded.");
+
+        interpreter.handle_cycle();
+        let provenance = interpreter.provenance().unwrap();
+        assert_eq!((provenance.get(0), provenance.get(4).map(|record| record.address)), (None, Some(0x204)), "Draw not scrolled with its pixels.");
+
+        interpreter.handle_cycle();
+        assert_eq!(interpreter.provenance().unwrap().get(4), None, "Draw kept after clearing the display.");
+    }
+
+    #[test]
+    fn halt_past_end_of_ram() {
+        let mut interpreter = Interpreter::new();
+
+        #[allow(clippy::cast_possible_truncation)]
+        let address = (RAM_SIZE - 1) as u16;
+        interpreter.program_counter = address;
+        interpreter.handle_cycle();
+        assert_eq!(interpreter.halt_reason(), Some(HaltReason::ProgramCounterOutOfBounds { address }), "Running past the RAM did not halt.");
+    }
+
+    #[test]
+    fn handle_timers() {
+        let mut interpreter = Interpreter::new();
+
+        interpreter.delay_timer = 0x3;
+        interpreter.sound_timer = 0x2;
+        interpreter.handle_timers();
+        assert_eq!(interpreter.delay_timer, 0x2, "Delay timer not decremented.");
+        assert_eq!(interpreter.sound_timer, 0x1, "Sound timer not decremented.");
+
+        interpreter.handle_timers();
+        assert_eq!(interpreter.delay_timer, 0x1, "Delay timer not decremented.");
+        assert_eq!(interpreter.sound_timer, 0x0, "Sound timer not decremented.");
+
+        interpreter.handle_timers();
+        assert_eq!(interpreter.delay_timer, 0x0, "Delay timer not decremented.");
+        assert_eq!(interpreter.sound_timer, 0x0, "Sound timer not saturated at 0.");
+
+        interpreter.handle_timers();
+        assert_eq!(interpreter.delay_timer, 0x0, "Delay timer not saturated at 0.");
+        assert_eq!(interpreter.sound_timer, 0x0, "Sound timer not saturated at 0.");
+    }
+
+    #[test]
+    fn write_ram() {
+        let mut interpreter = Interpreter::new();
+
+        assert!(interpreter.write_ram(0x300, &[0x12, 0x34]).is_ok(), "Valid write failed.");
+        assert_eq!(interpreter.ram[0x300..0x302], [0x12, 0x34], "RAM not written.");
+
+        #[allow(clippy::cast_possible_truncation)]
+        let last_address = (RAM_SIZE - 1) as u16;
+        assert!(interpreter.write_ram(last_address, &[0xAB]).is_ok(), "Write to the last address failed.");
+        assert!(interpreter.write_ram(last_address, &[0xCD, 0xEF]).is_err(), "Write past the end of the RAM succeeded.");
+        assert_eq!(interpreter.ram[RAM_SIZE - 1], 0xAB, "Failed write modified the RAM.");
+    }
+
+    #[test]
+    fn handle_frame() {
+        let mut interpreter = Interpreter::new();
+
+        interpreter.delay_timer = 0x1;
+        interpreter.sound_timer = 0x1;
+        interpreter.handle_timers();
+        assert_eq!(interpreter.delay_timer, 0x0, "Delay timer not decremented.");
+        assert_eq!(interpreter.sound_timer, 0x0, "Sound timer not decremented.");
+    }
+
+    #[test]
+    fn hooks() {
+        use std::sync::{Arc, Mutex};
+
+        let mut interpreter = Interpreter::new();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let pre_calls = Arc::clone(&calls);
+        let pre_id = interpreter.add_pre_instruction_hook(move |interpreter, opcode| pre_calls.lock().unwrap().push(format!("pre {:#05X} {opcode}", interpreter.program_counter())));
+        let post_calls = Arc::clone(&calls);
+        interpreter.add_post_instruction_hook(move |interpreter, _| post_calls.lock().unwrap().push(format!("post {}", interpreter.registers()[0])));
+        let frame_calls = Arc::clone(&calls);
+        interpreter.add_frame_hook(move |_| frame_calls.lock().unwrap().push(String::from("frame")));
+
+        interpreter.load_game(&[0x60, 0x05, 0x60, 0x06]);
+        interpreter.handle_cycle();
+        interpreter.handle_frame();
+        assert_eq!(*calls.lock().unwrap(), ["pre 0x200 LD V0, 0x05", "post 5", "frame"], "Hooks not called in order.");
+
+        calls.lock().unwrap().clear();
+        assert!(interpreter.remove_hook(pre_id), "Hook not removed.");
+        assert!(!interpreter.remove_hook(pre_id), "Removed hook removed again.");
+        interpreter.handle_cycle();
+        assert_eq!(*calls.lock().unwrap(), ["post 6"], "Removed hook called.");
+    }
+
+    #[test]
+    fn frame_draw_count() {
+        let mut interpreter = Interpreter::new();
+        interpreter.quirk_config.display_wait = DisplayWaitQuirk::NoWait;
+
+        interpreter.load_game(&[0xD0, 0x15, 0xD0, 0x15]);
+        interpreter.handle_cycle();
+        interpreter.handle_cycle();
+        assert_eq!(interpreter.frame_draw_count(), 0, "Draws counted before the frame ended.");
+        interpreter.handle_frame();
+        assert_eq!(interpreter.frame_draw_count(), 2, "Draws not counted.");
+        interpreter.handle_frame();
+        assert_eq!(interpreter.frame_draw_count(), 0, "Draw count not reset between frames.");
+    }
+
+    #[test]
+    fn display_changed() {
+        let mut interpreter = Interpreter::new();
+        interpreter.quirk_config.display_wait = DisplayWaitQuirk::NoWait;
+
+        // Sets a register, draws, then sets the register again
+        interpreter.load_game(&[0x60, 0x01, 0xD0, 0x15, 0x60, 0x02]);
+        assert!(interpreter.take_display_changed(), "Loaded game not reported as a change.");
+        assert!(!interpreter.has_display_changed(), "Change not taken.");
+        interpreter.handle_cycle();
+        assert!(!interpreter.take_display_changed(), "Register change reported as a display change.");
+        interpreter.handle_cycle();
+        assert!(interpreter.take_display_changed(), "Draw not reported.");
+        interpreter.handle_cycle();
+        assert!(!interpreter.take_display_changed(), "Change reported twice.");
+
+        let framebuffer = interpreter.framebuffer();
+        assert_eq!((framebuffer.width(), framebuffer.height()), (SCREEN_WIDTH, SCREEN_HEIGHT), "Framebuffer size incorrect.");
+        assert!(framebuffer.pixels().eq(interpreter.display_buffer()), "Framebuffer pixels not the display's.");
+    }
+
+    #[test]
+    fn count_work() {
+        let mut interpreter = Interpreter::new();
+        interpreter.load_game(&[0xD0, 0x15, 0xF0, 0x0A, 0x51, 0x27]);
+        for _ in 0..12 {
+            interpreter.handle_cycle();
+        }
+        interpreter.handle_frame();
+        interpreter.handle_cycle();
+        interpreter.press_key(0x1);
+        interpreter.release_key(0x1);
+        interpreter.handle_cycle();
+
+        assert_eq!(
+            interpreter.counters(),
+            Counters { instructions: 2, draws: 1, stalled_draws: 1, stalled_cycles: 11, invalid_opcodes: 1, key_waits: 1 },
+            "Work counted incorrectly."
+        );
+        interpreter.load_game(&[0x12, 0x00]);
+        assert_eq!(interpreter.counters(), Counters::default(), "Counters not reset by a load.");
+    }
+
+    #[test]
+    fn frame_draw_stats() {
+        let mut interpreter = Interpreter::new();
+
+        interpreter.load_game(&[0xD0, 0x15, 0x60, 0x01]);
+        for _ in 0..10 {
+            interpreter.handle_cycle();
+        }
+        interpreter.handle_frame();
+        assert_eq!(interpreter.frame_draw_stats(), DrawStats { draws: 1, stalled_draws: 1, stalled_cycles: 9 }, "Stalled draw not counted.");
+    }
+
+    #[test]
+    fn handle_key_press() {
+        let mut interpreter = Interpreter::new();
+
+        let q_key_mapping = &CharKeyboard.keypad_key('q').unwrap();
+        let f_key_mapping = &CharKeyboard.keypad_key('f').unwrap();
+        interpreter.handle_key_press(&CharKeyboard, 'q');
+        assert!(interpreter.keyboard & (1 << q_key_mapping) != 0, "Key press not stored.");
+        assert_eq!(interpreter.keyboard.count_ones(), 1, "Wrong number of key presses stored.");
+
+        // Testing that repeated press doesn't break anything
+        interpreter.handle_key_press(&CharKeyboard, 'q');
+        assert!(interpreter.keyboard & (1 << q_key_mapping) != 0, "Key press not stored.");
+        assert_eq!(interpreter.keyboard.count_ones(), 1, "Wrong number of key presses stored.");
+
+        interpreter.handle_key_press(&CharKeyboard, 'f');
+        assert!(interpreter.keyboard & (1 << f_key_mapping) != 0, "Key press not stored.");
+        assert!(interpreter.keyboard & (1 << q_key_mapping) != 0, "Stored key press removed.");
+        assert_eq!(interpreter.keyboard.count_ones(), 2, "Wrong number of key presses stored.");
+        assert_eq!(interpreter.pressed_keys(), (1 << 0x4) | (1 << 0xE), "Wrong key bitmask.");
+    }
+
+    #[test]
+    fn handle_key_release() {
+        let mut interpreter = Interpreter::new();
+
+        let q_key_mapping = &CharKeyboard.keypad_key('q').unwrap();
+        let f_key_mapping = &CharKeyboard.keypad_key('f').unwrap();
+        interpreter.keyboard |= 1 << q_key_mapping;
+        interpreter.keyboard |= 1 << f_key_mapping;
+        interpreter.handle_key_release(&CharKeyboard, 'l');
+        assert!(interpreter.keyboard & (1 << q_key_mapping) != 0, "Stored key press removed.");
+        assert!(interpreter.keyboard & (1 << f_key_mapping) != 0, "Stored key press removed.");
+        assert_eq!(interpreter.keyboard.count_ones(), 2, "Wrong number of key presses stored.");
+
+        interpreter.handle_key_release(&CharKeyboard, 'q');
+        assert!(interpreter.keyboard & (1 << q_key_mapping) == 0, "Key press stored.");
+        assert!(interpreter.keyboard & (1 << f_key_mapping) != 0, "Key press not stored.");
+        assert_eq!(interpreter.keyboard.count_ones(), 1, "Wrong number of key presses stored.");
+
+        // Testing that repeated release doesn't break anything
+        interpreter.handle_key_release(&CharKeyboard, 'q');
+        assert!(interpreter.keyboard & (1 << q_key_mapping) == 0, "Key press stored.");
+        assert!(interpreter.keyboard & (1 << f_key_mapping) != 0, "Key press not stored.");
+        assert_eq!(interpreter.keyboard.count_ones(), 1, "Wrong number of key presses stored.");
+
+        interpreter.handle_key_release(&CharKeyboard, 'f');
+        assert!(interpreter.keyboard & (1 << f_key_mapping) == 0, "Key press stored.");
+        assert!(interpreter.keyboard & (1 << q_key_mapping) == 0, "Key press stored.");
+        assert_eq!(interpreter.keyboard.count_ones(), 0, "Wrong number of key presses stored.");
+    }
+
+    #[cfg(test)]
+    mod quirk_tests {
+        use super::*;
+
+        #[test]
+        fn reset_quirk() {
+            let mut reset_quirk_config = QuirkConfig::new();
+            reset_quirk_config.reset_vf = ResetVfQuirk::Reset;
+            let mut no_reset_quirk_config = QuirkConfig::new();
+            no_reset_quirk_config.reset_vf = ResetVfQuirk::NoReset;
+            let mut reset_interpreter = Interpreter::new_headless(reset_quirk_config);
+            let mut no_reset_interpreter = Interpreter::new_headless(no_reset_quirk_config);
+
+            let first_register = 0x0;
+            let second_register = 0x1;
+            let first_value = 0xAA;
+            let second_value = 0xF0;
+            reset_interpreter.registers[first_register] = first_value;
+            reset_interpreter.registers[second_register] = second_value;
+            reset_interpreter.registers[REGISTER_F] = 0x1;
+            no_reset_interpreter.registers[first_register] = first_value;
+            no_reset_interpreter.registers[second_register] = second_value;
+            no_reset_interpreter.registers[REGISTER_F] = 0x1;
+            reset_interpreter.handle_opcode(&Opcode::And(first_register, second_register));
+            no_reset_interpreter.handle_opcode(&Opcode::And(first_register, second_register));
+            assert_eq!(reset_interpreter.registers[REGISTER_F], 0x0, "Register F not reset.");
+            assert_eq!(no_reset_interpreter.registers[REGISTER_F], 0x1, "Register F reset.");
+
+            reset_interpreter.registers[REGISTER_F] = 0x1;
+            no_reset_interpreter.registers[REGISTER_F] = 0x1;
+            reset_interpreter.handle_opcode(&Opcode::Or(first_register, second_register));
+            no_reset_interpreter.handle_opcode(&Opcode::Or(first_register, second_register));
+            assert_eq!(reset_interpreter.registers[REGISTER_F], 0x0, "Register F not reset.");
+            assert_eq!(no_reset_interpreter.registers[REGISTER_F], 0x1, "Register F reset.");
+
+            reset_interpreter.registers[REGISTER_F] = 0x1;
+            no_reset_interpreter.registers[REGISTER_F] = 0x1;
+            reset_interpreter.handle_opcode(&Opcode::Xor(first_register, second_register));
+            no_reset_interpreter.handle_opcode(&Opcode::Xor(first_register, second_register));
+            assert_eq!(reset_interpreter.registers[REGISTER_F], 0x0, "Register F not reset.");
+            assert_eq!(no_reset_interpreter.registers[REGISTER_F], 0x1, "Register F reset.");
+        }
+
+        #[test]
+        #[allow(clippy::cast_possible_truncation)]
+        fn memory_quirk() {
+            let mut increment_quirk_config = QuirkConfig::new();
+            increment_quirk_config.memory = MemoryIncrementQuirk::Increment;
+            let mut no_increment_quirk_config = QuirkConfig::new();
+            no_increment_quirk_config.memory = MemoryIncrementQuirk::NoIncrement;
+            let mut increment_interpreter = Interpreter::new_headless(increment_quirk_config);
+            let mut no_increment_interpreter = Interpreter::new_headless(no_increment_quirk_config);
+
+            let register_values = &[0x32, 0xBC, 0x12, 0xFF, 0x74];
+            let register = 0x4;
+            let starting_address = 0x834;
+            increment_interpreter.register_i = starting_address;
+            no_increment_interpreter.register_i = starting_address;
+            increment_interpreter.registers[..=register].copy_from_slice(&register_values[..=register]);
+            no_increment_interpreter.registers[..=register].copy_from_slice(&register_values[..=register]);
+
+            increment_interpreter.handle_opcode(&Opcode::StoreRegisters(register));
+            no_increment_interpreter.handle_opcode(&Opcode::StoreRegisters(register));
+
+            assert_eq!(increment_interpreter.register_i, starting_address + register as u16 + 1, "Register I value not incremented.");
+            assert_eq!(no_increment_interpreter.register_i, starting_address, "Register I value incremented.");
+        }
+
+        #[test]
+        fn display_wait_quirk() {
+            let mut wait_quirk_config = QuirkConfig::new();
+            wait_quirk_config.display_wait = DisplayWaitQuirk::Wait;
+            let mut no_wait_quirk_config = QuirkConfig::new();
+            no_wait_quirk_config.display_wait = DisplayWaitQuirk::NoWait;
+            let mut wait_interpreter = Interpreter::new_headless(wait_quirk_config);
+            let mut no_wait_interpreter = Interpreter::new_headless(no_wait_quirk_config);
+
+            let first_register = 0x0;
+            let second_register = 0x1;
+            let sprite = 0xAA;
+            let sprite_location = 0x999;
+            wait_interpreter.register_i = sprite_location;
+            no_wait_interpreter.register_i = sprite_location;
+            wait_interpreter.ram[sprite_location as usize] = sprite;
+            no_wait_interpreter.ram[sprite_location as usize] = sprite;
+            wait_interpreter.handle_opcode(&Opcode::Draw(first_register, second_register, HEXADECIMAL_DIGIT_SPRITE_LENGTH));
+            no_wait_interpreter.handle_opcode(&Opcode::Draw(first_register, second_register, HEXADECIMAL_DIGIT_SPRITE_LENGTH));
+            assert!(wait_interpreter.should_wait_for_display_refresh, "Not waiting for display refresh.");
+            assert!(!no_wait_interpreter.should_wait_for_display_refresh, "Waiting for display refresh.");
+            assert_eq!(wait_interpreter.wait_for_display_refresh_data, (first_register, second_register, HEXADECIMAL_DIGIT_SPRITE_LENGTH), "Wrong data set to wait for display refresh.");
+            assert_eq!(no_wait_interpreter.wait_for_display_refresh_data, (0x0, 0x0, 0x0), "Data set to wait for display refresh.");
+            assert!(!wait_interpreter.drawing_buffer[0], "Data drawn to buffer.");
+            assert!(no_wait_interpreter.drawing_buffer[0], "Data not drawn to buffer.");
+        }
+
+        #[test]
+        fn shifting_quirk() {
+            let mut disabled_quirk_config = QuirkConfig::new();
+            disabled_quirk_config.shifting = ShiftingQuirk::Vy;
+            let mut enabled_quirk_config = QuirkConfig::new();
+            enabled_quirk_config.shifting = ShiftingQuirk::Vx;
+            let mut disabled_shift_interpreter = Interpreter::new_headless(disabled_quirk_config);
+            let mut enabled_shift_interpreter = Interpreter::new_headless(enabled_quirk_config);
+
+            let first_register = 0x0;
+            let second_register = 0x1;
+            let first_value = 0xAA;
+            let second_value = 0xF0;
+            disabled_shift_interpreter.registers[first_register] = first_value;
+            disabled_shift_interpreter.registers[second_register] = second_value;
+            enabled_shift_interpreter.registers[first_register] = first_value;
+            enabled_shift_interpreter.registers[second_register] = second_value;
+            disabled_shift_interpreter.handle_opcode(&Opcode::BitShiftLeft(first_register, second_register));
+            enabled_shift_interpreter.handle_opcode(&Opcode::BitShiftLeft(first_register, second_register));
+            assert_eq!(disabled_shift_interpreter.registers[first_register], second_value << 1, "Left shift not performed correctly.");
+            assert_eq!(disabled_shift_interpreter.registers[second_register], second_value, "Second register modified.");
+            assert_eq!(disabled_shift_interpreter.registers[REGISTER_F], 0x1, "Register F not set.");
+            assert_eq!(enabled_shift_interpreter.registers[first_register], first_value << 1, "Left shift not performed correctly.");
+            assert_eq!(enabled_shift_interpreter.registers[second_register], second_value, "Second register modified.");
+            assert_eq!(enabled_shift_interpreter.registers[REGISTER_F], 0x1, "Register F not set.");
+
+            disabled_shift_interpreter.registers[first_register] = first_value;
+            enabled_shift_interpreter.registers[first_register] = first_value;
+            disabled_shift_interpreter.handle_opcode(&Opcode::BitShiftRight(first_register, second_register));
+            enabled_shift_interpreter.handle_opcode(&Opcode::BitShiftRight(first_register, second_register));
+            assert_eq!(disabled_shift_interpreter.registers[first_register], second_value >> 1, "Left shift not performed correctly.");
+            assert_eq!(disabled_shift_interpreter.registers[second_register], second_value, "Second register modified.");
+            assert_eq!(disabled_shift_interpreter.registers[REGISTER_F], 0x0, "Register F not set.");
+            assert_eq!(enabled_shift_interpreter.registers[first_register], first_value >> 1, "Left shift not performed correctly.");
+            assert_eq!(enabled_shift_interpreter.registers[second_register], second_value, "Second register modified.");
+            assert_eq!(enabled_shift_interpreter.registers[REGISTER_F], 0x0, "Register F not set.");
+        }
+
+        #[test]
+        fn clipping_quirk() {
+            let combinations = [
+                (ClippingQuirk::Clip, ClippingQuirk::Clip),
+                (ClippingQuirk::Clip, ClippingQuirk::Wrap),
+                (ClippingQuirk::Wrap, ClippingQuirk::Clip),
+                (ClippingQuirk::Wrap, ClippingQuirk::Wrap)
+            ];
+
+            let first_register = 0x0;
+            let second_register = 0x1;
+            #[allow(clippy::cast_possible_truncation)]
+            let first_value = (SCREEN_WIDTH - 1) as u8;
+            #[allow(clippy::cast_possible_truncation)]
+            let second_value = (SCREEN_HEIGHT - 1) as u8;
+            let sprite = 0xFF;
+            let sprite_height = 0x2;
+            let start_address = 0x888;
+            let start_address_usize = start_address as usize;
+            let bottom_row = ((SCREEN_HEIGHT - 1) * SCREEN_WIDTH) as usize;
+
+            for (horizontal_clipping, vertical_clipping) in combinations {
+                let mut quirk_config = QuirkConfig::new();
+                quirk_config.horizontal_clipping = horizontal_clipping.clone();
+                quirk_config.vertical_clipping = vertical_clipping.clone();
+                let mut interpreter = Interpreter::new_headless(quirk_config);
+
+                interpreter.registers[first_register] = first_value;
+                interpreter.registers[second_register] = second_value;
+                interpreter.register_i = start_address;
+                interpreter.ram[start_address_usize] = sprite;
+                interpreter.ram[start_address_usize + 1] = sprite;
+                interpreter.complete_draw(first_register, second_register, sprite_height);
+
+                let wraps_horizontally = horizontal_clipping == ClippingQuirk::Wrap;
+                let wraps_vertically = vertical_clipping == ClippingQuirk::Wrap;
+                assert!(interpreter.drawing_buffer[LOW_RESOLUTION_PIXEL_COUNT - 1], "Pre-clip sprite not drawn for {horizontal_clipping:?}/{vertical_clipping:?}.");
+                assert_eq!(interpreter.drawing_buffer[SCREEN_WIDTH as usize - 1], wraps_vertically, "Sprite handled incorrectly on the Y axis for {horizontal_clipping:?}/{vertical_clipping:?}.");
+                for x in 0..7 {
+                    assert_eq!(interpreter.drawing_buffer[bottom_row + x], wraps_horizontally, "Sprite handled incorrectly on the X axis for {horizontal_clipping:?}/{vertical_clipping:?}.");
+                    assert_eq!(interpreter.drawing_buffer[x], wraps_horizontally && wraps_vertically, "Sprite handled incorrectly on the X and Y axes for {horizontal_clipping:?}/{vertical_clipping:?}.");
+                }
+            }
+        }
+
+        #[test]
+        fn combined_clipping_quirk() {
+            let mut quirk_config = QuirkConfig::new();
+            quirk_config.set_clipping(ClippingQuirk::Wrap);
+            assert_eq!(quirk_config.horizontal_clipping, ClippingQuirk::Wrap, "Combined clipping not applied horizontally.");
+            assert_eq!(quirk_config.vertical_clipping, ClippingQuirk::Wrap, "Combined clipping not applied vertically.");
+
+            quirk_config.set_clipping(ClippingQuirk::Clip);
+            assert_eq!(quirk_config.horizontal_clipping, ClippingQuirk::Clip, "Combined clipping not applied horizontally.");
+            assert_eq!(quirk_config.vertical_clipping, ClippingQuirk::Clip, "Combined clipping not applied vertically.");
+        }
+
+        #[test]
+        fn jumping_quirk() {
+            let mut disabled_quirk_config = QuirkConfig::new();
+            disabled_quirk_config.jumping = JumpingQuirk::V0;
+            let mut enabled_quirk_config = QuirkConfig::new();
+            enabled_quirk_config.jumping = JumpingQuirk::Vx;
+            let mut disabled_jump_interpreter = Interpreter::new_headless(disabled_quirk_config);
+            let mut enabled_jump_interpreter = Interpreter::new_headless(enabled_quirk_config);
+
+            let first_register = 0x0;
+            let second_register = 0x5;
+            let first_value = 0xA;
+            let second_value = 0x6;
+            let address = 0x543;
+            disabled_jump_interpreter.registers[first_register] = first_value;
+            disabled_jump_interpreter.registers[second_register] = second_value;
+            enabled_jump_interpreter.registers[first_register] = first_value;
+            enabled_jump_interpreter.registers[second_register] = second_value;
+            disabled_jump_interpreter.handle_opcode(&Opcode::JumpAddrV0(address));
+            enabled_jump_interpreter.handle_opcode(&Opcode::JumpAddrV0(address));
+
+            assert_eq!(disabled_jump_interpreter.program_counter, address + u16::from(first_value), "Jumped to value in wrong register.");
+            assert_eq!(enabled_jump_interpreter.program_counter, address + u16::from(second_value), "Jumped to value in wrong register.");
+        }
+
+        #[test]
+        fn memory_timing_quirk() {
+            let mut disabled_quirk_config = QuirkConfig::new();
+            disabled_quirk_config.memory_timing = MemoryTimingQuirk::Instant;
+            let mut enabled_quirk_config = QuirkConfig::new();
+            enabled_quirk_config.memory_timing = MemoryTimingQuirk::PerRegister;
+            let mut disabled_timing_interpreter = Interpreter::new_headless(disabled_quirk_config);
+            let mut enabled_timing_interpreter = Interpreter::new_headless(enabled_quirk_config);
+
+            // Store V0-V2, BCD, then load V0-V3
+            let game = [0xF2, 0x55, 0xF0, 0x33, 0xF3, 0x65, 0x12, 0x06];
+            disabled_timing_interpreter.load_game(&game);
+            enabled_timing_interpreter.load_game(&game);
+            let mut enabled_addresses = Vec::new();
+            for _ in 0..10 {
+                enabled_addresses.push(enabled_timing_interpreter.program_counter);
+                disabled_timing_interpreter.handle_cycle();
+                enabled_timing_interpreter.handle_cycle();
+            }
+
+            assert_eq!(disabled_timing_interpreter.program_counter, 0x206, "Memory opcodes took more than a cycle.");
+            assert_eq!(enabled_addresses, [0x200, 0x202, 0x202, 0x202, 0x204, 0x204, 0x204, 0x206, 0x206, 0x206], "Memory opcodes took the wrong number of cycles.");
+        }
+
+        #[test]
+        fn key_ghosting_quirk() {
+            let mut disabled_quirk_config = QuirkConfig::new();
+            disabled_quirk_config.key_ghosting = KeyGhostingQuirk::Independent;
+            let mut enabled_quirk_config = QuirkConfig::new();
+            enabled_quirk_config.key_ghosting = KeyGhostingQuirk::Ghosting;
+            let mut disabled_ghosting_interpreter = Interpreter::new_headless(disabled_quirk_config);
+            let mut enabled_ghosting_interpreter = Interpreter::new_headless(enabled_quirk_config);
+
+            // Skip the next instruction if key 5 is pressed
+            let game = [0x60, 0x05, 0xE0, 0x9E];
+            disabled_ghosting_interpreter.load_game(&game);
+            enabled_ghosting_interpreter.load_game(&game);
+            for key in [0x1, 0x2, 0x4] {
+                disabled_ghosting_interpreter.keyboard |= 1 << key;
+                enabled_ghosting_interpreter.keyboard |= 1 << key;
+            }
+            for _ in 0..2 {
+                disabled_ghosting_interpreter.handle_cycle();
+                enabled_ghosting_interpreter.handle_cycle();
+            }
+
+            assert_eq!(disabled_ghosting_interpreter.program_counter, 0x204, "Key ghosted with the quirk disabled.");
+            assert_eq!(enabled_ghosting_interpreter.program_counter, 0x206, "Key not ghosted with the quirk enabled.");
+        }
+    }
+
+    #[cfg(test)]
+    mod opcode_tests {
+        use super::*;
+
+        #[test]
+        fn handle_jump_addr_opcode() {
+            let mut interpreter = Interpreter::new();
+
+            let address = 0x381;
+            interpreter.handle_opcode(&Opcode::JumpAddr(address));
+            assert_eq!(interpreter.program_counter, address, "Program counter not updated.");
+        }
+
+        #[test]
+        fn handle_skip_register_equals_value_opcode() {
+            let mut interpreter = Interpreter::new();
+
+            let register = 0x5;
+            let value = 0xA2;
+            interpreter.handle_opcode(&Opcode::SkipRegisterEqualsValue(register, value));
+            assert_eq!(interpreter.program_counter, 0x0, "Program counter updated when register value doesn't match.");
+            assert_eq!(interpreter.registers[register], 0x0, "Register value modified.");
+
+            interpreter.registers[register] = value;
+            interpreter.handle_opcode(&Opcode::SkipRegisterEqualsValue(register, value));
+            assert_eq!(interpreter.program_counter, PROGRAM_COUNTER_INCREMENT, "Program counter not updated when register value matches.");
+            assert_eq!(interpreter.registers[register], value, "Register value modified.");
+        }
+
+        #[test]
+        fn handle_skip_register_not_equals_value_opcode() {
+            let mut interpreter = Interpreter::new();
+
+            let register = 0xA;
+            let first_value = 0x23;
+            interpreter.registers[register] = first_value;
+            interpreter.handle_opcode(&Opcode::SkipRegisterNotEqualsValue(register, first_value));
+            assert_eq!(interpreter.program_counter, 0x0, "Program counter updated when register value matches.");
+            assert_eq!(interpreter.registers[register], first_value, "Register value modified.");
+
+            let second_value = 0x24;
+            interpreter.registers[register] = second_value;
+            interpreter.handle_opcode(&Opcode::SkipRegisterNotEqualsValue(register, first_value));
+            assert_eq!(interpreter.program_counter, PROGRAM_COUNTER_INCREMENT, "Program counter not updated when register value doesn't match.");
+            assert_eq!(interpreter.registers[register], second_value, "Register value modified.");
+        }
+
+        #[test]
+        fn handle_skip_registers_equal_opcode() {
+            let mut interpreter = Interpreter::new();
+
+            let first_register = 0x1;
+            let second_register = 0x2;
+            let first_value = 0x4;
+            let second_value = 0x5;
+            interpreter.registers[first_register] = first_value;
+            interpreter.registers[second_register] = second_value;
+            interpreter.handle_opcode(&Opcode::SkipRegistersEqual(first_register, second_register));
+            assert_eq!(interpreter.program_counter, 0x0, "Program counter updated when registers don't match.");
+            assert_eq!(interpreter.registers[first_register], first_value, "First register value modified.");
+            assert_eq!(interpreter.registers[second_register], second_value, "Second register value modified.");
+
+            interpreter.registers[first_register] = second_value;
+            interpreter.handle_opcode(&Opcode::SkipRegistersEqual(first_register, second_register));
+            assert_eq!(interpreter.program_counter, PROGRAM_COUNTER_INCREMENT, "Program counter not updated when registers match.");
+            assert_eq!(interpreter.registers[first_register], second_value, "First register value modified.");
+            assert_eq!(interpreter.registers[second_register], second_value, "Second register value modified.");
+        }
+
+        #[test]
+        fn handle_skip_registers_not_equal_opcode() {
+            let mut interpreter = Interpreter::new();
+
+            let first_register = 0x1;
+            let second_register = 0x2;
+            let first_value = 0x4;
+            interpreter.registers[first_register] = first_value;
+            interpreter.registers[second_register] = first_value;
+            interpreter.handle_opcode(&Opcode::SkipRegistersNotEqual(first_register, second_register));
+            assert_eq!(interpreter.program_counter, 0x0, "Program counter updated when registers match.");
+            assert_eq!(interpreter.registers[first_register], first_value, "First register value modified.");
+            assert_eq!(interpreter.registers[second_register], first_value, "Second register value modified.");
+
+            let second_value = 0x5;
+            interpreter.registers[first_register] = second_value;
+            interpreter.handle_opcode(&Opcode::SkipRegistersNotEqual(first_register, second_register));
+            assert_eq!(interpreter.program_counter, PROGRAM_COUNTER_INCREMENT, "Program counter not updated when registers don't match.");
+            assert_eq!(interpreter.registers[first_register], second_value, "First register value modified.");
+            assert_eq!(interpreter.registers[second_register], first_value, "Second register value modified.");
+        }
+
+        #[test]
+        fn handle_load_value_opcode() {
+            let mut interpreter = Interpreter::new();
+
+            let register = 0x0;
+            let value = 0x58;
+            interpreter.handle_opcode(&Opcode::LoadValue(register, value));
+            assert_eq!(interpreter.registers[register], value, "Value not loaded into register.");
+        }
+
+        #[test]
+        fn handle_add_value_opcode() {
+            let mut interpreter = Interpreter::new();
+
+            let register = 0x6;
+            let value = 0xAB;
+            let first_added_value = 0x11;
+            interpreter.registers[register] = value;
+            interpreter.handle_opcode(&Opcode::AddValue(register, first_added_value));
+            assert_eq!(interpreter.registers[register], value + first_added_value, "Regular addition failed.");
+
+            let second_added_value = 0xAA;
+            interpreter.handle_opcode(&Opcode::AddValue(register, second_added_value));
+            let sum_with_truncation = 0x66;
+            assert_eq!(interpreter.registers[register], sum_with_truncation, "Overflow not handled. Should truncate.");
+        }
+
+        #[test]
+        fn handle_load_register_value_opcode() {
+            let mut interpreter = Interpreter::new();
+
+            let original_value = 0xDD;
+            let first_register = 0x0;
+            let second_register = 0x1;
+            interpreter.registers[second_register] = original_value;
+            interpreter.handle_opcode(&Opcode::LoadRegisterValue(first_register, second_register));
+            assert_eq!(interpreter.registers[first_register], original_value, "Value not loaded into register.");
+            assert_eq!(interpreter.registers[second_register], original_value, "Original register value modified.");
+        }
+
+        #[test]
+        fn handle_or_opcode() {
+            let mut interpreter = Interpreter::new();
+
+            let first_register = 0x2;
+            let second_register = 0x4;
+            let first_value = 0xCC;
+            let second_value = 0xC3;
+            interpreter.registers[first_register] = first_value;
+            interpreter.registers[second_register] = second_value;
+            interpreter.registers[REGISTER_F] = 0x1;
+            interpreter.handle_opcode(&Opcode::Or(first_register, second_register));
+            assert_eq!(interpreter.registers[first_register], first_value | second_value, "Bitwise OR not applied correctly.");
+            assert_eq!(interpreter.registers[second_register], second_value, "Second register value modified.");
+            assert_eq!(interpreter.registers[REGISTER_F], 0x0, "Register F not reset.");
+        }
+
+        #[test]
+        fn handle_and_opcode() {
+            let mut interpreter = Interpreter::new();
+
+            let first_register = 0x6;
+            let second_register = 0x3;
+            let first_value = 0xAA;
+            let second_value = 0xCC;
+            interpreter.registers[first_register] = first_value;
+            interpreter.registers[second_register] = second_value;
+            interpreter.registers[REGISTER_F] = 0x1;
+            interpreter.handle_opcode(&Opcode::And(first_register, second_register));
+            assert_eq!(interpreter.registers[first_register], first_value & second_value, "Bitwise AND not applied correctly.");
+            assert_eq!(interpreter.registers[second_register], second_value, "Second register value modified.");
+            assert_eq!(interpreter.registers[REGISTER_F], 0x0, "Register F not reset.");
+        }
+
+        #[test]
+        fn handle_xor_opcode() {
+            let mut interpreter = Interpreter::new();
+
+            let first_register = 0xB;
+            let second_register = 0xE;
+            let first_value = 0x33;
+            let second_value = 0x55;
+            interpreter.registers[first_register] = first_value;
+            interpreter.registers[second_register] = second_value;
+            interpreter.registers[REGISTER_F] = 0x1;
+            interpreter.handle_opcode(&Opcode::Xor(first_register, second_register));
+            assert_eq!(interpreter.registers[first_register], first_value ^ second_value, "Bitwise XOR not applied correctly.");
+            assert_eq!(interpreter.registers[second_register], second_value, "Second register value modified.");
+            assert_eq!(interpreter.registers[REGISTER_F], 0x0, "Register F not reset.");
+        }
+
+        #[test]
+        fn handle_random_opcode() {
+            let mut interpreter = Interpreter::new();
+
+            // Since the result is random, we basically just check to make sure that it doesn't panic
+            interpreter.handle_opcode(&Opcode::Random(0x9, 0x53));
+        }
+
+        #[allow(clippy::cast_possible_truncation)]
+        #[test]
+        fn handle_store_registers_opcode() {
+            let mut interpreter = Interpreter::new();
+
+            let register_values = &[0x32, 0xBC, 0x12, 0xFF, 0x74];
+            let register = 0x4;
+            let starting_address = 0x834;
+            let starting_address_usize = starting_address as usize;
+            interpreter.register_i = starting_address;
+            interpreter.registers[0..=register].copy_from_slice(&register_values[0..=register]);
+
+            interpreter.handle_opcode(&Opcode::StoreRegisters(register));
+
+            assert_eq!(interpreter.ram[starting_address_usize - 0x1], 0x0, "Ram location before starting address modified.");
+            assert_eq!(interpreter.ram[starting_address_usize + register + 0x1], 0x0, "Ram location past modification area modified.");
+            assert_eq!(interpreter.register_i, starting_address + register as u16 + 1, "Register I value not incremented.");
+
+            for (i, register_value) in register_values.iter().enumerate() {
+                assert_eq!(interpreter.ram[starting_address_usize + i], *register_value, "Register value not stored.");
+                assert_eq!(interpreter.registers[i], *register_value, "Register value modified.");
+            }
+        }
+
+        #[test]
+        fn font_write_warning() {
+            let mut interpreter = Interpreter::new();
+            interpreter.load_game(&[0xA0, 0x00, 0x60, 0x00, 0xF0, 0x55, 0xA0, 0x00, 0x12, 0x04]);
+            for _ in 0..11 {
+                interpreter.handle_cycle();
+            }
+
+            assert_eq!(interpreter.ram[0x000], 0x00, "Font not overwritten.");
+            assert_eq!(interpreter.take_memory_warnings(), vec![MemoryWarning::FontWrite { address: 0x204, target: 0x000 }], "Font write not warned about exactly once.");
+            assert!(interpreter.take_memory_warnings().is_empty(), "Warnings not taken.");
+        }
+
+        #[test]
+        fn font_write_warning_outside_font() {
+            // Writes between the end of the fonts and a later load address leave the fonts alone
+            for (target, is_font) in [(0x300, false), (0x0F0, false), (0x0A0, true)] {
+                for memory_protection in [MemoryProtection::Off, MemoryProtection::Font] {
+                    let mut interpreter = Interpreter::new();
+                    interpreter.set_memory_protection(memory_protection);
+                    interpreter.set_load_address(0x600).unwrap();
+                    let [high, low] = (0xA000_u16 | target).to_be_bytes();
+                    interpreter.load_game(&[high, low, 0x60, 0xAB, 0xF0, 0x55, 0x16, 0x06]);
+                    for _ in 0..4 {
+                        interpreter.handle_cycle();
+                    }
+
+                    let is_protected = is_font && memory_protection == MemoryProtection::Font;
+                    let warnings = if is_font && !is_protected { vec![MemoryWarning::FontWrite { address: 0x604, target }] } else { Vec::new() };
+                    assert_eq!(interpreter.take_memory_warnings(), warnings, "Write to {target:#05X} warned about incorrectly for {memory_protection:?}.");
+                    assert_eq!(interpreter.halt_reason().is_some(), is_protected, "Write to {target:#05X} protected incorrectly for {memory_protection:?}.");
+                }
+            }
+        }
+
+        #[test]
+        fn memory_protection() {
+            let game = [0xA0, 0x00, 0xF1, 0x55, 0x00, 0x00];
+            for (memory_protection, target, is_halted) in [
+                (MemoryProtection::Off, 0x000, false),
+                (MemoryProtection::Font, 0x000, true),
+                (MemoryProtection::Font, 0x202, false),
+                (MemoryProtection::Program, 0x202, true),
+                (MemoryProtection::Program, 0x206, false)
+            ] {
+                let mut interpreter = Interpreter::new();
+                interpreter.set_memory_protection(memory_protection);
+                interpreter.load_game(&game);
+                interpreter.handle_cycle();
+                interpreter.register_i = target;
+                interpreter.registers[0x0] = 0xAB;
+                interpreter.registers[0x1] = 0xCD;
+                let original = interpreter.ram[target as usize..target as usize + 2].to_vec();
+                interpreter.handle_cycle();
+
+                let halt_reason = is_halted.then_some(HaltReason::ProtectedWrite { address: 0x202, target });
+                assert_eq!(interpreter.halt_reason(), halt_reason, "Write handled incorrectly for {memory_protection:?} at {target:#05X}.");
+                assert_eq!(interpreter.ram[target as usize..target as usize + 2] == original, is_halted, "Protected memory handled incorrectly for {memory_protection:?} at {target:#05X}.");
+            }
+        }
+
+        #[allow(clippy::cast_possible_truncation)]
+        #[test]
+        fn handle_load_registers_opcode() {
+            let mut interpreter = Interpreter::new();
+
+            let ram_values = &[0x32, 0xBC, 0x12, 0xFF, 0x74, 0x92, 0x11, 0xF0];
+            let register = 0x7;
+            let starting_address = 0x900;
+            let starting_address_usize = starting_address as usize;
+            interpreter.register_i = starting_address;
+            interpreter.ram[starting_address_usize..=(starting_address_usize + register)].copy_from_slice(&ram_values[0..=register]);
+
+            interpreter.handle_opcode(&Opcode::LoadRegisters(register));
+
+            assert_eq!(interpreter.registers[register + 0x1], 0x0, "Register after modification area modified.");
+            assert_eq!(interpreter.register_i, starting_address + register as u16 + 1, "Register I value not incremented.");
+
+            for (i, ram_value) in ram_values.iter().enumerate() {
+                assert_eq!(interpreter.registers[i], *ram_value, "Register value not loaded.");
+                assert_eq!(interpreter.ram[starting_address_usize + i], *ram_value, "Ram value modified.");
+            }
+        }
+
+        #[test]
+        fn handle_load_register_i_opcode() {
+            let mut interpreter = Interpreter::new();
+
+            let address = 0x246;
+            interpreter.handle_opcode(&Opcode::LoadRegisterI(address));
+            assert_eq!(interpreter.register_i, address, "Register I not updated.");
+        }
+
+        #[test]
+        fn handle_jump_address_v0_opcode() {
+            let mut interpreter = Interpreter::new();
+
+            let register = 0x0;
+            let value = 0x34;
+            let address = 0x111;
+            interpreter.registers[register] = value;
+            interpreter.handle_opcode(&Opcode::JumpAddrV0(address));
+            assert_eq!(interpreter.program_counter, u16::from(value) + address, "Program counter not updated.");
+            assert_eq!(interpreter.registers[register], value, "Register 0 modified.");
+        }
+
+        #[test]
+        fn handle_load_delay_timer_opcode() {
+            let mut interpreter = Interpreter::new();
+
+            let value = 0x54;
+            let register = 0x6;
+            interpreter.delay_timer = value;
+            interpreter.handle_opcode(&Opcode::LoadDelayTimer(register));
+            assert_eq!(interpreter.registers[register], value, "Register not updated.");
+            assert_eq!(interpreter.delay_timer, value, "Delay timer modified.");
+        }
+
+        #[test]
+        fn handle_set_delay_timer_opcode() {
+            let mut interpreter = Interpreter::new();
+
+            let value = 0x20;
+            let register = 0x9;
+            interpreter.registers[register] = value;
+            interpreter.handle_opcode(&Opcode::SetDelayTimer(register));
+            assert_eq!(interpreter.delay_timer, value, "Delay timer not updated.");
+            assert_eq!(interpreter.registers[register], value, "Register modified.");
+        }
+
+        #[test]
+        fn handle_set_sound_timer_opcode() {
+            let mut interpreter = Interpreter::new();
+            let audio = MockAudio::new();
+            interpreter.attach_backends(None, Some(Box::new(audio.clone())));
+
+            let value = 0x77;
+            let register = 0x4;
+            interpreter.registers[register] = value;
+            interpreter.handle_opcode(&Opcode::SetSoundTimer(register));
+            assert_eq!(interpreter.sound_timer, value, "Sound timer not updated.");
+            assert_eq!(interpreter.registers[register], value, "Register modified.");
+            assert!(audio.is_playing(PRIMARY_VOICE), "Sound not started.");
+
+            interpreter.set_muted(true);
+            interpreter.set_muted(false);
+            interpreter.set_audio_paused(false);
+            interpreter.sound_timer = 1;
+            interpreter.handle_timers();
+            assert_eq!(audio.gates(PRIMARY_VOICE), [true, false, true, false], "Sound not stopped while muted and when the timer ran out.");
+        }
+
+        #[test]
+        fn handle_add_register_i_opcode() {
+            let mut interpreter = Interpreter::new();
+
+            let value = 0x52;
+            let starting_address: u16 = 0x894;
+            let register = 0x7;
+            interpreter.register_i = starting_address;
+            interpreter.registers[register] = value;
+            interpreter.handle_opcode(&Opcode::AddRegisterI(register));
+            assert_eq!(interpreter.register_i, starting_address + u16::from(value), "Register I not updated.");
+            assert_eq!(interpreter.registers[register], value, "Register modified.");
+        }
+
+        #[test]
+        fn handle_add_registers_opcode() {
+            let mut interpreter = Interpreter::new();
+
+            let first_register = 0x0;
+            let second_register = 0x1;
+            let first_value = 0x4;
+            let second_value = 0x8;
+            interpreter.registers[first_register] = first_value;
+            interpreter.registers[second_register] = second_value;
+            interpreter.handle_opcode(&Opcode::AddRegisters(first_register, second_register));
+            assert_eq!(interpreter.registers[first_register], first_value + second_value, "Basic addition failed.");
+            assert_eq!(interpreter.registers[second_register], second_value, "Second register modified.");
+            assert_eq!(interpreter.registers[REGISTER_F], 0, "Overflow bit incorrectly set.");
+
+            interpreter.registers[REGISTER_F] = first_value;
+            interpreter.registers[second_register] = second_value;
+            interpreter.handle_opcode(&Opcode::AddRegisters(REGISTER_F, second_register));
+            assert_eq!(interpreter.registers[second_register], second_value, "Second register modified.");
+            assert_eq!(interpreter.registers[REGISTER_F], 0, "Overflow bit incorrectly set.");
+
+            interpreter.registers[first_register] = first_value;
+            interpreter.registers[REGISTER_F] = second_value;
+            interpreter.handle_opcode(&Opcode::AddRegisters(first_register, REGISTER_F));
+            assert_eq!(interpreter.registers[first_register], first_value + second_value, "Basic addition with register F failed.");
+            assert_eq!(interpreter.registers[REGISTER_F], 0, "Overflow bit incorrectly set.");
+
+            let first_value = 0xEE;
+            let second_value = 0xDD;
+            interpreter.registers[first_register] = first_value;
+            interpreter.registers[second_register] = second_value;
+            interpreter.handle_opcode(&Opcode::AddRegisters(first_register, second_register));
+            assert_eq!(interpreter.registers[first_register], 0xCB, "Addition with overflow failed.");
+            assert_eq!(interpreter.registers[second_register], second_value, "Second register modified.");
+            assert_eq!(interpreter.registers[REGISTER_F], 1, "Overflow bit incorrectly not set.");
+
+            interpreter.registers[REGISTER_F] = first_value;
+            interpreter.registers[second_register] = second_value;
+            interpreter.handle_opcode(&Opcode::AddRegisters(REGISTER_F, second_register));
+            assert_eq!(interpreter.registers[second_register], second_value, "Second register modified.");
+            assert_eq!(interpreter.registers[REGISTER_F], 1, "Overflow bit incorrectly set.");
+
+            interpreter.registers[first_register] = first_value;
+            interpreter.registers[REGISTER_F] = second_value;
+            interpreter.handle_opcode(&Opcode::AddRegisters(first_register, REGISTER_F));
+            assert_eq!(interpreter.registers[first_register], 0xCB, "Addition with overflow with register F failed.");
+            assert_eq!(interpreter.registers[REGISTER_F], 1, "Overflow bit incorrectly set.");
+        }
+
+        #[test]
+        fn handle_subtract_from_register_opcodes() {
+            let mut interpreter = Interpreter::new();
+
+            let first_register = 0x7;
+            let second_register = 0x6;
+            let first_value: u8 = 0xF;
+            let second_value: u8 = 0x2;
+            let third_value: u8 = 0xE;
+            let difference = first_value - second_value;
+            let underflow_difference = difference.overflowing_sub(third_value).0;
+            interpreter.registers[first_register] = first_value;
+            interpreter.registers[second_register] = second_value;
+            interpreter.handle_opcode(&Opcode::SubtractFromFirstRegister(first_register, second_register));
+            assert_eq!(interpreter.registers[first_register], difference, "Basic subtraction failed.");
+            assert_eq!(interpreter.registers[second_register], second_value, "Second register modified.");
+            assert_eq!(interpreter.registers[REGISTER_F], 1, "Borrow bit incorrectly not set.");
+
+            interpreter.registers[second_register] = third_value;
+            interpreter.handle_opcode(&Opcode::SubtractFromFirstRegister(first_register, second_register));
+            assert_eq!(interpreter.registers[first_register], underflow_difference, "Underflow subtraction failed.");
+            assert_eq!(interpreter.registers[second_register], third_value, "Second register modified.");
+            assert_eq!(interpreter.registers[REGISTER_F], 0, "Borrow bit incorrectly set.");
+
+            interpreter.registers[second_register] = first_value;
+            interpreter.registers[first_register] = second_value;
+            interpreter.handle_opcode(&Opcode::SubtractFromSecondRegister(first_register, second_register));
+            assert_eq!(interpreter.registers[first_register], difference, "Basic subtraction failed.");
+            assert_eq!(interpreter.registers[second_register], first_value, "Second register modified.");
+            assert_eq!(interpreter.registers[REGISTER_F], 1, "Borrow bit incorrectly not set.");
+
+            interpreter.registers[second_register] = third_value;
+            interpreter.handle_opcode(&Opcode::SubtractFromSecondRegister(second_register, first_register));
+            assert_eq!(interpreter.registers[second_register], underflow_difference, "Underflow subtraction failed.");
+            assert_eq!(interpreter.registers[first_register], difference, "First register modified.");
+            assert_eq!(interpreter.registers[REGISTER_F], 0, "Borrow bit incorrectly set.");
+
+            interpreter.registers[first_register] = third_value;
+            interpreter.registers[second_register] = third_value;
+            interpreter.handle_opcode(&Opcode::SubtractFromSecondRegister(second_register, first_register));
+            assert_eq!(interpreter.registers[second_register], 0x0, "Zero-result subtraction failed.");
+            assert_eq!(interpreter.registers[first_register], third_value, "First register modified.");
+            assert_eq!(interpreter.registers[REGISTER_F], 1, "Borrow bit incorrectly not set.");
+
+            interpreter.registers[REGISTER_F] = first_value;
+            interpreter.registers[second_register] = second_value;
+            interpreter.handle_opcode(&Opcode::SubtractFromFirstRegister(REGISTER_F, second_register));
+            assert_eq!(interpreter.registers[second_register], second_value, "Second register modified.");
+            assert_eq!(interpreter.registers[REGISTER_F], 1, "Borrow bit incorrectly not set.");
+
+            interpreter.registers[second_register] = third_value;
+            interpreter.handle_opcode(&Opcode::SubtractFromFirstRegister(REGISTER_F, second_register));
+            assert_eq!(interpreter.registers[second_register], third_value, "Second register modified.");
+            assert_eq!(interpreter.registers[REGISTER_F], 0, "Borrow bit incorrectly set.");
+
+            interpreter.registers[REGISTER_F] = first_value;
+            interpreter.registers[first_register] = second_value;
+            interpreter.handle_opcode(&Opcode::SubtractFromSecondRegister(first_register, REGISTER_F));
+            assert_eq!(interpreter.registers[first_register], difference, "Basic subtraction with register F failed.");
+            assert_eq!(interpreter.registers[REGISTER_F], 1, "Borrow bit incorrectly not set.");
+
+            interpreter.registers[REGISTER_F] = third_value;
+            interpreter.handle_opcode(&Opcode::SubtractFromSecondRegister(REGISTER_F, first_register));
+            assert_eq!(interpreter.registers[first_register], difference, "First register modified.");
+            assert_eq!(interpreter.registers[REGISTER_F], 0, "Borrow bit incorrectly set.");
+
+            interpreter.registers[REGISTER_F] = third_value;
+            interpreter.registers[second_register] = third_value;
+            interpreter.handle_opcode(&Opcode::SubtractFromSecondRegister(second_register, REGISTER_F));
+            assert_eq!(interpreter.registers[second_register], 0x0, "Zero-result subtraction failed.");
+            assert_eq!(interpreter.registers[REGISTER_F], 1, "Borrow bit incorrectly not set.");
+        }
+
+        #[test]
+        fn handle_bit_shift_right_opcode() {
+            let mut interpreter = Interpreter::new();
+
+            let first_register = 0x3;
+            let second_register = 0x5;
+            let value = 0xAA;
+            interpreter.registers[second_register] = value;
+            interpreter.handle_opcode(&Opcode::BitShiftRight(first_register, second_register));
+            assert_eq!(interpreter.registers[first_register], value >> 1, "Bit shift right failed.");
+            assert_eq!(interpreter.registers[second_register], value, "Second register modified.");
+            assert_eq!(interpreter.registers[REGISTER_F], 0, "Shift bit incorrectly set");
+
+            interpreter.registers[second_register] = interpreter.registers[first_register];
+            interpreter.handle_opcode(&Opcode::BitShiftRight(first_register, second_register));
+            assert_eq!(interpreter.registers[first_register], value >> 2, "Bit shift right failed.");
+            assert_eq!(interpreter.registers[second_register], value >> 1, "Second register modified.");
+            assert_eq!(interpreter.registers[REGISTER_F], 1, "Shift bit incorrectly not set");
+
+            interpreter.handle_opcode(&Opcode::BitShiftRight(REGISTER_F, second_register));
+            assert_eq!(interpreter.registers[second_register], value >> 1, "Second register modified.");
+            assert_eq!(interpreter.registers[REGISTER_F], 1, "Shift bit incorrectly set");
+        }
+
+        #[test]
+        fn handle_bit_shift_left_opcode() {
+            let mut interpreter = Interpreter::new();
+
+            let first_register = 0xC;
+            let second_register = 0xB;
+            let value = 0xAA;
+            interpreter.registers[second_register] = value;
+            interpreter.handle_opcode(&Opcode::BitShiftLeft(first_register, second_register));
+            assert_eq!(interpreter.registers[first_register], value << 1, "Bit shift left failed.");
+            assert_eq!(interpreter.registers[second_register], value, "Second register modified.");
+            assert_eq!(interpreter.registers[REGISTER_F], 1, "Shift bit incorrectly not set");
+
+            interpreter.registers[second_register] = interpreter.registers[first_register];
+            interpreter.handle_opcode(&Opcode::BitShiftLeft(first_register, second_register));
+            assert_eq!(interpreter.registers[first_register], value << 2, "Bit shift left failed.");
+            assert_eq!(interpreter.registers[second_register], value << 1, "Second register modified.");
+            assert_eq!(interpreter.registers[REGISTER_F], 0, "Shift bit incorrectly set");
+
+            interpreter.handle_opcode(&Opcode::BitShiftLeft(REGISTER_F, second_register));
+            assert_eq!(interpreter.registers[second_register], value << 1, "Second register modified.");
+            assert_eq!(interpreter.registers[REGISTER_F], 0, "Shift bit incorrectly set");
+        }
+
+        #[test]
+        fn handle_binary_encoded_decimal_opcode() {
+            let mut interpreter = Interpreter::new();
+
+            let register = 0xA;
+            let value = 0xDA;
+            let starting_address = 0x783;
+            interpreter.registers[register] = value;
+            interpreter.register_i = starting_address;
+            interpreter.handle_opcode(&Opcode::BinaryCodedDecimal(register));
+            assert_eq!(interpreter.ram[starting_address as usize], 0x2, "Binary encoded decimal hundreds digit incorrect.");
+            assert_eq!(interpreter.ram[(starting_address + 0x1) as usize], 0x1, "Binary encoded decimal tens digit incorrect.");
+            assert_eq!(interpreter.ram[(starting_address + 0x2) as usize], 0x8, "Binary encoded decimal units digit incorrect.");
+            assert_eq!(interpreter.registers[register], value, "Register modified.");
+            assert_eq!(interpreter.register_i, starting_address, "Register I modified.");
+        }
+
+        #[test]
+        fn handle_call_addr_opcode() {
+            let mut interpreter = Interpreter::new();
+
+            let original_program_counter = 0x7E2;
+            let first_address = 0x999;
+            let second_address = 0x432;
+            let current_program_counter = original_program_counter;
+            interpreter.program_counter = current_program_counter;
+            interpreter.handle_opcode(&Opcode::CallAddr(first_address));
+            assert_eq!(interpreter.program_counter, first_address, "Program counter not updated.");
+            assert_eq!(interpreter.stack[interpreter.stack_pointer - 1], current_program_counter, "Program counter not placed on the stack.");
+            assert_eq!(interpreter.stack_pointer, 0x1, "Stack pointer not incremented.");
+
+            let current_program_counter = first_address;
+            interpreter.handle_opcode(&Opcode::SystemAddr(second_address));
+            assert_eq!(interpreter.program_counter, second_address, "Program counter not updated.");
+            assert_eq!(interpreter.stack[interpreter.stack_pointer - 1], current_program_counter, "Program counter not placed on the stack.");
+            assert_eq!(interpreter.stack_pointer, 0x2, "Stack pointer not incremented.");
+            assert_eq!(interpreter.stack[interpreter.stack_pointer - 2], original_program_counter, "Previous address on the stack modified.");
+        }
+
+        #[test]
+        fn handle_call_addr_opcode_overflow() {
+            let mut interpreter = Interpreter::new();
+
+            interpreter.load_game(&[0x22, 0x00]);
+            for _ in 0..STACK_SIZE {
+                interpreter.handle_cycle();
+            }
+            assert_eq!(interpreter.halt_reason(), None, "Halted before the stack was full.");
+
+            interpreter.handle_cycle();
+            assert_eq!(interpreter.halt_reason(), Some(HaltReason::StackOverflow { address: PROGRAM_START_ADDRESS }), "Stack overflow not caught.");
+            assert_eq!(interpreter.stack_pointer, STACK_SIZE, "Stack pointer moved past the stack.");
+        }
+
+        #[test]
+        fn handle_return_opcode_underflow() {
+            let mut interpreter = Interpreter::new();
+
+            interpreter.load_game(&[0x00, 0xEE]);
+            interpreter.handle_cycle();
+            assert_eq!(interpreter.halt_reason(), Some(HaltReason::StackUnderflow { address: PROGRAM_START_ADDRESS }), "Stack underflow not caught.");
+        }
+
+        #[test]
+        fn handle_exit_opcodes() {
+            for exit_opcode in [[0x00, 0x00], [0x00, 0xFD]] {
+                let mut interpreter = Interpreter::new();
+
+                interpreter.load_game(&exit_opcode);
+                interpreter.handle_cycle();
+                assert_eq!(interpreter.halt_reason(), Some(HaltReason::Exit), "Exit opcode did not halt.");
+                assert_eq!(interpreter.stack_pointer, 0, "Exit opcode called a subroutine.");
+            }
+        }
+
+        #[test]
+        fn handle_return_opcode() {
+            let mut interpreter = Interpreter::new();
+
+            let top_address = 0x234;
+            let bottom_address = 0x123;
+            interpreter.stack_pointer = 0x2;
+            interpreter.stack[0x0] = bottom_address;
+            interpreter.stack[0x1] = top_address;
+            interpreter.handle_opcode(&Opcode::Return);
+            assert_eq!(interpreter.program_counter, top_address, "Return from subroutine failed after one call.");
+            assert_eq!(interpreter.stack_pointer, 0x1, "Stack pointer not decremented.");
+            assert_eq!(interpreter.stack[interpreter.stack_pointer], top_address, "Top address on the stack modified.");
+            assert_eq!(interpreter.stack[interpreter.stack_pointer - 1], bottom_address, "Bottom address on the stack modified.");
+
+            interpreter.handle_opcode(&Opcode::Return);
+            assert_eq!(interpreter.program_counter, bottom_address, "Return from subroutine failed after two calls.");
+            assert_eq!(interpreter.stack_pointer, 0x0, "Stack pointer not decremented.");
+            assert_eq!(interpreter.stack[interpreter.stack_pointer + 1], top_address, "Top address on the stack modified.");
+            assert_eq!(interpreter.stack[interpreter.stack_pointer], bottom_address, "Bottom address on the stack modified.");
+        }
+
+        #[test]
+        fn handle_set_register_i_hex_sprite_location_opcode() {
+            let mut interpreter = Interpreter::new();
+
+            let register = 0x3;
+            let value = 0xE;
+            interpreter.registers[register] = value;
+            interpreter.handle_opcode(&Opcode::SetIHexSpriteLocation(register));
+            assert_eq!(interpreter.register_i, 0x46, "Register I not set correctly.");
+            assert_eq!(interpreter.registers[register], value, "Register value modified.");
+        }
+
+        #[test]
+        fn handle_set_register_i_large_hex_sprite_location_opcode() {
+            let mut interpreter = Interpreter::new();
+
+            let register = 0x3;
+            let value = 0xE;
+            interpreter.registers[register] = value;
+            interpreter.handle_opcode(&Opcode::SetILargeHexSpriteLocation(register));
+            assert_eq!(interpreter.register_i, 0xDC, "Register I not set correctly.");
+            assert_eq!(interpreter.ram[usize::from(interpreter.register_i)..usize::from(interpreter.register_i) + 2], [0xFF, 0xFF], "Register I not pointing at the sprite.");
+            assert_eq!(interpreter.registers[register], value, "Register value modified.");
+        }
+
+        #[test]
+        fn handle_skip_key_pressed_opcode() {
+            let mut interpreter = Interpreter::new();
+
+            let register = 0xA;
+            let value = 0x5;
+            let other_key = 0x8;
+            interpreter.registers[register] = value;
+            interpreter.keyboard |= 1 << other_key;
+            interpreter.handle_opcode(&Opcode::SkipKeyPressed(register));
+            assert_eq!(interpreter.program_counter, 0x0, "Program counter incremented incorrectly.");
+            assert_eq!(interpreter.registers[register], value, "Register value modified.");
+            assert!(interpreter.keyboard & (1 << other_key) != 0, "Keys pressed modified.");
+            assert_eq!(interpreter.keyboard.count_ones(), 0x1, "Number of keys pressed is incorrect.");
+
+            interpreter.keyboard |= 1 << value;
+            interpreter.handle_opcode(&Opcode::SkipKeyPressed(register));
+            assert_eq!(interpreter.program_counter, PROGRAM_COUNTER_INCREMENT, "Program counter not incremented.");
+            assert_eq!(interpreter.registers[register], value, "Register value modified.");
+            assert!(interpreter.keyboard & (1 << value) != 0, "Keys pressed modified.");
+            assert!(interpreter.keyboard & (1 << other_key) != 0, "Keys pressed modified.");
+            assert_eq!(interpreter.keyboard.count_ones(), 0x2, "Number of keys pressed is incorrect.");
+        }
+
+        #[test]
+        fn handle_skip_key_not_pressed_opcode() {
+            let mut interpreter = Interpreter::new();
+
+            let register = 0xA;
+            let value = 0x5;
+            let other_key = 0x8;
+            interpreter.registers[register] = value;
+            interpreter.keyboard |= 1 << value;
+            interpreter.keyboard |= 1 << other_key;
+            interpreter.handle_opcode(&Opcode::SkipKeyNotPressed(register));
+            assert_eq!(interpreter.program_counter, 0x0, "Program counter incremented incorrectly.");
+            assert_eq!(interpreter.registers[register], value, "Register value modified.");
+            assert!(interpreter.keyboard & (1 << value) != 0, "Keys pressed modified.");
+            assert!(interpreter.keyboard & (1 << other_key) != 0, "Keys pressed modified.");
+            assert_eq!(interpreter.keyboard.count_ones(), 0x2, "Number of keys pressed is incorrect.");
+
+            interpreter.keyboard &= !(1 << value);
+            interpreter.handle_opcode(&Opcode::SkipKeyNotPressed(register));
+            assert_eq!(interpreter.program_counter, PROGRAM_COUNTER_INCREMENT, "Program counter not incremented.");
+            assert_eq!(interpreter.registers[register], value, "Register value modified.");
+            assert!(interpreter.keyboard & (1 << other_key) != 0, "Keys pressed modified.");
+            assert_eq!(interpreter.keyboard.count_ones(), 0x1, "Number of keys pressed is incorrect.");
+        }
+
+        #[test]
+        fn handle_load_key_press_opcode() {
+            let mut interpreter = Interpreter::new();
+
+            let register = 0x7;
+            let program_start_usize = PROGRAM_START_ADDRESS as usize;
+            interpreter.program_counter = PROGRAM_START_ADDRESS;
+            interpreter.ram[program_start_usize] = 0xAA;
+            interpreter.ram[program_start_usize + 1] = 0xAA;
+            interpreter.handle_opcode(&Opcode::LoadKeyPress(register));
+            interpreter.handle_cycle();
+            assert_eq!(interpreter.register_i, 0x0, "Opcode handled when execution should have been paused.");
+            assert_eq!(interpreter.program_counter, PROGRAM_START_ADDRESS, "Program counter incremented incorrectly.");
+            assert!(interpreter.should_wait_for_key, "Not waiting for key press.");
+            assert_eq!(interpreter.wait_for_key_register, register, "Wrong register set for loading.");
+
+            interpreter.press_key(0x4);
+            interpreter.handle_cycle();
+            assert_eq!(interpreter.register_i, 0x0, "Opcode handled when execution should have been paused.");
+            assert_eq!(interpreter.program_counter, PROGRAM_START_ADDRESS, "Program counter incremented incorrectly.");
+            assert!(interpreter.should_wait_for_key, "Not waiting for key press.");
+            assert_eq!(interpreter.registers[register], 0x4, "Wrong key loaded into register.");
+
+            interpreter.release_key(0x4);
+            interpreter.handle_cycle();
+            assert_eq!(interpreter.register_i, 0xAAA, "Opcode not handled.");
+            assert_eq!(interpreter.program_counter, PROGRAM_START_ADDRESS + PROGRAM_COUNTER_INCREMENT, "Program counter not incremented.");
+            assert!(!interpreter.should_wait_for_key, "Waiting for key press.");
+            assert_eq!(interpreter.registers[register], 0x4, "Wrong key loaded into register.");
+        }
+
+        #[test]
+        fn handle_clear_screen_opcode() {
+            let mut interpreter = Interpreter::new();
+
+            (0..DRAWING_BUFFER_SIZE).for_each(|i| interpreter.drawing_buffer.set(i, rand::random()));
+            interpreter.handle_opcode(&Opcode::ClearScreen);
+            assert_eq!(interpreter.drawing_buffer, PlaneBuffer::new(), "Drawing buffer was not cleared.");
+        }
+
+        #[test]
+        fn handle_long_load_register_i_opcode() {
+            let mut interpreter = Interpreter::new();
+
+            // Skips over the long load, landing on the load after it
+            interpreter.load_game(&[0x30, 0x00, 0xF0, 0x00, 0xAB, 0xCD, 0xF0, 0x00, 0xFF, 0xFE]);
+            interpreter.handle_cycle();
+            assert_eq!(interpreter.program_counter, 0x206, "Long instruction not skipped.");
+            interpreter.handle_cycle();
+            assert_eq!(interpreter.register_i, 0xFFFE, "Register I not set.");
+            assert_eq!(interpreter.program_counter, 0x20A, "Program counter not moved past the long instruction.");
+
+            // Memory is addressed up to 64K
+            interpreter.registers[0x0] = 0x12;
+            interpreter.registers[0x1] = 0x34;
+            interpreter.handle_opcode(&Opcode::StoreRegisters(0x1));
+            assert_eq!(interpreter.ram[0xFFFE..], [0x12, 0x34], "Extended memory not written.");
+        }
+
+        #[test]
+        fn handle_rpl_flags_opcodes() {
+            let mut interpreter = Interpreter::new();
+            for (register, value) in interpreter.registers.iter_mut().zip(1..) {
+                *register = value;
+            }
+
+            interpreter.handle_opcode(&Opcode::StoreRplFlags(0xF));
+            assert_eq!(interpreter.rpl_flags, [1, 2, 3, 4, 5, 6, 7, 8], "RPL flags not stored.");
+            assert!(interpreter.take_rpl_flags_changed(), "RPL flag change not reported.");
+            assert!(!interpreter.take_rpl_flags_changed(), "RPL flag change reported twice.");
+
+            interpreter.load_game(&[]);
+            assert_eq!(interpreter.rpl_flags, [1, 2, 3, 4, 5, 6, 7, 8], "RPL flags reset after game load.");
+            interpreter.handle_opcode(&Opcode::LoadRplFlags(0x2));
+            assert_eq!(interpreter.registers[..4], [1, 2, 3, 0], "Registers not loaded from the RPL flags.");
+        }
+
+        #[test]
+        fn handle_audio_opcodes() {
+            let mut interpreter = Interpreter::new();
+            let audio = MockAudio::new();
+            interpreter.attach_backends(None, Some(Box::new(audio.clone())));
+            interpreter.register_i = 0xFFF8;
+            interpreter.ram[0xFFF8] = 0xAA;
+            interpreter.ram[0x7] = 0x55;
+            interpreter.registers[0x3] = 0x70;
+
+            interpreter.handle_opcode(&Opcode::LoadAudioPattern);
+            let pattern = interpreter.audio_pattern.unwrap();
+            assert_eq!((pattern[0], pattern[PATTERN_LENGTH - 1]), (0xAA, 0x55), "Audio pattern not loaded from register I.");
+
+            assert_eq!(audio.take_commands()[0], AudioCommand::SetPattern(PRIMARY_VOICE, Some(pattern)), "Audio pattern not sent to the output.");
+
+            interpreter.handle_opcode(&Opcode::SetPitch(0x3));
+            assert_eq!(interpreter.pitch, 0x70, "Pitch not updated.");
+            assert_eq!(audio.commands()[1], AudioCommand::SetFrequency(PRIMARY_VOICE, audio::pattern_frequency(0x70)), "Pitch not sent to the output.");
+
+            interpreter.load_game(&[]);
+            assert!(interpreter.audio_pattern.is_none(), "Audio pattern not reset after game load.");
+            assert_eq!(interpreter.pitch, DEFAULT_PITCH, "Pitch not reset after game load.");
+        }
+
+        #[test]
+        fn draw_planes() {
+            let mut interpreter = Interpreter::new();
+            let start_address: u16 = 0x888;
+            interpreter.ram[start_address as usize] = 0x80;
+            interpreter.ram[start_address as usize + 1] = 0x40;
+            interpreter.register_i = start_address;
+
+            // Both planes draw their own byte, one after the other
+            interpreter.handle_opcode(&Opcode::SelectPlanes(3));
+            interpreter.complete_draw(0x0, 0x1, 1);
+            assert_eq!(interpreter.display_buffer().take(2).collect::<Vec<bool>>(), [true, false], "First plane drawn incorrectly.");
+            assert_eq!(interpreter.second_plane_buffer().take(2).collect::<Vec<bool>>(), [false, true], "Second plane drawn incorrectly.");
+
+            interpreter.handle_opcode(&Opcode::SelectPlanes(2));
+            interpreter.complete_draw(0x0, 0x1, 1);
+            assert_eq!(interpreter.registers[REGISTER_F], 0x0, "Collision bit set for an unselected plane.");
+            assert_eq!(interpreter.second_plane_buffer().take(2).collect::<Vec<bool>>(), [true, true], "Selected plane not drawn.");
+
+            interpreter.handle_opcode(&Opcode::ClearScreen);
+            assert!(interpreter.second_plane_buffer().all(|pixel| !pixel), "Selected plane not cleared.");
+            assert!(interpreter.drawing_buffer[0], "Unselected plane cleared.");
+        }
+
+        #[test]
+        fn handle_scroll_opcodes() {
+            for is_high_resolution in [false, true] {
+                let mut interpreter = Interpreter::new();
+                interpreter.set_high_resolution(is_high_resolution);
+                let width = interpreter.display_width() as usize;
+                let height = interpreter.display_height() as usize;
+                interpreter.drawing_buffer.set(width + 5, true);
+                interpreter.drawing_buffer.set((height - 1) * width, true);
+
+                interpreter.handle_opcode(&Opcode::ScrollDown(3));
+                assert!(interpreter.drawing_buffer[4 * width + 5], "Display not scrolled down.");
+                assert_eq!(interpreter.display_buffer().filter(|pixel| *pixel).count(), 1, "Pixel not scrolled off the bottom.");
+
+                interpreter.handle_opcode(&Opcode::ScrollRight);
+                assert!(interpreter.drawing_buffer[4 * width + 9], "Display not scrolled right.");
+                interpreter.handle_opcode(&Opcode::ScrollLeft);
+                interpreter.handle_opcode(&Opcode::ScrollLeft);
+                assert!(interpreter.drawing_buffer[4 * width + 1], "Display not scrolled left.");
+                interpreter.handle_opcode(&Opcode::ScrollLeft);
+                assert!(interpreter.display_buffer().all(|pixel| !pixel), "Pixel not scrolled off the left.");
+            }
+        }
+
+        #[test]
+        fn handle_resolution_opcodes() {
+            let mut interpreter = Interpreter::new();
+            assert_eq!((interpreter.display_width(), interpreter.display_height()), (SCREEN_WIDTH, SCREEN_HEIGHT), "Display started in the wrong resolution.");
+
+            interpreter.drawing_buffer.set(0, true);
+            interpreter.handle_opcode(&Opcode::HighResolution);
+            assert!(interpreter.is_high_resolution(), "High resolution not set.");
+            assert_eq!(interpreter.display_buffer().len(), DRAWING_BUFFER_SIZE, "Display buffer not resized.");
+            assert!(!interpreter.drawing_buffer[0], "Drawing buffer not cleared on switching resolution.");
+
+            interpreter.handle_opcode(&Opcode::LowResolution);
+            assert!(!interpreter.is_high_resolution(), "Low resolution not set.");
+            assert_eq!(interpreter.display_buffer().len(), LOW_RESOLUTION_PIXEL_COUNT, "Display buffer not resized.");
+
+            interpreter.handle_opcode(&Opcode::HighResolution);
+            interpreter.load_game(&[0x00, 0xE0]);
+            assert!(!interpreter.is_high_resolution(), "Resolution not reset after game load.");
+        }
+
+        #[test]
+        fn two_page_display() {
+            let mut interpreter = Interpreter::new();
+            let mut game_data = vec![0x12, 0x60];
+            game_data.resize(usize::from(TWO_PAGE_PROGRAM_START_ADDRESS - PROGRAM_START_ADDRESS), 0);
+            game_data.extend([0x61, 0x00, 0x62, 0x3C, 0xA0, 0x00, 0xD1, 0x25, 0x02, 0x30]);
+            interpreter.load_game(&game_data);
+            assert!(interpreter.is_two_page_display(), "HiRes CHIP-8 game not recognized.");
+            assert_eq!((interpreter.display_width(), interpreter.display_height()), (SCREEN_WIDTH, TWO_PAGE_SCREEN_HEIGHT), "Display not 64x64.");
+            assert_eq!(interpreter.program_counter, TWO_PAGE_PROGRAM_START_ADDRESS, "Game not started after the interpreter patch.");
+
+            for _ in 0..4 {
+                interpreter.handle_cycle();
+            }
+            interpreter.handle_frame();
+            assert!(interpreter.drawing_buffer[63 * SCREEN_WIDTH as usize], "Sprite not drawn on the second page.");
+            interpreter.handle_cycle();
+            assert!(interpreter.display_buffer().all(|pixel| !pixel), "Display not cleared by 0230.");
+            assert_eq!(interpreter.halt_reason(), None, "0230 taken for another opcode.");
+
+            interpreter.load_game(&[0x12, 0x60]);
+            assert!(interpreter.is_two_page_display(), "Two page display not kept for the next HiRes game.");
+            interpreter.load_game(&[0x00, 0xE0]);
+            assert!(!interpreter.is_two_page_display(), "Two page display kept for a regular game.");
+
+            interpreter.set_hires_detection(false);
+            interpreter.load_game(&game_data);
+            assert!(!interpreter.is_two_page_display(), "HiRes CHIP-8 game recognized with the detection off.");
+            assert_eq!(interpreter.program_counter, PROGRAM_START_ADDRESS, "Game not started at the load address with the detection off.");
+        }
+
+        #[test]
+        fn draw_large_sprite() {
+            let mut interpreter = Interpreter::new();
+            let start_address: u16 = 0x888;
+            for i in 0..16 {
+                interpreter.ram[start_address as usize + i * 2] = 0x80;
+                interpreter.ram[start_address as usize + i * 2 + 1] = 0x01;
+            }
+            interpreter.register_i = start_address;
+            interpreter.registers[0x0] = 100;
+            interpreter.registers[0x1] = 2;
+
+            // Outside of high resolution, a length of 0 draws nothing
+            interpreter.complete_draw(0x0, 0x1, 0);
+            assert!(interpreter.display_buffer().all(|pixel| !pixel), "Empty sprite drawn in low resolution.");
+
+            interpreter.set_high_resolution(true);
+            interpreter.complete_draw(0x0, 0x1, 0);
+            let width = HIGH_RESOLUTION_SCREEN_WIDTH as usize;
+            for y in 2..18 {
+                assert!(interpreter.drawing_buffer[y * width + 100], "Left column of large sprite not drawn.");
+                assert!(interpreter.drawing_buffer[y * width + 115], "Right column of large sprite not drawn.");
+                assert!(!interpreter.drawing_buffer[y * width + 101], "Large sprite drawn incorrectly.");
+            }
+            assert!(!interpreter.drawing_buffer[18 * width + 100], "Large sprite drawn too tall.");
+            assert_eq!(interpreter.registers[REGISTER_F], 0x0, "Collision bit incorrectly set.");
+
+            interpreter.complete_draw(0x0, 0x1, 0);
+            assert_eq!(interpreter.registers[REGISTER_F], 0x1, "Collision bit not set.");
+        }
+
+        #[test]
+        fn handle_draw_opcode() {
+            let mut interpreter = Interpreter::new();
+
+            let first_register = 0x0;
+            let second_register = 0x2;
+            interpreter.handle_opcode(&Opcode::Draw(first_register, second_register, HEXADECIMAL_DIGIT_SPRITE_LENGTH));
+            assert!(interpreter.should_wait_for_display_refresh, "Not waiting for display refresh.");
+            assert_eq!(interpreter.wait_for_display_refresh_data, (first_register, second_register, HEXADECIMAL_DIGIT_SPRITE_LENGTH), "Wrong data set to wait for display refresh.");
+        }
+
+        #[allow(clippy::cast_possible_truncation)]
+        #[test]
+        fn draw_complete() {
+            let mut interpreter = Interpreter::new();
+
+            let first_register = 0x0;
+            let second_register = 0x2;
+            let first_value = 0x1;
+            let second_value = 0x0;
+            interpreter.registers[first_register] = first_value;
+            interpreter.registers[second_register] = second_value;
+            interpreter.set_register_i_hex_sprite_location(first_register);
+            let first_value = 0x0;
+            interpreter.registers[first_register] = first_value;
+            interpreter.complete_draw(first_register, second_register, HEXADECIMAL_DIGIT_SPRITE_LENGTH);
+
+            // Draw a regular sprite
+            let ram_values = &HEXADECIMAL_DIGIT_SPRITES[HEXADECIMAL_DIGIT_SPRITE_LENGTH as usize..HEXADECIMAL_DIGIT_SPRITE_LENGTH as usize * 2];
+            assert_eq!(interpreter.registers[REGISTER_F], 0x0, "Collision bit incorrectly set.");
+            for (i, ram_value) in ram_values.iter().enumerate() {
+                for j in 0..8 {
+                    assert_eq!(interpreter.drawing_buffer[i * SCREEN_WIDTH as usize + j], ((*ram_value >> (7 - j)) & 1) == 0x1, "Simple drawn value is incorrect.");
+                }
+            }
+
+            // Draw a sprite that clips at the edge of the screen
+            let first_value = 20;
+            let second_value = (SCREEN_HEIGHT - 1) as u8;
+            interpreter.registers[first_register] = first_value;
+            interpreter.registers[second_register] = second_value;
+            interpreter.complete_draw(first_register, second_register, HEXADECIMAL_DIGIT_SPRITE_LENGTH);
+            assert_eq!(interpreter.registers[REGISTER_F], 0x0, "Collision bit incorrectly set.");
+            for (i, ram_value) in ram_values.iter().enumerate() {
+                for j in 0..8 {
+                    let drawing_buffer_index = ((i + second_value as usize) * SCREEN_WIDTH as usize) + first_value as usize + j;
+                    let target_value = if drawing_buffer_index >= LOW_RESOLUTION_PIXEL_COUNT { false } else { ((*ram_value >> (7 - j)) & 1) == 0x1 };
+                    assert_eq!(interpreter.drawing_buffer[drawing_buffer_index % LOW_RESOLUTION_PIXEL_COUNT], target_value, "Clipping drawn value is incorrect.");
+                }
+            }
+
+            // Draw a sprite that wraps around the edge of the screen
+            let first_value = SCREEN_WIDTH as u8;
+            let second_value = 10;
+            interpreter.registers[first_register] = first_value;
+            interpreter.registers[second_register] = second_value;
+            interpreter.complete_draw(first_register, second_register, HEXADECIMAL_DIGIT_SPRITE_LENGTH);
+            assert_eq!(interpreter.registers[REGISTER_F], 0x0, "Collision bit incorrectly set.");
+            for (i, ram_value) in ram_values.iter().enumerate() {
+                for j in 0..8 {
+                    let drawing_buffer_index = ((i + second_value as usize) * SCREEN_WIDTH as usize) + (first_value % SCREEN_WIDTH as u8) as usize + j;
+                    let target_value = ((*ram_value >> (7 - j)) & 1) == 0x1;
+                    assert_eq!(interpreter.drawing_buffer[drawing_buffer_index], target_value, "Wrapping drawn value is incorrect.");
+                }
+            }
+
+            // Draw a colliding sprite
+            let first_value = 0x0;
+            let second_value = 0x0;
+            interpreter.registers[first_register] = first_value;
+            interpreter.registers[second_register] = second_value;
+            interpreter.set_register_i_hex_sprite_location(first_register);
+            let new_ram_values = &HEXADECIMAL_DIGIT_SPRITES[0..HEXADECIMAL_DIGIT_SPRITE_LENGTH as usize];
+            interpreter.complete_draw(first_register, second_register, HEXADECIMAL_DIGIT_SPRITE_LENGTH);
+            assert_eq!(interpreter.registers[REGISTER_F], 0x1, "Collision bit incorrectly not set.");
+            for i in 0..HEXADECIMAL_DIGIT_SPRITE_LENGTH as usize {
+                let sprite_one_byte = ram_values[i];
+                let sprite_two_byte = new_ram_values[i];
+                for j in 0..8 {
+                    let sprite_one_bit = (sprite_one_byte >> (7 - j)) & 1;
+                    let sprite_two_bit = (sprite_two_byte >> (7 - j)) & 1;
+                    assert_eq!(interpreter.drawing_buffer[i * SCREEN_WIDTH as usize + j], (sprite_one_bit ^ sprite_two_bit) == 0x1, "Overlapping drawn value is incorrect.");
+                }
+            }
+
+            // Draw a simple colliding sprite to ensure the collision bit is set correctly
+            let first_value = 0xA;
+            let second_value = 0x0;
+            interpreter.registers[first_register] = first_value;
+            interpreter.registers[second_register] = second_value;
+            interpreter.ram[0xCCC] = 0xFF;
+            interpreter.register_i = 0xCCC;
+            interpreter.registers[REGISTER_F] = 0x0;
+            interpreter.complete_draw(first_register, second_register, 1);
+            assert_eq!(interpreter.registers[REGISTER_F], 0x0, "Collision bit incorrectly not set.");
+
+            interpreter.complete_draw(first_register, second_register, 1);
+            assert_eq!(interpreter.registers[REGISTER_F], 0x1, "Collision bit incorrectly not set.");
+        }
+    }
+}
@@ -0,0 +1,153 @@
+//! A module to remap the keyboard per game, as games use different parts of the keypad (e.g. 2/4/6/8 to move rather than Q/E).
+//! A profile is kept alongside the game file as `<game>.keys`, with one entry per line and `#` starting a comment:
+//!
+//! ```text
+//! name Arrow keys
+//! Up = 2
+//! Left = 4
+//! Right = 6
+//! Down = 8
+//! ```
+//!
+//! Each physical key is named as SDL names it, and mapped to a CHIP-8 key in hexadecimal. Keys not in the profile keep their usual mapping, and hotkeys take priority over both.
+
+use std::fmt::{Display, Formatter};
+use std::io::{self, ErrorKind};
+use std::str::FromStr;
+
+use crate::storage::StorageBackend;
+
+/// The extension added to the game file for its key profile.
+const KEY_PROFILE_FILE_EXTENSION: &str = "keys";
+
+/// The character starting a comment line.
+const COMMENT_PREFIX: char = '#';
+
+/// The keyword starting the line which names the profile.
+const NAME_KEYWORD: &str = "name";
+
+/// The largest CHIP-8 key.
+const MAX_KEY: u8 = 0xF;
+
+/// Stores the physical keys a game maps to CHIP-8 keys in place of the usual mapping.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KeyProfile {
+    /// The name of the profile shown when it is applied, if it has one.
+    pub name: Option<String>,
+    /// The physical keys, named as SDL names them, along with the CHIP-8 keys they press.
+    pub keys: Vec<(String, u8)>
+}
+
+impl KeyProfile {
+    /// Returns the path of the file storing the key profile of the provided game.
+    ///
+    /// # Parameters
+    ///
+    /// * `game_path` - The path to the game file.
+    #[must_use]
+    pub fn path_for_game(game_path: &str) -> String {
+        format!("{game_path}.{KEY_PROFILE_FILE_EXTENSION}")
+    }
+
+    /// Loads the key profile of the provided game, returning `None` if it has none.
+    ///
+    /// # Parameters
+    ///
+    /// * `storage` - Where the key profile is saved.
+    /// * `game_path` - The path to the game file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the file exists but cannot be read or is malformed.
+    pub fn load_for_game(storage: &dyn StorageBackend, game_path: &str) -> io::Result<Option<KeyProfile>> {
+        let Some(contents) = storage.read_to_string(&Self::path_for_game(game_path))? else {
+            return Ok(None);
+        };
+
+        contents.parse().map(Some).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))
+    }
+
+    /// Returns the CHIP-8 key the physical key is mapped to by the profile, or `None` if the profile leaves it alone.
+    /// Key names are matched regardless of case.
+    ///
+    /// # Parameters
+    ///
+    /// * `key_name` - The name of the physical key, as SDL names it.
+    #[must_use]
+    pub fn key_for(&self, key_name: &str) -> Option<u8> {
+        self.keys.iter().find(|(name, _)| name.eq_ignore_ascii_case(key_name)).map(|(_, key)| *key)
+    }
+}
+
+impl FromStr for KeyProfile {
+    type Err = String;
+
+    /// Parses the key profile format described in the [module documentation](self).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut profile = KeyProfile::default();
+        for line in s.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with(COMMENT_PREFIX)) {
+            if let Some(name) = line.strip_prefix(NAME_KEYWORD).filter(|name| name.starts_with(char::is_whitespace)) {
+                profile.name = Some(name.trim().to_string());
+                continue;
+            }
+
+            let (key_name, key) = line.split_once('=').ok_or_else(|| format!("Key mapping without an =: {line}"))?;
+            let (key_name, key) = (key_name.trim(), key.trim());
+            if key_name.is_empty() {
+                return Err(format!("Key mapping without a key name: {line}"));
+            }
+            let key = u8::from_str_radix(key.trim_start_matches("0x"), 16).ok().filter(|key| *key <= MAX_KEY).ok_or_else(|| format!("Invalid CHIP-8 key: {key}"))?;
+            profile.keys.push((key_name.to_string(), key));
+        }
+
+        if profile.keys.is_empty() {
+            return Err(String::from("The key profile maps no keys."));
+        }
+
+        Ok(profile)
+    }
+}
+
+impl Display for KeyProfile {
+    /// Formats the profile for the window title, e.g. `Arrow keys (Up=2, Left=4)`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let keys: Vec<String> = self.keys.iter().map(|(name, key)| format!("{name}={key:X}")).collect();
+        match &self.name {
+            Some(name) => write!(f, "{name} ({})", keys.join(", ")),
+            None => write!(f, "{}", keys.join(", "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    #[test]
+    fn parse_key_profile() {
+        let profile: KeyProfile = "# Moves with the arrows\nname Arrow keys\nUp = 2\n\nLeft=0x4\nKeypad 6 = 6\n".parse().unwrap();
+        assert_eq!(profile.name.as_deref(), Some("Arrow keys"), "Profile name not parsed.");
+        assert_eq!(profile.to_string(), "Arrow keys (Up=2, Left=4, Keypad 6=6)", "Profile described incorrectly.");
+        assert_eq!(profile.key_for("left"), Some(0x4), "Key not matched regardless of case.");
+        assert_eq!(profile.key_for("Keypad 6"), Some(0x6), "Key name with a space not matched.");
+        assert_eq!(profile.key_for("Q"), None, "Unmapped key matched.");
+
+        assert!("Up = 10".parse::<KeyProfile>().is_err(), "Key past F parsed.");
+        assert!("Up 2".parse::<KeyProfile>().is_err(), "Mapping without an = parsed.");
+        assert!(" = 2".parse::<KeyProfile>().is_err(), "Mapping without a key name parsed.");
+        assert!("name Empty".parse::<KeyProfile>().is_err(), "Profile without keys parsed.");
+        assert_eq!("names = 3".parse::<KeyProfile>().map(|profile| profile.key_for("names")), Ok(Some(0x3)), "Key starting with the name keyword not parsed.");
+    }
+
+    #[test]
+    fn load_for_game() {
+        let mut storage = MemoryStorage::new();
+        assert_eq!(KeyProfile::load_for_game(&storage, "games/game.ch8").unwrap(), None, "Profile loaded for game without one.");
+
+        storage.write(&KeyProfile::path_for_game("games/game.ch8"), b"Space = 5").unwrap();
+        assert_eq!(KeyProfile::load_for_game(&storage, "games/game.ch8").unwrap().and_then(|profile| profile.key_for("Space")), Some(0x5), "Saved profile not loaded.");
+        storage.write(&KeyProfile::path_for_game("games/game.ch8"), b"Space").unwrap();
+        assert!(KeyProfile::load_for_game(&storage, "games/game.ch8").is_err(), "Malformed profile loaded.");
+    }
+}
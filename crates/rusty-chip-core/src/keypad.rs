@@ -0,0 +1,62 @@
+//! A module to simulate the wiring of a hex keypad built as a 4x4 matrix of rows and columns, for the [key ghosting quirk](crate::quirks::KeyGhostingQuirk).
+//! A matrix keypad without diodes senses a key whenever current can flow between its row and column, so holding three keys on the corners of a rectangle also senses the key on the fourth corner.
+//! Games which read several keys at once (e.g. two-player games) can then see keys which were never pressed, as they could on such keypads.
+
+/// The keys of the keypad by row and column, as they are laid out on the COSMAC VIP's hex keypad.
+pub const KEYPAD_LAYOUT: [[u8; 4]; 4] = [
+    [0x1, 0x2, 0x3, 0xC],
+    [0x4, 0x5, 0x6, 0xD],
+    [0x7, 0x8, 0x9, 0xE],
+    [0xA, 0x0, 0xB, 0xF]
+];
+
+/// Returns the keys the matrix senses while the provided keys are held, as a bitmask with bit N set if key N is sensed.
+/// Rows sharing a held column are connected, so every column held on one of them is sensed on all of them.
+///
+/// # Parameters
+///
+/// * `held_keys` - The keys being held, as a bitmask with bit N set if key N is held.
+#[must_use]
+pub fn sensed_keys(held_keys: u16) -> u16 {
+    // The columns held on each row, as a bitmask with bit N set for column N
+    let mut rows = KEYPAD_LAYOUT.map(|row| row.iter().enumerate().fold(0u8, |columns, (column, key)| {
+        if held_keys & (1 << key) != 0 { columns | (1 << column) } else { columns }
+    }));
+
+    // Connect the rows which share a column until nothing changes, as the current can flow through several of them
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for first in 0..rows.len() {
+            for second in 0..rows.len() {
+                if rows[first] & rows[second] != 0 && rows[first] | rows[second] != rows[first] {
+                    rows[first] |= rows[second];
+                    changed = true;
+                }
+            }
+        }
+    }
+
+    KEYPAD_LAYOUT.iter().zip(rows).fold(0, |sensed, (row, columns)| {
+        row.iter().enumerate().filter(|(column, _)| columns & (1 << column) != 0).fold(sensed, |sensed, (_, key)| sensed | (1 << key))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the bitmask of the provided keys.
+    fn mask(keys: &[u8]) -> u16 {
+        keys.iter().fold(0, |mask, key| mask | (1 << key))
+    }
+
+    #[test]
+    fn ghost_fourth_corner() {
+        assert_eq!(sensed_keys(mask(&[0x1, 0x2])), mask(&[0x1, 0x2]), "Keys on a single row ghosted.");
+        assert_eq!(sensed_keys(mask(&[0x1, 0x5])), mask(&[0x1, 0x5]), "Diagonal keys ghosted.");
+        assert_eq!(sensed_keys(mask(&[0x1, 0x2, 0x4])), mask(&[0x1, 0x2, 0x4, 0x5]), "Fourth corner not ghosted.");
+        assert_eq!(sensed_keys(mask(&[0x1, 0x2, 0x4, 0xB])), mask(&[0x1, 0x2, 0x4, 0x5, 0xB]), "Unconnected key ghosted.");
+        assert_eq!(sensed_keys(mask(&[0x1, 0x2, 0x5, 0x6, 0x9])), mask(&[0x1, 0x2, 0x3, 0x4, 0x5, 0x6, 0x8, 0x9, 0x7]), "Chained rows not connected.");
+    }
+}
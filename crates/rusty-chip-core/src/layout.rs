@@ -0,0 +1,318 @@
+//! A module to arrange the overlays drawn over the game (e.g. the [input display](crate::input_display)), so that they can be moved out of the way of the gameplay.
+//! The layout is kept in [`LAYOUT_PATH`](LAYOUT_PATH), with one overlay per line and `#` starting a comment:
+//!
+//! ```text
+//! input-display anchor=top scale=0.5 opacity=0.75
+//! ```
+//!
+//! Each overlay is anchored to a corner, an edge, or the centre of the window, scaled from its usual size, and drawn with an opacity from 0 (invisible) to 1 (opaque).
+//! Settings left out of a line, and overlays left out of the file, keep their usual placement.
+
+#[cfg(feature = "std")]
+use core::fmt::{Display, Formatter};
+#[cfg(feature = "std")]
+use core::str::FromStr;
+#[cfg(feature = "std")]
+use std::io::{self, ErrorKind};
+
+#[cfg(feature = "cli")]
+use clap::ValueEnum;
+
+#[cfg(not(feature = "std"))]
+use crate::alloc_prelude::*;
+use crate::names::named;
+#[cfg(feature = "std")]
+use crate::names::Named;
+#[cfg(feature = "std")]
+use crate::storage::StorageBackend;
+
+/// The path of the file storing the layout, relative to the working directory.
+pub const LAYOUT_PATH: &str = "overlays.layout";
+
+/// The character starting a comment line.
+#[cfg(feature = "std")]
+const COMMENT_PREFIX: char = '#';
+
+/// The largest scale an overlay can be drawn at, which already fills most of the window.
+#[cfg(feature = "std")]
+const MAX_SCALE: f32 = 4.0;
+
+/// Denotes an overlay drawn over the game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+pub enum Overlay {
+    /// The keypad timeline of the [input display](crate::input_display).
+    InputDisplay
+}
+
+named!(Overlay { InputDisplay => "input-display" });
+
+impl Overlay {
+    /// Returns where the overlay is drawn unless the layout says otherwise.
+    #[must_use]
+    pub fn default_placement(self) -> Placement {
+        match self {
+            Overlay::InputDisplay => Placement::new(Anchor::Bottom)
+        }
+    }
+}
+
+/// Denotes the point of the window an overlay is attached to, which the matching point of the overlay is placed on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Centre,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight
+}
+
+named!(Anchor {
+    TopLeft => "top-left",
+    Top => "top",
+    TopRight => "top-right",
+    Left => "left",
+    Centre => "centre",
+    Right => "right",
+    BottomLeft => "bottom-left",
+    Bottom => "bottom",
+    BottomRight => "bottom-right"
+});
+
+impl Anchor {
+    /// Returns how far across and down the window the anchor is, as halves (0 for the left or top, 1 for the centre, and 2 for the right or bottom).
+    fn halves(self) -> (u32, u32) {
+        match self {
+            Anchor::TopLeft => (0, 0),
+            Anchor::Top => (1, 0),
+            Anchor::TopRight => (2, 0),
+            Anchor::Left => (0, 1),
+            Anchor::Centre => (1, 1),
+            Anchor::Right => (2, 1),
+            Anchor::BottomLeft => (0, 2),
+            Anchor::Bottom => (1, 2),
+            Anchor::BottomRight => (2, 2)
+        }
+    }
+}
+
+/// Stores where an overlay is drawn, how large, and how opaque.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Placement {
+    pub anchor: Anchor,
+    /// The size of the overlay relative to its usual size, above 0 and at most 4.
+    pub scale: f32,
+    /// How opaque the overlay is, from 0 (invisible) to 1 (opaque).
+    pub opacity: f32
+}
+
+impl Placement {
+    /// Returns a placement at the anchor, at the usual size and fully opaque.
+    ///
+    /// # Parameters
+    ///
+    /// * `anchor` - The point of the window the overlay is attached to.
+    #[must_use]
+    pub fn new(anchor: Anchor) -> Placement {
+        Placement { anchor, scale: 1.0, opacity: 1.0 }
+    }
+
+    /// Returns the length scaled from the overlay's usual size, rounded to the nearest pixel.
+    ///
+    /// # Parameters
+    ///
+    /// * `length` - The length at the usual size, in pixels.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+    pub fn scaled(&self, length: u32) -> u32 {
+        (length as f32 * self.scale).round() as u32
+    }
+
+    /// Returns the top left corner of the overlay in the window, once scaled.
+    ///
+    /// # Parameters
+    ///
+    /// * `size` - The width and height of the overlay at its usual size.
+    /// * `window_size` - The width and height of the window.
+    #[must_use]
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn origin(&self, size: (u32, u32), window_size: (u32, u32)) -> (i32, i32) {
+        let (across, down) = self.anchor.halves();
+        let offset = |length: u32, window_length: u32, halves: u32| (window_length as i32 - length as i32) * halves as i32 / 2;
+
+        (offset(self.scaled(size.0), window_size.0, across), offset(self.scaled(size.1), window_size.1, down))
+    }
+
+    /// Returns the opacity as an alpha channel value, from 0 to 255.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn alpha(&self) -> u8 {
+        (self.opacity * f32::from(u8::MAX)).round() as u8
+    }
+}
+
+/// Stores the placement of every overlay which is not in its usual place.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OverlayLayout {
+    placements: Vec<(Overlay, Placement)>
+}
+
+impl OverlayLayout {
+    /// Returns a layout with every overlay in its usual place.
+    #[must_use]
+    pub fn new() -> OverlayLayout {
+        OverlayLayout::default()
+    }
+
+    /// Loads the layout saved at [`LAYOUT_PATH`](LAYOUT_PATH), returning the usual layout if none has been saved.
+    ///
+    /// # Parameters
+    ///
+    /// * `storage` - Where the layout is saved.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the layout exists but cannot be read or is malformed.
+    #[cfg(feature = "std")]
+    pub fn load(storage: &dyn StorageBackend) -> io::Result<OverlayLayout> {
+        let Some(text) = storage.read_to_string(LAYOUT_PATH)? else {
+            return Ok(OverlayLayout::new());
+        };
+
+        text.parse().map_err(|e| io::Error::new(ErrorKind::InvalidData, e))
+    }
+
+    /// Saves the layout to [`LAYOUT_PATH`](LAYOUT_PATH), replacing any earlier one.
+    ///
+    /// # Parameters
+    ///
+    /// * `storage` - Where to save the layout.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the layout cannot be written.
+    #[cfg(feature = "std")]
+    pub fn save(&self, storage: &mut dyn StorageBackend) -> io::Result<()> {
+        storage.write(LAYOUT_PATH, self.to_string().as_bytes())
+    }
+
+    /// Returns where the overlay is drawn.
+    ///
+    /// # Parameters
+    ///
+    /// * `overlay` - The overlay to look up.
+    #[must_use]
+    pub fn placement(&self, overlay: Overlay) -> Placement {
+        self.placements.iter().find(|(placed, _)| *placed == overlay).map_or_else(|| overlay.default_placement(), |(_, placement)| *placement)
+    }
+
+    /// Moves the overlay, replacing its earlier placement.
+    ///
+    /// # Parameters
+    ///
+    /// * `overlay` - The overlay to move.
+    /// * `placement` - Where to draw the overlay.
+    pub fn set_placement(&mut self, overlay: Overlay, placement: Placement) {
+        self.placements.retain(|(placed, _)| *placed != overlay);
+        self.placements.push((overlay, placement));
+    }
+
+    /// Applies a line of the layout file (e.g. `input-display anchor=top scale=0.5`), changing only the settings it names.
+    ///
+    /// # Parameters
+    ///
+    /// * `line` - The overlay followed by its settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the overlay or a setting is unknown, or a value is out of range.
+    #[cfg(feature = "std")]
+    pub fn apply(&mut self, line: &str) -> Result<(), String> {
+        let mut tokens = line.split_whitespace();
+        let name = tokens.next().ok_or_else(|| String::from("Missing overlay in layout entry."))?;
+        let overlay = Overlay::from_name(name).ok_or_else(|| format!("Unknown overlay \"{name}\"."))?;
+
+        let mut placement = self.placement(overlay);
+        for setting in tokens {
+            let (key, value) = setting.split_once('=').ok_or_else(|| format!("Setting \"{setting}\" of {name} is not of the form key=value."))?;
+            match key {
+                "anchor" => placement.anchor = Anchor::from_name(value).ok_or_else(|| format!("Unknown anchor \"{value}\" for {name}."))?,
+                "scale" => placement.scale = value.parse().ok().filter(|scale| *scale > 0.0 && *scale <= MAX_SCALE).ok_or_else(|| format!("The scale of {name} must be above 0 and at most {MAX_SCALE}."))?,
+                "opacity" => placement.opacity = value.parse().ok().filter(|opacity| (0.0..=1.0).contains(opacity)).ok_or_else(|| format!("The opacity of {name} must be from 0 to 1."))?,
+                _ => return Err(format!("Unknown setting \"{key}\" for {name}."))
+            }
+        }
+        self.set_placement(overlay, placement);
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl FromStr for OverlayLayout {
+    type Err = String;
+
+    /// Parses the contents of a layout file, returning an `Err` naming the first malformed line.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut layout = OverlayLayout::new();
+        for (index, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with(COMMENT_PREFIX) {
+                continue;
+            }
+
+            layout.apply(line).map_err(|e| format!("line {}: {e}", index + 1))?;
+        }
+
+        Ok(layout)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Display for OverlayLayout {
+    /// Writes the layout in the form it is read, a line per overlay which has been moved.
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        for (overlay, placement) in &self.placements {
+            writeln!(f, "{} anchor={} scale={} opacity={}", overlay.name(), placement.anchor.name(), placement.scale, placement.opacity)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    #[test]
+    fn parse_layout() {
+        let layout: OverlayLayout = "# Keep the floor visible\ninput-display anchor=top-right scale=0.5\n".parse().unwrap();
+        let placement = layout.placement(Overlay::InputDisplay);
+        assert_eq!(placement, Placement { anchor: Anchor::TopRight, scale: 0.5, opacity: 1.0 }, "Placement read incorrectly.");
+        assert_eq!(placement.origin((640, 48), (640, 320)), (320, 0), "Overlay not anchored to the corner.");
+        assert_eq!(OverlayLayout::new().placement(Overlay::InputDisplay).origin((640, 48), (640, 320)), (0, 272), "Usual placement changed.");
+
+        assert!("input-display scale=0".parse::<OverlayLayout>().is_err(), "Empty scale read.");
+        assert!("input-display opacity=2".parse::<OverlayLayout>().is_err(), "Opacity above 1 read.");
+        assert!("input-display anchor=middle".parse::<OverlayLayout>().is_err(), "Unknown anchor read.");
+        assert_eq!("\nhud".parse::<OverlayLayout>(), Err(String::from("line 2: Unknown overlay \"hud\".")), "Malformed line not named.");
+    }
+
+    #[test]
+    fn layout_round_trip() {
+        let mut storage = MemoryStorage::new();
+        assert_eq!(OverlayLayout::load(&storage).unwrap(), OverlayLayout::new(), "Layout loaded without one saved.");
+
+        let mut layout = OverlayLayout::new();
+        layout.apply("input-display anchor=centre opacity=0.5").unwrap();
+        assert_eq!(layout.placement(Overlay::InputDisplay).alpha(), 128, "Opacity not converted to alpha.");
+        layout.save(&mut storage).unwrap();
+        assert_eq!(OverlayLayout::load(&storage).unwrap(), layout, "Layout changed by a round trip.");
+    }
+}
@@ -0,0 +1,109 @@
+//! # `RustyChip` Core
+//!
+//! `rusty_chip_core` is the frontend-agnostic core of the `RustyChip` CHIP-8 emulator: the interpreter, its opcodes and quirks, and the tools built around them (e.g. save states, the debugger, and fuzzing).
+//! It does not depend on SDL, so it can be embedded in other frontends, which draw, play sound, and read keys through the [backend traits](backend).
+//!
+//! Embedders should import the stable surface through the [`prelude`](prelude). Modules hidden from the documentation are internal to the emulator's frontends and may change at any time.
+//!
+//! Everything is built with the `std` feature, which is on by default. Without it the crate is `no_std`, only needing `alloc`, and provides the interpreter, the opcode decoder, and the modules they are built from, for running games on microcontrollers (e.g. driving an LED matrix).
+//! Loading and saving files, the emulator's frame pacing, and the tools built around it all need the standard library.
+//! The `cli` feature derives clap's `ValueEnum` for the quirks, platforms, and other settings, for frontends which take them as command-line arguments; without it they are still read and written by their [names](names).
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+/// The items of the standard prelude which come from `alloc`, for the modules built without the standard library.
+#[cfg(not(feature = "std"))]
+mod alloc_prelude {
+    pub(crate) use alloc::boxed::Box;
+    pub(crate) use alloc::format;
+    pub(crate) use alloc::string::{String, ToString};
+    pub(crate) use alloc::vec;
+    pub(crate) use alloc::vec::Vec;
+}
+
+pub mod prelude;
+pub mod opcodes;
+pub mod interpreter;
+pub mod builder;
+#[doc(hidden)]
+pub mod audio;
+pub mod backend;
+pub mod quirks;
+pub mod names;
+#[cfg(feature = "std")]
+pub mod emulator;
+pub mod palette;
+#[cfg(feature = "std")]
+pub mod watch;
+#[cfg(feature = "std")]
+pub mod metrics;
+#[cfg(feature = "std")]
+pub mod fuzz;
+#[cfg(feature = "std")]
+#[doc(hidden)]
+pub mod clipboard;
+#[cfg(feature = "std")]
+pub mod savestate;
+pub mod coverage;
+#[cfg(feature = "std")]
+pub mod database;
+#[cfg(feature = "std")]
+pub mod config;
+pub mod extensions;
+pub mod banking;
+pub mod input_display;
+pub mod layout;
+pub mod keypad;
+#[cfg(feature = "std")]
+pub mod macros;
+#[cfg(feature = "std")]
+pub mod debugger;
+#[cfg(feature = "std")]
+pub mod speedrun;
+pub mod provenance;
+#[cfg(feature = "std")]
+pub mod memory_view;
+#[cfg(feature = "std")]
+pub mod storage;
+#[cfg(feature = "std")]
+pub mod trace;
+#[cfg(feature = "std")]
+pub mod key_profile;
+#[cfg(feature = "std")]
+pub mod notes;
+#[cfg(feature = "std")]
+pub mod compat;
+#[cfg(feature = "std")]
+pub mod profiler;
+pub mod dispatch;
+pub mod events;
+pub mod frame_log;
+pub mod framebuffer;
+pub mod renderer;
+pub mod hooks;
+#[cfg(feature = "std")]
+pub mod graph;
+#[cfg(feature = "std")]
+pub mod poke;
+pub mod symbols;
+#[cfg(feature = "std")]
+pub mod bench;
+#[cfg(feature = "std")]
+pub mod screenshot;
+#[cfg(feature = "std")]
+pub mod state_diff;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+#[cfg(feature = "serde")]
+pub mod serialization;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "alloc-tracking")]
+#[doc(hidden)]
+pub mod alloc_tracker;
+
+#[cfg(all(test, feature = "alloc-tracking"))]
+#[global_allocator]
+static GLOBAL: alloc_tracker::CountingAllocator = alloc_tracker::CountingAllocator;
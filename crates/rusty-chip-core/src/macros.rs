@@ -0,0 +1,263 @@
+//! A module to record short input macros (e.g. the keys which navigate a game's menu) and play them back on a hotkey.
+//! Unlike an [input script](crate::fuzz::InputScript), a macro does not replay a whole run: its key inputs are timed from the frame it is started on and played over whatever the game is doing.
+//! Macros go in numbered slots under [`MACROS_DIR`](MACROS_DIR), in a directory per game named after its hash, with a key input per line and `#` starting a comment:
+//!
+//! ```text
+//! 0 press 5
+//! 4 release 5
+//! ```
+
+use std::fmt::{Display, Formatter};
+use std::io::{self, ErrorKind};
+use std::str::FromStr;
+
+use crate::database;
+use crate::fuzz::InputEvent;
+use crate::storage::StorageBackend;
+
+/// The directory the macros of every game are kept under, relative to the working directory.
+pub const MACROS_DIR: &str = "macros";
+
+/// The extension of the files holding the macros.
+const MACRO_FILE_EXTENSION: &str = "macro";
+
+/// Stores the key inputs of a macro in frame order, timed from the frame it is started on.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InputMacro {
+    events: Vec<InputEvent>
+}
+
+impl InputMacro {
+    /// Returns the key inputs, with the frame of each counted from the start of the macro.
+    #[must_use]
+    pub fn events(&self) -> &[InputEvent] {
+        &self.events
+    }
+
+    /// Returns the number of frames the macro lasts, up to and including its final input.
+    #[must_use]
+    pub fn frames(&self) -> u64 {
+        self.events.last().map_or(0, |event| event.frame + 1)
+    }
+}
+
+impl Display for InputMacro {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for event in &self.events {
+            writeln!(f, "{} {} {:X}", event.frame, if event.pressed { "press" } else { "release" }, event.key)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for InputMacro {
+    type Err = String;
+
+    /// Parses the contents of a macro file, returning an `Err` naming the first malformed line.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut events = Vec::new();
+        for (number, line) in s.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let invalid_line = || format!("Invalid macro line {}: \"{line}\".", number + 1);
+            match line.split_whitespace().collect::<Vec<&str>>().as_slice() {
+                [] => {},
+                [frame, action @ ("press" | "release"), key] => {
+                    let frame = frame.parse().map_err(|_| invalid_line())?;
+                    let key = u8::from_str_radix(key, 16).ok().filter(|key| *key < 0x10).ok_or_else(invalid_line)?;
+                    events.push(InputEvent { frame, key, pressed: *action == "press" });
+                },
+                _ => return Err(invalid_line())
+            }
+        }
+
+        if events.windows(2).any(|pair| pair[0].frame > pair[1].frame) {
+            return Err(String::from("Macro inputs are not in frame order."));
+        }
+
+        Ok(InputMacro { events })
+    }
+}
+
+/// Records the keys pressed and released from a frame onwards into a macro.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MacroRecorder {
+    start_frame: u64,
+    held_keys: u16,
+    events: Vec<InputEvent>
+}
+
+impl MacroRecorder {
+    /// Returns a recorder timing its inputs from the provided frame.
+    ///
+    /// # Parameters
+    ///
+    /// * `start_frame` - The number of the next frame to be run, which becomes frame 0 of the macro.
+    #[must_use]
+    pub fn new(start_frame: u64) -> MacroRecorder {
+        MacroRecorder { start_frame, held_keys: 0, events: Vec::new() }
+    }
+
+    /// Records a key being pressed or released before the provided frame. Presses of held keys and releases of keys pressed before recording started are skipped, as they do not change the keypad.
+    ///
+    /// # Parameters
+    ///
+    /// * `frame` - The number of the next frame to be run.
+    /// * `key` - The CHIP-8 key (`0x0` to `0xF`).
+    /// * `pressed` - True if the key was pressed, false if it was released.
+    pub fn record(&mut self, frame: u64, key: u8, pressed: bool) {
+        let bit = 1 << key;
+        if pressed == (self.held_keys & bit != 0) {
+            return;
+        }
+
+        self.held_keys ^= bit;
+        self.events.push(InputEvent { frame: frame.saturating_sub(self.start_frame), key, pressed });
+    }
+
+    /// Returns the recorded macro, releasing any keys still held before the provided frame so that playing it back never leaves a key stuck.
+    ///
+    /// # Parameters
+    ///
+    /// * `frame` - The number of the next frame to be run.
+    #[must_use]
+    pub fn finish(mut self, frame: u64) -> InputMacro {
+        for key in 0..0x10 {
+            self.record(frame, key, false);
+        }
+
+        InputMacro { events: self.events }
+    }
+}
+
+/// Plays a macro back from a frame onwards.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MacroPlayback {
+    input_macro: InputMacro,
+    start_frame: u64,
+    next_event: usize
+}
+
+impl MacroPlayback {
+    /// Returns a playback of the macro starting on the provided frame.
+    ///
+    /// # Parameters
+    ///
+    /// * `input_macro` - The macro to play.
+    /// * `start_frame` - The number of the next frame to be run, which the macro's frame 0 is played before.
+    #[must_use]
+    pub fn new(input_macro: InputMacro, start_frame: u64) -> MacroPlayback {
+        MacroPlayback { input_macro, start_frame, next_event: 0 }
+    }
+
+    /// Returns the inputs due before the provided frame which have not been played yet, marking them as played.
+    ///
+    /// # Parameters
+    ///
+    /// * `frame` - The number of the frame about to be run.
+    pub fn take_due(&mut self, frame: u64) -> &[InputEvent] {
+        let elapsed = frame.saturating_sub(self.start_frame);
+        let first = self.next_event;
+        let due = self.input_macro.events[first..].iter().take_while(|event| event.frame <= elapsed).count();
+        self.next_event += due;
+
+        &self.input_macro.events[first..self.next_event]
+    }
+
+    /// Returns true once every input of the macro has been played.
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.next_event == self.input_macro.events.len()
+    }
+}
+
+/// Returns the path of the file storing a macro slot of the provided game, e.g. `macros/<hash>/macro3.macro`.
+///
+/// # Parameters
+///
+/// * `game_data` - The bytes of the game file, which the directory is named after the hash of.
+/// * `slot` - The number of the slot.
+#[must_use]
+pub fn macro_path(game_data: &[u8], slot: u8) -> String {
+    format!("{MACROS_DIR}/{}/macro{slot}.{MACRO_FILE_EXTENSION}", database::hash(game_data))
+}
+
+/// Saves the macro to a slot of the provided game, replacing whatever it held. Returns the path it was saved to.
+///
+/// # Parameters
+///
+/// * `storage` - Where to save the macro.
+/// * `game_data` - The bytes of the game file.
+/// * `slot` - The number of the slot.
+/// * `input_macro` - The macro to save.
+///
+/// # Errors
+///
+/// Returns an `Err` if the macro cannot be written.
+pub fn save_macro(storage: &mut dyn StorageBackend, game_data: &[u8], slot: u8, input_macro: &InputMacro) -> io::Result<String> {
+    let path = macro_path(game_data, slot);
+    storage.write(&path, input_macro.to_string().as_bytes())?;
+
+    Ok(path)
+}
+
+/// Loads the macro from a slot of the provided game, returning `None` if nothing has been recorded to it.
+///
+/// # Parameters
+///
+/// * `storage` - Where the macro is saved.
+/// * `game_data` - The bytes of the game file.
+/// * `slot` - The number of the slot.
+///
+/// # Errors
+///
+/// Returns an `Err` if the macro exists but cannot be read or is malformed.
+pub fn load_macro(storage: &dyn StorageBackend, game_data: &[u8], slot: u8) -> io::Result<Option<InputMacro>> {
+    let Some(text) = storage.read_to_string(&macro_path(game_data, slot))? else {
+        return Ok(None);
+    };
+
+    text.parse().map(Some).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    #[test]
+    fn record_and_play_macro() {
+        let mut recorder = MacroRecorder::new(100);
+        recorder.record(100, 0x5, true);
+        recorder.record(101, 0x5, true);
+        recorder.record(102, 0x7, false);
+        recorder.record(104, 0x5, false);
+        recorder.record(104, 0xA, true);
+        let input_macro = recorder.finish(106);
+        assert_eq!(input_macro.to_string(), "0 press 5\n4 release 5\n4 press A\n6 release A\n", "Inputs recorded incorrectly.");
+        assert_eq!(input_macro.frames(), 7, "Length of the macro incorrect.");
+
+        let mut playback = MacroPlayback::new(input_macro, 50);
+        assert_eq!(playback.take_due(50), &[InputEvent { frame: 0, key: 0x5, pressed: true }], "First input not played.");
+        assert!(playback.take_due(53).is_empty(), "Input played early.");
+        assert_eq!(playback.take_due(54).len(), 2, "Inputs of the same frame not played together.");
+        assert!(!playback.is_finished(), "Playback finished early.");
+        assert_eq!(playback.take_due(60).len(), 1, "Late input not played.");
+        assert!(playback.is_finished(), "Playback not finished.");
+    }
+
+    #[test]
+    fn macro_slots_round_trip() {
+        let mut storage = MemoryStorage::new();
+        let game_data = [0x12, 0x00];
+        assert_eq!(load_macro(&storage, &game_data, 2).unwrap(), None, "Empty slot loaded.");
+
+        let input_macro: InputMacro = "# Skip the title screen\n0 press 5\n3 release 5\n".parse().unwrap();
+        let path = save_macro(&mut storage, &game_data, 2, &input_macro).unwrap();
+        assert!(path.starts_with("macros/") && path.ends_with("/macro2.macro"), "Macro saved to the wrong path.");
+        assert_eq!(load_macro(&storage, &game_data, 2).unwrap(), Some(input_macro), "Macro changed by a round trip.");
+
+        assert!("3 press 5\n1 release 5".parse::<InputMacro>().is_err(), "Inputs out of order parsed.");
+        assert_eq!("0 press G".parse::<InputMacro>(), Err(String::from("Invalid macro line 1: \"0 press G\".")), "Malformed line not named.");
+    }
+}
@@ -0,0 +1,131 @@
+//! A module to format the RAM around the program counter and register I as a hex view, for working out what a game is doing when it starts executing garbage.
+//! The bytes of the instruction about to run are bracketed, as are the bytes register I points at, so they can be picked out of the surrounding data.
+
+use std::fmt::Write;
+use std::ops::Range;
+
+use crate::clipboard;
+use crate::interpreter::Interpreter;
+use crate::opcodes::OpcodeBytes;
+
+/// The number of bytes shown on each row of the view.
+const ROW_LENGTH: usize = 16;
+
+/// The number of rows shown before and after the row holding the address being viewed.
+const SURROUNDING_ROWS: usize = 2;
+
+/// The number of bytes register I is highlighted for, as a hint of what the next draw or load would read.
+const REGISTER_I_HIGHLIGHT_LENGTH: usize = 2;
+
+/// Returns the rows of the RAM around the address, one per line with the address of their first byte, with the highlighted bytes bracketed.
+/// The rows are aligned to the row length and clamped to the RAM, so a few more or fewer rows may be shown at its edges.
+///
+/// # Parameters
+///
+/// * `ram` - The RAM to view.
+/// * `address` - The address to view around.
+/// * `highlight` - The addresses of the bytes to bracket.
+#[must_use]
+pub fn hexdump(ram: &[u8], address: u16, highlight: &Range<usize>) -> String {
+    let row = usize::from(address) / ROW_LENGTH;
+    let first_row = row.saturating_sub(SURROUNDING_ROWS);
+    let last_row = (row + SURROUNDING_ROWS).min(ram.len().saturating_sub(1) / ROW_LENGTH);
+
+    let mut view = String::new();
+    for row in first_row..=last_row {
+        let start = row * ROW_LENGTH;
+        let _ = write!(view, "0x{start:03X}:");
+        for (offset, byte) in ram.iter().enumerate().skip(start).take(ROW_LENGTH) {
+            let separator = match offset {
+                offset if offset == highlight.start => '[',
+                offset if offset == highlight.end && !highlight.is_empty() => ']',
+                _ => ' '
+            };
+            let _ = write!(view, "{separator}{byte:02X}");
+        }
+        if highlight.end == start + ROW_LENGTH && !highlight.is_empty() {
+            view.push(']');
+        }
+        view.push('\n');
+    }
+
+    view
+}
+
+/// Returns the hex views around the program counter and register I, with the instruction about to run and the bytes register I points at bracketed.  
+/// The subroutine calls on the stack follow as a backtrace, the most recent first, then the changes made since the start of the previous frame if the [event log](Interpreter::event_log) is recording.
+///
+/// # Parameters
+///
+/// * `interpreter` - The interpreter to read from.
+#[must_use]
+pub fn memory_view(interpreter: &Interpreter) -> String {
+    let ram = interpreter.ram();
+    let program_counter = interpreter.program_counter();
+    let start = usize::from(program_counter);
+    let length = ram.get(start..start + 2).map_or(2, |bytes| usize::from(OpcodeBytes::build(bytes).length()));
+    let register_i = interpreter.register_i();
+    let register_i_start = usize::from(register_i);
+
+    let mut view = format!(
+        "PC {}\n{}I 0x{register_i:03X}\n{}",
+        clipboard::disassembly_line(interpreter),
+        hexdump(ram, program_counter, &(start..start + length)),
+        hexdump(ram, register_i, &(register_i_start..register_i_start + REGISTER_I_HIGHLIGHT_LENGTH))
+    );
+    let call_stack = interpreter.call_stack();
+    if call_stack.is_empty() {
+        view.push_str("Stack empty\n");
+    } else {
+        view.push_str("Stack\n");
+        for (depth, frame) in call_stack.iter().enumerate() {
+            let _ = writeln!(view, "#{depth} {frame}");
+        }
+    }
+    if let Some(event_log) = interpreter.event_log() {
+        let from_frame = event_log.frame().saturating_sub(1);
+        let changes: Vec<String> = event_log.changes_between(from_frame, event_log.frame() + 1).iter().map(ToString::to_string).collect();
+        let _ = writeln!(view, "Changes since frame {from_frame}: {}", if changes.is_empty() { String::from("none") } else { changes.join(", ") });
+    }
+
+    view
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::EventLog;
+
+    #[test]
+    fn bracket_highlighted_bytes() {
+        let ram: Vec<u8> = (0..=0x4F).collect();
+        let view = hexdump(&ram, 0x22, &(0x22..0x24));
+        assert_eq!(view.lines().count(), 5, "Surrounding rows not shown.");
+        assert_eq!(view.lines().nth(2), Some("0x020: 20 21[22 23]24 25 26 27 28 29 2A 2B 2C 2D 2E 2F"), "Highlighted bytes not bracketed.");
+        assert_eq!(hexdump(&ram, 0x0F, &(0x0F..0x11)).lines().next(), Some("0x000: 00 01 02 03 04 05 06 07 08 09 0A 0B 0C 0D 0E[0F"), "Highlight not continued onto the next row.");
+        assert_eq!(hexdump(&ram, 0x4E, &(0x4E..0x50)).lines().count(), 3, "Rows past the end of the RAM shown.");
+    }
+
+    #[test]
+    fn view_around_registers() {
+        let mut interpreter = Interpreter::new();
+        interpreter.load_game(&[0xA2, 0x08, 0x12, 0x02]);
+        interpreter.handle_cycle();
+
+        let view = memory_view(&interpreter);
+        assert!(view.starts_with("PC 0x202: 1202 JP 0x202\n"), "Instruction not shown: {view}");
+        assert!(view.contains("0x200: A2 08[12 02]00"), "Instruction bytes not bracketed: {view}");
+        assert!(view.contains("I 0x208\n"), "Register I not shown: {view}");
+        assert!(view.contains("0x200: A2 08 12 02 00 00 00 00[00 00]00"), "Register I bytes not bracketed: {view}");
+        assert!(view.ends_with("Stack empty\n"), "Empty stack not shown: {view}");
+
+        interpreter.load_game(&[0x22, 0x04, 0x00, 0x00, 0x12, 0x04]);
+        interpreter.handle_cycle();
+        assert!(memory_view(&interpreter).ends_with("Stack\n#0 0x200: 2204 CALL 0x204\n"), "Call stack not shown.");
+
+        interpreter.set_event_log(Some(EventLog::default()));
+        interpreter.load_game(&[0x22, 0x04, 0x00, 0x00, 0x12, 0x04]);
+        interpreter.handle_cycle();
+        assert!(memory_view(&interpreter).ends_with("Changes since frame 0: Stack #0: 0x000 -> 0x202, PC: 0x200 -> 0x204, SP: 0 -> 1\n"), "Changes not shown.");
+    }
+}
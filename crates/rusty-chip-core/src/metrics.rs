@@ -0,0 +1,222 @@
+//! A module to export a snapshot of the interpreter's state every frame, for analysing game behaviour in spreadsheets or notebooks.
+//! Metrics are written as CSV, or as JSON Lines (one object per frame) if the file ends in `.json` or `.jsonl`.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::interpreter::{Counters, Interpreter};
+
+/// The columns written for every frame, in order.
+const COLUMNS: [&str; 29] = [
+    "frame", "pc", "i",
+    "v0", "v1", "v2", "v3", "v4", "v5", "v6", "v7", "v8", "v9", "va", "vb", "vc", "vd", "ve", "vf",
+    "dt", "st", "draws", "stalled_draws", "stalled_cycles", "sound", "keys",
+    "instructions", "invalid_opcodes", "key_waits"
+];
+
+/// Denotes the format the metrics are written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricsFormat {
+    Csv,
+    JsonLines
+}
+
+impl MetricsFormat {
+    /// Returns the format matching the extension of the provided path, defaulting to CSV.
+    ///
+    /// # Parameters
+    ///
+    /// * `path` - The path of the metrics file.
+    #[must_use]
+    pub fn from_path(path: &str) -> MetricsFormat {
+        let is_json = Path::new(path)
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json") || ext.eq_ignore_ascii_case("jsonl"));
+        if is_json { MetricsFormat::JsonLines } else { MetricsFormat::Csv }
+    }
+}
+
+/// Returns a short summary of the draw opcodes of the most recent frame, suitable for the window title.  
+/// Games which lose at least half their cycles waiting for the display are flagged as display-bound, as raising the cycles per frame will not speed them up.
+///
+/// # Parameters
+///
+/// * `interpreter` - The interpreter to read from.
+/// * `cycles_per_frame` - The number of instruction cycles run per frame.
+#[must_use]
+pub fn describe_draw_budget(interpreter: &Interpreter, cycles_per_frame: u32) -> String {
+    let draw_stats = interpreter.frame_draw_stats();
+    let mut description = format!("Draws: {} ({} stalled), stalled cycles: {}/{cycles_per_frame}", draw_stats.draws, draw_stats.stalled_draws, draw_stats.stalled_cycles);
+    if cycles_per_frame > 0 && draw_stats.stalled_cycles * 2 >= cycles_per_frame {
+        description.push_str(" (display-bound)");
+    }
+
+    description
+}
+
+/// Returns a short summary of the [counters](Counters) since the game was loaded, suitable for the window title.
+///
+/// # Parameters
+///
+/// * `counters` - The counters to summarize.
+#[must_use]
+pub fn describe_counters(counters: &Counters) -> String {
+    format!("Instructions: {}, key waits: {}, invalid opcodes: {}", counters.instructions, counters.key_waits, counters.invalid_opcodes)
+}
+
+/// Writes one row of metrics per frame to a file.
+pub struct MetricsLogger<W: Write> {
+    writer: W,
+    format: MetricsFormat
+}
+
+impl MetricsLogger<BufWriter<File>> {
+    /// Creates the metrics file at the provided path, choosing the format from its extension.
+    ///
+    /// # Parameters
+    ///
+    /// * `path` - The path of the metrics file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the file cannot be created or written.
+    pub fn create(path: &str) -> io::Result<Self> {
+        MetricsLogger::new(BufWriter::new(File::create(path)?), MetricsFormat::from_path(path))
+    }
+}
+
+impl<W: Write> MetricsLogger<W> {
+    /// Wraps the provided writer, writing the header row if the format has one.
+    ///
+    /// # Parameters
+    ///
+    /// * `writer` - Where the metrics are written.
+    /// * `format` - The format to write the metrics in.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the header cannot be written.
+    pub fn new(mut writer: W, format: MetricsFormat) -> io::Result<Self> {
+        if format == MetricsFormat::Csv {
+            writeln!(writer, "{}", COLUMNS.join(","))?;
+        }
+
+        Ok(MetricsLogger { writer, format })
+    }
+
+    /// Writes the metrics of the frame which just ran.
+    /// The key column is a bitmask with bit N set if key N is held, and the last columns are the [counters](Counters) since the game was loaded.
+    ///
+    /// # Parameters
+    ///
+    /// * `frame` - The number of the frame being logged.
+    /// * `interpreter` - The interpreter to read from.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the row cannot be written.
+    pub fn log(&mut self, frame: u64, interpreter: &Interpreter) -> io::Result<()> {
+        let mut values = [0; COLUMNS.len()];
+        values[0] = frame;
+        values[1] = u64::from(interpreter.program_counter());
+        values[2] = u64::from(interpreter.register_i());
+        for (value, register) in values[3..19].iter_mut().zip(interpreter.registers()) {
+            *value = u64::from(*register);
+        }
+        values[19] = u64::from(interpreter.delay_timer());
+        values[20] = u64::from(interpreter.sound_timer());
+        let draw_stats = interpreter.frame_draw_stats();
+        values[21] = u64::from(draw_stats.draws);
+        values[22] = u64::from(draw_stats.stalled_draws);
+        values[23] = u64::from(draw_stats.stalled_cycles);
+        values[24] = u64::from(interpreter.sound_timer() > 0);
+        values[25] = u64::from(interpreter.pressed_keys());
+        let counters = interpreter.counters();
+        values[26] = counters.instructions;
+        values[27] = counters.invalid_opcodes;
+        values[28] = counters.key_waits;
+
+        match self.format {
+            MetricsFormat::Csv => {
+                let row: Vec<String> = values.iter().map(u64::to_string).collect();
+                writeln!(self.writer, "{}", row.join(","))
+            },
+            MetricsFormat::JsonLines => {
+                let fields: Vec<String> = COLUMNS.iter().zip(values).map(|(column, value)| format!("\"{column}\":{value}")).collect();
+                writeln!(self.writer, "{{{}}}", fields.join(","))
+            }
+        }
+    }
+
+    /// Returns the underlying writer.
+    #[must_use]
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns an interpreter which has drawn once and is holding a key.
+    fn measured_interpreter() -> Interpreter {
+        let mut interpreter = Interpreter::new();
+        interpreter.load_game(&[0x63, 0x1F, 0xF3, 0x18, 0xD0, 0x15]);
+        for _ in 0..3 {
+            interpreter.handle_cycle();
+        }
+        interpreter.press_key(0x1);
+        interpreter.handle_frame();
+
+        interpreter
+    }
+
+    #[test]
+    fn describe_draw_budget_flags_display_bound() {
+        let mut interpreter = Interpreter::new();
+        interpreter.load_game(&[0xD0, 0x15, 0x12, 0x00]);
+        for _ in 0..10 {
+            interpreter.handle_cycle();
+        }
+        interpreter.handle_frame();
+
+        assert_eq!(describe_draw_budget(&interpreter, 10), "Draws: 1 (1 stalled), stalled cycles: 9/10 (display-bound)", "Display-bound frame described incorrectly.");
+        assert_eq!(describe_draw_budget(&interpreter, 20), "Draws: 1 (1 stalled), stalled cycles: 9/20", "Frame described incorrectly.");
+    }
+
+    #[test]
+    fn describe_counters_since_load() {
+        assert_eq!(describe_counters(&measured_interpreter().counters()), "Instructions: 3, key waits: 0, invalid opcodes: 0", "Counters described incorrectly.");
+    }
+
+    #[test]
+    fn format_from_path() {
+        assert_eq!(MetricsFormat::from_path("out.csv"), MetricsFormat::Csv, "CSV path detected incorrectly.");
+        assert_eq!(MetricsFormat::from_path("out.JSONL"), MetricsFormat::JsonLines, "JSON Lines path detected incorrectly.");
+        assert_eq!(MetricsFormat::from_path("out"), MetricsFormat::Csv, "Path without extension did not default to CSV.");
+    }
+
+    #[test]
+    fn log_csv() {
+        let mut logger = MetricsLogger::new(Vec::new(), MetricsFormat::Csv).unwrap();
+        logger.log(4, &measured_interpreter()).unwrap();
+
+        let output = String::from_utf8(logger.into_inner()).unwrap();
+        let mut lines = output.lines();
+        assert_eq!(lines.next(), Some(COLUMNS.join(",").as_str()), "Header written incorrectly.");
+        assert_eq!(lines.next(), Some("4,518,0,0,0,0,31,0,0,0,0,0,0,0,0,0,0,0,0,0,30,1,1,0,1,2,3,0,0"), "Row written incorrectly.");
+        assert_eq!(lines.next(), None, "Extra rows written.");
+    }
+
+    #[test]
+    fn log_json_lines() {
+        let mut logger = MetricsLogger::new(Vec::new(), MetricsFormat::JsonLines).unwrap();
+        logger.log(4, &measured_interpreter()).unwrap();
+
+        let output = String::from_utf8(logger.into_inner()).unwrap();
+        assert!(output.starts_with("{\"frame\":4,\"pc\":518,"), "Row written incorrectly.");
+        assert!(output.ends_with("\"st\":30,\"draws\":1,\"stalled_draws\":1,\"stalled_cycles\":0,\"sound\":1,\"keys\":2,\"instructions\":3,\"invalid_opcodes\":0,\"key_waits\":0}\n"), "Row written incorrectly.");
+    }
+}
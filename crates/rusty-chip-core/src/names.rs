@@ -0,0 +1,74 @@
+//! A module to contain the names the settings of the emulator are written as (e.g. `no-wait`), which config, layout, and database files are read with.
+//! They match the names given on the command line, but do not need a command-line parser, so they are also available without the `cli` feature.
+
+/// A setting with a fixed set of values, each written as a name.
+pub trait Named: Sized + Clone + PartialEq + 'static {
+    /// Every value, in the order they are declared.
+    const VARIANTS: &'static [Self];
+
+    /// Returns the name the value is written as (e.g. `no-wait`).
+    fn name(&self) -> &'static str;
+
+    /// Returns the value with the provided name, regardless of case, or `None` if there is none.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the value.
+    #[must_use]
+    fn from_name(name: &str) -> Option<Self> {
+        Self::VARIANTS.iter().find(|variant| variant.name().eq_ignore_ascii_case(name)).cloned()
+    }
+}
+
+/// Implements [`Named`](Named) for an enum, from its variants and their names.
+macro_rules! named {
+    ($type:ident { $($variant:ident => $name:literal),+ $(,)? }) => {
+        impl $crate::names::Named for $type {
+            const VARIANTS: &'static [Self] = &[$(Self::$variant),+];
+
+            fn name(&self) -> &'static str {
+                match self {
+                    $(Self::$variant => $name),+
+                }
+            }
+        }
+    };
+}
+
+pub(crate) use named;
+
+#[cfg(all(test, feature = "cli"))]
+mod tests {
+    use clap::ValueEnum;
+
+    use super::*;
+    use crate::layout::{Anchor, Overlay};
+    use crate::quirks::*;
+    use crate::screenshot::ScreenshotTrigger;
+
+    /// Asserts that every value is named as it is on the command line.
+    fn assert_matches_cli<T: Named + ValueEnum>() {
+        assert_eq!(T::VARIANTS.len(), T::value_variants().len(), "Values missing from the names.");
+        for variant in T::VARIANTS {
+            let cli_name = variant.to_possible_value().unwrap();
+            assert_eq!(variant.name(), cli_name.get_name(), "Name differs from the command line.");
+            assert!(T::from_name(&variant.name().to_uppercase()) == Some(variant.clone()), "Name not read back.");
+        }
+    }
+
+    #[test]
+    fn names_match_cli() {
+        assert_matches_cli::<ResetVfQuirk>();
+        assert_matches_cli::<MemoryIncrementQuirk>();
+        assert_matches_cli::<DisplayWaitQuirk>();
+        assert_matches_cli::<ClippingQuirk>();
+        assert_matches_cli::<ShiftingQuirk>();
+        assert_matches_cli::<JumpingQuirk>();
+        assert_matches_cli::<MemoryTimingQuirk>();
+        assert_matches_cli::<KeyGhostingQuirk>();
+        assert_matches_cli::<Platform>();
+        assert_matches_cli::<Overlay>();
+        assert_matches_cli::<Anchor>();
+        assert_matches_cli::<ScreenshotTrigger>();
+    }
+}
@@ -0,0 +1,75 @@
+//! A module to keep free-text notes on a game (e.g. its controls, level passwords, or where it breaks), so that they are at hand while playing it.
+//! The notes are kept alongside the game file as `<game>.notes`, and can be written there by hand or added a line at a time with the `notes` command.
+
+use std::io;
+
+use crate::storage::StorageBackend;
+
+/// The extension added to the game file for its notes.
+const NOTES_FILE_EXTENSION: &str = "notes";
+
+/// Returns the path of the file storing the notes of the provided game.
+///
+/// # Parameters
+///
+/// * `game_path` - The path to the game file.
+#[must_use]
+pub fn path_for_game(game_path: &str) -> String {
+    format!("{game_path}.{NOTES_FILE_EXTENSION}")
+}
+
+/// Loads the notes of the provided game, returning `None` if it has none.
+///
+/// # Parameters
+///
+/// * `storage` - Where the notes are saved.
+/// * `game_path` - The path to the game file.
+///
+/// # Errors
+///
+/// Returns an `Err` if the file exists but cannot be read.
+pub fn load_for_game(storage: &dyn StorageBackend, game_path: &str) -> io::Result<Option<String>> {
+    let notes = storage.read_to_string(&path_for_game(game_path))?;
+
+    Ok(notes.map(|notes| notes.trim().to_string()).filter(|notes| !notes.is_empty()))
+}
+
+/// Adds a note to the end of the notes of the provided game, on its own line.
+///
+/// # Parameters
+///
+/// * `storage` - Where the notes are saved.
+/// * `game_path` - The path to the game file.
+/// * `note` - The note to add.
+///
+/// # Errors
+///
+/// Returns an `Err` if the existing notes cannot be read or the notes cannot be saved.
+pub fn add_for_game(storage: &mut dyn StorageBackend, game_path: &str, note: &str) -> io::Result<()> {
+    let mut notes = load_for_game(storage, game_path)?.unwrap_or_default();
+    if !notes.is_empty() {
+        notes.push('\n');
+    }
+    notes.push_str(note.trim());
+    notes.push('\n');
+
+    storage.write(&path_for_game(game_path), notes.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    #[test]
+    fn add_notes() {
+        let mut storage = MemoryStorage::new();
+        assert_eq!(load_for_game(&storage, "games/game.ch8").unwrap(), None, "Notes loaded for game without any.");
+        storage.write(&path_for_game("games/game.ch8"), b"\n  \n").unwrap();
+        assert_eq!(load_for_game(&storage, "games/game.ch8").unwrap(), None, "Blank notes loaded.");
+
+        add_for_game(&mut storage, "games/game.ch8", "Move with 4 and 6.").unwrap();
+        add_for_game(&mut storage, "games/game.ch8", " Level 3 password: 0451 ").unwrap();
+        assert_eq!(load_for_game(&storage, "games/game.ch8").unwrap().as_deref(), Some("Move with 4 and 6.\nLevel 3 password: 0451"), "Notes not added on their own lines.");
+    }
+}
@@ -0,0 +1,1074 @@
+//! A module to contain the types and operations related to determining opcodes.  
+//! For more information on CHIP-8 opcodes, please see [this section](https://en.wikipedia.org/wiki/CHIP-8#Opcode_table) of the wikipedia page.
+
+use core::fmt::{Display, Formatter, Write};
+
+#[cfg(not(feature = "std"))]
+use crate::alloc_prelude::*;
+use crate::banking::{BANK_SIZE, BANK_WINDOW_ADDRESS, MAX_BANKS};
+use crate::symbols::SymbolTable;
+
+const CLEAR_SCREEN_OPCODE_FIRST_BYTE: u8 = 0x00;
+const CLEAR_SCREEN_OPCODE_SECOND_BYTE: u8 = 0xE0;
+const RETURN_OPCODE_OPCODE_FIRST_BYTE: u8 = 0x00;
+const RETURN_OPCODE_OPCODE_SECOND_BYTE: u8 = 0xEE;
+const LOW_RESOLUTION_OPCODE_FIRST_BYTE: u8 = 0x00;
+const LOW_RESOLUTION_OPCODE_SECOND_BYTE: u8 = 0xFE;
+const HIGH_RESOLUTION_OPCODE_FIRST_BYTE: u8 = 0x00;
+const HIGH_RESOLUTION_OPCODE_SECOND_BYTE: u8 = 0xFF;
+const EXIT_OPCODE_FIRST_BYTE: u8 = 0x00;
+const EXIT_OPCODE_SECOND_BYTE: u8 = 0xFD;
+const SCROLL_RIGHT_OPCODE_FIRST_BYTE: u8 = 0x00;
+const SCROLL_RIGHT_OPCODE_SECOND_BYTE: u8 = 0xFB;
+const SCROLL_LEFT_OPCODE_FIRST_BYTE: u8 = 0x00;
+const SCROLL_LEFT_OPCODE_SECOND_BYTE: u8 = 0xFC;
+const SCROLL_DOWN_OPCODE_FIRST_BYTE: u8 = 0x00;
+const SCROLL_DOWN_OPCODE_UPPER_NIBBLE: u8 = 0xC0;
+const LONG_LOAD_REGISTER_I_OPCODE_FIRST_BYTE: u8 = 0xF0;
+const LONG_LOAD_REGISTER_I_OPCODE_SECOND_BYTE: u8 = 0x00;
+const LOAD_AUDIO_PATTERN_OPCODE_FIRST_BYTE: u8 = 0xF0;
+const LOAD_AUDIO_PATTERN_OPCODE_SECOND_BYTE: u8 = 0x02;
+const OPCODE_LENGTH: u16 = 2;
+const LONG_OPCODE_LENGTH: u16 = 4;
+const LOWER_NIBBLE_MASK: u8 = 0xF;
+const UPPER_NIBBLE_MASK: u8 = 0xF0;
+
+/// Denotes a particular opcode and stores the necessary information to process it.
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Opcode {
+    /// 0nnn
+    SystemAddr(u16),
+
+    /// 00E0
+    ClearScreen,
+
+    /// 00EE
+    Return,
+
+    /// 00Cn (SUPER-CHIP)
+    ScrollDown(u8),
+
+    /// 00FB (SUPER-CHIP)
+    ScrollRight,
+
+    /// 00FC (SUPER-CHIP)
+    ScrollLeft,
+
+    /// 00FE (SUPER-CHIP)
+    LowResolution,
+
+    /// 00FF (SUPER-CHIP)
+    HighResolution,
+
+    /// 00FD (SUPER-CHIP)
+    Exit,
+
+    /// 1nnn
+    JumpAddr(u16),
+
+    /// 2nnn
+    CallAddr(u16),
+
+    /// 3xkk
+    SkipRegisterEqualsValue(usize, u8),
+
+    /// 4xkk
+    SkipRegisterNotEqualsValue(usize, u8),
+
+    /// 5xy0
+    SkipRegistersEqual(usize, usize),
+
+    /// 6xkk
+    LoadValue(usize, u8),
+
+    /// 7xkk
+    AddValue(usize, u8),
+
+    /// 8xy0
+    LoadRegisterValue(usize, usize),
+
+    /// 8xy1
+    Or(usize, usize),
+
+    /// 8xy2
+    And(usize, usize),
+
+    /// 8xy3
+    Xor(usize, usize),
+
+    /// 8xy4
+    AddRegisters(usize, usize),
+
+    /// 8xy5
+    SubtractFromFirstRegister(usize, usize),
+
+    /// 8xy6
+    BitShiftRight(usize, usize),
+
+    /// 8xy7
+    SubtractFromSecondRegister(usize, usize),
+
+    /// 8xyE
+    BitShiftLeft(usize, usize),
+
+    /// 9xy0
+    SkipRegistersNotEqual(usize, usize),
+
+    /// Annn
+    LoadRegisterI(u16),
+
+    /// F000 nnnn (XO-CHIP)
+    LongLoadRegisterI(u16),
+
+    /// Bnnn
+    JumpAddrV0(u16),
+
+    /// Cxkk
+    Random(usize, u8),
+
+    /// Dxyn
+    Draw(usize, usize, u8),
+
+    /// Ex9E
+    SkipKeyPressed(usize),
+
+    /// ExA1
+    SkipKeyNotPressed(usize),
+
+    /// Fn01 (XO-CHIP)
+    SelectPlanes(u8),
+
+    /// F002 (XO-CHIP)
+    LoadAudioPattern,
+
+    /// Fx07
+    LoadDelayTimer(usize),
+
+    /// Fx0A
+    LoadKeyPress(usize),
+
+    /// Fx15
+    SetDelayTimer(usize),
+
+    /// Fx18
+    SetSoundTimer(usize),
+
+    /// Fx1E
+    AddRegisterI(usize),
+
+    /// Fx29
+    SetIHexSpriteLocation(usize),
+
+    /// Fx30 (SUPER-CHIP)
+    SetILargeHexSpriteLocation(usize),
+
+    /// Fx33
+    BinaryCodedDecimal(usize),
+
+    /// Fx3A (XO-CHIP)
+    SetPitch(usize),
+
+    /// Fx75 (SUPER-CHIP)
+    StoreRplFlags(usize),
+
+    /// Fx85 (SUPER-CHIP)
+    LoadRplFlags(usize),
+
+    /// Fx55
+    StoreRegisters(usize),
+
+    /// Fx65
+    LoadRegisters(usize)
+}
+
+impl Opcode {
+    /// The [name](Opcode::name) of every variant, in the order they are declared.
+    pub const NAMES: [&'static str; 48] = [
+        "SystemAddr", "ClearScreen", "Return", "ScrollDown", "ScrollRight", "ScrollLeft", "LowResolution", "HighResolution", "Exit", "JumpAddr",
+        "CallAddr", "SkipRegisterEqualsValue", "SkipRegisterNotEqualsValue", "SkipRegistersEqual", "LoadValue", "AddValue", "LoadRegisterValue", "Or",
+        "And", "Xor", "AddRegisters", "SubtractFromFirstRegister", "BitShiftRight", "SubtractFromSecondRegister", "BitShiftLeft",
+        "SkipRegistersNotEqual", "LoadRegisterI", "LongLoadRegisterI", "JumpAddrV0", "Random", "Draw", "SkipKeyPressed", "SkipKeyNotPressed",
+        "SelectPlanes", "LoadAudioPattern", "LoadDelayTimer", "LoadKeyPress", "SetDelayTimer", "SetSoundTimer", "AddRegisterI",
+        "SetIHexSpriteLocation", "SetILargeHexSpriteLocation", "BinaryCodedDecimal", "SetPitch", "StoreRplFlags", "LoadRplFlags", "StoreRegisters",
+        "LoadRegisters"
+    ];
+
+    /// Returns the name of the opcode's variant without its operands, e.g. `LoadValue` for `LD V1, 0x05`, for grouping opcodes of the same kind.
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        match self {
+            Opcode::SystemAddr(..) => "SystemAddr",
+            Opcode::ClearScreen => "ClearScreen",
+            Opcode::Return => "Return",
+            Opcode::ScrollDown(..) => "ScrollDown",
+            Opcode::ScrollRight => "ScrollRight",
+            Opcode::ScrollLeft => "ScrollLeft",
+            Opcode::LowResolution => "LowResolution",
+            Opcode::HighResolution => "HighResolution",
+            Opcode::Exit => "Exit",
+            Opcode::JumpAddr(..) => "JumpAddr",
+            Opcode::CallAddr(..) => "CallAddr",
+            Opcode::SkipRegisterEqualsValue(..) => "SkipRegisterEqualsValue",
+            Opcode::SkipRegisterNotEqualsValue(..) => "SkipRegisterNotEqualsValue",
+            Opcode::SkipRegistersEqual(..) => "SkipRegistersEqual",
+            Opcode::LoadValue(..) => "LoadValue",
+            Opcode::AddValue(..) => "AddValue",
+            Opcode::LoadRegisterValue(..) => "LoadRegisterValue",
+            Opcode::Or(..) => "Or",
+            Opcode::And(..) => "And",
+            Opcode::Xor(..) => "Xor",
+            Opcode::AddRegisters(..) => "AddRegisters",
+            Opcode::SubtractFromFirstRegister(..) => "SubtractFromFirstRegister",
+            Opcode::BitShiftRight(..) => "BitShiftRight",
+            Opcode::SubtractFromSecondRegister(..) => "SubtractFromSecondRegister",
+            Opcode::BitShiftLeft(..) => "BitShiftLeft",
+            Opcode::SkipRegistersNotEqual(..) => "SkipRegistersNotEqual",
+            Opcode::LoadRegisterI(..) => "LoadRegisterI",
+            Opcode::LongLoadRegisterI(..) => "LongLoadRegisterI",
+            Opcode::JumpAddrV0(..) => "JumpAddrV0",
+            Opcode::Random(..) => "Random",
+            Opcode::Draw(..) => "Draw",
+            Opcode::SkipKeyPressed(..) => "SkipKeyPressed",
+            Opcode::SkipKeyNotPressed(..) => "SkipKeyNotPressed",
+            Opcode::SelectPlanes(..) => "SelectPlanes",
+            Opcode::LoadAudioPattern => "LoadAudioPattern",
+            Opcode::LoadDelayTimer(..) => "LoadDelayTimer",
+            Opcode::LoadKeyPress(..) => "LoadKeyPress",
+            Opcode::SetDelayTimer(..) => "SetDelayTimer",
+            Opcode::SetSoundTimer(..) => "SetSoundTimer",
+            Opcode::AddRegisterI(..) => "AddRegisterI",
+            Opcode::SetIHexSpriteLocation(..) => "SetIHexSpriteLocation",
+            Opcode::SetILargeHexSpriteLocation(..) => "SetILargeHexSpriteLocation",
+            Opcode::BinaryCodedDecimal(..) => "BinaryCodedDecimal",
+            Opcode::SetPitch(..) => "SetPitch",
+            Opcode::StoreRplFlags(..) => "StoreRplFlags",
+            Opcode::LoadRplFlags(..) => "LoadRplFlags",
+            Opcode::StoreRegisters(..) => "StoreRegisters",
+            Opcode::LoadRegisters(..) => "LoadRegisters"
+        }
+    }
+
+    /// Returns the address the opcode jumps to, calls, or points register I at, or `None` if it has no address operand.
+    #[must_use]
+    pub fn address(&self) -> Option<u16> {
+        match *self {
+            Opcode::SystemAddr(addr) | Opcode::JumpAddr(addr) | Opcode::CallAddr(addr) | Opcode::LoadRegisterI(addr) | Opcode::LongLoadRegisterI(addr) | Opcode::JumpAddrV0(addr) => Some(addr),
+            _ => None
+        }
+    }
+}
+
+impl Display for Opcode {
+    /// Writes the opcode as a mnemonic in the style of [Cowgod's reference](http://devernay.free.fr/hacks/chip8/C8TECH10.HTM), e.g. `LD V3, 0x1F`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            Opcode::SystemAddr(addr) => write!(f, "SYS 0x{addr:03X}"),
+            Opcode::ClearScreen => write!(f, "CLS"),
+            Opcode::Return => write!(f, "RET"),
+            Opcode::ScrollDown(rows) => write!(f, "SCD {rows}"),
+            Opcode::ScrollRight => write!(f, "SCR"),
+            Opcode::ScrollLeft => write!(f, "SCL"),
+            Opcode::LowResolution => write!(f, "LOW"),
+            Opcode::HighResolution => write!(f, "HIGH"),
+            Opcode::Exit => write!(f, "EXIT"),
+            Opcode::JumpAddr(addr) => write!(f, "JP 0x{addr:03X}"),
+            Opcode::CallAddr(addr) => write!(f, "CALL 0x{addr:03X}"),
+            Opcode::SkipRegisterEqualsValue(x, value) => write!(f, "SE V{x:X}, 0x{value:02X}"),
+            Opcode::SkipRegisterNotEqualsValue(x, value) => write!(f, "SNE V{x:X}, 0x{value:02X}"),
+            Opcode::SkipRegistersEqual(x, y) => write!(f, "SE V{x:X}, V{y:X}"),
+            Opcode::LoadValue(x, value) => write!(f, "LD V{x:X}, 0x{value:02X}"),
+            Opcode::AddValue(x, value) => write!(f, "ADD V{x:X}, 0x{value:02X}"),
+            Opcode::LoadRegisterValue(x, y) => write!(f, "LD V{x:X}, V{y:X}"),
+            Opcode::Or(x, y) => write!(f, "OR V{x:X}, V{y:X}"),
+            Opcode::And(x, y) => write!(f, "AND V{x:X}, V{y:X}"),
+            Opcode::Xor(x, y) => write!(f, "XOR V{x:X}, V{y:X}"),
+            Opcode::AddRegisters(x, y) => write!(f, "ADD V{x:X}, V{y:X}"),
+            Opcode::SubtractFromFirstRegister(x, y) => write!(f, "SUB V{x:X}, V{y:X}"),
+            Opcode::BitShiftRight(x, y) => write!(f, "SHR V{x:X}, V{y:X}"),
+            Opcode::SubtractFromSecondRegister(x, y) => write!(f, "SUBN V{x:X}, V{y:X}"),
+            Opcode::BitShiftLeft(x, y) => write!(f, "SHL V{x:X}, V{y:X}"),
+            Opcode::SkipRegistersNotEqual(x, y) => write!(f, "SNE V{x:X}, V{y:X}"),
+            Opcode::LoadRegisterI(addr) => write!(f, "LD I, 0x{addr:03X}"),
+            Opcode::LongLoadRegisterI(addr) => write!(f, "LD I, 0x{addr:04X}"),
+            Opcode::JumpAddrV0(addr) => write!(f, "JP V0, 0x{addr:03X}"),
+            Opcode::Random(x, value) => write!(f, "RND V{x:X}, 0x{value:02X}"),
+            Opcode::Draw(x, y, height) => write!(f, "DRW V{x:X}, V{y:X}, {height}"),
+            Opcode::SkipKeyPressed(x) => write!(f, "SKP V{x:X}"),
+            Opcode::SkipKeyNotPressed(x) => write!(f, "SKNP V{x:X}"),
+            Opcode::SelectPlanes(planes) => write!(f, "PLANE {planes}"),
+            Opcode::LoadAudioPattern => write!(f, "AUDIO"),
+            Opcode::LoadDelayTimer(x) => write!(f, "LD V{x:X}, DT"),
+            Opcode::LoadKeyPress(x) => write!(f, "LD V{x:X}, K"),
+            Opcode::SetDelayTimer(x) => write!(f, "LD DT, V{x:X}"),
+            Opcode::SetSoundTimer(x) => write!(f, "LD ST, V{x:X}"),
+            Opcode::AddRegisterI(x) => write!(f, "ADD I, V{x:X}"),
+            Opcode::SetIHexSpriteLocation(x) => write!(f, "LD F, V{x:X}"),
+            Opcode::SetILargeHexSpriteLocation(x) => write!(f, "LD HF, V{x:X}"),
+            Opcode::BinaryCodedDecimal(x) => write!(f, "LD B, V{x:X}"),
+            Opcode::SetPitch(x) => write!(f, "PITCH V{x:X}"),
+            Opcode::StoreRplFlags(x) => write!(f, "LD R, V{x:X}"),
+            Opcode::LoadRplFlags(x) => write!(f, "LD V{x:X}, R"),
+            Opcode::StoreRegisters(x) => write!(f, "LD [I], V{x:X}"),
+            Opcode::LoadRegisters(x) => write!(f, "LD V{x:X}, [I]")
+        }
+    }
+}
+
+/// Stores the information necessary to determine an [Opcode](Opcode) from a pair of bytes read from memory.  
+/// The XO-CHIP long load is followed by a further pair of bytes holding its address.
+pub struct OpcodeBytes {
+    first_byte: u8,
+    second_byte: u8,
+    first_nibble: u8,
+    last_nibble: u8,
+    extension: Option<u16>
+}
+
+impl OpcodeBytes {
+    /// Returns an opcode-convertible structure from a slice of `u8`.
+    /// The resulting structure can be used to get a proper [Opcode](Opcode).
+    ///
+    /// # Arguments
+    ///
+    /// * `opcode_bytes` - A `u8` slice that holds the bytes necessary to form an opcode, with the following two bytes for a [long opcode](OpcodeBytes::length).
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `opcode_bytes` does not have exactly 2 or 4 elements as 2 bytes make up a valid opcode, or 4 for a long one.
+    #[must_use]
+    pub fn build(opcode_bytes: &[u8]) -> OpcodeBytes {
+        assert!(matches!(opcode_bytes.len(), 2 | 4), "Improper opcode format: Opcodes must be two bytes, or four for a long opcode.");
+
+        OpcodeBytes {
+            first_byte: opcode_bytes[0],
+            second_byte: opcode_bytes[1],
+            first_nibble: Self::get_upper_nibble_u8(opcode_bytes[0]),
+            last_nibble: Self::get_lower_nibble_u8(opcode_bytes[1]),
+            extension: opcode_bytes.get(2..4).map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+        }
+    }
+
+    /// Returns the number of bytes making up the instruction which starts with these bytes: 4 for the XO-CHIP long load, and 2 for every other opcode.
+    #[must_use]
+    pub fn length(&self) -> u16 {
+        if (self.first_byte, self.second_byte) == (LONG_LOAD_REGISTER_I_OPCODE_FIRST_BYTE, LONG_LOAD_REGISTER_I_OPCODE_SECOND_BYTE) {
+            LONG_OPCODE_LENGTH
+        } else {
+            OPCODE_LENGTH
+        }
+    }
+
+    /// Returns a `u8` containing the first 4 bits of a byte.
+    ///
+    /// # Parameters
+    ///
+    /// * `byte` - The byte from which to read the nibble.
+    fn get_upper_nibble_u8(byte: u8) -> u8 {
+        (byte & UPPER_NIBBLE_MASK) >> 4
+    }
+
+    /// Returns a `u8` containing the last 4 bits of a byte.
+    ///
+    /// # Parameters
+    ///
+    /// * `byte` - The byte from which to read the nibble.
+    fn get_lower_nibble_u8(byte: u8) -> u8 {
+        byte & LOWER_NIBBLE_MASK
+    }
+
+    /// Returns a `usize` containing the first 4 bits of a byte.
+    ///
+    /// # Parameters
+    ///
+    /// * `byte` - The byte from which to read the nibble.
+    fn get_upper_nibble(byte: u8) -> usize {
+        Self::get_upper_nibble_u8(byte) as usize
+    }
+
+    /// Returns a `usize` containing the last 4 bits of a byte.
+    ///
+    /// # Parameters
+    ///
+    /// * `byte` - The byte from which to read the nibble.
+    fn get_lower_nibble(byte: u8) -> usize {
+        Self::get_lower_nibble_u8(byte) as usize
+    }
+
+    /// Returns the 12-bit address contained in an opcode as a `u16`.
+    #[allow(clippy::cast_possible_truncation)]
+    fn get_addr(&self) -> u16 {
+        ((Self::get_lower_nibble(self.first_byte) as u16) << 8) + (u16::from(self.second_byte))
+    }
+
+    /// Returns a proper [Opcode](Opcode) with the data needed to handle it.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the opcode is not recognized as a valid one. See [wikipedia](https://en.wikipedia.org/wiki/CHIP-8#Opcode_table) for a list of valid opcodes.
+    #[must_use]
+    pub fn get_opcode(&self) -> Opcode {
+        self.try_get_opcode().unwrap_or_else(|| panic!("Unrecognized opcode: {self}"))
+    }
+
+    /// Returns a proper [Opcode](Opcode) with the data needed to handle it, or `None` if the opcode is not recognized as a valid one.  
+    /// A long opcode built from only its first two bytes is not recognized.
+    #[must_use]
+    pub fn try_get_opcode(&self) -> Option<Opcode> {
+        match self.first_nibble {
+            0x0 => self.decode_system(),
+            0x1 => self.decode_jump(),
+            0x2 => self.decode_call(),
+            0x3 => self.decode_skip_register_equals_value(),
+            0x4 => self.decode_skip_register_not_equals_value(),
+            0x5 => self.decode_skip_registers_equal(),
+            0x6 => self.decode_load_value(),
+            0x7 => self.decode_add_value(),
+            0x8 => self.decode_arithmetic(),
+            0x9 => self.decode_skip_registers_not_equal(),
+            0xA => self.decode_load_register_i(),
+            0xB => self.decode_jump_v0(),
+            0xC => self.decode_random(),
+            0xD => self.decode_draw(),
+            0xE => self.decode_key(),
+            _ => self.decode_misc()
+        }
+    }
+
+    /// Behaves like [`try_get_opcode`](OpcodeBytes::try_get_opcode), choosing the decoder for the first nibble from a table of functions rather than a match.  
+    /// Kept alongside it so that the [dispatch designs](crate::dispatch::Dispatch) can be compared.
+    #[must_use]
+    pub fn try_get_opcode_from_table(&self) -> Option<Opcode> {
+        Self::DECODERS[self.first_nibble as usize](self)
+    }
+
+    /// The decoders for each first nibble, in order.
+    const DECODERS: [fn(&OpcodeBytes) -> Option<Opcode>; 16] = [
+        Self::decode_system,
+        Self::decode_jump,
+        Self::decode_call,
+        Self::decode_skip_register_equals_value,
+        Self::decode_skip_register_not_equals_value,
+        Self::decode_skip_registers_equal,
+        Self::decode_load_value,
+        Self::decode_add_value,
+        Self::decode_arithmetic,
+        Self::decode_skip_registers_not_equal,
+        Self::decode_load_register_i,
+        Self::decode_jump_v0,
+        Self::decode_random,
+        Self::decode_draw,
+        Self::decode_key,
+        Self::decode_misc
+    ];
+
+    /// Decodes the opcodes starting with `0`: the screen, subroutine return, resolution, and exit opcodes, or a machine code call.
+    fn decode_system(&self) -> Option<Opcode> {
+        let opcode = match (self.first_byte, self.second_byte) {
+            (CLEAR_SCREEN_OPCODE_FIRST_BYTE, CLEAR_SCREEN_OPCODE_SECOND_BYTE) => Opcode::ClearScreen,
+            (RETURN_OPCODE_OPCODE_FIRST_BYTE, RETURN_OPCODE_OPCODE_SECOND_BYTE) => Opcode::Return,
+            (SCROLL_RIGHT_OPCODE_FIRST_BYTE, SCROLL_RIGHT_OPCODE_SECOND_BYTE) => Opcode::ScrollRight,
+            (SCROLL_LEFT_OPCODE_FIRST_BYTE, SCROLL_LEFT_OPCODE_SECOND_BYTE) => Opcode::ScrollLeft,
+            (SCROLL_DOWN_OPCODE_FIRST_BYTE, second_byte) if second_byte & UPPER_NIBBLE_MASK == SCROLL_DOWN_OPCODE_UPPER_NIBBLE => Opcode::ScrollDown(OpcodeBytes::get_lower_nibble_u8(second_byte)),
+            (LOW_RESOLUTION_OPCODE_FIRST_BYTE, LOW_RESOLUTION_OPCODE_SECOND_BYTE) => Opcode::LowResolution,
+            (HIGH_RESOLUTION_OPCODE_FIRST_BYTE, HIGH_RESOLUTION_OPCODE_SECOND_BYTE) => Opcode::HighResolution,
+            (EXIT_OPCODE_FIRST_BYTE, EXIT_OPCODE_SECOND_BYTE) => Opcode::Exit,
+            _ => Opcode::SystemAddr(self.get_addr())
+        };
+
+        Some(opcode)
+    }
+
+    /// Decodes the opcodes starting with `1`.
+    fn decode_jump(&self) -> Option<Opcode> {
+        Some(Opcode::JumpAddr(self.get_addr()))
+    }
+
+    /// Decodes the opcodes starting with `2`.
+    fn decode_call(&self) -> Option<Opcode> {
+        Some(Opcode::CallAddr(self.get_addr()))
+    }
+
+    /// Decodes the opcodes starting with `3`.
+    fn decode_skip_register_equals_value(&self) -> Option<Opcode> {
+        Some(Opcode::SkipRegisterEqualsValue(OpcodeBytes::get_lower_nibble(self.first_byte), self.second_byte))
+    }
+
+    /// Decodes the opcodes starting with `4`.
+    fn decode_skip_register_not_equals_value(&self) -> Option<Opcode> {
+        Some(Opcode::SkipRegisterNotEqualsValue(OpcodeBytes::get_lower_nibble(self.first_byte), self.second_byte))
+    }
+
+    /// Decodes the opcodes starting with `5`.
+    fn decode_skip_registers_equal(&self) -> Option<Opcode> {
+        (self.last_nibble == 0x0).then(|| Opcode::SkipRegistersEqual(OpcodeBytes::get_lower_nibble(self.first_byte), OpcodeBytes::get_upper_nibble(self.second_byte)))
+    }
+
+    /// Decodes the opcodes starting with `6`.
+    fn decode_load_value(&self) -> Option<Opcode> {
+        Some(Opcode::LoadValue(OpcodeBytes::get_lower_nibble(self.first_byte), self.second_byte))
+    }
+
+    /// Decodes the opcodes starting with `7`.
+    fn decode_add_value(&self) -> Option<Opcode> {
+        Some(Opcode::AddValue(OpcodeBytes::get_lower_nibble(self.first_byte), self.second_byte))
+    }
+
+    /// Decodes the opcodes starting with `8`, which operate on a pair of registers.
+    fn decode_arithmetic(&self) -> Option<Opcode> {
+        let first_register = OpcodeBytes::get_lower_nibble(self.first_byte);
+        let second_register = OpcodeBytes::get_upper_nibble(self.second_byte);
+        let opcode = match self.last_nibble {
+            0x0 => Opcode::LoadRegisterValue(first_register, second_register),
+            0x1 => Opcode::Or(first_register, second_register),
+            0x2 => Opcode::And(first_register, second_register),
+            0x3 => Opcode::Xor(first_register, second_register),
+            0x4 => Opcode::AddRegisters(first_register, second_register),
+            0x5 => Opcode::SubtractFromFirstRegister(first_register, second_register),
+            0x6 => Opcode::BitShiftRight(first_register, second_register),
+            0x7 => Opcode::SubtractFromSecondRegister(first_register, second_register),
+            0xE => Opcode::BitShiftLeft(first_register, second_register),
+            _ => return None
+        };
+
+        Some(opcode)
+    }
+
+    /// Decodes the opcodes starting with `9`.
+    fn decode_skip_registers_not_equal(&self) -> Option<Opcode> {
+        (self.last_nibble == 0x0).then(|| Opcode::SkipRegistersNotEqual(OpcodeBytes::get_lower_nibble(self.first_byte), OpcodeBytes::get_upper_nibble(self.second_byte)))
+    }
+
+    /// Decodes the opcodes starting with `A`.
+    fn decode_load_register_i(&self) -> Option<Opcode> {
+        Some(Opcode::LoadRegisterI(self.get_addr()))
+    }
+
+    /// Decodes the opcodes starting with `B`.
+    fn decode_jump_v0(&self) -> Option<Opcode> {
+        Some(Opcode::JumpAddrV0(self.get_addr()))
+    }
+
+    /// Decodes the opcodes starting with `C`.
+    fn decode_random(&self) -> Option<Opcode> {
+        Some(Opcode::Random(OpcodeBytes::get_lower_nibble(self.first_byte), self.second_byte))
+    }
+
+    /// Decodes the opcodes starting with `D`.
+    fn decode_draw(&self) -> Option<Opcode> {
+        Some(Opcode::Draw(OpcodeBytes::get_lower_nibble(self.first_byte), OpcodeBytes::get_upper_nibble(self.second_byte), OpcodeBytes::get_lower_nibble_u8(self.second_byte)))
+    }
+
+    /// Decodes the opcodes starting with `E`, which skip on the keys.
+    fn decode_key(&self) -> Option<Opcode> {
+        match self.second_byte {
+            0x9E => Some(Opcode::SkipKeyPressed(OpcodeBytes::get_lower_nibble(self.first_byte))),
+            0xA1 => Some(Opcode::SkipKeyNotPressed(OpcodeBytes::get_lower_nibble(self.first_byte))),
+            _ => None
+        }
+    }
+
+    /// Decodes the opcodes starting with `F`: the timers, register I, memory, audio, plane, and RPL flag opcodes.
+    fn decode_misc(&self) -> Option<Opcode> {
+        let register = OpcodeBytes::get_lower_nibble(self.first_byte);
+        let opcode = match (self.first_byte, self.second_byte) {
+            (LONG_LOAD_REGISTER_I_OPCODE_FIRST_BYTE, LONG_LOAD_REGISTER_I_OPCODE_SECOND_BYTE) => Opcode::LongLoadRegisterI(self.extension?),
+            (first_byte, 0x01) if first_byte <= 0xF3 => Opcode::SelectPlanes(OpcodeBytes::get_lower_nibble_u8(first_byte)),
+            (LOAD_AUDIO_PATTERN_OPCODE_FIRST_BYTE, LOAD_AUDIO_PATTERN_OPCODE_SECOND_BYTE) => Opcode::LoadAudioPattern,
+            (_, 0x07) => Opcode::LoadDelayTimer(register),
+            (_, 0x0A) => Opcode::LoadKeyPress(register),
+            (_, 0x15) => Opcode::SetDelayTimer(register),
+            (_, 0x18) => Opcode::SetSoundTimer(register),
+            (_, 0x1E) => Opcode::AddRegisterI(register),
+            (_, 0x29) => Opcode::SetIHexSpriteLocation(register),
+            (_, 0x30) => Opcode::SetILargeHexSpriteLocation(register),
+            (_, 0x3A) => Opcode::SetPitch(register),
+            (_, 0x33) => Opcode::BinaryCodedDecimal(register),
+            (_, 0x55) => Opcode::StoreRegisters(register),
+            (_, 0x65) => Opcode::LoadRegisters(register),
+            (_, 0x75) => Opcode::StoreRplFlags(register),
+            (_, 0x85) => Opcode::LoadRplFlags(register),
+            _ => return None
+        };
+
+        Some(opcode)
+    }
+}
+
+impl Display for OpcodeBytes {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:0>2X?}{:0>2X?}", self.first_byte, self.second_byte)?;
+        if let Some(extension) = self.extension {
+            write!(f, " {extension:04X}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns a linear disassembly of the program, one instruction per line along with its address and bytes (e.g. `0x200: 00E0 CLS`).  
+/// Every two bytes are taken as an instruction (four for the XO-CHIP long load), so any sprite data mixed in with the code is shown as whatever it decodes to. A trailing odd byte is shown on its own.
+///
+/// # Parameters
+///
+/// * `program` - The bytes of the program.
+/// * `load_address` - The address the program is loaded at, which the first instruction is shown at.
+#[must_use]
+pub fn disassemble(program: &[u8], load_address: u16) -> String {
+    disassemble_with_symbols(program, load_address, &SymbolTable::new())
+}
+
+/// Returns a linear disassembly of the program like [`disassemble`](disassemble), with the labels of the symbol table shown alongside the addresses they mark (e.g. `0x20A (loop): 1200 JP 0x200 (main)`).
+///
+/// # Parameters
+///
+/// * `program` - The bytes of the program.
+/// * `load_address` - The address the program is loaded at, which the first instruction is shown at.
+/// * `symbols` - The labels of the program.
+#[must_use]
+pub fn disassemble_with_symbols(program: &[u8], load_address: u16, symbols: &SymbolTable) -> String {
+    let mut disassembly = String::new();
+    let mut offset = 0;
+    while offset < program.len() {
+        #[allow(clippy::cast_possible_truncation)]
+        let address = load_address.wrapping_add(offset as u16);
+        let Some(bytes) = program.get(offset..offset + 2) else {
+            let _ = writeln!(disassembly, "{}: {:02X}", symbols.describe(address), program[offset]);
+            break;
+        };
+
+        // The XO-CHIP long load is followed by its address, unless the program ends first
+        let length = usize::from(OpcodeBytes::build(bytes).length());
+        let bytes = program.get(offset..offset + length).unwrap_or(bytes);
+        let _ = writeln!(disassembly, "{}", instruction_line(address, &OpcodeBytes::build(bytes), symbols));
+        offset += bytes.len();
+    }
+
+    disassembly
+}
+
+/// Returns a linear disassembly of a program run with [banking](crate::banking) like [`disassemble_with_symbols`](disassemble_with_symbols): the fixed part from the load address, followed by each bank from the bank window under its `.bank` directive.  
+/// The labels of each bank are only shown alongside that bank (see [`SymbolTable::for_bank`](SymbolTable::for_bank)).
+///
+/// # Parameters
+///
+/// * `program` - The bytes of the program.
+/// * `load_address` - The address the program is loaded at, which must not be past the bank window.
+/// * `symbols` - The labels of the program.
+#[must_use]
+pub fn disassemble_banked(program: &[u8], load_address: u16, symbols: &SymbolTable) -> String {
+    let (fixed, banked) = program.split_at(program.len().min(BANK_WINDOW_ADDRESS.saturating_sub(usize::from(load_address))));
+    let mut disassembly = disassemble_with_symbols(fixed, load_address, symbols);
+    for (bank, bank_data) in (0..=u8::MAX).zip(banked.chunks(BANK_SIZE).take(MAX_BANKS)) {
+        let _ = writeln!(disassembly, ".bank {bank}");
+        #[allow(clippy::cast_possible_truncation)]
+        let bank_window_address = BANK_WINDOW_ADDRESS as u16;
+        disassembly.push_str(&disassemble_with_symbols(bank_data, bank_window_address, &symbols.for_bank(bank)));
+    }
+
+    disassembly
+}
+
+/// Returns a single instruction along with its address and bytes, labelling the address and the opcode's address operand from the symbol table (e.g. `0x20A (loop): 1200 JP 0x200 (main)`).
+///
+/// # Parameters
+///
+/// * `address` - The address of the instruction.
+/// * `opcode_bytes` - The bytes of the instruction.
+/// * `symbols` - The labels of the program, which may be empty.
+#[must_use]
+pub fn instruction_line(address: u16, opcode_bytes: &OpcodeBytes, symbols: &SymbolTable) -> String {
+    let address = symbols.describe(address);
+    match opcode_bytes.try_get_opcode() {
+        Some(opcode) => match opcode.address().and_then(|target| symbols.label(target)) {
+            Some(label) => format!("{address}: {opcode_bytes} {opcode} ({label})"),
+            None => format!("{address}: {opcode_bytes} {opcode}")
+        },
+        None => format!("{address}: {opcode_bytes} (unrecognized)")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[0xAB, 0xCD]);
+        assert_eq!(opcode_bytes.first_byte, 0xAB);
+        assert_eq!(opcode_bytes.second_byte, 0xCD);
+        assert_eq!(opcode_bytes.first_nibble, 0xA);
+        assert_eq!(opcode_bytes.last_nibble, 0xD);
+    }
+
+    #[test]
+    #[should_panic(expected = "Improper opcode format")]
+    fn build_improper_opcode_short() {
+        let _ = OpcodeBytes::build(&[0x68]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Improper opcode format")]
+    fn build_improper_opcode_long() {
+        let _ = OpcodeBytes::build(&[0x23, 0x81, 0x54]);
+    }
+
+    #[test]
+    fn table_decodes_like_match() {
+        for opcode in 0..=u16::MAX {
+            let opcode_bytes = OpcodeBytes::build(&opcode.to_be_bytes());
+            assert_eq!(opcode_bytes.try_get_opcode_from_table(), opcode_bytes.try_get_opcode(), "{opcode:04X} decoded differently.");
+        }
+        let long_load = OpcodeBytes::build(&[0xF0, 0x00, 0x12, 0x34]);
+        assert_eq!(long_load.try_get_opcode_from_table(), Some(Opcode::LongLoadRegisterI(0x1234)), "Long opcode decoded differently.");
+    }
+
+    #[test]
+    fn get_nibble() {
+        let byte = 0xAE;
+        assert_eq!(OpcodeBytes::get_upper_nibble(byte), 0xA);
+        assert_eq!(OpcodeBytes::get_lower_nibble(byte), 0xE);
+    }
+
+    #[test]
+    fn get_addr_value() {
+        let opcode_bytes = OpcodeBytes::build(&[0x8A, 0x78]);
+        assert_eq!(opcode_bytes.get_addr(), 0xA78);
+    }
+
+    #[test]
+    fn to_string() {
+        let opcode_bytes = OpcodeBytes::build(&[0x36, 0x91]);
+        assert_eq!(opcode_bytes.to_string(), String::from("3691"));
+    }
+
+    #[test]
+    fn opcode_mnemonics() {
+        assert_eq!(Opcode::LoadValue(0x3, 0x1F).to_string(), "LD V3, 0x1F", "Value opcode written incorrectly.");
+        assert_eq!(Opcode::Draw(0xA, 0xB, 5).to_string(), "DRW VA, VB, 5", "Draw opcode written incorrectly.");
+        assert_eq!(Opcode::CallAddr(0x2F0).to_string(), "CALL 0x2F0", "Address opcode written incorrectly.");
+        assert_eq!(Opcode::StoreRegisters(0xF).to_string(), "LD [I], VF", "Store opcode written incorrectly.");
+        assert_eq!(Opcode::HighResolution.to_string(), "HIGH", "Resolution opcode written incorrectly.");
+        assert_eq!(Opcode::ScrollDown(4).to_string(), "SCD 4", "Scroll opcode written incorrectly.");
+    }
+
+    #[test]
+    #[should_panic(expected = "Unrecognized opcode")]
+    fn get_unrecognized_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[0x51, 0xC7]);
+        let _ = opcode_bytes.get_opcode();
+    }
+
+    #[test]
+    fn try_get_unrecognized_opcode() {
+        assert_eq!(OpcodeBytes::build(&[0x51, 0xC7]).try_get_opcode(), None, "Unrecognized opcode returned.");
+        assert_eq!(OpcodeBytes::build(&[0x51, 0xC0]).try_get_opcode(), Some(Opcode::SkipRegistersEqual(0x1, 0xC)), "Recognized opcode not returned.");
+    }
+
+    #[test]
+    fn get_sys_addr_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[0x0A, 0x78]);
+        let opcode = opcode_bytes.get_opcode();
+        assert_eq!(opcode, Opcode::SystemAddr(0xA78));
+    }
+
+    #[test]
+    fn get_clear_screen_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[CLEAR_SCREEN_OPCODE_FIRST_BYTE, CLEAR_SCREEN_OPCODE_SECOND_BYTE]);
+        assert_eq!(opcode_bytes.get_opcode(), Opcode::ClearScreen);
+    }
+
+    #[test]
+    fn get_return_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[RETURN_OPCODE_OPCODE_FIRST_BYTE, RETURN_OPCODE_OPCODE_SECOND_BYTE]);
+        assert_eq!(opcode_bytes.get_opcode(), Opcode::Return);
+    }
+
+    #[test]
+    fn get_xo_chip_opcodes() {
+        let long_load = OpcodeBytes::build(&[LONG_LOAD_REGISTER_I_OPCODE_FIRST_BYTE, LONG_LOAD_REGISTER_I_OPCODE_SECOND_BYTE, 0xAB, 0xCD]);
+        assert_eq!(long_load.length(), LONG_OPCODE_LENGTH, "Long opcode length incorrect.");
+        assert_eq!(long_load.get_opcode(), Opcode::LongLoadRegisterI(0xABCD));
+        assert_eq!(long_load.to_string(), "F000 ABCD", "Long opcode bytes written incorrectly.");
+        assert_eq!(OpcodeBytes::build(&[LONG_LOAD_REGISTER_I_OPCODE_FIRST_BYTE, LONG_LOAD_REGISTER_I_OPCODE_SECOND_BYTE]).try_get_opcode(), None, "Long opcode recognized without its address.");
+        assert_eq!(OpcodeBytes::build(&[0xAB, 0xCD]).length(), OPCODE_LENGTH, "Opcode length incorrect.");
+
+        assert_eq!(OpcodeBytes::build(&[0xF2, 0x01]).get_opcode(), Opcode::SelectPlanes(2));
+        assert_eq!(OpcodeBytes::build(&[0xF4, 0x01]).try_get_opcode(), None, "Nonexistent plane selected.");
+
+        assert_eq!(OpcodeBytes::build(&[LOAD_AUDIO_PATTERN_OPCODE_FIRST_BYTE, LOAD_AUDIO_PATTERN_OPCODE_SECOND_BYTE]).get_opcode(), Opcode::LoadAudioPattern);
+        assert_eq!(OpcodeBytes::build(&[0xF5, 0x3A]).get_opcode(), Opcode::SetPitch(5));
+    }
+
+    #[test]
+    fn get_scroll_opcodes() {
+        assert_eq!(OpcodeBytes::build(&[SCROLL_DOWN_OPCODE_FIRST_BYTE, 0xC7]).get_opcode(), Opcode::ScrollDown(7));
+        assert_eq!(OpcodeBytes::build(&[SCROLL_RIGHT_OPCODE_FIRST_BYTE, SCROLL_RIGHT_OPCODE_SECOND_BYTE]).get_opcode(), Opcode::ScrollRight);
+        assert_eq!(OpcodeBytes::build(&[SCROLL_LEFT_OPCODE_FIRST_BYTE, SCROLL_LEFT_OPCODE_SECOND_BYTE]).get_opcode(), Opcode::ScrollLeft);
+    }
+
+    #[test]
+    fn get_low_resolution_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[LOW_RESOLUTION_OPCODE_FIRST_BYTE, LOW_RESOLUTION_OPCODE_SECOND_BYTE]);
+        assert_eq!(opcode_bytes.get_opcode(), Opcode::LowResolution);
+    }
+
+    #[test]
+    fn get_high_resolution_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[HIGH_RESOLUTION_OPCODE_FIRST_BYTE, HIGH_RESOLUTION_OPCODE_SECOND_BYTE]);
+        assert_eq!(opcode_bytes.get_opcode(), Opcode::HighResolution);
+    }
+
+    #[test]
+    fn get_exit_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[EXIT_OPCODE_FIRST_BYTE, EXIT_OPCODE_SECOND_BYTE]);
+        assert_eq!(opcode_bytes.get_opcode(), Opcode::Exit);
+        assert_eq!(Opcode::Exit.to_string(), "EXIT", "Exit opcode written incorrectly.");
+    }
+
+    #[test]
+    fn get_jump_addr_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[0x1B, 0xEE]);
+        assert_eq!(opcode_bytes.get_opcode(), Opcode::JumpAddr(0xBEE));
+    }
+
+    #[test]
+    fn get_call_addr_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[0x23, 0x10]);
+        assert_eq!(opcode_bytes.get_opcode(), Opcode::CallAddr(0x310));
+    }
+
+    #[test]
+    fn get_skip_register_equals_value_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[0x36, 0x5A]);
+        assert_eq!(opcode_bytes.get_opcode(), Opcode::SkipRegisterEqualsValue(0x6, 0x5A));
+    }
+
+    #[test]
+    fn get_skip_register_not_equals_value_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[0x47, 0x1F]);
+        assert_eq!(opcode_bytes.get_opcode(), Opcode::SkipRegisterNotEqualsValue(0x7, 0x1F));
+    }
+
+    #[test]
+    fn get_skip_registers_equal_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[0x51, 0xC0]);
+        assert_eq!(opcode_bytes.get_opcode(), Opcode::SkipRegistersEqual(0x1, 0xC));
+    }
+
+    #[test]
+    fn get_load_value_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[0x64, 0x88]);
+        assert_eq!(opcode_bytes.get_opcode(), Opcode::LoadValue(0x4, 0x88));
+    }
+
+    #[test]
+    fn get_add_value_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[0x7B, 0xEF]);
+        assert_eq!(opcode_bytes.get_opcode(), Opcode::AddValue(0xB, 0xEF));
+    }
+
+    #[test]
+    fn get_load_register_value_value_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[0x80, 0x60]);
+        assert_eq!(opcode_bytes.get_opcode(), Opcode::LoadRegisterValue(0x0, 0x6));
+    }
+
+    #[test]
+    fn get_or_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[0x8D, 0x91]);
+        assert_eq!(opcode_bytes.get_opcode(), Opcode::Or(0xD, 0x9));
+    }
+
+    #[test]
+    fn get_and_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[0x85, 0x42]);
+        assert_eq!(opcode_bytes.get_opcode(), Opcode::And(0x5, 0x4));
+    }
+
+    #[test]
+    fn get_xor_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[0x87, 0x23]);
+        assert_eq!(opcode_bytes.get_opcode(), Opcode::Xor(0x7, 0x2));
+    }
+
+    #[test]
+    fn get_add_registers_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[0x83, 0xA4]);
+        assert_eq!(opcode_bytes.get_opcode(), Opcode::AddRegisters(0x3, 0xA));
+    }
+
+    #[test]
+    fn get_subtract_from_first_register_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[0x88, 0x95]);
+        assert_eq!(opcode_bytes.get_opcode(), Opcode::SubtractFromFirstRegister(0x8, 0x9));
+    }
+
+    #[test]
+    fn get_bit_shift_right_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[0x85, 0x36]);
+        assert_eq!(opcode_bytes.get_opcode(), Opcode::BitShiftRight(0x5, 0x3));
+    }
+
+    #[test]
+    fn get_subtract_from_second_register_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[0x81, 0x07]);
+        assert_eq!(opcode_bytes.get_opcode(), Opcode::SubtractFromSecondRegister(0x1, 0x0));
+    }
+
+    #[test]
+    fn get_bit_shift_left_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[0x8E, 0xCE]);
+        assert_eq!(opcode_bytes.get_opcode(), Opcode::BitShiftLeft(0xE, 0xC));
+    }
+
+    #[test]
+    fn get_skip_registers_not_equal_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[0x97, 0x50]);
+        assert_eq!(opcode_bytes.get_opcode(), Opcode::SkipRegistersNotEqual(0x7, 0x5));
+    }
+
+    #[test]
+    fn get_set_register_i_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[0xAB, 0xF3]);
+        assert_eq!(opcode_bytes.get_opcode(), Opcode::LoadRegisterI(0xBF3));
+    }
+
+    #[test]
+    fn get_jump_addr_v0_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[0xB2, 0x09]);
+        assert_eq!(opcode_bytes.get_opcode(), Opcode::JumpAddrV0(0x209));
+    }
+
+    #[test]
+    fn get_random_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[0xCF, 0x58]);
+        assert_eq!(opcode_bytes.get_opcode(), Opcode::Random(0xF, 0x58));
+    }
+
+    #[test]
+    fn get_draw_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[0xDA, 0xBC]);
+        assert_eq!(opcode_bytes.get_opcode(), Opcode::Draw(0xA, 0xB, 0xC));
+    }
+
+    #[test]
+    fn get_skip_key_pressed_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[0xEB, 0x9E]);
+        assert_eq!(opcode_bytes.get_opcode(), Opcode::SkipKeyPressed(0xB));
+    }
+
+    #[test]
+    fn get_skip_key_not_pressed_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[0xED, 0xA1]);
+        assert_eq!(opcode_bytes.get_opcode(), Opcode::SkipKeyNotPressed(0xD));
+    }
+
+    #[test]
+    fn get_load_delay_timer_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[0xFC, 0x07]);
+        assert_eq!(opcode_bytes.get_opcode(), Opcode::LoadDelayTimer(0xC));
+    }
+
+    #[test]
+    fn get_load_key_press_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[0xF3, 0x0A]);
+        assert_eq!(opcode_bytes.get_opcode(), Opcode::LoadKeyPress(0x3));
+    }
+
+    #[test]
+    fn get_set_delay_timer_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[0xF8, 0x15]);
+        assert_eq!(opcode_bytes.get_opcode(), Opcode::SetDelayTimer(0x8));
+    }
+
+    #[test]
+    fn get_set_sound_timer_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[0xF6, 0x18]);
+        assert_eq!(opcode_bytes.get_opcode(), Opcode::SetSoundTimer(0x6));
+    }
+
+    #[test]
+    fn get_add_register_i_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[0xF2, 0x1E]);
+        assert_eq!(opcode_bytes.get_opcode(), Opcode::AddRegisterI(0x2));
+    }
+
+    #[test]
+    fn get_set_i_hex_sprite_location_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[0xFB, 0x29]);
+        assert_eq!(opcode_bytes.get_opcode(), Opcode::SetIHexSpriteLocation(0xB));
+    }
+
+    #[test]
+    fn get_set_i_large_hex_sprite_location_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[0xF4, 0x30]);
+        assert_eq!(opcode_bytes.get_opcode(), Opcode::SetILargeHexSpriteLocation(0x4));
+    }
+
+    #[test]
+    fn get_rpl_flags_opcodes() {
+        assert_eq!(OpcodeBytes::build(&[0xF7, 0x75]).get_opcode(), Opcode::StoreRplFlags(0x7));
+        assert_eq!(OpcodeBytes::build(&[0xF3, 0x85]).get_opcode(), Opcode::LoadRplFlags(0x3));
+    }
+
+    #[test]
+    fn get_binary_coded_decimal_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[0xF7, 0x33]);
+        assert_eq!(opcode_bytes.get_opcode(), Opcode::BinaryCodedDecimal(0x7));
+    }
+
+    #[test]
+    fn get_store_registers_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[0xF3, 0x55]);
+        assert_eq!(opcode_bytes.get_opcode(), Opcode::StoreRegisters(0x3));
+    }
+
+    #[test]
+    fn get_load_registers_opcode() {
+        let opcode_bytes = OpcodeBytes::build(&[0xFA, 0x65]);
+        assert_eq!(opcode_bytes.get_opcode(), Opcode::LoadRegisters(0xA));
+    }
+
+    #[test]
+    fn every_name_listed() {
+        let mut names = std::collections::HashSet::new();
+        for opcode in 0..=u16::MAX {
+            let [first_byte, second_byte] = opcode.to_be_bytes();
+            let bytes = [first_byte, second_byte, 0x12, 0x34];
+            let length = usize::from(OpcodeBytes::build(&bytes[..2]).length());
+            if let Some(opcode) = OpcodeBytes::build(&bytes[..length]).try_get_opcode() {
+                names.insert(opcode.name());
+            }
+        }
+
+        assert_eq!(names.len(), Opcode::NAMES.len(), "Names not all decoded.");
+        assert!(names.iter().all(|name| Opcode::NAMES.contains(name)), "Decoded name not listed.");
+    }
+
+    #[test]
+    fn disassemble_program() {
+        let program = [0x00, 0xE0, 0x51, 0x27, 0xF0, 0x00, 0x12, 0x34, 0xAB];
+        assert_eq!(disassemble(&program, 0x200), "0x200: 00E0 CLS\n0x202: 5127 (unrecognized)\n0x204: F000 1234 LD I, 0x1234\n0x208: AB\n", "Program disassembled incorrectly.");
+        assert_eq!(disassemble(&[0xF0, 0x00], 0x2C0), "0x2C0: F000 (unrecognized)\n", "Cut off long load disassembled incorrectly.");
+
+        let symbols: SymbolTable = "main 0x200\nloop 0x202".parse().unwrap();
+        assert_eq!(disassemble_with_symbols(&[0x00, 0xE0, 0x12, 0x02], 0x200, &symbols), "0x200 (main): 00E0 CLS\n0x202 (loop): 1202 JP 0x202 (loop)\n", "Labels not shown.");
+    }
+
+    #[test]
+    fn disassemble_banked_program() {
+        let mut program = vec![0x18, 0x00];
+        program.resize(0x600, 0x00);
+        program.extend([0x00, 0xE0]);
+        program.resize(0x600 + BANK_SIZE, 0x00);
+        program.extend([0x12, 0x00]);
+
+        let symbols: SymbolTable = "main 0x200\n.bank 1\nlevel-two 0x800\n".parse().unwrap();
+        let disassembly = disassemble_banked(&program, 0x200, &symbols);
+        assert!(disassembly.starts_with("0x200 (main): 1800 JP 0x800\n"), "Fixed part not disassembled from the load address: {disassembly}");
+        assert!(disassembly.contains("\n.bank 0\n0x800: 00E0 CLS\n"), "First bank not disassembled from the window.");
+        assert!(disassembly.ends_with("\n.bank 1\n0x800 (level-two): 1200 JP 0x200 (main)\n"), "Second bank not disassembled with its labels.");
+        assert_eq!(disassemble_banked(&[0x12, 0x00], 0x200, &symbols), "0x200 (main): 1200 JP 0x200 (main)\n", "Bank directive written for a game without banks.");
+    }
+}
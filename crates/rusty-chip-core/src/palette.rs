@@ -0,0 +1,391 @@
+//! A module to contain the colours used to draw the display.  
+//! The palette has an entry for the background and one for each combination of drawing planes, so that multi-plane games can be given their intended look.  
+//! Palettes can be edited live through the [`PaletteEditor`](PaletteEditor) and saved per game in a small file alongside it.  
+//! Palettes can also be written out as text (e.g. on the command line), starting from one of the [presets](PalettePreset): `amber`, `bg=#000000,fg=#33FF66`, or `lcd,fg=#000000`.
+
+use core::fmt::{Display, Formatter};
+use core::str::FromStr;
+#[cfg(feature = "std")]
+use std::io;
+#[cfg(feature = "std")]
+use std::io::ErrorKind;
+
+#[cfg(not(feature = "std"))]
+use crate::alloc_prelude::*;
+#[cfg(feature = "std")]
+use crate::storage::StorageBackend;
+
+/// The number of entries in a palette: the background, plane 1, plane 2, and both planes.
+pub const PALETTE_SIZE: usize = 4;
+
+/// The palette entry used for the background.
+pub const BACKGROUND: usize = 0;
+
+/// The palette entry used for pixels set on the first plane (the only plane of the original CHIP-8).
+pub const FIRST_PLANE: usize = 1;
+
+/// The extension appended to a game's path to store its palette.
+const PALETTE_FILE_EXTENSION: &str = "palette";
+
+/// The names of each palette entry, for display to the user.
+const ENTRY_NAMES: [&str; PALETTE_SIZE] = ["Background", "Plane 1", "Plane 2", "Planes 1+2"];
+
+/// The names of the palette entries when a palette is written out as text: the background, the first plane, the second plane, and the blend of both planes.
+const ENTRY_KEYS: [&str; PALETTE_SIZE] = ["bg", "fg", "fg2", "blend"];
+
+/// The colours the editor cycles through, ending with those of Octo's palette which are not already listed.
+const PRESET_COLOURS: [Colour; 12] = [
+    Colour::rgb(0x00, 0x00, 0x00),
+    Colour::rgb(0xFF, 0xFF, 0xFF),
+    Colour::rgb(0x00, 0xFF, 0x00),
+    Colour::rgb(0xFF, 0xB0, 0x00),
+    Colour::rgb(0xFF, 0x00, 0x00),
+    Colour::rgb(0x00, 0x00, 0xFF),
+    Colour::rgb(0x00, 0xFF, 0xFF),
+    Colour::rgb(0xFF, 0x00, 0xFF),
+    Colour::rgb(0x99, 0x66, 0x00),
+    Colour::rgb(0x66, 0x22, 0x00),
+    Colour::rgb(0xFF, 0xCC, 0x00),
+    Colour::rgb(0xFF, 0x66, 0x00)
+];
+
+/// Stores a single RGB colour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Colour {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8
+}
+
+impl Colour {
+    /// Returns the colour with the provided components.
+    #[must_use]
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Colour {
+        Colour { r, g, b }
+    }
+
+    /// Returns the colour described by a hex string of the form `#RRGGBB` (the `#` is optional), or `None` if it is malformed.
+    ///
+    /// # Parameters
+    ///
+    /// * `hex` - The string to parse.
+    #[must_use]
+    pub fn from_hex(hex: &str) -> Option<Colour> {
+        let hex = hex.trim();
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        if hex.len() != 6 || !hex.is_ascii() {
+            return None;
+        }
+
+        let component = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        Some(Colour::rgb(component(0)?, component(2)?, component(4)?))
+    }
+}
+
+impl Display for Colour {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "#{:02X}{:02X}{:02X}", self.r, self.g, self.b)
+    }
+}
+
+/// Stores the colour of the background and of each combination of drawing planes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Palette {
+    pub colours: [Colour; PALETTE_SIZE]
+}
+
+impl Palette {
+    /// Returns the classic black and green palette, with the extra planes taken from Octo's defaults.
+    #[must_use]
+    pub fn new() -> Palette {
+        Palette {
+            colours: [
+                Colour::rgb(0x00, 0x00, 0x00),
+                Colour::rgb(0x00, 0xFF, 0x00),
+                Colour::rgb(0xFF, 0x66, 0x00),
+                Colour::rgb(0x66, 0x22, 0x00)
+            ]
+        }
+    }
+
+    /// Returns the path of the file storing the palette of the provided game.
+    ///
+    /// # Parameters
+    ///
+    /// * `game_path` - The path to the game file.
+    #[must_use]
+    pub fn path_for_game(game_path: &str) -> String {
+        format!("{game_path}.{PALETTE_FILE_EXTENSION}")
+    }
+
+    /// Returns the palette saved for the provided game, or `None` if it has none.
+    ///
+    /// # Parameters
+    ///
+    /// * `storage` - Where the palette is saved.
+    /// * `game_path` - The path to the game file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the palette file exists but cannot be read or is malformed.
+    #[cfg(feature = "std")]
+    pub fn load_for_game(storage: &dyn StorageBackend, game_path: &str) -> io::Result<Option<Palette>> {
+        let Some(contents) = storage.read_to_string(&Self::path_for_game(game_path))? else {
+            return Ok(None);
+        };
+
+        let mut palette = Palette::new();
+        let mut lines = contents.lines().filter(|line| !line.trim().is_empty());
+        for colour in &mut palette.colours {
+            let line = lines.next().ok_or_else(|| io::Error::new(ErrorKind::InvalidData, "Palette file has too few colours."))?;
+            *colour = Colour::from_hex(line).ok_or_else(|| io::Error::new(ErrorKind::InvalidData, format!("Invalid palette colour: {line}")))?;
+        }
+
+        Ok(Some(palette))
+    }
+
+    /// Saves the palette for the provided game, one colour per line.
+    ///
+    /// # Parameters
+    ///
+    /// * `storage` - Where to save the palette.
+    /// * `game_path` - The path to the game file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the palette file cannot be written.
+    #[cfg(feature = "std")]
+    pub fn save_for_game(&self, storage: &mut dyn StorageBackend, game_path: &str) -> io::Result<()> {
+        let contents: String = self.colours.iter().map(|colour| format!("{colour}\n")).collect();
+        storage.write(&Self::path_for_game(game_path), contents.as_bytes())
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Palette::new()
+    }
+}
+
+impl FromStr for Palette {
+    type Err = String;
+
+    /// Parses a [preset](PalettePreset) name, colours separated by commas, or a preset followed by colours changing it (e.g. `amber`, `#000000, #33FF66`, or `lcd, fg=#000000`).  
+    /// Colours are either named by their entry (`bg` for the background, `fg` and `fg2` for the first and second planes, or `blend` for pixels on both) or listed in the order of the entries, starting from the background. Entries left out keep the colour of the preset, or the usual colour without one.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut items = s.split(',').map(str::trim).peekable();
+        let mut palette = match items.peek().and_then(|item| PalettePreset::from_name(item)) {
+            Some(preset) => {
+                items.next();
+                preset.palette()
+            },
+            None => Palette::new()
+        };
+
+        let mut next_entry = BACKGROUND;
+        for item in items {
+            let (entry, colour) = match item.split_once('=') {
+                Some((key, colour)) => {
+                    let key = key.trim();
+                    let entry = ENTRY_KEYS.iter().position(|entry_key| entry_key.eq_ignore_ascii_case(key)).ok_or_else(|| format!("Unknown palette entry \"{key}\"."))?;
+                    (entry, colour)
+                },
+                None => {
+                    next_entry += 1;
+                    (next_entry - 1, item)
+                }
+            };
+            let slot = palette.colours.get_mut(entry).ok_or_else(|| format!("A palette has at most {PALETTE_SIZE} colours."))?;
+            *slot = Colour::from_hex(colour).ok_or_else(|| format!("Invalid palette colour: {}", colour.trim()))?;
+        }
+
+        Ok(palette)
+    }
+}
+
+/// Denotes a ready-made palette, which can be named in place of its colours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PalettePreset {
+    /// Green on black, the usual palette.
+    ClassicGreen,
+    /// Dark green on pale green, like an early handheld's LCD.
+    Lcd,
+    /// Amber on black, like a monochrome monitor.
+    Amber,
+    /// Black on off-white, like ink on paper.
+    PaperWhite,
+    /// Octo's default yellow and orange on brown, which most XO-CHIP games were made with.
+    Octo
+}
+
+impl PalettePreset {
+    /// Every preset, in the order they are listed to the user.
+    pub const ALL: [PalettePreset; 5] = [PalettePreset::ClassicGreen, PalettePreset::Lcd, PalettePreset::Amber, PalettePreset::PaperWhite, PalettePreset::Octo];
+
+    /// Returns the name the preset is written as (e.g. `paper-white`).
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            PalettePreset::ClassicGreen => "classic-green",
+            PalettePreset::Lcd => "lcd",
+            PalettePreset::Amber => "amber",
+            PalettePreset::PaperWhite => "paper-white",
+            PalettePreset::Octo => "octo"
+        }
+    }
+
+    /// Returns the preset with the provided name, regardless of case, or `None` if there is none.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the preset.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<PalettePreset> {
+        PalettePreset::ALL.into_iter().find(|preset| preset.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Returns the colours of the preset. Apart from Octo's, the extra planes are shades of the first, so that XO-CHIP games stay readable.
+    #[must_use]
+    pub fn palette(self) -> Palette {
+        let colours = match self {
+            PalettePreset::ClassicGreen => return Palette::new(),
+            PalettePreset::Lcd => [Colour::rgb(0x9B, 0xBC, 0x0F), Colour::rgb(0x0F, 0x38, 0x0F), Colour::rgb(0x8B, 0xAC, 0x0F), Colour::rgb(0x30, 0x62, 0x30)],
+            PalettePreset::Amber => [Colour::rgb(0x00, 0x00, 0x00), Colour::rgb(0xFF, 0xB0, 0x00), Colour::rgb(0xCC, 0x7A, 0x00), Colour::rgb(0x66, 0x3D, 0x00)],
+            PalettePreset::PaperWhite => [Colour::rgb(0xF4, 0xF1, 0xE8), Colour::rgb(0x1A, 0x1A, 0x1A), Colour::rgb(0x8A, 0x86, 0x7E), Colour::rgb(0x4A, 0x47, 0x42)],
+            PalettePreset::Octo => [Colour::rgb(0x99, 0x66, 0x00), Colour::rgb(0xFF, 0xCC, 0x00), Colour::rgb(0xFF, 0x66, 0x00), Colour::rgb(0x66, 0x22, 0x00)]
+        };
+
+        Palette { colours }
+    }
+}
+
+/// Tracks the state of the palette menu, which edits one palette entry at a time.
+#[derive(Debug, Default)]
+pub struct PaletteEditor {
+    selected: usize
+}
+
+impl PaletteEditor {
+    /// Returns the palette entry currently being edited.
+    #[must_use]
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Moves the selection to the next (or previous) palette entry, wrapping around.
+    ///
+    /// # Parameters
+    ///
+    /// * `forward` - True to select the next entry, false to select the previous one.
+    pub fn select(&mut self, forward: bool) {
+        self.selected = if forward { (self.selected + 1) % PALETTE_SIZE } else { (self.selected + PALETTE_SIZE - 1) % PALETTE_SIZE };
+    }
+
+    /// Changes the selected entry of the palette to the next (or previous) preset colour.
+    ///
+    /// # Parameters
+    ///
+    /// * `palette` - The palette to edit.
+    /// * `forward` - True to use the next preset, false to use the previous one.
+    pub fn cycle_colour(&self, palette: &mut Palette, forward: bool) {
+        let colour = &mut palette.colours[self.selected];
+        let preset_count = PRESET_COLOURS.len();
+        let next = match PRESET_COLOURS.iter().position(|preset| preset == colour) {
+            Some(i) if forward => (i + 1) % preset_count,
+            Some(i) => (i + preset_count - 1) % preset_count,
+            None => 0
+        };
+        *colour = PRESET_COLOURS[next];
+    }
+
+    /// Returns a short description of the selected entry, suitable for the window title.
+    ///
+    /// # Parameters
+    ///
+    /// * `palette` - The palette being edited.
+    #[must_use]
+    pub fn describe(&self, palette: &Palette) -> String {
+        format!("Palette: {} {}", ENTRY_NAMES[self.selected], palette.colours[self.selected])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    #[test]
+    fn colour_hex_round_trip() {
+        let colour = Colour::rgb(0x12, 0xAB, 0xFF);
+        assert_eq!(colour.to_string(), "#12ABFF", "Colour formatted incorrectly.");
+        assert_eq!(Colour::from_hex("#12abff"), Some(colour), "Colour parsed incorrectly.");
+        assert_eq!(Colour::from_hex("12ABFF"), Some(colour), "Colour without # parsed incorrectly.");
+        assert_eq!(Colour::from_hex("#12AB"), None, "Short colour parsed.");
+        assert_eq!(Colour::from_hex("#12ABZZ"), None, "Invalid colour parsed.");
+    }
+
+    #[test]
+    fn parse_palette() {
+        let palette: Palette = "#112233, 445566".parse().unwrap();
+        assert_eq!(palette.colours[..2], [Colour::rgb(0x11, 0x22, 0x33), Colour::rgb(0x44, 0x55, 0x66)], "Colours not parsed in order.");
+        assert_eq!(palette.colours[2..], Palette::new().colours[2..], "Entries left out not kept.");
+
+        assert!("".parse::<Palette>().is_err(), "Empty palette parsed.");
+        assert!("#000000,#000000,#000000,#000000,#000000".parse::<Palette>().is_err(), "Palette with too many colours parsed.");
+
+        let palette: Palette = "bg=#112233, FG = #445566".parse().unwrap();
+        assert_eq!(palette.colours[..2], [Colour::rgb(0x11, 0x22, 0x33), Colour::rgb(0x44, 0x55, 0x66)], "Named colours not parsed.");
+        let palette: Palette = "fg2=#778899, blend=#AABBCC".parse().unwrap();
+        assert_eq!(palette.colours[2..], [Colour::rgb(0x77, 0x88, 0x99), Colour::rgb(0xAA, 0xBB, 0xCC)], "Named plane colours not parsed.");
+        assert_eq!(palette.colours[..2], Palette::new().colours[..2], "Entries left out not kept.");
+        assert!("sky=#112233".parse::<Palette>().is_err(), "Unknown entry parsed.");
+        assert!("fg=green".parse::<Palette>().is_err(), "Invalid named colour parsed.");
+    }
+
+    #[test]
+    fn parse_preset() {
+        for preset in PalettePreset::ALL {
+            assert_eq!(preset.name().parse(), Ok(preset.palette()), "Preset not parsed by name.");
+        }
+        assert_eq!("Classic-Green".parse(), Ok(Palette::new()), "Preset not matched regardless of case.");
+
+        let palette: Palette = "amber, fg=#FFFFFF".parse().unwrap();
+        assert_eq!(palette.colours[BACKGROUND], PalettePreset::Amber.palette().colours[BACKGROUND], "Preset colour not kept.");
+        assert_eq!(palette.colours[FIRST_PLANE], Colour::rgb(0xFF, 0xFF, 0xFF), "Preset colour not changed.");
+        assert!("#000000, amber".parse::<Palette>().is_err(), "Preset after a colour parsed.");
+    }
+
+    #[test]
+    fn save_and_load_for_game() {
+        let mut storage = MemoryStorage::new();
+        let game_path = "games/game.ch8";
+
+        assert_eq!(Palette::load_for_game(&storage, game_path).unwrap(), None, "Palette loaded for game without one.");
+
+        let mut palette = Palette::new();
+        palette.colours[BACKGROUND] = Colour::rgb(0x11, 0x22, 0x33);
+        palette.save_for_game(&mut storage, game_path).unwrap();
+        assert_eq!(Palette::load_for_game(&storage, game_path).unwrap(), Some(palette), "Saved palette not loaded.");
+
+        storage.write(&Palette::path_for_game(game_path), b"#112233\n").unwrap();
+        assert!(Palette::load_for_game(&storage, game_path).is_err(), "Palette with too few colours loaded.");
+    }
+
+    #[test]
+    fn editor_cycles() {
+        let mut editor = PaletteEditor::default();
+        let mut palette = Palette::new();
+
+        editor.select(false);
+        assert_eq!(editor.selected(), PALETTE_SIZE - 1, "Selection did not wrap backwards.");
+        editor.select(true);
+        assert_eq!(editor.selected(), BACKGROUND, "Selection did not wrap forwards.");
+
+        editor.cycle_colour(&mut palette, true);
+        assert_eq!(palette.colours[BACKGROUND], PRESET_COLOURS[1], "Colour not cycled forwards.");
+        editor.cycle_colour(&mut palette, false);
+        editor.cycle_colour(&mut palette, false);
+        assert_eq!(palette.colours[BACKGROUND], PRESET_COLOURS[PRESET_COLOURS.len() - 1], "Colour not cycled backwards.");
+        assert_eq!(editor.describe(&palette), format!("Palette: Background {}", PRESET_COLOURS[PRESET_COLOURS.len() - 1]), "Selection described incorrectly.");
+    }
+}
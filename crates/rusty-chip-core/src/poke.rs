@@ -0,0 +1,150 @@
+//! A module to edit the registers and memory of a paused game, for testing a hypothesis about a bug without reassembling the game.
+//! A poke is written as `<target> = <value>`, where the target is a register (`V0`-`VF`, `I`, `PC`, `DT`, or `ST`) or a memory byte (`[0x3F0]` or `[I + 1]`), and the value is a [watch expression](crate::watch) (e.g. `0x1F`, `V3 + 1`, or `[I]`).
+//! Both are evaluated against the game as it is when the poke is applied.
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use crate::interpreter::Interpreter;
+use crate::watch::WatchExpression;
+
+/// Denotes what a poke writes to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PokeTarget {
+    Register(usize),
+    RegisterI,
+    ProgramCounter,
+    DelayTimer,
+    SoundTimer,
+    /// The memory byte at the address the expression evaluates to.
+    Memory(WatchExpression)
+}
+
+impl FromStr for PokeTarget {
+    type Err = String;
+
+    /// Parses a register name regardless of case, or a memory address in brackets.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some(address) = s.strip_prefix('[').and_then(|address| address.strip_suffix(']')) {
+            return Ok(PokeTarget::Memory(address.parse()?));
+        }
+
+        let upper = s.to_ascii_uppercase();
+        match upper.as_str() {
+            "I" => Ok(PokeTarget::RegisterI),
+            "PC" => Ok(PokeTarget::ProgramCounter),
+            "DT" => Ok(PokeTarget::DelayTimer),
+            "ST" => Ok(PokeTarget::SoundTimer),
+            _ => upper.strip_prefix('V')
+                .filter(|register| register.len() == 1)
+                .and_then(|register| usize::from_str_radix(register, 16).ok())
+                .map(PokeTarget::Register)
+                .ok_or_else(|| format!("Unknown register or address '{s}' to poke."))
+        }
+    }
+}
+
+impl Display for PokeTarget {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PokeTarget::Register(register) => write!(f, "V{register:X}"),
+            PokeTarget::RegisterI => write!(f, "I"),
+            PokeTarget::ProgramCounter => write!(f, "PC"),
+            PokeTarget::DelayTimer => write!(f, "DT"),
+            PokeTarget::SoundTimer => write!(f, "ST"),
+            PokeTarget::Memory(address) => write!(f, "[{address}]")
+        }
+    }
+}
+
+/// Stores a single edit of the game's state.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Poke {
+    pub target: PokeTarget,
+    pub value: WatchExpression
+}
+
+impl Poke {
+    /// Writes the value to the target, returning a description of what was written (e.g. `V3 = 0x1F`).
+    ///
+    /// # Parameters
+    ///
+    /// * `interpreter` - The interpreter to edit.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` without writing anything if the value or address cannot be evaluated, or does not fit the target (e.g. 0x100 for a register or an address past the end of the RAM).
+    pub fn apply(&self, interpreter: &mut Interpreter) -> Result<String, String> {
+        let value = self.value.evaluate(interpreter).ok_or_else(|| format!("Cannot evaluate {}.", self.value))?;
+        let too_large = || format!("{value:#X} does not fit in {}.", self.target);
+        match &self.target {
+            PokeTarget::Register(register) => interpreter.set_register(*register, u8::try_from(value).map_err(|_| too_large())?),
+            PokeTarget::RegisterI => interpreter.set_register_i(u16::try_from(value).map_err(|_| too_large())?),
+            PokeTarget::ProgramCounter => interpreter.set_program_counter(u16::try_from(value).map_err(|_| too_large())?),
+            PokeTarget::DelayTimer => interpreter.set_delay_timer_value(u8::try_from(value).map_err(|_| too_large())?),
+            PokeTarget::SoundTimer => interpreter.set_sound_timer_value(u8::try_from(value).map_err(|_| too_large())?),
+            PokeTarget::Memory(address) => {
+                let address = address.evaluate(interpreter).ok_or_else(|| format!("Cannot evaluate {address}."))?;
+                let address = u16::try_from(address).map_err(|_| format!("Address {address:#X} is past the end of the RAM."))?;
+                interpreter.write_ram(address, &[u8::try_from(value).map_err(|_| too_large())?])?;
+                return Ok(format!("[0x{address:03X}] = {value:#04X}"));
+            }
+        }
+
+        Ok(format!("{} = {value:#X}", self.target))
+    }
+}
+
+impl FromStr for Poke {
+    type Err = String;
+
+    /// Parses a poke of the form `<target> = <value>`, returning an `Err` describing the problem if it is malformed.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (target, value) = s.split_once('=').ok_or_else(|| format!("Missing '=' in poke \"{s}\"."))?;
+
+        Ok(Poke { target: target.parse()?, value: value.parse()? })
+    }
+}
+
+impl Display for Poke {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} = {}", self.target, self.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_pokes() {
+        assert_eq!("v3 = 0x1F".parse::<Poke>().map(|poke| poke.target), Ok(PokeTarget::Register(0x3)), "Register not parsed regardless of case.");
+        assert_eq!("[I + 1] = V0".parse::<Poke>().map(|poke| poke.to_string()), Ok(String::from("[I + 1] = V0")), "Memory poke not parsed.");
+        assert!("V3 0x1F".parse::<Poke>().is_err(), "Poke without an = parsed.");
+        assert!("VG = 1".parse::<Poke>().is_err(), "Unknown register parsed.");
+        assert!("V3 = ".parse::<Poke>().is_err(), "Poke without a value parsed.");
+    }
+
+    #[test]
+    fn apply_pokes() {
+        let mut interpreter = Interpreter::new();
+        interpreter.load_game(&[0x12, 0x00]);
+
+        assert_eq!("V3 = 0x1F".parse::<Poke>().unwrap().apply(&mut interpreter), Ok(String::from("V3 = 0x1F")), "Register poke not described.");
+        assert_eq!("I = 0x300".parse::<Poke>().unwrap().apply(&mut interpreter), Ok(String::from("I = 0x300")), "Register I poke not described.");
+        assert_eq!("[I + 1] = V3 + 1".parse::<Poke>().unwrap().apply(&mut interpreter), Ok(String::from("[0x301] = 0x20")), "Memory poke not described.");
+        "PC = 0x204".parse::<Poke>().unwrap().apply(&mut interpreter).unwrap();
+        "DT = 10".parse::<Poke>().unwrap().apply(&mut interpreter).unwrap();
+        assert_eq!(
+            (interpreter.registers()[0x3], interpreter.register_i(), interpreter.ram()[0x301], interpreter.program_counter(), interpreter.delay_timer()),
+            (0x1F, 0x300, 0x20, 0x204, 10),
+            "Pokes not applied."
+        );
+
+        assert!("V3 = 0x100".parse::<Poke>().unwrap().apply(&mut interpreter).is_err(), "Value too large for a register poked.");
+        assert!("[0x10000] = 1".parse::<Poke>().unwrap().apply(&mut interpreter).is_err(), "Address past the end of the RAM poked.");
+        assert!("V3 = 1 / 0".parse::<Poke>().unwrap().apply(&mut interpreter).is_err(), "Value which cannot be evaluated poked.");
+        assert_eq!(interpreter.registers()[0x3], 0x1F, "Failed poke written.");
+    }
+}
@@ -0,0 +1,32 @@
+//! A module gathering the stable surface of the crate, for embedders to import in one go with `use rusty_chip_core::prelude::*;` (or `use rusty_chip::prelude::*;` through the emulator's crate, which re-exports it).  
+//! Everything exported here is kept compatible within a major version, while the modules it comes from may be reorganized (e.g. the opcode decoder or the interpreter's internals), so embedders should prefer these paths.
+
+pub use crate::backend::{AudioSink, Display, InputSource, MessageKind, Rect};
+pub use crate::builder::InterpreterBuilder;
+#[cfg(feature = "std")]
+pub use crate::emulator::{Emulator, EmulatorMode, FrameAdvance, QuirkComparison, QuirkSide, DEFAULT_CYCLES_PER_FRAME, FRAME_DURATION};
+pub use crate::events::{Change, Event, EventLog};
+pub use crate::extensions::{Extension, ExtensionRegistry, OpcodePattern};
+pub use crate::framebuffer::Framebuffer;
+pub use crate::hooks::HookId;
+#[cfg(feature = "std")]
+pub use crate::fuzz::{Fault, InputEvent, InputScript};
+pub use crate::interpreter::{Counters, HaltReason, Interpreter, MemoryProtection, Snapshot, StackFrame, Step, WaitReason};
+#[cfg(feature = "std")]
+pub use crate::key_profile::KeyProfile;
+pub use crate::layout::{Anchor, Overlay, OverlayLayout, Placement};
+#[cfg(feature = "std")]
+pub use crate::macros::InputMacro;
+pub use crate::opcodes::Opcode;
+pub use crate::palette::{Colour, Palette};
+#[cfg(feature = "std")]
+pub use crate::poke::{Poke, PokeTarget};
+pub use crate::quirks::{ClippingQuirk, DisplayWaitQuirk, JumpingQuirk, KeyGhostingQuirk, MemoryIncrementQuirk, MemoryTimingQuirk, Platform, QuirkConfig, ResetVfQuirk, ShiftingQuirk};
+pub use crate::renderer::Renderer;
+#[cfg(feature = "std")]
+pub use crate::screenshot::{AutoScreenshot, ScreenshotTrigger};
+#[cfg(feature = "std")]
+pub use crate::state_diff::{Difference, StateDiff};
+#[cfg(feature = "std")]
+pub use crate::storage::{FileStorage, MemoryStorage, StorageBackend};
+pub use crate::symbols::SymbolTable;
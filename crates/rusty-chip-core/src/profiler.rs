@@ -0,0 +1,123 @@
+//! A module to count how often each kind of opcode runs and how long the interpreter spends on it, for game authors wondering where their cycles go.
+//! Recording is optional, as timing every instruction costs far more than running most of them.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::time::Duration;
+
+use crate::opcodes::Opcode;
+
+/// Stores how often a kind of opcode ran and the time spent running it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OpcodeStats {
+    /// The number of times the opcode ran.
+    pub count: u64,
+    /// The total time spent running the opcode.
+    pub time: Duration
+}
+
+/// Stores the [statistics](OpcodeStats) of every kind of opcode which has run, keyed by the name of its [`Opcode`](Opcode) variant.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Profile {
+    stats: HashMap<&'static str, OpcodeStats>
+}
+
+impl Profile {
+    /// Returns an empty profile.
+    #[must_use]
+    pub fn new() -> Profile {
+        Profile::default()
+    }
+
+    /// Records a single run of the opcode.
+    ///
+    /// # Parameters
+    ///
+    /// * `opcode` - The opcode which ran.
+    /// * `time` - The time spent running it.
+    pub fn record(&mut self, opcode: &Opcode, time: Duration) {
+        let stats = self.stats.entry(opcode.name()).or_default();
+        stats.count += 1;
+        stats.time += time;
+    }
+
+    /// Returns the statistics of the kind of opcode, or `None` if it has not run.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the [`Opcode`](Opcode) variant, e.g. `Draw`.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<OpcodeStats> {
+        self.stats.get(name).copied()
+    }
+
+    /// Returns the kinds of opcode which have run along with their statistics, the most time-consuming first.
+    #[must_use]
+    pub fn sorted(&self) -> Vec<(&'static str, OpcodeStats)> {
+        let mut stats: Vec<(&'static str, OpcodeStats)> = self.stats.iter().map(|(name, stats)| (*name, *stats)).collect();
+        stats.sort_by(|(first_name, first), (second_name, second)| second.time.cmp(&first.time).then(second.count.cmp(&first.count)).then(first_name.cmp(second_name)));
+
+        stats
+    }
+
+    /// Returns the number of instructions which have run.
+    #[must_use]
+    pub fn total_count(&self) -> u64 {
+        self.stats.values().map(|stats| stats.count).sum()
+    }
+
+    /// Returns the total time spent running instructions.
+    #[must_use]
+    pub fn total_time(&self) -> Duration {
+        self.stats.values().map(|stats| stats.time).sum()
+    }
+
+    /// Clears every recorded run.
+    pub fn clear(&mut self) {
+        self.stats.clear();
+    }
+
+    /// Returns a table of the kinds of opcode which have run, the most time-consuming first, with their counts and times along with their share of the totals.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn report(&self) -> String {
+        let total_count = self.total_count().max(1) as f64;
+        let total_time = self.total_time().as_secs_f64().max(f64::MIN_POSITIVE);
+        let mut report = format!("{:<28} {:>12} {:>7} {:>12} {:>7}\n", "Opcode", "Count", "Count%", "Time (us)", "Time%");
+        for (name, stats) in self.sorted() {
+            let _ = writeln!(
+                report,
+                "{name:<28} {:>12} {:>6.1}% {:>12.1} {:>6.1}%",
+                stats.count,
+                stats.count as f64 * 100.0 / total_count,
+                stats.time.as_secs_f64() * 1_000_000.0,
+                stats.time.as_secs_f64() * 100.0 / total_time
+            );
+        }
+        let _ = writeln!(report, "{:<28} {:>12} {:>7} {:>12.1}", "Total", self.total_count(), "", self.total_time().as_secs_f64() * 1_000_000.0);
+
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_opcodes() {
+        let mut profile = Profile::new();
+        profile.record(&Opcode::LoadValue(0x1, 0x05), Duration::from_micros(1));
+        profile.record(&Opcode::LoadValue(0x2, 0x06), Duration::from_micros(1));
+        profile.record(&Opcode::Draw(0x0, 0x1, 5), Duration::from_micros(8));
+
+        assert_eq!(profile.get("LoadValue"), Some(OpcodeStats { count: 2, time: Duration::from_micros(2) }), "Variants not counted together.");
+        assert_eq!(profile.sorted().first().map(|(name, _)| *name), Some("Draw"), "Most time-consuming opcode not first.");
+        assert_eq!((profile.total_count(), profile.total_time()), (3, Duration::from_micros(10)), "Totals not summed.");
+        let report = profile.report();
+        assert!(report.lines().nth(1).is_some_and(|line| line.starts_with("Draw") && line.ends_with("80.0%")), "Report written incorrectly: {report}");
+
+        profile.clear();
+        assert_eq!(profile.total_count(), 0, "Profile not cleared.");
+    }
+}
@@ -0,0 +1,186 @@
+//! A module to record which draw instruction last changed each pixel of the display, and on which frame, for inspecting pixels while debugging.
+//! Recording is optional, costing a write for every pixel a sprite flips, and is kept in a buffer alongside the drawing buffers which scrolls and clears along with them.
+//! As every pixel of a sprite shares its draw, the buffer also answers "who drew this sprite", finding the rest of the sprite from any one of its pixels. This is invaluable when reverse engineering rendering code.
+
+#[cfg(not(feature = "std"))]
+use crate::alloc_prelude::*;
+use crate::interpreter::{Interpreter, DRAWING_BUFFER_SIZE};
+use crate::opcodes::OpcodeBytes;
+
+/// Stores the draw instruction which last changed a pixel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DrawRecord {
+    /// The address of the draw instruction.
+    pub address: u16,
+    /// The draw instruction as it was when it ran, in case the game has since changed it.
+    pub opcode: u16,
+    /// The frame the draw ran on, counting from 0 when the game was loaded.
+    pub frame: u64
+}
+
+/// Stores the draw which last changed each pixel, laid out as the [display buffer](Interpreter::display_buffer).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DrawProvenance {
+    records: Vec<Option<DrawRecord>>,
+    frame: u64
+}
+
+impl DrawProvenance {
+    /// Returns a buffer with no pixels drawn.
+    #[must_use]
+    pub fn new() -> DrawProvenance {
+        DrawProvenance { records: vec![None; DRAWING_BUFFER_SIZE], frame: 0 }
+    }
+
+    /// Returns the frame draws are currently recorded on, counting from 0 when the game was loaded.
+    #[must_use]
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Returns the draw which last changed the pixel, or `None` if no draw has changed it since it was last cleared.
+    ///
+    /// # Parameters
+    ///
+    /// * `index` - The index of the pixel in the display buffer.
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<DrawRecord> {
+        self.records.get(index).copied().flatten()
+    }
+
+    /// Returns the pixels of the sprite the pixel belongs to: every pixel still showing the draw which last changed it, in display buffer order.  
+    /// Nothing is returned if no draw has changed the pixel.
+    ///
+    /// # Parameters
+    ///
+    /// * `index` - The index of any pixel of the sprite in the display buffer.
+    pub fn sprite_pixels(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        let record = self.get(index);
+        self.records.iter().enumerate()
+            .filter(move |(_, other)| record.is_some() && **other == record)
+            .map(|(index, _)| index)
+    }
+
+    /// Returns the pixels last changed by the draw instruction at the address on any frame, in display buffer order.
+    ///
+    /// # Parameters
+    ///
+    /// * `address` - The address of the draw instruction.
+    pub fn pixels_drawn_by(&self, address: u16) -> impl Iterator<Item = usize> + '_ {
+        self.records.iter().enumerate()
+            .filter(move |(_, record)| record.is_some_and(|record| record.address == address))
+            .map(|(index, _)| index)
+    }
+
+    /// Records the draw as the last to change the pixel.
+    ///
+    /// # Parameters
+    ///
+    /// * `index` - The index of the pixel in the display buffer.
+    /// * `record` - The draw which changed it.
+    pub(crate) fn record(&mut self, index: usize, record: DrawRecord) {
+        self.records[index] = Some(record);
+    }
+
+    /// Forgets the draw which last changed the pixel (e.g. once it has been cleared).
+    ///
+    /// # Parameters
+    ///
+    /// * `index` - The index of the pixel in the display buffer.
+    pub(crate) fn forget(&mut self, index: usize) {
+        self.records[index] = None;
+    }
+
+    /// Returns the draws of every pixel, to move them along with the pixels (e.g. when scrolling).
+    pub(crate) fn records_mut(&mut self) -> &mut [Option<DrawRecord>] {
+        &mut self.records
+    }
+
+    /// Moves on to recording draws on the next frame.
+    pub(crate) fn end_frame(&mut self) {
+        self.frame += 1;
+    }
+
+    /// Forgets every recorded draw, starting again from frame 0 (e.g. when a game is loaded).
+    pub(crate) fn clear(&mut self) {
+        self.records.fill(None);
+        self.frame = 0;
+    }
+}
+
+impl Default for DrawProvenance {
+    fn default() -> Self {
+        DrawProvenance::new()
+    }
+}
+
+/// Returns the position of a pixel, the XO-CHIP planes it is set on, and the draw which last changed it for the window title, e.g. `Pixel (12, 5) on plane 1, drawn by 0x206: D015 DRW V0, V1, 5 on frame 41`.
+///
+/// # Parameters
+///
+/// * `interpreter` - The interpreter to read from.
+/// * `x` - The X coordinate of the pixel on the display.
+/// * `y` - The Y coordinate of the pixel on the display.
+#[must_use]
+pub fn describe_pixel(interpreter: &Interpreter, x: u32, y: u32) -> String {
+    let index = (y * interpreter.display_width() + x) as usize;
+    let planes = match interpreter.framebuffer().colour_index(x, y) {
+        0 => "off",
+        1 => "on plane 1",
+        2 => "on plane 2",
+        _ => "on planes 1 and 2"
+    };
+
+    let drawn_by = match interpreter.provenance().map(|provenance| provenance.get(index)) {
+        None => String::from("draws not recorded"),
+        Some(None) => String::from("not drawn"),
+        Some(Some(DrawRecord { address, opcode, frame })) => {
+            let opcode_bytes = OpcodeBytes::build(&opcode.to_be_bytes());
+            match opcode_bytes.try_get_opcode() {
+                Some(opcode) => format!("drawn by 0x{address:03X}: {opcode_bytes} {opcode} on frame {frame}"),
+                None => format!("drawn by 0x{address:03X}: {opcode_bytes} on frame {frame}")
+            }
+        }
+    };
+
+    format!("Pixel ({x}, {y}) {planes}, {drawn_by}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describe_drawn_pixel() {
+        let mut interpreter = Interpreter::new();
+        interpreter.load_game(&[0xA0, 0x00, 0xD0, 0x05]);
+        assert_eq!(describe_pixel(&interpreter, 0, 0), "Pixel (0, 0) off, draws not recorded", "Missing recording not described.");
+
+        interpreter.set_provenance_enabled(true);
+        interpreter.handle_cycle();
+        interpreter.handle_cycle();
+        interpreter.handle_frame();
+        assert_eq!(describe_pixel(&interpreter, 0, 0), "Pixel (0, 0) on plane 1, drawn by 0x202: D005 DRW V0, V0, 5 on frame 0", "Drawn pixel not described.");
+        assert_eq!(describe_pixel(&interpreter, 4, 0), "Pixel (4, 0) off, not drawn", "Undrawn pixel not described.");
+    }
+
+    #[test]
+    fn find_sprite() {
+        let mut provenance = DrawProvenance::new();
+        let first_draw = DrawRecord { address: 0x206, opcode: 0xD015, frame: 3 };
+        let second_draw = DrawRecord { frame: 4, ..first_draw };
+        for index in [1, 2, 3] {
+            provenance.record(index, first_draw);
+        }
+        provenance.record(2, second_draw);
+        provenance.record(9, second_draw);
+
+        assert_eq!(provenance.sprite_pixels(1).collect::<Vec<_>>(), [1, 3], "Sprite pixels not found.");
+        assert_eq!(provenance.sprite_pixels(0).count(), 0, "Sprite found for an undrawn pixel.");
+        assert_eq!(provenance.pixels_drawn_by(0x206).collect::<Vec<_>>(), [1, 2, 3, 9], "Pixels of the draw not found.");
+
+        provenance.end_frame();
+        provenance.clear();
+        assert_eq!((provenance.get(1), provenance.frame()), (None, 0), "Draws not cleared.");
+    }
+}
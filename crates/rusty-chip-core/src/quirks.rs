@@ -0,0 +1,445 @@
+//! A module to contain the types related to the quirk configuration.  
+//! For more information on CHIP-8 quirks, please see [this section](https://github.com/Timendus/chip8-test-suite#quirks-test) of the test suite.  
+//! Quirks can be toggled mid-game through the [`QuirkEditor`](QuirkEditor).
+
+#[cfg(feature = "std")]
+use core::fmt::{Display, Formatter};
+
+#[cfg(feature = "cli")]
+use clap::ValueEnum;
+
+use crate::names::named;
+#[cfg(feature = "std")]
+use crate::names::Named;
+
+/// The number of quirks in a [`QuirkConfig`](QuirkConfig).
+#[cfg(feature = "std")]
+const QUIRK_COUNT: usize = 9;
+
+/// The names of the quirks as shown in the quirk menu, in the order of the [`QuirkConfig`](QuirkConfig) members.
+#[cfg(feature = "std")]
+const QUIRK_NAMES: [&str; QUIRK_COUNT] = ["Reset VF", "Memory increment", "Display wait", "Horizontal clipping", "Vertical clipping", "Shifting", "Jumping", "Memory timing", "Key ghosting"];
+
+/// Denotes the enabled/disabled status of the reset register F quirk.  
+/// This quirk can cause the AND, OR, and XOR opcodes to reset the value of register F.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ResetVfQuirk {
+    #[default]
+    Reset,
+    NoReset
+}
+
+/// Denotes the enabled/disabled status of the store/load registers opcodes' register I increment quirk.  
+/// This quirk can cause the store/load registers opcodes to increment register I as they operate. 
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MemoryIncrementQuirk {
+    #[default]
+    Increment,
+    NoIncrement
+}
+
+/// Denotes the enabled/disabled status of the display wait quirk.  
+/// This quirk can cause the draw opcode to wait for a screen refresh prior to drawing to prevent partial draws.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DisplayWaitQuirk {
+    #[default]
+    Wait,
+    NoWait
+}
+
+/// Denotes the enabled/disabled status of the clipping quirk along a single axis.  
+/// This quirk can cause the draw opcode to either clip sprites drawn on the edges or have them wrap around the screen.  
+/// Some historical interpreters differ per axis, so the horizontal and vertical settings are stored separately in the [`QuirkConfig`](QuirkConfig).
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ClippingQuirk {
+    #[default]
+    Clip,
+    Wrap
+}
+
+/// Denotes the enabled/disabled status of the shifting quirk.  
+/// This quirk can cause the shift register opcodes to operate on a single register or on a second one while storing the result in the first.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ShiftingQuirk {
+    #[default]
+    Vy,
+    Vx,
+}
+
+/// Denotes the enabled/disabled status of the jumping quirk.  
+/// This quirk can cause the jump to address + register 0 opcode to operate on a different register instead.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum JumpingQuirk {
+    #[default]
+    V0,
+    Vx
+}
+
+/// Denotes the enabled/disabled status of the memory timing quirk.  
+/// This quirk can cause the store/load registers opcodes to take an instruction cycle per register and the BCD opcode to take one per digit, as they took time proportional to their work on the original COSMAC VIP.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MemoryTimingQuirk {
+    #[default]
+    Instant,
+    PerRegister
+}
+
+/// Denotes the enabled/disabled status of the key ghosting quirk.  
+/// This quirk can cause the keypad to sense keys which are not held, as a [matrix keypad](crate::keypad) without diodes does when three held keys form the corners of a rectangle. Only the skip if key opcodes see the ghosted keys.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum KeyGhostingQuirk {
+    #[default]
+    Independent,
+    Ghosting
+}
+
+named!(ResetVfQuirk { Reset => "reset", NoReset => "no-reset" });
+named!(MemoryIncrementQuirk { Increment => "increment", NoIncrement => "no-increment" });
+named!(DisplayWaitQuirk { Wait => "wait", NoWait => "no-wait" });
+named!(ClippingQuirk { Clip => "clip", Wrap => "wrap" });
+named!(ShiftingQuirk { Vy => "vy", Vx => "vx" });
+named!(JumpingQuirk { V0 => "v0", Vx => "vx" });
+named!(MemoryTimingQuirk { Instant => "instant", PerRegister => "per-register" });
+named!(KeyGhostingQuirk { Independent => "independent", Ghosting => "ghosting" });
+
+/// Denotes a machine whose behaviour can be matched with a preset of all the quirks.  
+/// The presets follow the expectations of [the test suite](https://github.com/Timendus/chip8-test-suite#quirks-test) for each platform.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Platform {
+    /// The original CHIP-8 interpreter on the COSMAC VIP.
+    CosmacVip,
+    /// The CHIP-48 interpreter on the HP48 calculators.
+    #[cfg_attr(feature = "cli", value(name = "chip-48"))]
+    Chip48,
+    /// SUPER-CHIP 1.1, as expected by most SUPER-CHIP games.
+    Schip,
+    /// XO-CHIP, as implemented by Octo.
+    XoChip
+}
+
+named!(Platform { CosmacVip => "cosmac-vip", Chip48 => "chip-48", Schip => "schip", XoChip => "xo-chip" });
+
+impl Platform {
+    /// Returns the quirk settings matching the platform.
+    #[must_use]
+    pub fn quirk_config(self) -> QuirkConfig {
+        match self {
+            Platform::CosmacVip => QuirkConfig {
+                memory_timing: MemoryTimingQuirk::PerRegister,
+                ..QuirkConfig::new()
+            },
+            // CHIP-48 incremented I by one less than the number of registers, so incrementing is the closer of the two settings
+            Platform::Chip48 => QuirkConfig {
+                reset_vf: ResetVfQuirk::NoReset,
+                display_wait: DisplayWaitQuirk::NoWait,
+                shifting: ShiftingQuirk::Vx,
+                jumping: JumpingQuirk::Vx,
+                ..QuirkConfig::new()
+            },
+            Platform::Schip => QuirkConfig {
+                reset_vf: ResetVfQuirk::NoReset,
+                memory: MemoryIncrementQuirk::NoIncrement,
+                display_wait: DisplayWaitQuirk::NoWait,
+                shifting: ShiftingQuirk::Vx,
+                jumping: JumpingQuirk::Vx,
+                ..QuirkConfig::new()
+            },
+            Platform::XoChip => QuirkConfig {
+                reset_vf: ResetVfQuirk::NoReset,
+                display_wait: DisplayWaitQuirk::NoWait,
+                horizontal_clipping: ClippingQuirk::Wrap,
+                vertical_clipping: ClippingQuirk::Wrap,
+                ..QuirkConfig::new()
+            }
+        }
+    }
+}
+
+/// Stores all the quirk settings together.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct QuirkConfig {
+    pub reset_vf: ResetVfQuirk,
+    pub memory: MemoryIncrementQuirk,
+    pub display_wait: DisplayWaitQuirk,
+    pub horizontal_clipping: ClippingQuirk,
+    pub vertical_clipping: ClippingQuirk,
+    pub shifting: ShiftingQuirk,
+    pub jumping: JumpingQuirk,
+    pub memory_timing: MemoryTimingQuirk,
+    pub key_ghosting: KeyGhostingQuirk
+}
+
+impl QuirkConfig {
+    /// Returns a new `QuirkConfig` with default values for all members.
+    #[must_use]
+    pub fn new() -> QuirkConfig {
+        QuirkConfig {
+            reset_vf: ResetVfQuirk::default(),
+            memory: MemoryIncrementQuirk::default(),
+            display_wait: DisplayWaitQuirk::default(),
+            horizontal_clipping: ClippingQuirk::default(),
+            vertical_clipping: ClippingQuirk::default(),
+            shifting: ShiftingQuirk::default(),
+            jumping: JumpingQuirk::default(),
+            memory_timing: MemoryTimingQuirk::default(),
+            key_ghosting: KeyGhostingQuirk::default(),
+        }
+    }
+
+    /// Applies the same clipping behaviour to both axes.  
+    /// This matches the combined clipping quirk used by most interpreters.
+    ///
+    /// # Parameters
+    ///
+    /// * `clipping` - The clipping behaviour to use horizontally and vertically.
+    pub fn set_clipping(&mut self, clipping: ClippingQuirk) {
+        self.horizontal_clipping = clipping.clone();
+        self.vertical_clipping = clipping;
+    }
+
+    /// Changes the quirks named in the settings, which are written as they are [displayed](Display) (e.g. `shifting=vx jumping=vx`), leaving the others alone.  
+    /// `clipping` sets both clipping quirks at once, as on the command line.
+    ///
+    /// # Parameters
+    ///
+    /// * `settings` - The quirks to change, separated by whitespace.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if a quirk or setting is unknown, or a setting is not of the form `quirk=setting`. The quirks before it are still changed.
+    #[cfg(feature = "std")]
+    pub fn apply_settings(&mut self, settings: &str) -> Result<(), String> {
+        for setting in settings.split_whitespace() {
+            let (name, value) = setting.split_once('=').ok_or_else(|| format!("Quirk setting \"{setting}\" is not of the form quirk=setting."))?;
+            match name {
+                "reset-vf" => self.reset_vf = parse_setting(name, value)?,
+                "memory" => self.memory = parse_setting(name, value)?,
+                "display-wait" => self.display_wait = parse_setting(name, value)?,
+                "clipping" => self.set_clipping(parse_setting(name, value)?),
+                "horizontal-clipping" => self.horizontal_clipping = parse_setting(name, value)?,
+                "vertical-clipping" => self.vertical_clipping = parse_setting(name, value)?,
+                "shifting" => self.shifting = parse_setting(name, value)?,
+                "jumping" => self.jumping = parse_setting(name, value)?,
+                "memory-timing" => self.memory_timing = parse_setting(name, value)?,
+                "key-ghosting" => self.key_ghosting = parse_setting(name, value)?,
+                _ => return Err(format!("Unknown quirk \"{name}\"."))
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl Display for QuirkConfig {
+    /// Writes each quirk as it is named on the command line, e.g. `reset-vf=reset memory=increment ...`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "reset-vf={} memory={} display-wait={} horizontal-clipping={} vertical-clipping={} shifting={} jumping={} memory-timing={} key-ghosting={}",
+            setting_name(&self.reset_vf),
+            setting_name(&self.memory),
+            setting_name(&self.display_wait),
+            setting_name(&self.horizontal_clipping),
+            setting_name(&self.vertical_clipping),
+            setting_name(&self.shifting),
+            setting_name(&self.jumping),
+            setting_name(&self.memory_timing),
+            setting_name(&self.key_ghosting)
+        )
+    }
+}
+
+impl Default for QuirkConfig {
+    fn default() -> Self {
+        QuirkConfig::new()
+    }
+}
+
+/// Returns the other setting of a quirk, as every quirk has exactly two.
+///
+/// # Parameters
+///
+/// * `quirk` - The current setting.
+#[cfg(feature = "std")]
+fn toggled<T: Named>(quirk: &T) -> T {
+    T::VARIANTS.iter().find(|variant| *variant != quirk).cloned().unwrap_or_else(|| quirk.clone())
+}
+
+/// Returns the name of a quirk setting as it is written on the command line (e.g. `no-wait`).
+///
+/// # Parameters
+///
+/// * `quirk` - The setting to name.
+#[cfg(feature = "std")]
+fn setting_name<T: Named>(quirk: &T) -> String {
+    quirk.name().to_string()
+}
+
+/// Returns the setting of a quirk with the provided name, as it is written on the command line (e.g. `no-wait`), regardless of case.
+///
+/// # Parameters
+///
+/// * `quirk` - The name of the quirk, for the error.
+/// * `setting` - The name of the setting.
+///
+/// # Errors
+///
+/// Returns an `Err` if the quirk has no such setting.
+#[cfg(feature = "std")]
+fn parse_setting<T: Named>(quirk: &str, setting: &str) -> Result<T, String> {
+    T::from_name(setting).ok_or_else(|| format!("Unknown setting \"{setting}\" for the {quirk} quirk."))
+}
+
+/// Tracks the state of the quirk menu, which toggles one quirk at a time.
+#[cfg(feature = "std")]
+#[derive(Debug, Default)]
+pub struct QuirkEditor {
+    selected: usize
+}
+
+#[cfg(feature = "std")]
+impl QuirkEditor {
+    /// Returns the index of the quirk currently selected, in the order of the [`QuirkConfig`](QuirkConfig) members.
+    #[must_use]
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Moves the selection to the next (or previous) quirk, wrapping around.
+    ///
+    /// # Parameters
+    ///
+    /// * `forward` - True to select the next quirk, false to select the previous one.
+    pub fn select(&mut self, forward: bool) {
+        self.selected = if forward { (self.selected + 1) % QUIRK_COUNT } else { (self.selected + QUIRK_COUNT - 1) % QUIRK_COUNT };
+    }
+
+    /// Switches the selected quirk to its other setting.
+    ///
+    /// # Parameters
+    ///
+    /// * `quirk_config` - The quirks to edit.
+    pub fn toggle(&self, quirk_config: &mut QuirkConfig) {
+        match self.selected {
+            0 => quirk_config.reset_vf = toggled(&quirk_config.reset_vf),
+            1 => quirk_config.memory = toggled(&quirk_config.memory),
+            2 => quirk_config.display_wait = toggled(&quirk_config.display_wait),
+            3 => quirk_config.horizontal_clipping = toggled(&quirk_config.horizontal_clipping),
+            4 => quirk_config.vertical_clipping = toggled(&quirk_config.vertical_clipping),
+            5 => quirk_config.shifting = toggled(&quirk_config.shifting),
+            6 => quirk_config.jumping = toggled(&quirk_config.jumping),
+            7 => quirk_config.memory_timing = toggled(&quirk_config.memory_timing),
+            _ => quirk_config.key_ghosting = toggled(&quirk_config.key_ghosting)
+        }
+    }
+
+    /// Returns the name of the selected quirk (e.g. `Display wait`).
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        QUIRK_NAMES[self.selected]
+    }
+
+    /// Returns the setting of the selected quirk as it is written on the command line (e.g. `no-wait`).
+    ///
+    /// # Parameters
+    ///
+    /// * `quirk_config` - The quirks being edited.
+    #[must_use]
+    pub fn setting(&self, quirk_config: &QuirkConfig) -> String {
+        match self.selected {
+            0 => setting_name(&quirk_config.reset_vf),
+            1 => setting_name(&quirk_config.memory),
+            2 => setting_name(&quirk_config.display_wait),
+            3 => setting_name(&quirk_config.horizontal_clipping),
+            4 => setting_name(&quirk_config.vertical_clipping),
+            5 => setting_name(&quirk_config.shifting),
+            6 => setting_name(&quirk_config.jumping),
+            7 => setting_name(&quirk_config.memory_timing),
+            _ => setting_name(&quirk_config.key_ghosting)
+        }
+    }
+
+    /// Returns a short description of the selected quirk, suitable for the window title.
+    ///
+    /// # Parameters
+    ///
+    /// * `quirk_config` - The quirks being edited.
+    #[must_use]
+    pub fn describe(&self, quirk_config: &QuirkConfig) -> String {
+        format!("Quirk: {} {}", self.name(), self.setting(quirk_config))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn editor_toggles() {
+        let mut editor = QuirkEditor::default();
+        let mut quirk_config = QuirkConfig::new();
+
+        editor.select(false);
+        assert_eq!(editor.selected(), QUIRK_COUNT - 1, "Selection did not wrap backwards.");
+        editor.toggle(&mut quirk_config);
+        assert_eq!(quirk_config.key_ghosting, KeyGhostingQuirk::Ghosting, "Quirk not toggled.");
+        assert_eq!(editor.describe(&quirk_config), "Quirk: Key ghosting ghosting", "Selection described incorrectly.");
+        editor.toggle(&mut quirk_config);
+        editor.select(false);
+        editor.toggle(&mut quirk_config);
+        assert_eq!(quirk_config.memory_timing, MemoryTimingQuirk::PerRegister, "Quirk not toggled.");
+        assert_eq!(editor.describe(&quirk_config), "Quirk: Memory timing per-register", "Selection described incorrectly.");
+        editor.toggle(&mut quirk_config);
+        editor.select(false);
+        editor.toggle(&mut quirk_config);
+        assert_eq!(quirk_config.jumping, JumpingQuirk::Vx, "Quirk not toggled.");
+        assert_eq!(editor.describe(&quirk_config), "Quirk: Jumping vx", "Selection described incorrectly.");
+        editor.toggle(&mut quirk_config);
+        assert_eq!(quirk_config, QuirkConfig::new(), "Quirk not toggled back.");
+
+        editor.select(true);
+        editor.select(true);
+        editor.select(true);
+        editor.select(true);
+        editor.select(true);
+        editor.toggle(&mut quirk_config);
+        assert_eq!(quirk_config.display_wait, DisplayWaitQuirk::NoWait, "Selected quirk not toggled.");
+        assert_eq!(editor.describe(&quirk_config), "Quirk: Display wait no-wait", "Setting not named as on the command line.");
+    }
+
+    #[test]
+    fn apply_settings() {
+        let schip = Platform::Schip.quirk_config();
+        let mut quirk_config = QuirkConfig::new();
+        quirk_config.apply_settings(&schip.to_string()).unwrap();
+        assert_eq!(quirk_config, schip, "Displayed settings not applied.");
+
+        quirk_config.apply_settings("clipping=wrap  shifting=VY").unwrap();
+        assert_eq!((quirk_config.horizontal_clipping.clone(), quirk_config.vertical_clipping.clone()), (ClippingQuirk::Wrap, ClippingQuirk::Wrap), "Clipping not applied to both axes.");
+        assert_eq!(quirk_config.shifting, ShiftingQuirk::Vy, "Setting not matched regardless of case.");
+
+        assert!(quirk_config.apply_settings("shifting").is_err(), "Setting without an = applied.");
+        assert!(quirk_config.apply_settings("turbo=on").is_err(), "Unknown quirk applied.");
+        assert!(quirk_config.apply_settings("jumping=vy").is_err(), "Unknown setting applied.");
+    }
+}
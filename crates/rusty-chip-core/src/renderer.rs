@@ -0,0 +1,369 @@
+//! A module to draw the display of an interpreter to its [display backend](crate::backend::Display), in the colours of a [palette](Palette) and with the overlays placed by an [overlay layout](OverlayLayout) drawn over it.  
+//! The interpreter only holds the [framebuffer](crate::framebuffer), so everything about how it is shown (the colours, the overlays, and the rectangles reused between frames) is kept here by the frontend, usually through the [emulator](crate::emulator).
+
+#[cfg(not(feature = "std"))]
+use crate::alloc_prelude::*;
+use crate::backend::Rect;
+use crate::frame_log::{self, Subsystem};
+use crate::framebuffer::Framebuffer;
+use crate::input_display::{self, InputTimeline};
+use crate::interpreter::{Interpreter, SCALED_HEIGHT, SCALED_WIDTH};
+use crate::layout::{Overlay, OverlayLayout};
+use crate::palette::{self, Colour, Palette};
+
+/// The number of colours pixels can be drawn in: one for each combination of the XO-CHIP planes they are lit in.
+const PLANE_COLOUR_COUNT: usize = 3;
+
+/// The colours outlining pixels lit only on the display (red) and only on the [compared frame](Renderer::set_compared_frame) (blue).
+const DIFFERENCE_COLOURS: [Colour; 2] = [Colour::rgb(0xFF, 0x30, 0x30), Colour::rgb(0x30, 0x80, 0xFF)];
+
+/// The colours of the [input display](input_display)'s strip, held keys, and pressed keys.
+const INPUT_TIMELINE_COLOURS: [Colour; 3] = [Colour::rgb(0x20, 0x20, 0x20), Colour::rgb(0x80, 0x80, 0x80), Colour::rgb(0xFF, 0xD0, 0x20)];
+
+/// Stores how the display is drawn: its colours, where the overlays go, and the rectangles filled each frame.
+#[derive(Debug, Clone, Default)]
+pub struct Renderer {
+    palette: Palette,
+    overlay_layout: OverlayLayout,
+    pixel_rects: [Vec<Rect>; PLANE_COLOUR_COUNT],
+    compared_pixels: Option<Vec<bool>>,
+    difference_rects: [Vec<Rect>; 2],
+    input_timeline_rects: Option<[Vec<Rect>; 2]>
+}
+
+impl Renderer {
+    /// Returns a renderer with the default palette and overlay layout, and nothing drawn over the display.
+    #[must_use]
+    pub fn new() -> Renderer {
+        Renderer::default()
+    }
+
+    /// Returns the colours used to paint the display.
+    #[must_use]
+    pub fn palette(&self) -> &Palette {
+        &self.palette
+    }
+
+    /// Sets the colours used to paint the display, taking effect the next time it is [presented](Renderer::present).
+    ///
+    /// # Parameters
+    ///
+    /// * `palette` - The new colours.
+    pub fn set_palette(&mut self, palette: Palette) {
+        self.palette = palette;
+    }
+
+    /// Returns where the overlays are drawn over the display.
+    #[must_use]
+    pub fn overlay_layout(&self) -> &OverlayLayout {
+        &self.overlay_layout
+    }
+
+    /// Sets where the overlays are drawn over the display, taking effect on the next frame.
+    ///
+    /// # Parameters
+    ///
+    /// * `overlay_layout` - The new layout.
+    pub fn set_overlay_layout(&mut self, overlay_layout: OverlayLayout) {
+        self.overlay_layout = overlay_layout;
+    }
+
+    /// Sets the display of another interpreter to compare against, highlighting the pixels which differ from this one when the display is drawn (e.g. to compare quirk settings).  
+    /// Pixels lit only on this display are outlined in red, while pixels lit only on the other display are outlined in blue. Nothing is highlighted if the displays are at different resolutions.
+    ///
+    /// # Parameters
+    ///
+    /// * `framebuffer` - The display being drawn.
+    /// * `other` - The display to compare against, or `None` to stop comparing.
+    pub fn set_compared_frame(&mut self, framebuffer: Framebuffer<'_>, other: Option<Framebuffer<'_>>) {
+        let Some(other) = other.filter(|other| (other.width(), other.height()) == (framebuffer.width(), framebuffer.height())) else {
+            self.compared_pixels = None;
+            return;
+        };
+
+        let compared_pixels = self.compared_pixels.get_or_insert_with(Vec::new);
+        compared_pixels.clear();
+        compared_pixels.extend(other.colour_indices().map(|index| index != palette::BACKGROUND));
+    }
+
+    /// Sets the keypad timeline to draw over the display as the [input display](input_display), placed by the [overlay layout](Renderer::set_overlay_layout) (along the bottom by default).
+    ///
+    /// # Parameters
+    ///
+    /// * `input_timeline` - The timeline to draw, or `None` to hide the input display.
+    pub fn set_input_timeline(&mut self, input_timeline: Option<&InputTimeline>) {
+        let Some(input_timeline) = input_timeline else {
+            self.input_timeline_rects = None;
+            return;
+        };
+
+        let placement = self.overlay_layout.placement(Overlay::InputDisplay);
+        input_timeline.fill_rects(self.input_timeline_rects.get_or_insert_with(|| [Vec::new(), Vec::new()]), &placement);
+    }
+
+    /// Draws the interpreter's display to its display backend, doing nothing if it has none.
+    ///
+    /// # Parameters
+    ///
+    /// * `interpreter` - The interpreter whose display is drawn.
+    pub fn present(&mut self, interpreter: &mut Interpreter) {
+        if interpreter.display_mut().is_none() {
+            return;
+        }
+        self.update_pixel_rects(interpreter.framebuffer());
+        let Some(display) = interpreter.display_mut() else {
+            return;
+        };
+
+        display.clear(self.palette.colours[palette::BACKGROUND]);
+        for (i, pixel_rects) in self.pixel_rects.iter().enumerate() {
+            if !pixel_rects.is_empty() {
+                draw_result(display.fill_rects(self.palette.colours[palette::FIRST_PLANE + i], u8::MAX, pixel_rects));
+            }
+        }
+
+        for (colour, difference_rects) in DIFFERENCE_COLOURS.iter().zip(&self.difference_rects) {
+            if !difference_rects.is_empty() {
+                draw_result(display.draw_rects(*colour, difference_rects));
+            }
+        }
+
+        if let Some(input_timeline_rects) = &self.input_timeline_rects {
+            // The strip is blended over the game with the opacity of its placement
+            let placement = self.overlay_layout.placement(Overlay::InputDisplay);
+            let alpha = placement.alpha();
+            draw_result(display.fill_rects(INPUT_TIMELINE_COLOURS[0], alpha, &[input_display::strip_rect(&placement)]));
+            for (colour, rects) in INPUT_TIMELINE_COLOURS[1..].iter().zip(input_timeline_rects) {
+                draw_result(display.fill_rects(*colour, alpha, rects));
+            }
+        }
+
+        display.present();
+    }
+
+    /// Refills the pixel rectangles with the lit pixels of the display, scaled to the window.  
+    /// Pixels are grouped by the XO-CHIP planes they are set in (the first, the second, or both), as each combination has its own colour.  
+    /// The window is the same size in both resolutions, so high resolution pixels are drawn at half the scale.  
+    /// The taller [two page display](Interpreter::is_two_page_display) is drawn at half the scale as well, centred horizontally.  
+    /// The rectangles are kept between frames so that no allocations are made once the buffer has grown to fit.  
+    /// If a [compared frame](Renderer::set_compared_frame) is set, the pixels which are lit on only one of the displays are collected as well.
+    ///
+    /// # Parameters
+    ///
+    /// * `framebuffer` - The display to draw.
+    fn update_pixel_rects(&mut self, framebuffer: Framebuffer<'_>) {
+        let width = framebuffer.width();
+        let (scale, x_offset) = scale_and_offset(&framebuffer);
+        self.pixel_rects.iter_mut().for_each(Vec::clear);
+        self.difference_rects.iter_mut().for_each(Vec::clear);
+        // A comparison made at another resolution no longer lines up with the display
+        let compared_pixels = self.compared_pixels.as_deref().filter(|compared_pixels| compared_pixels.len() == (width * framebuffer.height()) as usize);
+        for (i, planes) in framebuffer.colour_indices().enumerate() {
+            let is_compared_lit = compared_pixels.map(|compared_pixels| compared_pixels[i]);
+            if planes == palette::BACKGROUND && is_compared_lit != Some(true) {
+                continue;
+            }
+
+            #[allow(clippy::cast_possible_truncation)]
+            let x = x_offset + (i as u32 % width) * scale;
+            #[allow(clippy::cast_possible_truncation)]
+            let y = (i as u32 / width) * scale;
+            #[allow(clippy::cast_possible_wrap)]
+            let rect = Rect::new(x as i32, y as i32, scale, scale);
+            if planes != palette::BACKGROUND {
+                self.pixel_rects[planes - 1].push(rect);
+            }
+            if is_compared_lit.is_some_and(|is_compared_lit| is_compared_lit != (planes != palette::BACKGROUND)) {
+                self.difference_rects[usize::from(planes == palette::BACKGROUND)].push(rect);
+            }
+        }
+    }
+}
+
+/// Returns the pixel of the display under a point in the window, or `None` if the point is outside the display (e.g. beside the taller [two page display](Interpreter::is_two_page_display)).
+///
+/// # Parameters
+///
+/// * `framebuffer` - The display drawn in the window.
+/// * `x` - The X coordinate of the point in the window.
+/// * `y` - The Y coordinate of the point in the window.
+#[must_use]
+pub fn display_position(framebuffer: &Framebuffer<'_>, x: i32, y: i32) -> Option<(u32, u32)> {
+    let (scale, x_offset) = scale_and_offset(framebuffer);
+    let x = u32::try_from(x).ok()?.checked_sub(x_offset)? / scale;
+    let y = u32::try_from(y).ok()? / scale;
+
+    (x < framebuffer.width() && y < framebuffer.height()).then_some((x, y))
+}
+
+/// Returns the number of window pixels along each side of a display pixel, and the gap left of the display which centres it in the window.
+///
+/// # Parameters
+///
+/// * `framebuffer` - The display drawn in the window.
+fn scale_and_offset(framebuffer: &Framebuffer<'_>) -> (u32, u32) {
+    let scale = (SCALED_WIDTH / framebuffer.width()).min(SCALED_HEIGHT / framebuffer.height());
+
+    (scale, (SCALED_WIDTH - framebuffer.width() * scale) / 2)
+}
+
+/// Logs an error from drawing to the display backend, carrying on with the rest of the frame.
+///
+/// # Parameters
+///
+/// * `result` - The result of the draw.
+fn draw_result(result: Result<(), String>) {
+    if let Err(e) = result {
+        frame_log::warn(Subsystem::Draw, format_args!("Error drawing: {e}"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::palette::PalettePreset;
+    use crate::quirks::{DisplayWaitQuirk, QuirkConfig};
+    use crate::testing::{DisplayCall, MockDisplay};
+
+    /// The number of window pixels along each side of a display pixel at the usual resolution.
+    const SCALE: u32 = SCALED_WIDTH / 64;
+
+    /// Returns an interpreter which has run every instruction of the game once, with the display wait quirk off so every draw lands straight away.
+    ///
+    /// # Parameters
+    ///
+    /// * `game` - The instructions to run.
+    /// * `cycles` - The number of instructions to run.
+    fn run_game(game: &[u8], cycles: usize) -> Interpreter {
+        let mut quirk_config = QuirkConfig::new();
+        quirk_config.display_wait = DisplayWaitQuirk::NoWait;
+        let mut interpreter = Interpreter::new_headless(quirk_config);
+        interpreter.load_game(game);
+        for _ in 0..cycles {
+            interpreter.handle_cycle();
+        }
+
+        interpreter
+    }
+
+    /// Returns the rectangle of the pixel in the top row.
+    ///
+    /// # Parameters
+    ///
+    /// * `x` - The column of the pixel.
+    fn pixel(x: u32) -> Rect {
+        #[allow(clippy::cast_possible_wrap)]
+        Rect::new((x * SCALE) as i32, 0, SCALE, SCALE)
+    }
+
+    #[test]
+    fn present() {
+        // Draws a single pixel at (1, 0)
+        let mut interpreter = run_game(&[0x60, 0x01, 0xA2, 0x06, 0xD0, 0x11, 0x80], 0);
+        let display = MockDisplay::new();
+        interpreter.attach_backends(Some(Box::new(display.clone())), None);
+        let mut renderer = Renderer::new();
+
+        renderer.present(&mut interpreter);
+        assert_eq!(display.last_frame(), [DisplayCall::Clear(renderer.palette().colours[palette::BACKGROUND])], "Blank display not presented as the background.");
+
+        for _ in 0..3 {
+            interpreter.handle_cycle();
+        }
+        renderer.set_palette(PalettePreset::Amber.palette());
+        renderer.present(&mut interpreter);
+        assert_eq!(display.present_count(), 2, "Frame not presented.");
+        assert_eq!(display.last_frame()[0], DisplayCall::Clear(PalettePreset::Amber.palette().colours[palette::BACKGROUND]), "Palette not used.");
+        assert_eq!(display.filled_rects(renderer.palette().colours[palette::FIRST_PLANE]), [pixel(1)], "Pixel not presented.");
+
+        let mut headless = run_game(&[], 0);
+        renderer.present(&mut headless);
+        assert_eq!(display.present_count(), 2, "Interpreter without a display presented.");
+    }
+
+    #[test]
+    fn draw_planes() {
+        // Draws a pixel at (0, 0) on the first plane, (1, 0) on the second, and (2, 0) on both
+        let game = [0xA2, 0x10, 0xD0, 0x11, 0x60, 0x01, 0xF2, 0x01, 0xD0, 0x11, 0x60, 0x02, 0xF3, 0x01, 0xD0, 0x11, 0x80, 0x80];
+        let mut interpreter = run_game(&game, 0);
+        let display = MockDisplay::new();
+        interpreter.attach_backends(Some(Box::new(display.clone())), None);
+        for _ in 0..8 {
+            interpreter.handle_cycle();
+        }
+        let mut renderer = Renderer::new();
+
+        renderer.present(&mut interpreter);
+        assert_eq!(renderer.pixel_rects, [vec![pixel(0)], vec![pixel(1)], vec![pixel(2)]], "Pixels not grouped by plane.");
+        let colours = renderer.palette().colours;
+        assert_eq!(display.filled_rects(colours[palette::FIRST_PLANE]), [pixel(0)], "First plane not presented in its colour.");
+        assert_eq!(display.filled_rects(colours[palette::FIRST_PLANE + 1]), [pixel(1)], "Second plane not presented in its colour.");
+        assert_eq!(display.filled_rects(colours[palette::FIRST_PLANE + 2]), [pixel(2)], "Both planes not presented in their colour.");
+
+        let interpreter = run_game(&game, 0);
+        renderer.update_pixel_rects(interpreter.framebuffer());
+        assert!(renderer.pixel_rects.iter().all(Vec::is_empty), "Stale pixels kept between frames.");
+    }
+
+    #[test]
+    fn compared_frame_differences() {
+        // Draws pixels at (0, 0) and (1, 0), against pixels at (1, 0) and (2, 0)
+        let interpreter = run_game(&[0xA2, 0x08, 0xD0, 0x11, 0x60, 0x01, 0xD0, 0x11, 0x80], 4);
+        let other = run_game(&[0x60, 0x01, 0xA2, 0x0A, 0xD0, 0x11, 0x60, 0x02, 0xD0, 0x11, 0x80], 5);
+        let mut renderer = Renderer::new();
+
+        renderer.set_compared_frame(interpreter.framebuffer(), Some(other.framebuffer()));
+        renderer.update_pixel_rects(interpreter.framebuffer());
+        assert_eq!(renderer.pixel_rects[0], [pixel(0), pixel(1)], "Compared pixels drawn.");
+        assert_eq!(renderer.difference_rects, [vec![pixel(0)], vec![pixel(2)]], "Differences not highlighted.");
+
+        // Switches to high resolution
+        let other = run_game(&[0x00, 0xFF], 1);
+        renderer.set_compared_frame(interpreter.framebuffer(), Some(other.framebuffer()));
+        renderer.update_pixel_rects(interpreter.framebuffer());
+        assert!(renderer.difference_rects.iter().all(Vec::is_empty), "Displays at different resolutions compared.");
+
+        renderer.set_compared_frame(interpreter.framebuffer(), None);
+        assert!(renderer.compared_pixels.is_none(), "Comparison not stopped.");
+    }
+
+    #[test]
+    fn window_positions() {
+        let interpreter = run_game(&[], 0);
+        #[allow(clippy::cast_possible_wrap)]
+        let scale = SCALE as i32;
+        assert_eq!(display_position(&interpreter.framebuffer(), scale + 1, scale * 2), Some((1, 2)), "Point not mapped onto its pixel.");
+        assert_eq!(display_position(&interpreter.framebuffer(), -1, 0), None, "Point outside the window mapped.");
+
+        let interpreter = run_game(&[0x00, 0xFF], 1);
+        assert_eq!(display_position(&interpreter.framebuffer(), scale + 1, 0), Some((2, 0)), "High resolution pixels not drawn at half the scale.");
+    }
+
+    #[cfg(feature = "alloc-tracking")]
+    #[test]
+    fn steady_state_frames_do_not_allocate() {
+        // Repeatedly draws the "0" sprite at the origin
+        let mut interpreter = run_game(&[0xA0, 0x00, 0xD0, 0x15, 0x12, 0x00], 0);
+        let mut renderer = Renderer::new();
+        let mut run_frame = |interpreter: &mut Interpreter| {
+            for _ in 0..10 {
+                interpreter.handle_cycle();
+            }
+
+            interpreter.handle_frame();
+            renderer.update_pixel_rects(interpreter.framebuffer());
+        };
+
+        // Let the buffers settle before measuring
+        for _ in 0..3 {
+            run_frame(&mut interpreter);
+        }
+
+        let ((), allocations) = crate::alloc_tracker::count_allocations(|| {
+            for _ in 0..60 {
+                run_frame(&mut interpreter);
+            }
+        });
+        assert_eq!(allocations, 0, "Steady-state frames allocated on the heap.");
+    }
+}
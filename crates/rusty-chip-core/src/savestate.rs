@@ -0,0 +1,591 @@
+//! A module to save [snapshots](Snapshot) of a game to disk, so that progress survives the emulator being closed.  
+//! States are written in a small binary format: a magic number and format version, followed by the emulated hardware in little-endian order.  
+//! States from older versions are still loaded, with the hardware added since then left at its defaults, so saves survive emulator upgrades.  
+//! The emulator autosaves a game alongside it when the system suspends or shuts down, and offers to resume from it the next time the game is loaded.  
+//! The SUPER-CHIP RPL flags, which games use to save progress themselves, are kept alongside the game as well.  
+//! States saved by the user go in numbered slots under [`SAVE_SLOTS_DIR`](SAVE_SLOTS_DIR), in a directory per game named after its hash, so that they follow the game when it is moved or renamed.
+
+use std::io::{self, ErrorKind};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::audio::{DEFAULT_PITCH, PATTERN_LENGTH};
+use crate::database;
+use crate::framebuffer::PlaneBuffer;
+use crate::storage::StorageBackend;
+use crate::interpreter::{Snapshot, DRAWING_BUFFER_SIZE, FIRST_PLANE_MASK, LARGE_HEXADECIMAL_DIGIT_SPRITES, LARGE_HEXADECIMAL_DIGIT_SPRITES_ADDRESS, RAM_SIZE, REGISTERS_SIZE, RPL_FLAGS_SIZE, SCREEN_HEIGHT, SCREEN_WIDTH, STACK_SIZE};
+
+/// The bytes every save state starts with.
+const SAVE_STATE_MAGIC: &[u8; 4] = b"RCSS";
+
+/// The version of the format written by [`encode`](encode).
+const SAVE_STATE_VERSION: u8 = 6;
+
+/// The first version storing the high resolution mode, with room for a 128x64 display.
+const HIGH_RESOLUTION_VERSION: u8 = 2;
+
+/// The first version storing 64K of memory and the selected XO-CHIP planes along with the second plane.
+const XO_CHIP_VERSION: u8 = 3;
+
+/// The first version storing the XO-CHIP audio pattern and pitch.
+const AUDIO_PATTERN_VERSION: u8 = 4;
+
+/// The last version which could have been saved before the SUPER-CHIP large font was added to memory, so it is put back when loading them.
+const LAST_PRE_LARGE_FONT_VERSION: u8 = AUDIO_PATTERN_VERSION;
+
+/// The first version storing the cycles left of an opcode slowed by the memory timing quirk.
+const MEMORY_TIMING_VERSION: u8 = 5;
+
+/// The first version storing the HiRes CHIP-8 two page display alongside the high resolution mode.
+const TWO_PAGE_DISPLAY_VERSION: u8 = 6;
+
+/// The bit of the resolution byte set in the high resolution mode.
+const HIGH_RESOLUTION_BIT: u8 = 0b01;
+
+/// The bit of the resolution byte set on the two page display.
+const TWO_PAGE_DISPLAY_BIT: u8 = 0b10;
+
+/// The size of the memory stored by versions before [`XO_CHIP_VERSION`](XO_CHIP_VERSION).
+const LEGACY_RAM_SIZE: usize = 4096;
+
+/// The number of pixels stored by versions before [`HIGH_RESOLUTION_VERSION`](HIGH_RESOLUTION_VERSION).
+const LEGACY_PIXEL_COUNT: usize = (SCREEN_WIDTH * SCREEN_HEIGHT) as usize;
+
+/// The extension added to the game file for its autosave.
+const AUTOSAVE_FILE_EXTENSION: &str = "autosave";
+
+/// The extension added to the game file for its RPL flags.
+const RPL_FLAGS_FILE_EXTENSION: &str = "flags";
+
+/// The directory the save slots of every game are kept under, relative to the working directory.
+pub const SAVE_SLOTS_DIR: &str = "saves";
+
+/// The number of save slots each game has, numbered from 1.
+pub const SAVE_SLOT_COUNT: u8 = 9;
+
+/// The extension of the files holding the save slots.
+const SAVE_STATE_FILE_EXTENSION: &str = "rcss";
+
+/// Returns the snapshot in the save state format.  
+/// Games are saved while running, so a halt is not stored. The random numbers are stored as a seed drawn from the snapshot's generator, so a restored game is reproducible but does not draw the exact numbers the original would have.
+///
+/// # Parameters
+///
+/// * `snapshot` - The snapshot to save.
+#[must_use]
+pub fn encode(snapshot: &Snapshot) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(RAM_SIZE + 512);
+    bytes.extend_from_slice(SAVE_STATE_MAGIC);
+    bytes.push(SAVE_STATE_VERSION);
+    bytes.extend_from_slice(&snapshot.ram);
+    bytes.extend_from_slice(&snapshot.registers);
+    bytes.extend_from_slice(&snapshot.register_i.to_le_bytes());
+    bytes.push(snapshot.delay_timer);
+    bytes.push(snapshot.sound_timer);
+    bytes.extend_from_slice(&snapshot.program_counter.to_le_bytes());
+    bytes.push(u8::try_from(snapshot.stack_pointer).unwrap_or(u8::MAX));
+    for address in snapshot.stack {
+        bytes.extend_from_slice(&address.to_le_bytes());
+    }
+    bytes.push(u8::from(snapshot.should_wait_for_key));
+    bytes.push(u8::try_from(snapshot.wait_for_key_register).unwrap_or(u8::MAX));
+    bytes.push(u8::from(snapshot.should_wait_for_display_refresh));
+    let (x, y, height) = snapshot.wait_for_display_refresh_data;
+    bytes.push(u8::try_from(x).unwrap_or(u8::MAX));
+    bytes.push(u8::try_from(y).unwrap_or(u8::MAX));
+    bytes.push(height);
+    bytes.push(u8::try_from(snapshot.busy_cycles).unwrap_or(u8::MAX));
+    let mut resolution = 0;
+    if snapshot.is_high_resolution {
+        resolution |= HIGH_RESOLUTION_BIT;
+    }
+    if snapshot.is_two_page_display {
+        resolution |= TWO_PAGE_DISPLAY_BIT;
+    }
+    bytes.push(resolution);
+    bytes.push(snapshot.selected_planes);
+    pack_pixels(&mut bytes, &snapshot.drawing_buffer);
+    pack_pixels(&mut bytes, &snapshot.second_plane_buffer);
+    bytes.push(u8::from(snapshot.audio_pattern.is_some()));
+    bytes.extend_from_slice(&snapshot.audio_pattern.unwrap_or_default());
+    bytes.push(snapshot.pitch);
+    bytes.extend_from_slice(&snapshot.rng.clone().gen::<u64>().to_le_bytes());
+
+    bytes
+}
+
+/// Writes the pixels of a drawing buffer to the save state, packed 8 to a byte with the first pixel in the lowest bit.
+///
+/// # Parameters
+///
+/// * `bytes` - The save state to write to.
+/// * `buffer` - The drawing buffer to write.
+fn pack_pixels(bytes: &mut Vec<u8>, buffer: &PlaneBuffer) {
+    for start in (0..DRAWING_BUFFER_SIZE).step_by(8) {
+        bytes.push((0..8).fold(0, |byte, i| byte | (u8::from(buffer[start + i]) << i)));
+    }
+}
+
+/// Reads the fields of a save state in order.
+struct Reader<'a> {
+    bytes: &'a [u8]
+}
+
+impl<'a> Reader<'a> {
+    /// Returns the next bytes of the save state.
+    ///
+    /// # Parameters
+    ///
+    /// * `length` - The number of bytes to read.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the save state ends early.
+    fn take(&mut self, length: usize) -> Result<&'a [u8], String> {
+        if self.bytes.len() < length {
+            return Err(String::from("Save state is truncated."));
+        }
+
+        let (taken, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    /// Returns the next byte of the save state.
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    /// Returns the next two bytes of the save state as a little-endian `u16`.
+    fn u16(&mut self) -> Result<u16, String> {
+        Ok(u16::from_le_bytes([self.u8()?, self.u8()?]))
+    }
+
+    /// Returns the next drawing buffer of the save state, as written by [`pack_pixels`](pack_pixels).  
+    /// Pixels beyond those stored are left off.
+    ///
+    /// # Parameters
+    ///
+    /// * `count` - The number of pixels stored.
+    fn pixels(&mut self, count: usize) -> Result<PlaneBuffer, String> {
+        let mut buffer = PlaneBuffer::new();
+        for (start, byte) in (0..count).step_by(8).zip(self.take(count / 8)?) {
+            for i in 0..8 {
+                buffer.set(start + i, byte & (1 << i) != 0);
+            }
+        }
+
+        Ok(buffer)
+    }
+
+    /// Returns the next eight bytes of the save state as a little-endian `u64`.
+    fn u64(&mut self) -> Result<u64, String> {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(self.take(8)?);
+        Ok(u64::from_le_bytes(bytes))
+    }
+}
+
+/// Returns the snapshot stored in the save state.
+///
+/// # Parameters
+///
+/// * `bytes` - The save state, as written by [`encode`](encode).
+///
+/// # Errors
+///
+/// Returns an `Err` if the bytes are not a save state, are from an unsupported (newer) version, or hold an impossible state.
+pub fn decode(bytes: &[u8]) -> Result<Snapshot, String> {
+    let mut reader = Reader { bytes };
+    if reader.take(SAVE_STATE_MAGIC.len()).ok() != Some(SAVE_STATE_MAGIC.as_slice()) {
+        return Err(String::from("Not a RustyChip save state."));
+    }
+    let version = reader.u8()?;
+    if version == 0 || version > SAVE_STATE_VERSION {
+        return Err(format!("Unsupported save state version {version}."));
+    }
+
+    let ram_size = if version >= XO_CHIP_VERSION { RAM_SIZE } else { LEGACY_RAM_SIZE };
+    let mut ram = [0; RAM_SIZE];
+    ram[..ram_size].copy_from_slice(reader.take(ram_size)?);
+    if version <= LAST_PRE_LARGE_FONT_VERSION {
+        ram[LARGE_HEXADECIMAL_DIGIT_SPRITES_ADDRESS..LARGE_HEXADECIMAL_DIGIT_SPRITES_ADDRESS + LARGE_HEXADECIMAL_DIGIT_SPRITES.len()].copy_from_slice(&LARGE_HEXADECIMAL_DIGIT_SPRITES);
+    }
+    let mut registers = [0; REGISTERS_SIZE];
+    registers.copy_from_slice(reader.take(REGISTERS_SIZE)?);
+    let register_i = reader.u16()?;
+    let delay_timer = reader.u8()?;
+    let sound_timer = reader.u8()?;
+    let program_counter = reader.u16()?;
+    let stack_pointer = usize::from(reader.u8()?);
+    let mut stack = [0; STACK_SIZE];
+    for address in &mut stack {
+        *address = reader.u16()?;
+    }
+    let should_wait_for_key = reader.u8()? != 0;
+    let wait_for_key_register = usize::from(reader.u8()?);
+    let should_wait_for_display_refresh = reader.u8()? != 0;
+    let wait_for_display_refresh_data = (usize::from(reader.u8()?), usize::from(reader.u8()?), reader.u8()?);
+    let busy_cycles = if version >= MEMORY_TIMING_VERSION { u32::from(reader.u8()?) } else { 0 };
+    let resolution = if version >= HIGH_RESOLUTION_VERSION { reader.u8()? } else { 0 };
+    let is_high_resolution = resolution & HIGH_RESOLUTION_BIT != 0;
+    let is_two_page_display = version >= TWO_PAGE_DISPLAY_VERSION && resolution & TWO_PAGE_DISPLAY_BIT != 0;
+    let selected_planes = if version >= XO_CHIP_VERSION { reader.u8()? } else { FIRST_PLANE_MASK };
+    let drawing_buffer = reader.pixels(if version >= HIGH_RESOLUTION_VERSION { DRAWING_BUFFER_SIZE } else { LEGACY_PIXEL_COUNT })?;
+    let second_plane_buffer = reader.pixels(if version >= XO_CHIP_VERSION { DRAWING_BUFFER_SIZE } else { 0 })?;
+    let (audio_pattern, pitch) = if version >= AUDIO_PATTERN_VERSION {
+        let has_audio_pattern = reader.u8()? != 0;
+        let mut audio_pattern = [0; PATTERN_LENGTH];
+        audio_pattern.copy_from_slice(reader.take(PATTERN_LENGTH)?);
+        (has_audio_pattern.then_some(audio_pattern), reader.u8()?)
+    } else {
+        (None, DEFAULT_PITCH)
+    };
+    let rng = StdRng::seed_from_u64(reader.u64()?);
+
+    if !reader.bytes.is_empty() {
+        return Err(String::from("Save state has trailing data."));
+    }
+    let (draw_x_register, draw_y_register, _) = wait_for_display_refresh_data;
+    if stack_pointer > STACK_SIZE || wait_for_key_register >= REGISTERS_SIZE || draw_x_register >= REGISTERS_SIZE || draw_y_register >= REGISTERS_SIZE || selected_planes > 0b11 {
+        return Err(String::from("Save state holds an impossible state."));
+    }
+
+    Ok(Snapshot {
+        halt_reason: None,
+        ram,
+        registers,
+        register_i,
+        delay_timer,
+        sound_timer,
+        program_counter,
+        stack_pointer,
+        stack,
+        should_wait_for_key,
+        wait_for_key_register,
+        should_wait_for_display_refresh,
+        wait_for_display_refresh_data,
+        busy_cycles,
+        drawing_buffer,
+        second_plane_buffer,
+        selected_planes,
+        is_high_resolution,
+        is_two_page_display,
+        audio_pattern,
+        pitch,
+        rng
+    })
+}
+
+/// Returns the path of the file storing the autosave of the provided game.
+///
+/// # Parameters
+///
+/// * `game_path` - The path to the game file.
+#[must_use]
+pub fn autosave_path(game_path: &str) -> String {
+    format!("{game_path}.{AUTOSAVE_FILE_EXTENSION}")
+}
+
+/// Saves the snapshot as the autosave of the provided game, replacing any earlier one.
+///
+/// # Parameters
+///
+/// * `storage` - Where to save the autosave.
+/// * `game_path` - The path to the game file.
+/// * `snapshot` - The state of the game.
+///
+/// # Errors
+///
+/// Returns an `Err` if the autosave cannot be written.
+pub fn save_autosave(storage: &mut dyn StorageBackend, game_path: &str, snapshot: &Snapshot) -> io::Result<()> {
+    storage.write(&autosave_path(game_path), &encode(snapshot))
+}
+
+/// Returns the autosave of the provided game, or `None` if it has none.
+///
+/// # Parameters
+///
+/// * `storage` - Where the autosave is saved.
+/// * `game_path` - The path to the game file.
+///
+/// # Errors
+///
+/// Returns an `Err` if the autosave exists but cannot be read or is malformed.
+pub fn load_autosave(storage: &dyn StorageBackend, game_path: &str) -> io::Result<Option<Snapshot>> {
+    load_state(storage, &autosave_path(game_path))
+}
+
+/// Deletes the autosave of the provided game, if it has one.
+///
+/// # Parameters
+///
+/// * `storage` - Where the autosave is saved.
+/// * `game_path` - The path to the game file.
+///
+/// # Errors
+///
+/// Returns an `Err` if the autosave exists but cannot be deleted.
+pub fn delete_autosave(storage: &mut dyn StorageBackend, game_path: &str) -> io::Result<()> {
+    storage.delete(&autosave_path(game_path))
+}
+
+/// Returns the path of the file storing a save slot of the provided game, e.g. `saves/<hash>/slot3.rcss`.
+///
+/// # Parameters
+///
+/// * `game_data` - The bytes of the game file, which the directory is named after the hash of.
+/// * `slot` - The number of the slot.
+#[must_use]
+pub fn slot_path(game_data: &[u8], slot: u8) -> String {
+    format!("{SAVE_SLOTS_DIR}/{}/slot{slot}.{SAVE_STATE_FILE_EXTENSION}", database::hash(game_data))
+}
+
+/// Saves the snapshot to a slot of the provided game, replacing whatever it held. Returns the path it was saved to.
+///
+/// # Parameters
+///
+/// * `storage` - Where to save the slot.
+/// * `game_data` - The bytes of the game file.
+/// * `slot` - The number of the slot.
+/// * `snapshot` - The state of the game.
+///
+/// # Errors
+///
+/// Returns an `Err` if the slot cannot be written.
+pub fn save_slot(storage: &mut dyn StorageBackend, game_data: &[u8], slot: u8, snapshot: &Snapshot) -> io::Result<String> {
+    let path = slot_path(game_data, slot);
+    storage.write(&path, &encode(snapshot))?;
+
+    Ok(path)
+}
+
+/// Returns the state saved in a slot of the provided game, or `None` if the slot is empty.
+///
+/// # Parameters
+///
+/// * `storage` - Where the slot is saved.
+/// * `game_data` - The bytes of the game file.
+/// * `slot` - The number of the slot.
+///
+/// # Errors
+///
+/// Returns an `Err` if the slot holds a state which cannot be read or is malformed.
+pub fn load_slot(storage: &dyn StorageBackend, game_data: &[u8], slot: u8) -> io::Result<Option<Snapshot>> {
+    load_state(storage, &slot_path(game_data, slot))
+}
+
+/// Returns the state named on the command line: the number of one of the game's save slots, or the path to a save state file.
+///
+/// # Parameters
+///
+/// * `storage` - Where the state is saved.
+/// * `game_data` - The bytes of the game file, whose slots are looked in.
+/// * `name` - The number of the slot or the path to the file.
+///
+/// # Errors
+///
+/// Returns an `Err` if there is no such state, or it cannot be read or is malformed.
+pub fn load_named_state(storage: &dyn StorageBackend, game_data: &[u8], name: &str) -> io::Result<Snapshot> {
+    let path = match name.parse::<u8>() {
+        Ok(slot) if (1..=SAVE_SLOT_COUNT).contains(&slot) => slot_path(game_data, slot),
+        _ => String::from(name)
+    };
+
+    load_state(storage, &path)?.ok_or_else(|| io::Error::new(ErrorKind::NotFound, format!("No save state at {path}.")))
+}
+
+/// Returns the state saved at the provided path, or `None` if nothing is saved there.
+///
+/// # Parameters
+///
+/// * `storage` - Where the state is saved.
+/// * `path` - The path of the state.
+///
+/// # Errors
+///
+/// Returns an `Err` if the state exists but cannot be read or is malformed.
+fn load_state(storage: &dyn StorageBackend, path: &str) -> io::Result<Option<Snapshot>> {
+    let Some(bytes) = storage.read(path)? else {
+        return Ok(None);
+    };
+
+    decode(&bytes).map(Some).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))
+}
+
+/// Saves the RPL flags of the provided game, replacing any earlier ones.
+///
+/// # Parameters
+///
+/// * `storage` - Where to save the flags.
+/// * `game_path` - The path to the game file.
+/// * `rpl_flags` - The flags to save.
+///
+/// # Errors
+///
+/// Returns an `Err` if the flags cannot be written.
+pub fn save_rpl_flags(storage: &mut dyn StorageBackend, game_path: &str, rpl_flags: &[u8; RPL_FLAGS_SIZE]) -> io::Result<()> {
+    storage.write(&format!("{game_path}.{RPL_FLAGS_FILE_EXTENSION}"), rpl_flags)
+}
+
+/// Returns the RPL flags saved for the provided game, or `None` if it has none.
+///
+/// # Parameters
+///
+/// * `storage` - Where the flags are saved.
+/// * `game_path` - The path to the game file.
+///
+/// # Errors
+///
+/// Returns an `Err` if the flags exist but cannot be read or are malformed.
+pub fn load_rpl_flags(storage: &dyn StorageBackend, game_path: &str) -> io::Result<Option<[u8; RPL_FLAGS_SIZE]>> {
+    let Some(bytes) = storage.read(&format!("{game_path}.{RPL_FLAGS_FILE_EXTENSION}"))? else {
+        return Ok(None);
+    };
+
+    bytes.try_into().map(Some).map_err(|_| io::Error::new(ErrorKind::InvalidData, "RPL flags file has the wrong length."))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::Interpreter;
+    use crate::storage::MemoryStorage;
+
+    /// Returns an interpreter part way through drawing a sprite and calling a subroutine.
+    fn played_interpreter() -> Interpreter {
+        let mut interpreter = Interpreter::new();
+        interpreter.load_game(&[0x63, 0x1F, 0xF3, 0x15, 0xD0, 0x05, 0x22, 0x0A, 0x12, 0x08, 0x12, 0x0A]);
+        for _ in 0..5 {
+            interpreter.handle_cycle();
+        }
+        interpreter.handle_frame();
+
+        interpreter
+    }
+
+    #[test]
+    fn encode_decode_round_trip() {
+        let interpreter = played_interpreter();
+        let snapshot = decode(&encode(&interpreter.snapshot())).unwrap();
+
+        let mut restored = Interpreter::new();
+        restored.restore(&snapshot);
+        assert_eq!(restored.ram(), interpreter.ram(), "RAM not restored.");
+        assert_eq!(restored.registers(), interpreter.registers(), "Registers not restored.");
+        assert_eq!(restored.program_counter(), interpreter.program_counter(), "Program counter not restored.");
+        assert_eq!(restored.delay_timer(), interpreter.delay_timer(), "Delay timer not restored.");
+        assert!(restored.display_buffer().eq(interpreter.display_buffer()), "Display not restored.");
+        assert_eq!(encode(&restored.snapshot()), encode(&snapshot), "State changed by a round trip.");
+    }
+
+    #[test]
+    fn decode_rejects_invalid() {
+        let bytes = encode(&played_interpreter().snapshot());
+
+        assert!(decode(b"PNG").is_err(), "Foreign file decoded.");
+        assert!(decode(&bytes[..bytes.len() - 1]).is_err(), "Truncated state decoded.");
+
+        let mut newer = bytes.clone();
+        newer[SAVE_STATE_MAGIC.len()] = SAVE_STATE_VERSION + 1;
+        assert!(decode(&newer).is_err(), "Unsupported version decoded.");
+
+        // The registers of the draw waiting for the display follow the wait flags, after the stack
+        let draw_registers = SAVE_STATE_MAGIC.len() + 1 + RAM_SIZE + REGISTERS_SIZE + 2 + 2 + 2 + 1 + STACK_SIZE * 2 + 3;
+        for offset in 0..2 {
+            let mut corrupt = bytes.clone();
+            corrupt[draw_registers - 1] = 1;
+            corrupt[draw_registers + offset] = 0x10;
+            assert!(decode(&corrupt).is_err(), "Draw waiting on a register past VF decoded.");
+        }
+    }
+
+    /// Draws the large hex digit 8 pointed at by `FX30` on a cleared display, as a game would after loading a save state.
+    ///
+    /// # Parameters
+    ///
+    /// * `interpreter` - The interpreter to draw with.
+    fn draw_large_digit(interpreter: &mut Interpreter) {
+        interpreter.write_ram(0x300, &[0x00, 0xE0, 0x60, 0x08, 0x61, 0x00, 0x62, 0x00, 0xF0, 0x30, 0xD1, 0x2A]).unwrap();
+        interpreter.set_program_counter(0x300);
+        for _ in 0..6 {
+            interpreter.handle_cycle();
+        }
+        interpreter.handle_frame();
+    }
+
+    #[test]
+    fn decode_older_versions() {
+        let interpreter = played_interpreter();
+        let mut drawn = played_interpreter();
+        draw_large_digit(&mut drawn);
+        assert!(drawn.display_buffer().any(|pixel| pixel), "Large digit not drawn.");
+        let fixtures: [&[u8]; 5] = [
+            include_bytes!("../tests/fixtures/savestate_v1.rcss"),
+            include_bytes!("../tests/fixtures/savestate_v2.rcss"),
+            include_bytes!("../tests/fixtures/savestate_v3.rcss"),
+            include_bytes!("../tests/fixtures/savestate_v4.rcss"),
+            include_bytes!("../tests/fixtures/savestate_v5.rcss")
+        ];
+        for (version, fixture) in (1..).zip(fixtures) {
+            assert_eq!(fixture[SAVE_STATE_MAGIC.len()], version, "Fixture has the wrong version.");
+            let snapshot = decode(fixture).unwrap_or_else(|e| panic!("Version {version} not migrated: {e}"));
+
+            let mut restored = Interpreter::new();
+            restored.restore(&snapshot);
+            assert_eq!(restored.ram()[..LEGACY_RAM_SIZE], interpreter.ram()[..LEGACY_RAM_SIZE], "RAM not migrated.");
+            assert_eq!(restored.registers(), interpreter.registers(), "Registers not migrated.");
+            assert_eq!(restored.program_counter(), interpreter.program_counter(), "Program counter not migrated.");
+            assert!(restored.display_buffer().eq(interpreter.display_buffer()), "Display not migrated.");
+            assert!(restored.second_plane_buffer().all(|pixel| !pixel), "Second plane not defaulted.");
+            assert!(decode(&encode(&snapshot)).is_ok(), "Migrated state not saved in the current version.");
+
+            draw_large_digit(&mut restored);
+            assert!(restored.display_buffer().eq(drawn.display_buffer()), "Large font not restored for FX30.");
+        }
+    }
+
+    #[test]
+    fn autosave_round_trip() {
+        let mut storage = MemoryStorage::new();
+        let game_path = "games/game.ch8";
+
+        assert!(load_autosave(&storage, game_path).unwrap().is_none(), "Autosave loaded for game without one.");
+        save_autosave(&mut storage, game_path, &played_interpreter().snapshot()).unwrap();
+        assert_eq!(storage.keys().collect::<Vec<_>>(), ["games/game.ch8.autosave"], "Autosave not saved alongside the game.");
+        assert!(load_autosave(&storage, game_path).unwrap().is_some(), "Autosave not loaded.");
+        delete_autosave(&mut storage, game_path).unwrap();
+        assert!(load_autosave(&storage, game_path).unwrap().is_none(), "Autosave not deleted.");
+        delete_autosave(&mut storage, game_path).unwrap();
+    }
+
+    #[test]
+    fn save_slots_round_trip() {
+        let mut storage = MemoryStorage::new();
+        let game_data = [0x12, 0x00];
+        let snapshot = played_interpreter().snapshot();
+
+        assert!(load_slot(&storage, &game_data, 3).unwrap().is_none(), "Empty slot loaded.");
+        let path = save_slot(&mut storage, &game_data, 3, &snapshot).unwrap();
+        assert_eq!(path, format!("saves/{}/slot3.rcss", database::hash(&game_data)), "Slot not saved under the game's hash.");
+        assert!(load_slot(&storage, &[0x12, 0x02], 3).unwrap().is_none(), "Slot loaded for another game.");
+        assert_eq!(load_slot(&storage, &game_data, 3).unwrap().map(|loaded| (loaded.registers, loaded.program_counter)), Some((snapshot.registers, snapshot.program_counter)), "Slot not loaded.");
+
+        assert!(load_named_state(&storage, &game_data, "3").is_ok(), "Slot not loaded by number.");
+        assert!(load_named_state(&storage, &game_data, &path).is_ok(), "State not loaded by path.");
+        assert_eq!(load_named_state(&storage, &game_data, "4").map_err(|e| e.kind()).err(), Some(ErrorKind::NotFound), "Empty slot loaded by number.");
+    }
+
+    #[test]
+    fn rpl_flags_round_trip() {
+        let mut storage = MemoryStorage::new();
+        let game_path = "games/game.ch8";
+
+        assert!(load_rpl_flags(&storage, game_path).unwrap().is_none(), "RPL flags loaded for game without any.");
+        save_rpl_flags(&mut storage, game_path, &[1, 2, 3, 4, 5, 6, 7, 8]).unwrap();
+        assert_eq!(load_rpl_flags(&storage, game_path).unwrap(), Some([1, 2, 3, 4, 5, 6, 7, 8]), "RPL flags not loaded.");
+        storage.write(&format!("{game_path}.{RPL_FLAGS_FILE_EXTENSION}"), &[1, 2]).unwrap();
+        assert!(load_rpl_flags(&storage, game_path).is_err(), "Malformed RPL flags loaded.");
+    }
+}
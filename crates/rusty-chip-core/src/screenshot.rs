@@ -0,0 +1,301 @@
+//! A module to save screenshots of the display as PNGs, taken automatically when chosen events happen so that automated runs can report what the game showed.
+//! The events are the game halting with an error, exiting, settling into a jump to itself (how most test games finish once their results are drawn), and finishing the [speedrun](crate::speedrun) splits.
+//! Each screenshot is named after the game, the frame, and the event, e.g. `pong-1234-exit.png`, and uses the game's [palette](crate::palette).
+
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "cli")]
+use clap::ValueEnum;
+
+use crate::emulator::Emulator;
+use crate::framebuffer::Framebuffer;
+use crate::interpreter::{HaltReason, Interpreter};
+use crate::names::{named, Named};
+use crate::palette::Palette;
+
+/// The directory screenshots are saved to by default, relative to the working directory.
+pub const DEFAULT_SCREENSHOT_DIR: &str = "screenshots";
+
+/// The number of PNG pixels along each side of a display pixel.
+const SCREENSHOT_SCALE: usize = 4;
+
+/// The bytes every PNG starts with.
+pub(crate) const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+/// The most bytes a single stored (uncompressed) deflate block can hold.
+const MAX_STORED_BLOCK_LENGTH: usize = u16::MAX as usize;
+
+/// Denotes an event which a screenshot can be taken for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+pub enum ScreenshotTrigger {
+    /// The game halted with an [error](HaltReason::is_error).
+    Error,
+    /// The game exited through the `0000` or SUPER-CHIP `00FD` opcode.
+    Exit,
+    /// The game started jumping to the same instruction forever, which is how most test games finish.
+    Spin,
+    /// The game met the last of its [speedrun splits](crate::speedrun).
+    Finish
+}
+
+named!(ScreenshotTrigger { Error => "error", Exit => "exit", Spin => "spin", Finish => "finish" });
+
+impl ScreenshotTrigger {
+    /// Returns the event of the game halting for the provided reason, or `None` if the user stopped it.
+    ///
+    /// # Parameters
+    ///
+    /// * `reason` - The reason the game halted.
+    #[must_use]
+    pub fn for_halt(reason: HaltReason) -> Option<ScreenshotTrigger> {
+        match reason {
+            HaltReason::UserStop => None,
+            HaltReason::Exit => Some(ScreenshotTrigger::Exit),
+            _ => Some(ScreenshotTrigger::Error)
+        }
+    }
+}
+
+impl Display for ScreenshotTrigger {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Stores which events screenshots are taken for and where they are saved.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AutoScreenshot {
+    triggers: Vec<ScreenshotTrigger>,
+    directory: PathBuf,
+    is_spinning: bool
+}
+
+impl AutoScreenshot {
+    /// Returns the settings for taking screenshots of the provided events.
+    ///
+    /// # Parameters
+    ///
+    /// * `triggers` - The events to take screenshots of, or none to not take any.
+    /// * `directory` - The directory the screenshots are saved to through the emulator's [storage backend](crate::storage), which creates it when the first is taken.
+    #[must_use]
+    pub fn new(triggers: Vec<ScreenshotTrigger>, directory: &str) -> AutoScreenshot {
+        AutoScreenshot { triggers, directory: PathBuf::from(directory), is_spinning: false }
+    }
+
+    /// Returns the events screenshots are taken of.
+    #[must_use]
+    pub fn triggers(&self) -> &[ScreenshotTrigger] {
+        &self.triggers
+    }
+
+    /// Returns whether the game has just started [spinning](is_spinning), so that a spin is only reported once.
+    ///
+    /// # Parameters
+    ///
+    /// * `interpreter` - The interpreter to check.
+    pub fn take_spin(&mut self, interpreter: &Interpreter) -> bool {
+        let was_spinning = self.is_spinning;
+        self.is_spinning = is_spinning(interpreter);
+
+        self.is_spinning && !was_spinning
+    }
+
+    /// Saves a screenshot of the display for the event through the emulator's [storage backend](Emulator::set_storage) if screenshots are taken of it, returning the path it was saved to.
+    /// Returns `None` if no screenshot is taken of the event.
+    ///
+    /// # Parameters
+    ///
+    /// * `trigger` - The event which happened.
+    /// * `game_path` - The path to the game, which the screenshot is named after.
+    /// * `emulator` - The emulator to capture, named by its current frame.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` in the option if the PNG cannot be stored.
+    pub fn capture(&self, trigger: ScreenshotTrigger, game_path: &str, emulator: &mut Emulator) -> Option<io::Result<PathBuf>> {
+        if !self.triggers.contains(&trigger) {
+            return None;
+        }
+
+        let path = self.directory.join(file_name(game_path, emulator.frame_count(), trigger));
+        let png = screenshot_png(emulator.interpreter().framebuffer(), emulator.renderer().palette());
+        let result = emulator.storage_mut().write(&path.to_string_lossy(), &png);
+
+        Some(result.map(|()| path))
+    }
+}
+
+/// Returns the name of the screenshot of the event, e.g. `pong-1234-exit.png`.
+///
+/// # Parameters
+///
+/// * `game_path` - The path to the game.
+/// * `frame` - The frame the event happened on.
+/// * `trigger` - The event which happened.
+#[must_use]
+pub fn file_name(game_path: &str, frame: u64, trigger: ScreenshotTrigger) -> String {
+    let game_name = Path::new(game_path).file_stem().map_or_else(|| game_path.into(), |name| name.to_string_lossy());
+    format!("{game_name}-{frame}-{trigger}.png")
+}
+
+/// Returns whether the instruction at the program counter jumps to itself, which leaves the game doing nothing until it is reset.
+///
+/// # Parameters
+///
+/// * `interpreter` - The interpreter to check.
+#[must_use]
+pub fn is_spinning(interpreter: &Interpreter) -> bool {
+    let address = interpreter.program_counter();
+    let start = usize::from(address);
+    match interpreter.ram().get(start..start + 2) {
+        Some([high, low]) => u16::from_be_bytes([*high, *low]) == 0x1000 | address,
+        _ => false
+    }
+}
+
+/// Returns a PNG of the display, in the colours of the palette.
+///
+/// # Parameters
+///
+/// * `framebuffer` - The display to capture.
+/// * `palette` - The colours the display is drawn in.
+#[must_use]
+pub fn screenshot_png(framebuffer: Framebuffer<'_>, palette: &Palette) -> Vec<u8> {
+    let width = framebuffer.width() as usize * SCREENSHOT_SCALE;
+    let height = framebuffer.height() as usize * SCREENSHOT_SCALE;
+    let colours = &palette.colours;
+
+    let mut image = Vec::with_capacity((width * 3 + 1) * height);
+    let mut row = Vec::with_capacity(width * 3 + 1);
+    for y in 0..framebuffer.height() {
+        // Every row starts with its filter type, which is always none
+        row.clear();
+        row.push(0);
+        for x in 0..framebuffer.width() {
+            let colour = colours[framebuffer.colour_index(x, y)];
+            for _ in 0..SCREENSHOT_SCALE {
+                row.extend_from_slice(&[colour.r, colour.g, colour.b]);
+            }
+        }
+
+        for _ in 0..SCREENSHOT_SCALE {
+            image.extend_from_slice(&row);
+        }
+    }
+
+    encode_png(width, height, &image)
+}
+
+/// Returns the RGB image as a PNG. The image data is stored uncompressed, as the PNGs are small and only written on occasion (e.g. when a test fails).
+///
+/// # Parameters
+///
+/// * `width` - The width of the image in pixels.
+/// * `height` - The height of the image in pixels.
+/// * `image` - The rows of the image, each starting with its filter type followed by 3 bytes per pixel.
+pub(crate) fn encode_png(width: usize, height: usize, image: &[u8]) -> Vec<u8> {
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&u32::try_from(width).unwrap_or(u32::MAX).to_be_bytes());
+    header.extend_from_slice(&u32::try_from(height).unwrap_or(u32::MAX).to_be_bytes());
+    // 8-bit RGB, with the default compression, filtering, and no interlacing
+    header.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+    // A zlib stream of stored deflate blocks
+    let mut data = vec![0x78, 0x01];
+    let block_count = image.len().div_ceil(MAX_STORED_BLOCK_LENGTH).max(1);
+    for (i, block) in image.chunks(MAX_STORED_BLOCK_LENGTH).chain(image.is_empty().then_some(&[][..])).enumerate() {
+        let length = u16::try_from(block.len()).unwrap_or(u16::MAX);
+        data.push(u8::from(i + 1 == block_count));
+        data.extend_from_slice(&length.to_le_bytes());
+        data.extend_from_slice(&(!length).to_le_bytes());
+        data.extend_from_slice(block);
+    }
+    data.extend_from_slice(&adler32(image).to_be_bytes());
+
+    let mut png = PNG_SIGNATURE.to_vec();
+    write_chunk(&mut png, b"IHDR", &header);
+    write_chunk(&mut png, b"IDAT", &data);
+    write_chunk(&mut png, b"IEND", &[]);
+
+    png
+}
+
+/// Writes a PNG chunk: its length, type, data, and the CRC of its type and data.
+///
+/// # Parameters
+///
+/// * `png` - The PNG to write to.
+/// * `chunk_type` - The four letter type of the chunk.
+/// * `data` - The contents of the chunk.
+fn write_chunk(png: &mut Vec<u8>, chunk_type: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&u32::try_from(data.len()).unwrap_or(u32::MAX).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(chunk_type);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// Returns the CRC-32 of the bytes, as used by PNG chunks.
+///
+/// # Parameters
+///
+/// * `bytes` - The bytes to check.
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(u32::MAX, |crc, byte| {
+        (0..8).fold(crc ^ u32::from(*byte), |crc, _| if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 })
+    })
+}
+
+/// Returns the Adler-32 checksum of the bytes, as used by zlib streams.
+///
+/// # Parameters
+///
+/// * `bytes` - The bytes to check.
+fn adler32(bytes: &[u8]) -> u32 {
+    const MODULUS: u32 = 65521;
+    let (a, b) = bytes.iter().fold((1, 0), |(a, b), byte| {
+        let a = (a + u32::from(*byte)) % MODULUS;
+        (a, (b + a) % MODULUS)
+    });
+
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    #[test]
+    fn screenshot_events() {
+        let mut emulator = Emulator::new(Interpreter::new(), 1);
+        emulator.set_storage(Box::new(MemoryStorage::new()));
+        emulator.queue_load(vec![0xD0, 0x05, 0x12, 0x02]);
+        emulator.step_frame();
+
+        let mut auto_screenshot = AutoScreenshot::new(vec![ScreenshotTrigger::Spin], "screenshots");
+        assert!(auto_screenshot.take_spin(emulator.interpreter()), "Jump to itself not noticed.");
+        emulator.step_frame();
+        assert!(!auto_screenshot.take_spin(emulator.interpreter()), "Spin reported twice.");
+        assert!(auto_screenshot.capture(ScreenshotTrigger::Exit, "games/pong.ch8", &mut emulator).is_none(), "Screenshot taken of an unchosen event.");
+
+        let path = auto_screenshot.capture(ScreenshotTrigger::Spin, "games/pong.ch8", &mut emulator).unwrap().unwrap();
+        assert_eq!(path, Path::new("screenshots/pong-2-spin.png"), "Screenshot named incorrectly.");
+        let png = emulator.storage().read("screenshots/pong-2-spin.png").unwrap().unwrap();
+        assert_eq!(png[..PNG_SIGNATURE.len()], PNG_SIGNATURE, "PNG signature missing.");
+        assert_eq!(png[16..24], [0, 0, 1, 0, 0, 0, 0, 128], "PNG size incorrect.");
+
+        assert_eq!(ScreenshotTrigger::for_halt(HaltReason::StackUnderflow { address: 0x200 }), Some(ScreenshotTrigger::Error), "Error halt not an error event.");
+        assert_eq!(ScreenshotTrigger::for_halt(HaltReason::UserStop), None, "User stop screenshot.");
+    }
+
+    #[test]
+    fn checksums() {
+        assert_eq!(crc32(b"IEND"), 0xAE42_6082, "CRC-32 calculated incorrectly.");
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398, "Adler-32 calculated incorrectly.");
+    }
+}
@@ -0,0 +1,292 @@
+//! A module to serialize the interpreter and its settings with serde, so that save states, config files, and debugging tools can use standard formats (e.g. JSON or bincode) rather than the [binary save state format](crate::savestate).
+//! Only built with the `serde` feature, which also derives the traits for the [quirks](crate::quirks::QuirkConfig), the [memory protection](crate::interpreter::MemoryProtection), [opcodes](crate::opcodes::Opcode), and [snapshots](crate::interpreter::Snapshot).
+//!
+//! An [`Interpreter`](Interpreter) is serialized as its quirks, memory settings, RPL flags, the reason it halted, the banks of its game, and a snapshot of its hardware. As with the binary format, the held keys and the tools attached to it (e.g. the coverage or hooks) are not kept, and it deserializes without a display or audio.
+//! The random number generator cannot be serialized as is, so it is stored as a seed drawn from a copy of it. Every deserialization of a state replays the same numbers, but not the ones the original generator would have gone on to draw, so a round trip changes the random sequence.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::de::{self, Deserializer};
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+
+use crate::banking::MAX_BANKS;
+use crate::extensions::ExtensionRegistry;
+use crate::interpreter::{HaltReason, Interpreter, MemoryProtection, Snapshot, RAM_SIZE, REGISTERS_SIZE, RPL_FLAGS_SIZE};
+use crate::quirks::QuirkConfig;
+
+/// Stores everything kept when an interpreter is serialized.
+#[derive(Serialize, Deserialize)]
+struct InterpreterState {
+    quirk_config: QuirkConfig,
+    memory_protection: MemoryProtection,
+    load_address: u16,
+    is_banking: bool,
+    #[serde(deserialize_with = "below::<_, u8, { MAX_BANKS + 1 }>")]
+    bank_count: u8,
+    #[serde(deserialize_with = "below::<_, usize, { RAM_SIZE + 1 }>")]
+    program_length: usize,
+    rpl_flags: [u8; RPL_FLAGS_SIZE],
+    halt_reason: Option<HaltReasonState>,
+    snapshot: Snapshot
+}
+
+impl Serialize for Interpreter {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        InterpreterState {
+            quirk_config: self.quirk_config().clone(),
+            memory_protection: self.memory_protection(),
+            load_address: self.load_address(),
+            is_banking: self.is_banking(),
+            bank_count: self.bank_count(),
+            program_length: self.program_range().len(),
+            rpl_flags: *self.rpl_flags(),
+            halt_reason: self.halt_reason().map(HaltReasonState::from),
+            snapshot: self.snapshot()
+        }.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Interpreter {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Interpreter, D::Error> {
+        let state = InterpreterState::deserialize(deserializer)?;
+        let mut interpreter = Interpreter::new_headless(state.quirk_config);
+        interpreter.set_load_address(state.load_address).map_err(de::Error::custom)?;
+        interpreter.set_banking(state.is_banking).map_err(de::Error::custom)?;
+        interpreter.set_memory_protection(state.memory_protection);
+        interpreter.set_rpl_flags(state.rpl_flags);
+        interpreter.restore(&state.snapshot);
+        interpreter.restore_program(state.program_length, state.bank_count);
+        if let Some(halt_reason) = state.halt_reason {
+            interpreter.halt(halt_reason.into());
+        }
+
+        Ok(interpreter)
+    }
+}
+
+/// Stores the reason an interpreter halted. Unlike [`HaltReason`](HaltReason), the name of an unsupported extension is owned, as it cannot be borrowed from the format.
+#[derive(Serialize, Deserialize)]
+enum HaltReasonState {
+    InvalidOpcode { address: u16, opcode: u16 },
+    StackOverflow { address: u16 },
+    StackUnderflow { address: u16 },
+    ProgramCounterOutOfBounds { address: u16 },
+    ProtectedWrite { address: u16, target: u16 },
+    UnsupportedExtension { address: u16, opcode: u16, extension: String },
+    InvalidBank { address: u16, bank: u8 },
+    Exit,
+    UserStop
+}
+
+impl From<HaltReason> for HaltReasonState {
+    fn from(halt_reason: HaltReason) -> Self {
+        match halt_reason {
+            HaltReason::InvalidOpcode { address, opcode } => HaltReasonState::InvalidOpcode { address, opcode },
+            HaltReason::StackOverflow { address } => HaltReasonState::StackOverflow { address },
+            HaltReason::StackUnderflow { address } => HaltReasonState::StackUnderflow { address },
+            HaltReason::ProgramCounterOutOfBounds { address } => HaltReasonState::ProgramCounterOutOfBounds { address },
+            HaltReason::ProtectedWrite { address, target } => HaltReasonState::ProtectedWrite { address, target },
+            HaltReason::UnsupportedExtension { address, opcode, extension } => HaltReasonState::UnsupportedExtension { address, opcode, extension: extension.to_string() },
+            HaltReason::InvalidBank { address, bank } => HaltReasonState::InvalidBank { address, bank },
+            HaltReason::Exit => HaltReasonState::Exit,
+            HaltReason::UserStop => HaltReasonState::UserStop
+        }
+    }
+}
+
+impl From<HaltReasonState> for HaltReason {
+    /// Returns the halt reason, naming an unsupported extension through the [known extensions](ExtensionRegistry). Extensions registered by the embedder are not known after deserializing, so their opcodes are reported as invalid instead.
+    fn from(halt_reason: HaltReasonState) -> Self {
+        match halt_reason {
+            HaltReasonState::InvalidOpcode { address, opcode } => HaltReason::InvalidOpcode { address, opcode },
+            HaltReasonState::StackOverflow { address } => HaltReason::StackOverflow { address },
+            HaltReasonState::StackUnderflow { address } => HaltReason::StackUnderflow { address },
+            HaltReasonState::ProgramCounterOutOfBounds { address } => HaltReason::ProgramCounterOutOfBounds { address },
+            HaltReasonState::ProtectedWrite { address, target } => HaltReason::ProtectedWrite { address, target },
+            HaltReasonState::UnsupportedExtension { address, opcode, extension } => match ExtensionRegistry::new().find(opcode) {
+                Some(known) if known.name == extension => HaltReason::UnsupportedExtension { address, opcode, extension: known.name },
+                _ => HaltReason::InvalidOpcode { address, opcode }
+            },
+            HaltReasonState::InvalidBank { address, bank } => HaltReason::InvalidBank { address, bank },
+            HaltReasonState::Exit => HaltReason::Exit,
+            HaltReasonState::UserStop => HaltReason::UserStop
+        }
+    }
+}
+
+/// Serializes arrays of any length (serde only supports up to 32 elements) as sequences, e.g. the RAM and the drawing buffers.
+pub(crate) mod array {
+    use serde::de::{self, Deserializer};
+    use serde::ser::Serializer;
+    use serde::{Deserialize, Serialize};
+
+    /// Serializes the array as a sequence of its elements.
+    ///
+    /// # Parameters
+    ///
+    /// * `array` - The array to serialize.
+    /// * `serializer` - The format to serialize to.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the format fails to serialize an element.
+    pub(crate) fn serialize<S: Serializer, T: Serialize, const N: usize>(array: &[T; N], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(array)
+    }
+
+    /// Deserializes a sequence of exactly the length of the array.
+    ///
+    /// # Parameters
+    ///
+    /// * `deserializer` - The format to deserialize from.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the sequence cannot be deserialized or has the wrong length.
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>, T: Deserialize<'de>, const N: usize>(deserializer: D) -> Result<[T; N], D::Error> {
+        let elements = Vec::<T>::deserialize(deserializer)?;
+        let length = elements.len();
+        elements.try_into().map_err(|_| de::Error::invalid_length(length, &format!("{N} elements").as_str()))
+    }
+}
+
+/// Serializes a random number generator as a seed drawn from a copy of it, leaving the original untouched.  
+/// The generator deserialized from the seed is not the original one, so it draws different numbers than the original would have gone on to.
+pub(crate) mod rng {
+    use super::*;
+
+    /// Serializes a seed drawn from a copy of the generator.
+    ///
+    /// # Parameters
+    ///
+    /// * `rng` - The generator to serialize.
+    /// * `serializer` - The format to serialize to.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the format fails to serialize the seed.
+    pub(crate) fn serialize<S: Serializer>(rng: &StdRng, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(rng.clone().gen())
+    }
+
+    /// Deserializes the seed, returning a generator seeded with it.
+    ///
+    /// # Parameters
+    ///
+    /// * `deserializer` - The format to deserialize from.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the seed cannot be deserialized.
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<StdRng, D::Error> {
+        Ok(StdRng::seed_from_u64(u64::deserialize(deserializer)?))
+    }
+}
+
+/// Deserializes a value, rejecting those at or past the limit so that an impossible state cannot be restored (e.g. a stack pointer past the stack).
+///
+/// # Parameters
+///
+/// * `deserializer` - The format to deserialize from.
+///
+/// # Errors
+///
+/// Returns an `Err` if the value cannot be deserialized or is not below the limit.
+pub(crate) fn below<'de, D: Deserializer<'de>, T: Deserialize<'de> + Copy + Into<usize>, const LIMIT: usize>(deserializer: D) -> Result<T, D::Error> {
+    let value = T::deserialize(deserializer)?;
+    if value.into() >= LIMIT {
+        return Err(de::Error::custom(format!("{} is not below {LIMIT}", value.into())));
+    }
+
+    Ok(value)
+}
+
+/// Deserializes the draw waiting for the display to refresh (the registers holding its position, and its height), rejecting registers past `VF` so that the draw cannot read outside the registers.
+///
+/// # Parameters
+///
+/// * `deserializer` - The format to deserialize from.
+///
+/// # Errors
+///
+/// Returns an `Err` if the draw cannot be deserialized or names a register past `VF`.
+pub(crate) fn pending_draw<'de, D: Deserializer<'de>>(deserializer: D) -> Result<(usize, usize, u8), D::Error> {
+    let (x_register, y_register, height) = <(usize, usize, u8)>::deserialize(deserializer)?;
+    if x_register >= REGISTERS_SIZE || y_register >= REGISTERS_SIZE {
+        return Err(de::Error::custom(format!("the draw registers {x_register} and {y_register} are not both below {REGISTERS_SIZE}")));
+    }
+
+    Ok((x_register, y_register, height))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::de::value::{Error, SeqDeserializer, UsizeDeserializer};
+    use serde::de::DeserializeOwned;
+
+    use super::*;
+    use crate::opcodes::Opcode;
+
+    /// The size of the stack the JSON round trip runs on.
+    const THREAD_STACK_SIZE: usize = 32 * 1024 * 1024;
+
+    /// Fails to compile unless the type can be both serialized and deserialized.
+    fn assert_serde<T: Serialize + DeserializeOwned>() {}
+
+    #[test]
+    fn serializable_types() {
+        assert_serde::<Interpreter>();
+        assert_serde::<Snapshot>();
+        assert_serde::<QuirkConfig>();
+        assert_serde::<MemoryProtection>();
+        assert_serde::<Opcode>();
+    }
+
+    #[test]
+    fn deserialize_checked() {
+        let deserialize_index = |index: usize| below::<_, usize, 16>(UsizeDeserializer::<Error>::new(index));
+        assert_eq!(deserialize_index(15), Ok(15), "Value below the limit rejected.");
+        assert!(deserialize_index(16).is_err(), "Value at the limit accepted.");
+
+        let deserialize_array = |length: usize| array::deserialize::<_, u8, 4>(SeqDeserializer::<_, Error>::new(std::iter::repeat_n(7, length)));
+        assert_eq!(deserialize_array(4), Ok([7; 4]), "Array of the right length rejected.");
+        assert!(deserialize_array(3).is_err(), "Short array accepted.");
+        assert!(deserialize_array(5).is_err(), "Long array accepted.");
+
+        let deserialize_draw = |draw: [usize; 3]| pending_draw(SeqDeserializer::<_, Error>::new(draw.into_iter()));
+        assert_eq!(deserialize_draw([0, 15, 5]), Ok((0, 15, 5)), "Draw from valid registers rejected.");
+        assert!(deserialize_draw([16, 0, 5]).is_err(), "Draw from a register past VF accepted.");
+        assert!(deserialize_draw([0, 16, 5]).is_err(), "Draw from a register past VF accepted.");
+    }
+
+    #[test]
+    fn json_round_trip() {
+        // The interpreter's memory is copied around the stack while it is deserialized, which needs more room than a test thread has
+        std::thread::Builder::new().stack_size(THREAD_STACK_SIZE).spawn(|| {
+            let mut interpreter = Interpreter::builder().banking(true).build().unwrap();
+            let mut game = vec![0x00; 0x600 + 2 * 0x800];
+            // Switches to bank 1, then halts with an unsupported MegaChip opcode
+            game[..6].copy_from_slice(&[0x60, 0x01, 0xA1, 0xFF, 0xF0, 0x55]);
+            game[6..8].copy_from_slice(&[0x00, 0x11]);
+            interpreter.load_game(&game);
+            for _ in 0..4 {
+                interpreter.handle_cycle();
+            }
+            let halt_reason = interpreter.halt_reason();
+            assert!(matches!(halt_reason, Some(HaltReason::UnsupportedExtension { .. })), "Game not halted.");
+
+            let json = serde_json::to_string(&interpreter).unwrap();
+            let restored: Interpreter = serde_json::from_str(&json).unwrap();
+            assert!(restored.is_banking(), "Banking not kept.");
+            assert_eq!(restored.bank_count(), 2, "Banks not kept.");
+            assert_eq!(restored.program_range(), interpreter.program_range(), "Game length not kept.");
+            assert_eq!(restored.halt_reason(), halt_reason, "Halt not kept.");
+            assert_eq!(restored.ram(), interpreter.ram(), "Memory not kept.");
+
+            let mut corrupt: serde_json::Value = serde_json::from_str(&json).unwrap();
+            corrupt["snapshot"]["wait_for_display_refresh_data"][0] = 99.into();
+            assert!(serde_json::from_value::<Interpreter>(corrupt).is_err(), "Draw from a register past VF deserialized.");
+        }).unwrap().join().unwrap();
+    }
+}
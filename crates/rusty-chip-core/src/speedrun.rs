@@ -0,0 +1,411 @@
+//! A module to time speedruns, starting, splitting, and stopping automatically when conditions on the game's memory are met.
+//! The conditions are kept alongside the game file as `<game>.splits`, with one entry per line and `#` starting a comment:
+//!
+//! ```text
+//! start [0x3F0] == 1
+//! split Level 2: [0x3F0] == 2
+//! split Finish: V5 >= 10
+//! ```
+//!
+//! Each side of a condition is a [watch expression](crate::watch), compared with `==`, `!=`, `<`, `<=`, `>`, or `>=`.
+//! The splits are checked in order, and the run finishes with the last one, at which point the times can be saved for LiveSplit as `<game>.lss`.
+
+use std::fmt::{Display, Formatter};
+use std::io::{self, ErrorKind};
+use std::str::FromStr;
+
+use crate::interpreter::Interpreter;
+use crate::storage::StorageBackend;
+use crate::watch::WatchExpression;
+
+/// The extension added to the game file for its splits.
+const SPLITS_FILE_EXTENSION: &str = "splits";
+
+/// The extension added to the game file for the split times exported for LiveSplit.
+const LIVESPLIT_FILE_EXTENSION: &str = "lss";
+
+/// The character starting a comment line in the splits file.
+const COMMENT_PREFIX: char = '#';
+
+/// The runs are timed in frames, which the CHIP-8 runs at 60Hz.
+const FRAMES_PER_SECOND: u64 = 60;
+
+/// Denotes how the two sides of a condition are compared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Equal,
+    NotEqual,
+    LessOrEqual,
+    GreaterOrEqual,
+    Less,
+    Greater
+}
+
+/// The comparisons along with how they are written, with the longer operators first so that `<=` is not taken for `<`.
+const COMPARISONS: [(&str, Comparison); 6] = [
+    ("==", Comparison::Equal),
+    ("!=", Comparison::NotEqual),
+    ("<=", Comparison::LessOrEqual),
+    (">=", Comparison::GreaterOrEqual),
+    ("<", Comparison::Less),
+    (">", Comparison::Greater)
+];
+
+/// Stores a comparison between two watch expressions, which is met when it holds for the current state of the game.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Condition {
+    left: WatchExpression,
+    comparison: Comparison,
+    right: WatchExpression
+}
+
+impl Condition {
+    /// Returns whether the condition holds for the current state of the interpreter. Conditions which cannot be evaluated (e.g. division by zero) are not met.
+    ///
+    /// # Parameters
+    ///
+    /// * `interpreter` - The interpreter to read from.
+    #[must_use]
+    pub fn is_met(&self, interpreter: &Interpreter) -> bool {
+        let (Some(left), Some(right)) = (self.left.evaluate(interpreter), self.right.evaluate(interpreter)) else {
+            return false;
+        };
+
+        match self.comparison {
+            Comparison::Equal => left == right,
+            Comparison::NotEqual => left != right,
+            Comparison::LessOrEqual => left <= right,
+            Comparison::GreaterOrEqual => left >= right,
+            Comparison::Less => left < right,
+            Comparison::Greater => left > right
+        }
+    }
+}
+
+impl FromStr for Condition {
+    type Err = String;
+
+    /// Parses a condition of the form `<expression> <comparison> <expression>`, returning an `Err` describing the problem if it is malformed.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (operator, comparison) = COMPARISONS.iter()
+            .find(|(operator, _)| s.contains(operator))
+            .ok_or_else(|| format!("Missing comparison in condition \"{s}\"."))?;
+        let (left, right) = s.split_once(operator).expect("Comparison not found.");
+
+        Ok(Condition { left: left.parse()?, comparison: *comparison, right: right.parse()? })
+    }
+}
+
+impl Display for Condition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (operator, _) = COMPARISONS.iter().find(|(_, comparison)| *comparison == self.comparison).expect("Comparison not listed.");
+        write!(f, "{} {operator} {}", self.left, self.right)
+    }
+}
+
+/// Stores a named split, which ends its segment of the run once its condition is met.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Split {
+    pub name: String,
+    pub condition: Condition
+}
+
+/// Stores the conditions which start the run and end each of its segments.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Splits {
+    pub start: Condition,
+    pub splits: Vec<Split>
+}
+
+impl Splits {
+    /// Returns the path of the file storing the splits of the provided game.
+    ///
+    /// # Parameters
+    ///
+    /// * `game_path` - The path to the game file.
+    #[must_use]
+    pub fn path_for_game(game_path: &str) -> String {
+        format!("{game_path}.{SPLITS_FILE_EXTENSION}")
+    }
+
+    /// Loads the splits of the provided game, returning `None` if it has none.
+    ///
+    /// # Parameters
+    ///
+    /// * `storage` - Where the splits are saved.
+    /// * `game_path` - The path to the game file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the file exists but cannot be read or is malformed.
+    pub fn load_for_game(storage: &dyn StorageBackend, game_path: &str) -> io::Result<Option<Splits>> {
+        let Some(contents) = storage.read_to_string(&Self::path_for_game(game_path))? else {
+            return Ok(None);
+        };
+
+        contents.parse().map(Some).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))
+    }
+}
+
+impl FromStr for Splits {
+    type Err = String;
+
+    /// Parses the splits file format described in the [module documentation](self).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut start = None;
+        let mut splits = Vec::new();
+        for line in s.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with(COMMENT_PREFIX)) {
+            let (keyword, rest) = line.split_once(char::is_whitespace).ok_or_else(|| format!("Incomplete splits entry: {line}"))?;
+            match keyword {
+                "start" => start = Some(rest.parse()?),
+                "split" => {
+                    let (name, condition) = rest.split_once(':').ok_or_else(|| format!("Missing name in split: {line}"))?;
+                    splits.push(Split { name: name.trim().to_string(), condition: condition.parse()? });
+                },
+                _ => return Err(format!("Unknown splits entry: {line}"))
+            }
+        }
+
+        let start = start.ok_or_else(|| String::from("The splits have no start condition."))?;
+        if splits.is_empty() {
+            return Err(String::from("The splits have no split to finish the run."));
+        }
+
+        Ok(Splits { start, splits })
+    }
+}
+
+/// Times runs of a game against its [splits](Splits), one frame at a time.
+#[derive(Debug, Clone)]
+pub struct SpeedrunTimer {
+    splits: Splits,
+    start_frame: Option<u64>,
+    split_frames: Vec<u64>,
+    current_frame: u64,
+    was_met: bool,
+    is_newly_finished: bool
+}
+
+impl SpeedrunTimer {
+    /// Returns a timer waiting for the run to start.
+    ///
+    /// # Parameters
+    ///
+    /// * `splits` - The conditions to start, split, and finish the run on.
+    #[must_use]
+    pub fn new(splits: Splits) -> SpeedrunTimer {
+        SpeedrunTimer { splits, start_frame: None, split_frames: Vec::new(), current_frame: 0, was_met: false, is_newly_finished: false }
+    }
+
+    /// Clears the times, waiting for the run to start again (e.g. when the game is reset).
+    pub fn reset(&mut self) {
+        *self = SpeedrunTimer::new(self.splits.clone());
+    }
+
+    /// Returns whether the run has finished, with every split met.
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.split_frames.len() == self.splits.splits.len()
+    }
+
+    /// Returns whether the run finished since this was last called, so that its times can be saved once.
+    pub fn take_finished(&mut self) -> bool {
+        std::mem::take(&mut self.is_newly_finished)
+    }
+
+    /// Checks the condition the run is waiting for after a frame has run, starting or splitting if it has just been met.
+    /// A condition is only met on the frame it becomes true, so a split does not trigger on a value left over from the previous one.
+    ///
+    /// # Parameters
+    ///
+    /// * `frame` - The number of frames run so far.
+    /// * `interpreter` - The interpreter running the game.
+    pub fn update(&mut self, frame: u64, interpreter: &Interpreter) {
+        self.current_frame = frame;
+        let condition = match self.start_frame {
+            None => &self.splits.start,
+            Some(_) if self.is_finished() => return,
+            Some(_) => &self.splits.splits[self.split_frames.len()].condition
+        };
+
+        let is_met = condition.is_met(interpreter);
+        if !is_met || self.was_met {
+            self.was_met = is_met;
+            return;
+        }
+
+        if self.start_frame.is_none() {
+            self.start_frame = Some(frame);
+        } else {
+            self.split_frames.push(frame);
+            self.is_newly_finished = self.is_finished();
+        }
+
+        // The next condition must become true after this frame
+        self.was_met = match self.split_frames.len() {
+            index if index < self.splits.splits.len() => self.splits.splits[index].condition.is_met(interpreter),
+            _ => true
+        };
+    }
+
+    /// Returns the number of frames the run has taken so far, or in total once finished.
+    #[must_use]
+    pub fn elapsed_frames(&self) -> u64 {
+        let Some(start_frame) = self.start_frame else {
+            return 0;
+        };
+
+        let end_frame = if self.is_finished() { self.split_frames[self.split_frames.len() - 1] } else { self.current_frame };
+        end_frame - start_frame
+    }
+
+    /// Returns the times of the splits met so far in frames since the start, along with their names.
+    pub fn split_times(&self) -> impl Iterator<Item = (&str, u64)> {
+        let start_frame = self.start_frame.unwrap_or_default();
+        self.splits.splits.iter().zip(&self.split_frames).map(move |(split, frame)| (split.name.as_str(), frame - start_frame))
+    }
+
+    /// Returns the state of the run for the window title, e.g. `Run 1:02.35 (Level 2)`.
+    #[must_use]
+    pub fn describe(&self) -> String {
+        let time = format_time(self.elapsed_frames());
+        match (self.start_frame, self.splits.splits.get(self.split_frames.len())) {
+            (None, _) => String::from("Run not started"),
+            (Some(_), Some(split)) => format!("Run {time} ({})", split.name),
+            (Some(_), None) => format!("Run finished in {time}")
+        }
+    }
+
+    /// Returns the split times as a LiveSplit splits file, with each split as a segment and this run as the personal best.
+    ///
+    /// # Parameters
+    ///
+    /// * `game_name` - The name of the game shown in LiveSplit.
+    #[must_use]
+    pub fn to_livesplit(&self, game_name: &str) -> String {
+        let mut segments = String::new();
+        let mut previous_time = 0;
+        for (name, time) in self.split_times() {
+            segments.push_str(&format!(
+                "    <Segment>\n      <Name>{}</Name>\n      <Icon />\n      <SplitTimes>\n        <SplitTime name=\"Personal Best\">\n          <RealTime>{}</RealTime>\n        </SplitTime>\n      </SplitTimes>\n      <BestSegmentTime>\n        <RealTime>{}</RealTime>\n      </BestSegmentTime>\n      <SegmentHistory />\n    </Segment>\n",
+                escape_xml(name), livesplit_time(time), livesplit_time(time - previous_time)
+            ));
+            previous_time = time;
+        }
+
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<Run version=\"1.7.0\">\n  <GameIcon />\n  <GameName>{}</GameName>\n  <CategoryName>Any%</CategoryName>\n  <Offset>00:00:00</Offset>\n  <AttemptCount>1</AttemptCount>\n  <AttemptHistory />\n  <Segments>\n{segments}  </Segments>\n  <AutoSplitterSettings />\n</Run>\n",
+            escape_xml(game_name)
+        )
+    }
+
+    /// Saves the split times alongside the game file as `<game>.lss`, returning the path they were saved to.
+    ///
+    /// # Parameters
+    ///
+    /// * `storage` - Where to save the split times.
+    /// * `game_path` - The path to the game file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the file cannot be written.
+    pub fn save_livesplit(&self, storage: &mut dyn StorageBackend, game_path: &str) -> io::Result<String> {
+        let game_name = std::path::Path::new(game_path).file_stem().map_or_else(|| game_path.into(), |name| name.to_string_lossy());
+        let path = format!("{game_path}.{LIVESPLIT_FILE_EXTENSION}");
+        storage.write(&path, self.to_livesplit(&game_name).as_bytes())?;
+
+        Ok(path)
+    }
+}
+
+/// Returns the frames as a time for the window title, e.g. `1:02.35`.
+///
+/// # Parameters
+///
+/// * `frames` - The number of frames.
+fn format_time(frames: u64) -> String {
+    let hundredths = frames * 100 / FRAMES_PER_SECOND;
+    format!("{}:{:02}.{:02}", hundredths / 6000, hundredths / 100 % 60, hundredths % 100)
+}
+
+/// Returns the frames as a time in the format LiveSplit stores, e.g. `00:01:02.3500000`.
+///
+/// # Parameters
+///
+/// * `frames` - The number of frames.
+fn livesplit_time(frames: u64) -> String {
+    let ticks = frames * 10_000_000 / FRAMES_PER_SECOND;
+    let seconds = ticks / 10_000_000;
+    format!("{:02}:{:02}:{:02}.{:07}", seconds / 3600, seconds / 60 % 60, seconds % 60, ticks % 10_000_000)
+}
+
+/// Returns the text with the characters which are special in XML escaped.
+///
+/// # Parameters
+///
+/// * `text` - The text to escape.
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    const SPLITS: &str = "# Counts V0 up to 3\nstart V0 == 1\nsplit Two: V0 >= 2\n\nsplit Three & Done: V0 == 3\n";
+
+    #[test]
+    fn parse_splits() {
+        let splits: Splits = SPLITS.parse().unwrap();
+        assert_eq!(splits.start.to_string(), "V0 == 1", "Start condition not parsed.");
+        assert_eq!(splits.splits.iter().map(|split| split.name.as_str()).collect::<Vec<_>>(), ["Two", "Three & Done"], "Split names not parsed.");
+        assert_eq!(splits.splits[0].condition.comparison, Comparison::GreaterOrEqual, "Longer comparison not preferred.");
+
+        assert!("split One: V0 == 1".parse::<Splits>().is_err(), "Splits without a start parsed.");
+        assert!("start V0 == 1".parse::<Splits>().is_err(), "Splits without a split parsed.");
+        assert!("start V0 == 1\nsplit V0 == 2".parse::<Splits>().is_err(), "Split without a name parsed.");
+        assert!("start V0".parse::<Splits>().is_err(), "Condition without a comparison parsed.");
+
+        let mut storage = MemoryStorage::new();
+        assert!(Splits::load_for_game(&storage, "games/counter.ch8").unwrap().is_none(), "Splits loaded for game without any.");
+        storage.write(&Splits::path_for_game("games/counter.ch8"), SPLITS.as_bytes()).unwrap();
+        assert_eq!(Splits::load_for_game(&storage, "games/counter.ch8").unwrap().map(|splits| splits.splits.len()), Some(2), "Saved splits not loaded.");
+    }
+
+    #[test]
+    fn time_run() {
+        let mut interpreter = Interpreter::new();
+        let mut timer = SpeedrunTimer::new(SPLITS.parse().unwrap());
+        let mut run_frame = |frame: u64, v0: u8, timer: &mut SpeedrunTimer| {
+            interpreter.load_game(&[0x60, v0]);
+            interpreter.handle_cycle();
+            timer.update(frame, &interpreter);
+        };
+
+        run_frame(1, 0, &mut timer);
+        assert_eq!(timer.describe(), "Run not started", "Run started early.");
+        run_frame(2, 1, &mut timer);
+        run_frame(32, 1, &mut timer);
+        assert_eq!(timer.describe(), "Run 0:00.50 (Two)", "Run not timed.");
+        run_frame(62, 2, &mut timer);
+        run_frame(92, 3, &mut timer);
+        assert!(timer.is_finished(), "Run not finished.");
+        assert!(timer.take_finished(), "Finish not reported.");
+        assert!(!timer.take_finished(), "Finish reported twice.");
+        run_frame(122, 3, &mut timer);
+        assert_eq!(timer.split_times().collect::<Vec<_>>(), [("Two", 60), ("Three & Done", 90)], "Split times wrong.");
+        assert_eq!(timer.describe(), "Run finished in 0:01.50", "Finished run still timed.");
+
+        let livesplit = timer.to_livesplit("Counter");
+        assert!(livesplit.contains("<Name>Three &amp; Done</Name>"), "Split name not escaped.");
+        assert!(livesplit.contains("<RealTime>00:00:01.5000000</RealTime>"), "Split time not exported.");
+        assert!(livesplit.contains("<RealTime>00:00:00.5000000</RealTime>"), "Segment time not exported.");
+        let mut storage = MemoryStorage::new();
+        assert_eq!(timer.save_livesplit(&mut storage, "games/counter.ch8").unwrap(), "games/counter.ch8.lss", "Split times not saved alongside the game.");
+        assert!(storage.read_to_string("games/counter.ch8.lss").unwrap().is_some_and(|saved| saved.contains("<GameName>counter</GameName>")), "Game name not taken from the file.");
+
+        timer.reset();
+        assert_eq!(timer.split_times().count(), 0, "Times not reset.");
+    }
+}
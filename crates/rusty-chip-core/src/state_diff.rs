@@ -0,0 +1,165 @@
+//! A module to compare two [snapshots](Snapshot) of a game (e.g. loaded from save states), listing the registers, timers, stack, and RAM regions which differ.
+//! Useful for tracking down where two runs of the same game diverge, e.g. under different quirk settings: save a state from each at the same point and compare them.
+
+use std::fmt::{Display, Formatter};
+
+use crate::interpreter::{Snapshot, RAM_SIZE};
+
+/// The most identical bytes between two differing bytes of RAM for them to be reported as a single region.
+const REGION_GAP: usize = 4;
+
+/// The most bytes a RAM region can have for its values to be listed, rather than just how many of them differ.
+const MAX_LISTED_BYTES: usize = 16;
+
+/// Denotes a part of the state which differs between two snapshots, with its value in the first and the second.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Difference {
+    Register { register: usize, first: u8, second: u8 },
+    RegisterI { first: u16, second: u16 },
+    ProgramCounter { first: u16, second: u16 },
+    StackPointer { first: usize, second: usize },
+    Stack { slot: usize, first: u16, second: u16 },
+    DelayTimer { first: u8, second: u8 },
+    SoundTimer { first: u8, second: u8 },
+    /// A region of RAM starting at the address, which may include a few identical bytes between those which differ.
+    Ram { start: usize, first: Vec<u8>, second: Vec<u8> },
+    /// The number of pixels which differ across both XO-CHIP planes.
+    Display { pixels: usize }
+}
+
+impl Display for Difference {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Difference::Register { register, first, second } => write!(f, "V{register:X}: 0x{first:02X} -> 0x{second:02X}"),
+            Difference::RegisterI { first, second } => write!(f, "I: 0x{first:03X} -> 0x{second:03X}"),
+            Difference::ProgramCounter { first, second } => write!(f, "PC: 0x{first:03X} -> 0x{second:03X}"),
+            Difference::StackPointer { first, second } => write!(f, "SP: {first} -> {second}"),
+            Difference::Stack { slot, first, second } => write!(f, "Stack {slot}: 0x{first:03X} -> 0x{second:03X}"),
+            Difference::DelayTimer { first, second } => write!(f, "DT: {first} -> {second}"),
+            Difference::SoundTimer { first, second } => write!(f, "ST: {first} -> {second}"),
+            Difference::Ram { start, first, second } => {
+                write!(f, "RAM 0x{start:03X}-0x{:03X}: ", start + first.len() - 1)?;
+                if first.len() > MAX_LISTED_BYTES {
+                    let differing = first.iter().zip(second).filter(|(first, second)| first != second).count();
+                    return write!(f, "{differing} of {} bytes differ", first.len());
+                }
+
+                let hex = |bytes: &[u8]| bytes.iter().map(|byte| format!("{byte:02X}")).collect::<Vec<_>>().join(" ");
+                write!(f, "{} -> {}", hex(first), hex(second))
+            },
+            Difference::Display { pixels } => write!(f, "Display: {pixels} pixel(s) differ")
+        }
+    }
+}
+
+/// Stores every difference between two snapshots, in the order the registers, timers, stack, RAM, and display are compared.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StateDiff {
+    differences: Vec<Difference>
+}
+
+impl StateDiff {
+    /// Compares the snapshots.
+    ///
+    /// # Parameters
+    ///
+    /// * `first` - The snapshot the values are compared from.
+    /// * `second` - The snapshot the values are compared to.
+    #[must_use]
+    pub fn new(first: &Snapshot, second: &Snapshot) -> StateDiff {
+        let mut differences: Vec<Difference> = first.registers.iter().zip(second.registers).enumerate()
+            .filter(|(_, (first, second))| *first != second)
+            .map(|(register, (first, second))| Difference::Register { register, first: *first, second })
+            .collect();
+
+        if first.register_i != second.register_i {
+            differences.push(Difference::RegisterI { first: first.register_i, second: second.register_i });
+        }
+        if first.program_counter != second.program_counter {
+            differences.push(Difference::ProgramCounter { first: first.program_counter, second: second.program_counter });
+        }
+        if first.delay_timer != second.delay_timer {
+            differences.push(Difference::DelayTimer { first: first.delay_timer, second: second.delay_timer });
+        }
+        if first.sound_timer != second.sound_timer {
+            differences.push(Difference::SoundTimer { first: first.sound_timer, second: second.sound_timer });
+        }
+        if first.stack_pointer != second.stack_pointer {
+            differences.push(Difference::StackPointer { first: first.stack_pointer, second: second.stack_pointer });
+        }
+        differences.extend(first.stack.iter().zip(second.stack).enumerate()
+            .filter(|(_, (first, second))| *first != second)
+            .map(|(slot, (first, second))| Difference::Stack { slot, first: *first, second }));
+
+        // Differing bytes separated by only a few identical ones are reported as a single region
+        let mut regions: Vec<(usize, usize)> = Vec::new();
+        for address in (0..RAM_SIZE).filter(|address| first.ram[*address] != second.ram[*address]) {
+            match regions.last_mut() {
+                Some((_, end)) if address - *end <= REGION_GAP => *end = address + 1,
+                _ => regions.push((address, address + 1))
+            }
+        }
+        differences.extend(regions.into_iter().map(|(start, end)| Difference::Ram { start, first: first.ram[start..end].to_vec(), second: second.ram[start..end].to_vec() }));
+
+        let pixels = first.drawing_buffer.iter().zip(second.drawing_buffer.iter())
+            .chain(first.second_plane_buffer.iter().zip(second.second_plane_buffer.iter()))
+            .filter(|(first, second)| first != second)
+            .count();
+        if pixels > 0 {
+            differences.push(Difference::Display { pixels });
+        }
+
+        StateDiff { differences }
+    }
+
+    /// Returns the differences.
+    #[must_use]
+    pub fn differences(&self) -> &[Difference] {
+        &self.differences
+    }
+
+    /// Returns true if the snapshots are identical in every compared part.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.differences.is_empty()
+    }
+}
+
+impl Display for StateDiff {
+    /// Writes a line per difference, or a single line saying the states are identical.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "The states are identical.");
+        }
+
+        for difference in &self.differences {
+            writeln!(f, "{difference}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::Interpreter;
+
+    #[test]
+    fn diff_snapshots() {
+        let mut interpreter = Interpreter::new();
+        interpreter.load_game(&[0x63, 0x1F, 0x12, 0x02]);
+        let first = interpreter.snapshot();
+        assert!(StateDiff::new(&first, &first).is_empty(), "Identical snapshots differ.");
+        assert_eq!(StateDiff::new(&first, &first).to_string(), "The states are identical.\n", "Identical snapshots described incorrectly.");
+
+        interpreter.handle_cycle();
+        interpreter.write_ram(0x300, &[0x01, 0x02]).unwrap();
+        interpreter.write_ram(0x305, &[0x03]).unwrap();
+        interpreter.write_ram(0x400, &[0xFF; 20]).unwrap();
+        let second = interpreter.snapshot();
+        let diff = StateDiff::new(&first, &second);
+        assert_eq!(diff.differences()[..2], [Difference::Register { register: 3, first: 0x00, second: 0x1F }, Difference::ProgramCounter { first: 0x200, second: 0x202 }], "Registers compared incorrectly.");
+        assert_eq!(diff.to_string(), "V3: 0x00 -> 0x1F\nPC: 0x200 -> 0x202\nRAM 0x300-0x305: 00 00 00 00 00 00 -> 01 02 00 00 00 03\nRAM 0x400-0x413: 20 of 20 bytes differ\n", "Differences described incorrectly.");
+    }
+}
@@ -0,0 +1,155 @@
+//! A module to abstract where the emulator persists its files: autosaves, save slots, RPL flags, palettes, key profiles, notes, splits, and split times.
+//! Everything is stored as bytes under a key, which the [filesystem backend](FileStorage) treats as a path so that files are kept alongside their game as before.
+//! The [in-memory backend](MemoryStorage) keeps tests off the disk, and frontends without a filesystem (e.g. a browser) can implement [`StorageBackend`](StorageBackend) over whatever storage they have.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+
+/// A place the emulator can store files, each as bytes under a key. Backends must be `Send` and `Sync` so that the emulator can run on its own thread.
+pub trait StorageBackend: Send + Sync {
+    /// Returns the bytes stored under the key, or `None` if nothing is.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The key to read, e.g. `games/TETRIS.chip8.palette`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if something is stored under the key but cannot be read.
+    fn read(&self, key: &str) -> io::Result<Option<Vec<u8>>>;
+
+    /// Stores the bytes under the key, replacing anything stored there before.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The key to write.
+    /// * `bytes` - The bytes to store.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the bytes cannot be stored.
+    fn write(&mut self, key: &str, bytes: &[u8]) -> io::Result<()>;
+
+    /// Removes whatever is stored under the key. Removing a key with nothing stored is not an error.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The key to remove.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if something is stored under the key but cannot be removed.
+    fn delete(&mut self, key: &str) -> io::Result<()>;
+
+    /// Returns the text stored under the key, or `None` if nothing is.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The key to read.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if something is stored under the key but cannot be read or is not UTF-8.
+    fn read_to_string(&self, key: &str) -> io::Result<Option<String>> {
+        self.read(key)?
+            .map(|bytes| String::from_utf8(bytes).map_err(|e| io::Error::new(ErrorKind::InvalidData, e)))
+            .transpose()
+    }
+}
+
+/// Stores each key as the file at that path, creating any directories it names.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FileStorage;
+
+impl StorageBackend for FileStorage {
+    fn read(&self, key: &str) -> io::Result<Option<Vec<u8>>> {
+        match fs::read(key) {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e)
+        }
+    }
+
+    fn write(&mut self, key: &str, bytes: &[u8]) -> io::Result<()> {
+        if let Some(directory) = Path::new(key).parent() {
+            fs::create_dir_all(directory)?;
+        }
+        fs::write(key, bytes)
+    }
+
+    fn delete(&mut self, key: &str) -> io::Result<()> {
+        match fs::remove_file(key) {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(e),
+            _ => Ok(())
+        }
+    }
+}
+
+/// Stores everything in memory, forgetting it when dropped.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemoryStorage {
+    entries: HashMap<String, Vec<u8>>
+}
+
+impl MemoryStorage {
+    /// Returns an empty store.
+    #[must_use]
+    pub fn new() -> MemoryStorage {
+        MemoryStorage::default()
+    }
+
+    /// Returns the keys with something stored under them, in no particular order.
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(String::as_str)
+    }
+}
+
+impl StorageBackend for MemoryStorage {
+    fn read(&self, key: &str) -> io::Result<Option<Vec<u8>>> {
+        Ok(self.entries.get(key).cloned())
+    }
+
+    fn write(&mut self, key: &str, bytes: &[u8]) -> io::Result<()> {
+        self.entries.insert(key.to_string(), bytes.to_vec());
+        Ok(())
+    }
+
+    fn delete(&mut self, key: &str) -> io::Result<()> {
+        self.entries.remove(key);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks the backend reads back what was written until it is deleted.
+    fn round_trip(storage: &mut dyn StorageBackend, key: &str) {
+        assert_eq!(storage.read(key).unwrap(), None, "Missing key read.");
+        storage.write(key, b"first").unwrap();
+        storage.write(key, b"second").unwrap();
+        assert_eq!(storage.read_to_string(key).unwrap().as_deref(), Some("second"), "Written bytes not read back.");
+        storage.delete(key).unwrap();
+        assert_eq!(storage.read(key).unwrap(), None, "Deleted key read.");
+        storage.delete(key).unwrap();
+    }
+
+    #[test]
+    fn memory_round_trip() {
+        let mut storage = MemoryStorage::new();
+        round_trip(&mut storage, "game.ch8.flags");
+
+        storage.write("game.ch8.palette", &[0xFF]).unwrap();
+        assert_eq!(storage.keys().collect::<Vec<_>>(), ["game.ch8.palette"], "Keys not listed.");
+        assert!(storage.read_to_string("game.ch8.palette").is_err(), "Invalid text read.");
+    }
+
+    #[test]
+    fn file_round_trip() {
+        let path = std::env::temp_dir().join(format!("rusty_chip_storage_test_{}", std::process::id()));
+        round_trip(&mut FileStorage, path.to_str().unwrap());
+    }
+}
@@ -0,0 +1,248 @@
+//! A module to read the labels of a game from a symbol file, so that the disassembler, tracer, and debugger can show and accept names like `main` instead of raw addresses.
+//! A symbol file holds a label and an address per line in either order, optionally separated by `=` (e.g. `main 0x202`, `0x202 main`, or `main = 0x202`), which covers the listings written by Octo and most assemblers.
+//! Labels may be written with Octo's leading colon (`: main 0x202`), and blank lines along with lines starting with `#` or `;` are skipped.  
+//! For games run with [banking](crate::banking), a `.bank <n>` directive line places the labels after it which fall in the bank window in bank `n`, until the next directive, so that each bank can name its own code.
+
+use alloc::collections::BTreeMap;
+use core::str::FromStr;
+#[cfg(feature = "std")]
+use std::fs;
+
+#[cfg(not(feature = "std"))]
+use crate::alloc_prelude::*;
+use crate::banking::{BANK_SIZE, BANK_WINDOW_ADDRESS, MAX_BANKS};
+
+/// The directive placing the labels after it in a bank.
+const BANK_DIRECTIVE: &str = ".bank";
+
+/// Stores the labels of a game along with the addresses they mark.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SymbolTable {
+    labels: BTreeMap<u16, String>,
+    banked_labels: BTreeMap<(u8, u16), String>,
+    addresses: BTreeMap<String, u16>
+}
+
+impl SymbolTable {
+    /// Returns a table without any labels.
+    #[must_use]
+    pub fn new() -> SymbolTable {
+        SymbolTable::default()
+    }
+
+    /// Reads the symbol file at the provided path.
+    ///
+    /// # Parameters
+    ///
+    /// * `path` - The path of the symbol file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the file cannot be read or a line holds something other than a label and an address.
+    #[cfg(feature = "std")]
+    pub fn load(path: &str) -> Result<SymbolTable, String> {
+        fs::read_to_string(path)
+            .map_err(|e| format!("Error reading symbol file {path}: {e}"))?
+            .parse()
+            .map_err(|e| format!("Error reading symbol file {path}: {e}"))
+    }
+
+    /// Adds a label for the address. An address with several labels is shown by the first one added, while all of them are accepted.
+    ///
+    /// # Parameters
+    ///
+    /// * `label` - The name of the label.
+    /// * `address` - The address the label marks.
+    pub fn insert(&mut self, label: &str, address: u16) {
+        self.labels.entry(address).or_insert_with(|| String::from(label));
+        self.addresses.insert(String::from(label), address);
+    }
+
+    /// Adds a label for the address within a bank. The label is only shown for [that bank](SymbolTable::for_bank), while it is accepted wherever a label is.
+    ///
+    /// # Parameters
+    ///
+    /// * `label` - The name of the label.
+    /// * `bank` - The bank the label is in.
+    /// * `address` - The address the label marks while the bank is in the window.
+    pub fn insert_banked(&mut self, label: &str, bank: u8, address: u16) {
+        self.banked_labels.entry((bank, address)).or_insert_with(|| String::from(label));
+        self.addresses.insert(String::from(label), address);
+    }
+
+    /// Returns the labels shown while the bank is in the window: those outside of any bank, with the bank's own labels shown over them.
+    ///
+    /// # Parameters
+    ///
+    /// * `bank` - The bank in the window.
+    #[must_use]
+    pub fn for_bank(&self, bank: u8) -> SymbolTable {
+        let mut symbols = self.clone();
+        for (&(_, address), label) in self.banked_labels.range((bank, 0)..=(bank, u16::MAX)) {
+            symbols.labels.insert(address, label.clone());
+        }
+
+        symbols
+    }
+
+    /// Returns the number of labels.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.addresses.len()
+    }
+
+    /// Returns true if there are no labels.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.addresses.is_empty()
+    }
+
+    /// Returns the label shown for the address, or `None` if it has none.
+    ///
+    /// # Parameters
+    ///
+    /// * `address` - The address to look up.
+    #[must_use]
+    pub fn label(&self, address: u16) -> Option<&str> {
+        self.labels.get(&address).map(String::as_str)
+    }
+
+    /// Returns the address the label marks, or `None` if there is no such label.
+    ///
+    /// # Parameters
+    ///
+    /// * `label` - The name of the label.
+    #[must_use]
+    pub fn address(&self, label: &str) -> Option<u16> {
+        self.addresses.get(label).copied()
+    }
+
+    /// Returns the address along with its label if it has one, e.g. `0x2A4 (loop)` or `0x2A6`.
+    ///
+    /// # Parameters
+    ///
+    /// * `address` - The address to describe.
+    #[must_use]
+    pub fn describe(&self, address: u16) -> String {
+        match self.label(address) {
+            Some(label) => format!("0x{address:03X} ({label})"),
+            None => format!("0x{address:03X}")
+        }
+    }
+
+    /// Returns the address written as a label, in hexadecimal with a `0x` prefix, or in decimal.
+    ///
+    /// # Parameters
+    ///
+    /// * `text` - The label or address, e.g. `loop` or `0x2A4`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the text is neither a known label nor a number which fits in the 16-bit address space.
+    pub fn resolve(&self, text: &str) -> Result<u16, String> {
+        let text = text.trim();
+        match self.address(text) {
+            Some(address) => Ok(address),
+            None => parse_number(text).ok_or_else(|| format!("{text} is neither a label nor an address."))
+        }
+    }
+}
+
+impl FromStr for SymbolTable {
+    type Err = String;
+
+    /// Parses the contents of a symbol file, returning an `Err` naming the first malformed line.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut symbols = SymbolTable::new();
+        let mut bank = None;
+        let bank_window = BANK_WINDOW_ADDRESS..BANK_WINDOW_ADDRESS + BANK_SIZE;
+        for (index, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+
+            let tokens: Vec<&str> = line.split(|c: char| c.is_whitespace() || c == '=')
+                .map(|token| token.trim_matches(':'))
+                .filter(|token| !token.is_empty())
+                .collect();
+            if tokens.first() == Some(&BANK_DIRECTIVE) {
+                let number = match tokens[..] {
+                    [_, number] => parse_number(number).and_then(|number| u8::try_from(number).ok()).filter(|&number| usize::from(number) < MAX_BANKS),
+                    _ => None
+                };
+                bank = Some(number.ok_or_else(|| format!("line {} is not a bank directive: {line}", index + 1))?);
+                continue;
+            }
+
+            let symbol = match tokens[..] {
+                [first, second] => match (parse_number(first), parse_number(second)) {
+                    (None, Some(address)) => Some((first, address)),
+                    (Some(address), None) => Some((second, address)),
+                    _ => None
+                },
+                _ => None
+            };
+            let (label, address) = symbol.ok_or_else(|| format!("line {} is not a label and an address: {line}", index + 1))?;
+            match bank {
+                Some(bank) if bank_window.contains(&usize::from(address)) => symbols.insert_banked(label, bank, address),
+                _ => symbols.insert(label, address)
+            }
+        }
+
+        Ok(symbols)
+    }
+}
+
+/// Returns the number written in hexadecimal with a `0x` prefix or in decimal, or `None` if it is not a number which fits in 16 bits.
+///
+/// # Parameters
+///
+/// * `text` - The number as written.
+fn parse_number(text: &str) -> Option<u16> {
+    match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hexadecimal) => u16::from_str_radix(hexadecimal, 16).ok(),
+        None => text.parse().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_symbol_file() {
+        let symbols: SymbolTable = "# Labels\nmain 0x200\n0x20A loop\n: draw-player = 522\n\nstart 0x200\n".parse().unwrap();
+        assert_eq!(symbols.len(), 4, "Labels not all read.");
+        assert_eq!((symbols.address("loop"), symbols.address("draw-player")), (Some(0x20A), Some(0x20A)), "Labels read incorrectly.");
+        assert_eq!(symbols.label(0x200), Some("main"), "First label of an address not shown.");
+        assert_eq!(symbols.address("start"), Some(0x200), "Second label of an address not accepted.");
+        assert_eq!((symbols.describe(0x20A), symbols.describe(0x20C)), (String::from("0x20A (loop)"), String::from("0x20C")), "Addresses described incorrectly.");
+
+        assert!("main".parse::<SymbolTable>().is_err(), "Label without an address parsed.");
+        assert_eq!("main 0x200\nmain 0x202 0x204".parse::<SymbolTable>(), Err(String::from("line 2 is not a label and an address: main 0x202 0x204")), "Malformed line not named.");
+    }
+
+    #[test]
+    fn parse_bank_directives() {
+        let symbols: SymbolTable = "main 0x200\nwindow 0x800\n.bank 0\nlevel-one 0x800\nswitch 0x204\n.bank 1\nlevel-two 0x800\n".parse().unwrap();
+        assert_eq!(symbols.len(), 5, "Labels not all read.");
+        assert_eq!((symbols.label(0x204), symbols.label(0x800)), (Some("switch"), Some("window")), "Labels outside the window not kept outside of the banks.");
+        assert_eq!(symbols.for_bank(0).label(0x800), Some("level-one"), "First bank's label not shown.");
+        assert_eq!(symbols.for_bank(1).label(0x800), Some("level-two"), "Second bank's label not shown.");
+        assert_eq!(symbols.for_bank(2).label(0x800), Some("window"), "Label outside of the banks not shown for a bank without its own.");
+        assert_eq!(symbols.resolve("level-two"), Ok(0x800), "Banked label not resolved.");
+
+        assert_eq!(".bank".parse::<SymbolTable>(), Err(String::from("line 1 is not a bank directive: .bank")), "Bank directive without a number parsed.");
+        assert!(".bank 0x100".parse::<SymbolTable>().is_err(), "Bank past the last one parsed.");
+    }
+
+    #[test]
+    fn resolve_labels() {
+        let symbols: SymbolTable = "loop 0x2A4".parse().unwrap();
+        assert_eq!(symbols.resolve("loop"), Ok(0x2A4), "Label not resolved.");
+        assert_eq!(symbols.resolve("0x2A6"), Ok(0x2A6), "Hexadecimal address not resolved.");
+        assert_eq!(symbols.resolve("512"), Ok(0x200), "Decimal address not resolved.");
+        assert!(symbols.resolve("start").is_err(), "Unknown label resolved.");
+    }
+}
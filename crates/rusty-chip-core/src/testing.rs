@@ -0,0 +1,601 @@
+//! A module of test doubles for the [backend traits](crate::backend), which record what is asked of them so that tests can check how the emulator drives its frontend rather than its internal fields.
+//! Clones of a double share its record, so one clone can be handed to the interpreter (e.g. through its [builder](crate::builder::InterpreterBuilder)) while the test keeps the other to inspect.
+//! It also has helpers for comparing the display against an expected frame written as text (a row of characters per display row), so the expected frames can sit in the tests themselves.
+//! When a frame does not match, a PNG with the expected frame, the actual frame, and the differences side by side is written to [`FRAME_DIFF_DIR`](FRAME_DIFF_DIR) so the regression can be reviewed by eye rather than by comparing hashes.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use crate::audio::{AudioCommand, AudioError};
+use crate::backend::{AudioSink, Display, InputSource, MessageKind, Rect};
+use crate::interpreter::Interpreter;
+use crate::palette::Colour;
+use crate::screenshot::encode_png;
+
+/// The number of keys on the CHIP-8 keypad, which a [`MockInput`](MockInput) maps its first host keys onto.
+const KEYPAD_KEY_COUNT: u8 = 16;
+
+/// The directory the PNGs of mismatched frames are written to, relative to the working directory of the tests.
+pub const FRAME_DIFF_DIR: &str = "target/frame-diffs";
+
+/// The characters used for a pixel in the text form of a frame, indexed by its XO-CHIP planes.
+const PIXEL_CHARACTERS: [char; 4] = ['.', '#', '+', '@'];
+
+/// The number of PNG pixels along each side of a display pixel.
+const PNG_SCALE: usize = 4;
+
+/// The number of PNG pixels between the panels.
+const PANEL_GAP: usize = 8;
+
+/// The colours of the expected and actual panels, indexed by a pixel's planes.
+const PLANE_COLOURS: [[u8; 3]; 4] = [[0x00, 0x00, 0x00], [0xFF, 0xFF, 0xFF], [0x00, 0xA0, 0xFF], [0xFF, 0xB0, 0x00]];
+
+/// The colour of a pixel which matches but is lit, dimmed so the differences stand out.
+const MATCH_COLOUR: [u8; 3] = [0x40, 0x40, 0x40];
+
+/// The colour of a pixel which is lit in the actual frame but not the expected one.
+const EXTRA_COLOUR: [u8; 3] = [0xFF, 0x30, 0x30];
+
+/// The colour of a pixel which is lit in the expected frame but not the actual one.
+const MISSING_COLOUR: [u8; 3] = [0x30, 0x60, 0xFF];
+
+/// The colour of a pixel which is lit in both frames but in different planes.
+const CHANGED_COLOUR: [u8; 3] = [0xFF, 0x00, 0xFF];
+
+/// The colour of the gaps between the panels.
+const GAP_COLOUR: [u8; 3] = [0x80, 0x80, 0x80];
+
+/// Locks a record, carrying on with its contents if a test panicked while holding it.
+///
+/// # Parameters
+///
+/// * `record` - The record to lock.
+fn lock<T>(record: &Mutex<T>) -> MutexGuard<'_, T> {
+    record.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Denotes a call made to a [`MockDisplay`](MockDisplay), along with its arguments.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DisplayCall {
+    Clear(Colour),
+    FillRects(Colour, u8, Vec<Rect>),
+    DrawRects(Colour, Vec<Rect>),
+    Present,
+    SetTitle(String),
+    ShowMessage(MessageKind, String, String),
+    ShowChoice(MessageKind, String, String, Vec<String>)
+}
+
+/// A [`Display`](Display) which records every call made to it instead of drawing.
+/// Choices offered to it are answered with the choice it was [given](MockDisplay::with_choice), or nothing.
+#[derive(Debug, Clone, Default)]
+pub struct MockDisplay {
+    calls: Arc<Mutex<Vec<DisplayCall>>>,
+    choice: Option<usize>
+}
+
+impl MockDisplay {
+    /// Returns a display which has not been called yet.
+    #[must_use]
+    pub fn new() -> MockDisplay {
+        MockDisplay::default()
+    }
+
+    /// Returns a display which answers every choice offered to it with the provided option.
+    ///
+    /// # Parameters
+    ///
+    /// * `choice` - The index of the option to choose, or `None` to choose nothing.
+    #[must_use]
+    pub fn with_choice(choice: Option<usize>) -> MockDisplay {
+        MockDisplay { choice, ..MockDisplay::default() }
+    }
+
+    /// Returns every call made to the display so far, in order.
+    #[must_use]
+    pub fn calls(&self) -> Vec<DisplayCall> {
+        lock(&self.calls).clone()
+    }
+
+    /// Returns every call made to the display so far, in order, and forgets them.
+    pub fn take_calls(&self) -> Vec<DisplayCall> {
+        core::mem::take(&mut *lock(&self.calls))
+    }
+
+    /// Returns the number of times the display has been presented.
+    #[must_use]
+    pub fn present_count(&self) -> usize {
+        lock(&self.calls).iter().filter(|call| **call == DisplayCall::Present).count()
+    }
+
+    /// Returns the calls of the last frame presented, from the clear starting it up to (but not including) the present, or an empty list if no frame has been presented.
+    #[must_use]
+    pub fn last_frame(&self) -> Vec<DisplayCall> {
+        let calls = lock(&self.calls);
+        let Some(end) = calls.iter().rposition(|call| *call == DisplayCall::Present) else {
+            return Vec::new();
+        };
+        let start = calls[..end].iter().rposition(|call| matches!(call, DisplayCall::Clear(_))).unwrap_or(0);
+
+        calls[start..end].to_vec()
+    }
+
+    /// Returns the rectangles filled with the colour in the last frame presented.
+    ///
+    /// # Parameters
+    ///
+    /// * `colour` - The colour of the rectangles.
+    #[must_use]
+    pub fn filled_rects(&self, colour: Colour) -> Vec<Rect> {
+        self.last_frame().into_iter()
+            .filter_map(|call| match call {
+                DisplayCall::FillRects(fill_colour, _, rects) if fill_colour == colour => Some(rects),
+                _ => None
+            })
+            .flatten()
+            .collect()
+    }
+
+    /// Returns the last title the window was given, or `None` if it has not been given one.
+    #[must_use]
+    pub fn title(&self) -> Option<String> {
+        lock(&self.calls).iter().rev().find_map(|call| match call {
+            DisplayCall::SetTitle(title) => Some(title.clone()),
+            _ => None
+        })
+    }
+
+    /// Records a call to the display.
+    ///
+    /// # Parameters
+    ///
+    /// * `call` - The call made.
+    fn record(&self, call: DisplayCall) {
+        lock(&self.calls).push(call);
+    }
+}
+
+impl Display for MockDisplay {
+    fn clear(&mut self, colour: Colour) {
+        self.record(DisplayCall::Clear(colour));
+    }
+
+    fn fill_rects(&mut self, colour: Colour, alpha: u8, rects: &[Rect]) -> Result<(), String> {
+        self.record(DisplayCall::FillRects(colour, alpha, rects.to_vec()));
+        Ok(())
+    }
+
+    fn draw_rects(&mut self, colour: Colour, rects: &[Rect]) -> Result<(), String> {
+        self.record(DisplayCall::DrawRects(colour, rects.to_vec()));
+        Ok(())
+    }
+
+    fn present(&mut self) {
+        self.record(DisplayCall::Present);
+    }
+
+    fn set_title(&mut self, title: &str) -> Result<(), String> {
+        self.record(DisplayCall::SetTitle(title.to_string()));
+        Ok(())
+    }
+
+    fn show_message(&self, kind: MessageKind, title: &str, message: &str) -> Result<(), String> {
+        self.record(DisplayCall::ShowMessage(kind, title.to_string(), message.to_string()));
+        Ok(())
+    }
+
+    fn show_choice(&self, kind: MessageKind, title: &str, message: &str, choices: &[&str]) -> Result<Option<usize>, String> {
+        self.record(DisplayCall::ShowChoice(kind, title.to_string(), message.to_string(), choices.iter().map(ToString::to_string).collect()));
+        Ok(self.choice)
+    }
+}
+
+/// An [`AudioSink`](AudioSink) which records every command sent to it instead of playing sound.
+/// Once [disconnected](MockAudio::disconnect), it fails every command as an output which has stopped running does.
+#[derive(Debug, Clone, Default)]
+pub struct MockAudio {
+    commands: Arc<Mutex<Vec<AudioCommand>>>,
+    is_disconnected: Arc<Mutex<bool>>
+}
+
+impl MockAudio {
+    /// Returns an output which has not been sent anything yet.
+    #[must_use]
+    pub fn new() -> MockAudio {
+        MockAudio::default()
+    }
+
+    /// Returns every command sent to the output so far, in order.
+    #[must_use]
+    pub fn commands(&self) -> Vec<AudioCommand> {
+        lock(&self.commands).clone()
+    }
+
+    /// Returns every command sent to the output so far, in order, and forgets them.
+    pub fn take_commands(&self) -> Vec<AudioCommand> {
+        core::mem::take(&mut *lock(&self.commands))
+    }
+
+    /// Returns every time the gate of the voice was opened (true) or closed (false), in order, i.e. the beep turning on and off.
+    ///
+    /// # Parameters
+    ///
+    /// * `voice` - The index of the voice.
+    #[must_use]
+    pub fn gates(&self, voice: usize) -> Vec<bool> {
+        lock(&self.commands).iter()
+            .filter_map(|command| match command {
+                AudioCommand::SetGate(gate_voice, is_open) if *gate_voice == voice => Some(*is_open),
+                _ => None
+            })
+            .collect()
+    }
+
+    /// Returns true if the gate of the voice was last opened, i.e. the voice is beeping.
+    ///
+    /// # Parameters
+    ///
+    /// * `voice` - The index of the voice.
+    #[must_use]
+    pub fn is_playing(&self, voice: usize) -> bool {
+        self.gates(voice).last().copied().unwrap_or(false)
+    }
+
+    /// Makes every later command fail, as though the output had been closed.
+    pub fn disconnect(&self) {
+        *lock(&self.is_disconnected) = true;
+    }
+}
+
+impl AudioSink for MockAudio {
+    fn send(&self, command: AudioCommand) -> Result<(), AudioError> {
+        if *lock(&self.is_disconnected) {
+            return Err(AudioError::Disconnected(command));
+        }
+
+        lock(&self.commands).push(command);
+        Ok(())
+    }
+}
+
+/// An [`InputSource`](InputSource) whose host keys are numbers, which map onto the CHIP-8 key of the same value (`0x0` to `0xF`) unless [remapped](MockInput::map).
+/// Host keys are named `Key` followed by their number, e.g. `Key5`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockInput {
+    keys: BTreeMap<u32, u8>
+}
+
+impl MockInput {
+    /// Returns an input whose host keys `0x0` to `0xF` map onto the matching CHIP-8 keys.
+    #[must_use]
+    pub fn new() -> MockInput {
+        MockInput { keys: (0..KEYPAD_KEY_COUNT).map(|key| (u32::from(key), key)).collect() }
+    }
+
+    /// Returns the input with the host key mapped onto the CHIP-8 key, or removed from the keypad.
+    ///
+    /// # Parameters
+    ///
+    /// * `host_key` - The host key.
+    /// * `key` - The CHIP-8 key, or `None` to leave the host key off the keypad.
+    #[must_use]
+    pub fn map(mut self, host_key: u32, key: Option<u8>) -> MockInput {
+        match key {
+            Some(key) => self.keys.insert(host_key, key),
+            None => self.keys.remove(&host_key)
+        };
+        self
+    }
+}
+
+impl Default for MockInput {
+    fn default() -> Self {
+        MockInput::new()
+    }
+}
+
+impl InputSource for MockInput {
+    type Key = u32;
+
+    fn keypad_key(&self, key: u32) -> Option<u8> {
+        self.keys.get(&key).copied()
+    }
+
+    fn key_name(&self, key: u32) -> String {
+        format!("Key{key}")
+    }
+}
+
+/// Stores the pixels of the display at a point in time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    width: usize,
+    height: usize,
+    /// The XO-CHIP planes each pixel is lit in (0 for off), row by row.
+    pixels: Vec<u8>
+}
+
+impl Frame {
+    /// Returns the frame currently on the interpreter's display.
+    ///
+    /// # Parameters
+    ///
+    /// * `interpreter` - The interpreter to capture.
+    #[must_use]
+    pub fn capture(interpreter: &Interpreter) -> Frame {
+        let pixels = interpreter.display_buffer().zip(interpreter.second_plane_buffer())
+            .map(|(first, second)| u8::from(first) | (u8::from(second) << 1))
+            .collect();
+
+        Frame { width: interpreter.display_width() as usize, height: interpreter.display_height() as usize, pixels }
+    }
+
+    /// Returns the number of pixels which differ from the other frame, counting every pixel if the sizes differ.
+    ///
+    /// # Parameters
+    ///
+    /// * `other` - The frame to compare against.
+    #[must_use]
+    pub fn count_differences(&self, other: &Frame) -> usize {
+        if (self.width, self.height) != (other.width, other.height) {
+            return self.pixels.len().max(other.pixels.len());
+        }
+
+        self.pixels.iter().zip(&other.pixels).filter(|(pixel, other_pixel)| pixel != other_pixel).count()
+    }
+
+    /// Returns the planes of the pixel, or 0 if it is outside the frame.
+    ///
+    /// # Parameters
+    ///
+    /// * `x` - The column of the pixel.
+    /// * `y` - The row of the pixel.
+    fn pixel(&self, x: usize, y: usize) -> u8 {
+        if x < self.width && y < self.height { self.pixels[y * self.width + x] } else { 0 }
+    }
+}
+
+impl fmt::Display for Frame {
+    /// Writes the frame as text, a line per row with `.` for an unlit pixel and `#`, `+`, or `@` for one lit in the first, second, or both planes.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for row in self.pixels.chunks(self.width) {
+            let line: String = row.iter().map(|pixel| PIXEL_CHARACTERS[usize::from(*pixel)]).collect();
+            writeln!(f, "{line}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Frame {
+    type Err = String;
+
+    /// Reads a frame in the text form written by its `Display` implementation. Blank lines and leading whitespace are ignored.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rows: Vec<&str> = s.lines().map(str::trim).filter(|line| !line.is_empty()).collect();
+        let width = rows.first().map_or(0, |row| row.chars().count());
+        let mut pixels = Vec::with_capacity(width * rows.len());
+        for row in &rows {
+            if row.chars().count() != width {
+                return Err(format!("Frame row has the wrong width: {row}"));
+            }
+            for character in row.chars() {
+                let planes = PIXEL_CHARACTERS.iter().position(|pixel_character| *pixel_character == character).ok_or_else(|| format!("Invalid frame pixel: {character}"))?;
+                pixels.push(u8::try_from(planes).unwrap_or_default());
+            }
+        }
+
+        Ok(Frame { width, height: rows.len(), pixels })
+    }
+}
+
+/// Returns a PNG of the expected frame, the actual frame, and their differences side by side.
+/// In the differences, pixels lit only in the actual frame are red, pixels lit only in the expected frame are blue, pixels lit in different planes are magenta, and lit pixels which match are dimmed.
+///
+/// # Parameters
+///
+/// * `expected` - The frame the test expected.
+/// * `actual` - The frame the test produced.
+#[must_use]
+pub fn diff_png(expected: &Frame, actual: &Frame) -> Vec<u8> {
+    let panel_width = expected.width.max(actual.width);
+    let panel_height = expected.height.max(actual.height);
+    let width = (panel_width * 3 * PNG_SCALE) + (PANEL_GAP * 2);
+    let height = panel_height * PNG_SCALE;
+
+    let mut image = Vec::with_capacity((width * 3 + 1) * height);
+    for png_y in 0..height {
+        // Every row starts with its filter type, which is always none
+        image.push(0);
+        for png_x in 0..width {
+            let panel = png_x / (panel_width * PNG_SCALE + PANEL_GAP);
+            let panel_x = png_x % (panel_width * PNG_SCALE + PANEL_GAP);
+            let colour = if panel_x >= panel_width * PNG_SCALE {
+                GAP_COLOUR
+            } else {
+                let (x, y) = (panel_x / PNG_SCALE, png_y / PNG_SCALE);
+                let (expected_pixel, actual_pixel) = (expected.pixel(x, y), actual.pixel(x, y));
+                match panel {
+                    0 => PLANE_COLOURS[usize::from(expected_pixel)],
+                    1 => PLANE_COLOURS[usize::from(actual_pixel)],
+                    _ => match (expected_pixel, actual_pixel) {
+                        (0, 0) => PLANE_COLOURS[0],
+                        (0, _) => EXTRA_COLOUR,
+                        (_, 0) => MISSING_COLOUR,
+                        (expected_pixel, actual_pixel) if expected_pixel == actual_pixel => MATCH_COLOUR,
+                        _ => CHANGED_COLOUR
+                    }
+                }
+            };
+            image.extend_from_slice(&colour);
+        }
+    }
+
+    encode_png(width, height, &image)
+}
+
+/// Asserts that the interpreter's display shows the expected frame.
+///
+/// # Parameters
+///
+/// * `interpreter` - The interpreter to check.
+/// * `expected` - The frame which should be displayed, in the text form of a [`Frame`](Frame).
+/// * `name` - The name of the PNG written if the frames differ, unique to the test.
+///
+/// # Panics
+///
+/// Will panic if the expected frame cannot be read or the display does not match it, naming the PNG of the differences.
+pub fn assert_frame(interpreter: &Interpreter, expected: &str, name: &str) {
+    let expected: Frame = expected.parse().unwrap_or_else(|e| panic!("Invalid expected frame: {e}"));
+    assert_frame_eq(&expected, &Frame::capture(interpreter), name);
+}
+
+/// Asserts that two frames match.
+///
+/// # Parameters
+///
+/// * `expected` - The frame the test expected.
+/// * `actual` - The frame the test produced.
+/// * `name` - The name of the PNG written if the frames differ, unique to the test.
+///
+/// # Panics
+///
+/// Will panic if the frames differ, naming the PNG of the differences.
+pub fn assert_frame_eq(expected: &Frame, actual: &Frame, name: &str) {
+    let differences = expected.count_differences(actual);
+    if differences == 0 {
+        return;
+    }
+
+    let location = match write_diff_png(expected, actual, name) {
+        Ok(path) => format!("See {} for the differences.", path.display()),
+        Err(e) => format!("The differences could not be saved: {e}")
+    };
+    panic!("Frame {name} has {differences} pixel(s) which differ from the expected frame. {location}\nExpected:\n{expected}Actual:\n{actual}");
+}
+
+/// Writes the PNG of the differences between the frames to [`FRAME_DIFF_DIR`](FRAME_DIFF_DIR), returning its path.
+///
+/// # Parameters
+///
+/// * `expected` - The frame the test expected.
+/// * `actual` - The frame the test produced.
+/// * `name` - The name of the PNG.
+///
+/// # Errors
+///
+/// Returns an `Err` if the directory or PNG cannot be written.
+fn write_diff_png(expected: &Frame, actual: &Frame, name: &str) -> std::io::Result<PathBuf> {
+    fs::create_dir_all(FRAME_DIFF_DIR)?;
+    let path = Path::new(FRAME_DIFF_DIR).join(format!("{name}.png"));
+    fs::write(&path, diff_png(expected, actual))?;
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::screenshot::PNG_SIGNATURE;
+
+    /// The top left of the display after drawing the 0 digit of the font there, cropped to the sprite.
+    const ZERO_DIGIT: &str = "
+        ####
+        #..#
+        #..#
+        #..#
+        ####
+    ";
+
+    #[test]
+    fn display_records_frames() {
+        let display = MockDisplay::new();
+        let mut window = display.clone();
+        let colour = Colour::rgb(0xFF, 0x00, 0x00);
+        window.clear(Colour::rgb(0, 0, 0));
+        window.fill_rects(colour, u8::MAX, &[Rect::new(0, 0, 1, 1)]).unwrap();
+        window.present();
+        window.clear(Colour::rgb(0, 0, 0));
+        window.fill_rects(colour, u8::MAX, &[Rect::new(1, 1, 1, 1)]).unwrap();
+        window.present();
+        window.set_title("Game").unwrap();
+
+        assert_eq!(display.present_count(), 2, "Presents not recorded.");
+        assert_eq!(display.filled_rects(colour), [Rect::new(1, 1, 1, 1)], "Rectangles not taken from the last frame.");
+        assert_eq!(display.title().as_deref(), Some("Game"), "Title not recorded.");
+        assert_eq!(display.take_calls().len(), 7, "Calls not recorded.");
+        assert!(display.calls().is_empty(), "Calls not forgotten.");
+        assert_eq!(MockDisplay::with_choice(Some(1)).show_choice(MessageKind::Information, "", "", &["a", "b"]), Ok(Some(1)), "Choice not answered.");
+    }
+
+    #[test]
+    fn audio_records_gates() {
+        let audio = MockAudio::new();
+        audio.set_gate(0, true).unwrap();
+        audio.send(AudioCommand::SetFrequency(0, 440.0)).unwrap();
+        audio.set_gate(1, true).unwrap();
+        audio.set_gate(0, false).unwrap();
+        assert_eq!(audio.gates(0), [true, false], "Gates not recorded.");
+        assert!(!audio.is_playing(0) && audio.is_playing(1), "Playing voices incorrect.");
+
+        audio.disconnect();
+        assert_eq!(audio.set_gate(0, true), Err(AudioError::Disconnected(AudioCommand::SetGate(0, true))), "Disconnected output accepted a command.");
+        assert_eq!(audio.commands().len(), 4, "Failed command recorded.");
+    }
+
+    #[test]
+    fn input_maps_keys() {
+        let input = MockInput::new().map(0x20, Some(0x5)).map(0x3, None);
+        assert_eq!(input.keypad_key(0xA), Some(0xA), "Key not mapped onto the matching keypad key.");
+        assert_eq!(input.keypad_key(0x20), Some(0x5), "Remapped key not mapped.");
+        assert_eq!(input.keypad_key(0x3), None, "Removed key still mapped.");
+        assert_eq!(input.key_name(0x20), "Key32", "Key named incorrectly.");
+    }
+
+    #[test]
+    fn frame_text_round_trip() {
+        let frame: Frame = ZERO_DIGIT.parse().unwrap();
+        assert_eq!((frame.width, frame.height), (4, 5), "Frame size read incorrectly.");
+        assert_eq!(frame.to_string().parse::<Frame>(), Ok(frame), "Frame changed by a round trip.");
+        assert!("#.\n#".parse::<Frame>().is_err(), "Ragged frame read.");
+        assert!("#x".parse::<Frame>().is_err(), "Invalid pixel read.");
+    }
+
+    #[test]
+    fn capture_frame() {
+        let mut interpreter = Interpreter::new();
+        interpreter.load_game(&[0xD0, 0x05]);
+        interpreter.handle_cycle();
+        interpreter.handle_frame();
+
+        let frame = Frame::capture(&interpreter);
+        assert_eq!((frame.width, frame.height), (64, 32), "Frame captured at the wrong size.");
+        let expected: Frame = ZERO_DIGIT.parse().unwrap();
+        for y in 0..frame.height {
+            for x in 0..frame.width {
+                assert_eq!(frame.pixel(x, y), expected.pixel(x, y), "Pixel ({x}, {y}) captured incorrectly.");
+            }
+        }
+    }
+
+    #[test]
+    fn mismatched_frames_write_diff() {
+        let expected: Frame = ZERO_DIGIT.parse().unwrap();
+        let actual: Frame = ZERO_DIGIT.replace("#..#\n        ####", "#..#\n        ###.").parse().unwrap();
+        assert_eq!(expected.count_differences(&actual), 1, "Differences counted incorrectly.");
+
+        let png = diff_png(&expected, &actual);
+        assert_eq!(png[..PNG_SIGNATURE.len()], PNG_SIGNATURE, "PNG signature missing.");
+        assert_eq!(png[16..24], [0, 0, 0, 64, 0, 0, 0, 20], "PNG size incorrect.");
+
+        let result = std::panic::catch_unwind(|| assert_frame_eq(&expected, &actual, "testing_mismatch"));
+        let path = Path::new(FRAME_DIFF_DIR).join("testing_mismatch.png");
+        assert!(result.is_err(), "Mismatched frames not reported.");
+        assert_eq!(fs::read(&path).unwrap(), png, "Diff PNG not written.");
+        fs::remove_file(path).unwrap();
+    }
+}
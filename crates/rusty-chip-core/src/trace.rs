@@ -0,0 +1,105 @@
+//! A module to log every instruction the game runs, for comparing its behaviour against reference emulators when chasing quirk bugs.
+//! Each line holds the instruction (address, bytes, and mnemonic), the registers it changed, and register I and the timers afterwards, separated by tabs so that logs from two runs can be diffed.
+//! Cycles which do not run an instruction (e.g. waiting for a key or the display) are not logged.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+use crate::clipboard;
+use crate::interpreter::{Interpreter, REGISTERS_SIZE};
+
+/// Writes one line per instruction run to a log.
+pub struct TraceLogger<W: Write> {
+    writer: W
+}
+
+impl TraceLogger<BufWriter<File>> {
+    /// Creates the trace log at the provided path.
+    ///
+    /// # Parameters
+    ///
+    /// * `path` - The path of the trace log.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the file cannot be created.
+    pub fn create(path: &str) -> io::Result<Self> {
+        Ok(TraceLogger::new(BufWriter::new(File::create(path)?)))
+    }
+}
+
+impl<W: Write> TraceLogger<W> {
+    /// Wraps the provided writer.
+    ///
+    /// # Parameters
+    ///
+    /// * `writer` - Where the trace is written.
+    pub fn new(writer: W) -> Self {
+        TraceLogger { writer }
+    }
+
+    /// Runs a single instruction cycle of the interpreter, logging the instruction if one was run, e.g. `0x200: 6105 LD V1, 0x05`, `V1=00->05`, and `I=0x000 DT=0 ST=0` separated by tabs.
+    /// An instruction which halts the game is logged along with the reason.
+    ///
+    /// # Parameters
+    ///
+    /// * `interpreter` - The interpreter to run.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the line cannot be written.
+    pub fn trace_cycle(&mut self, interpreter: &mut Interpreter) -> io::Result<()> {
+        if !interpreter.is_ready_to_execute() {
+            interpreter.handle_cycle();
+            return Ok(());
+        }
+
+        let instruction = clipboard::disassembly_line(interpreter);
+        let registers: [u8; REGISTERS_SIZE] = *interpreter.registers();
+        interpreter.handle_cycle();
+
+        let changes: Vec<String> = registers.iter().zip(interpreter.registers()).enumerate()
+            .filter(|(_, (before, after))| before != after)
+            .map(|(register, (before, after))| format!("V{register:X}={before:02X}->{after:02X}"))
+            .collect();
+        let changes = if changes.is_empty() { String::from("-") } else { changes.join(",") };
+        write!(self.writer, "{instruction}\t{changes}\tI=0x{:03X} DT={} ST={}", interpreter.register_i(), interpreter.delay_timer(), interpreter.sound_timer())?;
+        match interpreter.halt_reason() {
+            Some(reason) => writeln!(self.writer, "\tHalted: {reason}"),
+            None => writeln!(self.writer)
+        }
+    }
+
+    /// Returns the underlying writer.
+    #[must_use]
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trace_instructions() {
+        let mut interpreter = Interpreter::new();
+        interpreter.load_game(&[0x61, 0x05, 0xA3, 0x00, 0xF1, 0x15, 0xF0, 0x0A, 0x00, 0x00]);
+        let mut trace = TraceLogger::new(Vec::new());
+        for _ in 0..5 {
+            trace.trace_cycle(&mut interpreter).unwrap();
+        }
+        interpreter.press_key(0x3);
+        interpreter.release_key(0x3);
+        trace.trace_cycle(&mut interpreter).unwrap();
+
+        let log = String::from_utf8(trace.into_inner()).unwrap();
+        assert_eq!(log.lines().collect::<Vec<_>>(), [
+            "0x200: 6105 LD V1, 0x05\tV1=00->05\tI=0x000 DT=0 ST=0",
+            "0x202: A300 LD I, 0x300\t-\tI=0x300 DT=0 ST=0",
+            "0x204: F115 LD DT, V1\t-\tI=0x300 DT=5 ST=0",
+            "0x206: F00A LD V0, K\t-\tI=0x300 DT=5 ST=0",
+            "0x208: 0000 SYS 0x000\t-\tI=0x300 DT=5 ST=0\tHalted: The game exited."
+        ], "Trace written incorrectly.");
+    }
+}
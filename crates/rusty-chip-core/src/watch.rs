@@ -0,0 +1,416 @@
+//! A module to contain watch expressions, which are pinned by the user and evaluated against the interpreter every frame.
+//! Expressions can read the registers (`V0`-`VF`, `I`, `PC`, `DT`, `ST`), memory bytes (`[I + 1]`), and numbers (`0x1F` or `31`), combined with simple arithmetic (`+ - * / % & | ^`) and parentheses.
+//! The values can be logged to a CSV file with one row per frame for offline graphing.
+
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::iter::Peekable;
+use std::str::{Chars, FromStr};
+
+use crate::interpreter::Interpreter;
+
+/// Denotes a value which can be read from the interpreter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Operand {
+    Register(usize),
+    RegisterI,
+    ProgramCounter,
+    DelayTimer,
+    SoundTimer
+}
+
+/// Denotes an arithmetic operation between two values.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BinaryOperator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+    And,
+    Or,
+    Xor
+}
+
+/// Denotes a node of a parsed expression.
+#[derive(Debug, Clone, PartialEq)]
+enum Expression {
+    Number(i64),
+    Operand(Operand),
+    Memory(Box<Expression>),
+    Negate(Box<Expression>),
+    Binary(Box<Expression>, BinaryOperator, Box<Expression>)
+}
+
+/// Stores a parsed watch expression along with the text it was parsed from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchExpression {
+    source: String,
+    expression: Expression
+}
+
+impl WatchExpression {
+    /// Returns the text the expression was parsed from.
+    #[must_use]
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Returns the value of the expression for the current state of the interpreter, or `None` if it cannot be evaluated (e.g. division by zero).
+    /// Memory addresses wrap around the size of the RAM.
+    ///
+    /// # Parameters
+    ///
+    /// * `interpreter` - The interpreter to read from.
+    #[must_use]
+    pub fn evaluate(&self, interpreter: &Interpreter) -> Option<i64> {
+        Self::evaluate_expression(&self.expression, interpreter)
+    }
+
+    /// Returns the value of a single node of an expression.
+    ///
+    /// # Parameters
+    ///
+    /// * `expression` - The node to evaluate.
+    /// * `interpreter` - The interpreter to read from.
+    fn evaluate_expression(expression: &Expression, interpreter: &Interpreter) -> Option<i64> {
+        let value = match expression {
+            Expression::Number(value) => *value,
+            Expression::Operand(Operand::Register(register)) => i64::from(interpreter.registers()[*register]),
+            Expression::Operand(Operand::RegisterI) => i64::from(interpreter.register_i()),
+            Expression::Operand(Operand::ProgramCounter) => i64::from(interpreter.program_counter()),
+            Expression::Operand(Operand::DelayTimer) => i64::from(interpreter.delay_timer()),
+            Expression::Operand(Operand::SoundTimer) => i64::from(interpreter.sound_timer()),
+            Expression::Memory(address) => {
+                let ram = interpreter.ram();
+                #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+                let address = Self::evaluate_expression(address, interpreter)?.rem_euclid(ram.len() as i64);
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                i64::from(ram[address as usize])
+            },
+            Expression::Negate(value) => Self::evaluate_expression(value, interpreter)?.wrapping_neg(),
+            Expression::Binary(left, operator, right) => {
+                let left = Self::evaluate_expression(left, interpreter)?;
+                let right = Self::evaluate_expression(right, interpreter)?;
+                match operator {
+                    BinaryOperator::Add => left.wrapping_add(right),
+                    BinaryOperator::Subtract => left.wrapping_sub(right),
+                    BinaryOperator::Multiply => left.wrapping_mul(right),
+                    BinaryOperator::Divide => left.checked_div(right)?,
+                    BinaryOperator::Remainder => left.checked_rem(right)?,
+                    BinaryOperator::And => left & right,
+                    BinaryOperator::Or => left | right,
+                    BinaryOperator::Xor => left ^ right
+                }
+            }
+        };
+
+        Some(value)
+    }
+}
+
+impl FromStr for WatchExpression {
+    type Err = String;
+
+    /// Parses a watch expression, returning an `Err` describing the problem if it is malformed.
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser { chars: source.chars().peekable() };
+        let expression = parser.parse_or()?;
+        parser.skip_whitespace();
+        if let Some(c) = parser.chars.peek() {
+            return Err(format!("Unexpected '{c}' in watch expression \"{source}\"."));
+        }
+
+        Ok(WatchExpression { source: source.trim().to_string(), expression })
+    }
+}
+
+impl Display for WatchExpression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+/// A recursive descent parser for watch expressions.
+/// From lowest to highest precedence: `|`, `^`, `&`, `+ -`, `* / %`, unary `-`, then numbers, operands, memory, and parentheses.
+struct Parser<'s> {
+    chars: Peekable<Chars<'s>>
+}
+
+impl Parser<'_> {
+    /// Skips any whitespace before the next token.
+    fn skip_whitespace(&mut self) {
+        while self.chars.peek().is_some_and(|c| c.is_whitespace()) {
+            self.chars.next();
+        }
+    }
+
+    /// Consumes the next character if it matches any of the provided ones, returning it.
+    ///
+    /// # Parameters
+    ///
+    /// * `expected` - The characters to match.
+    fn consume(&mut self, expected: &[char]) -> Option<char> {
+        self.skip_whitespace();
+        let c = *self.chars.peek()?;
+        if expected.contains(&c) {
+            self.chars.next();
+            Some(c)
+        } else {
+            None
+        }
+    }
+
+    /// Parses a chain of left-associative binary operations.
+    ///
+    /// # Parameters
+    ///
+    /// * `operators` - The characters of the operators at this precedence, along with the operator each denotes.
+    /// * `parse_operand` - Parses the operands, which have a higher precedence.
+    fn parse_binary(&mut self, operators: &[(char, BinaryOperator)], parse_operand: fn(&mut Self) -> Result<Expression, String>) -> Result<Expression, String> {
+        let symbols: Vec<char> = operators.iter().map(|(symbol, _)| *symbol).collect();
+        let mut left = parse_operand(self)?;
+        while let Some(symbol) = self.consume(&symbols) {
+            let operator = operators.iter().find(|(candidate, _)| *candidate == symbol).map(|(_, operator)| *operator).expect("Consumed symbol has no operator.");
+            let right = parse_operand(self)?;
+            left = Expression::Binary(Box::new(left), operator, Box::new(right));
+        }
+
+        Ok(left)
+    }
+
+    fn parse_or(&mut self) -> Result<Expression, String> {
+        self.parse_binary(&[('|', BinaryOperator::Or)], Self::parse_xor)
+    }
+
+    fn parse_xor(&mut self) -> Result<Expression, String> {
+        self.parse_binary(&[('^', BinaryOperator::Xor)], Self::parse_and)
+    }
+
+    fn parse_and(&mut self) -> Result<Expression, String> {
+        self.parse_binary(&[('&', BinaryOperator::And)], Self::parse_additive)
+    }
+
+    fn parse_additive(&mut self) -> Result<Expression, String> {
+        self.parse_binary(&[('+', BinaryOperator::Add), ('-', BinaryOperator::Subtract)], Self::parse_multiplicative)
+    }
+
+    fn parse_multiplicative(&mut self) -> Result<Expression, String> {
+        self.parse_binary(&[('*', BinaryOperator::Multiply), ('/', BinaryOperator::Divide), ('%', BinaryOperator::Remainder)], Self::parse_unary)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expression, String> {
+        if self.consume(&['-']).is_some() {
+            return Ok(Expression::Negate(Box::new(self.parse_unary()?)));
+        }
+
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Expression, String> {
+        self.skip_whitespace();
+        match self.chars.peek().copied() {
+            Some('(') => {
+                self.chars.next();
+                let expression = self.parse_or()?;
+                self.consume(&[')']).ok_or("Missing ')' in watch expression.")?;
+                Ok(expression)
+            },
+            Some('[') => {
+                self.chars.next();
+                let address = self.parse_or()?;
+                self.consume(&[']']).ok_or("Missing ']' in watch expression.")?;
+                Ok(Expression::Memory(Box::new(address)))
+            },
+            Some(c) if c.is_ascii_alphanumeric() => {
+                let mut word = String::new();
+                while let Some(c) = self.chars.peek().copied().filter(char::is_ascii_alphanumeric) {
+                    word.push(c);
+                    self.chars.next();
+                }
+
+                Self::parse_word(&word)
+            },
+            Some(c) => Err(format!("Unexpected '{c}' in watch expression.")),
+            None => Err(String::from("Unexpected end of watch expression."))
+        }
+    }
+
+    /// Parses a number or the name of a register.
+    ///
+    /// # Parameters
+    ///
+    /// * `word` - The alphanumeric text to parse.
+    fn parse_word(word: &str) -> Result<Expression, String> {
+        let upper = word.to_ascii_uppercase();
+        let operand = match upper.as_str() {
+            "I" => Some(Operand::RegisterI),
+            "PC" => Some(Operand::ProgramCounter),
+            "DT" => Some(Operand::DelayTimer),
+            "ST" => Some(Operand::SoundTimer),
+            _ => upper.strip_prefix('V')
+                .filter(|register| register.len() == 1)
+                .and_then(|register| usize::from_str_radix(register, 16).ok())
+                .map(Operand::Register)
+        };
+        if let Some(operand) = operand {
+            return Ok(Expression::Operand(operand));
+        }
+
+        let number = match upper.strip_prefix("0X") {
+            Some(hex) => i64::from_str_radix(hex, 16),
+            None => upper.parse()
+        };
+        number.map(Expression::Number).map_err(|_| format!("Unknown value '{word}' in watch expression."))
+    }
+}
+
+/// Stores the expressions pinned by the user and where (if anywhere) to log them.
+#[derive(Debug, Clone, Default)]
+pub struct WatchConfig {
+    pub expressions: Vec<WatchExpression>,
+    pub csv_path: Option<String>,
+    /// The expressions to graph over the recent frames in the terminal.
+    pub graphs: Vec<WatchExpression>,
+    /// The number of frames each graph covers.
+    pub graph_width: usize
+}
+
+/// Writes the values of watch expressions to a CSV file, one row per frame.
+pub struct WatchLogger {
+    writer: BufWriter<File>
+}
+
+impl WatchLogger {
+    /// Creates the CSV file at the provided path and writes the header row.
+    ///
+    /// # Parameters
+    ///
+    /// * `path` - The path of the CSV file.
+    /// * `expressions` - The expressions which will be logged.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the file cannot be created or written.
+    pub fn create(path: &str, expressions: &[WatchExpression]) -> io::Result<WatchLogger> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        write!(writer, "frame")?;
+        for expression in expressions {
+            write!(writer, ",\"{}\"", expression.source().replace('"', "\"\""))?;
+        }
+        writeln!(writer)?;
+
+        Ok(WatchLogger { writer })
+    }
+
+    /// Writes a row containing the current values of the expressions, leaving a field empty if it could not be evaluated.
+    ///
+    /// # Parameters
+    ///
+    /// * `frame` - The number of the frame being logged.
+    /// * `expressions` - The expressions to log, in the same order as when created.
+    /// * `interpreter` - The interpreter to read from.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the file cannot be written.
+    pub fn log(&mut self, frame: u64, expressions: &[WatchExpression], interpreter: &Interpreter) -> io::Result<()> {
+        write!(self.writer, "{frame}")?;
+        for expression in expressions {
+            match expression.evaluate(interpreter) {
+                Some(value) => write!(self.writer, ",{value}")?,
+                None => write!(self.writer, ",")?
+            }
+        }
+
+        writeln!(self.writer)
+    }
+}
+
+/// Returns a short summary of the values of the expressions, suitable for the window title.
+///
+/// # Parameters
+///
+/// * `expressions` - The expressions to summarize.
+/// * `interpreter` - The interpreter to read from.
+#[must_use]
+pub fn describe(expressions: &[WatchExpression], interpreter: &Interpreter) -> String {
+    expressions.iter()
+        .map(|expression| match expression.evaluate(interpreter) {
+            Some(value) => format!("{expression}={value:#X}"),
+            None => format!("{expression}=ERR")
+        })
+        .collect::<Vec<String>>()
+        .join(" | ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns an interpreter with some recognizable state to watch.
+    fn watched_interpreter() -> Interpreter {
+        let mut interpreter = Interpreter::new();
+        interpreter.load_game(&[0xA3, 0x00, 0x63, 0x1F, 0x64, 0x02]);
+        for _ in 0..3 {
+            interpreter.handle_cycle();
+        }
+
+        interpreter
+    }
+
+    /// Returns the value of the provided expression for the watched interpreter.
+    fn evaluate(source: &str) -> Option<i64> {
+        source.parse::<WatchExpression>().unwrap().evaluate(&watched_interpreter())
+    }
+
+    #[test]
+    fn evaluate_operands() {
+        assert_eq!(evaluate("V3"), Some(0x1F), "Register read incorrectly.");
+        assert_eq!(evaluate("vf"), Some(0x0), "Lowercase register read incorrectly.");
+        assert_eq!(evaluate("I"), Some(0x300), "Register I read incorrectly.");
+        assert_eq!(evaluate("PC"), Some(0x206), "Program counter read incorrectly.");
+        assert_eq!(evaluate("DT + ST"), Some(0x0), "Timers read incorrectly.");
+        assert_eq!(evaluate("[0x200]"), Some(0xA3), "Memory read incorrectly.");
+        assert_eq!(evaluate("[PC - 3]"), Some(0x1F), "Computed memory address read incorrectly.");
+    }
+
+    #[test]
+    fn evaluate_arithmetic() {
+        assert_eq!(evaluate("V3 + V4 * 2"), Some(0x23), "Precedence not respected.");
+        assert_eq!(evaluate("(V3 + V4) * 2"), Some(0x42), "Parentheses not respected.");
+        assert_eq!(evaluate("-V4 + 10"), Some(8), "Negation evaluated incorrectly.");
+        assert_eq!(evaluate("V3 & 0xF | 0x100 ^ 1"), Some(0x10F), "Bitwise operators evaluated incorrectly.");
+        assert_eq!(evaluate("V3 % V4"), Some(1), "Remainder evaluated incorrectly.");
+        assert_eq!(evaluate("V3 / (V4 - 2)"), None, "Division by zero not caught.");
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!("V3 +".parse::<WatchExpression>().is_err(), "Incomplete expression parsed.");
+        assert!("VG".parse::<WatchExpression>().is_err(), "Unknown register parsed.");
+        assert!("(V3".parse::<WatchExpression>().is_err(), "Unclosed parenthesis parsed.");
+        assert!("V3 V4".parse::<WatchExpression>().is_err(), "Trailing input parsed.");
+    }
+
+    #[test]
+    fn describe_values() {
+        let expressions = vec!["V3".parse().unwrap(), "V3 / 0".parse().unwrap()];
+        assert_eq!(describe(&expressions, &watched_interpreter()), "V3=0x1F | V3 / 0=ERR", "Watches described incorrectly.");
+    }
+
+    #[test]
+    fn log_to_csv() {
+        let path = std::env::temp_dir().join(format!("rusty_chip_watch_test_{}.csv", std::process::id()));
+        let path = path.to_str().unwrap();
+        let expressions: Vec<WatchExpression> = vec!["V3".parse().unwrap(), "V3 / 0".parse().unwrap()];
+
+        let mut logger = WatchLogger::create(path, &expressions).unwrap();
+        logger.log(7, &expressions, &watched_interpreter()).unwrap();
+        drop(logger);
+        let contents = std::fs::read_to_string(path);
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(contents.unwrap(), "frame,\"V3\",\"V3 / 0\"\n7,31,\n", "Watches logged incorrectly.");
+    }
+}
@@ -0,0 +1,310 @@
+//! # `RustyChip` Web
+//!
+//! The browser frontend of `RustyChip`, compiled to `wasm32-unknown-unknown` and driven by the page in `www/`.
+//! Its functions are exported with the C ABI rather than through generated JavaScript bindings, so the build only needs the Rust toolchain:
+//! the page copies the game into the buffer from [`game_buffer`], calls [`start`], then calls [`run`] every animation frame with the time elapsed,
+//! drawing the pixels from [`frame_buffer`] to a canvas and filling Web Audio buffers through [`mix_audio`].
+
+use std::cell::RefCell;
+use std::time::Duration;
+
+use rusty_chip_core::audio::ToneGenerator;
+use rusty_chip_core::backend::CharKeyboard;
+use rusty_chip_core::database;
+use rusty_chip_core::emulator::{Emulator, EmulatorMode, DEFAULT_CYCLES_PER_FRAME};
+use rusty_chip_core::interpreter::Interpreter;
+use rusty_chip_core::quirks::QuirkConfig;
+
+/// The number of bytes each pixel takes up in the frame buffer (red, green, blue, and alpha).
+const BYTES_PER_PIXEL: usize = 4;
+
+thread_local! {
+    // The browser runs the module on a single thread, which every exported function is called from
+    static FRONTEND: RefCell<WebFrontend> = RefCell::new(WebFrontend::default());
+}
+
+/// Stores the emulator along with the buffers shared with the page.
+#[derive(Default)]
+struct WebFrontend {
+    /// The bytes of the game, written by the page before starting it.
+    game_data: Vec<u8>,
+    emulator: Option<Box<Emulator>>,
+    tone_generator: Option<ToneGenerator>,
+    /// The display as RGBA pixels, in the layout of the canvas' `ImageData`.
+    frame: Vec<u8>,
+    /// The mono samples mixed for the page's audio buffer.
+    audio_samples: Vec<f32>
+}
+
+impl WebFrontend {
+    /// Returns the buffer the page writes the game into, sized to hold it.
+    ///
+    /// # Parameters
+    ///
+    /// * `len` - The size of the game in bytes.
+    fn game_buffer(&mut self, len: usize) -> &mut [u8] {
+        self.game_data.resize(len, 0);
+        &mut self.game_data
+    }
+
+    /// Starts the game in the game buffer, with the quirks and cycles per frame the database recommends for known games.
+    ///
+    /// # Parameters
+    ///
+    /// * `sample_rate` - The sample rate of the page's audio, or 0 to run without sound.
+    /// * `seed` - The seed for the random opcode, as the browser's entropy can only be reached through JavaScript.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the database's load address for the game leaves no room for the font.
+    fn start(&mut self, sample_rate: u32, seed: u32) -> Result<(), String> {
+        let entry = database::lookup(&self.game_data);
+        let quirk_config = entry.map_or_else(QuirkConfig::new, |entry| entry.quirk_config(&QuirkConfig::new()));
+        let cycles_per_frame = entry.and_then(|entry| entry.tick_rate).unwrap_or(DEFAULT_CYCLES_PER_FRAME);
+
+        let mut builder = Interpreter::builder().quirks(quirk_config).seed(u64::from(seed));
+        if let Some(load_address) = entry.and_then(|entry| entry.load_address) {
+            builder = builder.load_address(load_address);
+        }
+        self.tone_generator = None;
+        if let Ok(sample_rate @ 1..) = i32::try_from(sample_rate) {
+            let (audio_controller, tone_generator) = ToneGenerator::new(sample_rate, 1);
+            self.tone_generator = Some(tone_generator);
+            builder = builder.audio(Box::new(audio_controller));
+        }
+
+        // The emulator is boxed straight away, as it is too large to pass around the stack of a test thread
+        let mut emulator = Box::new(Emulator::new(builder.build()?, cycles_per_frame));
+        emulator.queue_load(self.game_data.clone());
+        self.emulator = Some(emulator);
+
+        Ok(())
+    }
+
+    /// Advances the game by the time elapsed, returning the number of frames run.
+    ///
+    /// # Parameters
+    ///
+    /// * `elapsed_ms` - The milliseconds elapsed since the previous call.
+    fn run(&mut self, elapsed_ms: f64) -> u32 {
+        let Some(emulator) = self.emulator.as_mut() else {
+            return 0;
+        };
+
+        // A tab in the background can report a negative or absurd time, which is clamped rather than panicking
+        let elapsed = Duration::try_from_secs_f64(elapsed_ms / 1000.0).unwrap_or_default();
+        emulator.advance(elapsed).frames
+    }
+
+    /// Presses or releases the key the page reported.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The character of the key, as a Unicode scalar value.
+    /// * `pressed` - True if the key was pressed, false if it was released.
+    fn handle_key(&mut self, key: u32, pressed: bool) {
+        let (Some(emulator), Some(key)) = (self.emulator.as_mut(), char::from_u32(key)) else {
+            return;
+        };
+
+        if pressed {
+            emulator.handle_key_press(&CharKeyboard, key);
+        } else {
+            emulator.handle_key_release(&CharKeyboard, key);
+        }
+    }
+
+    /// Returns the display as RGBA pixels in the colours of the palette, row by row.
+    fn frame_buffer(&mut self) -> &[u8] {
+        self.frame.clear();
+        if let Some(emulator) = &self.emulator {
+            let colours = &emulator.renderer().palette().colours;
+            let framebuffer = emulator.interpreter().framebuffer();
+            self.frame.reserve(framebuffer.width() as usize * framebuffer.height() as usize * BYTES_PER_PIXEL);
+            for index in framebuffer.colour_indices() {
+                let colour = colours[index];
+                self.frame.extend_from_slice(&[colour.r, colour.g, colour.b, u8::MAX]);
+            }
+        }
+
+        &self.frame
+    }
+
+    /// Returns the width and height of the display in pixels.
+    fn frame_size(&self) -> (u32, u32) {
+        self.emulator.as_ref().map_or((0, 0), |emulator| (emulator.interpreter().display_width(), emulator.interpreter().display_height()))
+    }
+
+    /// Returns the buffer of samples the page reads the sound from, sized to hold the provided number.
+    ///
+    /// # Parameters
+    ///
+    /// * `len` - The number of samples in the page's audio buffer.
+    fn audio_buffer(&mut self, len: usize) -> &mut [f32] {
+        self.audio_samples.resize(len, 0.0);
+        &mut self.audio_samples
+    }
+
+    /// Mixes the next samples of the sound into the audio buffer, which is left silent if the game was started without sound.
+    fn mix_audio(&mut self) {
+        match self.tone_generator.as_mut() {
+            Some(tone_generator) => tone_generator.mix(&mut self.audio_samples),
+            None => self.audio_samples.fill(0.0)
+        }
+    }
+
+    /// Returns true if the game has halted, either through an error or by exiting.
+    fn is_halted(&self) -> bool {
+        self.emulator.as_ref().is_some_and(|emulator| matches!(emulator.mode(), EmulatorMode::Halted(_)))
+    }
+}
+
+/// Returns a pointer to the buffer the page writes the game into before calling [`start`], sized to hold it.
+///
+/// # Parameters
+///
+/// * `len` - The size of the game in bytes.
+#[no_mangle]
+pub extern "C" fn game_buffer(len: usize) -> *mut u8 {
+    FRONTEND.with(|frontend| frontend.borrow_mut().game_buffer(len).as_mut_ptr())
+}
+
+/// Starts the game in the game buffer, replacing any game already running. Returns false if it could not be started.
+///
+/// # Parameters
+///
+/// * `sample_rate` - The sample rate of the page's audio, or 0 to run without sound.
+/// * `seed` - The seed for the random opcode (e.g. from `crypto.getRandomValues`).
+#[no_mangle]
+pub extern "C" fn start(sample_rate: u32, seed: u32) -> bool {
+    FRONTEND.with(|frontend| frontend.borrow_mut().start(sample_rate, seed).is_ok())
+}
+
+/// Advances the game by the time elapsed, returning the number of frames run. Called every animation frame.
+///
+/// # Parameters
+///
+/// * `elapsed_ms` - The milliseconds elapsed since the previous call.
+#[no_mangle]
+pub extern "C" fn run(elapsed_ms: f64) -> u32 {
+    FRONTEND.with(|frontend| frontend.borrow_mut().run(elapsed_ms))
+}
+
+/// Presses the key with the provided character (e.g. from `KeyboardEvent.key`), if it is on the keypad.
+///
+/// # Parameters
+///
+/// * `key` - The character of the key, as a Unicode scalar value.
+#[no_mangle]
+pub extern "C" fn key_down(key: u32) {
+    FRONTEND.with(|frontend| frontend.borrow_mut().handle_key(key, true));
+}
+
+/// Releases the key with the provided character, if it is on the keypad.
+///
+/// # Parameters
+///
+/// * `key` - The character of the key, as a Unicode scalar value.
+#[no_mangle]
+pub extern "C" fn key_up(key: u32) {
+    FRONTEND.with(|frontend| frontend.borrow_mut().handle_key(key, false));
+}
+
+/// Draws the display into the frame buffer, returning a pointer to its RGBA pixels. The buffer holds [`frame_width`] by [`frame_height`] pixels.
+#[no_mangle]
+pub extern "C" fn frame_buffer() -> *const u8 {
+    FRONTEND.with(|frontend| frontend.borrow_mut().frame_buffer().as_ptr())
+}
+
+/// Returns the width of the display in pixels, which changes with the resolution the game uses.
+#[no_mangle]
+pub extern "C" fn frame_width() -> u32 {
+    FRONTEND.with(|frontend| frontend.borrow().frame_size().0)
+}
+
+/// Returns the height of the display in pixels, which changes with the resolution the game uses.
+#[no_mangle]
+pub extern "C" fn frame_height() -> u32 {
+    FRONTEND.with(|frontend| frontend.borrow().frame_size().1)
+}
+
+/// Returns a pointer to the buffer of samples [`mix_audio`] fills, sized to hold the provided number.
+///
+/// # Parameters
+///
+/// * `len` - The number of samples in the page's audio buffer.
+#[no_mangle]
+pub extern "C" fn audio_buffer(len: usize) -> *mut f32 {
+    FRONTEND.with(|frontend| frontend.borrow_mut().audio_buffer(len).as_mut_ptr())
+}
+
+/// Mixes the next samples of the sound into the audio buffer.
+#[no_mangle]
+pub extern "C" fn mix_audio() {
+    FRONTEND.with(|frontend| frontend.borrow_mut().mix_audio());
+}
+
+/// Returns true if the game has halted, either through an error or by exiting.
+#[no_mangle]
+pub extern "C" fn is_halted() -> bool {
+    FRONTEND.with(|frontend| frontend.borrow().is_halted())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a frontend running the provided game.
+    fn started_frontend(game_data: &[u8], sample_rate: u32) -> WebFrontend {
+        let mut frontend = WebFrontend::default();
+        frontend.game_buffer(game_data.len()).copy_from_slice(game_data);
+        frontend.start(sample_rate, 1).unwrap();
+        frontend
+    }
+
+    #[test]
+    fn run_and_draw() {
+        // Draws the "0" sprite at the origin, then jumps to itself
+        let mut frontend = started_frontend(&[0xA0, 0x00, 0xD0, 0x15, 0x12, 0x04], 0);
+        assert_eq!(frontend.run(1.0), 0, "Frame run before a whole frame elapsed.");
+        assert_eq!(frontend.run(40.0), 2, "Elapsed frames not run.");
+        assert_eq!(frontend.run(-5.0), 0, "Negative time run.");
+
+        assert_eq!(frontend.frame_size(), (64, 32), "Display size incorrect.");
+        let frame = frontend.frame_buffer().to_vec();
+        assert_eq!(frame.len(), 64 * 32 * BYTES_PER_PIXEL, "Frame buffer size incorrect.");
+        let colours = frontend.emulator.as_ref().unwrap().renderer().palette().colours;
+        assert_eq!(frame[..3], [colours[1].r, colours[1].g, colours[1].b], "Lit pixel not drawn in the foreground colour.");
+        assert_eq!(frame[4 * BYTES_PER_PIXEL..4 * BYTES_PER_PIXEL + 3], [colours[0].r, colours[0].g, colours[0].b], "Unlit pixel not drawn in the background colour.");
+        assert!(!frontend.is_halted(), "Running game halted.");
+    }
+
+    #[test]
+    fn keys_and_sound() {
+        // Waits for a key into V0, then sets the sound timer from it and jumps to itself
+        let mut frontend = started_frontend(&[0xF0, 0x0A, 0xF0, 0x18, 0x12, 0x04], 44100);
+        frontend.run(17.0);
+        frontend.handle_key(u32::from('W'), true);
+        frontend.run(17.0);
+        frontend.handle_key(u32::from('W'), false);
+        frontend.run(17.0);
+        let interpreter = frontend.emulator.as_ref().unwrap().interpreter();
+        assert_eq!(interpreter.registers()[0x0], 0x5, "Key not pressed.");
+        assert!(interpreter.sound_timer() > 0, "Sound not started.");
+
+        frontend.audio_buffer(256);
+        frontend.mix_audio();
+        assert!(frontend.audio_samples.iter().any(|sample| *sample != 0.0), "Sound not mixed.");
+    }
+
+    #[test]
+    fn halt_on_exit() {
+        let mut frontend = WebFrontend::default();
+        assert_eq!(frontend.run(17.0), 0, "Frame run without a game.");
+        assert!(frontend.frame_buffer().is_empty(), "Display drawn without a game.");
+
+        let mut frontend = started_frontend(&[0x00, 0x00], 0);
+        frontend.run(17.0);
+        assert!(frontend.is_halted(), "Exit not reported.");
+    }
+}
@@ -0,0 +1,100 @@
+// Runs the emulator compiled to WebAssembly, drawing its display to the canvas and playing its sound through Web Audio.
+// Build the module with `cargo build --release -p rusty-chip-web --target wasm32-unknown-unknown` and copy
+// `target/wasm32-unknown-unknown/release/rusty_chip_web.wasm` next to this file.
+
+const AUDIO_BUFFER_SIZE = 1024;
+
+const canvas = document.getElementById("display");
+const context = canvas.getContext("2d");
+const status = document.getElementById("status");
+
+let wasm = null;
+let audioContext = null;
+let lastTime = null;
+let animationFrame = null;
+
+async function loadModule() {
+    const { instance } = await WebAssembly.instantiateStreaming(fetch("rusty_chip_web.wasm"));
+    wasm = instance.exports;
+}
+
+// The module's memory can grow and detach earlier views of it, so a fresh view is taken for every access
+function bytes(pointer, length) {
+    return new Uint8Array(wasm.memory.buffer, pointer, length);
+}
+
+function startAudio() {
+    if (audioContext !== null) {
+        audioContext.close();
+    }
+
+    audioContext = new AudioContext();
+    const processor = audioContext.createScriptProcessor(AUDIO_BUFFER_SIZE, 0, 1);
+    processor.onaudioprocess = (event) => {
+        const pointer = wasm.audio_buffer(AUDIO_BUFFER_SIZE);
+        wasm.mix_audio();
+        event.outputBuffer.getChannelData(0).set(new Float32Array(wasm.memory.buffer, pointer, AUDIO_BUFFER_SIZE));
+    };
+    processor.connect(audioContext.destination);
+
+    return audioContext.sampleRate;
+}
+
+function draw() {
+    const width = wasm.frame_width();
+    const height = wasm.frame_height();
+    const pointer = wasm.frame_buffer();
+    if (canvas.width !== width || canvas.height !== height) {
+        canvas.width = width;
+        canvas.height = height;
+    }
+    context.putImageData(new ImageData(new Uint8ClampedArray(bytes(pointer, width * height * 4)), width, height), 0, 0);
+}
+
+function tick(time) {
+    if (lastTime !== null) {
+        wasm.run(time - lastTime);
+    }
+    lastTime = time;
+    draw();
+
+    if (wasm.is_halted()) {
+        status.textContent = "The game has halted. Choose another game file to play.";
+        return;
+    }
+    animationFrame = requestAnimationFrame(tick);
+}
+
+document.getElementById("game").addEventListener("change", async (event) => {
+    const file = event.target.files[0];
+    if (!file) {
+        return;
+    }
+    if (wasm === null) {
+        await loadModule();
+    }
+
+    const game = new Uint8Array(await file.arrayBuffer());
+    bytes(wasm.game_buffer(game.length), game.length).set(game);
+    const seed = crypto.getRandomValues(new Uint32Array(1))[0];
+    if (!wasm.start(startAudio(), seed)) {
+        status.textContent = "The game could not be started.";
+        return;
+    }
+
+    status.textContent = `Playing ${file.name}.`;
+    cancelAnimationFrame(animationFrame);
+    lastTime = null;
+    animationFrame = requestAnimationFrame(tick);
+    event.target.blur();
+});
+
+// Only single characters are passed on, so keys such as Shift or the arrows are ignored
+function handleKey(event, handler) {
+    if (wasm !== null && event.key.length === 1) {
+        handler(event.key.codePointAt(0));
+    }
+}
+
+document.addEventListener("keydown", (event) => handleKey(event, (key) => wasm.key_down(key)));
+document.addEventListener("keyup", (event) => handleKey(event, (key) => wasm.key_up(key)));
@@ -0,0 +1,67 @@
+//! A module to describe the running build, so that reports from users can be matched to the exact version, features, and quirks they were using.
+
+use sdl2::rwops::RWops;
+use sdl2::surface::Surface;
+
+use crate::quirks::QuirkConfig;
+
+/// The version of the crate.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// The short git hash of the commit the emulator was built from, or `unknown` if it was not built from a git checkout.
+pub const GIT_HASH: &str = env!("RUSTY_CHIP_GIT_HASH");
+
+/// The window icon, a bitmap so that SDL can load it without any extra image libraries.
+const ICON_BMP: &[u8] = include_bytes!("../assets/icon.bmp");
+
+/// Returns the names of the optional crate features this build was made with.
+#[must_use]
+pub fn enabled_features() -> Vec<&'static str> {
+    let features = [
+        ("sdl", cfg!(feature = "sdl")),
+        ("tui", cfg!(feature = "tui")),
+        ("egui", cfg!(feature = "egui")),
+        ("alloc-tracking", cfg!(feature = "alloc-tracking")),
+        ("serde", cfg!(feature = "serde"))
+    ];
+
+    features.into_iter().filter(|(_, is_enabled)| *is_enabled).map(|(feature, _)| feature).collect()
+}
+
+/// Returns the text of the About dialog: the version, git hash, enabled features, and active quirks.
+///
+/// # Parameters
+///
+/// * `quirk_config` - The quirks currently in use.
+#[must_use]
+pub fn about_text(quirk_config: &QuirkConfig) -> String {
+    let features = enabled_features();
+    let features = if features.is_empty() { String::from("none") } else { features.join(", ") };
+
+    format!("RustyChip {VERSION} ({GIT_HASH})\nFeatures: {features}\nQuirks: {quirk_config}")
+}
+
+/// Returns the window icon.
+///
+/// # Errors
+///
+/// Returns an `Err` if the embedded bitmap cannot be read by SDL.
+pub fn window_icon() -> Result<Surface<'static>, String> {
+    Surface::load_bmp_rw(&mut RWops::from_bytes(ICON_BMP)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn about_text_describes_build() {
+        let text = about_text(&QuirkConfig::new());
+
+        assert!(text.starts_with(&format!("RustyChip {VERSION} (")), "Version not described.");
+        assert!(text.contains("Features: sdl"), "Window feature not described.");
+        assert_eq!(enabled_features().contains(&"serde"), cfg!(feature = "serde"), "Serde feature misreported.");
+        assert!(text.contains("Quirks: reset-vf=reset memory=increment display-wait=wait"), "Quirks not described.");
+        assert_eq!(window_icon().map(|icon| icon.size()), Ok((32, 32)), "Icon not loaded.");
+    }
+}
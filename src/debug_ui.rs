@@ -0,0 +1,389 @@
+//! A module to run games in a debugging window built on egui, for working through what a game is doing.
+//! The display is shown alongside panels for the registers, the disassembly around the program counter, the memory, and the quirks, each of which can be hidden or resized.
+//! The game can be paused, stepped a frame or an instruction at a time, and reset from the toolbar, and the keypad is played with the same keys as the window.
+//! There is no sound, as the debugger is meant for stepping through games rather than playing them.
+
+use eframe::egui::{self, Color32, ColorImage, Key, TextStyle, TextureHandle, TextureOptions};
+
+use crate::backend::CharKeyboard;
+use crate::emulator::{Emulator, EmulatorMode, QuirkSide};
+use crate::framebuffer::Framebuffer;
+use crate::interpreter::Interpreter;
+use crate::opcodes::{self, OpcodeBytes};
+use crate::palette::Palette;
+use crate::quirks::QuirkEditor;
+use crate::GameSettings;
+
+const WINDOW_TITLE: &str = "RustyChip Debugger";
+
+/// The number of instructions shown before the program counter in the disassembly, with twice as many shown after.
+const DISASSEMBLY_CONTEXT: u16 = 8;
+
+/// The number of bytes shown on each row of the memory panel.
+const MEMORY_ROW_LENGTH: usize = 16;
+
+/// Stores which of the panels around the display are shown.
+struct Panels {
+    registers: bool,
+    disassembly: bool,
+    memory: bool,
+    quirks: bool
+}
+
+/// Runs the game and draws the debugging window every frame.
+struct DebugApp {
+    emulator: Box<Emulator>,
+    texture: Option<TextureHandle>,
+    panels: Panels
+}
+
+impl DebugApp {
+    /// Returns the debugger for the emulator, with every panel shown.
+    ///
+    /// # Parameters
+    ///
+    /// * `emulator` - The emulator running the game.
+    fn new(emulator: Box<Emulator>) -> DebugApp {
+        DebugApp {
+            emulator,
+            texture: None,
+            panels: Panels { registers: true, disassembly: true, memory: true, quirks: true }
+        }
+    }
+
+    /// Passes the keypad keys pressed and released on to the game, unless a text field has the keyboard.
+    ///
+    /// # Parameters
+    ///
+    /// * `ctx` - The egui context holding the input.
+    fn handle_keys(&mut self, ctx: &egui::Context) {
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+
+        let events = ctx.input(|input| input.events.clone());
+        for event in events {
+            let egui::Event::Key { key, pressed, repeat: false, .. } = event else {
+                continue;
+            };
+            let Some(key) = key_char(key) else {
+                continue;
+            };
+
+            if pressed {
+                self.emulator.handle_key_press(&CharKeyboard, key);
+            } else {
+                self.emulator.handle_key_release(&CharKeyboard, key);
+            }
+        }
+    }
+
+    /// Draws the buttons which pause, step, and reset the game, the toggles for the panels, and the state of the game.
+    ///
+    /// # Parameters
+    ///
+    /// * `ui` - The toolbar to draw into.
+    fn toolbar(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let mode = self.emulator.mode();
+            if mode == EmulatorMode::Running {
+                if ui.button("Pause").clicked() {
+                    self.emulator.pause();
+                }
+            } else if ui.add_enabled(!matches!(mode, EmulatorMode::Halted(_)), egui::Button::new("Resume")).clicked() {
+                self.emulator.resume();
+            }
+            if ui.button("Step frame").clicked() {
+                self.emulator.step();
+            }
+            if ui.add_enabled(mode == EmulatorMode::Paused, egui::Button::new("Step instruction")).clicked() {
+                self.emulator.step_instruction();
+            }
+            if ui.button("Reset").clicked() {
+                self.emulator.reset();
+            }
+
+            ui.separator();
+            ui.toggle_value(&mut self.panels.registers, "Registers");
+            ui.toggle_value(&mut self.panels.disassembly, "Disassembly");
+            ui.toggle_value(&mut self.panels.memory, "Memory");
+            ui.toggle_value(&mut self.panels.quirks, "Quirks");
+
+            ui.separator();
+            ui.label(match mode {
+                EmulatorMode::Halted(reason) => reason.to_string(),
+                EmulatorMode::Running => format!("Running, frame {}", self.emulator.frame_count()),
+                _ => format!("Paused at frame {}", self.emulator.frame_count())
+            });
+        });
+    }
+
+    /// Draws the program counter, register I, timers, general purpose registers, and stack.
+    ///
+    /// # Parameters
+    ///
+    /// * `ui` - The panel to draw into.
+    fn registers_panel(&self, ui: &mut egui::Ui) {
+        let interpreter = self.emulator.interpreter();
+        ui.heading("Registers");
+        ui.monospace(format!("PC 0x{:03X}  I 0x{:03X}", interpreter.program_counter(), interpreter.register_i()));
+        ui.monospace(format!("DT {:>5}  ST {:>5}", interpreter.delay_timer(), interpreter.sound_timer()));
+        egui::Grid::new("registers").show(ui, |ui| {
+            for (index, value) in interpreter.registers().iter().enumerate() {
+                ui.monospace(format!("V{index:X} {value:02X}"));
+                if index % 4 == 3 {
+                    ui.end_row();
+                }
+            }
+        });
+
+        ui.separator();
+        ui.heading("Stack");
+        if interpreter.stack().is_empty() {
+            ui.label("Empty");
+        }
+        for address in interpreter.stack().iter().rev() {
+            ui.monospace(interpreter.symbols().describe(*address));
+        }
+    }
+
+    /// Draws the instructions around the program counter, marking the one about to run.
+    ///
+    /// # Parameters
+    ///
+    /// * `ui` - The panel to draw into.
+    fn disassembly_panel(&self, ui: &mut egui::Ui) {
+        let interpreter = self.emulator.interpreter();
+        ui.heading("Disassembly");
+        for (address, line) in disassembly_lines(interpreter) {
+            let marker = if address == interpreter.program_counter() { '>' } else { ' ' };
+            ui.monospace(format!("{marker} {line}"));
+        }
+    }
+
+    /// Draws the whole of the RAM, a row of bytes at a time, only laying out the rows scrolled into view.
+    ///
+    /// # Parameters
+    ///
+    /// * `ui` - The panel to draw into.
+    fn memory_panel(&self, ui: &mut egui::Ui) {
+        let ram = self.emulator.interpreter().ram();
+        ui.heading("Memory");
+        let row_height = ui.text_style_height(&TextStyle::Monospace);
+        let row_count = ram.len().div_ceil(MEMORY_ROW_LENGTH);
+        egui::ScrollArea::vertical().auto_shrink(false).show_rows(ui, row_height, row_count, |ui, rows| {
+            for row in rows {
+                ui.monospace(memory_row(ram, row * MEMORY_ROW_LENGTH));
+            }
+        });
+    }
+
+    /// Draws a button per quirk which switches it to its other setting, along with the switch between the settings from before and after the change.
+    ///
+    /// # Parameters
+    ///
+    /// * `ui` - The panel to draw into.
+    fn quirks_panel(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Quirks");
+        if self.emulator.is_pure() {
+            ui.label("The quirks are locked in pure mode.");
+        }
+
+        let mut editor = QuirkEditor::default();
+        egui::Grid::new("quirks").show(ui, |ui| {
+            loop {
+                let mut quirk_config = self.emulator.interpreter().quirk_config().clone();
+                ui.label(editor.name());
+                if ui.add_enabled(!self.emulator.is_pure(), egui::Button::new(editor.setting(&quirk_config))).clicked() {
+                    editor.toggle(&mut quirk_config);
+                    self.emulator.change_quirks(quirk_config);
+                }
+                ui.end_row();
+
+                editor.select(true);
+                if editor.selected() == 0 {
+                    break;
+                }
+            }
+        });
+
+        if let Some(side) = self.emulator.quirk_comparison().map(|comparison| comparison.side()) {
+            let label = match side {
+                QuirkSide::Before => "Showing the quirks before the change",
+                QuirkSide::After => "Showing the quirks after the change"
+            };
+            ui.label(label);
+            if ui.button("Switch").clicked() {
+                self.emulator.switch_quirk_side();
+            }
+        }
+    }
+
+    /// Draws the display in the central panel, scaled to fit while keeping its shape.
+    ///
+    /// # Parameters
+    ///
+    /// * `ui` - The panel to draw into.
+    fn display_panel(&mut self, ui: &mut egui::Ui) {
+        let image = display_image(self.emulator.interpreter().framebuffer(), self.emulator.renderer().palette());
+        let texture = match &mut self.texture {
+            Some(texture) => {
+                texture.set(image, TextureOptions::NEAREST);
+                texture
+            },
+            None => self.texture.insert(ui.ctx().load_texture("display", image, TextureOptions::NEAREST))
+        };
+
+        let texture_size = texture.size_vec2();
+        let available = ui.available_size();
+        let scale = (available.x / texture_size.x).min(available.y / texture_size.y).max(1.0);
+        ui.centered_and_justified(|ui| {
+            ui.add(egui::Image::new(&*texture).fit_to_exact_size(texture_size * scale));
+        });
+    }
+}
+
+impl eframe::App for DebugApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.handle_keys(ctx);
+        self.emulator.tick();
+
+        egui::TopBottomPanel::top("toolbar").show(ctx, |ui| self.toolbar(ui));
+        if self.panels.memory {
+            egui::TopBottomPanel::bottom("memory").resizable(true).default_height(160.0).show(ctx, |ui| self.memory_panel(ui));
+        }
+        if self.panels.disassembly {
+            egui::SidePanel::left("disassembly").resizable(true).show(ctx, |ui| self.disassembly_panel(ui));
+        }
+        if self.panels.registers {
+            egui::SidePanel::right("registers").resizable(true).show(ctx, |ui| self.registers_panel(ui));
+        }
+        if self.panels.quirks {
+            egui::SidePanel::right("quirks").resizable(true).show(ctx, |ui| self.quirks_panel(ui));
+        }
+        egui::CentralPanel::default().show(ctx, |ui| self.display_panel(ui));
+
+        // The game keeps running between input events, so the window is redrawn continuously
+        ctx.request_repaint();
+    }
+}
+
+/// Runs the game in the debugging window until it is closed.
+///
+/// # Parameters
+///
+/// * `path` - The path to the game.
+/// * `game_settings` - The quirks, cycles per frame, load address, and banking to run the game with, and whether the [database](crate::database) may override them.
+///
+/// # Errors
+///
+/// Returns an `Err` if the game file cannot be read, the load address leaves no room for the font or is past the bank window while banking, or the window cannot be opened.
+pub fn run(path: &str, game_settings: &GameSettings) -> Result<(), String> {
+    let game_data = crate::read_game_file(path).map_err(|e| e.to_string())?;
+    let emulator = crate::headless_emulator(game_data, game_settings)?;
+
+    let options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default().with_title(WINDOW_TITLE).with_inner_size([1280.0, 720.0]),
+        ..Default::default()
+    };
+    eframe::run_native(WINDOW_TITLE, options, Box::new(|_| Ok(Box::new(DebugApp::new(emulator))))).map_err(|e| e.to_string())
+}
+
+/// Returns the keypad character for a key, using the same characters as the [keyboard](CharKeyboard) layout, or `None` if the key has no single character.
+///
+/// # Parameters
+///
+/// * `key` - The key pressed.
+fn key_char(key: Key) -> Option<char> {
+    let mut chars = key.name().chars();
+    match (chars.next(), chars.next()) {
+        (Some(key), None) => Some(key.to_ascii_lowercase()),
+        _ => None
+    }
+}
+
+/// Returns the display as an image in the colours of the game's palette.
+///
+/// # Parameters
+///
+/// * `framebuffer` - The display to draw.
+/// * `palette` - The colours the display is drawn in.
+fn display_image(framebuffer: Framebuffer<'_>, palette: &Palette) -> ColorImage {
+    let colours = &palette.colours;
+    let pixels = framebuffer.colour_indices().map(|index| Color32::from_rgb(colours[index].r, colours[index].g, colours[index].b)).collect();
+
+    ColorImage { size: [framebuffer.width() as usize, framebuffer.height() as usize], pixels }
+}
+
+/// Returns the addresses and lines of the instructions around the program counter, labelled from the interpreter's [symbols](Interpreter::symbols).
+///
+/// # Parameters
+///
+/// * `interpreter` - The interpreter to read from.
+fn disassembly_lines(interpreter: &Interpreter) -> Vec<(u16, String)> {
+    let ram = interpreter.ram();
+    let mut address = interpreter.program_counter().saturating_sub(DISASSEMBLY_CONTEXT * 2);
+    let mut lines = Vec::new();
+    while lines.len() < usize::from(DISASSEMBLY_CONTEXT) * 3 {
+        let start = usize::from(address);
+        let Some(bytes) = ram.get(start..start + 2) else {
+            break;
+        };
+
+        // The XO-CHIP long load is followed by its address
+        let length = OpcodeBytes::build(bytes).length();
+        let opcode_bytes = OpcodeBytes::build(ram.get(start..start + usize::from(length)).unwrap_or(bytes));
+        lines.push((address, opcodes::instruction_line(address, &opcode_bytes, interpreter.symbols())));
+        address += length;
+    }
+
+    lines
+}
+
+/// Returns a row of the memory panel: the address of its first byte followed by the bytes in hexadecimal.
+///
+/// # Parameters
+///
+/// * `ram` - The RAM to read from.
+/// * `start` - The address of the first byte of the row.
+fn memory_row(ram: &[u8], start: usize) -> String {
+    let bytes = ram.iter().skip(start).take(MEMORY_ROW_LENGTH).map(|byte| format!("{byte:02X}")).collect::<Vec<String>>();
+    format!("0x{start:04X}: {}", bytes.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::PROGRAM_START_ADDRESS;
+    use crate::quirks::{DisplayWaitQuirk, QuirkConfig};
+
+    #[test]
+    fn display_and_disassembly() {
+        let mut quirk_config = QuirkConfig::new();
+        quirk_config.display_wait = DisplayWaitQuirk::NoWait;
+        let mut interpreter = Interpreter::new_headless(quirk_config);
+
+        // Draws the top of the "0" sprite (a row of four pixels) at the origin, then clears the screen
+        interpreter.load_game(&[0xA0, 0x00, 0xD0, 0x11, 0x00, 0xE0]);
+        interpreter.handle_cycle();
+        interpreter.handle_cycle();
+        let palette = Palette::new();
+        let image = display_image(interpreter.framebuffer(), &palette);
+        let colours = palette.colours;
+        assert_eq!(image.size, [64, 32], "Image not the size of the display.");
+        assert_eq!(image.pixels[0], Color32::from_rgb(colours[1].r, colours[1].g, colours[1].b), "Lit pixel not drawn in the foreground colour.");
+        assert_eq!(image.pixels[4], Color32::from_rgb(colours[0].r, colours[0].g, colours[0].b), "Unlit pixel not drawn in the background colour.");
+
+        let lines = disassembly_lines(&interpreter);
+        assert_eq!(lines.len(), usize::from(DISASSEMBLY_CONTEXT) * 3, "Disassembly not the expected length.");
+        assert!(lines.contains(&(PROGRAM_START_ADDRESS + 4, String::from("0x204: 00E0 CLS"))), "Next instruction not disassembled.");
+        assert_eq!(memory_row(interpreter.ram(), 0x200), "0x0200: A0 00 D0 11 00 E0 00 00 00 00 00 00 00 00 00 00", "Memory row formatted incorrectly.");
+    }
+
+    #[test]
+    fn keypad_keys() {
+        assert_eq!(key_char(Key::Q), Some('q'), "Letter key not mapped.");
+        assert_eq!(key_char(Key::Num4), Some('4'), "Number key not mapped.");
+        assert_eq!(key_char(Key::Escape), None, "Named key mapped.");
+    }
+}
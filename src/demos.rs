@@ -0,0 +1,92 @@
+//! A module bundling a curated set of the public domain games from the `games` directory into the emulator, so new users have something to play straight away.  
+//! The games are embedded in the binary and written out to a directory when launched, so that their palettes and autosaves are kept alongside them like any other game.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Stores a bundled game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Demo {
+    /// The name used to launch the game.
+    pub name: &'static str,
+    /// A short description of the game.
+    pub description: &'static str,
+    /// The bytes of the game file.
+    pub data: &'static [u8]
+}
+
+/// The bundled games, in the order they are listed.
+pub const DEMOS: [Demo; 7] = [
+    Demo { name: "BRIX", description: "Break out of the wall of bricks with a paddle and ball.", data: include_bytes!("../games/BRIX.chip8") },
+    Demo { name: "INVADERS", description: "Shoot down the invaders before they reach the ground.", data: include_bytes!("../games/INVADERS.chip8") },
+    Demo { name: "KALEID", description: "Draw kaleidoscope patterns with the keypad.", data: include_bytes!("../games/KALEID.chip8") },
+    Demo { name: "MAZE", description: "Watch a random maze being drawn (no input needed).", data: include_bytes!("../games/MAZE.chip8") },
+    Demo { name: "PONG2", description: "The classic two player bat and ball game.", data: include_bytes!("../games/PONG2.chip8") },
+    Demo { name: "TANK", description: "Drive a tank around and shoot the target.", data: include_bytes!("../games/TANK.chip8") },
+    Demo { name: "TETRIS", description: "Stack the falling pieces to clear lines.", data: include_bytes!("../games/TETRIS.chip8") }
+];
+
+/// The extension of the files the bundled games are written to.
+const DEMO_FILE_EXTENSION: &str = "ch8";
+
+/// Returns the bundled game with the provided name, ignoring case.
+///
+/// # Parameters
+///
+/// * `name` - The name of the game.
+#[must_use]
+pub fn find(name: &str) -> Option<&'static Demo> {
+    DEMOS.iter().find(|demo| demo.name.eq_ignore_ascii_case(name))
+}
+
+/// Writes the game to the provided directory (created if needed), returning the path of the game file to launch.  
+/// The file is only written if it does not already exist, so anything saved alongside it is kept.
+///
+/// # Parameters
+///
+/// * `demo` - The game to write.
+/// * `dir` - The directory to write the game to.
+///
+/// # Errors
+///
+/// Returns an `Err` if the directory or file cannot be written.
+pub fn extract(demo: &Demo, dir: &str) -> io::Result<String> {
+    fs::create_dir_all(dir)?;
+    let path = Path::new(dir).join(format!("{}.{DEMO_FILE_EXTENSION}", demo.name));
+    if !path.exists() {
+        fs::write(&path, demo.data)?;
+    }
+
+    Ok(path.to_string_lossy().into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fuzz::{self, InputScript};
+    use crate::quirks::QuirkConfig;
+
+    #[test]
+    fn demos_run_without_faults() {
+        let script = InputScript { seed: 0, frames: 300, events: Vec::new() };
+        for demo in &DEMOS {
+            assert_eq!(find(&demo.name.to_lowercase()), Some(demo), "Demo not found by name.");
+            assert_eq!(fuzz::replay(demo.data, &script, 10, QuirkConfig::new()), None, "Demo {} faulted.", demo.name);
+        }
+        assert_eq!(find("FAKE"), None, "Nonexistent demo found.");
+    }
+
+    #[test]
+    fn extract_demo() {
+        let dir = std::env::temp_dir().join(format!("rusty_chip_demos_test_{}", std::process::id()));
+        let dir = dir.to_str().unwrap();
+
+        let path = extract(&DEMOS[0], dir).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), DEMOS[0].data, "Demo not written.");
+        fs::write(&path, [0x12, 0x00]).unwrap();
+        extract(&DEMOS[0], dir).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), [0x12, 0x00], "Existing demo file overwritten.");
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
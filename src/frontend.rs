@@ -0,0 +1,376 @@
+//! A module to contain the SDL implementations of the [backend traits](crate::backend), which the emulator's own window is built on.
+//! SDL windows cannot leave the thread which created them, so the interpreter draws to a [`RelayDisplay`](RelayDisplay) on the emulation thread, whose frames the [`SdlDisplay`](SdlDisplay) replays on the window's thread.
+
+use std::mem;
+use std::ops::Range;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use sdl2::AudioSubsystem;
+use sdl2::audio::{AudioCallback, AudioDevice, AudioSpecDesired};
+use sdl2::keyboard::Keycode;
+use sdl2::messagebox::{ButtonData, ClickedButton, MessageBoxButtonFlag, MessageBoxFlag};
+use sdl2::pixels::Color;
+use sdl2::render::{BlendMode, WindowCanvas};
+
+use crate::audio::{AudioController, ToneGenerator};
+use crate::backend::{Display, InputSource, MessageKind, Rect};
+use crate::palette::Colour;
+
+/// A drawing call recorded by a [`RelayDisplay`](RelayDisplay), along with the range of the recorded rectangles it draws.
+#[derive(Debug, Clone)]
+enum DrawCommand {
+    Clear(Colour),
+    FillRects { colour: Colour, alpha: u8, rects: Range<usize> },
+    DrawRects { colour: Colour, rects: Range<usize> }
+}
+
+/// Stores the drawing calls making up a frame, with the rectangles of all of them kept together so that the buffers are reused from frame to frame.
+#[derive(Debug, Default)]
+struct DrawList {
+    commands: Vec<DrawCommand>,
+    rects: Vec<Rect>
+}
+
+impl DrawList {
+    /// Records the rectangles, returning their range in the list.
+    ///
+    /// # Parameters
+    ///
+    /// * `rects` - The rectangles to record.
+    fn push_rects(&mut self, rects: &[Rect]) -> Range<usize> {
+        let start = self.rects.len();
+        self.rects.extend_from_slice(rects);
+        start..self.rects.len()
+    }
+
+    /// Forgets every call, keeping the buffers.
+    fn clear(&mut self) {
+        self.commands.clear();
+        self.rects.clear();
+    }
+}
+
+/// Stores what the emulation thread has handed over to the window's thread.
+#[derive(Debug, Default)]
+struct Relay {
+    /// The last frame presented, or a spare list once the window has taken it.
+    frame: DrawList,
+    is_new_frame: bool,
+    /// The last title set, kept in the same buffer every time.
+    title: String,
+    is_new_title: bool
+}
+
+/// Returns the relay, locked. A panic while it was locked cannot leave it inconsistent, so the lock is taken regardless.
+///
+/// # Parameters
+///
+/// * `relay` - The relay to lock.
+fn lock_relay(relay: &Mutex<Relay>) -> MutexGuard<'_, Relay> {
+    relay.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Records the frames the interpreter draws, handing each one over to the [`SdlDisplay`](SdlDisplay) it came from once presented.  
+/// Only the latest frame is kept, so a window which falls behind skips frames rather than delaying the game.  
+/// Messages are shown without a parent window, as the window belongs to another thread; they must still be shown from the main thread, which SDL requires of message boxes.
+pub struct RelayDisplay {
+    recording: DrawList,
+    relay: Arc<Mutex<Relay>>
+}
+
+impl Display for RelayDisplay {
+    fn clear(&mut self, colour: Colour) {
+        self.recording.commands.push(DrawCommand::Clear(colour));
+    }
+
+    fn fill_rects(&mut self, colour: Colour, alpha: u8, rects: &[Rect]) -> Result<(), String> {
+        let rects = self.recording.push_rects(rects);
+        self.recording.commands.push(DrawCommand::FillRects { colour, alpha, rects });
+        Ok(())
+    }
+
+    fn draw_rects(&mut self, colour: Colour, rects: &[Rect]) -> Result<(), String> {
+        let rects = self.recording.push_rects(rects);
+        self.recording.commands.push(DrawCommand::DrawRects { colour, rects });
+        Ok(())
+    }
+
+    fn present(&mut self) {
+        // The recording swaps places with the frame it replaces, so the buffers go round without being reallocated
+        let mut relay = lock_relay(&self.relay);
+        mem::swap(&mut relay.frame, &mut self.recording);
+        relay.is_new_frame = true;
+        drop(relay);
+        self.recording.clear();
+    }
+
+    fn set_title(&mut self, title: &str) -> Result<(), String> {
+        let mut relay = lock_relay(&self.relay);
+        relay.title.clear();
+        relay.title.push_str(title);
+        relay.is_new_title = true;
+        Ok(())
+    }
+
+    fn show_message(&self, kind: MessageKind, title: &str, message: &str) -> Result<(), String> {
+        sdl2::messagebox::show_simple_message_box(message_box_flag(kind), title, message, None).map_err(|e| e.to_string())
+    }
+
+    fn show_choice(&self, kind: MessageKind, title: &str, message: &str, choices: &[&str]) -> Result<Option<usize>, String> {
+        let buttons: Vec<ButtonData> = choices.iter().enumerate().map(|(i, text)| ButtonData {
+            flags: if i == 0 { MessageBoxButtonFlag::RETURNKEY_DEFAULT } else { MessageBoxButtonFlag::NOTHING },
+            button_id: i32::try_from(i).unwrap_or(i32::MAX),
+            text
+        }).collect();
+        let clicked = sdl2::messagebox::show_message_box(message_box_flag(kind), &buttons, title, message, None, None).map_err(|e| e.to_string())?;
+
+        Ok(match clicked {
+            ClickedButton::CustomButton(button) => usize::try_from(button.button_id).ok(),
+            ClickedButton::CloseButton => None
+        })
+    }
+}
+
+/// Draws the frames recorded by its [`RelayDisplay`](RelayDisplay) to an SDL window, on the thread which owns the window.
+pub struct SdlDisplay {
+    canvas: WindowCanvas,
+    relay: Arc<Mutex<Relay>>,
+    // The frame being drawn, swapped out of the relay so that it is not locked while drawing
+    frame: DrawList,
+    // The rectangles converted to SDL's, kept between draws so that no allocations are made once it has grown to fit
+    sdl_rects: Vec<sdl2::rect::Rect>
+}
+
+impl SdlDisplay {
+    /// Returns a display drawing to the canvas of a window.
+    ///
+    /// # Parameters
+    ///
+    /// * `canvas` - The canvas of the window.
+    #[must_use]
+    pub fn new(canvas: WindowCanvas) -> SdlDisplay {
+        SdlDisplay { canvas, relay: Arc::default(), frame: DrawList::default(), sdl_rects: Vec::new() }
+    }
+
+    /// Returns a display recording frames for this window, to attach to an interpreter which may run on another thread.
+    #[must_use]
+    pub fn relay_display(&self) -> RelayDisplay {
+        RelayDisplay { recording: DrawList::default(), relay: Arc::clone(&self.relay) }
+    }
+
+    /// Sets the title of the window to the one last set through the relay, and draws the frame last presented through it. Does nothing if neither changed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the title could not be set or the frame could not be drawn.
+    pub fn update(&mut self) -> Result<(), String> {
+        let mut relay = lock_relay(&self.relay);
+        if relay.is_new_title {
+            relay.is_new_title = false;
+            self.canvas.window_mut().set_title(&relay.title).map_err(|e| e.to_string())?;
+        }
+        if !relay.is_new_frame {
+            return Ok(());
+        }
+        mem::swap(&mut relay.frame, &mut self.frame);
+        relay.is_new_frame = false;
+        drop(relay);
+
+        for command in &self.frame.commands {
+            match command {
+                DrawCommand::Clear(colour) => {
+                    self.canvas.set_draw_color(sdl_colour(*colour));
+                    self.canvas.clear();
+                },
+                DrawCommand::FillRects { colour, alpha, rects } => {
+                    convert_rects(&mut self.sdl_rects, &self.frame.rects[rects.clone()]);
+                    let is_blended = *alpha < u8::MAX;
+                    if is_blended {
+                        self.canvas.set_blend_mode(BlendMode::Blend);
+                    }
+                    self.canvas.set_draw_color(Color::RGBA(colour.r, colour.g, colour.b, *alpha));
+                    let result = self.canvas.fill_rects(&self.sdl_rects);
+                    if is_blended {
+                        self.canvas.set_blend_mode(BlendMode::None);
+                    }
+                    result?;
+                },
+                DrawCommand::DrawRects { colour, rects } => {
+                    convert_rects(&mut self.sdl_rects, &self.frame.rects[rects.clone()]);
+                    self.canvas.set_draw_color(sdl_colour(*colour));
+                    self.canvas.draw_rects(&self.sdl_rects)?;
+                }
+            }
+        }
+        self.canvas.present();
+
+        Ok(())
+    }
+}
+
+/// Fills the buffer of SDL rectangles with the provided ones.
+///
+/// # Parameters
+///
+/// * `sdl_rects` - The buffer to fill.
+/// * `rects` - The rectangles to convert.
+fn convert_rects(sdl_rects: &mut Vec<sdl2::rect::Rect>, rects: &[Rect]) {
+    sdl_rects.clear();
+    sdl_rects.extend(rects.iter().map(|rect| sdl2::rect::Rect::new(rect.x, rect.y, rect.width, rect.height)));
+}
+
+/// Returns the SDL flag giving a message box the icon of its kind.
+///
+/// # Parameters
+///
+/// * `kind` - The kind of message.
+fn message_box_flag(kind: MessageKind) -> MessageBoxFlag {
+    match kind {
+        MessageKind::Information => MessageBoxFlag::INFORMATION,
+        MessageKind::Warning => MessageBoxFlag::WARNING,
+        MessageKind::Error => MessageBoxFlag::ERROR
+    }
+}
+
+/// Maps the keyboard onto the keypad, with the left four columns of keys standing in for its 4x4 grid.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SdlKeyboard;
+
+impl InputSource for SdlKeyboard {
+    type Key = Keycode;
+
+    fn keypad_key(&self, key: Keycode) -> Option<u8> {
+        let key = match key {
+            Keycode::Num1 => 0x1,
+            Keycode::Num2 => 0x2,
+            Keycode::Num3 => 0x3,
+            Keycode::Num4 => 0xC,
+            Keycode::Q => 0x4,
+            Keycode::W => 0x5,
+            Keycode::E => 0x6,
+            Keycode::R => 0xD,
+            Keycode::A => 0x7,
+            Keycode::S => 0x8,
+            Keycode::D => 0x9,
+            Keycode::F => 0xE,
+            Keycode::Z => 0xA,
+            Keycode::X => 0x0,
+            Keycode::C => 0xB,
+            Keycode::V => 0xF,
+            _ => return None
+        };
+
+        Some(key)
+    }
+
+    fn key_name(&self, key: Keycode) -> String {
+        key.name()
+    }
+}
+
+/// Plays the mix of a tone generator through an SDL audio device.
+pub struct SdlAudio(ToneGenerator);
+
+impl AudioCallback for SdlAudio {
+    type Channel = f32;
+
+    fn callback(&mut self, out: &mut [f32]) {
+        self.0.mix(out);
+    }
+}
+
+/// Returns the colour as SDL represents it.
+///
+/// # Parameters
+///
+/// * `colour` - The colour to convert.
+fn sdl_colour(colour: Colour) -> Color {
+    Color::RGB(colour.r, colour.g, colour.b)
+}
+
+/// Opens the audio device along with the controller for its callback.  
+/// Stereo output is preferred so that voices can be panned, falling back to mono if the device does not support it.  
+/// Mostly taken from the example provided by the crate.
+///
+/// # Parameters
+///
+/// * `audio_subsystem` - The SDL audio subsystem to open the device with.
+///
+/// # Errors
+///
+/// Returns an `Err` if neither a stereo nor a mono device could be opened.
+pub fn open_audio_device(audio_subsystem: &AudioSubsystem) -> Result<(AudioDevice<SdlAudio>, AudioController), String> {
+    let mut result = Err(String::new());
+    for channels in [2, 1] {
+        let desired_spec = AudioSpecDesired {
+            freq: Some(44100),
+            channels: Some(channels),
+            samples: None       // default sample size
+        };
+
+        let mut audio_controller = None;
+        result = audio_subsystem.open_playback(None, &desired_spec, |spec| {
+            // initialize the audio callback, keeping hold of its controller for runtime changes
+            let (controller, generator) = ToneGenerator::new(spec.freq, spec.channels);
+            audio_controller = Some(controller);
+            SdlAudio(generator)
+        }).map(|audio_device| (audio_device, audio_controller.expect("Audio callback was not initialized.")));
+
+        if result.is_ok() {
+            break;
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keypad_key() {
+        assert_eq!(SdlKeyboard.keypad_key(Keycode::Num1), Some(0x1), "Incorrect key mapping.");
+        assert_eq!(SdlKeyboard.keypad_key(Keycode::Num2), Some(0x2), "Incorrect key mapping.");
+        assert_eq!(SdlKeyboard.keypad_key(Keycode::Num3), Some(0x3), "Incorrect key mapping.");
+        assert_eq!(SdlKeyboard.keypad_key(Keycode::Num4), Some(0xC), "Incorrect key mapping.");
+        assert_eq!(SdlKeyboard.keypad_key(Keycode::Q), Some(0x4), "Incorrect key mapping.");
+        assert_eq!(SdlKeyboard.keypad_key(Keycode::W), Some(0x5), "Incorrect key mapping.");
+        assert_eq!(SdlKeyboard.keypad_key(Keycode::E), Some(0x6), "Incorrect key mapping.");
+        assert_eq!(SdlKeyboard.keypad_key(Keycode::R), Some(0xD), "Incorrect key mapping.");
+        assert_eq!(SdlKeyboard.keypad_key(Keycode::A), Some(0x7), "Incorrect key mapping.");
+        assert_eq!(SdlKeyboard.keypad_key(Keycode::S), Some(0x8), "Incorrect key mapping.");
+        assert_eq!(SdlKeyboard.keypad_key(Keycode::D), Some(0x9), "Incorrect key mapping.");
+        assert_eq!(SdlKeyboard.keypad_key(Keycode::F), Some(0xE), "Incorrect key mapping.");
+        assert_eq!(SdlKeyboard.keypad_key(Keycode::Z), Some(0xA), "Incorrect key mapping.");
+        assert_eq!(SdlKeyboard.keypad_key(Keycode::X), Some(0x0), "Incorrect key mapping.");
+        assert_eq!(SdlKeyboard.keypad_key(Keycode::C), Some(0xB), "Incorrect key mapping.");
+        assert_eq!(SdlKeyboard.keypad_key(Keycode::V), Some(0xF), "Incorrect key mapping.");
+        assert_eq!(SdlKeyboard.keypad_key(Keycode::G), None, "Ignored key is mapped.");
+    }
+
+    #[test]
+    fn relay_keeps_latest_frame() {
+        let relay = Arc::new(Mutex::new(Relay::default()));
+        let mut display = RelayDisplay { recording: DrawList::default(), relay: Arc::clone(&relay) };
+        let colour = Colour { r: 1, g: 2, b: 3 };
+        let rect = Rect { x: 0, y: 0, width: 10, height: 10 };
+
+        display.clear(colour);
+        display.fill_rects(colour, 128, &[rect, rect]).unwrap();
+        assert!(!lock_relay(&relay).is_new_frame, "Frame handed over before it was presented.");
+        display.present();
+        display.clear(colour);
+        display.draw_rects(colour, &[rect]).unwrap();
+        display.present();
+        display.set_title("Title").unwrap();
+
+        // Only the second frame is kept, while the recording starts afresh
+        let relay = lock_relay(&relay);
+        assert!(relay.is_new_frame, "Presented frame not handed over.");
+        assert!(matches!(relay.frame.commands[..], [DrawCommand::Clear(_), DrawCommand::DrawRects { ref rects, .. }] if *rects == (0..1)), "Latest frame not kept.");
+        assert_eq!(relay.frame.rects, [rect], "Rectangles of the latest frame not kept.");
+        assert!(display.recording.commands.is_empty(), "Recording not cleared.");
+        assert!(relay.is_new_title && relay.title == "Title", "Title not handed over.");
+    }
+}
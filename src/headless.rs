@@ -0,0 +1,211 @@
+//! A module to run games without a window or audio, for CI pipelines and automated testing of games.
+//! The [runner](HeadlessRunner) steps the game a frame at a time with no input pressed, after which its display and state can be inspected or dumped to files:
+//!
+//! ```no_run
+//! use rusty_chip::headless::HeadlessRunner;
+//! # fn run(game_data: Vec<u8>, game_settings: &rusty_chip::GameSettings) -> Result<(), String> {
+//! let mut runner = HeadlessRunner::new(game_data, game_settings)?;
+//! if let Some(reason) = runner.run(600) {
+//!     eprintln!("Halted: {reason}");
+//! }
+//! runner.dump_frame("final.png").map_err(|e| e.to_string())?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::io;
+
+use crate::emulator::{Emulator, EmulatorMode};
+use crate::interpreter::{HaltReason, Snapshot};
+use crate::{savestate, screenshot, GameSettings};
+
+/// Stores the files to dump the game to once a headless run ends.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DumpPaths {
+    /// An optional path to a save state file the final state of the game is written to.
+    pub state_path: Option<String>,
+    /// An optional path to a PNG file the final display of the game is written to.
+    pub frame_path: Option<String>
+}
+
+/// Runs a game without a window or audio, counting the frames run.
+pub struct HeadlessRunner {
+    emulator: Box<Emulator>,
+    frames_run: u64
+}
+
+impl HeadlessRunner {
+    /// Returns a runner with the game loaded, ready to run its first frame.
+    ///
+    /// # Parameters
+    ///
+    /// * `game_data` - The bytes of the game file.
+    /// * `game_settings` - The quirks, cycles per frame, load address, and banking to run the game with, and whether the [database](crate::database) may override them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the load address leaves no room for the font or is past the bank window while banking.
+    pub fn new(game_data: Vec<u8>, game_settings: &GameSettings) -> Result<HeadlessRunner, String> {
+        Ok(HeadlessRunner { emulator: crate::headless_emulator(game_data, game_settings)?, frames_run: 0 })
+    }
+
+    /// Returns the emulator running the game.
+    #[must_use]
+    pub fn emulator(&self) -> &Emulator {
+        &self.emulator
+    }
+
+    /// Returns the emulator running the game, e.g. to enable profiling or press keys before running it.
+    pub fn emulator_mut(&mut self) -> &mut Emulator {
+        &mut self.emulator
+    }
+
+    /// Returns the number of frames run so far.
+    #[must_use]
+    pub fn frames_run(&self) -> u64 {
+        self.frames_run
+    }
+
+    /// Returns the reason the game halted, or `None` if it can still run.
+    #[must_use]
+    pub fn halt_reason(&self) -> Option<HaltReason> {
+        match self.emulator.mode() {
+            EmulatorMode::Halted(reason) => Some(reason),
+            _ => None
+        }
+    }
+
+    /// Runs a single frame of the game, returning the reason it halted if it did. Nothing is run once the game has halted.
+    pub fn step_frame(&mut self) -> Option<HaltReason> {
+        if let Some(reason) = self.halt_reason() {
+            return Some(reason);
+        }
+
+        self.emulator.step_frame();
+        self.frames_run += 1;
+        self.halt_reason()
+    }
+
+    /// Runs frames of the game until it halts or the provided number of frames have been run, returning the reason it halted if it did.
+    ///
+    /// # Parameters
+    ///
+    /// * `max_frames` - The most frames to run.
+    pub fn run(&mut self, max_frames: u64) -> Option<HaltReason> {
+        for _ in 0..max_frames {
+            if let Some(reason) = self.step_frame() {
+                return Some(reason);
+            }
+        }
+
+        None
+    }
+
+    /// Returns the state of the game, as a save state would store it.
+    #[must_use]
+    pub fn snapshot(&self) -> Snapshot {
+        self.emulator.interpreter().snapshot()
+    }
+
+    /// Returns a PNG of the display, in the colours of the game's palette.
+    #[must_use]
+    pub fn frame_png(&self) -> Vec<u8> {
+        screenshot::screenshot_png(self.emulator.interpreter().framebuffer(), self.emulator.renderer().palette())
+    }
+
+    /// Writes the state of the game to a save state file through the emulator's [storage backend](Emulator::set_storage), which can be resumed from or [compared](crate::state_diff) against another.
+    ///
+    /// # Parameters
+    ///
+    /// * `path` - The path of the file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the file cannot be written.
+    pub fn dump_state(&mut self, path: &str) -> io::Result<()> {
+        let state = savestate::encode(&self.snapshot());
+        self.emulator.storage_mut().write(path, &state)
+    }
+
+    /// Writes a PNG of the display to a file through the emulator's [storage backend](Emulator::set_storage).
+    ///
+    /// # Parameters
+    ///
+    /// * `path` - The path of the file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the file cannot be written.
+    pub fn dump_frame(&mut self, path: &str) -> io::Result<()> {
+        let png = self.frame_png();
+        self.emulator.storage_mut().write(path, &png)
+    }
+
+    /// Writes the state and display of the game to the files provided through the emulator's storage backend, returning a line per file saying where it was written.
+    ///
+    /// # Parameters
+    ///
+    /// * `dump_paths` - The files to write.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` naming the file which cannot be written.
+    pub fn dump(&mut self, dump_paths: &DumpPaths) -> Result<Vec<String>, String> {
+        let mut lines = Vec::new();
+        if let Some(path) = &dump_paths.state_path {
+            self.dump_state(path).map_err(|e| format!("Error writing the state to {path}: {e}"))?;
+            lines.push(format!("Wrote the state to {path}."));
+        }
+        if let Some(path) = &dump_paths.frame_path {
+            self.dump_frame(path).map_err(|e| format!("Error writing the display to {path}: {e}"))?;
+            lines.push(format!("Wrote the display to {path}."));
+        }
+
+        Ok(lines)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::quirks::QuirkConfig;
+    use crate::interpreter::PROGRAM_START_ADDRESS;
+    use crate::storage::MemoryStorage;
+
+    fn game_settings() -> GameSettings {
+        GameSettings { quirk_config: QuirkConfig::new(), cycles_per_frame: 10, load_address: PROGRAM_START_ADDRESS, banking: false, hires_detection: true, use_database: false, use_config: false, palette: None, pure: false }
+    }
+
+    #[test]
+    fn run_until_halted() {
+        // Sets V3, then jumps to an unrecognized opcode
+        let mut runner = HeadlessRunner::new(vec![0x63, 0x1F, 0x12, 0x04, 0xFF, 0xFF], &game_settings()).unwrap();
+        let reason = runner.run(60);
+        assert_eq!(reason, Some(HaltReason::InvalidOpcode { address: 0x204, opcode: 0xFFFF }), "Halt not reported.");
+        assert_eq!(runner.frames_run(), 1, "Frames run after halting.");
+        assert_eq!(runner.step_frame(), reason, "Halted game stepped.");
+        assert_eq!(runner.frames_run(), 1, "Frame counted after halting.");
+        assert_eq!(runner.emulator().interpreter().registers()[3], 0x1F, "State not kept after halting.");
+        assert!(runner.frame_png().starts_with(b"\x89PNG"), "Display not captured as a PNG.");
+    }
+
+    #[test]
+    fn run_for_max_frames() {
+        // Jumps to itself forever
+        let mut runner = HeadlessRunner::new(vec![0x12, 0x00], &game_settings()).unwrap();
+        assert_eq!(runner.run(30), None, "Spinning game halted.");
+        assert_eq!(runner.frames_run(), 30, "Frames run incorrectly.");
+    }
+
+    #[test]
+    fn dump_to_storage() {
+        let mut runner = HeadlessRunner::new(vec![0x63, 0x1F, 0x12, 0x02], &game_settings()).unwrap();
+        runner.emulator_mut().set_storage(Box::new(MemoryStorage::new()));
+        runner.run(1);
+
+        let dump_paths = DumpPaths { state_path: Some(String::from("dumps/final.rcss")), frame_path: Some(String::from("dumps/final.png")) };
+        assert_eq!(runner.dump(&dump_paths).unwrap().len(), 2, "Dumps not reported.");
+        assert_eq!(runner.emulator().storage().read("dumps/final.rcss").unwrap(), Some(savestate::encode(&runner.snapshot())), "State not dumped to the storage backend.");
+        assert_eq!(runner.emulator().storage().read("dumps/final.png").unwrap(), Some(runner.frame_png()), "Display not dumped to the storage backend.");
+    }
+}
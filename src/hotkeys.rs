@@ -0,0 +1,283 @@
+//! A module to contain the emulator's hotkeys, which map key chords (a key along with any held modifiers) to emulator actions.
+//! Chords allow actions to be bound with Ctrl, Shift, or Alt so that they do not collide with the keys used by the CHIP-8 keypad.
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use sdl2::keyboard::{Keycode, Mod};
+
+/// The number keys along the top of the keyboard, bound to the save and macro slots they are numbered after.
+const SLOT_KEYS: [Keycode; 9] = [Keycode::Num1, Keycode::Num2, Keycode::Num3, Keycode::Num4, Keycode::Num5, Keycode::Num6, Keycode::Num7, Keycode::Num8, Keycode::Num9];
+
+/// Denotes an action of the emulator (rather than the game) which can be bound to a hotkey.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    LoadGame,
+    Reset,
+    TogglePaletteMenu,
+    ToggleQuirkMenu,
+    SwitchQuirkSide,
+    ToggleLockstep,
+    ToggleDrawStats,
+    ToggleInputDisplay,
+    ToggleMemoryView,
+    /// Pauses or resumes the game without the debugger's status in the window title.
+    TogglePause,
+    ToggleDebuggerPause,
+    StepInstruction,
+    StepBack,
+    /// Runs a single frame of a paused game, or pauses a running one.
+    AdvanceFrame,
+    /// Fast-forwards the game while held. Only bound once the key is configured.
+    Turbo,
+    /// Runs the game at the next faster speed.
+    SpeedUp,
+    /// Runs the game at the next slower speed.
+    SlowDown,
+    Poke,
+    ToggleBreakpoint,
+    CopyDisassembly,
+    CopyRegisters,
+    CopyFramebufferHash,
+    ShowNotes,
+    ShowAbout,
+    /// Saves the game to the numbered save slot.
+    SaveSlot(u8),
+    /// Loads the game from the numbered save slot.
+    LoadSlot(u8),
+    /// Starts recording a macro, or stops recording and saves it to the numbered macro slot.
+    RecordMacro(u8),
+    /// Plays the macro in the numbered macro slot.
+    PlayMacro(u8)
+}
+
+/// Stores which modifier keys must be held for a chord, regardless of which side of the keyboard they are on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Modifiers {
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool
+}
+
+impl Modifiers {
+    /// Returns the modifiers held according to SDL, ignoring lock keys such as caps lock.
+    ///
+    /// # Parameters
+    ///
+    /// * `keymod` - The modifier state of a keyboard event.
+    #[must_use]
+    pub fn from_keymod(keymod: Mod) -> Modifiers {
+        Modifiers {
+            ctrl: keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD),
+            shift: keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD),
+            alt: keymod.intersects(Mod::LALTMOD | Mod::RALTMOD)
+        }
+    }
+}
+
+/// Stores a key along with the modifiers which must be held alongside it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Chord {
+    pub keycode: Keycode,
+    pub modifiers: Modifiers
+}
+
+impl Chord {
+    /// Returns a chord for the key alone, without any modifiers.
+    ///
+    /// # Parameters
+    ///
+    /// * `keycode` - The physical key.
+    #[must_use]
+    pub fn key(keycode: Keycode) -> Chord {
+        Chord { keycode, modifiers: Modifiers::default() }
+    }
+
+    /// Returns a chord for the key while Ctrl is held.
+    ///
+    /// # Parameters
+    ///
+    /// * `keycode` - The physical key.
+    #[must_use]
+    pub fn ctrl(keycode: Keycode) -> Chord {
+        Chord { keycode, modifiers: Modifiers { ctrl: true, ..Modifiers::default() } }
+    }
+
+    /// Returns a chord for the key while Shift is held.
+    ///
+    /// # Parameters
+    ///
+    /// * `keycode` - The physical key.
+    #[must_use]
+    pub fn shift(keycode: Keycode) -> Chord {
+        Chord { keycode, modifiers: Modifiers { shift: true, ..Modifiers::default() } }
+    }
+
+    /// Returns a chord for the key while Alt is held.
+    ///
+    /// # Parameters
+    ///
+    /// * `keycode` - The physical key.
+    #[must_use]
+    pub fn alt(keycode: Keycode) -> Chord {
+        Chord { keycode, modifiers: Modifiers { alt: true, ..Modifiers::default() } }
+    }
+}
+
+impl Display for Chord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.modifiers.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.shift {
+            write!(f, "Shift+")?;
+        }
+        if self.modifiers.alt {
+            write!(f, "Alt+")?;
+        }
+
+        write!(f, "{}", self.keycode.name())
+    }
+}
+
+impl FromStr for Chord {
+    type Err = String;
+
+    /// Parses a chord of the form `Ctrl+Shift+F5`, where the key is named as SDL names it.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts: Vec<&str> = s.split('+').map(str::trim).collect();
+        let key_name = parts.pop().filter(|name| !name.is_empty()).ok_or_else(|| format!("Missing key in chord \"{s}\"."))?;
+        let keycode = Keycode::from_name(key_name).ok_or_else(|| format!("Unknown key \"{key_name}\" in chord \"{s}\"."))?;
+
+        let mut modifiers = Modifiers::default();
+        for part in parts {
+            match part.to_ascii_lowercase().as_str() {
+                "ctrl" => modifiers.ctrl = true,
+                "shift" => modifiers.shift = true,
+                "alt" => modifiers.alt = true,
+                _ => return Err(format!("Unknown modifier \"{part}\" in chord \"{s}\"."))
+            }
+        }
+
+        Ok(Chord { keycode, modifiers })
+    }
+}
+
+/// Maps chords to the actions they trigger.
+#[derive(Debug, Clone)]
+pub struct Hotkeys {
+    bindings: HashMap<Chord, Action>
+}
+
+impl Hotkeys {
+    /// Returns the default bindings, none of which collide with the keypad mapping.
+    #[must_use]
+    pub fn new() -> Hotkeys {
+        let mut hotkeys = Hotkeys { bindings: HashMap::new() };
+        hotkeys.bind(Chord::key(Keycode::Escape), Action::Quit);
+        hotkeys.bind(Chord::key(Keycode::L), Action::LoadGame);
+        hotkeys.bind(Chord::ctrl(Keycode::R), Action::Reset);
+        hotkeys.bind(Chord::key(Keycode::Backspace), Action::Reset);
+        hotkeys.bind(Chord::key(Keycode::P), Action::TogglePause);
+        hotkeys.bind(Chord::key(Keycode::M), Action::TogglePaletteMenu);
+        hotkeys.bind(Chord::key(Keycode::K), Action::ToggleQuirkMenu);
+        hotkeys.bind(Chord::key(Keycode::Tab), Action::SwitchQuirkSide);
+        hotkeys.bind(Chord::shift(Keycode::Tab), Action::ToggleLockstep);
+        hotkeys.bind(Chord::key(Keycode::F3), Action::ToggleDrawStats);
+        hotkeys.bind(Chord::key(Keycode::F4), Action::ToggleInputDisplay);
+        hotkeys.bind(Chord::key(Keycode::F5), Action::ToggleDebuggerPause);
+        hotkeys.bind(Chord::key(Keycode::F6), Action::ToggleMemoryView);
+        hotkeys.bind(Chord::key(Keycode::F9), Action::ToggleBreakpoint);
+        hotkeys.bind(Chord::key(Keycode::F10), Action::StepInstruction);
+        hotkeys.bind(Chord::shift(Keycode::F10), Action::StepBack);
+        hotkeys.bind(Chord::key(Keycode::F11), Action::AdvanceFrame);
+        hotkeys.bind(Chord::key(Keycode::F7), Action::Poke);
+        // Plus shares its key with equals, so it is bound with and without Shift
+        hotkeys.bind(Chord::key(Keycode::Equals), Action::SpeedUp);
+        hotkeys.bind(Chord::shift(Keycode::Equals), Action::SpeedUp);
+        hotkeys.bind(Chord::key(Keycode::KpPlus), Action::SpeedUp);
+        hotkeys.bind(Chord::key(Keycode::Minus), Action::SlowDown);
+        hotkeys.bind(Chord::key(Keycode::KpMinus), Action::SlowDown);
+        hotkeys.bind(Chord::ctrl(Keycode::C), Action::CopyDisassembly);
+        hotkeys.bind(Chord { keycode: Keycode::C, modifiers: Modifiers { ctrl: true, shift: true, alt: false } }, Action::CopyRegisters);
+        hotkeys.bind(Chord::ctrl(Keycode::H), Action::CopyFramebufferHash);
+        hotkeys.bind(Chord::key(Keycode::F1), Action::ShowAbout);
+        hotkeys.bind(Chord::key(Keycode::F2), Action::ShowNotes);
+        // The number keys alone are on the keypad, so the slots are only reached with a modifier
+        for (slot, keycode) in (1..).zip(SLOT_KEYS) {
+            hotkeys.bind(Chord::shift(keycode), Action::SaveSlot(slot));
+            hotkeys.bind(Chord::ctrl(keycode), Action::LoadSlot(slot));
+            hotkeys.bind(Chord::alt(keycode), Action::PlayMacro(slot));
+            hotkeys.bind(Chord { keycode, modifiers: Modifiers { ctrl: true, shift: false, alt: true } }, Action::RecordMacro(slot));
+        }
+
+        hotkeys
+    }
+
+    /// Binds the chord to the action, replacing any action it was previously bound to.
+    ///
+    /// # Parameters
+    ///
+    /// * `chord` - The chord to bind.
+    /// * `action` - The action the chord will trigger.
+    pub fn bind(&mut self, chord: Chord, action: Action) {
+        self.bindings.insert(chord, action);
+    }
+
+    /// Returns the action bound to the key press, if any.
+    /// The held modifiers must match the chord exactly, so e.g. Ctrl+Shift+R does not trigger Ctrl+R.
+    ///
+    /// # Parameters
+    ///
+    /// * `keycode` - The physical key pressed.
+    /// * `keymod` - The modifier state of the key press.
+    #[must_use]
+    pub fn action_for(&self, keycode: Keycode, keymod: Mod) -> Option<Action> {
+        self.bindings.get(&Chord { keycode, modifiers: Modifiers::from_keymod(keymod) }).copied()
+    }
+}
+
+impl Default for Hotkeys {
+    fn default() -> Self {
+        Hotkeys::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn action_for_chords() {
+        let mut hotkeys = Hotkeys::new();
+        hotkeys.bind(Chord::shift(Keycode::F5), Action::Reset);
+
+        assert_eq!(hotkeys.action_for(Keycode::L, Mod::NOMOD), Some(Action::LoadGame), "Plain key not matched.");
+        assert_eq!(hotkeys.action_for(Keycode::L, Mod::NUMMOD | Mod::CAPSMOD), Some(Action::LoadGame), "Lock keys not ignored.");
+        assert_eq!(hotkeys.action_for(Keycode::R, Mod::NOMOD), None, "Keypad key matched without its modifier.");
+        assert_eq!(hotkeys.action_for(Keycode::R, Mod::RCTRLMOD), Some(Action::Reset), "Right Ctrl chord not matched.");
+        assert_eq!(hotkeys.action_for(Keycode::R, Mod::LCTRLMOD | Mod::LSHIFTMOD), None, "Chord matched with extra modifiers.");
+        assert_eq!(hotkeys.action_for(Keycode::F5, Mod::LSHIFTMOD), Some(Action::Reset), "Bound chord not matched.");
+        assert_eq!(hotkeys.action_for(Keycode::F11, Mod::NOMOD), Some(Action::AdvanceFrame), "Frame advance not bound.");
+        assert_eq!(hotkeys.action_for(Keycode::Backspace, Mod::NOMOD), Some(Action::Reset), "Backspace not bound to reset.");
+        assert_eq!(hotkeys.action_for(Keycode::Equals, Mod::LSHIFTMOD), Some(Action::SpeedUp), "Plus not bound.");
+        assert_eq!(hotkeys.action_for(Keycode::Num3, Mod::LSHIFTMOD), Some(Action::SaveSlot(3)), "Save slot chord not matched.");
+        assert_eq!(hotkeys.action_for(Keycode::Num9, Mod::LCTRLMOD), Some(Action::LoadSlot(9)), "Load slot chord not matched.");
+        assert_eq!(hotkeys.action_for(Keycode::Num1, Mod::NOMOD), None, "Keypad key bound to a save slot.");
+        assert_eq!(hotkeys.action_for(Keycode::Num2, Mod::LALTMOD), Some(Action::PlayMacro(2)), "Play macro chord not matched.");
+        assert_eq!(hotkeys.action_for(Keycode::Num2, Mod::LCTRLMOD | Mod::RALTMOD), Some(Action::RecordMacro(2)), "Record macro chord not matched.");
+    }
+
+    #[test]
+    fn chord_text_round_trip() {
+        let chord: Chord = "ctrl + shift + F5".parse().unwrap();
+        assert_eq!(chord, Chord { keycode: Keycode::F5, modifiers: Modifiers { ctrl: true, shift: true, alt: false } }, "Chord parsed incorrectly.");
+        assert_eq!(chord.to_string(), "Ctrl+Shift+F5", "Chord formatted incorrectly.");
+        assert_eq!("Escape".parse::<Chord>(), Ok(Chord::key(Keycode::Escape)), "Plain key parsed incorrectly.");
+        assert!("Ctrl+".parse::<Chord>().is_err(), "Chord without a key parsed.");
+        assert!("Meta+R".parse::<Chord>().is_err(), "Unknown modifier parsed.");
+        assert!("Ctrl+Nonsense".parse::<Chord>().is_err(), "Unknown key parsed.");
+    }
+}
@@ -2,24 +2,345 @@
 //!
 //! `rusty_chip` is an implementation of a CHIP-8 emulator written in Rust.
 //! It is a first project in Rust for the author and as such is primarily a learning experience.
+//!
+//! The emulation itself lives in the `rusty_chip_core` crate, which does not depend on SDL, and its modules are re-exported here alongside the SDL [frontend](frontend). Embedders who do not want SDL2 and rfd should depend on the core directly.
+//! Embedders should import the stable surface through the [`prelude`](prelude). Modules hidden from the documentation are internal to the emulator's frontend and may change at any time.
 
-use std::{fs, io, time::Duration};
+use std::{fs, io};
+use std::time::Duration;
+#[cfg(feature = "sdl")]
+use std::time::Instant;
 use std::io::ErrorKind;
+#[cfg(feature = "sdl")]
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, SyncSender};
+#[cfg(feature = "sdl")]
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+#[cfg(feature = "sdl")]
+use std::thread;
 
+#[cfg(feature = "sdl")]
 use rfd::FileDialog;
-use sdl2::{event::Event, keyboard::Keycode};
-use sdl2::audio::AudioSpecDesired;
-use sdl2::messagebox::MessageBoxFlag;
+#[cfg(feature = "sdl")]
+use sdl2::{event::Event, keyboard::{Keycode, Mod}};
+#[cfg(feature = "sdl")]
+use sdl2::mouse::MouseButton;
 
-use audio::SquareWave;
+use emulator::{Emulator, LoadSettings};
+#[cfg(feature = "sdl")]
+use emulator::{EmulatorMode, FRAME_DURATION};
 use interpreter::Interpreter;
+#[cfg(feature = "sdl")]
+use interpreter::{HaltReason, MemoryProtection};
 
+#[cfg(feature = "sdl")]
+use crate::backend::{Display, MessageKind};
+use crate::bench::{BenchReport, BenchResult};
+use crate::compat::{CompatReport, GameResult};
+#[cfg(feature = "sdl")]
+use crate::config::{Config, RomOverrides, CONFIG_PATH};
+use crate::database::DatabaseEntry;
+use crate::dispatch::Dispatch;
+#[cfg(feature = "sdl")]
+use crate::events::EventLog;
+use crate::frame_log::Subsystem;
+#[cfg(feature = "sdl")]
+use crate::frontend::{SdlDisplay, SdlKeyboard};
+use crate::fuzz::{Fault, FuzzConfig, InputScript};
+use crate::headless::{DumpPaths, HeadlessRunner};
+#[cfg(feature = "sdl")]
+use crate::graph::{GraphPanel, GRAPH_REFRESH_FRAMES};
+#[cfg(feature = "sdl")]
+use crate::hotkeys::{Action, Chord, Hotkeys};
+#[cfg(feature = "sdl")]
+use crate::key_profile::KeyProfile;
+#[cfg(feature = "sdl")]
+use crate::layout::OverlayLayout;
+#[cfg(feature = "sdl")]
+use crate::metrics::MetricsLogger;
+use crate::names::Named;
+use crate::palette::Palette;
+#[cfg(feature = "sdl")]
+use crate::palette::PaletteEditor;
+#[cfg(feature = "sdl")]
+use crate::poke::Poke;
 use crate::quirks::QuirkConfig;
+use crate::screenshot::{AutoScreenshot, ScreenshotTrigger};
+use crate::state_diff::StateDiff;
+use crate::storage::FileStorage;
+#[cfg(feature = "sdl")]
+use crate::storage::StorageBackend;
+use crate::trace::TraceLogger;
+#[cfg(feature = "sdl")]
+use crate::quirks::QuirkEditor;
+use crate::speedrun::Condition;
+use crate::symbols::SymbolTable;
+#[cfg(feature = "sdl")]
+use crate::speedrun::{SpeedrunTimer, Splits};
+#[cfg(feature = "sdl")]
+use crate::watch::{WatchConfig, WatchExpression, WatchLogger};
+
+// The core is re-exported whole, so that the emulator's frontends and embedders alike can reach it through this crate
+pub use rusty_chip_core::{audio, backend, banking, bench, builder, compat, config, coverage, database, debugger, dispatch, emulator, events, extensions, frame_log, framebuffer, fuzz, graph, hooks, input_display, interpreter, key_profile, keypad, layout, macros, memory_view, metrics, names, notes, opcodes, palette, poke, prelude, profiler, provenance, quirks, renderer, savestate, screenshot, speedrun, state_diff, storage, symbols, trace, watch};
+use rusty_chip_core::clipboard;
+#[cfg(feature = "serde")]
+pub use rusty_chip_core::serialization;
+#[cfg(feature = "alloc-tracking")]
+#[doc(hidden)]
+pub use rusty_chip_core::alloc_tracker;
+
+#[cfg(feature = "sdl")]
+pub mod frontend;
+#[cfg(feature = "sdl")]
+mod hotkeys;
+#[cfg(feature = "sdl")]
+mod about;
+pub mod demos;
+pub mod headless;
+#[cfg(feature = "tui")]
+pub mod tui;
+#[cfg(feature = "egui")]
+pub mod debug_ui;
+
+#[cfg(feature = "sdl")]
+const WINDOW_TITLE: &str = "RustyChip";
+
+/// The factor the cycles per frame are multiplied by while fast-forwarding, unless configured otherwise.
+pub const DEFAULT_TURBO_FACTOR: u32 = 4;
+
+/// The chord held to fast-forward, unless configured otherwise.
+pub const DEFAULT_TURBO_KEY: &str = "Space";
+
+/// The speeds the speed hotkeys step through, relative to the usual speed.
+#[cfg(feature = "sdl")]
+const SPEED_STEPS: [f64; 8] = [0.25, 0.5, 0.75, 1.0, 1.5, 2.0, 3.0, 4.0];
+
+/// Denotes which menu has the input while the emulator is in the menu mode.
+#[cfg(feature = "sdl")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Menu {
+    Palette,
+    Quirks
+}
+
+/// Denotes the input the window's thread forwards to the game on the emulation thread.  
+/// Input is sent rather than applied by the window's thread, so that the game gets it without the window waiting for the emulator to be unlocked.
+#[cfg(feature = "sdl")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EmulationCommand {
+    PressKey(Keycode),
+    ReleaseKey(Keycode)
+}
+
+#[cfg(feature = "sdl")]
+impl EmulationCommand {
+    /// Forwards the input to the game.
+    ///
+    /// # Parameters
+    ///
+    /// * `emulator` - The emulator running the game.
+    fn apply(self, emulator: &mut Emulator) {
+        match self {
+            EmulationCommand::PressKey(keycode) => emulator.handle_key_press(&SdlKeyboard, keycode),
+            EmulationCommand::ReleaseKey(keycode) => emulator.handle_key_release(&SdlKeyboard, keycode)
+        }
+    }
+}
+
+/// Stores what watches every frame run on the emulation thread: the logs of the watch expressions and metrics, the graphs, and the simulated slow host.
+#[cfg(feature = "sdl")]
+struct FrameObservers {
+    watch_logger: Option<WatchLogger>,
+    watch_expressions: Vec<WatchExpression>,
+    metrics_logger: Option<MetricsLogger<io::BufWriter<fs::File>>>,
+    graph_panel: Option<GraphPanel>,
+    slow_host_frame_time: Option<Duration>
+}
+
+#[cfg(feature = "sdl")]
+impl FrameObservers {
+    /// Returns the observers of a run, creating the files the watches and metrics are logged to.
+    ///
+    /// # Parameters
+    ///
+    /// * `watch_config` - The expressions to watch and where to log them.
+    /// * `debug_config` - The metrics file and simulated slow host to run with.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the watch log or metrics file cannot be created.
+    fn create(watch_config: &WatchConfig, debug_config: &DebugConfig) -> Result<FrameObservers, String> {
+        let watch_logger = match &watch_config.csv_path {
+            Some(csv_path) => Some(WatchLogger::create(csv_path, &watch_config.expressions).map_err(|e| format!("Error creating watch log: {e}"))?),
+            None => None
+        };
+        let metrics_logger = match &debug_config.metrics_path {
+            Some(metrics_path) => Some(MetricsLogger::create(metrics_path).map_err(|e| format!("Error creating metrics file: {e}"))?),
+            None => None
+        };
+
+        // Graphs of the expressions over the recent frames, redrawn in the terminal
+        let graph_panel = (!watch_config.graphs.is_empty()).then(|| GraphPanel::new(&watch_config.graphs, watch_config.graph_width));
+
+        Ok(FrameObservers {
+            watch_logger,
+            watch_expressions: watch_config.expressions.clone(),
+            metrics_logger,
+            graph_panel,
+            slow_host_frame_time: debug_config.slow_host_frame_time
+        })
+    }
+
+    /// Logs the frame which has just run, slowing it down as much as the simulated slow host would.
+    ///
+    /// # Parameters
+    ///
+    /// * `emulator` - The emulator which ran the frame.
+    fn observe(&mut self, emulator: &Emulator) {
+        if let Some(slow_host_frame_time) = self.slow_host_frame_time {
+            thread::sleep(slow_host_frame_time);
+        }
+
+        if let Some(logger) = self.watch_logger.as_mut() {
+            if let Err(e) = logger.log(emulator.frame_count(), &self.watch_expressions, emulator.interpreter()) {
+                frame_log::warn(Subsystem::Emulator, format_args!("Error logging watches: {e}"));
+            }
+        }
+
+        if let Some(logger) = self.metrics_logger.as_mut() {
+            if let Err(e) = logger.log(emulator.frame_count(), emulator.interpreter()) {
+                frame_log::warn(Subsystem::Emulator, format_args!("Error logging metrics: {e}"));
+            }
+        }
+
+        if let Some(panel) = self.graph_panel.as_mut() {
+            panel.record(emulator.interpreter());
+        }
+    }
 
-pub mod opcodes;
-pub mod interpreter;
-pub mod audio;
-pub mod quirks;
+    /// Redraws the graphs in place a few times a second.
+    ///
+    /// # Parameters
+    ///
+    /// * `emulator` - The emulator which ran the frames.
+    /// * `frames` - The number of frames just run.
+    fn redraw_graphs(&self, emulator: &Emulator, frames: u32) {
+        if let Some(panel) = &self.graph_panel {
+            if frames > 0 && emulator.frame_count() % GRAPH_REFRESH_FRAMES < u64::from(frames) {
+                print!("\x1B[2J\x1B[H{}", panel.render());
+            }
+        }
+    }
+}
+
+/// Stores the settings games are run with unless the [database](database) knows better.
+pub struct GameSettings {
+    /// The enabled/disabled status of all the quirks.
+    pub quirk_config: QuirkConfig,
+    /// The number of instruction cycles to run in the emulator per frame (the emulator runs at 60 fps).
+    pub cycles_per_frame: u32,
+    /// The address games are loaded and started at.
+    pub load_address: u16,
+    /// True if games are split into [banks](banking) which they can switch between, false to load them whole.
+    pub banking: bool,
+    /// True if HiRes CHIP-8 games (starting with `1260`) are recognized and run on their 64x64 display, false to run them as regular games.
+    pub hires_detection: bool,
+    /// True if games found in the database should run with the settings it recommends, false to always use the provided ones.
+    pub use_database: bool,
+    /// True if games with a section in the [config](config) file should run with the settings it overrides, false to ignore the file.
+    pub use_config: bool,
+    /// The colours games are drawn in unless they have their own, or `None` for those of the [config](config) file (or the default ones).
+    pub palette: Option<Palette>,
+    /// True if the emulator should run in [pure mode](Emulator::set_pure), with every nonstandard extension, cheat, and overlay turned off.
+    pub pure: bool
+}
+
+/// Stores the debugging aids the emulator runs with.
+#[derive(Debug, Clone, Default)]
+pub struct DebugConfig {
+    /// An optional path to a file which the per-frame metrics will be exported to.
+    pub metrics_path: Option<String>,
+    /// An optional path to a file which every instruction run will be logged to.
+    pub trace_path: Option<String>,
+    /// The addresses of the instructions to pause the game before.
+    pub breakpoints: Vec<u16>,
+    /// The conditions to pause the game after the instruction meeting them.
+    pub break_conditions: Vec<Condition>,
+    /// True if the opcodes run should be [profiled](profiler), with the profile printed on exit.
+    pub profile: bool,
+    /// True if the instructions and kinds of opcode run should be recorded, with a [coverage](coverage) summary printed on exit.
+    pub coverage: bool,
+    /// The number of frames of [events](events) to record for stepping back, or `None` to not record them.
+    pub event_window: Option<u64>,
+    /// The extra time every frame takes to run, to simulate a host too slow to keep up with the emulator (e.g. while working on the frame pacing).
+    pub slow_host_frame_time: Option<Duration>,
+    /// The labels of the game, shown by the debugger and trace log alongside the addresses they mark.
+    pub symbols: SymbolTable,
+    /// The events to save a [screenshot](screenshot) of the display for, and where to save them.
+    pub auto_screenshot: AutoScreenshot
+}
+
+/// Stores how the user can change the speed of the game while playing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpeedConfig {
+    /// The speed the game starts at relative to its usual speed, from [`MIN_SPEED`](emulator::MIN_SPEED) to [`MAX_SPEED`](emulator::MAX_SPEED).
+    pub speed: f64,
+    /// The factor the cycles per frame are multiplied by while [fast-forwarding](Emulator::set_turbo).
+    pub turbo_factor: u32,
+    /// The chord held to fast-forward, as SDL names its key (e.g. `Space` or `Ctrl+F`).
+    pub turbo_key: String
+}
+
+impl Default for SpeedConfig {
+    fn default() -> Self {
+        SpeedConfig { speed: 1.0, turbo_factor: DEFAULT_TURBO_FACTOR, turbo_key: String::from(DEFAULT_TURBO_KEY) }
+    }
+}
+
+/// Stores what the window's thread keeps between frames: the game being played, what is shown alongside it, and the state of the menus and prompts.
+#[cfg(feature = "sdl")]
+struct WindowState {
+    game_settings: GameSettings,
+    current_game_path: Option<String>,
+    auto_screenshot: AutoScreenshot,
+    watch_expressions: Vec<WatchExpression>,
+    // Keys which trigger emulator actions rather than going to the game
+    hotkeys: Hotkeys,
+    turbo_chord: Chord,
+    turbo_factor: u32,
+    // Whether the draw budget of each frame is shown in the window title
+    show_draw_stats: bool,
+    // Whether the RAM around the program counter and register I is printed whenever the debugger stops
+    show_memory_view: bool,
+    // The poke being typed while the game is paused, if the prompt is open
+    poke_prompt: Option<String>,
+    // The menu which has the input, if one is open. Only this thread opens and closes menus, so it is known without locking the emulator
+    open_menu: Option<Menu>,
+    palette_editor: PaletteEditor,
+    quirk_editor: QuirkEditor,
+    // Whether the game was paused by the system suspending it, and so should be resumed along with the system
+    paused_for_background: bool,
+    // The mode the emulator was left in after the last events were handled, to tell what the emulation thread changed since
+    seen_mode: EmulatorMode
+}
+
+/// Denotes a dialog asked for by an event, shown once the emulator is unlocked so that the emulation thread is not held up while the user answers.
+#[cfg(feature = "sdl")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Dialog {
+    PickGame,
+    OpenGame(String),
+    ShowNotes(String),
+    ShowAbout
+}
+
+/// Denotes what the window's thread does after an event has been handled.
+#[cfg(feature = "sdl")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum EventOutcome {
+    Handled,
+    Quit,
+    Show(Dialog)
+}
 
 /// Runs the actual emulator.
 /// Returns either an `OK` signifying the process ended successfully or an `Err` containing a `String` which mentions the issue.
@@ -27,185 +348,2008 @@ pub mod quirks;
 /// # Parameters
 ///
 /// * `path` - An optional path to a chosen game.
-/// * `cycles_per_frame` - The number of instruction cycles to run in the emulator per frame (the emulator runs at 60 fps).
-/// * `quirk_config` - The enabled/disabled status of all the quirks.
+/// * `resume_state` - An optional save state to resume the chosen game from: the number of one of its save slots, or the path to a save state file.
+/// * `game_settings` - The quirks, cycles per frame, load address, and banking to run games with, and whether the [database](database) may override them.
+/// * `memory_protection` - The regions of memory the game is prevented from writing to.
+/// * `watch_config` - The expressions to watch and where to log them.
+/// * `debug_config` - The metrics file, trace log, breakpoints (conditional or not), profiling, and automatic screenshots to run with.
+/// * `speed_config` - The speed the game starts at, the key held to fast-forward, and how much faster it runs the game.
 ///
 /// # Errors
 ///
 /// Returns an `Err` if:
+/// * The fast-forward key is not a valid chord.
 /// * The game file cannot be found or read.
+/// * The save state to resume from cannot be found or read.
+/// * The load address leaves no room for the font, or is past the bank window while banking.
+/// * The watch log, metrics file, or trace log cannot be created.
 /// * Any SDL system cannot be initialized.
-pub fn run(path: &Option<String>, cycles_per_frame: u32, quirk_config: QuirkConfig) -> Result<(), String> {
+#[cfg(feature = "sdl")]
+pub fn run(path: &Option<String>, resume_state: Option<&str>, game_settings: GameSettings, memory_protection: MemoryProtection, watch_config: WatchConfig, debug_config: DebugConfig, speed_config: &SpeedConfig) -> Result<(), String> {
     // Initialize SDL
     let sdl_context = sdl2::init()?;
     let video_subsystem = sdl_context.video()?;
 
     // Create the window
-    let window = video_subsystem.window("RustyChip", interpreter::SCALED_WIDTH, interpreter::SCALED_HEIGHT)
+    let mut window = video_subsystem.window(WINDOW_TITLE, interpreter::SCALED_WIDTH, interpreter::SCALED_HEIGHT)
         .position_centered()
         .build()
         .map_err(|window_build_error| window_build_error.to_string())?;
+    match about::window_icon() {
+        Ok(icon) => window.set_icon(icon),
+        Err(e) => eprintln!("Error loading window icon: {e}")
+    }
 
-    // Prepare the canvas
-    let mut canvas = window.into_canvas()
+    // Prepare the canvas, which stays on this thread while the interpreter draws to it from the emulation thread
+    let canvas = window.into_canvas()
         .build()
         .map_err(|integer_or_sdl_error| integer_or_sdl_error.to_string())?;
+    let mut display = SdlDisplay::new(canvas);
+
+    // Messages are shown through a display of this thread's own, so that the emulator is not kept locked while they wait on the user
+    let dialogs = display.relay_display();
 
     // Prepare the audio
-    // Mostly taken from the example provided by the crate
     let audio_subsystem = sdl_context.audio()?;
-    let desired_spec = AudioSpecDesired {
-        freq: Some(44100),
-        channels: Some(1),  // mono
-        samples: None       // default sample size
-    };
-    let audio_device = audio_subsystem.open_playback(None, &desired_spec, |spec| {
-        // initialize the audio callback
-        SquareWave {
-            #[allow(clippy::cast_precision_loss)]
-            phase_inc: 440.0 / spec.freq as f32,
-            phase: 0.0,
-            volume: 0.25
-        }
-    })?;
+    let (audio_device, audio_controller) = frontend::open_audio_device(&audio_subsystem)?;
+
+    // The generator is silent until its gate is opened, so the device can play for the whole run
+    audio_device.resume();
 
     // Prepare for events
     let mut event_pump = sdl_context.event_pump()?;
 
     // Prepare the emulator
-    let mut interpreter = Interpreter::new_with_sdl(Some(&mut canvas), Some(&audio_device), quirk_config);
+    let interpreter = Interpreter::builder()
+        .quirks(game_settings.quirk_config.clone())
+        .load_address(game_settings.load_address)
+        .banking(game_settings.banking)
+        .hires_detection(game_settings.hires_detection)
+        .memory_protection(memory_protection)
+        .display(Box::new(display.relay_display()))
+        .audio(Box::new(audio_controller))
+        .build()?;
+    let mut emulator = prepare_emulator(interpreter, &game_settings, &debug_config)?;
+    emulator.set_speed(speed_config.speed);
+    let seen_mode = emulator.mode();
+    let emulator = Arc::new(Mutex::new(emulator));
 
     // Read the game file
+    let mut current_game_path = None;
     if let Some(path) = path {
-        load_game_file(&mut interpreter, path)?;
+        if open_game(&emulator, &dialogs, path, &game_settings)? {
+            current_game_path = Some(path.clone());
+        }
     }
+    if let (Some(path), Some(resume_state)) = (&current_game_path, resume_state) {
+        let game_data = read_game_file(path).map_err(|e| e.to_string())?;
+        let mut emulator = lock_emulator(&emulator);
+        let snapshot = savestate::load_named_state(emulator.storage(), &game_data, resume_state).map_err(|e| format!("Error loading save state {resume_state}: {e}"))?;
+        emulator.queue_restore(snapshot);
+    }
+
+    let observers = FrameObservers::create(&watch_config, &debug_config)?;
+    let mut hotkeys = Hotkeys::new();
+    let turbo_chord: Chord = speed_config.turbo_key.parse()?;
+    hotkeys.bind(turbo_chord, Action::Turbo);
+    let mut state = WindowState {
+        game_settings,
+        current_game_path,
+        auto_screenshot: debug_config.auto_screenshot,
+        watch_expressions: watch_config.expressions,
+        hotkeys,
+        turbo_chord,
+        turbo_factor: speed_config.turbo_factor,
+        show_draw_stats: false,
+        show_memory_view: false,
+        poke_prompt: None,
+        open_menu: None,
+        palette_editor: PaletteEditor::default(),
+        quirk_editor: QuirkEditor::default(),
+        paused_for_background: false,
+        seen_mode
+    };
+
+    // Run the emulation on its own thread, so that its frames are paced without waiting on the window.
+    // Only one count of frames is buffered, so that a fast-forwarding emulator waits for this thread to catch up rather than keeping it locked out.
+    let (frame_sender, frame_receiver) = mpsc::sync_channel(1);
+    let (command_sender, command_receiver) = mpsc::channel();
+    let emulation_thread = {
+        let emulator = Arc::clone(&emulator);
+        thread::Builder::new()
+            .name(String::from("emulation"))
+            .spawn(move || run_emulation(&emulator, &frame_sender, &command_receiver, observers))
+            .map_err(|e| format!("Error starting the emulation thread: {e}"))?
+    };
 
     // The main game loop
     'game_loop: loop {
-        // Go through each event and handle them
+        // Wait for the emulation thread to run frames, handling events at least once a frame regardless
+        let frames = match frame_receiver.recv_timeout(FRAME_DURATION) {
+            Ok(frames) => frames + frame_receiver.try_iter().sum::<u32>(),
+            Err(RecvTimeoutError::Timeout) => 0,
+            Err(RecvTimeoutError::Disconnected) => break 'game_loop
+        };
+        let halt_reason = {
+            let mut emulator = lock_emulator(&emulator);
+            let halt_reason = report_frames(&mut emulator, &mut state, frames);
+            show_status(&mut emulator, &state, frames);
+            halt_reason
+        };
+
+        // Let the user know why the game stopped
+        if let Some(reason) = halt_reason {
+            report_halt(&emulator, &dialogs, &mut state, reason);
+        }
+
+        // Go through each event and handle them, locking the emulator for one at a time
         for event in event_pump.poll_iter() {
-            match event {
-                Event::Quit { .. } |
-                Event::KeyDown { keycode: Some(Keycode::Escape), .. } => {
+            // Input for the game is sent to the emulation thread instead, so that it is never held up waiting for the emulator
+            if let Some(command) = game_input(&state, &event) {
+                if command_sender.send(command).is_err() {
                     break 'game_loop;
-                },
-                Event::KeyDown { keycode: Some(Keycode::L), .. } => {
-                    let path = FileDialog::new()
-                        .add_filter("CHIP-8", &["ch8", "chip8"])
-                        .set_directory("games")
-                        .pick_file();
-                    if let Some(path) = path {
-                        if let Some(path) = path.to_str() {
-                            load_game_file(&mut interpreter, path)?;
-                        }
-                    }
-                },
-                Event::KeyDown { keycode: Some(keycode), .. } => {
-                    interpreter.handle_key_press(keycode);
-                },
-                Event::KeyUp { keycode: Some(keycode), .. } => {
-                    interpreter.handle_key_release(keycode);
-                },
-                Event::DropFile { filename, .. } => {
-                    load_game_file(&mut interpreter, &filename)?;
-                },
-                _ => {}
-            }
-        }
-
-        // Run the interpreter logic
-        for _ in 0..cycles_per_frame {
-            interpreter.handle_cycle();
-        }
-
-        // Draw the frame
-        interpreter.handle_frame();
-
-        // Wait the requisite time for the next iteration. Effectively sets it to 60fps / 60Hz.
-        std::thread::sleep(Duration::new(0, 1_000_000_000u32 / 60));
+                }
+                continue;
+            }
+
+            let outcome = handle_event(&mut lock_emulator(&emulator), &mut state, &video_subsystem, event);
+            match outcome {
+                EventOutcome::Handled => {},
+                EventOutcome::Quit => break 'game_loop,
+                EventOutcome::Show(dialog) => show_dialog(&emulator, &dialogs, &mut state, dialog)
+            }
+        }
+
+        state.seen_mode = render(&emulator, &mut display);
+    }
+
+    // Stop the emulation thread before reporting on the last game
+    drop(command_sender);
+    drop(frame_receiver);
+    emulation_thread.join().map_err(|_| String::from("The emulation thread panicked."))?;
+    let emulator = lock_emulator(&emulator);
+
+    // Show where the cycles of the last game went
+    if let Some(profile) = emulator.interpreter().profile() {
+        print!("{}", profile.report());
+    }
+
+    // Show which code and opcodes the last game ran
+    if let Some(coverage) = emulator.interpreter().coverage() {
+        print!("{}", coverage.report(&emulator.interpreter().program_range()));
     }
 
     // Return success
     Ok(())
 }
 
-/// Loads the game at the provided path into the emulator if possible, or an `Err` containing a `String` if the file could not be read.  
-/// If the file type is wrong (see [`read_game_file`](read_game_file)), then an error is logged and we continue as if nothing happened.
-/// 
+/// Returns an emulator running the interpreter with the settings and debugging aids of the run.
+///
+/// # Parameters
+///
+/// * `interpreter` - The interpreter, attached to the window.
+/// * `game_settings` - The settings games are run with.
+/// * `debug_config` - The debugging aids to run with.
+///
 /// # Errors
-/// 
-/// Returns the forwarded `Err` from [`read_game_file`](read_game_file) if the file fails to be read.
-fn load_game_file(interpreter: &mut Interpreter, path: &str) -> Result<(), String> {
-    match read_game_file(path) {
-        Ok(game_data) => {
-            interpreter.load_game(&game_data);
-            Ok(())
-        },
-        Err(ref e) if e.kind() == ErrorKind::Unsupported => {
-            let error_message = &format!("{e}");
-            eprintln!("{error_message}");
-            interpreter.show_simple_message_box(MessageBoxFlag::WARNING, "Unsupported File", error_message)
-        },
-        Err(e) => Err(e.to_string())
+///
+/// Returns an `Err` if the trace log cannot be created.
+#[cfg(feature = "sdl")]
+fn prepare_emulator(mut interpreter: Interpreter, game_settings: &GameSettings, debug_config: &DebugConfig) -> Result<Emulator, String> {
+    interpreter.set_provenance_enabled(true);
+    interpreter.set_profiling_enabled(debug_config.profile);
+    interpreter.set_coverage_enabled(debug_config.coverage);
+    interpreter.set_event_log(debug_config.event_window.map(EventLog::new));
+    interpreter.set_symbols(debug_config.symbols.clone());
+    let mut emulator = Emulator::new(interpreter, game_settings.cycles_per_frame);
+    emulator.set_pure(game_settings.pure);
+    match OverlayLayout::load(emulator.storage()) {
+        Ok(overlay_layout) => emulator.set_overlay_layout(overlay_layout),
+        Err(e) => eprintln!("Error loading overlay layout: {e}")
+    }
+    let config = load_config(emulator.storage(), game_settings);
+    emulator.renderer_mut().set_palette(default_palette(game_settings, &config));
+    if let Some(trace_path) = &debug_config.trace_path {
+        emulator.set_trace_logger(Some(create_trace_logger(trace_path)?));
+    }
+    for address in &debug_config.breakpoints {
+        emulator.debugger_mut().add_breakpoint(*address);
     }
+    for condition in &debug_config.break_conditions {
+        emulator.debugger_mut().add_condition(condition.clone());
+    }
+
+    Ok(emulator)
 }
 
-/// Returns the byte contents of the provided game file, or an `io::Error` if the read fails.
+/// Reports on the frames the emulation thread just ran: the font being overwritten, the RPL flags and splits to keep, the screenshots to save, and where the debugger stopped.  
+/// Returns why the game halted if it has just done so, for the user to be told once the emulator is unlocked.
 ///
-/// # Errors
+/// # Parameters
 ///
-/// Returns an `Err` if the file fails to be read.
-fn read_game_file(path: &str) -> io::Result<Vec<u8>> {
-    if !std::path::Path::new(path)
-        .extension()
-        .map_or(false, |ext| ext.eq_ignore_ascii_case("ch8") || ext.eq_ignore_ascii_case("chip8")) {
-        return Err(io::Error::new(ErrorKind::Unsupported, format!("Invalid file found at {path}. Only CHIP-8 files (.ch8 or .chip8) are valid.")));
+/// * `emulator` - The emulator which ran the frames.
+/// * `state` - The state of the window.
+/// * `frames` - The number of frames run.
+#[cfg(feature = "sdl")]
+fn report_frames(emulator: &mut Emulator, state: &mut WindowState, frames: u32) -> Option<HaltReason> {
+    // Warn about the game overwriting the font, which usually means a bug in the game or a wrong quirk
+    for warning in emulator.interpreter_mut().take_memory_warnings() {
+        frame_log::warn(Subsystem::Memory, warning);
     }
 
-    fs::read(path)
+    // Keep any progress the game saved to the RPL flags
+    if emulator.interpreter_mut().take_rpl_flags_changed() {
+        if let Some(game_path) = &state.current_game_path {
+            let rpl_flags = *emulator.interpreter().rpl_flags();
+            if let Err(e) = savestate::save_rpl_flags(emulator.storage_mut(), game_path, &rpl_flags) {
+                frame_log::warn(Subsystem::Storage, format_args!("Error saving RPL flags: {e}"));
+            }
+        }
+    }
+
+    // Save screenshots of the events the user asked for as they happen
+    if let Some(game_path) = &state.current_game_path {
+        if frames > 0 && state.auto_screenshot.take_spin(emulator.interpreter()) {
+            if let Some(saved) = save_screenshot(&state.auto_screenshot, ScreenshotTrigger::Spin, game_path, emulator) {
+                println!("{saved}");
+            }
+        }
+    }
+
+    // Keep the split times of a finished run for LiveSplit
+    let finished_run = emulator.speedrun_timer_mut().and_then(|speedrun_timer| speedrun_timer.take_finished().then(|| speedrun_timer.clone()));
+    if let (Some(_), Some(game_path)) = (&finished_run, &state.current_game_path) {
+        if let Some(saved) = save_screenshot(&state.auto_screenshot, ScreenshotTrigger::Finish, game_path, emulator) {
+            println!("{saved}");
+        }
+    }
+    if let (Some(speedrun_timer), Some(game_path)) = (finished_run, &state.current_game_path) {
+        match speedrun_timer.save_livesplit(emulator.storage_mut(), game_path) {
+            Ok(path) => println!("{} Splits saved to {path}.", speedrun_timer.describe()),
+            Err(e) => frame_log::warn(Subsystem::Storage, format_args!("Error saving splits: {e}"))
+        }
+    }
+
+    // Let the user know which frame a frame advance stopped on, so the display can be studied a frame at a time
+    if state.seen_mode == EmulatorMode::Stepping && emulator.mode() == EmulatorMode::Paused {
+        let status = format!("Advanced to frame {}", emulator.frame_count());
+        show_debugger_status(emulator, &status, state.show_memory_view);
+    }
+
+    // Let the user know where the game stopped for a breakpoint, and which condition it met if any
+    if emulator.debugger_mut().take_hit().is_some() {
+        let status = match emulator.debugger_mut().take_met_condition() {
+            Some(condition) => format!("Met {condition}"),
+            None => String::from("Breakpoint")
+        };
+        show_debugger_status(emulator, &status, state.show_memory_view);
+    }
+
+    let EmulatorMode::Halted(reason) = emulator.mode() else {
+        return None;
+    };
+    if state.seen_mode == emulator.mode() {
+        return None;
+    }
+
+    if state.show_memory_view {
+        println!("Halted\n{}", memory_view::memory_view(emulator.interpreter()));
+    }
+    if let (Some(trigger), Some(game_path)) = (ScreenshotTrigger::for_halt(reason), &state.current_game_path) {
+        if let Some(saved) = save_screenshot(&state.auto_screenshot, trigger, game_path, emulator) {
+            println!("{saved}");
+        }
+    }
+
+    Some(reason)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Shows the speed, speedrun timer, watch expressions, and draw budget in the window title once frames have run, if any of them are to be shown.
+///
+/// # Parameters
+///
+/// * `emulator` - The emulator which ran the frames.
+/// * `state` - The state of the window.
+/// * `frames` - The number of frames run.
+#[cfg(feature = "sdl")]
+fn show_status(emulator: &mut Emulator, state: &WindowState, frames: u32) {
+    let is_usual_speed = emulator.speed() == 1.0 || emulator.turbo().is_some();
+    let has_status = state.show_draw_stats || !state.watch_expressions.is_empty() || emulator.speedrun_timer().is_some() || !is_usual_speed;
+    if frames == 0 || !emulator.mode().runs_frames() || !has_status {
+        return;
+    }
 
-    const EXISTING_GAME_PATH: &str = "games/15PUZZLE.chip8";
-    const NON_EXISTENT_GAME_PATH: &str = "games/FAKE.chip8";
-    const INVALID_GAME_PATH: &str = "README.md";
+    let cycles_per_frame = emulator.cycles_per_frame();
+    let speed_status = (!is_usual_speed).then(|| describe_speed(emulator.speed()));
+    let speedrun_status = emulator.speedrun_timer().map(SpeedrunTimer::describe);
+    let interpreter = emulator.interpreter_mut();
+    let mut status = Vec::with_capacity(5);
+    status.extend(speed_status);
+    status.extend(speedrun_status);
+    if !state.watch_expressions.is_empty() {
+        status.push(watch::describe(&state.watch_expressions, interpreter));
+    }
+    if state.show_draw_stats {
+        status.push(metrics::describe_draw_budget(interpreter, cycles_per_frame));
+        status.push(metrics::describe_counters(&interpreter.counters()));
+    }
+    interpreter.set_window_title(&format!("{WINDOW_TITLE} - {}", status.join(" | ")));
+}
 
-    #[test]
-    fn read_existing_game_file() {
-        assert!(read_game_file(EXISTING_GAME_PATH).is_ok(), "Valid game file was not read.");
+/// Returns the command forwarding the event to the game, or `None` if the event is for the window (e.g. a hotkey, or a key for the open menu or poke prompt).  
+/// The fast-forward key is kept by the window when let go of, as it stops the fast-forward as well as going to the game.
+///
+/// # Parameters
+///
+/// * `state` - The state of the window.
+/// * `event` - The event from the window.
+#[cfg(feature = "sdl")]
+fn game_input(state: &WindowState, event: &Event) -> Option<EmulationCommand> {
+    match *event {
+        Event::KeyDown { keycode: Some(keycode), keymod, .. } if state.poke_prompt.is_none() && state.open_menu.is_none() && state.hotkeys.action_for(keycode, keymod).is_none() => Some(EmulationCommand::PressKey(keycode)),
+        Event::KeyUp { keycode: Some(keycode), .. } if keycode != state.turbo_chord.keycode => Some(EmulationCommand::ReleaseKey(keycode)),
+        _ => None
     }
+}
 
-    #[test]
-    fn read_non_existent_game_file() {
-        assert_ne!(read_game_file(NON_EXISTENT_GAME_PATH).unwrap_err().kind(), ErrorKind::Unsupported, "Wrong error returned for non-existent game file.");
+/// Handles an event from the window, returning whether to carry on, quit, or show a dialog once the emulator is unlocked.
+///
+/// # Parameters
+///
+/// * `emulator` - The emulator, locked for the event.
+/// * `state` - The state of the window.
+/// * `video_subsystem` - The video subsystem, for the clipboard and text input.
+/// * `event` - The event to handle.
+#[cfg(feature = "sdl")]
+fn handle_event(emulator: &mut Emulator, state: &mut WindowState, video_subsystem: &sdl2::VideoSubsystem, event: Event) -> EventOutcome {
+    match event {
+        Event::Quit { .. } => {
+            return EventOutcome::Quit;
+        },
+        Event::TextInput { text, .. } if state.poke_prompt.is_some() => {
+            if let Some(prompt) = &mut state.poke_prompt {
+                prompt.push_str(&text);
+                emulator.interpreter_mut().set_window_title(&format!("{WINDOW_TITLE} - Poke: {prompt}"));
+            }
+        },
+        Event::KeyDown { keycode: Some(keycode), .. } if state.poke_prompt.is_some() => match keycode {
+            Keycode::Return | Keycode::KpEnter | Keycode::Escape => {
+                video_subsystem.text_input().stop();
+                let text = state.poke_prompt.take().unwrap_or_default();
+                if keycode == Keycode::Escape {
+                    show_debugger_status(emulator, "Cancelled the poke", state.show_memory_view);
+                } else {
+                    let status = match text.parse::<Poke>().and_then(|poke| emulator.poke(&poke)) {
+                        Ok(description) => format!("Poked {description}"),
+                        Err(e) => e
+                    };
+                    show_debugger_status(emulator, &status, state.show_memory_view);
+                }
+            },
+            Keycode::Backspace => {
+                if let Some(prompt) = &mut state.poke_prompt {
+                    prompt.pop();
+                    emulator.interpreter_mut().set_window_title(&format!("{WINDOW_TITLE} - Poke: {prompt}"));
+                }
+            },
+            _ => {}
+        },
+        Event::KeyDown { keycode: Some(keycode), keymod, .. } => return handle_key_down(emulator, state, video_subsystem, keycode, keymod),
+        Event::KeyUp { keycode: Some(keycode), .. } => {
+            // The modifiers of the chord may be let go of first, so only its key stops the fast-forward
+            if keycode == state.turbo_chord.keycode && emulator.turbo().is_some() {
+                emulator.set_turbo(None);
+                let title = if emulator.speed() == 1.0 { String::from(WINDOW_TITLE) } else { format!("{WINDOW_TITLE} - {}", describe_speed(emulator.speed())) };
+                emulator.interpreter_mut().set_window_title(&title);
+            }
+            emulator.handle_key_release(&SdlKeyboard, keycode);
+        },
+        Event::MouseMotion { x, y, .. } if emulator.mode() == EmulatorMode::Paused => {
+            // Inspect the pixel under the mouse while the debugger has the game paused
+            if let Some((x, y)) = renderer::display_position(&emulator.interpreter().framebuffer(), x, y) {
+                let title = format!("{WINDOW_TITLE} - {}", provenance::describe_pixel(emulator.interpreter(), x, y));
+                emulator.interpreter_mut().set_window_title(&title);
+            }
+        },
+        Event::MouseButtonDown { mouse_btn: MouseButton::Left, x, y, .. } if emulator.mode() == EmulatorMode::Paused => {
+            if let Some((x, y)) = renderer::display_position(&emulator.interpreter().framebuffer(), x, y) {
+                break_on_draw(emulator, x, y);
+            }
+        },
+        Event::AppWillEnterBackground { .. } | Event::AppTerminating { .. } => {
+            if emulator.mode().runs_frames() {
+                emulator.pause();
+                state.paused_for_background = true;
+            }
+            autosave(emulator, state.current_game_path.as_deref());
+        },
+        Event::AppDidEnterForeground { .. } => {
+            if state.paused_for_background && emulator.mode() == EmulatorMode::Paused {
+                emulator.resume();
+            }
+            state.paused_for_background = false;
+        },
+        Event::DropFile { filename, .. } => {
+            return EventOutcome::Show(Dialog::OpenGame(filename));
+        },
+        _ => {}
     }
 
-    #[test]
-    fn read_invalid_game_file() {
-        assert_eq!(read_game_file(INVALID_GAME_PATH).unwrap_err().kind(), ErrorKind::Unsupported, "Wrong error returned for invalid game file.");
+    EventOutcome::Handled
+}
+
+/// Handles a key press outside of the poke prompt: hotkeys trigger their action, while other keys go to the open menu or the game.
+///
+/// # Parameters
+///
+/// * `emulator` - The emulator, locked for the key press.
+/// * `state` - The state of the window.
+/// * `video_subsystem` - The video subsystem, for the clipboard and text input.
+/// * `keycode` - The physical key pressed.
+/// * `keymod` - The modifiers held with it.
+#[cfg(feature = "sdl")]
+fn handle_key_down(emulator: &mut Emulator, state: &mut WindowState, video_subsystem: &sdl2::VideoSubsystem, keycode: Keycode, keymod: Mod) -> EventOutcome {
+    match state.hotkeys.action_for(keycode, keymod) {
+        Some(Action::Quit) => {
+            return EventOutcome::Quit;
+        },
+        Some(Action::LoadGame) => {
+            return EventOutcome::Show(Dialog::PickGame);
+        },
+        Some(Action::Reset) => {
+            emulator.reset();
+        },
+        Some(Action::ToggleQuirkMenu) if emulator.is_pure() => {
+            emulator.interpreter_mut().set_window_title(&format!("{WINDOW_TITLE} - The quirks are locked by --pure"));
+        },
+        Some(action @ (Action::TogglePaletteMenu | Action::ToggleQuirkMenu)) => {
+            let menu = if action == Action::TogglePaletteMenu { Menu::Palette } else { Menu::Quirks };
+            if state.open_menu == Some(menu) {
+                emulator.close_menu();
+                state.open_menu = None;
+                emulator.interpreter_mut().set_window_title(WINDOW_TITLE);
+            } else {
+                emulator.open_menu();
+                state.open_menu = Some(menu);
+                state.palette_editor = PaletteEditor::default();
+                state.quirk_editor = QuirkEditor::default();
+                let title = match menu {
+                    Menu::Palette => state.palette_editor.describe(emulator.renderer().palette()),
+                    Menu::Quirks => state.quirk_editor.describe(emulator.interpreter().quirk_config())
+                };
+                emulator.interpreter_mut().set_window_title(&format!("{WINDOW_TITLE} - {title}"));
+            }
+        },
+        Some(Action::SwitchQuirkSide) => {
+            emulator.switch_quirk_side();
+            if let Some(comparison) = emulator.quirk_comparison() {
+                let title = format!("{WINDOW_TITLE} - {}", comparison.describe());
+                emulator.interpreter_mut().set_window_title(&title);
+            }
+        },
+        Some(Action::ToggleLockstep) => {
+            emulator.toggle_lockstep();
+            let status = match (emulator.quirk_comparison(), emulator.lockstep()) {
+                (None, _) => String::from("Change a quirk in the quirk menu to compare it in lockstep"),
+                (Some(comparison), Some(_)) => format!("{} (lockstep, red: only these quirks, blue: only the others)", comparison.describe()),
+                (Some(comparison), None) => comparison.describe()
+            };
+            emulator.interpreter_mut().set_window_title(&format!("{WINDOW_TITLE} - {status}"));
+        },
+        Some(action @ (Action::CopyDisassembly | Action::CopyRegisters | Action::CopyFramebufferHash)) => {
+            let interpreter = emulator.interpreter();
+            let (name, text) = match action {
+                Action::CopyDisassembly => ("instruction", clipboard::disassembly_line(interpreter)),
+                Action::CopyRegisters => ("registers", clipboard::register_dump(interpreter)),
+                _ => ("display hash", clipboard::framebuffer_hash(interpreter))
+            };
+            match video_subsystem.clipboard().set_clipboard_text(&text) {
+                Ok(()) => emulator.interpreter_mut().set_window_title(&format!("{WINDOW_TITLE} - Copied the {name}: {text}")),
+                Err(e) => frame_log::warn(Subsystem::Emulator, format_args!("Error copying to the clipboard: {e}"))
+            }
+        },
+        Some(Action::ShowNotes) => {
+            if let Some(path) = &state.current_game_path {
+                return EventOutcome::Show(Dialog::ShowNotes(path.clone()));
+            }
+        },
+        Some(Action::SaveSlot(slot)) => {
+            save_to_slot(emulator, slot);
+        },
+        Some(Action::LoadSlot(slot)) => {
+            load_from_slot(emulator, slot);
+        },
+        Some(Action::RecordMacro(slot)) => {
+            toggle_macro_recording(emulator, slot);
+        },
+        Some(Action::PlayMacro(slot)) => {
+            play_macro(emulator, slot);
+        },
+        Some(Action::ShowAbout) => {
+            return EventOutcome::Show(Dialog::ShowAbout);
+        },
+        Some(Action::ToggleDrawStats) => {
+            state.show_draw_stats = !state.show_draw_stats;
+            if !state.show_draw_stats && state.watch_expressions.is_empty() && emulator.mode().runs_frames() {
+                emulator.interpreter_mut().set_window_title(WINDOW_TITLE);
+            }
+        },
+        Some(Action::ToggleInputDisplay) => {
+            emulator.toggle_input_display();
+        },
+        Some(Action::ToggleMemoryView) => {
+            state.show_memory_view = !state.show_memory_view;
+            if state.show_memory_view {
+                println!("{}", memory_view::memory_view(emulator.interpreter()));
+            }
+        },
+        Some(Action::TogglePause) => {
+            if emulator.mode() == EmulatorMode::Paused {
+                emulator.resume();
+                emulator.interpreter_mut().set_window_title(WINDOW_TITLE);
+            } else if emulator.mode().runs_frames() {
+                emulator.pause();
+                emulator.interpreter_mut().set_window_title(&format!("{WINDOW_TITLE} - Paused"));
+            }
+        },
+        Some(Action::ToggleDebuggerPause) => {
+            if emulator.mode() == EmulatorMode::Paused {
+                emulator.resume();
+                emulator.interpreter_mut().set_window_title(WINDOW_TITLE);
+            } else if emulator.mode().runs_frames() {
+                emulator.pause();
+                show_debugger_status(emulator, "Paused", state.show_memory_view);
+            }
+        },
+        Some(Action::StepInstruction) => {
+            if emulator.mode() == EmulatorMode::Paused {
+                emulator.step_instruction();
+                show_debugger_status(emulator, "Stepped", state.show_memory_view);
+            }
+        },
+        Some(Action::Turbo) => {
+            if emulator.turbo().is_none() {
+                emulator.set_turbo(Some(state.turbo_factor));
+                emulator.interpreter_mut().set_window_title(&format!("{WINDOW_TITLE} - Fast-forwarding x{}", state.turbo_factor));
+            }
+        },
+        Some(action @ (Action::SpeedUp | Action::SlowDown)) => {
+            let speed = next_speed(emulator.speed(), action == Action::SpeedUp);
+            emulator.set_speed(speed);
+            emulator.interpreter_mut().set_window_title(&format!("{WINDOW_TITLE} - {}", describe_speed(speed)));
+        },
+        Some(Action::AdvanceFrame) => {
+            if emulator.mode() == EmulatorMode::Paused {
+                emulator.step();
+            } else if emulator.mode().runs_frames() {
+                emulator.pause();
+                show_debugger_status(emulator, "Paused", state.show_memory_view);
+            }
+        },
+        Some(Action::StepBack) => {
+            if emulator.mode() == EmulatorMode::Paused {
+                let status = if emulator.step_back_instruction() { "Stepped back" } else { "No recorded instruction to step back over" };
+                show_debugger_status(emulator, status, state.show_memory_view);
+            }
+        },
+        Some(Action::Poke) if emulator.is_pure() => {
+            emulator.interpreter_mut().set_window_title(&format!("{WINDOW_TITLE} - Poking is locked by --pure"));
+        },
+        Some(Action::Poke) => {
+            if emulator.mode() == EmulatorMode::Paused {
+                video_subsystem.text_input().start();
+                state.poke_prompt = Some(String::new());
+                emulator.interpreter_mut().set_window_title(&format!("{WINDOW_TITLE} - Poke: "));
+            }
+        },
+        Some(Action::ToggleBreakpoint) => {
+            let address = emulator.interpreter().program_counter();
+            let status = if emulator.debugger_mut().toggle_breakpoint(address) { "Added a breakpoint" } else { "Removed the breakpoint" };
+            show_debugger_status(emulator, status, state.show_memory_view);
+        },
+        None => match state.open_menu {
+            Some(Menu::Palette) => handle_palette_menu_key(emulator, &mut state.palette_editor, keycode, state.current_game_path.as_deref()),
+            Some(Menu::Quirks) => handle_quirk_menu_key(emulator, &mut state.quirk_editor, keycode),
+            None => emulator.handle_key_press(&SdlKeyboard, keycode)
+        }
     }
 
-    #[test]
-    fn load_existing_game_file() {
-        let mut interpreter = Interpreter::new();
+    EventOutcome::Handled
+}
+
+/// Shows the dialog an event asked for, with the emulator only locked for as long as it takes to read from or change it.  
+/// Errors showing the dialog or loading the chosen game are printed rather than returned, as the current game can carry on regardless.
+///
+/// # Parameters
+///
+/// * `emulator` - The emulator shared with the emulation thread.
+/// * `dialogs` - The display the dialog is shown through.
+/// * `state` - The state of the window.
+/// * `dialog` - The dialog to show.
+#[cfg(feature = "sdl")]
+fn show_dialog(emulator: &Mutex<Emulator>, dialogs: &dyn Display, state: &mut WindowState, dialog: Dialog) {
+    match dialog {
+        Dialog::PickGame => {
+            if let Some(path) = pick_game_file() {
+                switch_game(emulator, dialogs, state, path);
+            }
+        },
+        Dialog::OpenGame(path) => switch_game(emulator, dialogs, state, path),
+        Dialog::ShowNotes(path) => show_notes(emulator, dialogs, &path),
+        Dialog::ShowAbout => {
+            let text = about::about_text(lock_emulator(emulator).interpreter().quirk_config());
+            show_message(dialogs, MessageKind::Information, &format!("About {WINDOW_TITLE}"), &text);
+        }
+    }
+}
 
-        assert!(load_game_file(&mut interpreter, EXISTING_GAME_PATH).is_ok(), "Valid game file was not loaded.");
+/// Draws the latest frame the emulation thread presented, returning the mode the emulator was left in.  
+/// A frame is presented first while no frames are being run, so that the display is kept up to date (e.g. with palette changes).
+///
+/// # Parameters
+///
+/// * `emulator` - The emulator shared with the emulation thread.
+/// * `display` - The display of the window.
+#[cfg(feature = "sdl")]
+fn render(emulator: &Mutex<Emulator>, display: &mut SdlDisplay) -> EmulatorMode {
+    let mut locked = lock_emulator(emulator);
+    let mode = locked.mode();
+    if !mode.runs_frames() {
+        locked.present();
     }
+    drop(locked);
 
-    #[test]
-    fn load_non_existent_game_file() {
-        let mut interpreter = Interpreter::new();
+    if let Err(e) = display.update() {
+        frame_log::warn(Subsystem::Emulator, format_args!("Error drawing the display: {e}"));
+    }
+
+    mode
+}
+
+/// Runs the emulator on the calling thread until stopped, ticking it whenever its next frame is due and sending the number of frames each tick ran.  
+/// The emulator is only locked while it ticks or takes input, so that the window's thread can handle events and draw in between.
+///
+/// # Parameters
+///
+/// * `emulator` - The emulator, shared with the window's thread.
+/// * `frame_sender` - Where the number of frames run by each tick is sent.
+/// * `commands` - The input for the game, applied as soon as it arrives. The emulation stops once its sender is dropped.
+/// * `observers` - What watches every frame run.
+#[cfg(feature = "sdl")]
+fn run_emulation(emulator: &Mutex<Emulator>, frame_sender: &SyncSender<u32>, commands: &Receiver<EmulationCommand>, mut observers: FrameObservers) {
+    loop {
+        let mut locked = lock_emulator(emulator);
+        let mut log_frame = |emulator: &Emulator| observers.observe(emulator);
 
-        assert!(load_game_file(&mut interpreter, NON_EXISTENT_GAME_PATH).is_err(), "Non-existent game file was loaded successfully.");
+        #[cfg(not(feature = "alloc-tracking"))]
+        let frame_advance = locked.tick_with(&mut log_frame);
+        #[cfg(feature = "alloc-tracking")]
+        let frame_advance = {
+            let (frame_advance, allocations) = alloc_tracker::count_allocations(|| locked.tick_with(&mut log_frame));
+            if allocations > 0 {
+                frame_log::warn(Subsystem::Memory, format_args!("Frame made {allocations} heap allocation(s)."));
+            }
+
+            frame_advance
+        };
+
+        observers.redraw_graphs(&locked, frame_advance.frames);
+        let delay = locked.next_tick_delay();
+        drop(locked);
+
+        if frame_advance.frames > 0 && frame_sender.send(frame_advance.frames).is_err() {
+            return;
+        }
+
+        // Wait until the next frame is due, unless stopped first, passing on input as it arrives. Effectively sets it to 60fps / 60Hz, stretched or shrunk by the speed.
+        let next_tick = Instant::now() + delay;
+        loop {
+            match commands.recv_timeout(next_tick.saturating_duration_since(Instant::now())) {
+                Ok(command) => command.apply(&mut lock_emulator(emulator)),
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return
+            }
+        }
     }
+}
 
-    #[test]
-    fn load_invalid_game_file() {
-        let mut interpreter = Interpreter::new();
+/// Returns the emulator, locked. The emulation thread only panics on a bug, after which the window still reports on the game as best it can.
+///
+/// # Parameters
+///
+/// * `emulator` - The emulator shared between the threads.
+#[cfg(feature = "sdl")]
+fn lock_emulator(emulator: &Mutex<Emulator>) -> MutexGuard<'_, Emulator> {
+    emulator.lock().unwrap_or_else(PoisonError::into_inner)
+}
 
-        assert!(load_game_file(&mut interpreter, INVALID_GAME_PATH).is_ok(), "Invalid game file error was not swallowed.");
+/// Answers "who drew this sprite" for the pixel, adding a breakpoint at the draw which last changed it so that the game stops there the next time it draws.  
+/// The draw and the size of its sprite are shown in the window title.
+///
+/// # Parameters
+///
+/// * `emulator` - The paused emulator.
+/// * `x` - The X coordinate of the pixel on the display.
+/// * `y` - The Y coordinate of the pixel on the display.
+#[cfg(feature = "sdl")]
+fn break_on_draw(emulator: &mut Emulator, x: u32, y: u32) {
+    let index = (y * emulator.interpreter().display_width() + x) as usize;
+    let Some(provenance) = emulator.interpreter().provenance() else {
+        return;
+    };
+
+    let status = match provenance.get(index) {
+        Some(record) => {
+            let pixel_count = provenance.sprite_pixels(index).count();
+            emulator.debugger_mut().add_breakpoint(record.address);
+            format!("Added a breakpoint at 0x{:03X}, which drew {pixel_count} pixel(s) of this sprite on frame {}", record.address, record.frame)
+        },
+        None => format!("Pixel ({x}, {y}) not drawn")
+    };
+    emulator.interpreter_mut().set_window_title(&format!("{WINDOW_TITLE} - {status}"));
+}
+
+/// Returns the speed step after the provided speed, or the speed itself if it is already the fastest (or slowest) step.
+///
+/// # Parameters
+///
+/// * `speed` - The current speed, relative to the usual speed.
+/// * `faster` - True to step to a faster speed, false to step to a slower one.
+#[cfg(feature = "sdl")]
+fn next_speed(speed: f64, faster: bool) -> f64 {
+    let next = if faster { SPEED_STEPS.into_iter().find(|step| *step > speed) } else { SPEED_STEPS.into_iter().rev().find(|step| *step < speed) };
+
+    next.unwrap_or(speed)
+}
+
+/// Returns the speed as shown in the window title, e.g. `Speed x0.5`.
+///
+/// # Parameters
+///
+/// * `speed` - The speed relative to the usual speed.
+#[cfg(feature = "sdl")]
+fn describe_speed(speed: f64) -> String {
+    format!("Speed x{speed}")
+}
+
+/// Shows the next instruction of the game in the window title, along with what the debugger just did (e.g. `Breakpoint at 0x206: 6105 LD V1, 0x05`).
+///
+/// # Parameters
+///
+/// * `emulator` - The emulator running the game.
+/// * `status` - What the debugger just did.
+/// * `show_memory_view` - True if the [memory view](memory_view) should also be printed to the console.
+#[cfg(feature = "sdl")]
+fn show_debugger_status(emulator: &mut Emulator, status: &str, show_memory_view: bool) {
+    let title = format!("{WINDOW_TITLE} - {status} at {}", clipboard::disassembly_line(emulator.interpreter()));
+    emulator.interpreter_mut().set_window_title(&title);
+    if show_memory_view {
+        println!("{status}\n{}", memory_view::memory_view(emulator.interpreter()));
+    }
+}
+
+/// Shows the notes kept on the game in a message box, or how to add some if there are none.
+///
+/// # Parameters
+///
+/// * `emulator` - The emulator running the game, only locked while the notes are loaded.
+/// * `dialogs` - The display the message box is shown through.
+/// * `game_path` - The path to the game file.
+#[cfg(feature = "sdl")]
+fn show_notes(emulator: &Mutex<Emulator>, dialogs: &dyn Display, game_path: &str) {
+    let notes = notes::load_for_game(lock_emulator(emulator).storage(), game_path).unwrap_or_else(|e| {
+        frame_log::warn(Subsystem::Storage, format_args!("Error loading notes: {e}"));
+        None
+    });
+    let text = notes.unwrap_or_else(|| format!("No notes yet. Write them in {} or add them with the notes command.", notes::path_for_game(game_path)));
+
+    show_message(dialogs, MessageKind::Information, "Notes", &text);
+}
+
+/// Shows a message box, printing the error if it cannot be shown, as the game can carry on without it.
+///
+/// # Parameters
+///
+/// * `dialogs` - The display the message box is shown through.
+/// * `kind` - The kind of message, which decides its icon.
+/// * `title` - The title of the message box.
+/// * `message` - The message to show.
+#[cfg(feature = "sdl")]
+fn show_message(dialogs: &dyn Display, kind: MessageKind, title: &str, message: &str) {
+    if let Err(e) = dialogs.show_message(kind, title, message) {
+        eprintln!("Error showing message box: {e}");
+    }
+}
+
+/// Asks the user to pick one of the choices in a message box, returning the index of the one picked.  
+/// Returns `None` if the message box was closed or could not be shown, printing the error in the latter case.
+///
+/// # Parameters
+///
+/// * `dialogs` - The display the message box is shown through.
+/// * `kind` - The kind of message, which decides its icon.
+/// * `title` - The title of the message box.
+/// * `message` - The question to ask.
+/// * `choices` - The text of the buttons, the first of which is the default.
+#[cfg(feature = "sdl")]
+fn show_choice(dialogs: &dyn Display, kind: MessageKind, title: &str, message: &str, choices: &[&str]) -> Option<usize> {
+    dialogs.show_choice(kind, title, message, choices).unwrap_or_else(|e| {
+        eprintln!("Error showing message box: {e}");
+        None
+    })
+}
+
+/// Fuzzes the game with random key inputs without opening a window, saving the input script of every fault found to the output directory.  
+/// Returns the number of faults found.
+///
+/// # Parameters
+///
+/// * `path` - The path to the game.
+/// * `config` - The settings of the fuzzing session.
+/// * `output_dir` - The directory the input scripts are saved to, created if needed.
+///
+/// # Errors
+///
+/// Returns an `Err` if the game file cannot be read or an input script cannot be saved.
+pub fn fuzz(path: &str, config: &FuzzConfig, output_dir: &str) -> Result<usize, String> {
+    let game_data = read_game_file(path).map_err(|e| e.to_string())?;
+
+    // Panics are reported as faults, so the default message would only be noise
+    let panic_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(|_| {}));
+    let report = fuzz::fuzz(&game_data, config);
+    std::panic::set_hook(panic_hook);
+    println!("Reached {} instruction addresses.", report.coverage.count());
+
+    let faults = report.faults;
+    if !faults.is_empty() {
+        fs::create_dir_all(output_dir).map_err(|e| e.to_string())?;
+    }
+    for fault in &faults {
+        let script_path = std::path::Path::new(output_dir).join(format!("fault-{}.txt", fault.run));
+        let script = format!("# {path}: {}\n{}", fault.fault, fault.script);
+        fs::write(&script_path, script).map_err(|e| e.to_string())?;
+        println!("Run {} faulted in frame {}: {} Saved the inputs to {}.", fault.run, fault.frame, fault.fault, script_path.display());
+    }
+
+    Ok(faults.len())
+}
+
+/// Replays an input script saved by [`fuzz`](fuzz) against the game without opening a window, returning the fault it reproduces, if any.
+///
+/// # Parameters
+///
+/// * `path` - The path to the game.
+/// * `script_path` - The path to the input script.
+/// * `cycles_per_frame` - The number of instruction cycles to run per frame.
+/// * `quirk_config` - The enabled/disabled status of all the quirks.
+///
+/// # Errors
+///
+/// Returns an `Err` if the game file or input script cannot be read or parsed.
+pub fn replay_inputs(path: &str, script_path: &str, cycles_per_frame: u32, quirk_config: QuirkConfig) -> Result<Option<(u64, Fault)>, String> {
+    let game_data = read_game_file(path).map_err(|e| e.to_string())?;
+    let script: InputScript = fs::read_to_string(script_path).map_err(|e| e.to_string())?.parse()?;
+
+    Ok(fuzz::replay(&game_data, &script, cycles_per_frame, quirk_config))
+}
+
+/// Returns a linear disassembly of the game, one instruction per line, as it would be laid out from the load address.  
+/// With banking, each bank follows the fixed part of the game under its `.bank` directive, as it would be laid out in the bank window.
+///
+/// # Parameters
+///
+/// * `path` - The path to the game.
+/// * `load_address` - The address the game is loaded at.
+/// * `banking` - Whether the game is split into [banks](banking).
+/// * `symbols` - The labels of the game, shown alongside the addresses they mark.
+///
+/// # Errors
+///
+/// Returns an `Err` if the game file cannot be read.
+pub fn disassemble(path: &str, load_address: u16, banking: bool, symbols: &SymbolTable) -> Result<String, String> {
+    let game_data = read_game_file(path).map_err(|e| e.to_string())?;
+
+    if banking {
+        Ok(opcodes::disassemble_banked(&game_data, load_address, symbols))
+    } else {
+        Ok(opcodes::disassemble_with_symbols(&game_data, load_address, symbols))
+    }
+}
+
+/// Returns a description of the game file: its size, its hash, and what the [database](database) knows about it.
+///
+/// # Parameters
+///
+/// * `path` - The path to the game.
+///
+/// # Errors
+///
+/// Returns an `Err` if the game file cannot be read.
+pub fn info(path: &str) -> Result<String, String> {
+    let game_data = read_game_file(path).map_err(|e| e.to_string())?;
+    let mut info = format!("Size: {} bytes\nSHA-1: {}\n", game_data.len(), database::hash(&game_data));
+    match database::lookup(&game_data) {
+        Some(entry) => {
+            let platform = platform_name(&entry);
+            let tick_rate = entry.tick_rate.map_or_else(|| String::from("default"), |tick_rate| tick_rate.to_string());
+            let load_address = entry.load_address.map_or_else(|| String::from("default"), |load_address| format!("0x{load_address:03X}"));
+            let quirks = entry.quirks.map_or_else(|| String::from("platform's"), |quirks| quirks.replace(',', " "));
+            info.push_str(&format!("Title: {}\nPlatform: {platform}\nCycles per frame: {tick_rate}\nLoad address: {load_address}\nQuirks: {quirks}\n", entry.title));
+        },
+        None => info.push_str("Not in the game database.\n")
+    }
+
+    Ok(info)
+}
+
+/// Returns the name of the platform the database records for the game, as the `--platform` flag names it.
+///
+/// # Parameters
+///
+/// * `entry` - The database entry of the game.
+fn platform_name(entry: &DatabaseEntry) -> String {
+    entry.platform.map_or_else(|| String::from("CHIP-8"), |platform| platform.name().to_string())
+}
+
+/// Returns the registers, timers, stack, and RAM regions which differ between two save states, a line each.
+///
+/// # Parameters
+///
+/// * `first_path` - The path to the save state the values are compared from.
+/// * `second_path` - The path to the save state the values are compared to.
+///
+/// # Errors
+///
+/// Returns an `Err` if either save state cannot be read or is malformed.
+pub fn diff_states(first_path: &str, second_path: &str) -> Result<String, String> {
+    let read_state = |path: &str| fs::read(path)
+        .map_err(|e| e.to_string())
+        .and_then(|bytes| savestate::decode(&bytes))
+        .map_err(|e| format!("Error reading save state {path}: {e}"));
+
+    Ok(StateDiff::new(&read_state(first_path)?, &read_state(second_path)?).to_string())
+}
+
+/// Returns the notes kept on the game, or adds a note to them if one is provided.
+///
+/// # Parameters
+///
+/// * `path` - The path to the game.
+/// * `note` - An optional note to add.
+///
+/// # Errors
+///
+/// Returns an `Err` if the notes cannot be read or saved.
+pub fn notes(path: &str, note: Option<&str>) -> Result<String, String> {
+    let mut storage = FileStorage;
+    if let Some(note) = note {
+        notes::add_for_game(&mut storage, path, note).map_err(|e| format!("Error saving notes: {e}"))?;
+        return Ok(format!("Added the note to {}.\n", notes::path_for_game(path)));
+    }
+
+    let notes = notes::load_for_game(&storage, path).map_err(|e| format!("Error loading notes: {e}"))?;
+
+    Ok(notes.map_or_else(|| format!("No notes in {}.\n", notes::path_for_game(path)), |notes| format!("{notes}\n")))
+}
+
+/// Runs the game for a number of frames without opening a window or playing audio, returning a report of where it ended up.  
+/// The game stops early if it halts, and no input is pressed throughout.
+///
+/// # Parameters
+///
+/// * `path` - The path to the game.
+/// * `frames` - The number of frames to run.
+/// * `game_settings` - The quirks, cycles per frame, load address, and banking to run the game with, and whether the [database](database) may override them.
+/// * `debug_config` - The trace log, profiling, coverage, labels, and automatic screenshots to run with. The profile, coverage summary, and paths of the screenshots are added to the report, while the breakpoints and other interactive aids are ignored.
+/// * `dump_paths` - The files to write the final state and display of the game to.
+///
+/// # Errors
+///
+/// Returns an `Err` if the game file cannot be read, the trace log or a dump cannot be written, or the load address leaves no room for the font or is past the bank window while banking.
+pub fn run_headless(path: &str, frames: u64, game_settings: &GameSettings, debug_config: &DebugConfig, dump_paths: &DumpPaths) -> Result<String, String> {
+    let game_data = read_game_file(path).map_err(|e| e.to_string())?;
+    let mut runner = HeadlessRunner::new(game_data, game_settings)?;
+    let emulator = runner.emulator_mut();
+    emulator.interpreter_mut().set_profiling_enabled(debug_config.profile);
+    emulator.interpreter_mut().set_coverage_enabled(debug_config.coverage);
+    emulator.interpreter_mut().set_symbols(debug_config.symbols.clone());
+    if let Some(trace_path) = &debug_config.trace_path {
+        emulator.set_trace_logger(Some(create_trace_logger(trace_path)?));
+    }
+
+    let mut auto_screenshot = debug_config.auto_screenshot.clone();
+    let mut screenshots = Vec::new();
+    let mut halt_reason = None;
+    while halt_reason.is_none() && runner.frames_run() < frames {
+        halt_reason = runner.step_frame();
+        if auto_screenshot.take_spin(runner.emulator().interpreter()) {
+            screenshots.extend(save_screenshot(&auto_screenshot, ScreenshotTrigger::Spin, path, runner.emulator_mut()));
+        }
+    }
+    if let Some(reason) = halt_reason {
+        screenshots.extend(ScreenshotTrigger::for_halt(reason).and_then(|trigger| save_screenshot(&auto_screenshot, trigger, path, runner.emulator_mut())));
+    }
+
+    let mut report = format!("Ran {} frames.\n", runner.frames_run());
+    if let Some(reason) = halt_reason {
+        report.push_str(&format!("Halted: {reason}\n"));
+    }
+    for saved in screenshots.into_iter().chain(runner.dump(dump_paths)?) {
+        report.push_str(&format!("{saved}\n"));
+    }
+    let interpreter = runner.emulator().interpreter();
+    report.push_str(&format!("Next instruction: {}\n{}\nFramebuffer hash: {}\n", clipboard::disassembly_line(interpreter), clipboard::register_dump(interpreter), clipboard::framebuffer_hash(interpreter)));
+    if let Some(profile) = interpreter.profile() {
+        report.push_str(&profile.report());
+    }
+    if let Some(coverage) = interpreter.coverage() {
+        report.push_str(&coverage.report(&interpreter.program_range()));
+    }
+
+    Ok(report)
+}
+
+/// Runs every game in the directory without opening a window for up to a number of frames, writing a [compatibility report](compat) of how each run ended to the output path.  
+/// Returns a summary of the outcomes.
+///
+/// # Parameters
+///
+/// * `games_dir` - The directory holding the games. Files which are not CHIP-8 games are skipped.
+/// * `frames` - The most frames to run each game for.
+/// * `game_settings` - The settings to run the games with, which the [database](database) may override for known games.
+/// * `output_path` - The path the report is written to, as JSON if it ends in `.json` and as Markdown otherwise.
+///
+/// # Errors
+///
+/// Returns an `Err` if the directory or a game file cannot be read, the load address leaves no room for the font or is past the bank window while banking, or the report cannot be written.
+pub fn compat(games_dir: &str, frames: u64, game_settings: &GameSettings, output_path: &str) -> Result<String, String> {
+    let paths = game_paths(games_dir)?;
+
+    // Panics are reported as faults, so the default message would only be noise
+    let panic_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(|_| {}));
+    let results: Result<Vec<GameResult>, String> = paths.into_iter().map(|path| check_compat(path, frames, game_settings)).collect();
+    std::panic::set_hook(panic_hook);
+    let report = CompatReport { frames, results: results? };
+
+    report.write(output_path).map_err(|e| format!("Error writing compatibility report: {e}"))?;
+
+    Ok(format!("Wrote the report of {} games to {output_path}: {}.\n", report.results.len(), report.summary()))
+}
+
+/// Runs every game in the directory without opening a window under each of the [dispatch designs](dispatch), returning a [benchmark report](bench) comparing how long they took.  
+/// The report is also written to the output path if one is provided.
+///
+/// # Parameters
+///
+/// * `games_dir` - The directory holding the games. Files which are not CHIP-8 games are skipped.
+/// * `frames` - The number of frames to run each game for.
+/// * `game_settings` - The settings to run the games with, which the [database](database) may override for known games.
+/// * `output_path` - An optional path the report is written to.
+///
+/// # Errors
+///
+/// Returns an `Err` if the directory or a game file cannot be read, the load address leaves no room for the font or is past the bank window while banking, or the report cannot be written.
+pub fn bench_report(games_dir: &str, frames: u64, game_settings: &GameSettings, output_path: Option<&str>) -> Result<String, String> {
+    let mut report = BenchReport { frames, results: Vec::new() };
+    for path in game_paths(games_dir)? {
+        let game_data = read_game_file(&path).map_err(|e| e.to_string())?;
+        let mut emulator = headless_emulator(game_data.clone(), game_settings)?;
+        let mut times = Vec::new();
+        for dispatch in Dispatch::ALL {
+            let mut fastest = Duration::MAX;
+            for _ in 0..bench::BENCH_RUNS {
+                emulator.queue_load(game_data.clone());
+                fastest = fastest.min(bench::time_frames(&mut emulator, dispatch, frames));
+            }
+            times.push((dispatch, fastest));
+        }
+        eprintln!("Benchmarked {path}");
+        report.results.push(BenchResult { path, times });
+    }
+
+    let markdown = report.to_markdown();
+    if let Some(output_path) = output_path {
+        fs::write(output_path, &markdown).map_err(|e| format!("Error writing benchmark report: {e}"))?;
+    }
+
+    Ok(markdown)
+}
+
+/// Returns the paths of the games in the directory in order, skipping files which are not CHIP-8 games.
+///
+/// # Parameters
+///
+/// * `games_dir` - The directory holding the games.
+///
+/// # Errors
+///
+/// Returns an `Err` if the directory cannot be read.
+fn game_paths(games_dir: &str) -> Result<Vec<String>, String> {
+    let mut paths: Vec<String> = fs::read_dir(games_dir).map_err(|e| format!("Error reading {games_dir}: {e}"))?
+        .filter_map(|entry| entry.ok()?.path().to_str().map(ToString::to_string))
+        .filter(|path| is_game_file(path))
+        .collect();
+    paths.sort();
+
+    Ok(paths)
+}
+
+/// Runs the game without opening a window for up to a number of frames, returning how the run ended for the [compatibility report](compat).
+///
+/// # Parameters
+///
+/// * `path` - The path to the game.
+/// * `frames` - The most frames to run the game for.
+/// * `game_settings` - The settings to run the game with, which the [database](database) may override.
+///
+/// # Errors
+///
+/// Returns an `Err` if the game file cannot be read, or the load address leaves no room for the font or is past the bank window while banking.
+fn check_compat(path: String, frames: u64, game_settings: &GameSettings) -> Result<GameResult, String> {
+    let game_data = read_game_file(&path).map_err(|e| e.to_string())?;
+    let entry = database::lookup(&game_data).filter(|_| game_settings.use_database);
+    let mut emulator = headless_emulator(game_data, game_settings)?;
+    let (frames_run, outcome) = compat::check(&mut emulator, frames);
+    println!("{path}: {outcome}");
+
+    Ok(GameResult {
+        path,
+        title: entry.map(|entry| entry.title.to_string()),
+        platform: entry.map_or_else(|| String::from("default"), |entry| platform_name(&entry)),
+        frames_run,
+        outcome
+    })
+}
+
+/// Returns an emulator without a window or audio, with the game queued under the provided settings (or those the [database](database) recommends for it).  
+/// The emulator is boxed as it is too large to pass around the stack of a test thread.
+///
+/// # Parameters
+///
+/// * `game_data` - The bytes of the game file.
+/// * `game_settings` - The settings to run the game with.
+///
+/// # Errors
+///
+/// Returns an `Err` if the load address leaves no room for the font or is past the bank window while banking.
+fn headless_emulator(game_data: Vec<u8>, game_settings: &GameSettings) -> Result<Box<Emulator>, String> {
+    let interpreter = Interpreter::builder()
+        .quirks(game_settings.quirk_config.clone())
+        .load_address(game_settings.load_address)
+        .banking(game_settings.banking)
+        .hires_detection(game_settings.hires_detection)
+        .build()?;
+    let mut emulator = Box::new(Emulator::new(interpreter, game_settings.cycles_per_frame));
+    emulator.set_pure(game_settings.pure);
+    emulator.renderer_mut().set_palette(game_settings.palette.clone().unwrap_or_default());
+    let load_settings = if game_settings.use_database { apply_game_settings(&mut emulator, &game_data, game_settings) } else { LoadSettings::default() };
+    emulator.queue_load_with(game_data, load_settings);
+
+    Ok(emulator)
+}
+
+/// Saves a screenshot of the display for the event if the user asked for one, returning a line saying where it was saved.  
+/// Errors are printed rather than returned, as a missing screenshot is not worth stopping the game over.
+///
+/// # Parameters
+///
+/// * `auto_screenshot` - The events to save screenshots for and where to save them.
+/// * `trigger` - The event which happened.
+/// * `game_path` - The path to the game, which the screenshot is named after.
+/// * `emulator` - The emulator to capture, whose storage backend the screenshot is saved through.
+fn save_screenshot(auto_screenshot: &AutoScreenshot, trigger: ScreenshotTrigger, game_path: &str, emulator: &mut Emulator) -> Option<String> {
+    match auto_screenshot.capture(trigger, game_path, emulator)? {
+        Ok(path) => Some(format!("Saved a screenshot of the {trigger} to {}.", path.display())),
+        Err(e) => {
+            frame_log::warn(Subsystem::Storage, format_args!("Error saving screenshot: {e}"));
+            None
+        }
+    }
+}
+
+/// Creates the trace log at the provided path, for the emulator to write every instruction run to.
+///
+/// # Parameters
+///
+/// * `trace_path` - The path of the trace log.
+///
+/// # Errors
+///
+/// Returns an `Err` if the file cannot be created.
+fn create_trace_logger(trace_path: &str) -> Result<TraceLogger<Box<dyn io::Write + Send + Sync>>, String> {
+    let file = fs::File::create(trace_path).map_err(|e| format!("Error creating trace log: {e}"))?;
+
+    Ok(TraceLogger::new(Box::new(io::BufWriter::new(file))))
+}
+
+/// Tells the user why the game halted: errors are shown in a message box, while an exit offers to reload the game or open another.  
+/// The emulator is only locked while it is changed, not while the user decides.
+///
+/// # Parameters
+///
+/// * `emulator` - The emulator whose game halted.
+/// * `dialogs` - The display the message boxes are shown through.
+/// * `state` - The state of the window, which keeps the game opened instead, if any.
+/// * `reason` - Why the game halted.
+#[cfg(feature = "sdl")]
+fn report_halt(emulator: &Mutex<Emulator>, dialogs: &dyn Display, state: &mut WindowState, reason: HaltReason) {
+    if reason.is_error() {
+        frame_log::warn(Subsystem::Emulator, format_args!("Game halted: {reason}"));
+        show_message(dialogs, MessageKind::Error, "Game Halted", &format!("{reason}\nLoad a game or press Ctrl+R to restart."));
+        return;
+    }
+
+    if reason == HaltReason::UserStop {
+        return;
+    }
+
+    lock_emulator(emulator).interpreter_mut().set_window_title(&format!("{WINDOW_TITLE} - Program exited"));
+    let choice = show_choice(dialogs, MessageKind::Information, "Program Exited", &format!("{reason}\nWould you like to reload it or open another game?"), &["Reload", "Open Game", "Close"]);
+    match choice {
+        Some(0) => {
+            let mut emulator = lock_emulator(emulator);
+            emulator.reset();
+            emulator.interpreter_mut().set_window_title(WINDOW_TITLE);
+        },
+        Some(1) => {
+            if let Some(path) = pick_game_file() {
+                switch_game(emulator, dialogs, state, path);
+            }
+        },
+        _ => {}
+    }
+}
+
+/// Lets the user pick a game with a file picker which starts in the `games` directory, returning its path if one was picked.
+#[cfg(feature = "sdl")]
+fn pick_game_file() -> Option<String> {
+    let path = FileDialog::new()
+        .add_filter("CHIP-8", &["ch8", "chip8"])
+        .set_directory("games")
+        .pick_file();
+
+    path.and_then(|path| path.to_str().map(String::from))
+}
+
+/// Opens the game at the provided path (see [`open_game`](open_game)), making it the current game if it was queued.  
+/// Errors reading the file are printed rather than returned, so that the current game carries on as if nothing happened.
+///
+/// # Parameters
+///
+/// * `emulator` - The emulator to queue the game in.
+/// * `dialogs` - The display the message boxes are shown through.
+/// * `state` - The state of the window, which keeps the current game.
+/// * `path` - The path to the game file.
+#[cfg(feature = "sdl")]
+fn switch_game(emulator: &Mutex<Emulator>, dialogs: &dyn Display, state: &mut WindowState, path: String) {
+    match open_game(emulator, dialogs, &path, &state.game_settings) {
+        Ok(true) => state.current_game_path = Some(path),
+        Ok(false) => {},
+        Err(e) => eprintln!("Error loading game: {e}")
+    }
+}
+
+/// Handles a key press while the palette menu is open.  
+/// The up and down arrows select the palette entry, the left and right arrows change its colour, and enter saves the palette for the current game.
+///
+/// # Parameters
+///
+/// * `emulator` - The emulator whose palette is being edited.
+/// * `editor` - The state of the palette menu.
+/// * `keycode` - The physical key pressed.
+/// * `game_path` - The path to the current game, if one is loaded.
+#[cfg(feature = "sdl")]
+fn handle_palette_menu_key(emulator: &mut Emulator, editor: &mut PaletteEditor, keycode: Keycode, game_path: Option<&str>) {
+    let mut palette = emulator.renderer().palette().clone();
+    match keycode {
+        Keycode::Up => editor.select(false),
+        Keycode::Down => editor.select(true),
+        Keycode::Left => editor.cycle_colour(&mut palette, false),
+        Keycode::Right => editor.cycle_colour(&mut palette, true),
+        Keycode::Return => {
+            if let Some(game_path) = game_path {
+                if let Err(e) = palette.save_for_game(emulator.storage_mut(), game_path) {
+                    frame_log::warn(Subsystem::Storage, format_args!("Error saving palette: {e}"));
+                }
+            }
+        },
+        _ => return
+    }
+
+    emulator.interpreter_mut().set_window_title(&format!("{WINDOW_TITLE} - {}", editor.describe(&palette)));
+    emulator.renderer_mut().set_palette(palette);
+}
+
+/// Handles a key press while the quirk menu is open.  
+/// The up and down arrows select the quirk, while the left and right arrows (or enter) toggle it. The game is returned to where it was when the quirks were first changed, so that [`Tab`](Action::SwitchQuirkSide) can switch between the settings from before and after.
+///
+/// # Parameters
+///
+/// * `emulator` - The emulator whose quirks are being edited.
+/// * `editor` - The state of the quirk menu.
+/// * `keycode` - The physical key pressed.
+#[cfg(feature = "sdl")]
+fn handle_quirk_menu_key(emulator: &mut Emulator, editor: &mut QuirkEditor, keycode: Keycode) {
+    match keycode {
+        Keycode::Up => editor.select(false),
+        Keycode::Down => editor.select(true),
+        Keycode::Left | Keycode::Right | Keycode::Return => {
+            let mut quirk_config = emulator.interpreter().quirk_config().clone();
+            editor.toggle(&mut quirk_config);
+            emulator.change_quirks(quirk_config);
+        },
+        _ => return
+    }
+
+    let title = format!("{WINDOW_TITLE} - {}", editor.describe(emulator.interpreter().quirk_config()));
+    emulator.interpreter_mut().set_window_title(&title);
+}
+
+/// Queues the game at the provided path to be loaded into the emulator if possible, or an `Err` if the file could not be read.  
+/// The game replaces the running one at the start of the next frame (see [`Emulator::queue_load`](Emulator::queue_load)).  
+/// The palette saved for the game is applied if there is one, otherwise the one its config section sets, otherwise the [default](default_palette) one.  
+/// The RPL flags saved for the game are loaded as well, starting from 0 if there are none.  
+/// The game's [key profile](key_profile) is applied if it has one, and noted in the window title.  
+/// If enabled, games found in the [database](database) are run with the quirks, cycles per frame, and load address it recommends, and other games with the settings from the command line.  
+/// If enabled, games with a section in the [config](config) file then run with the quirks, cycles per frame, and palette it overrides.
+/// 
+/// # Errors
+/// 
+/// Returns the forwarded `Err` from [`read_game_file`](read_game_file) if the file fails to be read.
+#[cfg(feature = "sdl")]
+fn load_game_file(emulator: &mut Emulator, path: &str, game_settings: &GameSettings) -> io::Result<()> {
+    let game_data = read_game_file(path)?;
+    let config = load_config(emulator.storage(), game_settings);
+    let rom_overrides = if game_settings.use_config { config.rom_overrides(path, &game_data).cloned() } else { None };
+    // The settings are reset for every game while the config is used, so that one game's overrides are not kept for the next
+    let mut load_settings = if game_settings.use_database || game_settings.use_config { apply_game_settings(emulator, &game_data, game_settings) } else { LoadSettings::default() };
+    if let Some(rom_overrides) = &rom_overrides {
+        apply_rom_overrides(emulator, rom_overrides, &mut load_settings);
+    }
+    let palette = Palette::load_for_game(emulator.storage(), path).unwrap_or_else(|e| {
+        frame_log::warn(Subsystem::Storage, format_args!("Error loading palette: {e}"));
+        None
+    });
+    // A palette saved from the palette menu is newer than the config, so it is used over the config's
+    let palette = palette.or_else(|| rom_overrides.and_then(|rom_overrides| rom_overrides.palette));
+    load_settings.palette = Some(palette.unwrap_or_else(|| default_palette(game_settings, &config)));
+    emulator.queue_load_with(game_data, load_settings);
+    let rpl_flags = savestate::load_rpl_flags(emulator.storage(), path).unwrap_or_else(|e| {
+        frame_log::warn(Subsystem::Storage, format_args!("Error loading RPL flags: {e}"));
+        None
+    });
+    emulator.interpreter_mut().set_rpl_flags(rpl_flags.unwrap_or_default());
+    let splits = Splits::load_for_game(emulator.storage(), path).unwrap_or_else(|e| {
+        frame_log::warn(Subsystem::Storage, format_args!("Error loading splits: {e}"));
+        None
+    });
+    emulator.set_speedrun_timer(splits.map(SpeedrunTimer::new));
+    let key_profile = KeyProfile::load_for_game(emulator.storage(), path).unwrap_or_else(|e| {
+        frame_log::warn(Subsystem::Storage, format_args!("Error loading key profile: {e}"));
+        None
+    });
+    apply_key_profile(emulator, key_profile);
+
+    Ok(())
+}
+
+/// Queues the game at the provided path to be loaded (see [`load_game_file`](load_game_file)), then offers to resume it from its autosave if it has one.  
+/// If the file type is wrong (see [`read_game_file`](read_game_file)), then the user is warned and we continue as if nothing happened.  
+/// The emulator is only locked while the game is queued, not while the user is asked anything.  
+/// Returns whether the game was actually queued.
+///
+/// # Parameters
+///
+/// * `emulator` - The emulator to queue the game in.
+/// * `dialogs` - The display the message boxes are shown through.
+/// * `path` - The path to the game file.
+/// * `game_settings` - The settings the game is run with.
+///
+/// # Errors
+///
+/// Returns an `Err` if the file fails to be read.
+#[cfg(feature = "sdl")]
+fn open_game(emulator: &Mutex<Emulator>, dialogs: &dyn Display, path: &str, game_settings: &GameSettings) -> Result<bool, String> {
+    let loaded = load_game_file(&mut lock_emulator(emulator), path, game_settings);
+    match loaded {
+        Ok(()) => {
+            offer_autosave(emulator, dialogs, path);
+            Ok(true)
+        },
+        Err(ref e) if e.kind() == ErrorKind::Unsupported => {
+            let error_message = &format!("{e}");
+            frame_log::warn(Subsystem::Storage, error_message);
+            show_message(dialogs, MessageKind::Warning, "Unsupported File", error_message);
+            Ok(false)
+        },
+        Err(e) => Err(e.to_string())
+    }
+}
+
+/// Loads the [config](config) file, or returns an empty config in pure mode, which ignores the file.  
+/// Errors reading the config are logged rather than returned, and the emulator runs as if there was no config.
+///
+/// # Parameters
+///
+/// * `storage` - Where the config is saved.
+/// * `game_settings` - The settings games are run with.
+#[cfg(feature = "sdl")]
+fn load_config(storage: &dyn StorageBackend, game_settings: &GameSettings) -> Config {
+    if game_settings.pure {
+        return Config::default();
+    }
+
+    Config::load(storage).unwrap_or_else(|e| {
+        frame_log::warn(Subsystem::Storage, format_args!("Error loading {CONFIG_PATH}: {e}"));
+        Config::default()
+    })
+}
+
+/// Returns the palette games are drawn in unless they have their own: the one from the command line, otherwise the one the config sets for every game, otherwise the default one.
+///
+/// # Parameters
+///
+/// * `game_settings` - The settings games are run with.
+/// * `config` - The config file's settings.
+#[cfg(feature = "sdl")]
+fn default_palette(game_settings: &GameSettings, config: &Config) -> Palette {
+    game_settings.palette.clone().or_else(|| config.palette.clone()).unwrap_or_default()
+}
+
+/// Runs the game with the quirks and cycles per frame the [config](config) file overrides for it, on top of the settings it would otherwise run with.
+///
+/// # Parameters
+///
+/// * `emulator` - The emulator the game is being loaded into.
+/// * `rom_overrides` - The settings the config overrides for the game.
+#[cfg(feature = "sdl")]
+fn apply_rom_overrides(emulator: &Emulator, rom_overrides: &RomOverrides, load_settings: &mut LoadSettings) {
+    let quirk_config = load_settings.quirk_config.clone().unwrap_or_else(|| emulator.interpreter().quirk_config().clone());
+    let quirk_config = rom_overrides.quirk_config(&quirk_config).unwrap_or_else(|e| {
+        frame_log::warn(Subsystem::Emulator, format_args!("Error applying the quirks from {CONFIG_PATH}: {e}"));
+        quirk_config
+    });
+    let cycles_per_frame = rom_overrides.tick_rate.or(load_settings.cycles_per_frame).unwrap_or_else(|| emulator.cycles_per_frame());
+    println!("Running with the settings from {CONFIG_PATH}: {cycles_per_frame} cycles per frame and the quirks {quirk_config}.");
+    load_settings.quirk_config = Some(quirk_config);
+    load_settings.cycles_per_frame = Some(cycles_per_frame);
+}
+
+/// Remaps the keys with the provided profile, noting it in the window title, or restores the usual mapping if there is none.  
+/// Keys the profile names which SDL does not know are logged, as they can never be pressed.
+///
+/// # Parameters
+///
+/// * `emulator` - The emulator the game is being loaded into.
+/// * `key_profile` - The key profile of the game.
+#[cfg(feature = "sdl")]
+fn apply_key_profile(emulator: &mut Emulator, key_profile: Option<KeyProfile>) {
+    match &key_profile {
+        Some(key_profile) => {
+            for (key_name, _) in key_profile.keys.iter().filter(|(key_name, _)| Keycode::from_name(key_name).is_none()) {
+                frame_log::warn(Subsystem::Input, format_args!("Unknown key in key profile: {key_name}"));
+            }
+            println!("Key profile: {key_profile}");
+            emulator.interpreter_mut().set_window_title(&format!("{WINDOW_TITLE} - Key profile: {key_profile}"));
+        },
+        None => emulator.interpreter_mut().set_window_title(WINDOW_TITLE)
+    }
+
+    emulator.set_key_profile(key_profile);
+}
+
+/// Returns the quirks and cycles per frame to load the game with, as recommended by the [database](database), or the provided settings if the game is not in it (or the database is not used).  
+/// The load address is set right away, as it only takes effect when the next game is loaded.
+///
+/// # Parameters
+///
+/// * `emulator` - The emulator the game is being loaded into.
+/// * `game_data` - The bytes of the game file.
+/// * `game_settings` - The settings to use for unknown games.
+fn apply_game_settings(emulator: &mut Emulator, game_data: &[u8], game_settings: &GameSettings) -> LoadSettings {
+    let entry = if game_settings.use_database { database::lookup(game_data) } else { None };
+    apply_database_entry(emulator, entry, game_settings)
+}
+
+/// Returns the quirks and cycles per frame to load a game with, as recommended by its database entry, or the provided settings if there is none.  
+/// The load address is set right away, as it only takes effect when the next game is loaded.
+///
+/// # Parameters
+///
+/// * `emulator` - The emulator the game is being loaded into.
+/// * `entry` - The database entry of the game, if it is a known one.
+/// * `game_settings` - The settings to use for unknown games.
+fn apply_database_entry(emulator: &mut Emulator, entry: Option<DatabaseEntry>, game_settings: &GameSettings) -> LoadSettings {
+    let quirk_config = entry.map_or_else(|| game_settings.quirk_config.clone(), |entry| entry.quirk_config(&game_settings.quirk_config));
+    let cycles_per_frame = entry.and_then(|entry| entry.tick_rate).unwrap_or(game_settings.cycles_per_frame);
+    let load_address = entry.and_then(|entry| entry.load_address).unwrap_or(game_settings.load_address);
+    if let Some(entry) = entry {
+        println!("Recognized {}, running it from 0x{load_address:03X} with {cycles_per_frame} cycles per frame and the quirks {quirk_config}.", entry.title);
+    }
+
+    if let Err(e) = emulator.interpreter_mut().set_load_address(load_address) {
+        frame_log::warn(Subsystem::Emulator, format_args!("Error applying the game database's load address: {e}"));
+    }
+
+    LoadSettings { quirk_config: Some(quirk_config), cycles_per_frame: Some(cycles_per_frame), palette: None }
+}
+
+/// Saves the state of the current game alongside it, so that it can be resumed the next time it is loaded.  
+/// Nothing is saved if no game is running (e.g. it halted), and errors are logged rather than returned as the system is likely about to suspend or shut down.
+///
+/// # Parameters
+///
+/// * `emulator` - The emulator running the game.
+/// * `game_path` - The path to the current game, if one is loaded.
+#[cfg(feature = "sdl")]
+fn autosave(emulator: &mut Emulator, game_path: Option<&str>) {
+    let Some(game_path) = game_path else {
+        return;
+    };
+    if matches!(emulator.mode(), EmulatorMode::Idle | EmulatorMode::Halted(_)) || emulator.has_pending_load() {
+        return;
+    }
+
+    let snapshot = emulator.interpreter().snapshot();
+    if let Err(e) = savestate::save_autosave(emulator.storage_mut(), game_path, &snapshot) {
+        frame_log::warn(Subsystem::Storage, format_args!("Error autosaving: {e}"));
+    }
+}
+
+/// Saves the state of the current game to the numbered slot, noting it in the window title.  
+/// Nothing is saved if no game is running (e.g. it halted), and errors are logged rather than returned as the game can carry on without the save.
+///
+/// # Parameters
+///
+/// * `emulator` - The emulator running the game.
+/// * `slot` - The number of the slot.
+#[cfg(feature = "sdl")]
+fn save_to_slot(emulator: &mut Emulator, slot: u8) {
+    if matches!(emulator.mode(), EmulatorMode::Idle | EmulatorMode::Halted(_)) || emulator.has_pending_load() {
+        return;
+    }
+    let Some(game_data) = emulator.game_data().map(<[u8]>::to_vec) else {
+        return;
+    };
+
+    let snapshot = emulator.interpreter().snapshot();
+    match savestate::save_slot(emulator.storage_mut(), &game_data, slot, &snapshot) {
+        Ok(path) => {
+            println!("Saved slot {slot} to {path}.");
+            emulator.interpreter_mut().set_window_title(&format!("{WINDOW_TITLE} - Saved slot {slot}"));
+        },
+        Err(e) => frame_log::warn(Subsystem::Storage, format_args!("Error saving slot {slot}: {e}"))
+    }
+}
+
+/// Loads the current game from the numbered slot at the start of the next frame, noting it in the window title.  
+/// A halted game is reloaded first, so that it carries on from the slot.
+///
+/// # Parameters
+///
+/// * `emulator` - The emulator running the game.
+/// * `slot` - The number of the slot.
+#[cfg(feature = "sdl")]
+fn load_from_slot(emulator: &mut Emulator, slot: u8) {
+    let Some(game_data) = emulator.game_data() else {
+        return;
+    };
+
+    let status = match savestate::load_slot(emulator.storage(), game_data, slot) {
+        Ok(Some(snapshot)) => {
+            if matches!(emulator.mode(), EmulatorMode::Halted(_)) {
+                emulator.reset();
+            }
+            emulator.queue_restore(snapshot);
+            format!("Loaded slot {slot}")
+        },
+        Ok(None) => format!("Slot {slot} is empty"),
+        Err(e) => {
+            frame_log::warn(Subsystem::Storage, format_args!("Error loading slot {slot}: {e}"));
+            format!("Could not load slot {slot}")
+        }
+    };
+    emulator.interpreter_mut().set_window_title(&format!("{WINDOW_TITLE} - {status}"));
+}
+
+/// Starts recording a macro of the current game, or stops recording and saves it to the numbered slot, noting it in the window title.
+///
+/// # Parameters
+///
+/// * `emulator` - The emulator running the game.
+/// * `slot` - The number of the slot.
+#[cfg(feature = "sdl")]
+fn toggle_macro_recording(emulator: &mut Emulator, slot: u8) {
+    let Some(game_data) = emulator.game_data().map(<[u8]>::to_vec) else {
+        return;
+    };
+
+    let status = match emulator.finish_macro_recording() {
+        None => {
+            emulator.start_macro_recording();
+            format!("Recording macro {slot}")
+        },
+        Some(input_macro) => match macros::save_macro(emulator.storage_mut(), &game_data, slot, &input_macro) {
+            Ok(path) => {
+                println!("Saved macro {slot} to {path}.");
+                format!("Saved macro {slot}")
+            },
+            Err(e) => {
+                frame_log::warn(Subsystem::Storage, format_args!("Error saving macro {slot}: {e}"));
+                format!("Could not save macro {slot}")
+            }
+        }
+    };
+    emulator.interpreter_mut().set_window_title(&format!("{WINDOW_TITLE} - {status}"));
+}
+
+/// Plays the macro in the numbered slot of the current game from the next frame, noting it in the window title.
+///
+/// # Parameters
+///
+/// * `emulator` - The emulator running the game.
+/// * `slot` - The number of the slot.
+#[cfg(feature = "sdl")]
+fn play_macro(emulator: &mut Emulator, slot: u8) {
+    if emulator.is_pure() {
+        emulator.interpreter_mut().set_window_title(&format!("{WINDOW_TITLE} - Macros are locked by --pure"));
+        return;
+    }
+    let Some(game_data) = emulator.game_data() else {
+        return;
+    };
+
+    let status = match macros::load_macro(emulator.storage(), game_data, slot) {
+        Ok(Some(input_macro)) => {
+            emulator.play_macro(input_macro);
+            format!("Playing macro {slot}")
+        },
+        Ok(None) => format!("Macro {slot} is empty"),
+        Err(e) => {
+            frame_log::warn(Subsystem::Storage, format_args!("Error loading macro {slot}: {e}"));
+            format!("Could not load macro {slot}")
+        }
+    };
+    emulator.interpreter_mut().set_window_title(&format!("{WINDOW_TITLE} - {status}"));
+}
+
+/// Offers to resume the game from its autosave, if it has one. The autosave is deleted either way, so that the offer is only made once.  
+/// The emulator is not locked while the user decides.
+///
+/// # Parameters
+///
+/// * `emulator` - The emulator the game has been queued in.
+/// * `dialogs` - The display the message box is shown through.
+/// * `game_path` - The path to the game file.
+#[cfg(feature = "sdl")]
+fn offer_autosave(emulator: &Mutex<Emulator>, dialogs: &dyn Display, game_path: &str) {
+    let autosave = savestate::load_autosave(lock_emulator(emulator).storage(), game_path);
+    let snapshot = match autosave {
+        Ok(Some(snapshot)) => snapshot,
+        Ok(None) => return,
+        Err(e) => {
+            frame_log::warn(Subsystem::Storage, format_args!("Error loading autosave: {e}"));
+            return;
+        }
+    };
+
+    let choice = show_choice(dialogs, MessageKind::Information, "Resume Game", "This game was saved automatically when it was last closed. Would you like to resume where you left off?", &["Resume", "Start Over"]);
+    let mut emulator = lock_emulator(emulator);
+    if choice == Some(0) {
+        emulator.queue_restore(snapshot);
+    }
+    if let Err(e) = savestate::delete_autosave(emulator.storage_mut(), game_path) {
+        frame_log::warn(Subsystem::Storage, format_args!("Error deleting autosave: {e}"));
+    }
+}
+
+/// Returns the byte contents of the provided game file, or an `io::Error` if the read fails.
+///
+/// # Errors
+///
+/// Returns an `Err` if the file fails to be read.
+fn read_game_file(path: &str) -> io::Result<Vec<u8>> {
+    if !is_game_file(path) {
+        return Err(io::Error::new(ErrorKind::Unsupported, format!("Invalid file found at {path}. Only CHIP-8 files (.ch8 or .chip8) are valid.")));
+    }
+
+    fs::read(path)
+}
+
+/// Returns whether the file has the extension of a CHIP-8 game (.ch8 or .chip8).
+///
+/// # Parameters
+///
+/// * `path` - The path to the file.
+fn is_game_file(path: &str) -> bool {
+    std::path::Path::new(path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("ch8") || ext.eq_ignore_ascii_case("chip8"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "sdl")]
+    use crate::palette::PalettePreset;
+    #[cfg(feature = "sdl")]
+    use crate::quirks::Platform;
+    #[cfg(feature = "sdl")]
+    use crate::storage::{MemoryStorage, StorageBackend};
+    #[cfg(feature = "sdl")]
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    const EXISTING_GAME_PATH: &str = "games/15PUZZLE.chip8";
+    const NON_EXISTENT_GAME_PATH: &str = "games/FAKE.chip8";
+    const INVALID_GAME_PATH: &str = "README.md";
+
+    /// Returns the settings of a run without the database.
+    fn game_settings() -> GameSettings {
+        GameSettings { quirk_config: QuirkConfig::new(), cycles_per_frame: 10, load_address: interpreter::PROGRAM_START_ADDRESS, banking: false, hires_detection: true, use_database: false, use_config: false, palette: None, pure: false }
+    }
+
+    #[test]
+    fn read_existing_game_file() {
+        assert!(read_game_file(EXISTING_GAME_PATH).is_ok(), "Valid game file was not read.");
+    }
+
+    #[test]
+    fn read_non_existent_game_file() {
+        assert_ne!(read_game_file(NON_EXISTENT_GAME_PATH).unwrap_err().kind(), ErrorKind::Unsupported, "Wrong error returned for non-existent game file.");
+    }
+
+    #[test]
+    fn read_invalid_game_file() {
+        assert_eq!(read_game_file(INVALID_GAME_PATH).unwrap_err().kind(), ErrorKind::Unsupported, "Wrong error returned for invalid game file.");
+    }
+
+    #[test]
+    fn inspect_game_file_headless() {
+        let info = info(EXISTING_GAME_PATH).unwrap();
+        assert!(info.contains("SHA-1: ea9af3c09b0d9e265fcd92bcc5d51a2939fdf27a\nTitle: 15PUZZLE\nPlatform: cosmac-vip"), "Game not described: {info}");
+        assert!(disassemble(EXISTING_GAME_PATH, 0x200, false, &SymbolTable::new()).unwrap().starts_with("0x200: "), "Game not disassembled from the load address.");
+
+        let report = run_headless(EXISTING_GAME_PATH, 60, &game_settings(), &DebugConfig::default(), &DumpPaths::default()).unwrap();
+        assert!(report.starts_with("Ran 60 frames.\nNext instruction: 0x"), "Headless run not reported: {report}");
+        assert_eq!(run_headless(EXISTING_GAME_PATH, 60, &game_settings(), &DebugConfig::default(), &DumpPaths::default()), Ok(report), "Headless run not repeatable.");
+        assert!(run_headless(INVALID_GAME_PATH, 60, &game_settings(), &DebugConfig::default(), &DumpPaths::default()).is_err(), "Invalid game file was run.");
+        let report = run_headless(EXISTING_GAME_PATH, 60, &game_settings(), &DebugConfig { profile: true, ..DebugConfig::default() }, &DumpPaths::default()).unwrap();
+        assert!(report.contains("\nOpcode ") && report.contains("\nTotal "), "Profile not reported: {report}");
+        let report = run_headless(EXISTING_GAME_PATH, 60, &game_settings(), &DebugConfig { coverage: true, ..DebugConfig::default() }, &DumpPaths::default()).unwrap();
+        assert!(report.contains("\nInstructions run: ") && report.contains("\nOpcodes never used: "), "Coverage not reported: {report}");
+
+        let state_path = std::env::temp_dir().join(format!("rusty_chip_dump_test_{}.rcss", std::process::id())).to_string_lossy().into_owned();
+        let dump_paths = DumpPaths { state_path: Some(state_path.clone()), frame_path: None };
+        let report = run_headless(EXISTING_GAME_PATH, 60, &game_settings(), &DebugConfig::default(), &dump_paths).unwrap();
+        assert!(report.contains(&format!("\nWrote the state to {state_path}.\n")), "State dump not reported: {report}");
+        assert!(savestate::decode(&fs::read(&state_path).unwrap()).is_ok(), "State not dumped.");
+        fs::remove_file(state_path).unwrap();
+    }
+
+    #[test]
+    fn compat_report_of_directory() {
+        let games_dir = std::env::temp_dir().join(format!("rusty_chip_compat_test_{}", std::process::id()));
+        fs::create_dir_all(&games_dir).unwrap();
+        fs::write(games_dir.join("exit.ch8"), [0x00, 0x00]).unwrap();
+        fs::write(games_dir.join("loop.ch8"), [0x12, 0x00]).unwrap();
+        fs::write(games_dir.join("notes.txt"), "Not a game.").unwrap();
+        let output_path = games_dir.join("report.json");
+
+        let summary = compat(games_dir.to_str().unwrap(), 10, &game_settings(), output_path.to_str().unwrap()).unwrap();
+        assert_eq!(summary, format!("Wrote the report of 2 games to {}: 0 passed, 1 blank-screen, 1 exited, 0 faulted.\n", output_path.display()), "Games not run.");
+        let report = fs::read_to_string(&output_path).unwrap();
+        assert!(report.contains("exit.ch8\",\"title\":null,\"platform\":\"default\",\"frames_run\":1,\"outcome\":\"exited\""), "Report not written: {report}");
+        fs::remove_dir_all(games_dir).unwrap();
+    }
+
+    #[test]
+    fn bench_report_of_directory() {
+        let games_dir = std::env::temp_dir().join(format!("rusty_chip_bench_test_{}", std::process::id()));
+        fs::create_dir_all(&games_dir).unwrap();
+        fs::write(games_dir.join("loop.ch8"), [0x70, 0x01, 0x12, 0x00]).unwrap();
+        fs::write(games_dir.join("notes.txt"), "Not a game.").unwrap();
+        let output_path = games_dir.join("bench.md");
+
+        let report = bench_report(games_dir.to_str().unwrap(), 10, &game_settings(), Some(output_path.to_str().unwrap())).unwrap();
+        assert!(report.contains("1 games run for 10 frames"), "Games not run: {report}");
+        assert!(report.contains("loop.ch8 |"), "Game not reported: {report}");
+        assert_eq!(fs::read_to_string(&output_path).unwrap(), report, "Report not written.");
+        fs::remove_dir_all(games_dir).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "sdl")]
+    fn load_existing_game_file() {
+        let mut emulator = Emulator::new(Interpreter::new_headless(QuirkConfig::new()), 10);
+
+        assert!(load_game_file(&mut emulator, EXISTING_GAME_PATH, &game_settings()).is_ok(), "Valid game file was not loaded.");
+        assert!(emulator.has_pending_load(), "Valid game file was not queued.");
+    }
+
+    #[test]
+    #[cfg(feature = "sdl")]
+    fn load_game_file_key_profile() {
+        let mut emulator = Emulator::new(Interpreter::new_headless(QuirkConfig::new()), 10);
+        let mut storage = MemoryStorage::new();
+        storage.write(&KeyProfile::path_for_game(EXISTING_GAME_PATH), b"name Arrows\nLeft = 4").unwrap();
+        emulator.set_storage(Box::new(storage));
+
+        load_game_file(&mut emulator, EXISTING_GAME_PATH, &game_settings()).unwrap();
+        assert_eq!(emulator.key_profile().and_then(|key_profile| key_profile.name.as_deref()), Some("Arrows"), "Key profile not applied.");
+
+        emulator.storage_mut().delete(&KeyProfile::path_for_game(EXISTING_GAME_PATH)).unwrap();
+        load_game_file(&mut emulator, EXISTING_GAME_PATH, &game_settings()).unwrap();
+        assert!(emulator.key_profile().is_none(), "Key profile kept for a game without one.");
+    }
+
+    #[test]
+    #[cfg(feature = "sdl")]
+    fn load_game_file_from_database() {
+        let mut emulator = Emulator::new(Interpreter::new_headless(QuirkConfig::new()), 10);
+        let mut game_settings = GameSettings { quirk_config: Platform::XoChip.quirk_config(), cycles_per_frame: 20, load_address: 0x600, banking: false, hires_detection: true, use_database: false, use_config: false, palette: None, pure: false };
+
+        load_game_file(&mut emulator, EXISTING_GAME_PATH, &game_settings).unwrap();
+        emulator.step_frame();
+        assert_eq!(*emulator.interpreter().quirk_config(), QuirkConfig::new(), "Quirks changed without the database.");
+
+        game_settings.use_database = true;
+        load_game_file(&mut emulator, EXISTING_GAME_PATH, &game_settings).unwrap();
+        assert_eq!((emulator.interpreter().quirk_config(), emulator.cycles_per_frame()), (&QuirkConfig::new(), 10), "Settings changed before the queued load.");
+        emulator.step_frame();
+        assert_eq!(*emulator.interpreter().quirk_config(), Platform::CosmacVip.quirk_config(), "Database platform not applied.");
+        assert_eq!(emulator.cycles_per_frame(), 20, "Default cycles per frame not used.");
+        assert_eq!(emulator.interpreter().load_address(), 0x600, "Default load address not used.");
+
+        let entry = DatabaseEntry { title: "Some Game", platform: Some(Platform::Schip), tick_rate: Some(30), load_address: Some(0x300), quirks: None };
+        let load_settings = apply_database_entry(&mut emulator, Some(entry), &game_settings);
+        assert_eq!(load_settings.quirk_config, Some(Platform::Schip.quirk_config()), "Database quirks not applied.");
+        assert_eq!(load_settings.cycles_per_frame, Some(30), "Database cycles per frame not applied.");
+        assert_eq!(emulator.interpreter().load_address(), 0x300, "Database load address not applied.");
+    }
+
+    #[test]
+    #[cfg(feature = "sdl")]
+    fn load_game_file_from_config() {
+        let mut emulator = Emulator::new(Interpreter::new_headless(QuirkConfig::new()), 10);
+        let mut storage = MemoryStorage::new();
+        storage.write(CONFIG_PATH, b"[rom.\"15puzzle.CHIP8\"]\nplatform = \"schip\"\ntick-rate = 30\npalette = \"#112233\"\n").unwrap();
+        emulator.set_storage(Box::new(storage));
+        let mut game_settings = game_settings();
+
+        load_game_file(&mut emulator, EXISTING_GAME_PATH, &game_settings).unwrap();
+        assert_eq!(emulator.cycles_per_frame(), 10, "Config used while ignored.");
+
+        game_settings.use_config = true;
+        load_game_file(&mut emulator, EXISTING_GAME_PATH, &game_settings).unwrap();
+        emulator.step_frame();
+        assert_eq!(*emulator.interpreter().quirk_config(), Platform::Schip.quirk_config(), "Config quirks not applied.");
+        assert_eq!(emulator.cycles_per_frame(), 30, "Config tick rate not applied.");
+        assert_eq!(emulator.renderer().palette().colours[0].to_string(), "#112233", "Config palette not applied.");
+
+        emulator.storage_mut().delete(CONFIG_PATH).unwrap();
+        load_game_file(&mut emulator, EXISTING_GAME_PATH, &game_settings).unwrap();
+        emulator.step_frame();
+        assert_eq!((emulator.interpreter().quirk_config().clone(), emulator.cycles_per_frame()), (QuirkConfig::new(), 10), "Config settings kept for a game without overrides.");
+        assert_eq!(*emulator.renderer().palette(), Palette::new(), "Config palette kept for a game without overrides.");
+    }
+
+    #[test]
+    #[cfg(feature = "sdl")]
+    fn load_game_file_with_palette() {
+        let mut emulator = Emulator::new(Interpreter::new_headless(QuirkConfig::new()), 10);
+        let mut storage = MemoryStorage::new();
+        storage.write(CONFIG_PATH, b"palette = \"amber\"\n").unwrap();
+        emulator.set_storage(Box::new(storage));
+        let mut game_settings = game_settings();
+
+        load_game_file(&mut emulator, EXISTING_GAME_PATH, &game_settings).unwrap();
+        assert_eq!(*emulator.renderer().palette(), Palette::new(), "Palette changed before the queued load.");
+        emulator.step_frame();
+        assert_eq!(*emulator.renderer().palette(), PalettePreset::Amber.palette(), "Config palette for every game not applied.");
+
+        game_settings.palette = Some(PalettePreset::Lcd.palette());
+        load_game_file(&mut emulator, EXISTING_GAME_PATH, &game_settings).unwrap();
+        emulator.step_frame();
+        assert_eq!(*emulator.renderer().palette(), PalettePreset::Lcd.palette(), "Command line palette not used over the config's.");
+
+        game_settings.use_config = true;
+        emulator.storage_mut().write(CONFIG_PATH, b"[rom.\"15PUZZLE.chip8\"]\npalette = \"paper-white\"\n").unwrap();
+        load_game_file(&mut emulator, EXISTING_GAME_PATH, &game_settings).unwrap();
+        emulator.step_frame();
+        assert_eq!(*emulator.renderer().palette(), PalettePreset::PaperWhite.palette(), "Game's config palette not used over the command line's.");
+    }
+
+    #[test]
+    #[cfg(feature = "sdl")]
+    fn load_non_existent_game_file() {
+        let mut emulator = Emulator::new(Interpreter::new_headless(QuirkConfig::new()), 10);
+
+        assert!(load_game_file(&mut emulator, NON_EXISTENT_GAME_PATH, &game_settings()).is_err(), "Non-existent game file was loaded successfully.");
+    }
+
+    #[test]
+    #[cfg(feature = "sdl")]
+    fn load_invalid_game_file() {
+        let mut emulator = Emulator::new(Interpreter::new_headless(QuirkConfig::new()), 10);
+
+        assert_eq!(load_game_file(&mut emulator, INVALID_GAME_PATH, &game_settings()).map_err(|e| e.kind()), Err(ErrorKind::Unsupported), "Invalid game file not reported as unsupported.");
+        assert!(!emulator.has_pending_load(), "Invalid game file was queued.");
+    }
+
+    /// Stands in for a message box left open by the user, pressing a key for the game in the meantime and checking whether the game got it.
+    #[cfg(feature = "sdl")]
+    struct PendingDialog {
+        emulator: Arc<Mutex<Emulator>>,
+        commands: mpsc::Sender<EmulationCommand>,
+        is_key_pressed: AtomicBool
+    }
+
+    #[cfg(feature = "sdl")]
+    impl Display for PendingDialog {
+        fn clear(&mut self, _colour: palette::Colour) {}
+
+        fn fill_rects(&mut self, _colour: palette::Colour, _alpha: u8, _rects: &[backend::Rect]) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn draw_rects(&mut self, _colour: palette::Colour, _rects: &[backend::Rect]) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn present(&mut self) {}
+
+        fn set_title(&mut self, _title: &str) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn show_message(&self, _kind: MessageKind, _title: &str, _message: &str) -> Result<(), String> {
+            self.commands.send(EmulationCommand::PressKey(Keycode::Num1)).map_err(|e| e.to_string())?;
+
+            // The emulator is only tried, so that a dialog which kept it locked fails the test rather than hanging it
+            let deadline = Instant::now() + Duration::from_secs(5);
+            while Instant::now() < deadline && !self.is_key_pressed.load(Ordering::Relaxed) {
+                if let Ok(emulator) = self.emulator.try_lock() {
+                    self.is_key_pressed.store(emulator.interpreter().pressed_keys() & (1 << 0x1) != 0, Ordering::Relaxed);
+                }
+                thread::sleep(Duration::from_millis(1));
+            }
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "sdl")]
+    fn input_during_dialog() {
+        let mut emulator = Emulator::new(Interpreter::new_headless(QuirkConfig::new()), 10);
+        emulator.set_storage(Box::new(MemoryStorage::new()));
+        let emulator = Arc::new(Mutex::new(emulator));
+        let (frame_sender, _frame_receiver) = mpsc::sync_channel(1);
+        let (command_sender, command_receiver) = mpsc::channel();
+        let observers = FrameObservers { watch_logger: None, watch_expressions: Vec::new(), metrics_logger: None, graph_panel: None, slow_host_frame_time: None };
+        let emulation_thread = {
+            let emulator = Arc::clone(&emulator);
+            thread::spawn(move || run_emulation(&emulator, &frame_sender, &command_receiver, observers))
+        };
+        let dialogs = PendingDialog { emulator: Arc::clone(&emulator), commands: command_sender, is_key_pressed: AtomicBool::new(false) };
+
+        show_notes(&emulator, &dialogs, EXISTING_GAME_PATH);
+        let is_key_pressed = dialogs.is_key_pressed.load(Ordering::Relaxed);
+        drop(dialogs);
+        emulation_thread.join().unwrap();
+
+        assert!(is_key_pressed, "Key press not forwarded while the dialog was open.");
+    }
+
+    #[test]
+    #[cfg(feature = "sdl")]
+    fn step_through_speeds() {
+        assert_eq!(next_speed(1.0, true), 1.5, "Speed not stepped up.");
+        assert_eq!(next_speed(1.0, false), 0.75, "Speed not stepped down.");
+        assert_eq!(next_speed(1.2, false), 1.0, "Speed between steps not stepped to the nearest slower one.");
+        assert_eq!(next_speed(emulator::MAX_SPEED, true), emulator::MAX_SPEED, "Speed stepped past the fastest step.");
+        assert_eq!(next_speed(emulator::MIN_SPEED, false), emulator::MIN_SPEED, "Speed stepped past the slowest step.");
     }
 }
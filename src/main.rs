@@ -1,50 +1,834 @@
 use std::process;
+use std::time::Duration;
 
-use clap::Parser;
+use clap::{Parser, Subcommand, ValueEnum};
 
-use rusty_chip::quirks::{ClippingQuirk, DisplayWaitQuirk, JumpingQuirk, MemoryIncrementQuirk, QuirkConfig, ResetVfQuirk, ShiftingQuirk};
+use rusty_chip::bench::BENCH_FRAMES;
+use rusty_chip::demos::{self, DEMOS};
+use rusty_chip::emulator;
+use rusty_chip::frame_log::{self, FrameRange};
+use rusty_chip::fuzz::FuzzConfig;
+use rusty_chip::graph::DEFAULT_GRAPH_WIDTH;
+use rusty_chip::GameSettings;
+use rusty_chip::headless::DumpPaths;
+use rusty_chip::interpreter::{self, MemoryProtection};
+use rusty_chip::palette::Palette;
+use rusty_chip::speedrun::Condition;
+use rusty_chip::symbols::SymbolTable;
+use rusty_chip::screenshot::{AutoScreenshot, ScreenshotTrigger, DEFAULT_SCREENSHOT_DIR};
+use rusty_chip::quirks::{ClippingQuirk, DisplayWaitQuirk, JumpingQuirk, KeyGhostingQuirk, MemoryIncrementQuirk, MemoryTimingQuirk, Platform, QuirkConfig, ResetVfQuirk, ShiftingQuirk};
+use rusty_chip::DebugConfig;
+#[cfg(feature = "sdl")]
+use rusty_chip::layout::OverlayLayout;
+#[cfg(feature = "sdl")]
+use rusty_chip::storage::FileStorage;
+#[cfg(feature = "sdl")]
+use rusty_chip::SpeedConfig;
+#[cfg(feature = "sdl")]
+use rusty_chip::watch::WatchConfig;
+use rusty_chip::watch::WatchExpression;
 
-const CYCLES_PER_FRAME: u32 = 10;
+const CYCLES_PER_FRAME: u32 = emulator::DEFAULT_CYCLES_PER_FRAME;
+
+/// Ten seconds of play per fuzzing run.
+const FUZZ_FRAMES: u64 = 600;
+
+/// Above this many cycles per frame, games made for the original CHIP-8 will run far too fast.
+const MAX_SENSIBLE_CYCLES_PER_FRAME: u32 = 1000;
+
+#[cfg(feature = "alloc-tracking")]
+#[global_allocator]
+static GLOBAL: rusty_chip::alloc_tracker::CountingAllocator = rusty_chip::alloc_tracker::CountingAllocator;
 
 /// Holds the information to be parsed from the command line arguments.
 #[derive(Parser)]
-#[command(version, about, long_about = None)]
+#[command(version, long_version = concat!(env!("CARGO_PKG_VERSION"), " (", env!("RUSTY_CHIP_GIT_HASH"), ")"), about, long_about = None)]
 struct Cli {
     #[arg(long_help = "Path to the game file.")]
     game: Option<String>,
 
-    #[arg(short, long, default_value_t = CYCLES_PER_FRAME, long_help = "The number of instructions that will run in a single frame.")]
-    cycles_per_frame: u32,
+    #[arg(short, long, long_help = "The number of instructions that will run in a single frame. Defaults to 10, or the tick rate the game database recommends for a known game.")]
+    cycles_per_frame: Option<u32>,
+    #[arg(long, value_name = "ADDRESS", value_parser = parse_address, long_help = "The address the game is loaded and started at, in hexadecimal with a 0x prefix or in decimal (e.g. 0x600 for ETI-660 games). Defaults to 0x200, or the address the game database records for a known game.")]
+    load_address: Option<u16>,
+    #[arg(long, long_help = "Split games into 2K banks which they switch between by writing the bank number to 0x1FF, an experimental scheme for homebrew larger than the 4K the CHIP-8 can address. See the banking module documentation for the layout.")]
+    banking: bool,
+    #[arg(long, long_help = "Run games starting with 1260 as regular CHIP-8 games, rather than recognizing them as HiRes CHIP-8 games and running them on the 64x64 display from 0x2C0.")]
+    no_hires: bool,
+    #[arg(long, long_help = "Run games exactly as the --platform preset (or the default quirks) describes, for compatibility validation and comparisons against other emulators. Ignores the individual quirk flags, --banking, the game database, and the config file, and disables freezing bytes, poking, macros, the quirk menu, moved overlays, and the input display.")]
+    pure: bool,
+    #[arg(long = "overlay", value_name = "ENTRY", long_help = "Move an overlay out of the way of the game, e.g. \"input-display anchor=top scale=0.5 opacity=0.75\". The anchor is top-left, top, top-right, left, centre, right, bottom-left, bottom, or bottom-right, the scale is relative to the usual size, and the opacity is from 0 to 1. The layout is saved to overlays.layout, so later runs keep it. Can be repeated.")]
+    overlays: Vec<String>,
+    #[arg(long, value_name = "SLOT|FILE", long_help = "Resume the game from a save state at startup: the number (1-9) of one of its save slots, saved with Shift and the number key, or the path to a save state file.")]
+    resume_state: Option<String>,
+    #[arg(long, value_name = "MULTIPLIER", default_value_t = 1.0, value_parser = parse_speed, long_help = "How fast to run the game relative to its usual speed, from 0.25 to 4 (e.g. 0.5 to practise a hard section at half speed). The cycles, timers, and frames all keep their usual pace relative to each other. The speed can also be stepped with - and + while playing.")]
+    speed: f64,
+    #[arg(long, value_name = "CHORD", default_value = rusty_chip::DEFAULT_TURBO_KEY, long_help = "The key held to fast-forward the game, as SDL names it, optionally with modifiers (e.g. Space, Backspace, or Ctrl+F). It takes the place of any hotkey bound to the same chord.")]
+    turbo_key: String,
+    #[arg(long, value_name = "FACTOR", default_value_t = rusty_chip::DEFAULT_TURBO_FACTOR, value_parser = clap::value_parser!(u32).range(2..), long_help = "How many times the usual cycles per frame to run while fast-forwarding. Frames are also run back to back rather than at 60 per second, and the sound is muted.")]
+    turbo_factor: u32,
+    #[arg(long, value_name = "PALETTE", long_help = "The colours games are drawn in unless a palette was saved for them from the palette menu or the config file sets one for them: a preset (classic-green, lcd, amber, paper-white, or octo for Octo's XO-CHIP colours), colours such as \"fg=#FFB000,bg=#000000\", or a preset with some of its colours changed, such as \"lcd,fg=#000000\". XO-CHIP games also draw in fg2 on the second plane and blend where both planes are set. Up to four colours can also be listed in order (bg, fg, fg2, then blend). Defaults to the palette in rusty_chip.toml, or classic-green.")]
+    palette: Option<Palette>,
 
     // Quirk flags
-    #[arg(long, default_value_t, value_enum, long_help = "True if the AND, OR, and XOR opcodes should reset the flags register to 0, false if the flag register should be untouched.")]
-    quirk_reset_vf: ResetVfQuirk,
-    #[arg(long, default_value_t, value_enum, long_help = "True if the save and load register opcodes should increment the index register, false if the index register should be untouched.")]
-    quirk_memory: MemoryIncrementQuirk,
-    #[arg(long, default_value_t, value_enum, long_help = "True if the draw opcode should wait for a frame draw before writing, false if it should draw immediately even if it should result in partial sprite draws.")]
-    quirk_display_wait: DisplayWaitQuirk,
-    #[arg(long, default_value_t, value_enum, long_help = "True if the draw opcode clip sprites going off the screen and wrap sprites which are fully off the screen, false if all sprites should wrap.")]
-    quirk_clipping: ClippingQuirk,
-    #[arg(long, default_value_t, value_enum, long_help = "True if the bit shift opcodes should operate on vX, false if they should operate on vY and store the result in vX.")]
-    quirk_shifting: ShiftingQuirk,
-    #[arg(long, default_value_t, value_enum, long_help = "True if the jump v0 opcode should use vX instead (the highest nibble of nnn), false if it should use v0.")]
-    quirk_jumping: JumpingQuirk,
+    #[arg(long, value_enum, long_help = "The machine to match, setting all the quirks to its behaviour. Any quirk flags provided override the platform's setting. Without a platform, the quirks default to the test suite's expectations for the original CHIP-8.")]
+    platform: Option<Platform>,
+    #[arg(long, value_enum, long_help = "True if the AND, OR, and XOR opcodes should reset the flags register to 0, false if the flag register should be untouched.")]
+    quirk_reset_vf: Option<ResetVfQuirk>,
+    #[arg(long, value_enum, long_help = "True if the save and load register opcodes should increment the index register, false if the index register should be untouched.")]
+    quirk_memory: Option<MemoryIncrementQuirk>,
+    #[arg(long, value_enum, long_help = "True if the draw opcode should wait for a frame draw before writing, false if it should draw immediately even if it should result in partial sprite draws.")]
+    quirk_display_wait: Option<DisplayWaitQuirk>,
+    #[arg(long, value_enum, long_help = "True if the draw opcode clip sprites going off the screen and wrap sprites which are fully off the screen, false if all sprites should wrap. Applies to both axes unless overridden.")]
+    quirk_clipping: Option<ClippingQuirk>,
+    #[arg(long, value_enum, long_help = "Overrides the clipping quirk for the horizontal axis only.")]
+    quirk_horizontal_clipping: Option<ClippingQuirk>,
+    #[arg(long, value_enum, long_help = "Overrides the clipping quirk for the vertical axis only.")]
+    quirk_vertical_clipping: Option<ClippingQuirk>,
+    #[arg(long, value_enum, long_help = "True if the bit shift opcodes should operate on vX, false if they should operate on vY and store the result in vX.")]
+    quirk_shifting: Option<ShiftingQuirk>,
+    #[arg(long, value_enum, long_help = "True if the jump v0 opcode should use vX instead (the highest nibble of nnn), false if it should use v0.")]
+    quirk_jumping: Option<JumpingQuirk>,
+    #[arg(long, value_enum, long_help = "Instant if every opcode should take a single instruction cycle, per-register if the store/load registers opcodes should take a cycle per register and the BCD opcode a cycle per digit, as on the original COSMAC VIP.")]
+    quirk_memory_timing: Option<MemoryTimingQuirk>,
+    #[arg(long, value_enum, long_help = "Independent if every key is sensed on its own, ghosting if the keypad should also sense the key on the fourth corner of any three held keys forming a rectangle, as on a matrix keypad without diodes. Only the skip if key opcodes see the ghosted keys.")]
+    quirk_key_ghosting: Option<KeyGhostingQuirk>,
+    #[arg(long, long_help = "Always run with the provided (or default) quirks and cycles per frame, rather than those the bundled game database recommends for known games. The database is also ignored when a platform, quirk, cycles per frame, or load address flag is provided.")]
+    ignore_database: bool,
+
+    #[arg(long, default_value_t, value_enum, long_help = "The regions of memory the game is prevented from writing to, halting the game on a write. Off allows any write (warning about writes over the built-in font), font protects the built-in font, and program protects both the font and the loaded program.")]
+    memory_protection: MemoryProtection,
+    #[arg(long, default_value_t, value_enum, long_help = "Where to run the game: window opens a window with sound, tui draws the display in the terminal with the keys read from it, for SSH sessions and machines without a display server, and egui opens a debugger showing the display alongside panels for the registers, disassembly, memory, and quirks. The tui and egui frontends need a game file and a build with the feature of the same name.")]
+    frontend: Frontend,
+
+    // Debugging flags
+    #[arg(long = "watch", value_name = "EXPRESSION", long_help = "An expression to watch, shown in the window title every frame (e.g. \"V3\", \"[I + 1]\", or \"(V0 + V1) * 2\"). Can be repeated.")]
+    watches: Vec<WatchExpression>,
+    #[arg(long, value_name = "FILE", long_help = "Path to a CSV file which the values of the watch expressions will be logged to, one row per frame.")]
+    watch_csv: Option<String>,
+    #[arg(long = "graph", value_name = "EXPRESSION", long_help = "An expression to graph over the recent frames as a sparkline, redrawn in the terminal while the game runs (e.g. \"V3\" for a speed or \"[0x3F0]\" for a position). Can be repeated.")]
+    graphs: Vec<WatchExpression>,
+    #[arg(long, value_name = "FRAMES", default_value_t = DEFAULT_GRAPH_WIDTH, long_help = "The number of recent frames each graph covers, one character per frame.")]
+    graph_width: usize,
+    #[arg(long, value_name = "FILE", long_help = "Path to a file which the program counter, registers, timers, draw and stall counts, sound state, and key bitmask will be exported to, one row per frame. Written as JSON Lines if the file ends in .json or .jsonl, CSV otherwise.")]
+    metrics: Option<String>,
+    #[arg(long = "break", value_name = "ADDRESS", long_help = "The address of an instruction to pause the game before, in hexadecimal with a 0x prefix, in decimal, or as a label from the --symbols file. Can be repeated. Once paused, F10 steps a single instruction and F5 resumes.")]
+    breakpoints: Vec<String>,
+    #[arg(long, value_name = "FILE", long_help = "Path to a symbol file holding a label and an address per line (e.g. \"main 0x202\", as listed by Octo), so that the disassembler, trace log, and debugger show the labels and --break accepts them. Also applies to the disassemble and run-headless commands.")]
+    symbols: Option<String>,
+    #[arg(long = "break-if", value_name = "CONDITION", long_help = "A condition to pause the game after the instruction which meets it, comparing two watch expressions with ==, !=, <, <=, >, or >= (e.g. \"V3 == 0x1F\" or \"I > 0xE00\"). Checked after every instruction, pausing again only once the condition has stopped being met. Can be repeated.")]
+    break_conditions: Vec<Condition>,
+    #[arg(long, value_name = "FILE", long_help = "Path to a file which every instruction run will be logged to, one line per instruction with the registers it changed, register I, and the timers. Handy for diffing against reference emulators. Also applies to the run-headless command.")]
+    trace: Option<String>,
+    #[arg(long, value_name = "RANGE", long_help = "Log the events of every subsystem (keys pressed and released, sprites drawn, sounds started and stopped, halts) for the frames in the range, e.g. 120..180 (up to but not including frame 180), 600.., or ..60, and limit warnings to those frames. Every message is stamped with the frame and instruction cycle it happened on, so the subsystems can be lined up on a single timeline. Also applies to the run-headless command.")]
+    log_frames: Option<FrameRange>,
+    #[arg(long, long_help = "Count how often each kind of opcode runs and how long it takes, printing the most time-consuming first on exit. Also applies to the run-headless command.")]
+    profile: bool,
+    #[arg(long, long_help = "Record which instruction addresses run and which kinds of opcode are used, printing a summary of the ranges of code run and the opcodes never used on exit. Also applies to the run-headless command.")]
+    coverage: bool,
+    #[arg(long, value_name = "FRAMES", long_help = "Record every change the game makes to the registers, stack, memory, and timers over this many of the most recent frames. While paused, Shift+F10 then steps back an instruction at a time, and the memory view (F6) lists what changed since the previous frame.")]
+    record_events: Option<u64>,
+    #[arg(long, value_name = "MS", long_help = "Make every frame take this many extra milliseconds to run, simulating a host too slow to keep up. Frames beyond 16ms fall behind, which shows how the frame pacing and audio cope.")]
+    simulate_slow_host: Option<u64>,
+    #[arg(long = "screenshot-on", value_enum, value_name = "EVENT", long_help = "An event to save a PNG screenshot of the display for: error when the game halts with an error, exit when it exits, spin when it starts jumping to the same instruction forever (how most test games finish), or finish when it meets the last of its speedrun splits. Each is named after the game, frame, and event (e.g. pong-1234-exit.png). Can be repeated. Also applies to the run-headless command.")]
+    screenshot_on: Vec<ScreenshotTrigger>,
+    #[arg(long, value_name = "DIR", default_value = DEFAULT_SCREENSHOT_DIR, long_help = "The directory the screenshots taken for --screenshot-on are saved to.")]
+    screenshot_dir: String,
+
+    // Fuzzing flags
+    #[arg(long, value_name = "RUNS", long_help = "Fuzz the game with random key inputs for the provided number of runs without opening a window, looking for interpreter faults (e.g. stack overflows or unrecognized opcodes). The inputs reproducing each fault are saved to --fuzz-output.")]
+    fuzz: Option<u32>,
+    #[arg(long, default_value_t = FUZZ_FRAMES, long_help = "The most frames each fuzzing run lasts.")]
+    fuzz_frames: u64,
+    #[arg(long, long_help = "The seed of the fuzzing session, to repeat an earlier session exactly. Random if not provided.")]
+    fuzz_seed: Option<u64>,
+    #[arg(long, value_name = "DIR", default_value = "fuzz", long_help = "The directory the input scripts reproducing faults are saved to.")]
+    fuzz_output: String,
+    #[arg(long, value_name = "FILE", long_help = "Replay an input script saved by --fuzz without opening a window, reporting the fault it reproduces.")]
+    replay_inputs: Option<String>,
+
+    // Headless flags
+    #[arg(long, long_help = "Run the game without opening a window or playing audio, then print its registers and a hash of its display. Runs for --max-frames frames, stopping early if the game halts, with no input pressed throughout. The same as the run-headless command, for CI pipelines which pass the game first.")]
+    headless: bool,
+    #[arg(long, value_name = "FRAMES", long_help = "The most frames to run the game for with --headless. Defaults to 600 (ten seconds of play).")]
+    max_frames: Option<u64>,
+    #[arg(long, value_name = "FILE", long_help = "Path to a save state file the final state of the game is written to once a headless run ends, which can be resumed from with --resume-state or compared with diff-states. Also applies to the run-headless command.")]
+    dump_state: Option<String>,
+    #[arg(long, value_name = "FILE", long_help = "Path to a PNG file the final display of the game is written to once a headless run ends. Also applies to the run-headless command.")]
+    dump_frame: Option<String>,
+
+    #[arg(long, long_help = "Treat warnings about nonsensical flag combinations as errors, refusing to start.")]
+    strict_flags: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+/// Holds the commands which can be run instead of opening a game file.
+#[derive(Subcommand)]
+enum Command {
+    /// List the bundled public domain games, or launch one by name.
+    Demos {
+        #[arg(long_help = "The name of the bundled game to launch. The games are listed if not provided.")]
+        name: Option<String>,
+        #[arg(long, value_name = "DIR", default_value = "demos", long_help = "The directory the bundled games are written to when launched, alongside their palettes and autosaves.")]
+        demos_dir: String,
+    },
+    /// Print a disassembly of the game, one instruction per line from the load address, with each bank under its .bank directive with --banking.
+    Disassemble {
+        #[arg(long_help = "Path to the game file.")]
+        game: String,
+    },
+    /// Print the size and hash of the game, along with what the game database knows about it.
+    Info {
+        #[arg(long_help = "Path to the game file.")]
+        game: String,
+    },
+    /// Run every game in a directory without opening a window, writing a report of which ones halt, fault, or leave the display blank.
+    Compat {
+        #[arg(default_value = "games", long_help = "The directory holding the games. Files which are not CHIP-8 games are skipped.")]
+        games_dir: String,
+        #[arg(long, default_value_t = FUZZ_FRAMES, long_help = "The most frames to run each game for, stopping early if it halts.")]
+        frames: u64,
+        #[arg(long, value_name = "FILE", default_value = "compat.md", long_help = "Path to the report, written as JSON if it ends in .json and as a Markdown table otherwise.")]
+        output: String,
+    },
+    /// Compare the speed of the interpreter's dispatch designs.
+    Bench {
+        #[command(subcommand)]
+        command: BenchCommand,
+    },
+    /// Print the notes kept on the game, or add a note to them.
+    Notes {
+        #[arg(long_help = "Path to the game file.")]
+        game: String,
+        #[arg(long_help = "A note to add on its own line, e.g. the game's controls or a level password. The notes are printed if not provided.")]
+        note: Option<String>,
+    },
+    /// Print the registers, timers, stack, and RAM regions which differ between two save states.
+    DiffStates {
+        #[arg(long_help = "Path to the save state the values are compared from.")]
+        first: String,
+        #[arg(long_help = "Path to the save state the values are compared to.")]
+        second: String,
+    },
+    /// Run the game for a number of frames without opening a window, then print its registers and a hash of its display.
+    RunHeadless {
+        #[arg(long_help = "Path to the game file.")]
+        game: String,
+        #[arg(long, default_value_t = FUZZ_FRAMES, long_help = "The number of frames to run, stopping early if the game halts.")]
+        frames: u64,
+    },
+}
+
+/// Denotes where games are run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Default)]
+enum Frontend {
+    /// A window with sound.
+    #[default]
+    Window,
+    /// The terminal, drawn with text.
+    Tui,
+    /// A debugging window with panels around the display.
+    Egui
+}
+
+impl Frontend {
+    /// Returns the name of the frontend as it is written on the command line, which is also the name of the feature that builds it.
+    fn name(self) -> &'static str {
+        match self {
+            Frontend::Window => "window",
+            Frontend::Tui => "tui",
+            Frontend::Egui => "egui"
+        }
+    }
+}
+
+/// Holds the benchmark commands.
+#[derive(Subcommand)]
+enum BenchCommand {
+    /// Run every game in a directory without opening a window under each dispatch design, printing a table comparing how long they took.
+    Report {
+        #[arg(default_value = "games", long_help = "The directory holding the games. Files which are not CHIP-8 games are skipped.")]
+        games_dir: String,
+        #[arg(long, default_value_t = BENCH_FRAMES, long_help = "The number of frames to run each game for under each design.")]
+        frames: u64,
+        #[arg(long, value_name = "FILE", long_help = "Path to a file the Markdown table is also written to.")]
+        output: Option<String>,
+    },
+}
+
+/// Returns the address written in hexadecimal (with a `0x` prefix) or decimal.
+///
+/// # Parameters
+///
+/// * `address` - The address as written on the command line.
+///
+/// # Errors
+///
+/// Returns an `Err` if the address is not a number which fits in the 16-bit address space.
+fn parse_address(address: &str) -> Result<u16, String> {
+    match address.strip_prefix("0x").or_else(|| address.strip_prefix("0X")) {
+        Some(hexadecimal) => u16::from_str_radix(hexadecimal, 16),
+        None => address.parse()
+    }.map_err(|e| format!("{address} is not an address: {e}"))
+}
+
+/// Parses a speed multiplier from the command line, e.g. `0.5` for half speed.
+///
+/// # Parameters
+///
+/// * `speed` - The multiplier as written on the command line.
+///
+/// # Errors
+///
+/// Returns an `Err` if the multiplier is not a number from the slowest to the fastest speed.
+fn parse_speed(speed: &str) -> Result<f64, String> {
+    speed.parse().ok()
+        .filter(|speed| (emulator::MIN_SPEED..=emulator::MAX_SPEED).contains(speed))
+        .ok_or_else(|| format!("The speed must be a number from {} to {}.", emulator::MIN_SPEED, emulator::MAX_SPEED))
+}
+
+/// Returns the quirk settings of the platform preset (or the defaults), overridden by any individual quirk flags unless `--pure` locks them to the preset.
+///
+/// # Parameters
+///
+/// * `cli` - The parsed command line arguments.
+fn quirk_config(cli: &Cli) -> QuirkConfig {
+    let mut quirk_config = cli.platform.map_or_else(QuirkConfig::new, Platform::quirk_config);
+    if cli.pure {
+        return quirk_config;
+    }
+
+    if let Some(reset_vf) = &cli.quirk_reset_vf {
+        quirk_config.reset_vf = reset_vf.clone();
+    }
+    if let Some(memory) = &cli.quirk_memory {
+        quirk_config.memory = memory.clone();
+    }
+    if let Some(display_wait) = &cli.quirk_display_wait {
+        quirk_config.display_wait = display_wait.clone();
+    }
+    if let Some(clipping) = &cli.quirk_clipping {
+        quirk_config.set_clipping(clipping.clone());
+    }
+    if let Some(horizontal_clipping) = &cli.quirk_horizontal_clipping {
+        quirk_config.horizontal_clipping = horizontal_clipping.clone();
+    }
+    if let Some(vertical_clipping) = &cli.quirk_vertical_clipping {
+        quirk_config.vertical_clipping = vertical_clipping.clone();
+    }
+    if let Some(shifting) = &cli.quirk_shifting {
+        quirk_config.shifting = shifting.clone();
+    }
+    if let Some(jumping) = &cli.quirk_jumping {
+        quirk_config.jumping = jumping.clone();
+    }
+    if let Some(memory_timing) = &cli.quirk_memory_timing {
+        quirk_config.memory_timing = memory_timing.clone();
+    }
+    if let Some(key_ghosting) = &cli.quirk_key_ghosting {
+        quirk_config.key_ghosting = key_ghosting.clone();
+    }
+
+    quirk_config
+}
+
+/// Returns whether any individual quirk flag was provided.
+///
+/// # Parameters
+///
+/// * `cli` - The parsed command line arguments.
+fn has_quirk_flags(cli: &Cli) -> bool {
+    cli.quirk_reset_vf.is_some()
+        || cli.quirk_memory.is_some()
+        || cli.quirk_display_wait.is_some()
+        || cli.quirk_clipping.is_some()
+        || cli.quirk_horizontal_clipping.is_some()
+        || cli.quirk_vertical_clipping.is_some()
+        || cli.quirk_shifting.is_some()
+        || cli.quirk_jumping.is_some()
+        || cli.quirk_memory_timing.is_some()
+        || cli.quirk_key_ghosting.is_some()
+}
+
+/// Returns whether known games should run with the quirks and cycles per frame recommended by the game database, which is only the case if none were provided and `--pure` is not set.
+///
+/// # Parameters
+///
+/// * `cli` - The parsed command line arguments.
+fn uses_database(cli: &Cli) -> bool {
+    !cli.ignore_database && !cli.pure && cli.platform.is_none() && !has_quirk_flags(cli) && cli.cycles_per_frame.is_none() && cli.load_address.is_none()
+}
+
+/// Returns whether games with a section in the config file should run with the settings it overrides, which is only the case if no quirks or cycles per frame were provided and `--pure` is not set.
+///
+/// # Parameters
+///
+/// * `cli` - The parsed command line arguments.
+fn uses_config(cli: &Cli) -> bool {
+    !cli.pure && cli.platform.is_none() && !has_quirk_flags(cli) && cli.cycles_per_frame.is_none()
+}
+
+/// Returns a warning, along with a suggested fix, for each nonsensical combination of flags.
+///
+/// # Parameters
+///
+/// * `cli` - The parsed command line arguments.
+fn check_flags(cli: &Cli) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    let cycles_per_frame = cli.cycles_per_frame.unwrap_or(CYCLES_PER_FRAME);
+    if cycles_per_frame == 0 {
+        warnings.push(format!("--cycles-per-frame 0 will not run any instructions. Try the default of {CYCLES_PER_FRAME}."));
+    } else if cycles_per_frame > MAX_SENSIBLE_CYCLES_PER_FRAME {
+        warnings.push(format!("--cycles-per-frame {cycles_per_frame} will run most games far too fast. Try a value between 7 and 15, or the default of {CYCLES_PER_FRAME}."));
+    }
+
+    let quirk_config = quirk_config(cli);
+    let super_chip_quirks = quirk_config.shifting == ShiftingQuirk::Vx || quirk_config.jumping == JumpingQuirk::Vx;
+    if super_chip_quirks && quirk_config.display_wait == DisplayWaitQuirk::Wait {
+        warnings.push(String::from("The SUPER-CHIP shifting/jumping quirks are mixed with the original CHIP-8 display wait, which slows SUPER-CHIP games considerably. Try --quirk-display-wait no-wait."));
+    }
+
+    if cli.pure && has_quirk_flags(cli) {
+        warnings.push(String::from("--pure locks the quirks to the platform preset, so the individual quirk flags are ignored. Remove them, or choose the matching --platform."));
+    }
+    if cli.pure && cli.banking {
+        warnings.push(String::from("--banking is a nonstandard extension, so it is ignored under --pure. Remove one of them."));
+    }
+
+    if cli.resume_state.is_some() && cli.game.is_none() {
+        warnings.push(String::from("--resume-state without a game file has nothing to resume. Provide the game file as well."));
+    }
+
+    if cli.watch_csv.is_some() && cli.watches.is_empty() {
+        warnings.push(String::from("--watch-csv without any --watch expressions will only log frame numbers. Add an expression with --watch."));
+    }
+
+    let output_paths = [("--watch-csv", &cli.watch_csv), ("--metrics", &cli.metrics), ("--trace", &cli.trace), ("--dump-state", &cli.dump_state), ("--dump-frame", &cli.dump_frame)];
+    for (flag, output_path) in output_paths {
+        if output_path.is_some() && *output_path == cli.game {
+            warnings.push(format!("{flag} would overwrite the game file. Choose a different output path."));
+        }
+    }
+    if cli.headless && cli.game.is_none() {
+        warnings.push(String::from("--headless without a game file has nothing to run. Provide the game file as well."));
+    }
+    let is_headless = cli.headless || matches!(cli.command, Some(Command::RunHeadless { .. }));
+    if cli.max_frames.is_some() && !cli.headless {
+        warnings.push(String::from("--max-frames only limits headless runs, so it is ignored. Add --headless, or use --frames with the run-headless command."));
+    }
+    if (cli.dump_state.is_some() || cli.dump_frame.is_some()) && !is_headless {
+        warnings.push(String::from("--dump-state and --dump-frame are only written once a headless run ends, so they are ignored. Add --headless."));
+    }
+
+    if cli.frontend != Frontend::Window && cli.game.is_none() {
+        warnings.push(format!("--frontend {} cannot pick a game file. Provide the game file as well.", cli.frontend.name()));
+    }
+
+    if cli.fuzz.is_some() && cli.replay_inputs.is_some() {
+        warnings.push(String::from("--fuzz and --replay-inputs were both provided, so only the inputs will be replayed. Remove one of them."));
+    }
+
+    if cli.watch_csv.is_some() && cli.watch_csv == cli.metrics {
+        warnings.push(String::from("--watch-csv and --metrics write to the same file, so one will overwrite the other. Choose different output paths."));
+    }
+
+    warnings
 }
 
 fn main() {
-    let cli = Cli::parse();
-
-    let quirk_config = QuirkConfig {
-        reset_vf: cli.quirk_reset_vf,
-        memory: cli.quirk_memory,
-        display_wait: cli.quirk_display_wait,
-        clipping: cli.quirk_clipping,
-        shifting: cli.quirk_shifting,
-        jumping: cli.quirk_jumping,
+    let mut cli = Cli::parse();
+
+    let warnings = check_flags(&cli);
+    for warning in &warnings {
+        eprintln!("Warning: {warning}");
+    }
+    if cli.strict_flags && !warnings.is_empty() {
+        eprintln!("Application error: refusing to start with the above warnings as --strict-flags is set.");
+        process::exit(1);
+    }
+
+    frame_log::set_frame_range(cli.log_frames);
+
+    let quirk_config = quirk_config(&cli);
+    let cycles_per_frame = cli.cycles_per_frame.unwrap_or(CYCLES_PER_FRAME);
+    let use_database = uses_database(&cli);
+    let use_config = uses_config(&cli);
+    let load_address = cli.load_address.unwrap_or(interpreter::PROGRAM_START_ADDRESS);
+
+    if let Some(script_path) = &cli.replay_inputs {
+        replay_inputs(&cli.game, script_path, cycles_per_frame, quirk_config);
+    }
+    if let Some(runs) = cli.fuzz {
+        let seed = cli.fuzz_seed.unwrap_or_else(rand::random);
+        let config = FuzzConfig { runs, frames: cli.fuzz_frames, seed, cycles_per_frame, quirk_config };
+        fuzz(&cli.game, &config, &cli.fuzz_output);
+    }
+
+    let symbols = match &cli.symbols {
+        Some(path) => SymbolTable::load(path).unwrap_or_else(|e| {
+            eprintln!("Application error: {e}");
+            process::exit(1);
+        }),
+        None => SymbolTable::new()
     };
 
-    if let Err(e) = rusty_chip::run(&cli.game, cli.cycles_per_frame, quirk_config) {
+    let game_settings = GameSettings { quirk_config, cycles_per_frame, load_address, banking: cli.banking && !cli.pure, hires_detection: !cli.no_hires, use_database, use_config, palette: cli.palette.clone(), pure: cli.pure };
+
+    let breakpoints = cli.breakpoints.iter().map(|breakpoint| symbols.resolve(breakpoint)).collect::<Result<Vec<u16>, String>>().unwrap_or_else(|e| {
         eprintln!("Application error: {e}");
         process::exit(1);
+    });
+
+    let debug_config = DebugConfig {
+        metrics_path: cli.metrics,
+        trace_path: cli.trace,
+        breakpoints,
+        break_conditions: cli.break_conditions,
+        profile: cli.profile,
+        coverage: cli.coverage,
+        event_window: cli.record_events,
+        slow_host_frame_time: cli.simulate_slow_host.map(Duration::from_millis),
+        symbols,
+        auto_screenshot: AutoScreenshot::new(cli.screenshot_on, &cli.screenshot_dir),
+    };
+
+    let dump_paths = DumpPaths { state_path: cli.dump_state.clone(), frame_path: cli.dump_frame.clone() };
+    if cli.headless {
+        let Some(game) = &cli.game else {
+            eprintln!("Application error: --headless needs a game file to run.");
+            process::exit(1);
+        };
+        print_output(rusty_chip::run_headless(game, cli.max_frames.unwrap_or(FUZZ_FRAMES), &game_settings, &debug_config, &dump_paths));
+    }
+
+    match &cli.command {
+        Some(Command::Demos { name, demos_dir }) => cli.game = Some(demo_path(name.as_deref(), demos_dir)),
+        Some(Command::Disassemble { game }) => print_output(rusty_chip::disassemble(game, load_address, game_settings.banking, &debug_config.symbols)),
+        Some(Command::Info { game }) => print_output(rusty_chip::info(game)),
+        Some(Command::Compat { games_dir, frames, output }) => print_output(rusty_chip::compat(games_dir, *frames, &game_settings, output)),
+        Some(Command::Bench { command: BenchCommand::Report { games_dir, frames, output } }) => print_output(rusty_chip::bench_report(games_dir, *frames, &game_settings, output.as_deref())),
+        Some(Command::Notes { game, note }) => print_output(rusty_chip::notes(game, note.as_deref())),
+        Some(Command::DiffStates { first, second }) => print_output(rusty_chip::diff_states(first, second)),
+        Some(Command::RunHeadless { game, frames }) => print_output(rusty_chip::run_headless(game, *frames, &game_settings, &debug_config, &dump_paths)),
+        None => ()
+    }
+
+    if cli.frontend != Frontend::Window {
+        let Some(game) = &cli.game else {
+            eprintln!("Application error: --frontend {} needs a game file to run.", cli.frontend.name());
+            process::exit(1);
+        };
+        run_frontend(cli.frontend, game, &game_settings);
+    }
+
+    #[cfg(feature = "sdl")]
+    {
+        let watch_config = WatchConfig {
+            expressions: cli.watches,
+            csv_path: cli.watch_csv,
+            graphs: cli.graphs,
+            graph_width: cli.graph_width,
+        };
+
+        if let Err(e) = save_overlay_layout(&cli.overlays) {
+            eprintln!("Application error: {e}");
+            process::exit(1);
+        }
+
+        let speed_config = SpeedConfig { speed: cli.speed, turbo_factor: cli.turbo_factor, turbo_key: cli.turbo_key };
+
+        if let Err(e) = rusty_chip::run(&cli.game, cli.resume_state.as_deref(), game_settings, cli.memory_protection, watch_config, debug_config, &speed_config) {
+            eprintln!("Application error: {e}");
+            process::exit(1);
+        }
+    }
+
+    // Builds without SDL (e.g. for WASI) can only run the commands which do not need a window
+    #[cfg(not(feature = "sdl"))]
+    {
+        eprintln!("Application error: this build has no window to run games in. Try the `run-headless` command instead.");
+        process::exit(1);
+    }
+}
+
+/// Runs the game in one of the optional frontends, then exits.
+///
+/// # Parameters
+///
+/// * `frontend` - The frontend to run the game in.
+/// * `game` - The path to the game.
+/// * `game_settings` - The settings to run the game with.
+#[cfg_attr(not(all(feature = "tui", feature = "egui")), allow(unused_variables))]
+fn run_frontend(frontend: Frontend, game: &str, game_settings: &GameSettings) -> ! {
+    let result: Result<(), String> = match frontend {
+        #[cfg(feature = "tui")]
+        Frontend::Tui => rusty_chip::tui::run(game, game_settings),
+        #[cfg(feature = "egui")]
+        Frontend::Egui => rusty_chip::debug_ui::run(game, game_settings),
+        _ => Err(format!("this build has no {0} frontend. Rebuild it with `--features {0}`.", frontend.name()))
+    };
+
+    if let Err(e) = result {
+        eprintln!("Application error: {e}");
+        process::exit(1);
+    }
+    process::exit(0);
+}
+
+/// Applies the `--overlay` entries to the saved [overlay layout](rusty_chip::layout), saving it so that later runs keep it.
+/// Nothing is saved if there are no entries.
+///
+/// # Parameters
+///
+/// * `entries` - The overlays to move, each followed by its settings.
+///
+/// # Errors
+///
+/// Returns an `Err` if an entry is malformed, or the layout cannot be read or saved.
+#[cfg(feature = "sdl")]
+fn save_overlay_layout(entries: &[String]) -> Result<(), String> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+
+    let mut storage = FileStorage;
+    let mut overlay_layout = OverlayLayout::load(&storage).map_err(|e| format!("Error loading overlay layout: {e}"))?;
+    for entry in entries {
+        overlay_layout.apply(entry)?;
+    }
+
+    overlay_layout.save(&mut storage).map_err(|e| format!("Error saving overlay layout: {e}"))
+}
+
+/// Prints the output of a command which does not open a window, then exits with a failure code if it failed.
+///
+/// # Parameters
+///
+/// * `output` - The output of the command, or the error it failed with.
+fn print_output(output: Result<String, String>) -> ! {
+    match output {
+        Ok(output) => {
+            print!("{output}");
+            process::exit(0);
+        },
+        Err(e) => {
+            eprintln!("Application error: {e}");
+            process::exit(1);
+        }
+    }
+}
+
+/// Returns the path of the bundled game to launch, or lists the bundled games and exits if no name was provided.
+///
+/// # Parameters
+///
+/// * `name` - An optional name of the bundled game.
+/// * `demos_dir` - The directory the bundled game is written to.
+fn demo_path(name: Option<&str>, demos_dir: &str) -> String {
+    let Some(name) = name else {
+        for demo in &DEMOS {
+            println!("{:<10}{}", demo.name, demo.description);
+        }
+        println!("Launch one with `demos <NAME>`.");
+        process::exit(0);
+    };
+
+    let Some(demo) = demos::find(name) else {
+        eprintln!("Application error: there is no bundled game named {name}. Run `demos` to list them.");
+        process::exit(1);
+    };
+
+    demos::extract(demo, demos_dir).unwrap_or_else(|e| {
+        eprintln!("Application error: could not write the bundled game to {demos_dir}: {e}");
+        process::exit(1);
+    })
+}
+
+/// Replays the input script against the game, then exits with a failure code if it reproduced a fault.
+///
+/// # Parameters
+///
+/// * `game` - An optional path to the chosen game.
+/// * `script_path` - The path to the input script.
+/// * `cycles_per_frame` - The number of instruction cycles to run per frame.
+/// * `quirk_config` - The enabled/disabled status of all the quirks.
+fn replay_inputs(game: &Option<String>, script_path: &str, cycles_per_frame: u32, quirk_config: QuirkConfig) -> ! {
+    let Some(game) = game else {
+        eprintln!("Application error: a game file is required to replay inputs.");
+        process::exit(1);
+    };
+
+    match rusty_chip::replay_inputs(game, script_path, cycles_per_frame, quirk_config) {
+        Ok(Some((frame, fault))) => {
+            println!("Faulted in frame {frame}: {fault}");
+            process::exit(1);
+        },
+        Ok(None) => {
+            println!("The inputs ran without a fault.");
+            process::exit(0);
+        },
+        Err(e) => {
+            eprintln!("Application error: {e}");
+            process::exit(1);
+        }
+    }
+}
+
+/// Fuzzes the game, then exits with a failure code if any faults were found.
+///
+/// # Parameters
+///
+/// * `game` - An optional path to the chosen game.
+/// * `config` - The settings of the fuzzing session.
+/// * `output_dir` - The directory the input scripts reproducing faults are saved to.
+fn fuzz(game: &Option<String>, config: &FuzzConfig, output_dir: &str) -> ! {
+    let Some(game) = game else {
+        eprintln!("Application error: a game file is required to fuzz.");
+        process::exit(1);
+    };
+
+    println!("Fuzzing {game} for {} runs with seed {}.", config.runs, config.seed);
+    match rusty_chip::fuzz(game, config, output_dir) {
+        Ok(0) => {
+            println!("No faults found.");
+            process::exit(0);
+        },
+        Ok(faults) => {
+            println!("Found {faults} faults.");
+            process::exit(1);
+        },
+        Err(e) => {
+            eprintln!("Application error: {e}");
+            process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the warnings for the provided arguments (excluding the program name).
+    fn warnings_for(args: &[&str]) -> Vec<String> {
+        let cli = Cli::try_parse_from(std::iter::once("rusty_chip").chain(args.iter().copied())).unwrap();
+        check_flags(&cli)
+    }
+
+    #[test]
+    fn sensible_flags() {
+        assert!(warnings_for(&[]).is_empty(), "Default flags warned.");
+        assert!(warnings_for(&["game.ch8", "--watch", "V0", "--watch-csv", "watch.csv", "--metrics", "metrics.csv"]).is_empty(), "Sensible flags warned.");
+        assert!(warnings_for(&["--quirk-jumping", "vx", "--quirk-display-wait", "no-wait"]).is_empty(), "Consistent SUPER-CHIP quirks warned.");
+    }
+
+    #[test]
+    fn nonsensical_flags() {
+        assert_eq!(warnings_for(&["--cycles-per-frame", "0"]).len(), 1, "Zero cycles not warned.");
+        assert_eq!(warnings_for(&["--cycles-per-frame", "5000"]).len(), 1, "Excessive cycles not warned.");
+        assert_eq!(warnings_for(&["--quirk-shifting", "vx"]).len(), 1, "Mixed quirks not warned.");
+        assert_eq!(warnings_for(&["--watch-csv", "watch.csv"]).len(), 1, "Empty watch log not warned.");
+        assert_eq!(warnings_for(&["game.ch8", "--metrics", "game.ch8"]).len(), 1, "Overwriting the game not warned.");
+        assert_eq!(warnings_for(&["game.ch8", "--trace", "game.ch8"]).len(), 1, "Overwriting the game with the trace not warned.");
+        assert_eq!(warnings_for(&["--watch", "V0", "--watch-csv", "out.csv", "--metrics", "out.csv"]).len(), 1, "Shared output file not warned.");
+        assert_eq!(warnings_for(&["game.ch8", "--fuzz", "10", "--replay-inputs", "fault-0.txt"]).len(), 1, "Conflicting fuzz modes not warned.");
+        assert_eq!(warnings_for(&["--headless"]).len(), 1, "Headless run without a game not warned.");
+        assert_eq!(warnings_for(&["--frontend", "tui"]).len(), 1, "Terminal frontend without a game not warned.");
+        assert_eq!(warnings_for(&["--frontend", "egui"]).len(), 1, "Debugging frontend without a game not warned.");
+        assert_eq!(warnings_for(&["game.ch8", "--max-frames", "60", "--dump-frame", "final.png"]).len(), 2, "Headless flags without --headless not warned.");
+        assert!(warnings_for(&["--dump-state", "final.rcss", "run-headless", "game.ch8"]).is_empty(), "Dump with the run-headless command warned.");
+    }
+
+    #[test]
+    fn platform_presets() {
+        let parse = |args: &[&str]| quirk_config(&Cli::try_parse_from(std::iter::once("rusty_chip").chain(args.iter().copied())).unwrap());
+
+        assert_eq!(parse(&[]), QuirkConfig::new(), "Quirks without a platform not defaulted.");
+        assert_eq!(parse(&["--platform", "schip"]), Platform::Schip.quirk_config(), "Platform preset not applied.");
+        let quirk_config = parse(&["--platform", "schip", "--quirk-jumping", "v0", "--quirk-clipping", "wrap", "--quirk-vertical-clipping", "clip"]);
+        assert_eq!(quirk_config.jumping, JumpingQuirk::V0, "Platform preset not overridden.");
+        assert_eq!(quirk_config.shifting, ShiftingQuirk::Vx, "Platform preset overridden without a flag.");
+        assert_eq!((quirk_config.horizontal_clipping, quirk_config.vertical_clipping), (ClippingQuirk::Wrap, ClippingQuirk::Clip), "Clipping not overridden per axis.");
+        for platform in ["cosmac-vip", "chip-48", "schip", "xo-chip"] {
+            assert!(warnings_for(&["--platform", platform]).is_empty(), "Platform {platform} preset warned.");
+        }
+
+        assert_eq!(parse(&["--pure", "--platform", "schip", "--quirk-jumping", "v0"]), Platform::Schip.quirk_config(), "Quirks not locked to the preset by --pure.");
+        assert_eq!(warnings_for(&["--pure", "--quirk-jumping", "v0", "--banking"]).len(), 2, "Flags ignored by --pure not warned.");
+    }
+
+    #[test]
+    fn database_only_without_settings() {
+        let uses_database_for = |args: &[&str]| uses_database(&Cli::try_parse_from(std::iter::once("rusty_chip").chain(args.iter().copied())).unwrap());
+
+        assert!(uses_database_for(&["game.ch8"]), "Database not used by default.");
+        assert!(!uses_database_for(&["--pure", "game.ch8"]), "Database used under --pure.");
+        assert!(!uses_database_for(&["game.ch8", "--ignore-database"]), "Database used when ignored.");
+        assert!(!uses_database_for(&["game.ch8", "--platform", "schip"]), "Database used with a platform.");
+        assert!(!uses_database_for(&["game.ch8", "--quirk-vertical-clipping", "wrap"]), "Database used with a quirk flag.");
+        assert!(!uses_database_for(&["game.ch8", "-c", "15"]), "Database used with cycles per frame.");
+        assert!(!uses_database_for(&["game.ch8", "--load-address", "0x600"]), "Database used with a load address.");
+    }
+
+    #[test]
+    fn parse_load_address() {
+        assert_eq!(parse_address("0x600"), Ok(0x600), "Hexadecimal address not parsed.");
+        assert_eq!(parse_address("1536"), Ok(0x600), "Decimal address not parsed.");
+        assert!(parse_address("0x10000").is_err(), "Address outside of memory parsed.");
+        assert!(parse_address("start").is_err(), "Invalid address parsed.");
+    }
+
+    #[test]
+    fn parse_speed_multiplier() {
+        assert_eq!(parse_speed("0.5"), Ok(0.5), "Speed not parsed.");
+        assert!(parse_speed("8").is_err(), "Speed above the fastest parsed.");
+        assert!(parse_speed("fast").is_err(), "Invalid speed parsed.");
+    }
+
+    #[test]
+    fn parse_demos_command() {
+        let cli = Cli::try_parse_from(["rusty_chip", "demos", "pong2"]).unwrap();
+        assert!(cli.game.is_none(), "Demos command parsed as a game file.");
+        assert!(matches!(cli.command, Some(Command::Demos { name: Some(ref name), .. }) if name == "pong2"), "Demo name not parsed.");
+    }
+
+    #[test]
+    fn parse_headless_commands() {
+        let cli = Cli::try_parse_from(["rusty_chip", "-c", "15", "run-headless", "game.ch8", "--frames", "60"]).unwrap();
+        assert_eq!(cli.cycles_per_frame, Some(15), "Flags before the command not parsed.");
+        assert!(matches!(cli.command, Some(Command::RunHeadless { ref game, frames: 60 }) if game == "game.ch8"), "Headless run not parsed.");
+
+        let cli = Cli::try_parse_from(["rusty_chip", "game.ch8", "--headless", "--max-frames", "120", "--dump-state", "final.rcss"]).unwrap();
+        assert!(cli.headless && cli.max_frames == Some(120) && cli.dump_state.as_deref() == Some("final.rcss"), "Headless flags not parsed.");
+
+        let cli = Cli::try_parse_from(["rusty_chip", "--profile", "run-headless", "game.ch8"]).unwrap();
+        assert!(cli.profile, "Profiling not parsed.");
+
+        let cli = Cli::try_parse_from(["rusty_chip", "--simulate-slow-host", "20", "game.ch8"]).unwrap();
+        assert_eq!(cli.simulate_slow_host, Some(20), "Slow host not parsed.");
+
+        let cli = Cli::try_parse_from(["rusty_chip", "--record-events", "120", "game.ch8"]).unwrap();
+        assert_eq!(cli.record_events, Some(120), "Event window not parsed.");
+
+        let cli = Cli::try_parse_from(["rusty_chip", "--graph", "V3", "--graph", "[0x3F0]", "--graph-width", "30", "game.ch8"]).unwrap();
+        assert_eq!((cli.graphs.len(), cli.graph_width), (2, 30), "Graphs not parsed.");
+
+        let cli = Cli::try_parse_from(["rusty_chip", "--break-if", "V3 == 0x1F", "game.ch8"]).unwrap();
+        assert_eq!(cli.break_conditions.iter().map(ToString::to_string).collect::<Vec<_>>(), ["V3 == 0x1F"], "Break condition not parsed.");
+        assert!(Cli::try_parse_from(["rusty_chip", "--break-if", "V3", "game.ch8"]).is_err(), "Break condition without a comparison parsed.");
+
+        let cli = Cli::try_parse_from(["rusty_chip", "compat", "--output", "compat.json"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Compat { ref games_dir, frames: FUZZ_FRAMES, ref output }) if games_dir == "games" && output == "compat.json"), "Compatibility report not parsed.");
+
+        let cli = Cli::try_parse_from(["rusty_chip", "bench", "report", "roms"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Bench { command: BenchCommand::Report { ref games_dir, frames: BENCH_FRAMES, output: None } }) if games_dir == "roms"), "Benchmark report not parsed.");
+
+        let cli = Cli::try_parse_from(["rusty_chip", "notes", "game.ch8", "Move with 4 and 6."]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Notes { note: Some(ref note), .. }) if note == "Move with 4 and 6."), "Note not parsed.");
+
+        let cli = Cli::try_parse_from(["rusty_chip", "diff-states", "before.rcss", "after.rcss"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::DiffStates { ref first, ref second }) if first == "before.rcss" && second == "after.rcss"), "State diff not parsed.");
     }
 }
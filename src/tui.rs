@@ -0,0 +1,272 @@
+//! A module to run games in the terminal, for SSH sessions and machines without a display server.
+//! The display is drawn with Unicode half blocks, fitting two rows of pixels into each line of text, and the sound timer rings the terminal's bell.
+//! Keys are read with the same layout as the window (1-4, Q-R, A-F, and Z-V), and Escape quits.
+//! Most terminals only report key presses, so a key counts as held until shortly after its last press (or repeat), unless the terminal can report releases.
+
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags};
+use crossterm::style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+
+use crate::backend::CharKeyboard;
+use crate::emulator::{Emulator, EmulatorMode};
+use crate::framebuffer::Framebuffer;
+use crate::palette::{Colour, Palette};
+use crate::GameSettings;
+
+/// How long a key counts as held after its last press, on terminals which do not report releases.
+/// Long enough to bridge the gaps between a held key's repeats once they start.
+const KEY_HOLD_TIME: Duration = Duration::from_millis(150);
+
+/// The character drawn for every pair of pixels, its foreground being the top pixel and its background the bottom one.
+const HALF_BLOCK: char = '\u{2580}';
+
+/// Tracks the keys held down, releasing them after a while on terminals which only report presses.
+struct HeldKeys {
+    reports_releases: bool,
+    /// The held keys, along with when they were last pressed.
+    keys: Vec<(char, Instant)>
+}
+
+impl HeldKeys {
+    /// Returns a tracker with no keys held.
+    ///
+    /// # Parameters
+    ///
+    /// * `reports_releases` - True if the terminal reports key releases, false if keys should be released once they stop repeating.
+    fn new(reports_releases: bool) -> HeldKeys {
+        HeldKeys { reports_releases, keys: Vec::new() }
+    }
+
+    /// Records a press (or repeat) of the key, returning true if it was not already held.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The character of the key.
+    /// * `now` - The time of the press.
+    fn press(&mut self, key: char, now: Instant) -> bool {
+        let key = key.to_ascii_lowercase();
+        match self.keys.iter_mut().find(|(held_key, _)| *held_key == key) {
+            Some((_, pressed_at)) => {
+                *pressed_at = now;
+                false
+            },
+            None => {
+                self.keys.push((key, now));
+                true
+            }
+        }
+    }
+
+    /// Records a release of the key, returning true if it was held.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The character of the key.
+    fn release(&mut self, key: char) -> bool {
+        let key = key.to_ascii_lowercase();
+        let held_count = self.keys.len();
+        self.keys.retain(|(held_key, _)| *held_key != key);
+        self.keys.len() != held_count
+    }
+
+    /// Releases the keys which have not been pressed for a while, returning them. Nothing is released if the terminal reports releases.
+    ///
+    /// # Parameters
+    ///
+    /// * `now` - The current time.
+    fn expire(&mut self, now: Instant) -> Vec<char> {
+        if self.reports_releases {
+            return Vec::new();
+        }
+
+        let (expired, held) = self.keys.iter().partition(|(_, pressed_at)| now.duration_since(*pressed_at) >= KEY_HOLD_TIME);
+        self.keys = held;
+        expired.into_iter().map(|(key, _)| key).collect()
+    }
+}
+
+/// Runs the game in the terminal until the user presses Escape.
+///
+/// # Parameters
+///
+/// * `path` - The path to the game.
+/// * `game_settings` - The quirks, cycles per frame, load address, and banking to run the game with, and whether the [database](crate::database) may override them.
+///
+/// # Errors
+///
+/// Returns an `Err` if the game file cannot be read, the load address leaves no room for the font or is past the bank window while banking, or the terminal cannot be drawn to.
+pub fn run(path: &str, game_settings: &GameSettings) -> Result<(), String> {
+    let game_data = crate::read_game_file(path).map_err(|e| e.to_string())?;
+    let mut emulator = crate::headless_emulator(game_data, game_settings)?;
+
+    let mut stdout = io::stdout();
+    terminal::enable_raw_mode().map_err(|e| e.to_string())?;
+    let reports_releases = terminal::supports_keyboard_enhancement().unwrap_or(false);
+    let result = execute!(stdout, EnterAlternateScreen, Hide, Clear(ClearType::All))
+        .and_then(|()| if reports_releases { execute!(stdout, PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::REPORT_EVENT_TYPES)) } else { Ok(()) })
+        .and_then(|()| run_loop(&mut emulator, &mut stdout, reports_releases));
+
+    // The terminal is restored even if the game could not be run, so that the shell is usable afterwards
+    if reports_releases {
+        let _ = execute!(stdout, PopKeyboardEnhancementFlags);
+    }
+    let _ = execute!(stdout, ResetColor, Show, LeaveAlternateScreen);
+    let _ = terminal::disable_raw_mode();
+
+    result.map_err(|e| e.to_string())
+}
+
+/// Runs frames of the game as time passes, drawing them and passing on the keys pressed, until the user presses Escape.
+///
+/// # Parameters
+///
+/// * `emulator` - The emulator running the game.
+/// * `out` - The terminal to draw to.
+/// * `reports_releases` - True if the terminal reports key releases.
+///
+/// # Errors
+///
+/// Returns an `Err` if the terminal cannot be read from or drawn to.
+fn run_loop(emulator: &mut Emulator, out: &mut impl Write, reports_releases: bool) -> io::Result<()> {
+    let mut held_keys = HeldKeys::new(reports_releases);
+    let mut next_tick = Instant::now();
+    let mut was_sounding = false;
+    let mut is_halt_shown = false;
+    loop {
+        while event::poll(next_tick.saturating_duration_since(Instant::now()))? {
+            match event::read()? {
+                Event::Key(KeyEvent { code: KeyCode::Esc, .. }) => return Ok(()),
+                Event::Key(KeyEvent { code: KeyCode::Char('c'), modifiers, .. }) if modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+                Event::Key(KeyEvent { code: KeyCode::Char(key), kind: KeyEventKind::Release, .. }) if held_keys.release(key) => emulator.handle_key_release(&CharKeyboard, key),
+                Event::Key(KeyEvent { code: KeyCode::Char(key), kind: KeyEventKind::Press | KeyEventKind::Repeat, .. }) if held_keys.press(key, Instant::now()) => emulator.handle_key_press(&CharKeyboard, key),
+                Event::Resize(..) => {
+                    queue!(out, ResetColor, Clear(ClearType::All))?;
+                    draw(out, emulator.interpreter().framebuffer(), emulator.renderer().palette())?;
+                    is_halt_shown = false;
+                },
+                _ => {}
+            }
+        }
+
+        for key in held_keys.expire(Instant::now()) {
+            emulator.handle_key_release(&CharKeyboard, key);
+        }
+        let frame_advance = emulator.tick();
+        next_tick = Instant::now() + emulator.next_tick_delay();
+        if frame_advance.frames == 0 {
+            continue;
+        }
+
+        draw(out, emulator.interpreter().framebuffer(), emulator.renderer().palette())?;
+        let is_sounding = emulator.interpreter().sound_timer() > 0;
+        if is_sounding && !was_sounding {
+            queue!(out, Print('\u{7}'))?;
+        }
+        was_sounding = is_sounding;
+        if let (EmulatorMode::Halted(reason), false) = (emulator.mode(), is_halt_shown) {
+            let status_row = u16::try_from(emulator.interpreter().display_height().div_ceil(2)).unwrap_or(u16::MAX);
+            queue!(out, ResetColor, MoveTo(0, status_row), Print(format!("{reason} Press Escape to quit.")))?;
+            is_halt_shown = true;
+        }
+        out.flush()?;
+    }
+}
+
+/// Returns the colours of the pixels a character of the display covers, the top one first.
+///
+/// # Parameters
+///
+/// * `framebuffer` - The display to draw.
+/// * `palette` - The colours the display is drawn in.
+/// * `x` - The column of the pixels.
+/// * `row` - The line of text, covering pixel rows `2 * row` and `2 * row + 1`.
+fn cell_colours(framebuffer: Framebuffer<'_>, palette: &Palette, x: u32, row: u32) -> (Colour, Colour) {
+    let colours = &palette.colours;
+    (colours[framebuffer.colour_index(x, row * 2)], colours[framebuffer.colour_index(x, row * 2 + 1)])
+}
+
+/// Returns the colour as the terminal represents it.
+///
+/// # Parameters
+///
+/// * `colour` - The colour to convert.
+fn terminal_colour(colour: Colour) -> Color {
+    Color::Rgb { r: colour.r, g: colour.g, b: colour.b }
+}
+
+/// Draws the display from the top left of the terminal, only changing the colours between characters when they differ.
+///
+/// # Parameters
+///
+/// * `out` - The terminal to draw to.
+/// * `framebuffer` - The display to draw.
+/// * `palette` - The colours the display is drawn in.
+///
+/// # Errors
+///
+/// Returns an `Err` if the terminal cannot be drawn to.
+fn draw(out: &mut impl Write, framebuffer: Framebuffer<'_>, palette: &Palette) -> io::Result<()> {
+    let mut current_colours = None;
+    for row in 0..framebuffer.height().div_ceil(2) {
+        queue!(out, MoveTo(0, u16::try_from(row).unwrap_or(u16::MAX)))?;
+        for x in 0..framebuffer.width() {
+            let (top, bottom) = cell_colours(framebuffer, palette, x, row);
+            if current_colours != Some((top, bottom)) {
+                queue!(out, SetForegroundColor(terminal_colour(top)), SetBackgroundColor(terminal_colour(bottom)))?;
+                current_colours = Some((top, bottom));
+            }
+            queue!(out, Print(HALF_BLOCK))?;
+        }
+    }
+
+    queue!(out, ResetColor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::Interpreter;
+    use crate::quirks::{DisplayWaitQuirk, QuirkConfig};
+
+    #[test]
+    fn draw_half_blocks() {
+        let mut quirk_config = QuirkConfig::new();
+        quirk_config.display_wait = DisplayWaitQuirk::NoWait;
+        let mut interpreter = Interpreter::new_headless(quirk_config);
+
+        // Draws the top of the "0" sprite (a row of four pixels) at the origin
+        interpreter.load_game(&[0xA0, 0x00, 0xD0, 0x11]);
+        interpreter.handle_cycle();
+        interpreter.handle_cycle();
+        let palette = Palette::new();
+        let colours = palette.colours;
+        assert_eq!(cell_colours(interpreter.framebuffer(), &palette, 0, 0), (colours[1], colours[0]), "Top pixel not lit.");
+        assert_eq!(cell_colours(interpreter.framebuffer(), &palette, 4, 0), (colours[0], colours[0]), "Unlit pixels drawn.");
+
+        let mut out = Vec::new();
+        draw(&mut out, interpreter.framebuffer(), &palette).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(text.matches(HALF_BLOCK).count(), 64 * 16, "Display not drawn with a character per pair of pixels.");
+    }
+
+    #[test]
+    fn keys_held_until_released() {
+        let start = Instant::now();
+        let mut held_keys = HeldKeys::new(false);
+        assert!(held_keys.press('w', start), "New press not reported.");
+        assert!(!held_keys.press('W', start + KEY_HOLD_TIME / 2), "Repeat reported as a new press.");
+        assert!(held_keys.expire(start + KEY_HOLD_TIME).is_empty(), "Repeated key released early.");
+        assert_eq!(held_keys.expire(start + KEY_HOLD_TIME * 2), ['w'], "Key not released once it stopped repeating.");
+
+        let mut held_keys = HeldKeys::new(true);
+        held_keys.press('q', start);
+        assert!(held_keys.expire(start + KEY_HOLD_TIME * 10).is_empty(), "Key released without a release from the terminal.");
+        assert!(held_keys.release('q'), "Release not reported.");
+        assert!(!held_keys.release('q'), "Release of an unheld key reported.");
+    }
+}